@@ -1,6 +1,7 @@
 pub mod build_info;
 pub mod misc;
 pub mod node;
+pub mod payout;
 pub mod replay;
 pub mod snark;
 
@@ -21,6 +22,8 @@ pub enum Command {
     Snark(snark::Snark),
     /// Miscilaneous utilities.
     Misc(misc::Misc),
+    /// Delegation payouts of a block producer.
+    Payout(payout::Payout),
     Replay(replay::Replay),
     BuildInfo(build_info::Command),
 }
@@ -31,6 +34,7 @@ impl Command {
             Self::Snark(v) => v.run(),
             Self::Node(v) => v.run(),
             Self::Misc(v) => v.run(),
+            Self::Payout(v) => v.run(),
             Self::Replay(v) => v.run(),
             Self::BuildInfo(v) => v.run(),
         }
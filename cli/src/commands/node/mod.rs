@@ -21,21 +21,25 @@ use node::core::channels::mpsc;
 use node::core::log::inner::Level;
 use node::daemon_json::DaemonJson;
 use node::event_source::EventSourceAction;
-use node::ledger::{LedgerCtx, LedgerManager};
+use node::ledger::{LedgerCtx, LedgerManager, LEDGER_SYNC_CHECKPOINT_FILE_NAME};
 use node::p2p::channels::ChannelId;
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::p2p::identity::SecretKey;
+use node::p2p::service_impl::address_book::{self, ADDRESS_BOOK_FILE_NAME};
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
+use node::p2p::webrtc::{IceServer, IceServerCredentials, IceTransportPolicy, P2pIceConfig};
 use node::p2p::{P2pConfig, P2pLimits, P2pTimeouts};
 use node::service::{Recorder, Service};
 use node::snark::{get_srs, get_verifier_index, VerifierKind};
 use node::stats::Stats;
 use node::{
-    BlockProducerConfig, BuildEnv, Config, GlobalConfig, LedgerConfig, SnarkConfig, SnarkerConfig,
-    SnarkerStrategy, State, TransitionFrontierConfig,
+    BlockProducerConfig, BuildEnv, Config, GlobalConfig, LedgerConfig, SnarkConfig,
+    SnarkPoolConfig, SnarkerConfig, SnarkerStrategy, State, TransitionFrontierConfig,
 };
 
+use openmina_node_native::block_producer::won_blocks::WON_BLOCKS_DIR_NAME;
 use openmina_node_native::rpc::RpcService;
+use openmina_node_native::snark_pool::{self, SNARK_POOL_FILE_NAME};
 use openmina_node_native::{http_server, tracing, NodeService, P2pTaskSpawner, RpcSender};
 
 /// Openmina node
@@ -56,6 +60,10 @@ pub struct Node {
     #[arg(long, env, default_value = "8302")]
     pub libp2p_port: u16,
 
+    /// UDP port to listen on for QUIC connections
+    #[arg(long, env)]
+    pub quic_port: Option<u16>,
+
     /// Verbosity level
     #[arg(long, short, env, default_value = "info")]
     pub verbosity: Level,
@@ -78,9 +86,21 @@ pub struct Node {
     #[arg(long, env, default_value_t = 1_000_000)]
     pub snarker_fee: u64,
 
+    /// Strategy for picking the jobs to work on: `seq`, `rand` or `profit`
     #[arg(long, env, default_value = "seq")]
     pub snarker_strategy: SnarkerStrategy,
 
+    /// Coordinate remote snark workers, which lease jobs from this node
+    /// through the `/snarker/workers/{id}` http route.
+    #[arg(long, env)]
+    pub snarker_coordinator: bool,
+
+    /// Token the remote snark workers have to send as
+    /// `Authorization: Bearer <token>`. Without it, only the workers
+    /// running on localhost are accepted.
+    #[arg(long, env)]
+    pub snarker_coordinator_token: Option<String>,
+
     /// Mina snark worker path
     #[arg(long, env, default_value = "cli/bin/snark-worker")]
     pub snarker_exe_path: OsString,
@@ -91,10 +111,47 @@ pub struct Node {
     #[arg(long, default_value = "none")]
     pub additional_ledgers_path: Option<PathBuf>,
 
+    /// Directory to export staking and next epoch ledgers to, at each epoch
+    /// transition. Ledgers are written both as binprot and as JSON
+    /// (compatible with `mina ledger export staking-epoch-ledger`).
+    #[arg(long, env)]
+    pub export_epoch_ledgers_path: Option<PathBuf>,
+
     /// Do not use peers discovery.
     #[arg(long)]
     pub no_peers_discovery: bool,
 
+    /// Do not map the libp2p and QUIC ports on the gateway using UPnP or NAT-PMP.
+    #[arg(long)]
+    pub no_nat_port_mapping: bool,
+
+    /// Act as a circuit relay for the peers that are not reachable directly.
+    #[arg(long)]
+    pub relay_hop: bool,
+
+    /// Keep a reservation on the relay, so that the node can be reached
+    /// through it. The relay is also used as an initial peer.
+    #[arg(long, num_args = 0.., value_delimiter = ' ')]
+    pub relay: Vec<P2pConnectionOutgoingInitOpts>,
+
+    /// ICE server used for WebRTC connections, in the form
+    /// `[USERNAME:CREDENTIAL@]URL`. Replaces the default STUN servers.
+    #[arg(long, num_args = 0.., value_delimiter = ' ', env)]
+    pub ice_server: Vec<IceServer>,
+
+    /// Secret shared with the TURN servers, used to generate time-limited
+    /// credentials for the ones that are passed without credentials.
+    #[arg(long, env)]
+    pub turn_shared_secret: Option<String>,
+
+    /// Lifetime of the generated TURN credentials, in seconds.
+    #[arg(long, env, default_value_t = 86400)]
+    pub turn_credentials_ttl: u64,
+
+    /// Only use candidates relayed through TURN servers for WebRTC connections.
+    #[arg(long)]
+    pub ice_relay_only: bool,
+
     /// Config JSON file to load at startup.
     // TODO: make this argument required.
     #[arg(short = 'c', long, env)]
@@ -182,6 +239,24 @@ impl Node {
             })?;
 
         let work_dir = shellexpand::full(&self.work_dir).unwrap().into_owned();
+        let address_book_path = PathBuf::from(&work_dir).join(ADDRESS_BOOK_FILE_NAME);
+        let address_book = address_book::load(&address_book_path).unwrap_or_else(|e| {
+            openmina_core::warn!(openmina_core::log::system_time();
+                    kind = "AddressBookError",
+                    summary = "failed to load p2p address book",
+                    error = format!("{e}"));
+            Vec::new()
+        });
+        let snark_pool_path = PathBuf::from(&work_dir).join(SNARK_POOL_FILE_NAME);
+        let persisted_snarks = snark_pool::load(&snark_pool_path).unwrap_or_else(|e| {
+            openmina_core::warn!(openmina_core::log::system_time();
+                    kind = "SnarkPoolError",
+                    summary = "failed to load persisted snarks",
+                    error = format!("{e}"));
+            Vec::new()
+        });
+        let won_blocks_dir = PathBuf::from(&work_dir).join(WON_BLOCKS_DIR_NAME);
+        let ice = self.ice_config();
         let rng_seed = rng.next_u64();
         let srs: Arc<_> = get_srs();
 
@@ -190,7 +265,7 @@ impl Node {
             None => node::config::BERKELEY_CONFIG.clone(),
         };
         let transition_frontier = TransitionFrontierConfig::new(genesis_config);
-        let config = Config {
+        let mut config = Config {
             ledger: LedgerConfig {},
             snark: SnarkConfig {
                 // TODO(binier): use cache
@@ -208,23 +283,33 @@ impl Node {
                     )),
                     strategy: self.snarker_strategy,
                     auto_commit: true,
+                    coordinator: self.snarker_coordinator,
                     path: self.snarker_exe_path,
                 }),
             },
             p2p: P2pConfig {
                 libp2p_port: Some(self.libp2p_port),
+                quic_port: self.quic_port,
                 listen_port: self.port,
                 identity_pub_key: pub_key,
-                initial_peers: self.peers,
+                initial_peers: self.peers.into_iter().chain(self.relay.clone()).collect(),
+                address_book,
                 ask_initial_peers_interval: Duration::from_secs(3600),
                 enabled_channels: ChannelId::for_libp2p().collect(),
                 peer_discovery: !self.no_peers_discovery,
+                nat_port_mapping: !self.no_nat_port_mapping,
+                relay_hop: self.relay_hop,
+                relays: self.relay.iter().map(|opts| *opts.peer_id()).collect(),
                 initial_time: SystemTime::now()
                     .duration_since(SystemTime::UNIX_EPOCH)
                     .expect("linear time"),
                 timeouts: P2pTimeouts::default(),
                 limits: P2pLimits::default().with_max_peers(Some(100)),
             },
+            snark_pool: SnarkPoolConfig {
+                persisted_snarks,
+                save_interval: Some(Duration::from_secs(60)),
+            },
             transition_frontier,
             block_producer: block_producer.clone().map(|(config, _)| config),
         };
@@ -240,6 +325,7 @@ impl Node {
 
         let p2p_service_ctx = <NodeService as P2pServiceWebrtcWithLibp2p>::init(
             secret_key.clone(),
+            ice,
             P2pTaskSpawner {},
         );
 
@@ -247,6 +333,7 @@ impl Node {
 
         let http_port = self.port;
         let rpc_sender = RpcSender::new(rpc_service.req_sender().clone());
+        let remote_worker_token = self.snarker_coordinator_token;
 
         // spawn http-server
         let runtime = tokio::runtime::Builder::new_current_thread()
@@ -255,7 +342,9 @@ impl Node {
             .unwrap();
         std::thread::Builder::new()
             .name("openmina_http_server".to_owned())
-            .spawn(move || runtime.block_on(http_server::run(http_port, rpc_sender)))
+            .spawn(move || {
+                runtime.block_on(http_server::run(http_port, rpc_sender, remote_worker_token))
+            })
             .unwrap();
 
         let record = self.record;
@@ -266,6 +355,15 @@ impl Node {
             LedgerCtx::default()
         };
 
+        if let Some(path) = self.export_epoch_ledgers_path {
+            ledger.set_epoch_ledgers_export_dir(path);
+        }
+
+        let ledger_sync_checkpoint_path =
+            PathBuf::from(&work_dir).join(LEDGER_SYNC_CHECKPOINT_FILE_NAME);
+        config.transition_frontier.ledger_sync_checkpoint =
+            ledger.set_ledger_sync_checkpoint_path(ledger_sync_checkpoint_path);
+
         // TODO(tizoc): Only used for the current workaround to make staged ledger
         // reconstruction async, can be removed when the ledger services are made async
         ledger.set_event_sender(event_sender.clone());
@@ -288,7 +386,11 @@ impl Node {
                 peers: p2p_service_ctx.webrtc.peers,
                 #[cfg(feature = "p2p-libp2p")]
                 mio: p2p_service_ctx.mio,
+                #[cfg(feature = "p2p-libp2p")]
+                quic: p2p_service_ctx.quic,
                 network: Default::default(),
+                address_book_path: Some(address_book_path),
+                snark_pool_path: Some(snark_pool_path),
                 block_producer: None,
                 keypair,
                 rpc: rpc_service,
@@ -304,7 +406,7 @@ impl Node {
             };
 
             if let Some((_, keypair)) = block_producer {
-                service.block_producer_start(keypair);
+                service.block_producer_start(keypair, Some(won_blocks_dir));
             }
 
             let state = State::new(config);
@@ -352,4 +454,34 @@ impl Node {
 
         Ok(())
     }
+
+    fn ice_config(&self) -> P2pIceConfig {
+        let mut config = P2pIceConfig::default();
+        if !self.ice_server.is_empty() {
+            let servers = self
+                .ice_server
+                .iter()
+                .cloned()
+                .map(|mut server| {
+                    if let Some(secret) = self
+                        .turn_shared_secret
+                        .as_ref()
+                        .filter(|_| server.is_turn() && server.credentials.is_none())
+                    {
+                        server.credentials = Some(IceServerCredentials::SharedSecret {
+                            secret: secret.clone(),
+                            ttl: Duration::from_secs(self.turn_credentials_ttl),
+                            user: None,
+                        });
+                    }
+                    server
+                })
+                .collect();
+            config = config.with_servers(servers);
+        }
+        if self.ice_relay_only {
+            config = config.with_transport_policy(IceTransportPolicy::Relay);
+        }
+        config
+    }
 }
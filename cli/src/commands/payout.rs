@@ -0,0 +1,120 @@
+use mina_p2p_messages::v2::LedgerHash;
+use node::account::AccountPublicKey;
+use node::block_producer::payout::{DelegationPayouts, PayoutConfig, SuperchargedCoinbaseHandling};
+use node::rpc::RpcDelegationPayoutsGetQuery;
+
+use crate::CommandError;
+
+/// Computes delegators' shares of the rewards of blocks won by the producer
+/// in the epoch, and prints them along with the payments to sign.
+///
+/// Blocks and the staking ledger are taken from the node's view, so the
+/// node must have the epoch's blocks in its transition frontier (or in its
+/// block production stats, if it's the producer) and its staking ledger.
+#[derive(Debug, clap::Args)]
+pub struct Payout {
+    /// Address of the node's http server.
+    #[arg(
+        long,
+        default_value = "http://127.0.0.1:3000",
+        env = "OPENMINA_NODE_URL"
+    )]
+    pub node: String,
+
+    /// Public key of the block producer.
+    #[arg(long)]
+    pub producer: AccountPublicKey,
+
+    #[arg(long)]
+    pub epoch: u32,
+
+    /// Staking ledger of the epoch, if the node can't find it from its blocks.
+    #[arg(long)]
+    pub staking_ledger_hash: Option<LedgerHash>,
+
+    /// Commission kept by the producer, in basis points (`500` = 5%).
+    #[arg(long, default_value_t = 0)]
+    pub commission_bps: u16,
+
+    #[arg(long, value_enum, default_value_t = SuperchargedCoinbase::Shared)]
+    pub supercharged_coinbase: SuperchargedCoinbase,
+
+    /// Account which pays for the payouts. Defaults to the producer.
+    #[arg(long)]
+    pub payer: Option<AccountPublicKey>,
+
+    /// Nonce of the payer's first payment.
+    #[arg(long)]
+    pub nonce: u32,
+
+    /// Fee of each payment, in nanomina.
+    #[arg(long, default_value_t = 10_000_000)]
+    pub fee: u64,
+
+    /// Payouts below this amount (in nanomina) are skipped.
+    #[arg(long, default_value_t = 0)]
+    pub min_payout: u64,
+
+    #[arg(long, default_value = "")]
+    pub memo: String,
+
+    /// Global slot since genesis until which the payments are valid.
+    #[arg(long)]
+    pub valid_until: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum SuperchargedCoinbase {
+    /// Shared between all delegators like any other reward.
+    Shared,
+    /// Only goes to delegators whose accounts were unlocked when the block
+    /// was produced.
+    UnlockedOnly,
+}
+
+impl Payout {
+    pub fn run(self) -> Result<(), CommandError> {
+        let query = RpcDelegationPayoutsGetQuery {
+            producer: self.producer,
+            epoch: self.epoch,
+            staking_ledger_hash: self.staking_ledger_hash,
+            config: PayoutConfig {
+                commission_bps: self.commission_bps,
+                supercharged_coinbase: match self.supercharged_coinbase {
+                    SuperchargedCoinbase::Shared => SuperchargedCoinbaseHandling::Shared,
+                    SuperchargedCoinbase::UnlockedOnly => {
+                        SuperchargedCoinbaseHandling::UnlockedOnly
+                    }
+                },
+                payer: self.payer,
+                first_nonce: self.nonce,
+                payment_fee: self.fee,
+                min_payout: self.min_payout,
+                memo: self.memo,
+                valid_until: self.valid_until,
+            },
+        };
+
+        let url = format!("{}/block-producer/payouts", self.node.trim_end_matches('/'));
+        let response = reqwest::blocking::Client::new()
+            .post(url)
+            .json(&query)
+            .send()?;
+        if !response.status().is_success() {
+            let error: String = response.json()?;
+            return Err(error.into());
+        }
+        let payouts: DelegationPayouts = response.json()?;
+
+        eprintln!(
+            "{} blocks, total rewards: {}, producer rewards: {}, payments: {}",
+            payouts.blocks.len(),
+            payouts.total_rewards,
+            payouts.producer_rewards,
+            payouts.payments.len(),
+        );
+        println!("{}", serde_json::to_string_pretty(&payouts)?);
+
+        Ok(())
+    }
+}
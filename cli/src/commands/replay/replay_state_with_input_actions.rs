@@ -61,7 +61,11 @@ impl ReplayStateWithInputActions {
             peers: Default::default(),
             #[cfg(feature = "p2p-libp2p")]
             mio: node::p2p::service_impl::mio::MioService::mocked(),
+            #[cfg(feature = "p2p-libp2p")]
+            quic: node::p2p::service_impl::quic::QuicService::mocked(),
             network: Default::default(),
+            address_book_path: None,
+            snark_pool_path: None,
             block_producer: None,
             keypair: Keypair::generate_ed25519(),
             rpc: RpcService::new(),
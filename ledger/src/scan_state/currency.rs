@@ -477,6 +477,28 @@ macro_rules! impl_number {
                 Self(n)
             }
 
+            /// https://github.com/MinaProtocol/mina/blob/2ff0292b637684ce0372e7b8e23ec85404dc5091/src/lib/currency/currency.ml#L107
+            pub fn to_mina_string(&self) -> String {
+                const PRECISION: u32 = 9;
+                const PRECISION_EXP: $inner = (10 as $inner).pow(PRECISION);
+
+                let whole = self.0 / PRECISION_EXP;
+                let mut remainder = self.0 % PRECISION_EXP;
+
+                if remainder == 0 {
+                    return whole.to_string();
+                }
+
+                let mut num_stripped_zeros = 0;
+                while remainder % 10 == 0 {
+                    num_stripped_zeros += 1;
+                    remainder /= 10;
+                }
+
+                let width = (PRECISION - num_stripped_zeros) as usize;
+                format!("{whole}.{remainder:0width$}")
+            }
+
             pub fn to_bits(&self) -> [bool; <$inner>::BITS as usize] {
                 use crate::proofs::transaction::legacy_input::bits_iter;
 
@@ -1,4 +1,7 @@
 mod vrf_evaluator;
+pub mod won_blocks;
+
+use std::{collections::BTreeMap, path::PathBuf};
 
 use ledger::proofs::{
     block::BlockParams, gates::get_provers, generate_block_proof, transaction::ProofError,
@@ -8,8 +11,12 @@ use mina_p2p_messages::v2::{
 };
 use node::account::AccountSecretKey;
 use node::{
-    block_producer::{vrf_evaluator::VrfEvaluatorInput, BlockProducerEvent},
-    core::channels::mpsc,
+    block_producer::{
+        payout::{epoch_won_blocks_update, EpochWonBlocks},
+        vrf_evaluator::VrfEvaluatorInput,
+        BlockProducerEvent,
+    },
+    core::{block::ArcBlockWithHash, channels::mpsc},
 };
 
 use crate::NodeService;
@@ -17,22 +24,47 @@ use crate::NodeService;
 pub struct BlockProducerService {
     keypair: AccountSecretKey,
     vrf_evaluation_sender: mpsc::UnboundedSender<VrfEvaluatorInput>,
+    /// Directory where the won blocks records are persisted, if enabled.
+    won_blocks_dir: Option<PathBuf>,
+    won_blocks: BTreeMap<u32, EpochWonBlocks>,
 }
 
 impl BlockProducerService {
     pub fn new(
         keypair: AccountSecretKey,
         vrf_evaluation_sender: mpsc::UnboundedSender<VrfEvaluatorInput>,
+        won_blocks_dir: Option<PathBuf>,
     ) -> Self {
+        let producer = keypair.public_key();
+        let won_blocks = won_blocks_dir
+            .as_ref()
+            .map(|dir| {
+                won_blocks::load(dir, &producer).unwrap_or_else(|err| {
+                    node::core::warn!(node::core::log::system_time();
+                        summary = "failed to load won blocks",
+                        path = dir.display().to_string(),
+                        error = err.to_string());
+                    BTreeMap::new()
+                })
+            })
+            .unwrap_or_default();
         Self {
             keypair,
             vrf_evaluation_sender,
+            won_blocks_dir,
+            won_blocks,
         }
     }
 }
 
 impl NodeService {
-    pub fn block_producer_start(&mut self, producer_keypair: AccountSecretKey) {
+    /// Starts the block producer. Blocks won by the producer are recorded
+    /// into `won_blocks_dir`, if given.
+    pub fn block_producer_start(
+        &mut self,
+        producer_keypair: AccountSecretKey,
+        won_blocks_dir: Option<PathBuf>,
+    ) {
         let event_sender = self.event_sender.clone();
         let (vrf_evaluation_sender, vrf_evaluation_receiver) =
             mpsc::unbounded_channel::<VrfEvaluatorInput>();
@@ -40,8 +72,16 @@ impl NodeService {
         self.block_producer = Some(BlockProducerService::new(
             producer_keypair.clone(),
             vrf_evaluation_sender,
+            won_blocks_dir,
         ));
 
+        // Building provers takes a while, so do it in advance instead of
+        // paying that cost when proving the first won slot.
+        std::thread::Builder::new()
+            .name("openmina_prover_warmup".to_owned())
+            .spawn(provers_warmup)
+            .unwrap();
+
         std::thread::Builder::new()
             .name("openmina_vrf_evaluator".to_owned())
             .spawn(move || {
@@ -55,6 +95,14 @@ impl NodeService {
     }
 }
 
+fn provers_warmup() {
+    let start = std::time::Instant::now();
+    get_provers();
+    node::core::log::info!(node::core::log::system_time();
+        kind = "BlockProducerProversReady",
+        summary = format!("block provers initialized in {:?}", start.elapsed()));
+}
+
 pub fn prove(
     input: &ProverExtendBlockchainInputStableV2,
     only_verify_constraints: bool,
@@ -90,4 +138,33 @@ impl node::service::BlockProducerService for crate::NodeService {
             let _ = tx.send(BlockProducerEvent::BlockProve(block_hash, res).into());
         });
     }
+
+    fn won_blocks_record(&mut self, best_chain: &[ArcBlockWithHash]) {
+        let is_replay = self.replayer.is_some();
+        let Some(bp) = self.block_producer.as_mut() else {
+            return;
+        };
+        let producer = bp.keypair.public_key();
+        let updated = epoch_won_blocks_update(&mut bp.won_blocks, &producer, best_chain);
+        let Some(dir) = bp.won_blocks_dir.as_ref().filter(|_| !is_replay) else {
+            return;
+        };
+        for record in updated.iter().filter_map(|epoch| bp.won_blocks.get(epoch)) {
+            if let Err(err) = won_blocks::save(dir, record) {
+                node::core::warn!(node::core::log::system_time();
+                    summary = "failed to save won blocks",
+                    epoch = record.epoch,
+                    path = dir.display().to_string(),
+                    error = err.to_string());
+            }
+        }
+    }
+
+    fn won_blocks(&self, epoch: u32) -> Option<EpochWonBlocks> {
+        self.block_producer
+            .as_ref()?
+            .won_blocks
+            .get(&epoch)
+            .cloned()
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use mina_signer::Keypair;
 use node::{
     block_producer::BlockProducerVrfEvaluatorEvent,
@@ -8,35 +10,69 @@ use node::{
     core::channels::mpsc::{UnboundedReceiver, UnboundedSender},
     event_source::Event,
 };
-use vrf::{VrfEvaluationInput, VrfEvaluationOutput};
+use vrf::{VrfDelegator, VrfEvaluationOutput};
 
 use crate::NodeService;
 
+/// Number of slots evaluated at once, starting from the requested one.
+///
+/// The state machine requests slots one by one, so the results for the
+/// following slots of the batch are kept and used to answer next requests.
+const VRF_EVALUATION_BATCH_SIZE: u32 = 64;
+
 pub fn vrf_evaluator(
     event_sender: UnboundedSender<Event>,
     mut vrf_evaluation_receiver: UnboundedReceiver<VrfEvaluatorInput>,
     keypair: Keypair,
 ) {
+    // results of the last evaluated batch, valid only for the same inputs
+    let mut batch: Option<(VrfEvaluatorInput, BTreeMap<u32, VrfEvaluationOutput>)> = None;
+
     while let Some(vrf_evaluator_input) = vrf_evaluation_receiver.blocking_recv() {
-        let mut vrf_result = VrfEvaluationOutput::SlotLost(vrf_evaluator_input.global_slot);
-
-        for (index, (pub_key, stake)) in vrf_evaluator_input.delegator_table.iter() {
-            let vrf_input = VrfEvaluationInput::new(
-                keypair.clone(),
-                vrf_evaluator_input.epoch_seed.clone(),
-                pub_key.to_string(),
-                vrf_evaluator_input.global_slot,
-                *index,
-                (*stake).into(),
-                vrf_evaluator_input.total_currency.into(),
-            );
-            vrf_result = vrf::evaluate_vrf(vrf_input).unwrap();
-
-            // the first delegate that won the slot
-            if let VrfEvaluationOutput::SlotWon(_) = vrf_result {
-                break;
+        let global_slot = vrf_evaluator_input.global_slot;
+        let cached = batch
+            .as_mut()
+            .filter(|(input, _)| {
+                input.epoch_seed == vrf_evaluator_input.epoch_seed
+                    && input.staking_ledger_hash == vrf_evaluator_input.staking_ledger_hash
+            })
+            .and_then(|(_, results)| results.remove(&global_slot));
+
+        let vrf_result = match cached {
+            Some(vrf_result) => vrf_result,
+            None => {
+                let delegators = vrf_evaluator_input
+                    .delegator_table
+                    .iter()
+                    .map(|(index, (pub_key, stake))| VrfDelegator {
+                        index: *index,
+                        pub_key: pub_key.to_string(),
+                        stake: (*stake).into(),
+                    })
+                    .collect::<Vec<_>>();
+                let last_slot = global_slot.saturating_add(VRF_EVALUATION_BATCH_SIZE - 1);
+
+                // for each slot, the first delegate that won it
+                let mut results = (global_slot..=last_slot)
+                    .zip(
+                        vrf::evaluate_vrf_batch(
+                            &keypair,
+                            &vrf_evaluator_input.epoch_seed,
+                            global_slot..=last_slot,
+                            &delegators,
+                            &vrf_evaluator_input.total_currency.into(),
+                        )
+                        .unwrap(),
+                    )
+                    .collect::<BTreeMap<_, _>>();
+                let vrf_result = results
+                    .remove(&global_slot)
+                    .unwrap_or(VrfEvaluationOutput::SlotLost(global_slot));
+                batch = Some((vrf_evaluator_input.clone(), results));
+                vrf_result
             }
-        }
+        };
+
         let vrf_result_with_hash = VrfEvaluationOutputWithHash::new(
             vrf_result,
             vrf_evaluator_input.staking_ledger_hash.clone(),
@@ -0,0 +1,52 @@
+//! Stores the records of blocks won by the producer, one JSON file per epoch.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use node::account::AccountPublicKey;
+use node::block_producer::payout::EpochWonBlocks;
+
+/// Name of the won blocks directory in the node's work dir.
+pub const WON_BLOCKS_DIR_NAME: &str = "won_blocks";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WonBlocksFileError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid won blocks file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Reads the records of the `producer`, a missing directory is treated as
+/// an empty one.
+pub fn load(
+    dir: &Path,
+    producer: &AccountPublicKey,
+) -> Result<BTreeMap<u32, EpochWonBlocks>, WonBlocksFileError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut records = BTreeMap::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        let record = serde_json::from_slice::<EpochWonBlocks>(&fs::read(path)?)?;
+        if &record.producer == producer {
+            records.insert(record.epoch, record);
+        }
+    }
+    Ok(records)
+}
+
+/// Writes the record to a temporary file first and then renames it,
+/// so that the previous version stays intact if the node crashes meanwhile.
+pub fn save(dir: &Path, record: &EpochWonBlocks) -> Result<(), WonBlocksFileError> {
+    fs::create_dir_all(dir)?;
+    let tmp_path = dir.join(format!("{}.json.tmp", record.epoch));
+    fs::write(&tmp_path, serde_json::to_vec_pretty(record)?)?;
+    fs::rename(tmp_path, dir.join(format!("{}.json", record.epoch)))?;
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::{convert::Infallible, mem::size_of, str::FromStr};
+use std::{convert::Infallible, mem::size_of, net::SocketAddr, str::FromStr};
 
 use mina_p2p_messages::binprot::BinProtWrite;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -11,10 +11,13 @@ use warp::{
 
 use node::core::snark::SnarkJobId;
 use node::rpc::{
-    ActionStatsQuery, RpcBlockProducerStatsGetResponse, RpcMessageProgressResponse, RpcPeerInfo,
-    RpcRequest, RpcScanStateSummaryGetQuery, RpcScanStateSummaryGetResponse,
-    RpcSnarkPoolJobGetResponse, RpcSnarkerWorkersResponse, RpcStateGetError, RpcStatusGetResponse,
-    SyncStatsQuery,
+    ActionStatsQuery, RpcBlockProducerStatsGetResponse, RpcDelegationPayoutsGetQuery,
+    RpcDelegationPayoutsGetResponse, RpcEpochLedgerAccountsGetQuery,
+    RpcEpochLedgerAccountsGetResponse, RpcEpochLedgerKind, RpcMessageProgressResponse,
+    RpcP2pBandwidthStatsGetResponse, RpcPeerInfo, RpcRequest, RpcScanStateSummaryGetQuery,
+    RpcScanStateSummaryGetResponse, RpcSnarkPoolFeeStatsGetResponse, RpcSnarkPoolJobGetResponse,
+    RpcSnarkerRemoteWorkerRequest, RpcSnarkerRemoteWorkerResponse, RpcSnarkerWorkersResponse,
+    RpcStateGetError, RpcStatusGetResponse, SyncStatsQuery,
 };
 
 use super::rpc::{
@@ -22,7 +25,9 @@ use super::rpc::{
     RpcSnarkerJobSpecResponse, RpcStateGetResponse, RpcSyncStatsGetResponse,
 };
 
-pub async fn run(port: u16, rpc_sender: super::RpcSender) {
+/// `remote_worker_token` is the token the remote snark workers have to
+/// present. Without it, only the local workers are accepted.
+pub async fn run(port: u16, rpc_sender: super::RpcSender, remote_worker_token: Option<String>) {
     #[cfg(feature = "p2p-webrtc")]
     let signaling = {
         use node::p2p::{
@@ -243,7 +248,25 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
                 }
             });
 
-        action_stats.or(sync_stats).or(block_producer_stats)
+        let rpc_sender_clone = rpc_sender.clone();
+        let p2p_bandwidth_stats = warp::path!("stats" / "p2p_bandwidth")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcP2pBandwidthStatsGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::P2pBandwidthStatsGet)
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        action_stats
+            .or(sync_stats)
+            .or(block_producer_stats)
+            .or(p2p_bandwidth_stats)
     };
 
     let rpc_sender_clone = rpc_sender.clone();
@@ -291,6 +314,72 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    #[derive(Deserialize, Default)]
+    struct EpochLedgerAccountsQueryParams {
+        offset: Option<usize>,
+        limit: Option<usize>,
+    }
+    let epoch_ledger_accounts_get = warp::path!("epoch-ledger" / String / "accounts")
+        .and(warp::get())
+        .and(optq::<EpochLedgerAccountsQueryParams>())
+        .then(
+            move |ledger: String, params: EpochLedgerAccountsQueryParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let ledger = match ledger.as_str() {
+                        "staking" => RpcEpochLedgerKind::Staking,
+                        "next" => RpcEpochLedgerKind::Next,
+                        _ => {
+                            return with_json_reply(
+                                &"invalid arg! Expected `staking` or `next`",
+                                StatusCode::BAD_REQUEST,
+                            );
+                        }
+                    };
+                    let query = RpcEpochLedgerAccountsGetQuery {
+                        ledger,
+                        offset: params.offset.unwrap_or_default(),
+                        limit: params.limit,
+                    };
+                    let res: Option<RpcEpochLedgerAccountsGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::EpochLedgerAccountsGet(query))
+                        .await;
+                    match res {
+                        None => with_json_reply(
+                            &"response channel dropped",
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        ),
+                        Some(None) => {
+                            with_json_reply(&"epoch ledger not available", StatusCode::NOT_FOUND)
+                        }
+                        Some(resp) => with_json_reply(&resp, StatusCode::OK),
+                    }
+                }
+            },
+        );
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let delegation_payouts_get = warp::path!("block-producer" / "payouts")
+        .and(warp::post())
+        .and(warp::body::json())
+        .then(move |query: RpcDelegationPayoutsGetQuery| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcDelegationPayoutsGetResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::DelegationPayoutsGet(query))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Err(err)) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                    Some(Ok(payouts)) => with_json_reply(&payouts, StatusCode::OK),
+                }
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let snark_pool_jobs_get = warp::path!("snark-pool" / "jobs")
         .and(warp::get())
@@ -327,6 +416,25 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
         }
     });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let snark_pool_fee_stats_get = warp::path!("snark-pool" / "fee-stats")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcSnarkPoolFeeStatsGetResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkPoolFeeStatsGet)
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(resp) => with_json_reply(&resp, StatusCode::OK),
+                }
+            }
+        });
+
     // TODO(binier): make endpoint only accessible locally.
     let rpc_sender_clone = rpc_sender.clone();
     let snarker_job_commit = warp::path!("snarker" / "job" / "commit")
@@ -422,6 +530,48 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let snarker_remote_worker = warp::path!("snarker" / "workers" / String)
+        .and(warp::post())
+        .and(remote_worker_auth(remote_worker_token))
+        .and(warp::body::json())
+        .then(
+            move |worker_id: String, request: RpcSnarkerRemoteWorkerRequest| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    rpc_sender_clone
+                        .oneshot_request(RpcRequest::SnarkerRemoteWorker { worker_id, request })
+                        .await
+                        .map_or_else(
+                            dropped_channel_response,
+                            |reply: RpcSnarkerRemoteWorkerResponse| {
+                                let status = match &reply {
+                                    RpcSnarkerRemoteWorkerResponse::Ok
+                                    | RpcSnarkerRemoteWorkerResponse::Lease { .. }
+                                    | RpcSnarkerRemoteWorkerResponse::NoJob => StatusCode::OK,
+                                    RpcSnarkerRemoteWorkerResponse::LeaseLost => {
+                                        StatusCode::CONFLICT
+                                    }
+                                    RpcSnarkerRemoteWorkerResponse::UnknownWorker => {
+                                        StatusCode::NOT_FOUND
+                                    }
+                                    RpcSnarkerRemoteWorkerResponse::TooManyWorkers => {
+                                        StatusCode::SERVICE_UNAVAILABLE
+                                    }
+                                    RpcSnarkerRemoteWorkerResponse::Disabled => {
+                                        StatusCode::FORBIDDEN
+                                    }
+                                    RpcSnarkerRemoteWorkerResponse::SpecError { .. } => {
+                                        StatusCode::INTERNAL_SERVER_ERROR
+                                    }
+                                };
+                                with_json_reply(&reply, status)
+                            },
+                        )
+                }
+            },
+        );
+
     let rpc_sender_clone = rpc_sender.clone();
     let snarker_config = warp::path!("snarker" / "config")
         .and(warp::get())
@@ -451,12 +601,16 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
         .or(message_progress_get)
         .or(stats)
         .or(scan_state_summary_get)
+        .or(epoch_ledger_accounts_get)
+        .or(delegation_payouts_get)
         .or(snark_pool_jobs_get)
         .or(snark_pool_job_get)
+        .or(snark_pool_fee_stats_get)
         .or(snarker_config)
         .or(snarker_job_commit)
         .or(snarker_job_spec)
         .or(snark_workers)
+        .or(snarker_remote_worker)
         .or(healthcheck(rpc_sender.clone()))
         .or(readiness(rpc_sender.clone()))
         .or(discovery::routing_table(rpc_sender.clone()))
@@ -574,6 +728,36 @@ fn with_rpc_sender(
     warp::any().map(move || rpc_sender.clone())
 }
 
+/// Remote snark workers have to send `Authorization: Bearer <token>` if the
+/// token is set, otherwise only the requests from localhost are accepted.
+fn remote_worker_auth(
+    token: Option<String>,
+) -> impl warp::Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(
+            move |addr: Option<SocketAddr>, authorization: Option<String>| {
+                let is_authorized = match &token {
+                    Some(token) => {
+                        authorization
+                            .as_deref()
+                            .and_then(|value| value.strip_prefix("Bearer "))
+                            == Some(token.as_str())
+                    }
+                    None => addr.map_or(false, |addr| addr.ip().is_loopback()),
+                };
+                async move {
+                    if is_authorized {
+                        Ok(())
+                    } else {
+                        Err(warp::reject::custom(Unauthorized))
+                    }
+                }
+            },
+        )
+        .untuple_one()
+}
+
 const DROPPED_CHANNEL: &str = "response channel dropped, see error log for details";
 
 #[derive(Debug)]
@@ -581,12 +765,22 @@ struct DroppedChannel;
 
 impl warp::reject::Reject for DroppedChannel {}
 
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
 async fn recover(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
     if let Some(DroppedChannel) = rejection.find() {
         Ok(warp::reply::with_status(
             warp::reply::json(&serde_json::json!({"error": DROPPED_CHANNEL})),
             StatusCode::INTERNAL_SERVER_ERROR,
         ))
+    } else if let Some(Unauthorized) = rejection.find() {
+        Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "unauthorized"})),
+            StatusCode::UNAUTHORIZED,
+        ))
     } else {
         Err(rejection)
     }
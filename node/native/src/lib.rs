@@ -3,6 +3,7 @@ pub mod ext_snark_worker;
 pub mod graphql;
 pub mod http_server;
 pub mod rpc;
+pub mod snark_pool;
 pub mod tracing;
 
 mod service;
@@ -245,8 +245,20 @@ impl node::rpc::RpcService for NodeService {
         respond_scan_state_summary_get,
         RpcScanStateSummaryGetResponse
     );
+    rpc_service_impl!(
+        respond_epoch_ledger_accounts_get,
+        node::rpc::RpcEpochLedgerAccountsGetResponse
+    );
+    rpc_service_impl!(
+        respond_delegation_payouts_get,
+        node::rpc::RpcDelegationPayoutsGetResponse
+    );
     rpc_service_impl!(respond_snark_pool_get, RpcSnarkPoolGetResponse);
     rpc_service_impl!(respond_snark_pool_job_get, RpcSnarkPoolJobGetResponse);
+    rpc_service_impl!(
+        respond_snark_pool_fee_stats_get,
+        node::rpc::RpcSnarkPoolFeeStatsGetResponse
+    );
     rpc_service_impl!(respond_snarker_job_commit, RpcSnarkerJobCommitResponse);
     rpc_service_impl!(
         respond_snarker_job_spec,
@@ -256,6 +268,10 @@ impl node::rpc::RpcService for NodeService {
         respond_snarker_workers,
         node::rpc::RpcSnarkerWorkersResponse
     );
+    rpc_service_impl!(
+        respond_snarker_remote_worker,
+        node::rpc::RpcSnarkerRemoteWorkerResponse
+    );
     rpc_service_impl!(
         respond_snarker_config_get,
         node::rpc::RpcSnarkerConfigGetResponse
@@ -270,6 +286,10 @@ impl node::rpc::RpcService for NodeService {
         respond_discovery_bootstrap_stats,
         RpcDiscoveryBoostrapStatsResponse
     );
+    rpc_service_impl!(
+        respond_p2p_bandwidth_stats_get,
+        node::rpc::RpcP2pBandwidthStatsGetResponse
+    );
 }
 
 impl node::core::invariants::InvariantService for NodeService {
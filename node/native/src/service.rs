@@ -1,13 +1,14 @@
 use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
 
 use std::sync::{Arc, Mutex};
 
 use ledger::scan_state::scan_state::transaction_snark::{SokDigest, Statement};
 use libp2p_identity::Keypair;
 use mina_p2p_messages::v2::{LedgerProofProdStableV2, TransactionSnarkWorkTStableV2Proofs};
-#[cfg(feature = "p2p-libp2p")]
-use node::p2p::service_impl::mio::MioService;
 use node::p2p::service_impl::services::NativeP2pNetworkService;
+#[cfg(feature = "p2p-libp2p")]
+use node::p2p::service_impl::{mio::MioService, quic::QuicService};
 use rand::prelude::*;
 use redux::ActionMeta;
 use serde::Serialize;
@@ -18,6 +19,7 @@ use node::core::snark::{Snark, SnarkJobId};
 use node::event_source::Event;
 use node::ledger::ledger_manager::LedgerManager;
 use node::ledger::LedgerService;
+use node::p2p::address_book::{P2pAddressBookEntry, P2pAddressBookService};
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::p2p::service_impl::webrtc::{Cmd, P2pServiceWebrtc, PeerState};
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
@@ -50,7 +52,13 @@ pub struct NodeService {
     pub peers: BTreeMap<PeerId, PeerState>,
     #[cfg(feature = "p2p-libp2p")]
     pub mio: MioService,
+    #[cfg(feature = "p2p-libp2p")]
+    pub quic: QuicService,
     pub network: NativeP2pNetworkService,
+    /// File where the p2p address book is persisted, `None` disables it.
+    pub address_book_path: Option<PathBuf>,
+    /// File where the verified snarks are persisted, `None` disables it.
+    pub snark_pool_path: Option<PathBuf>,
     pub block_producer: Option<BlockProducerService>,
     pub keypair: Keypair,
     pub snark_worker_sender: Option<ext_snark_worker::ExternalSnarkWorkerFacade>,
@@ -167,6 +175,32 @@ impl P2pNetworkService for NodeService {
     }
 }
 
+impl P2pAddressBookService for NodeService {
+    fn address_book_save(&mut self, entries: Vec<P2pAddressBookEntry>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        let Some(path) = self.address_book_path.clone() else {
+            return;
+        };
+        let spawned = std::thread::Builder::new()
+            .name("p2p-address-book".to_owned())
+            .spawn(move || {
+                if let Err(err) = node::p2p::service_impl::address_book::save(&path, &entries) {
+                    node::core::warn!(node::core::log::system_time();
+                        summary = "failed to save p2p address book",
+                        path = path.display().to_string(),
+                        error = err.to_string());
+                }
+            });
+        if let Err(err) = spawned {
+            node::core::warn!(node::core::log::system_time();
+                summary = "failed to spawn p2p address book thread",
+                error = err.to_string());
+        }
+    }
+}
+
 impl EventSourceService for NodeService {
     fn next_event(&mut self) -> Option<Event> {
         self.event_receiver.try_next()
@@ -201,6 +235,10 @@ impl P2pServiceWebrtcWithLibp2p for NodeService {
     fn mio(&mut self) -> &mut MioService {
         &mut self.mio
     }
+
+    fn quic(&mut self) -> &mut QuicService {
+        &mut self.quic
+    }
 }
 
 impl SnarkBlockVerifyService for NodeService {
@@ -296,6 +334,30 @@ impl SnarkPoolService for NodeService {
             .map(|job| job.id.clone())
             .collect()
     }
+
+    fn snark_pool_save(&mut self, snarks: Vec<Snark>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        let Some(path) = self.snark_pool_path.clone() else {
+            return;
+        };
+        let spawned = std::thread::Builder::new()
+            .name("snark-pool-save".to_owned())
+            .spawn(move || {
+                if let Err(err) = crate::snark_pool::save(&path, snarks) {
+                    node::core::warn!(node::core::log::system_time();
+                        summary = "failed to save snark pool",
+                        path = path.display().to_string(),
+                        error = err.to_string());
+                }
+            });
+        if let Err(err) = spawned {
+            node::core::warn!(node::core::log::system_time();
+                summary = "failed to spawn snark pool save thread",
+                error = err.to_string());
+        }
+    }
 }
 
 impl TransitionFrontierGenesisService for NodeService {
@@ -0,0 +1,40 @@
+//! Stores the verified snarks of the snark pool as a binprot encoded file.
+
+use std::{fs, io, path::Path};
+
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+use node::core::snark::Snark;
+
+/// Name of the snark pool file in the node's work dir.
+pub const SNARK_POOL_FILE_NAME: &str = "snark_pool.bin";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnarkPoolFileError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid snark pool file: {0}")]
+    Binprot(#[from] mina_p2p_messages::binprot::Error),
+}
+
+/// Reads the persisted snarks, a missing file is treated as an empty one.
+pub fn load(path: &Path) -> Result<Vec<Snark>, SnarkPoolFileError> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(Vec::<Snark>::binprot_read(&mut bytes.as_slice())?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Writes the snarks to a temporary file first and then renames it,
+/// so that the previous version stays intact if the node crashes meanwhile.
+pub fn save(path: &Path, snarks: Vec<Snark>) -> Result<(), SnarkPoolFileError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut bytes = Vec::new();
+    snarks.binprot_write(&mut bytes)?;
+    let tmp_path = path.with_extension("bin.tmp");
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
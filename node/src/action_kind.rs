@@ -18,12 +18,15 @@ use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction;
 use crate::block_producer::BlockProducerAction;
 use crate::consensus::ConsensusAction;
 use crate::event_source::EventSourceAction;
+use crate::external_snark_worker::remote::ExternalSnarkWorkerRemoteAction;
 use crate::external_snark_worker::ExternalSnarkWorkerAction;
 use crate::ledger::read::LedgerReadAction;
 use crate::ledger::write::LedgerWriteAction;
 use crate::ledger::LedgerAction;
+use crate::p2p::address_book::P2pAddressBookAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
+use crate::p2p::channels::signaling::P2pChannelsSignalingAction;
 use crate::p2p::channels::snark::P2pChannelsSnarkAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
 use crate::p2p::channels::{P2pChannelsAction, P2pChannelsMessageReceivedAction};
@@ -33,15 +36,20 @@ use crate::p2p::connection::P2pConnectionAction;
 use crate::p2p::disconnection::P2pDisconnectionAction;
 use crate::p2p::discovery::P2pDiscoveryAction;
 use crate::p2p::identify::P2pIdentifyAction;
+use crate::p2p::nat::P2pNatAction;
 use crate::p2p::network::identify::stream::P2pNetworkIdentifyStreamAction;
 use crate::p2p::network::identify::P2pNetworkIdentifyAction;
 use crate::p2p::network::kad::bootstrap::P2pNetworkKadBootstrapAction;
 use crate::p2p::network::kad::request::P2pNetworkKadRequestAction;
 use crate::p2p::network::kad::stream::P2pNetworkKademliaStreamAction;
 use crate::p2p::network::kad::{P2pNetworkKadAction, P2pNetworkKademliaAction};
+use crate::p2p::network::node_status::P2pNetworkNodeStatusAction;
 use crate::p2p::network::noise::P2pNetworkNoiseAction;
+use crate::p2p::network::ping::P2pNetworkPingAction;
 use crate::p2p::network::pnet::P2pNetworkPnetAction;
 use crate::p2p::network::pubsub::P2pNetworkPubsubAction;
+use crate::p2p::network::quic::P2pNetworkQuicAction;
+use crate::p2p::network::relay::P2pNetworkRelayAction;
 use crate::p2p::network::rpc::P2pNetworkRpcAction;
 use crate::p2p::network::scheduler::P2pNetworkSchedulerAction;
 use crate::p2p::network::select::P2pNetworkSelectAction;
@@ -123,6 +131,18 @@ pub enum ActionKind {
     ExternalSnarkWorkerKill,
     ExternalSnarkWorkerKilled,
     ExternalSnarkWorkerPruneWork,
+    ExternalSnarkWorkerRemoteEvict,
+    ExternalSnarkWorkerRemoteHeartbeat,
+    ExternalSnarkWorkerRemoteLease,
+    ExternalSnarkWorkerRemoteLeaseRevoke,
+    ExternalSnarkWorkerRemoteLeaseTimeout,
+    ExternalSnarkWorkerRemoteRegister,
+    ExternalSnarkWorkerRemoteWorkError,
+    ExternalSnarkWorkerRemoteWorkResult,
+    ExternalSnarkWorkerRemoteWorkVerifyError,
+    ExternalSnarkWorkerRemoteWorkVerifyNext,
+    ExternalSnarkWorkerRemoteWorkVerifyPending,
+    ExternalSnarkWorkerRemoteWorkVerifySuccess,
     ExternalSnarkWorkerStart,
     ExternalSnarkWorkerStartTimeout,
     ExternalSnarkWorkerStarted,
@@ -139,6 +159,7 @@ pub enum ActionKind {
     LedgerWriteInit,
     LedgerWritePending,
     LedgerWriteSuccess,
+    P2pAddressBookSave,
     P2pChannelsBestTipInit,
     P2pChannelsBestTipPending,
     P2pChannelsBestTipReady,
@@ -156,6 +177,15 @@ pub enum ActionKind {
     P2pChannelsRpcResponseReceived,
     P2pChannelsRpcResponseSend,
     P2pChannelsRpcTimeout,
+    P2pChannelsSignalingAnswerReceived,
+    P2pChannelsSignalingAnswerRelay,
+    P2pChannelsSignalingAnswerSend,
+    P2pChannelsSignalingInit,
+    P2pChannelsSignalingOfferReceived,
+    P2pChannelsSignalingOfferRelay,
+    P2pChannelsSignalingOfferSend,
+    P2pChannelsSignalingPending,
+    P2pChannelsSignalingReady,
     P2pChannelsSnarkInit,
     P2pChannelsSnarkLibp2pBroadcast,
     P2pChannelsSnarkLibp2pReceived,
@@ -210,8 +240,15 @@ pub enum ActionKind {
     P2pDiscoveryInit,
     P2pDiscoverySuccess,
     P2pIdentifyNewRequest,
+    P2pIdentifyPush,
     P2pIdentifyUpdatePeerInformation,
     P2pInitializeInitialize,
+    P2pNatExternalAddrConfirmed,
+    P2pNatExternalAddrExpired,
+    P2pNatMapPort,
+    P2pNatMapPortError,
+    P2pNatMapPortSuccess,
+    P2pNatObservedAddr,
     P2pNetworkIdentifyStreamClose,
     P2pNetworkIdentifyStreamIncomingData,
     P2pNetworkIdentifyStreamNew,
@@ -229,10 +266,25 @@ pub enum ActionKind {
     P2pNetworkKadRequestRequestSent,
     P2pNetworkKadRequestStreamIsCreating,
     P2pNetworkKadRequestStreamReady,
+    P2pNetworkKademliaAddProviders,
     P2pNetworkKademliaAnswerFindNodeRequest,
+    P2pNetworkKademliaAnswerGetProvidersRequest,
+    P2pNetworkKademliaAnswerGetValueRequest,
+    P2pNetworkKademliaAnswerPutValueRequest,
     P2pNetworkKademliaBootstrapFinished,
+    P2pNetworkKademliaGetProviders,
+    P2pNetworkKademliaGetValue,
+    P2pNetworkKademliaPruneRecords,
+    P2pNetworkKademliaPublishProvider,
+    P2pNetworkKademliaPublishValue,
+    P2pNetworkKademliaPutValue,
+    P2pNetworkKademliaRepublishRecords,
     P2pNetworkKademliaStartBootstrap,
+    P2pNetworkKademliaStartProviding,
+    P2pNetworkKademliaStopProviding,
     P2pNetworkKademliaUpdateFindNodeRequest,
+    P2pNetworkKademliaUpdateGetProvidersRequest,
+    P2pNetworkKademliaUpdateGetValueRequest,
     P2pNetworkKademliaUpdateRoutingTable,
     P2pNetworkKademliaStreamClose,
     P2pNetworkKademliaStreamIncomingData,
@@ -244,6 +296,14 @@ pub enum ActionKind {
     P2pNetworkKademliaStreamSendResponse,
     P2pNetworkKademliaStreamWaitIncoming,
     P2pNetworkKademliaStreamWaitOutgoing,
+    P2pNetworkNodeStatusIncomingData,
+    P2pNetworkNodeStatusNewStream,
+    P2pNetworkNodeStatusPrune,
+    P2pNetworkNodeStatusRemoteClose,
+    P2pNetworkNodeStatusRequest,
+    P2pNetworkNodeStatusRequestReceived,
+    P2pNetworkNodeStatusResponseReceived,
+    P2pNetworkNodeStatusResponseSend,
     P2pNetworkNoiseDecryptedData,
     P2pNetworkNoiseHandshakeDone,
     P2pNetworkNoiseIncomingChunk,
@@ -251,6 +311,13 @@ pub enum ActionKind {
     P2pNetworkNoiseInit,
     P2pNetworkNoiseOutgoingChunk,
     P2pNetworkNoiseOutgoingData,
+    P2pNetworkPingEcho,
+    P2pNetworkPingIncomingData,
+    P2pNetworkPingNewStream,
+    P2pNetworkPingPing,
+    P2pNetworkPingPong,
+    P2pNetworkPingRemoteClose,
+    P2pNetworkPingTimeout,
     P2pNetworkPnetIncomingData,
     P2pNetworkPnetOutgoingData,
     P2pNetworkPnetSetupNonce,
@@ -261,6 +328,37 @@ pub enum ActionKind {
     P2pNetworkPubsubOutgoingData,
     P2pNetworkPubsubOutgoingMessage,
     P2pNetworkPubsubSign,
+    P2pNetworkQuicConnectionClosed,
+    P2pNetworkQuicIncomingData,
+    P2pNetworkQuicIncomingDidAccept,
+    P2pNetworkQuicIncomingStream,
+    P2pNetworkQuicListen,
+    P2pNetworkQuicListenerError,
+    P2pNetworkQuicListenerReady,
+    P2pNetworkQuicOpenStream,
+    P2pNetworkQuicOutgoingConnect,
+    P2pNetworkQuicOutgoingData,
+    P2pNetworkQuicOutgoingDidConnect,
+    P2pNetworkRelayCircuitOpen,
+    P2pNetworkRelayCircuitOutgoingData,
+    P2pNetworkRelayClose,
+    P2pNetworkRelayConnect,
+    P2pNetworkRelayConnectError,
+    P2pNetworkRelayConnectionClosed,
+    P2pNetworkRelayDcutrDial,
+    P2pNetworkRelayDcutrUpgrade,
+    P2pNetworkRelayHopConnect,
+    P2pNetworkRelayHopReserve,
+    P2pNetworkRelayHopSplice,
+    P2pNetworkRelayIncomingData,
+    P2pNetworkRelayIncomingMessage,
+    P2pNetworkRelayNewStream,
+    P2pNetworkRelayOutgoingMessage,
+    P2pNetworkRelayPrune,
+    P2pNetworkRelayRemoteClose,
+    P2pNetworkRelayReserve,
+    P2pNetworkRelayReserveError,
+    P2pNetworkRelayReserveSuccess,
     P2pNetworkRpcIncomingData,
     P2pNetworkRpcIncomingMessage,
     P2pNetworkRpcInit,
@@ -300,15 +398,27 @@ pub enum ActionKind {
     P2pNetworkYamuxPingStream,
     P2pPeerBestTipUpdate,
     P2pPeerDiscovered,
+    P2pPeerIceCandidatePairSelected,
     P2pPeerReady,
+    P2pPeerRttUpdate,
+    P2pPeerUsefulBlockReceived,
     RpcActionStatsGet,
     RpcBlockProducerStatsGet,
+    RpcDelegationPayoutsGetInit,
+    RpcDelegationPayoutsGetPending,
+    RpcDelegationPayoutsGetSuccess,
+    RpcDelegationPayoutsLedgerGetInit,
     RpcDiscoveryBoostrapStats,
     RpcDiscoveryRoutingTable,
+    RpcEpochLedgerAccountsGetInit,
+    RpcEpochLedgerAccountsGetPending,
+    RpcEpochLedgerAccountsGetSuccess,
+    RpcEpochLedgerAccountsLedgerGetInit,
     RpcFinish,
     RpcGlobalStateGet,
     RpcHealthCheck,
     RpcMessageProgressGet,
+    RpcP2pBandwidthStatsGet,
     RpcP2pConnectionIncomingError,
     RpcP2pConnectionIncomingInit,
     RpcP2pConnectionIncomingPending,
@@ -325,10 +435,12 @@ pub enum ActionKind {
     RpcScanStateSummaryGetSuccess,
     RpcScanStateSummaryLedgerGetInit,
     RpcSnarkPoolAvailableJobsGet,
+    RpcSnarkPoolFeeStatsGet,
     RpcSnarkPoolJobGet,
     RpcSnarkerConfigGet,
     RpcSnarkerJobCommit,
     RpcSnarkerJobSpec,
+    RpcSnarkerRemoteWorker,
     RpcSnarkerWorkersGet,
     RpcStatusGet,
     RpcSyncStatsGet,
@@ -345,6 +457,12 @@ pub enum ActionKind {
     SnarkPoolJobsUpdate,
     SnarkPoolP2pSend,
     SnarkPoolP2pSendAll,
+    SnarkPoolRestore,
+    SnarkPoolRestoreVerifyError,
+    SnarkPoolRestoreVerifyNext,
+    SnarkPoolRestoreVerifyPending,
+    SnarkPoolRestoreVerifySuccess,
+    SnarkPoolSave,
     SnarkPoolWorkAdd,
     SnarkPoolCandidateInfoReceived,
     SnarkPoolCandidatePeerPrune,
@@ -378,6 +496,8 @@ pub enum ActionKind {
     TransitionFrontierSyncBlocksPeerQueryError,
     TransitionFrontierSyncBlocksPeerQueryInit,
     TransitionFrontierSyncBlocksPeerQueryPending,
+    TransitionFrontierSyncBlocksPeerQueryProofPending,
+    TransitionFrontierSyncBlocksPeerQueryProofSuccess,
     TransitionFrontierSyncBlocksPeerQueryRetry,
     TransitionFrontierSyncBlocksPeerQuerySuccess,
     TransitionFrontierSyncBlocksPeersQuery,
@@ -401,7 +521,10 @@ pub enum ActionKind {
     TransitionFrontierSyncLedgerSnarkedChildHashesAccepted,
     TransitionFrontierSyncLedgerSnarkedChildHashesReceived,
     TransitionFrontierSyncLedgerSnarkedChildHashesRejected,
+    TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncCheckpoint,
     TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncPending,
+    TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncResume,
+    TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncRootMismatch,
     TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncSuccess,
     TransitionFrontierSyncLedgerSnarkedNumAccountsAccepted,
     TransitionFrontierSyncLedgerSnarkedNumAccountsReceived,
@@ -447,7 +570,7 @@ pub enum ActionKind {
 }
 
 impl ActionKind {
-    pub const COUNT: u16 = 374;
+    pub const COUNT: u16 = 489;
 }
 
 impl std::fmt::Display for ActionKind {
@@ -500,6 +623,8 @@ impl ActionKindGet for P2pAction {
             Self::Disconnection(a) => a.kind(),
             Self::Discovery(a) => a.kind(),
             Self::Identify(a) => a.kind(),
+            Self::Nat(a) => a.kind(),
+            Self::AddressBook(a) => a.kind(),
             Self::Channels(a) => a.kind(),
             Self::Peer(a) => a.kind(),
             Self::Network(a) => a.kind(),
@@ -565,6 +690,12 @@ impl ActionKindGet for SnarkPoolAction {
             Self::P2pSend { .. } => ActionKind::SnarkPoolP2pSend,
             Self::CheckTimeouts => ActionKind::SnarkPoolCheckTimeouts,
             Self::JobCommitmentTimeout { .. } => ActionKind::SnarkPoolJobCommitmentTimeout,
+            Self::Restore { .. } => ActionKind::SnarkPoolRestore,
+            Self::RestoreVerifyNext => ActionKind::SnarkPoolRestoreVerifyNext,
+            Self::RestoreVerifyPending { .. } => ActionKind::SnarkPoolRestoreVerifyPending,
+            Self::RestoreVerifyError { .. } => ActionKind::SnarkPoolRestoreVerifyError,
+            Self::RestoreVerifySuccess { .. } => ActionKind::SnarkPoolRestoreVerifySuccess,
+            Self::Save => ActionKind::SnarkPoolSave,
         }
     }
 }
@@ -572,6 +703,7 @@ impl ActionKindGet for SnarkPoolAction {
 impl ActionKindGet for ExternalSnarkWorkerAction {
     fn kind(&self) -> ActionKind {
         match self {
+            Self::Remote(a) => a.kind(),
             Self::Start => ActionKind::ExternalSnarkWorkerStart,
             Self::Started => ActionKind::ExternalSnarkWorkerStarted,
             Self::StartTimeout { .. } => ActionKind::ExternalSnarkWorkerStartTimeout,
@@ -652,16 +784,35 @@ impl ActionKindGet for RpcAction {
             }
             Self::ScanStateSummaryGetPending { .. } => ActionKind::RpcScanStateSummaryGetPending,
             Self::ScanStateSummaryGetSuccess { .. } => ActionKind::RpcScanStateSummaryGetSuccess,
+            Self::EpochLedgerAccountsGetInit { .. } => ActionKind::RpcEpochLedgerAccountsGetInit,
+            Self::EpochLedgerAccountsLedgerGetInit { .. } => {
+                ActionKind::RpcEpochLedgerAccountsLedgerGetInit
+            }
+            Self::EpochLedgerAccountsGetPending { .. } => {
+                ActionKind::RpcEpochLedgerAccountsGetPending
+            }
+            Self::EpochLedgerAccountsGetSuccess { .. } => {
+                ActionKind::RpcEpochLedgerAccountsGetSuccess
+            }
+            Self::DelegationPayoutsGetInit { .. } => ActionKind::RpcDelegationPayoutsGetInit,
+            Self::DelegationPayoutsLedgerGetInit { .. } => {
+                ActionKind::RpcDelegationPayoutsLedgerGetInit
+            }
+            Self::DelegationPayoutsGetPending { .. } => ActionKind::RpcDelegationPayoutsGetPending,
+            Self::DelegationPayoutsGetSuccess { .. } => ActionKind::RpcDelegationPayoutsGetSuccess,
             Self::SnarkPoolAvailableJobsGet { .. } => ActionKind::RpcSnarkPoolAvailableJobsGet,
             Self::SnarkPoolJobGet { .. } => ActionKind::RpcSnarkPoolJobGet,
+            Self::SnarkPoolFeeStatsGet { .. } => ActionKind::RpcSnarkPoolFeeStatsGet,
             Self::SnarkerConfigGet { .. } => ActionKind::RpcSnarkerConfigGet,
             Self::SnarkerJobCommit { .. } => ActionKind::RpcSnarkerJobCommit,
             Self::SnarkerJobSpec { .. } => ActionKind::RpcSnarkerJobSpec,
+            Self::SnarkerRemoteWorker { .. } => ActionKind::RpcSnarkerRemoteWorker,
             Self::SnarkerWorkersGet { .. } => ActionKind::RpcSnarkerWorkersGet,
             Self::HealthCheck { .. } => ActionKind::RpcHealthCheck,
             Self::ReadinessCheck { .. } => ActionKind::RpcReadinessCheck,
             Self::DiscoveryRoutingTable { .. } => ActionKind::RpcDiscoveryRoutingTable,
             Self::DiscoveryBoostrapStats { .. } => ActionKind::RpcDiscoveryBoostrapStats,
+            Self::P2pBandwidthStatsGet { .. } => ActionKind::RpcP2pBandwidthStatsGet,
             Self::Finish { .. } => ActionKind::RpcFinish,
         }
     }
@@ -739,11 +890,33 @@ impl ActionKindGet for P2pIdentifyAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::NewRequest { .. } => ActionKind::P2pIdentifyNewRequest,
+            Self::Push { .. } => ActionKind::P2pIdentifyPush,
             Self::UpdatePeerInformation { .. } => ActionKind::P2pIdentifyUpdatePeerInformation,
         }
     }
 }
 
+impl ActionKindGet for P2pNatAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::MapPort { .. } => ActionKind::P2pNatMapPort,
+            Self::MapPortSuccess { .. } => ActionKind::P2pNatMapPortSuccess,
+            Self::MapPortError { .. } => ActionKind::P2pNatMapPortError,
+            Self::ObservedAddr { .. } => ActionKind::P2pNatObservedAddr,
+            Self::ExternalAddrConfirmed { .. } => ActionKind::P2pNatExternalAddrConfirmed,
+            Self::ExternalAddrExpired { .. } => ActionKind::P2pNatExternalAddrExpired,
+        }
+    }
+}
+
+impl ActionKindGet for P2pAddressBookAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Save => ActionKind::P2pAddressBookSave,
+        }
+    }
+}
+
 impl ActionKindGet for P2pChannelsAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -751,6 +924,7 @@ impl ActionKindGet for P2pChannelsAction {
             Self::BestTip(a) => a.kind(),
             Self::Snark(a) => a.kind(),
             Self::SnarkJobCommitment(a) => a.kind(),
+            Self::Signaling(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
         }
     }
@@ -762,6 +936,9 @@ impl ActionKindGet for P2pPeerAction {
             Self::Discovered { .. } => ActionKind::P2pPeerDiscovered,
             Self::Ready { .. } => ActionKind::P2pPeerReady,
             Self::BestTipUpdate { .. } => ActionKind::P2pPeerBestTipUpdate,
+            Self::RttUpdate { .. } => ActionKind::P2pPeerRttUpdate,
+            Self::UsefulBlockReceived { .. } => ActionKind::P2pPeerUsefulBlockReceived,
+            Self::IceCandidatePairSelected { .. } => ActionKind::P2pPeerIceCandidatePairSelected,
         }
     }
 }
@@ -778,6 +955,10 @@ impl ActionKindGet for P2pNetworkAction {
             Self::Kad(a) => a.kind(),
             Self::Pubsub(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
+            Self::NodeStatus(a) => a.kind(),
+            Self::Ping(a) => a.kind(),
+            Self::Relay(a) => a.kind(),
+            Self::Quic(a) => a.kind(),
         }
     }
 }
@@ -868,6 +1049,12 @@ impl ActionKindGet for TransitionFrontierSyncAction {
             Self::BlocksPeerQueryRetry { .. } => {
                 ActionKind::TransitionFrontierSyncBlocksPeerQueryRetry
             }
+            Self::BlocksPeerQueryProofPending { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksPeerQueryProofPending
+            }
+            Self::BlocksPeerQueryProofSuccess { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksPeerQueryProofSuccess
+            }
             Self::BlocksPeerQueryPending { .. } => {
                 ActionKind::TransitionFrontierSyncBlocksPeerQueryPending
             }
@@ -910,6 +1097,29 @@ impl ActionKindGet for SnarkPoolCandidateAction {
     }
 }
 
+impl ActionKindGet for ExternalSnarkWorkerRemoteAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Register { .. } => ActionKind::ExternalSnarkWorkerRemoteRegister,
+            Self::Heartbeat { .. } => ActionKind::ExternalSnarkWorkerRemoteHeartbeat,
+            Self::Lease { .. } => ActionKind::ExternalSnarkWorkerRemoteLease,
+            Self::WorkResult { .. } => ActionKind::ExternalSnarkWorkerRemoteWorkResult,
+            Self::WorkVerifyNext => ActionKind::ExternalSnarkWorkerRemoteWorkVerifyNext,
+            Self::WorkVerifyPending { .. } => {
+                ActionKind::ExternalSnarkWorkerRemoteWorkVerifyPending
+            }
+            Self::WorkVerifyError { .. } => ActionKind::ExternalSnarkWorkerRemoteWorkVerifyError,
+            Self::WorkVerifySuccess { .. } => {
+                ActionKind::ExternalSnarkWorkerRemoteWorkVerifySuccess
+            }
+            Self::WorkError { .. } => ActionKind::ExternalSnarkWorkerRemoteWorkError,
+            Self::LeaseTimeout { .. } => ActionKind::ExternalSnarkWorkerRemoteLeaseTimeout,
+            Self::LeaseRevoke { .. } => ActionKind::ExternalSnarkWorkerRemoteLeaseRevoke,
+            Self::Evict { .. } => ActionKind::ExternalSnarkWorkerRemoteEvict,
+        }
+    }
+}
+
 impl ActionKindGet for BlockProducerVrfEvaluatorAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1077,6 +1287,22 @@ impl ActionKindGet for P2pChannelsSnarkJobCommitmentAction {
     }
 }
 
+impl ActionKindGet for P2pChannelsSignalingAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::P2pChannelsSignalingInit,
+            Self::Pending { .. } => ActionKind::P2pChannelsSignalingPending,
+            Self::Ready { .. } => ActionKind::P2pChannelsSignalingReady,
+            Self::OfferSend { .. } => ActionKind::P2pChannelsSignalingOfferSend,
+            Self::OfferReceived { .. } => ActionKind::P2pChannelsSignalingOfferReceived,
+            Self::OfferRelay { .. } => ActionKind::P2pChannelsSignalingOfferRelay,
+            Self::AnswerSend { .. } => ActionKind::P2pChannelsSignalingAnswerSend,
+            Self::AnswerReceived { .. } => ActionKind::P2pChannelsSignalingAnswerReceived,
+            Self::AnswerRelay { .. } => ActionKind::P2pChannelsSignalingAnswerRelay,
+        }
+    }
+}
+
 impl ActionKindGet for P2pChannelsRpcAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1219,6 +1445,80 @@ impl ActionKindGet for P2pNetworkRpcAction {
     }
 }
 
+impl ActionKindGet for P2pNetworkNodeStatusAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::NewStream { .. } => ActionKind::P2pNetworkNodeStatusNewStream,
+            Self::RequestReceived { .. } => ActionKind::P2pNetworkNodeStatusRequestReceived,
+            Self::ResponseSend { .. } => ActionKind::P2pNetworkNodeStatusResponseSend,
+            Self::Request { .. } => ActionKind::P2pNetworkNodeStatusRequest,
+            Self::IncomingData { .. } => ActionKind::P2pNetworkNodeStatusIncomingData,
+            Self::RemoteClose { .. } => ActionKind::P2pNetworkNodeStatusRemoteClose,
+            Self::ResponseReceived { .. } => ActionKind::P2pNetworkNodeStatusResponseReceived,
+            Self::Prune { .. } => ActionKind::P2pNetworkNodeStatusPrune,
+        }
+    }
+}
+
+impl ActionKindGet for P2pNetworkPingAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::NewStream { .. } => ActionKind::P2pNetworkPingNewStream,
+            Self::IncomingData { .. } => ActionKind::P2pNetworkPingIncomingData,
+            Self::Echo { .. } => ActionKind::P2pNetworkPingEcho,
+            Self::Ping { .. } => ActionKind::P2pNetworkPingPing,
+            Self::Pong { .. } => ActionKind::P2pNetworkPingPong,
+            Self::Timeout { .. } => ActionKind::P2pNetworkPingTimeout,
+            Self::RemoteClose { .. } => ActionKind::P2pNetworkPingRemoteClose,
+        }
+    }
+}
+
+impl ActionKindGet for P2pNetworkRelayAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::NewStream { .. } => ActionKind::P2pNetworkRelayNewStream,
+            Self::IncomingData { .. } => ActionKind::P2pNetworkRelayIncomingData,
+            Self::IncomingMessage { .. } => ActionKind::P2pNetworkRelayIncomingMessage,
+            Self::OutgoingMessage { .. } => ActionKind::P2pNetworkRelayOutgoingMessage,
+            Self::RemoteClose { .. } => ActionKind::P2pNetworkRelayRemoteClose,
+            Self::Close { .. } => ActionKind::P2pNetworkRelayClose,
+            Self::Prune { .. } => ActionKind::P2pNetworkRelayPrune,
+            Self::ConnectionClosed { .. } => ActionKind::P2pNetworkRelayConnectionClosed,
+            Self::Reserve { .. } => ActionKind::P2pNetworkRelayReserve,
+            Self::ReserveSuccess { .. } => ActionKind::P2pNetworkRelayReserveSuccess,
+            Self::ReserveError { .. } => ActionKind::P2pNetworkRelayReserveError,
+            Self::Connect { .. } => ActionKind::P2pNetworkRelayConnect,
+            Self::ConnectError { .. } => ActionKind::P2pNetworkRelayConnectError,
+            Self::CircuitOpen { .. } => ActionKind::P2pNetworkRelayCircuitOpen,
+            Self::CircuitOutgoingData { .. } => ActionKind::P2pNetworkRelayCircuitOutgoingData,
+            Self::HopReserve { .. } => ActionKind::P2pNetworkRelayHopReserve,
+            Self::HopConnect { .. } => ActionKind::P2pNetworkRelayHopConnect,
+            Self::HopSplice { .. } => ActionKind::P2pNetworkRelayHopSplice,
+            Self::DcutrUpgrade { .. } => ActionKind::P2pNetworkRelayDcutrUpgrade,
+            Self::DcutrDial { .. } => ActionKind::P2pNetworkRelayDcutrDial,
+        }
+    }
+}
+
+impl ActionKindGet for P2pNetworkQuicAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Listen { .. } => ActionKind::P2pNetworkQuicListen,
+            Self::ListenerReady { .. } => ActionKind::P2pNetworkQuicListenerReady,
+            Self::ListenerError { .. } => ActionKind::P2pNetworkQuicListenerError,
+            Self::OutgoingConnect { .. } => ActionKind::P2pNetworkQuicOutgoingConnect,
+            Self::OutgoingDidConnect { .. } => ActionKind::P2pNetworkQuicOutgoingDidConnect,
+            Self::IncomingDidAccept { .. } => ActionKind::P2pNetworkQuicIncomingDidAccept,
+            Self::OpenStream { .. } => ActionKind::P2pNetworkQuicOpenStream,
+            Self::IncomingStream { .. } => ActionKind::P2pNetworkQuicIncomingStream,
+            Self::IncomingData { .. } => ActionKind::P2pNetworkQuicIncomingData,
+            Self::OutgoingData { .. } => ActionKind::P2pNetworkQuicOutgoingData,
+            Self::ConnectionClosed { .. } => ActionKind::P2pNetworkQuicConnectionClosed,
+        }
+    }
+}
+
 impl ActionKindGet for TransitionFrontierSyncLedgerAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1254,6 +1554,31 @@ impl ActionKindGet for P2pNetworkKademliaAction {
             Self::StartBootstrap { .. } => ActionKind::P2pNetworkKademliaStartBootstrap,
             Self::BootstrapFinished => ActionKind::P2pNetworkKademliaBootstrapFinished,
             Self::UpdateRoutingTable { .. } => ActionKind::P2pNetworkKademliaUpdateRoutingTable,
+            Self::AnswerGetProvidersRequest { .. } => {
+                ActionKind::P2pNetworkKademliaAnswerGetProvidersRequest
+            }
+            Self::AnswerGetValueRequest { .. } => {
+                ActionKind::P2pNetworkKademliaAnswerGetValueRequest
+            }
+            Self::AnswerPutValueRequest { .. } => {
+                ActionKind::P2pNetworkKademliaAnswerPutValueRequest
+            }
+            Self::AddProviders { .. } => ActionKind::P2pNetworkKademliaAddProviders,
+            Self::UpdateGetProvidersRequest { .. } => {
+                ActionKind::P2pNetworkKademliaUpdateGetProvidersRequest
+            }
+            Self::UpdateGetValueRequest { .. } => {
+                ActionKind::P2pNetworkKademliaUpdateGetValueRequest
+            }
+            Self::StartProviding { .. } => ActionKind::P2pNetworkKademliaStartProviding,
+            Self::StopProviding { .. } => ActionKind::P2pNetworkKademliaStopProviding,
+            Self::PutValue { .. } => ActionKind::P2pNetworkKademliaPutValue,
+            Self::GetProviders { .. } => ActionKind::P2pNetworkKademliaGetProviders,
+            Self::GetValue { .. } => ActionKind::P2pNetworkKademliaGetValue,
+            Self::RepublishRecords => ActionKind::P2pNetworkKademliaRepublishRecords,
+            Self::PublishProvider { .. } => ActionKind::P2pNetworkKademliaPublishProvider,
+            Self::PublishValue { .. } => ActionKind::P2pNetworkKademliaPublishValue,
+            Self::PruneRecords => ActionKind::P2pNetworkKademliaPruneRecords,
         }
     }
 }
@@ -1336,6 +1661,12 @@ impl ActionKindGet for TransitionFrontierSyncLedgerSnarkedAction {
             Self::MerkleTreeSyncPending => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncPending
             }
+            Self::MerkleTreeSyncResume { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncResume
+            }
+            Self::MerkleTreeSyncCheckpoint => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncCheckpoint
+            }
             Self::PeerQueryAddressInit { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedPeerQueryAddressInit
             }
@@ -1372,6 +1703,9 @@ impl ActionKindGet for TransitionFrontierSyncLedgerSnarkedAction {
             Self::MerkleTreeSyncSuccess => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncSuccess
             }
+            Self::MerkleTreeSyncRootMismatch { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedMerkleTreeSyncRootMismatch
+            }
             Self::Success => ActionKind::TransitionFrontierSyncLedgerSnarkedSuccess,
         }
     }
@@ -86,12 +86,18 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     && won_slot.global_slot() >= state.cur_global_slot().unwrap()
                     && won_slot > best_tip
             }),
-            BlockProducerAction::WonSlotWait => state
-                .block_producer
-                .with(false, |this| this.current.won_slot_should_wait(time)),
-            BlockProducerAction::WonSlotProduceInit => state
-                .block_producer
-                .with(false, |this| this.current.won_slot_should_produce(time)),
+            BlockProducerAction::WonSlotWait => state.block_producer.with(false, |this| {
+                let lead_time = this.production_time.lead_time();
+                this.current.won_slot_should_wait(time, lead_time)
+            }),
+            BlockProducerAction::WonSlotProduceInit => state.block_producer.with(false, |this| {
+                let lead_time = this.production_time.lead_time();
+                this.current.won_slot_should_produce(time, lead_time)
+                    || state
+                        .transition_frontier
+                        .best_tip()
+                        .map_or(false, |tip| this.current.won_slot_should_restart(time, tip))
+            }),
             BlockProducerAction::StagedLedgerDiffCreateInit => {
                 state.block_producer.with(false, |this| {
                     matches!(
@@ -149,7 +155,7 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                 )
             }),
             BlockProducerAction::BlockInject => state.block_producer.with(false, |this| {
-                matches!(this.current, BlockProducerCurrentState::Produced { .. })
+                this.current.produced_block_should_inject(time)
                     && !state.transition_frontier.sync.is_commit_pending()
             }),
             BlockProducerAction::BlockInjected => state.block_producer.with(false, |this| {
@@ -80,6 +80,10 @@ pub fn block_producer_effects<S: crate::Service>(
                 .with(None, |bp| bp.current.won_slot_should_discard(&best_tip))
             {
                 store.dispatch(BlockProducerAction::WonSlotDiscard { reason });
+            } else if store.state().block_producer.with(false, |bp| {
+                bp.current.won_slot_should_restart(meta.time(), &best_tip)
+            }) {
+                store.dispatch(BlockProducerAction::WonSlotProduceInit);
             } else {
                 store.dispatch(BlockProducerAction::WonSlotSearch);
             }
@@ -97,6 +101,15 @@ pub fn block_producer_effects<S: crate::Service>(
             if let Some(stats) = store.service.stats() {
                 stats.block_producer().scheduled(meta.time(), &won_slot);
             }
+            if let Some(estimate) = store.state().block_producer.production_time_estimate() {
+                if estimate.exceeds_slot_budget(meta.time(), &won_slot) {
+                    openmina_core::log::warn!(meta.time();
+                        kind = "BlockProducerSlotBudgetExceeded",
+                        summary = "expected block production time exceeds the won slot budget",
+                        slot = won_slot.global_slot(),
+                        expected_production_time = format!("{:?}", estimate.total()));
+                }
+            }
             if !store.dispatch(BlockProducerAction::WonSlotWait) {
                 store.dispatch(BlockProducerAction::WonSlotProduceInit);
             }
@@ -152,9 +165,12 @@ pub fn block_producer_effects<S: crate::Service>(
         BlockProducerAction::StagedLedgerDiffCreatePending => {}
         BlockProducerAction::StagedLedgerDiffCreateSuccess { .. } => {
             if let Some(stats) = store.service.stats() {
-                stats
-                    .block_producer()
-                    .staged_ledger_diff_create_end(meta.time());
+                let stats = stats.block_producer();
+                stats.staged_ledger_diff_create_end(meta.time());
+                if let Some(estimate) = store.state.get().block_producer.production_time_estimate()
+                {
+                    stats.production_time_estimate_update(estimate);
+                }
             }
             store.dispatch(BlockProducerAction::BlockUnprovenBuild);
         }
@@ -249,7 +265,12 @@ pub fn block_producer_effects<S: crate::Service>(
         BlockProducerAction::BlockProvePending => {}
         BlockProducerAction::BlockProveSuccess { .. } => {
             if let Some(stats) = store.service.stats() {
-                stats.block_producer().proof_create_end(meta.time());
+                let stats = stats.block_producer();
+                stats.proof_create_end(meta.time());
+                if let Some(estimate) = store.state.get().block_producer.production_time_estimate()
+                {
+                    stats.production_time_estimate_update(estimate);
+                }
             }
             store.dispatch(BlockProducerAction::BlockProduced);
         }
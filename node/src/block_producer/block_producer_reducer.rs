@@ -113,6 +113,7 @@ impl BlockProducerEnabled {
             }
             BlockProducerAction::StagedLedgerDiffCreateSuccess { output } => {
                 let BlockProducerCurrentState::StagedLedgerDiffCreatePending {
+                    time: create_start,
                     won_slot,
                     chain,
                     ..
@@ -120,6 +121,10 @@ impl BlockProducerEnabled {
                 else {
                     return;
                 };
+                if let Some(measured) = meta.time().checked_sub(*create_start) {
+                    self.production_time
+                        .staged_ledger_diff_create_measured(measured);
+                }
                 self.current = BlockProducerCurrentState::StagedLedgerDiffCreateSuccess {
                     time: meta.time(),
                     won_slot: won_slot.clone(),
@@ -447,6 +452,7 @@ impl BlockProducerEnabled {
             }
             BlockProducerAction::BlockProveSuccess { proof } => {
                 if let BlockProducerCurrentState::BlockProvePending {
+                    time: prove_start,
                     won_slot,
                     chain,
                     block,
@@ -454,6 +460,9 @@ impl BlockProducerEnabled {
                     ..
                 } = std::mem::take(&mut self.current)
                 {
+                    if let Some(measured) = meta.time().checked_sub(prove_start) {
+                        self.production_time.block_prove_measured(measured);
+                    }
                     self.current = BlockProducerCurrentState::BlockProveSuccess {
                         time: meta.time(),
                         won_slot,
@@ -4,10 +4,13 @@ use mina_p2p_messages::v2::{
     MinaBaseStagedLedgerHashStableV1, ProverExtendBlockchainInputStableV2,
     StagedLedgerDiffDiffStableV2, StateHash,
 };
+use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use crate::account::AccountSecretKey;
 
+use super::payout::EpochWonBlocks;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StagedLedgerDiffCreateOutput {
     pub diff: StagedLedgerDiffDiffStableV2,
@@ -24,4 +27,11 @@ pub trait BlockProducerService {
     fn keypair(&mut self) -> Option<AccountSecretKey>;
 
     fn prove(&mut self, block_hash: StateHash, input: Box<ProverExtendBlockchainInputStableV2>);
+
+    /// Records the canonical blocks won by our producer in the new best
+    /// chain, see [`super::payout::epoch_won_blocks_update`].
+    fn won_blocks_record(&mut self, best_chain: &[ArcBlockWithHash]);
+
+    /// Recorded blocks won by our producer in the `epoch`.
+    fn won_blocks(&self, epoch: u32) -> Option<EpochWonBlocks>;
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use mina_p2p_messages::v2;
 use openmina_core::{block::ArcBlockWithHash, consensus::consensus_take};
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,19 @@ pub struct BlockProducerEnabled {
     pub config: BlockProducerConfig,
     pub vrf_evaluator: BlockProducerVrfEvaluatorState,
     pub current: BlockProducerCurrentState,
+    pub production_time: BlockProductionTimeEstimate,
+}
+
+/// Expected duration of the expensive block production steps.
+///
+/// Durations are measured during our own block production, `None` until
+/// the step was measured at least once, in which case an assumed default
+/// is used instead. Used to start producing ahead of the won slot, so
+/// that the block is ready once the slot begins.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct BlockProductionTimeEstimate {
+    pub staged_ledger_diff_create: Option<Duration>,
+    pub block_prove: Option<Duration>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -127,6 +142,7 @@ impl BlockProducerState {
             config: config.clone(),
             vrf_evaluator: BlockProducerVrfEvaluatorState::new(now),
             current: BlockProducerCurrentState::Idle { time: now },
+            production_time: Default::default(),
         }))
     }
 
@@ -183,6 +199,14 @@ impl BlockProducerState {
         self.with(None, |this| this.current.produced_block_with_chain())
     }
 
+    pub fn block_prove_pending_hash(&self) -> Option<&v2::StateHash> {
+        self.with(None, |this| this.current.block_prove_pending_hash())
+    }
+
+    pub fn production_time_estimate(&self) -> Option<&BlockProductionTimeEstimate> {
+        self.with(None, |this| Some(&this.production_time))
+    }
+
     pub fn vrf_evaluator(&self) -> Option<&BlockProducerVrfEvaluatorState> {
         self.with(None, |this| Some(&this.vrf_evaluator))
     }
@@ -249,22 +273,61 @@ impl BlockProducerCurrentState {
         }
     }
 
-    pub fn won_slot_should_wait(&self, now: redux::Timestamp) -> bool {
+    pub fn won_slot_should_wait(&self, now: redux::Timestamp, lead_time: Duration) -> bool {
         match self {
-            Self::WonSlot { won_slot, .. } => now < won_slot.slot_time,
+            Self::WonSlot { won_slot, .. } => now < won_slot.production_start_time(lead_time),
             _ => false,
         }
     }
 
-    pub fn won_slot_should_produce(&self, now: redux::Timestamp) -> bool {
+    pub fn won_slot_should_produce(&self, now: redux::Timestamp, lead_time: Duration) -> bool {
         match self {
             Self::WonSlot { won_slot, .. } | Self::WonSlotWait { won_slot, .. } => {
-                now >= won_slot.slot_time
+                now >= won_slot.production_start_time(lead_time)
             }
             _ => false,
         }
     }
 
+    /// Production started ahead of the won slot has to be restarted if
+    /// the best tip changed before the slot began, otherwise the block
+    /// would be built on top of a stale parent.
+    pub fn won_slot_should_restart(
+        &self,
+        now: redux::Timestamp,
+        best_tip: &ArcBlockWithHash,
+    ) -> bool {
+        let (Some(won_slot), Some(chain)) = (self.won_slot(), self.parent_chain()) else {
+            return false;
+        };
+        if matches!(self, Self::Injected { .. }) || now >= won_slot.slot_time {
+            return false;
+        }
+        let expected_parent = if best_tip.global_slot() == won_slot.global_slot() {
+            best_tip.pred_hash()
+        } else {
+            best_tip.hash()
+        };
+        chain.last().map(|block| block.hash()) != Some(expected_parent)
+    }
+
+    /// Hash of the block which proof is being generated.
+    pub fn block_prove_pending_hash(&self) -> Option<&v2::StateHash> {
+        match self {
+            Self::BlockProvePending { block_hash, .. } => Some(block_hash),
+            _ => None,
+        }
+    }
+
+    /// Produced block can only be broadcasted once its slot has begun,
+    /// otherwise peers would consider it to be from the future.
+    pub fn produced_block_should_inject(&self, now: redux::Timestamp) -> bool {
+        match self {
+            Self::Produced { won_slot, .. } => now >= won_slot.slot_time,
+            _ => false,
+        }
+    }
+
     pub fn won_slot_should_discard(
         &self,
         best_tip: &ArcBlockWithHash,
@@ -326,6 +389,62 @@ impl BlockProducerCurrentState {
     }
 }
 
+impl BlockProductionTimeEstimate {
+    /// Upper bound for how early we start producing before the won slot.
+    ///
+    /// Starting too early risks building on top of a best tip which
+    /// will get replaced by the block from the previous slot.
+    pub const MAX_LEAD_TIME: Duration = Duration::from_secs(60);
+
+    /// Assumed staged ledger diff creation time, until measured.
+    pub const DEFAULT_STAGED_LEDGER_DIFF_CREATE: Duration = Duration::from_secs(5);
+
+    /// Assumed block proving time, until measured.
+    pub const DEFAULT_BLOCK_PROVE: Duration = Duration::from_secs(40);
+
+    /// Weight of a new measurement in the exponential moving average.
+    const SMOOTHING: f64 = 0.3;
+
+    pub fn total(&self) -> Duration {
+        self.staged_ledger_diff_create
+            .unwrap_or(Self::DEFAULT_STAGED_LEDGER_DIFF_CREATE)
+            + self.block_prove.unwrap_or(Self::DEFAULT_BLOCK_PROVE)
+    }
+
+    /// How long before the won slot begins we should start producing.
+    pub fn lead_time(&self) -> Duration {
+        self.total().min(Self::MAX_LEAD_TIME)
+    }
+
+    /// Whether, given the expected production time, block for the
+    /// `won_slot` can't be finished before that slot ends.
+    pub fn exceeds_slot_budget(
+        &self,
+        now: redux::Timestamp,
+        won_slot: &BlockProducerWonSlot,
+    ) -> bool {
+        let start = won_slot.production_start_time(self.lead_time()).max(now);
+        start + self.total().as_nanos() as u64 > won_slot.next_slot_time()
+    }
+
+    pub fn staged_ledger_diff_create_measured(&mut self, measured: Duration) {
+        Self::observe(&mut self.staged_ledger_diff_create, measured);
+    }
+
+    pub fn block_prove_measured(&mut self, measured: Duration) {
+        Self::observe(&mut self.block_prove, measured);
+    }
+
+    fn observe(estimate: &mut Option<Duration>, measured: Duration) {
+        *estimate = Some(match *estimate {
+            None => measured,
+            Some(estimate) => {
+                estimate.mul_f64(1.0 - Self::SMOOTHING) + measured.mul_f64(Self::SMOOTHING)
+            }
+        });
+    }
+}
+
 impl Default for BlockProducerCurrentState {
     fn default() -> Self {
         Self::Idle {
@@ -333,3 +452,40 @@ impl Default for BlockProducerCurrentState {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::BlockProductionTimeEstimate;
+
+    #[test]
+    fn production_time_estimate_converges_to_measurements() {
+        let mut estimate = BlockProductionTimeEstimate::default();
+        assert_eq!(
+            estimate.total(),
+            BlockProductionTimeEstimate::DEFAULT_STAGED_LEDGER_DIFF_CREATE
+                + BlockProductionTimeEstimate::DEFAULT_BLOCK_PROVE
+        );
+
+        // first measurement replaces the assumed default
+        estimate.block_prove_measured(Duration::from_secs(10));
+        assert_eq!(estimate.block_prove, Some(Duration::from_secs(10)));
+
+        for _ in 0..50 {
+            estimate.staged_ledger_diff_create_measured(Duration::from_secs(2));
+            estimate.block_prove_measured(Duration::from_secs(20));
+        }
+        let total = estimate.total().as_secs_f64();
+        assert!((total - 22.0).abs() < 0.1, "unexpected estimate: {total}");
+        assert_eq!(estimate.lead_time(), estimate.total());
+
+        for _ in 0..50 {
+            estimate.block_prove_measured(Duration::from_secs(120));
+        }
+        assert_eq!(
+            estimate.lead_time(),
+            BlockProductionTimeEstimate::MAX_LEAD_TIME
+        );
+    }
+}
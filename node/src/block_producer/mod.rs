@@ -1,3 +1,4 @@
+pub mod payout;
 pub mod vrf_evaluator;
 
 mod block_producer_config;
@@ -113,6 +114,13 @@ impl BlockProducerWonSlot {
         ))
     }
 
+    /// Time at which we should start producing the block, given that
+    /// production is expected to take `lead_time`.
+    pub fn production_start_time(&self, lead_time: std::time::Duration) -> redux::Timestamp {
+        let slot_time = u64::from(self.slot_time);
+        redux::Timestamp::new(slot_time.saturating_sub(lead_time.as_nanos() as u64))
+    }
+
     pub fn next_slot_time(&self) -> redux::Timestamp {
         self.slot_time + 3 * 60 * 1_000_000_000_u64
     }
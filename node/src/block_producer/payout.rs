@@ -0,0 +1,676 @@
+//! Delegation payouts computation.
+//!
+//! Splits rewards of the blocks won by a producer in an epoch between
+//! its delegators, proportionally to their stake in the epoch's staking
+//! ledger, and builds a batch of payments ready to be signed by the payer.
+
+use std::collections::BTreeMap;
+
+use ledger::{
+    scan_state::currency::{Balance, Magnitude},
+    Timing,
+};
+use mina_p2p_messages::v2::LedgerHash;
+use openmina_core::block::{ArcBlockWithHash, BlockHash};
+use openmina_core::constants::CONSTRAINT_CONSTANTS;
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+use crate::stats::block_producer::ProducedBlock;
+
+const BASIS_POINTS: u128 = 10_000;
+/// Maximum length of the payment memo, in bytes.
+pub const PAYOUT_MEMO_MAX_LEN: usize = 32;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutConfig {
+    /// Commission kept by the producer, in basis points (`500` = 5%).
+    pub commission_bps: u16,
+    pub supercharged_coinbase: SuperchargedCoinbaseHandling,
+    /// Account which pays for the payouts. Defaults to the producer.
+    pub payer: Option<AccountPublicKey>,
+    /// Nonce of the first payment, incremented for each next one.
+    pub first_nonce: u32,
+    /// Fee of each payment.
+    pub payment_fee: u64,
+    /// Payouts below this amount are skipped.
+    pub min_payout: u64,
+    pub memo: String,
+    pub valid_until: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SuperchargedCoinbaseHandling {
+    /// Supercharged coinbase is shared between all delegators like any
+    /// other reward.
+    #[default]
+    Shared,
+    /// The supercharged part of the coinbase only goes to delegators whose
+    /// accounts were unlocked at the block's global slot, as only those
+    /// are eligible for supercharged rewards.
+    UnlockedOnly,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutDelegator {
+    pub public_key: AccountPublicKey,
+    pub stake: u64,
+    /// Global slot (since genesis) since which the account has no
+    /// locked tokens.
+    pub unlocked_at_global_slot: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutBlock {
+    pub hash: BlockHash,
+    pub height: u32,
+    pub global_slot_since_genesis: u32,
+    pub supercharged_coinbase: bool,
+    pub fees: u64,
+    pub snark_fees: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DelegationPayouts {
+    pub producer: AccountPublicKey,
+    pub epoch: u32,
+    pub blocks: Vec<PayoutBlock>,
+    /// Whether `blocks` are known to be all the blocks won by the producer
+    /// in the epoch. If not, rewards of the missing blocks aren't paid out.
+    pub complete: bool,
+    pub total_stake: u64,
+    pub total_rewards: u64,
+    /// Rewards kept by the producer (commission and rounding remainders).
+    pub producer_rewards: u64,
+    pub delegators: Vec<DelegatorPayout>,
+    pub payments: Vec<PayoutPayment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DelegatorPayout {
+    pub public_key: AccountPublicKey,
+    pub stake: u64,
+    pub amount: u64,
+}
+
+/// Payment with all the fields needed to sign it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutPayment {
+    pub fee_payer: AccountPublicKey,
+    pub receiver: AccountPublicKey,
+    pub amount: u64,
+    pub fee: u64,
+    pub nonce: u32,
+    pub valid_until: Option<u32>,
+    pub memo: String,
+}
+
+#[derive(Serialize, Deserialize, thiserror::Error, Debug, Clone)]
+pub enum PayoutError {
+    #[error("commission must be at most 10000 basis points, got: {0}")]
+    InvalidCommission(u16),
+    #[error("memo must be at most {PAYOUT_MEMO_MAX_LEN} bytes long")]
+    MemoTooLong,
+    #[error("producer has no stake delegated in the staking ledger")]
+    NoStake,
+    #[error("blocks won in epoch {0} are only partially known, as the node didn't follow the whole epoch")]
+    IncompleteEpoch(u32),
+}
+
+impl PayoutBlock {
+    pub fn coinbase(&self) -> u64 {
+        let coinbase = CONSTRAINT_CONSTANTS.coinbase_amount;
+        if self.supercharged_coinbase {
+            coinbase.saturating_mul(CONSTRAINT_CONSTANTS.supercharged_coinbase_factor)
+        } else {
+            coinbase
+        }
+    }
+
+    /// Rewards of the producer for the block: coinbase and transaction
+    /// fees, minus fees paid for snark work.
+    pub fn rewards(&self) -> u64 {
+        self.coinbase()
+            .saturating_add(self.fees)
+            .saturating_sub(self.snark_fees)
+    }
+
+    /// Part of the rewards which comes from the coinbase being supercharged.
+    fn supercharged_rewards(&self) -> u64 {
+        self.coinbase()
+            .saturating_sub(CONSTRAINT_CONSTANTS.coinbase_amount)
+            .min(self.rewards())
+    }
+}
+
+impl From<&ArcBlockWithHash> for PayoutBlock {
+    fn from(block: &ArcBlockWithHash) -> Self {
+        Self {
+            hash: block.hash().clone(),
+            height: block.height(),
+            global_slot_since_genesis: block.global_slot_since_genesis(),
+            supercharged_coinbase: block.consensus_state().supercharge_coinbase,
+            fees: block.body().fees_sum(),
+            snark_fees: block.body().snark_fees_sum(),
+        }
+    }
+}
+
+impl From<&ProducedBlock> for PayoutBlock {
+    fn from(block: &ProducedBlock) -> Self {
+        Self {
+            hash: block.hash.clone(),
+            height: block.height,
+            global_slot_since_genesis: block.global_slot_since_genesis,
+            supercharged_coinbase: block.supercharge_coinbase,
+            fees: block.fees,
+            snark_fees: block.snark_fees,
+        }
+    }
+}
+
+/// Whether `best_chain` contains all the canonical blocks of the `epoch`,
+/// meaning it starts before the epoch and ends after it.
+pub fn best_chain_covers_epoch(best_chain: &[ArcBlockWithHash], epoch: u32) -> bool {
+    let (Some(root), Some(best_tip)) = (best_chain.first(), best_chain.last()) else {
+        return false;
+    };
+    let root_epoch = root.consensus_state().epoch_count.as_u32();
+    let is_genesis = root.height() <= 1;
+    (root_epoch < epoch || (is_genesis && root_epoch == epoch))
+        && best_tip.consensus_state().epoch_count.as_u32() > epoch
+}
+
+/// Canonical blocks won by a producer in an epoch, recorded while the node
+/// follows the chain, so that they are known after the epoch has left the
+/// transition frontier.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EpochWonBlocks {
+    pub producer: AccountPublicKey,
+    pub epoch: u32,
+    pub staking_ledger_hash: LedgerHash,
+    /// Whether the chain was followed without gaps since the start of the
+    /// epoch.
+    pub recorded_from_start: bool,
+    /// Whether the root of the followed chain is past the epoch, so that
+    /// its blocks are final.
+    pub finalized: bool,
+    /// Root height of the last recorded best chain.
+    root_height: u32,
+    /// Best tip of the last recorded best chain.
+    best_tip: BlockHash,
+    pub blocks: Vec<PayoutBlock>,
+}
+
+/// Block of the best chain, as needed for recording won blocks.
+struct EpochChainBlock {
+    epoch: u32,
+    creator: AccountPublicKey,
+    staking_ledger_hash: LedgerHash,
+    block: PayoutBlock,
+}
+
+impl From<&ArcBlockWithHash> for EpochChainBlock {
+    fn from(block: &ArcBlockWithHash) -> Self {
+        Self {
+            epoch: block.consensus_state().epoch_count.as_u32(),
+            creator: block.consensus_state().block_creator.clone().into(),
+            staking_ledger_hash: block.staking_epoch_ledger_hash().clone(),
+            block: block.into(),
+        }
+    }
+}
+
+impl EpochWonBlocks {
+    /// Whether `blocks` are all the canonical blocks won by the producer in
+    /// the epoch.
+    pub fn is_complete(&self) -> bool {
+        self.recorded_from_start && self.finalized
+    }
+
+    fn new(producer: &AccountPublicKey, root: &EpochChainBlock, block: &EpochChainBlock) -> Self {
+        Self {
+            producer: producer.clone(),
+            epoch: block.epoch,
+            staking_ledger_hash: block.staking_ledger_hash.clone(),
+            recorded_from_start: root.epoch < block.epoch
+                || (root.epoch == block.epoch && root.block.height <= 1),
+            finalized: false,
+            root_height: root.block.height,
+            best_tip: root.block.hash.clone(),
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Replaces the blocks above the root of the `best_chain` with the ones
+    /// of the chain. The blocks below the root are final.
+    fn update(&mut self, best_chain: &[EpochChainBlock]) {
+        let (Some(root), Some(best_tip)) = (best_chain.first(), best_chain.last()) else {
+            return;
+        };
+        // blocks between the previous root and the new one are only known
+        // if the previous best tip is their descendant.
+        let is_continuous = root.block.height <= self.root_height.saturating_add(1)
+            || best_chain.iter().any(|b| b.block.hash == self.best_tip);
+        if !is_continuous {
+            self.recorded_from_start = false;
+        }
+        self.blocks.retain(|block| block.height < root.block.height);
+        self.blocks.extend(
+            best_chain
+                .iter()
+                .filter(|b| b.epoch == self.epoch && b.creator == self.producer)
+                .map(|b| b.block.clone()),
+        );
+        self.root_height = root.block.height;
+        self.best_tip = best_tip.block.hash.clone();
+        self.finalized = root.epoch > self.epoch;
+    }
+}
+
+/// Records the blocks won by the `producer` in the new `best_chain`,
+/// starting records for epochs seen for the first time. Records of
+/// finalized epochs are left as they are.
+///
+/// Returns epochs whose records were updated.
+pub fn epoch_won_blocks_update(
+    records: &mut BTreeMap<u32, EpochWonBlocks>,
+    producer: &AccountPublicKey,
+    best_chain: &[ArcBlockWithHash],
+) -> Vec<u32> {
+    let best_chain = best_chain
+        .iter()
+        .map(EpochChainBlock::from)
+        .collect::<Vec<_>>();
+    epoch_won_blocks_update_with(records, producer, &best_chain)
+}
+
+fn epoch_won_blocks_update_with(
+    records: &mut BTreeMap<u32, EpochWonBlocks>,
+    producer: &AccountPublicKey,
+    best_chain: &[EpochChainBlock],
+) -> Vec<u32> {
+    let Some(root) = best_chain.first() else {
+        return Vec::new();
+    };
+    for block in best_chain {
+        records
+            .entry(block.epoch)
+            .or_insert_with(|| EpochWonBlocks::new(producer, root, block));
+    }
+    records
+        .values_mut()
+        .filter(|record| !record.finalized)
+        .map(|record| {
+            record.update(best_chain);
+            record.epoch
+        })
+        .collect()
+}
+
+/// Returns the global slot since which the account with `timing` has no
+/// locked tokens.
+pub fn unlocked_at_global_slot(timing: &Timing) -> u32 {
+    match timing {
+        Timing::Untimed => 0,
+        Timing::Timed {
+            initial_minimum_balance,
+            cliff_time,
+            cliff_amount,
+            vesting_period,
+            vesting_increment,
+        } => {
+            let cliff_time = cliff_time.as_u32();
+            let Some(after_cliff) = initial_minimum_balance.sub_amount(*cliff_amount) else {
+                return cliff_time;
+            };
+            if after_cliff == Balance::zero() || vesting_period.is_zero() {
+                return cliff_time;
+            }
+            if vesting_increment.is_zero() {
+                return u32::MAX;
+            }
+            let (remaining, increment) = (after_cliff.as_u64(), vesting_increment.as_u64());
+            let periods = remaining / increment + u64::from(remaining % increment != 0);
+            periods
+                .saturating_mul(vesting_period.as_u32() as u64)
+                .saturating_add(cliff_time as u64)
+                .try_into()
+                .unwrap_or(u32::MAX)
+        }
+    }
+}
+
+/// Computes each delegator's share of the `blocks` rewards, and the
+/// payments to pay them out.
+///
+/// `complete` tells whether `blocks` are all the blocks won by the
+/// producer in the epoch.
+pub fn delegation_payouts(
+    producer: AccountPublicKey,
+    epoch: u32,
+    blocks: Vec<PayoutBlock>,
+    complete: bool,
+    delegators: &[PayoutDelegator],
+    config: &PayoutConfig,
+) -> Result<DelegationPayouts, PayoutError> {
+    if config.commission_bps as u128 > BASIS_POINTS {
+        return Err(PayoutError::InvalidCommission(config.commission_bps));
+    }
+    if config.memo.len() > PAYOUT_MEMO_MAX_LEN {
+        return Err(PayoutError::MemoTooLong);
+    }
+    let total_stake = delegators.iter().map(|d| d.stake as u128).sum::<u128>();
+    if total_stake == 0 {
+        return Err(PayoutError::NoStake);
+    }
+
+    let after_commission = |amount: u64| {
+        amount as u128 * (BASIS_POINTS - config.commission_bps as u128) / BASIS_POINTS
+    };
+    let mut amounts = vec![0_u128; delegators.len()];
+    // Splits `amount` between delegators for which `eligible` is true,
+    // proportionally to their stake.
+    let mut distribute = |amount: u128, eligible: &dyn Fn(&PayoutDelegator) -> bool| {
+        let stake = delegators
+            .iter()
+            .filter(|d| eligible(d))
+            .map(|d| d.stake as u128)
+            .sum::<u128>();
+        if stake == 0 {
+            return false;
+        }
+        for (i, delegator) in delegators.iter().enumerate() {
+            if eligible(delegator) {
+                amounts[i] += amount * delegator.stake as u128 / stake;
+            }
+        }
+        true
+    };
+
+    for block in &blocks {
+        let supercharged = match config.supercharged_coinbase {
+            SuperchargedCoinbaseHandling::Shared => 0,
+            SuperchargedCoinbaseHandling::UnlockedOnly => block.supercharged_rewards(),
+        };
+        let slot = block.global_slot_since_genesis;
+        if supercharged > 0
+            && !distribute(after_commission(supercharged), &|d| {
+                d.unlocked_at_global_slot <= slot
+            })
+        {
+            distribute(after_commission(supercharged), &|_| true);
+        }
+        distribute(after_commission(block.rewards() - supercharged), &|_| true);
+    }
+
+    let total_rewards = blocks.iter().map(PayoutBlock::rewards).sum::<u64>();
+    let delegators = delegators
+        .iter()
+        .zip(amounts)
+        .map(|(delegator, amount)| DelegatorPayout {
+            public_key: delegator.public_key.clone(),
+            stake: delegator.stake,
+            amount: amount as u64,
+        })
+        .collect::<Vec<_>>();
+    let distributed = delegators
+        .iter()
+        .filter(|d| d.public_key != producer)
+        .map(|d| d.amount)
+        .sum::<u64>();
+
+    let fee_payer = config.payer.clone().unwrap_or_else(|| producer.clone());
+    let payments = delegators
+        .iter()
+        .filter(|d| d.public_key != producer && d.public_key != fee_payer)
+        .filter(|d| d.amount > 0 && d.amount >= config.min_payout)
+        .zip(config.first_nonce..)
+        .map(|(delegator, nonce)| PayoutPayment {
+            fee_payer: fee_payer.clone(),
+            receiver: delegator.public_key.clone(),
+            amount: delegator.amount,
+            fee: config.payment_fee,
+            nonce,
+            valid_until: config.valid_until,
+            memo: config.memo.clone(),
+        })
+        .collect();
+
+    Ok(DelegationPayouts {
+        producer,
+        epoch,
+        blocks,
+        complete,
+        total_stake: total_stake as u64,
+        total_rewards,
+        producer_rewards: total_rewards.saturating_sub(distributed),
+        delegators,
+        payments,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use ledger::scan_state::{
+        currency::{Amount, Slot, SlotSpan},
+        transaction_logic::account_min_balance_at_slot,
+    };
+    use mina_hasher::Fp;
+
+    use super::*;
+
+    fn pk(i: usize) -> AccountPublicKey {
+        [
+            "B62qnLVz8wM7MfJsuYbjFf4UWbwrUBEL5ZdawExxxFhnGXB6siqokyM",
+            "B62qnJcRzJpdaXvi6ok3iH7BbP3R6oZtT1C9qTyUr9hNHWRf3eUAJxC",
+            "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg",
+        ][i]
+            .parse()
+            .unwrap()
+    }
+
+    fn block(supercharged_coinbase: bool) -> PayoutBlock {
+        PayoutBlock {
+            hash: "3NLx3eBDTvYmP27bUmYANzmhjL5rGe36nGW6N5XhGcuStF6Zv7ZD"
+                .parse()
+                .unwrap(),
+            height: 1,
+            global_slot_since_genesis: 100,
+            supercharged_coinbase,
+            fees: 10_000_000,
+            snark_fees: 0,
+        }
+    }
+
+    fn config(supercharged_coinbase: SuperchargedCoinbaseHandling) -> PayoutConfig {
+        PayoutConfig {
+            commission_bps: 500,
+            supercharged_coinbase,
+            payer: None,
+            first_nonce: 7,
+            payment_fee: 10_000_000,
+            min_payout: 0,
+            memo: "payout".to_owned(),
+            valid_until: None,
+        }
+    }
+
+    #[test]
+    fn payouts_are_proportional_to_stake() {
+        let delegators = [
+            PayoutDelegator {
+                public_key: pk(0),
+                stake: 1_000,
+                unlocked_at_global_slot: 0,
+            },
+            PayoutDelegator {
+                public_key: pk(1),
+                stake: 3_000,
+                unlocked_at_global_slot: 0,
+            },
+        ];
+        let blocks = vec![block(false)];
+        let config = config(SuperchargedCoinbaseHandling::Shared);
+        let payouts = delegation_payouts(pk(0), 1, blocks, true, &delegators, &config).unwrap();
+
+        let rewards = CONSTRAINT_CONSTANTS.coinbase_amount + 10_000_000;
+        let distributable = rewards / 100 * 95;
+        assert_eq!(payouts.total_rewards, rewards);
+        assert_eq!(payouts.delegators[1].amount, distributable / 4 * 3);
+        // producer's own share isn't paid out.
+        assert_eq!(payouts.payments.len(), 1);
+        assert_eq!(payouts.payments[0].receiver, pk(1));
+        assert_eq!(payouts.payments[0].nonce, 7);
+        assert_eq!(
+            payouts.producer_rewards,
+            rewards - payouts.payments[0].amount
+        );
+    }
+
+    #[test]
+    fn supercharged_rewards_go_to_unlocked_delegators() {
+        let delegators = [
+            PayoutDelegator {
+                public_key: pk(1),
+                stake: 1_000,
+                unlocked_at_global_slot: 0,
+            },
+            PayoutDelegator {
+                public_key: pk(2),
+                stake: 1_000,
+                unlocked_at_global_slot: 1_000,
+            },
+        ];
+        let mut block = block(true);
+        block.fees = 0;
+        let config = config(SuperchargedCoinbaseHandling::UnlockedOnly);
+        let payouts =
+            delegation_payouts(pk(0), 1, vec![block.clone()], true, &delegators, &config).unwrap();
+
+        let base = CONSTRAINT_CONSTANTS.coinbase_amount as u128 * 95 / 100;
+        let supercharged = block.supercharged_rewards() as u128 * 95 / 100;
+        assert_eq!(
+            payouts.delegators[0].amount as u128,
+            base / 2 + supercharged
+        );
+        assert_eq!(payouts.delegators[1].amount as u128, base / 2);
+    }
+
+    #[test]
+    fn unlocked_at_global_slot_matches_min_balance() {
+        let timing = Timing::Timed {
+            initial_minimum_balance: Balance::from_u64(1_000),
+            cliff_time: Slot::from_u32(100),
+            cliff_amount: Amount::from_u64(100),
+            vesting_period: SlotSpan::from_u32(10),
+            vesting_increment: Amount::from_u64(200),
+        };
+        let Timing::Timed {
+            initial_minimum_balance,
+            cliff_time,
+            cliff_amount,
+            vesting_period,
+            vesting_increment,
+        } = timing.clone()
+        else {
+            unreachable!()
+        };
+        let min_balance = |slot: u32| {
+            account_min_balance_at_slot(
+                Slot::from_u32(slot),
+                cliff_time,
+                cliff_amount,
+                vesting_period,
+                vesting_increment,
+                initial_minimum_balance,
+            )
+        };
+
+        let unlocked_at = unlocked_at_global_slot(&timing);
+        assert_eq!(unlocked_at, 150);
+        assert_eq!(min_balance(unlocked_at), Balance::zero());
+        assert!(min_balance(unlocked_at - 1) > Balance::zero());
+        assert_eq!(unlocked_at_global_slot(&Timing::Untimed), 0);
+    }
+
+    /// Block at `height` of the `fork`, produced by `pk(height % 2)`.
+    fn chain_block(height: u32, fork: u64, epoch: u32) -> EpochChainBlock {
+        let mut block = block(false);
+        block.hash = BlockHash::from_fp(Fp::from(height as u64 * 10 + fork));
+        block.height = height;
+        EpochChainBlock {
+            epoch,
+            creator: pk(height as usize % 2),
+            staking_ledger_hash: LedgerHash::from_fp(Fp::from(epoch as u64)),
+            block,
+        }
+    }
+
+    /// Best chain of the `fork` ending at `best_tip`, with 3 blocks before
+    /// the tip and epochs of 5 blocks.
+    fn best_chain(best_tip: u32, fork: u64) -> Vec<EpochChainBlock> {
+        (best_tip.saturating_sub(3).max(1)..=best_tip)
+            .map(|height| chain_block(height, fork, (height - 1) / 5))
+            .collect()
+    }
+
+    fn heights(record: &EpochWonBlocks) -> Vec<u32> {
+        record.blocks.iter().map(|block| block.height).collect()
+    }
+
+    #[test]
+    fn won_blocks_are_complete_once_epoch_is_final() {
+        let mut records = BTreeMap::new();
+        for best_tip in 1..=8 {
+            epoch_won_blocks_update_with(&mut records, &pk(0), &best_chain(best_tip, 0));
+            assert!(!records[&0].is_complete());
+        }
+        epoch_won_blocks_update_with(&mut records, &pk(0), &best_chain(9, 0));
+        assert!(records[&0].is_complete());
+        assert_eq!(heights(&records[&0]), vec![2, 4]);
+        assert_eq!(
+            records[&0].staking_ledger_hash,
+            LedgerHash::from_fp(Fp::from(0u64))
+        );
+
+        // finalized record isn't updated anymore.
+        let updated = epoch_won_blocks_update_with(&mut records, &pk(0), &best_chain(10, 0));
+        assert_eq!(updated, vec![1]);
+        assert!(records[&1].recorded_from_start);
+        assert_eq!(heights(&records[&1]), vec![6, 8, 10]);
+    }
+
+    #[test]
+    fn won_blocks_follow_reorgs() {
+        let mut records = BTreeMap::new();
+        for best_tip in 1..=4 {
+            epoch_won_blocks_update_with(&mut records, &pk(0), &best_chain(best_tip, 0));
+        }
+        assert_eq!(heights(&records[&0]), vec![2, 4]);
+        // block 4 gets orphaned by a fork branching off block 3.
+        let mut fork = best_chain(3, 0);
+        fork.extend([chain_block(4, 1, 0), chain_block(5, 1, 0)]);
+        fork[3].creator = pk(1);
+        fork.remove(0);
+        epoch_won_blocks_update_with(&mut records, &pk(0), &fork);
+        assert_eq!(heights(&records[&0]), vec![2]);
+        assert!(records[&0].recorded_from_start);
+    }
+
+    #[test]
+    fn won_blocks_are_incomplete_after_gap() {
+        let mut records = BTreeMap::new();
+        for best_tip in 1..=4 {
+            epoch_won_blocks_update_with(&mut records, &pk(0), &best_chain(best_tip, 0));
+        }
+        // node was offline meanwhile, so the blocks between aren't known.
+        epoch_won_blocks_update_with(&mut records, &pk(0), &best_chain(12, 1));
+        assert!(records[&0].finalized);
+        assert!(!records[&0].is_complete());
+        // the chain doesn't start before the epoch.
+        assert!(!records[&1].recorded_from_start);
+        assert!(records[&2].recorded_from_start);
+    }
+}
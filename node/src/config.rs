@@ -22,6 +22,7 @@ pub struct Config {
     pub ledger: LedgerConfig,
     pub snark: SnarkConfig,
     pub p2p: P2pConfig,
+    pub snark_pool: SnarkPoolConfig,
     pub transition_frontier: TransitionFrontierConfig,
     pub block_producer: Option<BlockProducerConfig>,
     pub global: GlobalConfig,
@@ -39,6 +40,9 @@ pub struct SnarkerConfig {
     pub fee: CurrencyFeeStableV1,
     pub strategy: SnarkerStrategy,
     pub auto_commit: bool,
+    /// Whether remote snark workers can lease jobs from this node.
+    #[serde(default)]
+    pub coordinator: bool,
     /// External Mina snark worker executable path
     pub path: OsString,
 }
@@ -47,6 +51,10 @@ pub struct SnarkerConfig {
 pub enum SnarkerStrategy {
     Sequential,
     Random,
+    /// Picks the jobs with the highest expected profit, taking into account
+    /// when the jobs are needed, competing commitments and snarks, and the
+    /// measured proving time. Commitments with a higher fee are outbid.
+    Profit,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -111,7 +119,7 @@ impl BuildEnv {
 }
 
 #[derive(thiserror::Error, Debug)]
-#[error("invalid strategy: {0}! expected one of: seq/sequential/rand/random")]
+#[error("invalid strategy: {0}! expected one of: seq/sequential/rand/random/profit")]
 pub struct SnarkerStrategyParseError(String);
 
 impl FromStr for SnarkerStrategy {
@@ -121,6 +129,7 @@ impl FromStr for SnarkerStrategy {
         Ok(match s {
             "seq" | "sequential" => SnarkerStrategy::Sequential,
             "rand" | "random" => SnarkerStrategy::Random,
+            "profit" => SnarkerStrategy::Profit,
             other => return Err(SnarkerStrategyParseError(other.to_owned())),
         })
     }
@@ -5,6 +5,7 @@ use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use crate::consensus::ConsensusBlockStatus;
+use crate::p2p::PeerId;
 use crate::snark::block_verify::SnarkBlockVerifyId;
 
 pub type ConsensusActionWithMeta = redux::ActionWithMeta<ConsensusAction>;
@@ -16,6 +17,8 @@ pub enum ConsensusAction {
         hash: StateHash,
         block: Arc<MinaBlockBlockStableV2>,
         chain_proof: Option<(Vec<StateHash>, ArcBlockWithHash)>,
+        /// Peer that sent us the block.
+        sender: PeerId,
     },
     BlockChainProofUpdate {
         hash: StateHash,
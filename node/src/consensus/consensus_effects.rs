@@ -1,3 +1,5 @@
+use crate::ledger::read::{LedgerReadAction, LedgerReadBlockVrfVerify, LedgerReadRequest};
+use crate::p2p::peer::P2pPeerAction;
 use crate::snark::block_verify::SnarkBlockVerifyAction;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::watched_accounts::WatchedAccountsAction;
@@ -36,10 +38,30 @@ pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: Consen
         ConsensusAction::LongRangeForkResolve { hash } => {
             store.dispatch(ConsensusAction::BestTipUpdate { hash });
         }
-        ConsensusAction::BestTipUpdate { .. } => {
+        ConsensusAction::BestTipUpdate { hash } => {
+            // the peer that delivered the new best tip first is worth keeping,
+            // the ones that only relayed it later are not
+            let sender = store
+                .state()
+                .consensus
+                .blocks
+                .get(&hash)
+                .map(|block| block.sender);
+            if let Some(peer_id) = sender {
+                store.dispatch(P2pPeerAction::UsefulBlockReceived { peer_id });
+            }
+
             let Some(block) = store.state.get().consensus.best_tip_block_with_hash() else {
                 return;
             };
+            // sanity check, the vrf evaluation itself is proven by the block's snark
+            if !block.is_genesis() {
+                store.dispatch(LedgerReadAction::Init {
+                    request: LedgerReadRequest::BlockVrfVerify(LedgerReadBlockVrfVerify::new(
+                        &block,
+                    )),
+                });
+            }
             for pub_key in store.state().watched_accounts.accounts() {
                 store.dispatch(WatchedAccountsAction::LedgerInitialStateGetInit {
                     pub_key: pub_key.clone(),
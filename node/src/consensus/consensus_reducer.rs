@@ -13,6 +13,7 @@ impl ConsensusState {
                 hash,
                 block,
                 chain_proof,
+                sender,
             } => {
                 self.blocks.insert(
                     hash.clone(),
@@ -20,6 +21,7 @@ impl ConsensusState {
                         block: block.clone(),
                         status: ConsensusBlockStatus::Received { time: meta.time() },
                         chain_proof: chain_proof.clone(),
+                        sender: *sender,
                     },
                 );
             }
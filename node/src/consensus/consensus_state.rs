@@ -11,6 +11,7 @@ use openmina_core::consensus::{
     ConsensusLongRangeForkDecisionReason, ConsensusShortRangeForkDecisionReason,
 };
 
+use crate::p2p::PeerId;
 use crate::snark::block_verify::SnarkBlockVerifyId;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -97,6 +98,8 @@ pub struct ConsensusBlockState {
     pub block: Arc<MinaBlockBlockStableV2>,
     pub status: ConsensusBlockStatus,
     pub chain_proof: Option<(Vec<StateHash>, ArcBlockWithHash)>,
+    /// Peer that delivered the block first.
+    pub sender: PeerId,
 }
 
 impl ConsensusBlockState {
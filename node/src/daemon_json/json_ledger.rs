@@ -7,9 +7,10 @@ use std::fmt::{self, Display, Formatter};
 
 use ledger::{
     scan_state::currency::{Amount, Balance, Magnitude, Nonce, Slot, SlotSpan, TxnVersion},
-    AuthRequired, Permissions, ReceiptChainHash, SetVerificationKey, Timing, TokenId, TokenSymbol,
-    VotingFor, ZkAppAccount, ZkAppUri,
+    AuthRequired, FpExt, Permissions, ReceiptChainHash, SetVerificationKey, Timing, TokenId,
+    TokenSymbol, VotingFor, ZkAppAccount, ZkAppUri,
 };
+use mina_p2p_messages::v2::TokenIdKeyHash;
 use openmina_node_account::{AccountPublicKey, AccountSecretKey};
 
 type RawCurrency = String;
@@ -67,9 +68,14 @@ impl Ledger {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
     pk: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     sk: Option<String>,
     balance: RawCurrency,
     delegate: Option<String>,
+    /// Base58 encoded token id, as in the daemon ledger exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token_id: Option<String>,
     token_symbol: Option<String>,
     nonce: Option<u32>,
@@ -87,6 +93,7 @@ impl Account {
             sk: None,
             balance,
             delegate,
+            token: None,
             token_id: None,
             token_symbol: None,
             nonce: None,
@@ -139,6 +146,11 @@ impl Account {
     }
 
     pub fn token_id(&self) -> Result<TokenId, AccountConfigError> {
+        if let Some(token) = self.token.as_ref() {
+            return TokenIdKeyHash::from_str(token)
+                .map(|token| token.into_inner().into())
+                .map_err(|_| AccountConfigError::MalformedTokenId(token.clone()));
+        }
         let token_fp = self
             .token_id
             .as_ref()
@@ -214,6 +226,48 @@ impl Account {
     }
 }
 
+/// Converts a ledger account into the format used by daemon ledger
+/// exports (e.g. `mina ledger export staking-epoch-ledger`).
+impl From<&ledger::Account> for Account {
+    fn from(account: &ledger::Account) -> Self {
+        let token = TokenIdKeyHash::from(account.token_id.clone()).to_string();
+        let timing = match &account.timing {
+            Timing::Untimed => None,
+            Timing::Timed {
+                initial_minimum_balance,
+                cliff_time,
+                cliff_amount,
+                vesting_period,
+                vesting_increment,
+            } => Some(AccountTiming {
+                initial_minimum_balance: initial_minimum_balance.to_mina_string(),
+                cliff_time: GlobalSlotSinceGenesis(cliff_time.as_u32()),
+                cliff_amount: cliff_amount.to_mina_string(),
+                vesting_period: GlobalSlotSpan(vesting_period.as_u32()),
+                vesting_increment: vesting_increment.to_mina_string(),
+            }),
+        };
+        Account {
+            pk: AccountPublicKey::from(account.public_key.clone()).to_string(),
+            sk: None,
+            balance: account.balance.to_mina_string(),
+            delegate: account
+                .delegate
+                .clone()
+                .map(|pk| AccountPublicKey::from(pk).to_string()),
+            token: Some(token),
+            token_id: None,
+            token_symbol: Some(account.token_symbol.0.clone()),
+            nonce: Some(account.nonce.as_u32()),
+            receipt_chain_hash: Some(account.receipt_chain_hash.0.to_decimal()),
+            voting_for: Some(account.voting_for.0.to_decimal()),
+            timing,
+            permissions: Some((&account.permissions).into()),
+            zkapp: account.zkapp.as_ref().map(Into::into),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountTiming {
     initial_minimum_balance: RawCurrency,
@@ -286,6 +340,29 @@ impl AccountPermissions {
     }
 }
 
+impl From<&Permissions<AuthRequired>> for AccountPermissions {
+    fn from(permissions: &Permissions<AuthRequired>) -> Self {
+        AccountPermissions {
+            access: permissions.access,
+            edit_state: permissions.edit_state,
+            send: permissions.send,
+            receive: permissions.receive,
+            set_delegate: permissions.set_delegate,
+            set_permissions: permissions.set_permissions,
+            set_verification_key: SerVrfKeyPerm {
+                auth: permissions.set_verification_key.auth,
+                txn_version: permissions.set_verification_key.txn_version.as_u32(),
+            },
+            set_zkapp_uri: permissions.set_zkapp_uri,
+            edit_action_state: permissions.edit_action_state,
+            set_token_symbol: permissions.set_token_symbol,
+            increment_nonce: permissions.increment_nonce,
+            set_voting_for: permissions.set_voting_for,
+            set_timing: permissions.set_timing,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Zkapp {
     app_state: Vec<String>,
@@ -355,6 +432,21 @@ impl Zkapp {
     }
 }
 
+/// Verification keys are not exported, see [`Zkapp::verification_key`].
+impl From<&ZkAppAccount> for Zkapp {
+    fn from(zkapp: &ZkAppAccount) -> Self {
+        Zkapp {
+            app_state: zkapp.app_state.iter().map(FpExt::to_decimal).collect(),
+            verification_key: None,
+            zkapp_version: zkapp.zkapp_version,
+            action_state: zkapp.action_state.iter().map(FpExt::to_decimal).collect(),
+            last_action_slot: zkapp.last_action_slot.as_u32().to_string(),
+            proved_state: zkapp.proved_state,
+            zkapp_uri: zkapp.zkapp_uri.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AccountConfigError {
     MalformedCurrencyValue(String),
@@ -30,7 +30,7 @@ mod test {
     use openmina_node_account::AccountPublicKey;
     use std::str::FromStr;
 
-    use crate::daemon_json::DaemonJson;
+    use crate::daemon_json::{Account, DaemonJson};
 
     #[test]
     fn test_daemon_json_read() {
@@ -70,4 +70,32 @@ mod test {
             panic!("Expected Timed account");
         }
     }
+
+    #[test]
+    fn test_daemon_json_account_roundtrip() {
+        let test_file = std::fs::File::open("testing/data/daemon.json").unwrap();
+        let daemon_json: DaemonJson = serde_json::from_reader(test_file).unwrap();
+        let accounts = daemon_json.ledger.unwrap().accounts.unwrap();
+        for account in accounts {
+            let account = account.to_account().unwrap();
+            let exported = serde_json::to_string(&Account::from(&account)).unwrap();
+            let imported: Account = serde_json::from_str(&exported).unwrap();
+            assert_eq!(imported.to_account().unwrap(), account);
+        }
+    }
+
+    #[test]
+    fn test_daemon_json_account_token() {
+        let mut account = ledger::Account::empty();
+        let exported = serde_json::to_value(Account::from(&account)).unwrap();
+        assert_eq!(
+            exported["token"],
+            "wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf"
+        );
+
+        account.token_id = ledger::TokenId::from(5);
+        let exported = serde_json::to_string(&Account::from(&account)).unwrap();
+        let imported: Account = serde_json::from_str(&exported).unwrap();
+        assert_eq!(imported.token_id().unwrap(), account.token_id);
+    }
 }
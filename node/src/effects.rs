@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use openmina_core::log::system_time;
 use p2p::p2p_timeout_effects;
 
@@ -5,6 +7,7 @@ use crate::block_producer::{block_producer_effects, BlockProducerAction};
 use crate::consensus::consensus_effects;
 use crate::event_source::event_source_effects;
 use crate::external_snark_worker::external_snark_worker_effects;
+use crate::external_snark_worker::remote::ExternalSnarkWorkerRemoteAction;
 use crate::ledger::ledger_effects;
 use crate::ledger::read::LedgerReadAction;
 use crate::logger::logger_effects;
@@ -14,6 +17,9 @@ use crate::snark::snark_effects;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::{snark_pool_effects, SnarkPoolAction};
 use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
+use crate::transition_frontier::sync::ledger::snarked::{
+    PeerLedgerQueryError, TransitionFrontierSyncLedgerSnarkedAction,
+};
 use crate::transition_frontier::transition_frontier_effects;
 use crate::watched_accounts::watched_accounts_effects;
 use crate::{p2p_ready, Action, ActionWithMeta, ExternalSnarkWorkerAction, Service, Store};
@@ -43,16 +49,41 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
 
                 p2p_request_best_tip_if_needed(store);
                 p2p_request_snarks_if_needed(store);
+                ledger_query_timeouts(store, &meta);
             }
 
             store.dispatch(SnarkPoolAction::CheckTimeouts);
+            store.dispatch(SnarkPoolAction::Save);
+            store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncCheckpoint);
             store.dispatch(SnarkPoolAction::P2pSendAll);
 
             store.dispatch(SnarkPoolCandidateAction::WorkFetchAll);
             store.dispatch(SnarkPoolCandidateAction::WorkVerifyNext);
+            store.dispatch(SnarkPoolAction::RestoreVerifyNext);
+            store.dispatch(ExternalSnarkWorkerRemoteAction::WorkVerifyNext);
 
             store.dispatch(ExternalSnarkWorkerAction::StartTimeout { now: meta.time() });
             store.dispatch(ExternalSnarkWorkerAction::WorkTimeout { now: meta.time() });
+            let expired_leases = store
+                .state()
+                .external_snark_worker
+                .remote()
+                .expired_leases_iter(meta.time())
+                .cloned()
+                .collect::<Vec<_>>();
+            for worker_id in expired_leases {
+                store.dispatch(ExternalSnarkWorkerRemoteAction::LeaseTimeout { worker_id });
+            }
+            let dead_workers = store
+                .state()
+                .external_snark_worker
+                .remote()
+                .dead_workers_iter(meta.time())
+                .cloned()
+                .collect::<Vec<_>>();
+            for worker_id in dead_workers {
+                store.dispatch(ExternalSnarkWorkerRemoteAction::Evict { worker_id });
+            }
 
             store.dispatch(BlockProducerAction::WonSlotProduceInit);
             store.dispatch(BlockProducerAction::BlockInject);
@@ -94,6 +125,34 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     }
 }
 
+/// Address queries of the snarked ledger sync time out sooner than the p2p
+/// `ledger_query` timeout, depending on the latency of the queried peer.
+fn ledger_query_timeouts<S: Service>(store: &mut Store<S>, meta: &redux::ActionMeta) {
+    let state = store.state();
+    let Some(p2p) = state.p2p.ready() else {
+        return;
+    };
+    let max_timeout = p2p.config.timeouts.ledger_query.unwrap_or(Duration::MAX);
+    let timeouts = state
+        .transition_frontier
+        .sync
+        .ledger()
+        .and_then(|s| s.snarked())
+        .map_or(vec![], |s| {
+            s.address_query_timeouts_iter(meta.time(), max_timeout)
+                .collect()
+        });
+    for (peer_id, rpc_id) in timeouts {
+        store.dispatch(
+            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressError {
+                peer_id,
+                rpc_id,
+                error: PeerLedgerQueryError::Timeout,
+            },
+        );
+    }
+}
+
 fn p2p_request_best_tip_if_needed<S: Service>(store: &mut Store<S>) {
     // TODO(binier): refactor
     let state = store.state();
@@ -46,20 +46,35 @@ impl std::fmt::Display for Event {
                     RpcRequest::ScanStateSummaryGet(query) => {
                         write!(f, "ScanStateSummaryGet, {query:?}")
                     }
+                    RpcRequest::EpochLedgerAccountsGet(query) => {
+                        write!(f, "EpochLedgerAccountsGet, {query:?}")
+                    }
+                    RpcRequest::DelegationPayoutsGet(query) => {
+                        write!(
+                            f,
+                            "DelegationPayoutsGet, {} {}",
+                            query.producer, query.epoch
+                        )
+                    }
                     RpcRequest::SnarkPoolGet => write!(f, "SnarkPoolGet"),
                     RpcRequest::SnarkPoolJobGet { job_id } => {
                         write!(f, "SnarkPoolJobGet, {job_id}")
                     }
+                    RpcRequest::SnarkPoolFeeStatsGet => write!(f, "SnarkPoolFeeStatsGet"),
                     RpcRequest::SnarkerConfig => write!(f, "SnarkerConfig"),
                     RpcRequest::SnarkerJobCommit { job_id } => {
                         write!(f, "SnarkerJobCommit, {job_id}")
                     }
                     RpcRequest::SnarkerJobSpec { job_id } => write!(f, "SnarkerJobSpec, {job_id}"),
                     RpcRequest::SnarkerWorkers => write!(f, "SnarkerWorkers"),
+                    RpcRequest::SnarkerRemoteWorker { worker_id, .. } => {
+                        write!(f, "SnarkerRemoteWorker, {worker_id}")
+                    }
                     RpcRequest::HealthCheck => write!(f, "HealthCheck"),
                     RpcRequest::ReadinessCheck => write!(f, "ReadinessCheck"),
                     RpcRequest::DiscoveryRoutingTable => write!(f, "DiscoveryRoutingTable"),
                     RpcRequest::DiscoveryBoostrapStats => write!(f, "DiscoveryBoostrapStats"),
+                    RpcRequest::P2pBandwidthStatsGet => write!(f, "P2pBandwidthStatsGet"),
                 }
             }
             Self::ExternalSnarkWorker(event) => {
@@ -6,6 +6,7 @@ use crate::ledger::read::LedgerReadAction;
 use crate::ledger::write::LedgerWriteAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
+use crate::p2p::channels::signaling::P2pChannelsSignalingAction;
 use crate::p2p::channels::snark::P2pChannelsSnarkAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
 use crate::p2p::channels::{ChannelId, P2pChannelsMessageReceivedAction};
@@ -13,9 +14,11 @@ use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
 use crate::p2p::connection::{P2pConnectionErrorResponse, P2pConnectionResponse};
 use crate::p2p::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
-use crate::p2p::P2pChannelEvent;
+use crate::p2p::nat::P2pNatAction;
+use crate::p2p::peer::P2pPeerAction;
 #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
-use crate::p2p::{MioEvent, P2pNetworkSchedulerAction};
+use crate::p2p::{MioEvent, P2pNetworkQuicAction, P2pNetworkSchedulerAction, QuicEvent};
+use crate::p2p::{P2pChannelEvent, P2pNatEvent};
 use crate::rpc::{RpcAction, RpcRequest};
 use crate::snark::block_verify::SnarkBlockVerifyAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
@@ -107,6 +110,51 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         }
                     }
                 },
+                #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
+                P2pEvent::QuicEvent(e) => match e {
+                    QuicEvent::ListenerReady { listener } => {
+                        store.dispatch(P2pNetworkQuicAction::ListenerReady { listener });
+                    }
+                    QuicEvent::ListenerError { listener, error } => {
+                        store.dispatch(P2pNetworkQuicAction::ListenerError { listener, error });
+                    }
+                    QuicEvent::IncomingConnectionDidAccept(addr, result) => {
+                        store.dispatch(P2pNetworkQuicAction::IncomingDidAccept { addr, result });
+                    }
+                    QuicEvent::OutgoingConnectionDidConnect(addr, result) => {
+                        store.dispatch(P2pNetworkQuicAction::OutgoingDidConnect { addr, result });
+                    }
+                    QuicEvent::IncomingStreamDidAccept(addr, stream_id) => {
+                        store.dispatch(P2pNetworkQuicAction::IncomingStream { addr, stream_id });
+                    }
+                    QuicEvent::IncomingDataDidReceive(addr, stream_id, result) => match result {
+                        Ok((data, fin)) => {
+                            store.dispatch(P2pNetworkQuicAction::IncomingData {
+                                addr,
+                                stream_id,
+                                data,
+                                fin,
+                            });
+                        }
+                        Err(e) => {
+                            store.dispatch(P2pNetworkSchedulerAction::Error {
+                                addr,
+                                error: p2p::P2pNetworkConnectionError::QuicError(e),
+                            });
+                        }
+                    },
+                    QuicEvent::ConnectionDidClose(addr, result) => {
+                        store.dispatch(P2pNetworkQuicAction::ConnectionClosed { addr, result });
+                    }
+                },
+                P2pEvent::Nat(e) => match e {
+                    P2pNatEvent::PortMapped(_, Ok(mapping)) => {
+                        store.dispatch(P2pNatAction::MapPortSuccess { mapping });
+                    }
+                    P2pNatEvent::PortMapped(transport, Err(error)) => {
+                        store.dispatch(P2pNatAction::MapPortError { transport, error });
+                    }
+                },
                 P2pEvent::Connection(e) => match e {
                     P2pConnectionEvent::OfferSdpReady(peer_id, res) => match res {
                         Err(error) => {
@@ -175,6 +223,12 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                                 });
                         }
                     },
+                    P2pConnectionEvent::CandidatePairSelected(peer_id, candidate_pair) => {
+                        store.dispatch(P2pPeerAction::IceCandidatePairSelected {
+                            peer_id,
+                            candidate_pair,
+                        });
+                    }
                     P2pConnectionEvent::Closed(peer_id) => {
                         store.dispatch(P2pDisconnectionAction::Finish { peer_id });
                     }
@@ -200,6 +254,10 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                                     peer_id,
                                 });
                             }
+                            ChannelId::Signaling => {
+                                // TODO(binier): maybe dispatch success and then ready.
+                                store.dispatch(P2pChannelsSignalingAction::Ready { peer_id });
+                            }
                             ChannelId::Rpc => {
                                 // TODO(binier): maybe dispatch success and then ready.
                                 store.dispatch(P2pChannelsRpcAction::Ready { peer_id });
@@ -291,12 +349,21 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::ScanStateSummaryGet(query) => {
                     store.dispatch(RpcAction::ScanStateSummaryGetInit { rpc_id, query });
                 }
+                RpcRequest::EpochLedgerAccountsGet(query) => {
+                    store.dispatch(RpcAction::EpochLedgerAccountsGetInit { rpc_id, query });
+                }
+                RpcRequest::DelegationPayoutsGet(query) => {
+                    store.dispatch(RpcAction::DelegationPayoutsGetInit { rpc_id, query });
+                }
                 RpcRequest::SnarkPoolGet => {
                     store.dispatch(RpcAction::SnarkPoolAvailableJobsGet { rpc_id });
                 }
                 RpcRequest::SnarkPoolJobGet { job_id } => {
                     store.dispatch(RpcAction::SnarkPoolJobGet { rpc_id, job_id });
                 }
+                RpcRequest::SnarkPoolFeeStatsGet => {
+                    store.dispatch(RpcAction::SnarkPoolFeeStatsGet { rpc_id });
+                }
                 RpcRequest::SnarkerConfig => {
                     store.dispatch(RpcAction::SnarkerConfigGet { rpc_id });
                 }
@@ -309,6 +376,13 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SnarkerWorkers => {
                     store.dispatch(RpcAction::SnarkerWorkersGet { rpc_id });
                 }
+                RpcRequest::SnarkerRemoteWorker { worker_id, request } => {
+                    store.dispatch(RpcAction::SnarkerRemoteWorker {
+                        rpc_id,
+                        worker_id,
+                        request,
+                    });
+                }
                 RpcRequest::HealthCheck => {
                     store.dispatch(RpcAction::HealthCheck { rpc_id });
                 }
@@ -321,6 +395,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::DiscoveryBoostrapStats => {
                     store.dispatch(RpcAction::DiscoveryBoostrapStats { rpc_id });
                 }
+                RpcRequest::P2pBandwidthStatsGet => {
+                    store.dispatch(RpcAction::P2pBandwidthStatsGet { rpc_id });
+                }
             },
             Event::ExternalSnarkWorker(e) => match e {
                 ExternalSnarkWorkerEvent::Started => {
@@ -369,7 +446,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             .map_or(false, |hash| hash == block_hash)
                         {
                             store.dispatch(TransitionFrontierGenesisAction::ProveSuccess { proof });
-                        } else {
+                        } else if store.state().block_producer.block_prove_pending_hash()
+                            == Some(&block_hash)
+                        {
                             store.dispatch(BlockProducerAction::BlockProveSuccess { proof });
                         }
                     }
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{snark_pool::JobSummary, State};
 
+use super::remote::ExternalSnarkWorkerRemoteAction;
 use super::{
     ExternalSnarkWorkerError, ExternalSnarkWorkerState, ExternalSnarkWorkerWorkError,
     SnarkWorkResult,
@@ -14,6 +15,8 @@ use super::{
 #[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
 #[action_event(fields(display(job_id), display(error)))]
 pub enum ExternalSnarkWorkerAction {
+    Remote(ExternalSnarkWorkerRemoteAction),
+
     Start,
     Started,
     StartTimeout {
@@ -52,8 +55,9 @@ pub type ExternalSnarkWorkerActionWithMetaRef<'a> =
     redux::ActionWithMeta<&'a ExternalSnarkWorkerAction>;
 
 impl EnablingCondition<State> for ExternalSnarkWorkerAction {
-    fn is_enabled(&self, state: &State, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &State, time: redux::Timestamp) -> bool {
         match self {
+            ExternalSnarkWorkerAction::Remote(action) => action.is_enabled(state, time),
             ExternalSnarkWorkerAction::Start => {
                 state.config.snarker.is_some()
                     && matches!(
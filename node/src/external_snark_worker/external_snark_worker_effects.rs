@@ -2,6 +2,7 @@ use openmina_core::snark::Snark;
 
 use crate::{p2p_ready, snark_pool::SnarkPoolAction};
 
+use super::remote::external_snark_worker_remote_effects;
 use super::{
     available_job_to_snark_worker_spec, ExternalSnarkWorkerAction,
     ExternalSnarkWorkerActionWithMeta,
@@ -13,6 +14,9 @@ pub fn external_snark_worker_effects<S: crate::Service>(
 ) {
     let (action, meta) = action.split();
     match action {
+        ExternalSnarkWorkerAction::Remote(action) => {
+            external_snark_worker_remote_effects(store, meta.with_action(action));
+        }
         ExternalSnarkWorkerAction::Start => {
             let Some(config) = &store.state.get().config.snarker else {
                 return;
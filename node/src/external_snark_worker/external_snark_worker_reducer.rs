@@ -5,7 +5,11 @@ use super::{
 
 impl ExternalSnarkWorkers {
     pub fn reducer(&mut self, action: ExternalSnarkWorkerActionWithMetaRef<'_>) {
-        self.0.reducer(action)
+        let (action, meta) = action.split();
+        match action {
+            ExternalSnarkWorkerAction::Remote(a) => self.1.reducer(meta.with_action(a)),
+            _ => self.0.reducer(meta.with_action(action)),
+        }
     }
 }
 
@@ -13,6 +17,9 @@ impl ExternalSnarkWorker {
     pub fn reducer(&mut self, action: ExternalSnarkWorkerActionWithMetaRef<'_>) {
         let (action, meta) = action.split();
         match action {
+            ExternalSnarkWorkerAction::Remote(_) => {
+                return;
+            }
             ExternalSnarkWorkerAction::Start => {
                 self.state = ExternalSnarkWorkerState::Starting;
             }
@@ -35,9 +42,13 @@ impl ExternalSnarkWorker {
                 self.state = ExternalSnarkWorkerState::Working(job_id.clone(), summary.clone());
             }
             ExternalSnarkWorkerAction::WorkResult { result } => {
-                let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
+                let ExternalSnarkWorkerState::Working(job_id, summary) = &self.state else {
                     return;
                 };
+                // `timestamp` is the time the work was submitted.
+                if let Some(duration) = meta.time().checked_sub(self.timestamp) {
+                    self.proving_times.update(summary, duration);
+                }
                 self.state = ExternalSnarkWorkerState::WorkReady(job_id.clone(), result.clone());
             }
             ExternalSnarkWorkerAction::WorkError { error } => {
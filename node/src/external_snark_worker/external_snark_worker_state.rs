@@ -1,17 +1,32 @@
+use std::time::Duration;
+
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::snark_pool::JobSummary;
 
+use super::remote::RemoteSnarkWorkers;
 use super::{ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkId, SnarkWorkResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkers(pub(crate) ExternalSnarkWorker);
+pub struct ExternalSnarkWorkers(
+    pub(crate) ExternalSnarkWorker,
+    #[serde(default)] pub(crate) RemoteSnarkWorkers,
+);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorker {
     pub(crate) state: ExternalSnarkWorkerState,
     pub(crate) timestamp: Timestamp,
+    #[serde(default)]
+    pub(crate) proving_times: ExternalSnarkWorkerProvingTimes,
+}
+
+/// Measured proving time per account update, for each kind of job.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ExternalSnarkWorkerProvingTimes {
+    tx: Option<Duration>,
+    merge: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,10 +48,14 @@ pub enum ExternalSnarkWorkerState {
 
 impl ExternalSnarkWorkers {
     pub fn new(now: Timestamp) -> Self {
-        ExternalSnarkWorkers(ExternalSnarkWorker {
-            state: ExternalSnarkWorkerState::None,
-            timestamp: now,
-        })
+        ExternalSnarkWorkers(
+            ExternalSnarkWorker {
+                state: ExternalSnarkWorkerState::None,
+                timestamp: now,
+                proving_times: Default::default(),
+            },
+            Default::default(),
+        )
     }
 
     pub fn is_idle(&self) -> bool {
@@ -61,4 +80,65 @@ impl ExternalSnarkWorkers {
             _ => None,
         }
     }
+
+    pub fn proving_times(&self) -> &ExternalSnarkWorkerProvingTimes {
+        &self.0.proving_times
+    }
+
+    pub fn remote(&self) -> &RemoteSnarkWorkers {
+        &self.1
+    }
+}
+
+impl ExternalSnarkWorkerProvingTimes {
+    /// Proving time of the job, estimated from the previous jobs of the same
+    /// kind, or [`JobSummary::estimated_duration`] if none was done yet.
+    pub fn estimate(&self, summary: &JobSummary) -> Duration {
+        let (measured, n) = match summary {
+            JobSummary::Tx(n) => (self.tx, *n),
+            JobSummary::Merge(n) => (self.merge, *n),
+        };
+        match measured {
+            Some(per_update) => per_update * n.max(1) as u32,
+            None => summary.estimated_duration(),
+        }
+    }
+
+    pub(super) fn update(&mut self, summary: &JobSummary, duration: Duration) {
+        let (measured, n) = match summary {
+            JobSummary::Tx(n) => (&mut self.tx, *n),
+            JobSummary::Merge(n) => (&mut self.merge, *n),
+        };
+        let per_update = duration / n.max(1) as u32;
+        // moving average, so that changes in the load are picked up.
+        *measured = Some(match *measured {
+            Some(prev) => (prev * 3 + per_update) / 4,
+            None => per_update,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proving_times_estimate() {
+        let mut times = ExternalSnarkWorkerProvingTimes::default();
+        let tx = JobSummary::Tx(2);
+        assert_eq!(times.estimate(&tx), tx.estimated_duration());
+
+        times.update(&tx, Duration::from_secs(20));
+        assert_eq!(times.estimate(&JobSummary::Tx(3)), Duration::from_secs(30));
+        assert_eq!(
+            times.estimate(&JobSummary::Merge(1)),
+            JobSummary::Merge(1).estimated_duration()
+        );
+
+        times.update(&JobSummary::Tx(1), Duration::from_secs(30));
+        assert_eq!(
+            times.estimate(&JobSummary::Tx(1)),
+            Duration::from_millis(15000)
+        );
+    }
 }
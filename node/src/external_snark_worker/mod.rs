@@ -1,3 +1,5 @@
+pub mod remote;
+
 mod external_snark_worker_types;
 pub use external_snark_worker_types::*;
 
@@ -0,0 +1,162 @@
+use openmina_core::{snark::SnarkJobId, ActionEvent};
+use redux::EnablingCondition;
+use serde::{Deserialize, Serialize};
+
+use crate::external_snark_worker::{ExternalSnarkWorkerAction, SnarkWorkResult};
+use crate::snark::work_verify::SnarkWorkVerifyId;
+use crate::snark_pool::JobSummary;
+use crate::{SnarkerStrategy, State};
+
+use super::RemoteSnarkWorkerId;
+
+pub type ExternalSnarkWorkerRemoteActionWithMeta =
+    redux::ActionWithMeta<ExternalSnarkWorkerRemoteAction>;
+pub type ExternalSnarkWorkerRemoteActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a ExternalSnarkWorkerRemoteAction>;
+
+/// Actions of the remote snark workers, when the node acts as their
+/// coordinator.
+#[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
+#[action_event(fields(
+    display(worker_id),
+    display(job_id),
+    display(verify_id),
+    display(error)
+))]
+pub enum ExternalSnarkWorkerRemoteAction {
+    Register {
+        worker_id: RemoteSnarkWorkerId,
+    },
+    Heartbeat {
+        worker_id: RemoteSnarkWorkerId,
+    },
+    /// Leases the job to the worker and commits to it.
+    Lease {
+        worker_id: RemoteSnarkWorkerId,
+        job_id: SnarkJobId,
+        summary: JobSummary,
+    },
+    /// Remote workers aren't trusted, so their work is verified before
+    /// being added to the pool.
+    WorkResult {
+        worker_id: RemoteSnarkWorkerId,
+        result: SnarkWorkResult,
+    },
+    WorkVerifyNext,
+    WorkVerifyPending {
+        worker_id: RemoteSnarkWorkerId,
+        verify_id: SnarkWorkVerifyId,
+    },
+    /// Worker returned invalid work, so it is evicted and has to register
+    /// again.
+    #[action_event(level = warn)]
+    WorkVerifyError {
+        worker_id: RemoteSnarkWorkerId,
+        verify_id: SnarkWorkVerifyId,
+    },
+    WorkVerifySuccess {
+        worker_id: RemoteSnarkWorkerId,
+        verify_id: SnarkWorkVerifyId,
+    },
+    WorkError {
+        worker_id: RemoteSnarkWorkerId,
+        error: String,
+    },
+    /// Worker stopped sending heartbeats. Our commitment for the job is
+    /// kept, so that the job is reassigned to the next worker asking for one.
+    LeaseTimeout {
+        worker_id: RemoteSnarkWorkerId,
+    },
+    /// Job is no longer needed.
+    LeaseRevoke {
+        worker_id: RemoteSnarkWorkerId,
+    },
+    /// Worker stopped sending heartbeats long ago, so it is forgotten.
+    Evict {
+        worker_id: RemoteSnarkWorkerId,
+    },
+}
+
+impl EnablingCondition<State> for ExternalSnarkWorkerRemoteAction {
+    fn is_enabled(&self, state: &State, time: redux::Timestamp) -> bool {
+        let remote = state.external_snark_worker.remote();
+        match self {
+            ExternalSnarkWorkerRemoteAction::Register { worker_id } => {
+                state
+                    .config
+                    .snarker
+                    .as_ref()
+                    .map_or(false, |config| config.coordinator)
+                    && remote.can_register(worker_id)
+            }
+            ExternalSnarkWorkerRemoteAction::Heartbeat { worker_id } => {
+                remote.get(worker_id).is_some()
+            }
+            ExternalSnarkWorkerRemoteAction::Lease {
+                worker_id, job_id, ..
+            } => {
+                remote.get(worker_id).map_or(false, |worker| {
+                    worker.lease.is_none() && worker.result.is_none()
+                }) && is_job_leasable(state, job_id)
+            }
+            ExternalSnarkWorkerRemoteAction::WorkResult { worker_id, result } => remote
+                .lease(worker_id)
+                .map_or(false, |lease| lease.job_id == SnarkJobId::from(&**result)),
+            ExternalSnarkWorkerRemoteAction::WorkVerifyNext => {
+                remote.next_result_to_verify().is_some() && state.snark.work_verify.jobs.is_empty()
+            }
+            ExternalSnarkWorkerRemoteAction::WorkVerifyPending { worker_id, .. } => remote
+                .result(worker_id)
+                .map_or(false, |result| result.verify_id.is_none()),
+            ExternalSnarkWorkerRemoteAction::WorkVerifyError {
+                worker_id,
+                verify_id,
+            }
+            | ExternalSnarkWorkerRemoteAction::WorkVerifySuccess {
+                worker_id,
+                verify_id,
+            } => remote.result_verify_worker(verify_id) == Some(worker_id),
+            ExternalSnarkWorkerRemoteAction::WorkError { worker_id, .. }
+            | ExternalSnarkWorkerRemoteAction::LeaseRevoke { worker_id } => {
+                remote.lease(worker_id).is_some()
+            }
+            ExternalSnarkWorkerRemoteAction::LeaseTimeout { worker_id } => remote
+                .get(worker_id)
+                .map_or(false, |worker| worker.is_lease_expired(time)),
+            ExternalSnarkWorkerRemoteAction::Evict { worker_id } => remote
+                .get(worker_id)
+                .map_or(false, |worker| worker.is_dead(time)),
+        }
+    }
+}
+
+/// Whether the job can be leased to a remote worker. Either it was left
+/// by a worker whose lease expired, or we can commit to it.
+pub fn is_job_leasable(state: &State, job_id: &SnarkJobId) -> bool {
+    let Some(config) = state.config.snarker.as_ref() else {
+        return false;
+    };
+    let Some(job) = state.snark_pool.get(job_id) else {
+        return false;
+    };
+    let workers = &state.external_snark_worker;
+    if job.snark.is_some()
+        || workers.remote().is_job_leased(job_id)
+        || workers.working_job_id() == Some(job_id)
+    {
+        return false;
+    }
+    job.is_committed_by(config.public_key.as_ref())
+        || match config.strategy {
+            SnarkerStrategy::Profit => state
+                .snark_pool
+                .can_outbid_commitment(job_id, config.fee.0.as_u64()),
+            _ => state.snark_pool.should_create_commitment(job_id),
+        }
+}
+
+impl From<ExternalSnarkWorkerRemoteAction> for crate::Action {
+    fn from(value: ExternalSnarkWorkerRemoteAction) -> Self {
+        Self::ExternalSnarkWorker(ExternalSnarkWorkerAction::Remote(value))
+    }
+}
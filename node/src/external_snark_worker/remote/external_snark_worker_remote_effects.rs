@@ -0,0 +1,149 @@
+use openmina_core::snark::{Snark, SnarkJobCommitment};
+
+use redux::Timestamp;
+
+use crate::snark::work_verify::SnarkWorkVerifyAction;
+use crate::snark_pool::SnarkPoolAction;
+use crate::{Service, SnarkerStrategy, Store};
+
+use super::{
+    is_job_leasable, ExternalSnarkWorkerRemoteAction, ExternalSnarkWorkerRemoteActionWithMeta,
+    RemoteSnarkWorkerId,
+};
+
+pub fn external_snark_worker_remote_effects<S: Service>(
+    store: &mut Store<S>,
+    action: ExternalSnarkWorkerRemoteActionWithMeta,
+) {
+    let (action, meta) = action.split();
+    match action {
+        ExternalSnarkWorkerRemoteAction::Register { .. } => {}
+        ExternalSnarkWorkerRemoteAction::Heartbeat { .. } => {}
+        ExternalSnarkWorkerRemoteAction::Lease { job_id, .. } => {
+            let state = store.state();
+            let Some(config) = state.config.snarker.as_ref() else {
+                return;
+            };
+            let is_ours = state
+                .snark_pool
+                .get(&job_id)
+                .map_or(false, |job| job.is_committed_by(config.public_key.as_ref()));
+            if is_ours {
+                // job reassigned after the previous lease expired.
+                return;
+            }
+            let timestamp_ms = meta.time_as_nanos() / 1_000_000;
+            let commitment = SnarkJobCommitment::new(
+                timestamp_ms,
+                job_id,
+                config.fee.clone(),
+                config.public_key.clone().into(),
+            );
+            let sender = state.p2p.my_id();
+            store.dispatch(SnarkPoolAction::CommitmentAdd { commitment, sender });
+        }
+        ExternalSnarkWorkerRemoteAction::WorkResult { .. } => {
+            store.dispatch(ExternalSnarkWorkerRemoteAction::WorkVerifyNext);
+        }
+        ExternalSnarkWorkerRemoteAction::WorkVerifyNext => {
+            let state = store.state();
+            let Some(config) = &state.config.snarker else {
+                return;
+            };
+            let Some((worker_id, result)) =
+                state.external_snark_worker.remote().next_result_to_verify()
+            else {
+                return;
+            };
+            // each result is verified on its own, so that the invalid one
+            // only fails its worker.
+            let work = Snark {
+                snarker: config.public_key.clone().into(),
+                fee: config.fee.clone(),
+                proofs: result.proofs.clone(),
+            };
+            let worker_id = worker_id.clone();
+            let req_id = state.snark.work_verify.next_req_id();
+            let sender = state.p2p.my_id().to_string();
+            store.dispatch(SnarkWorkVerifyAction::Init {
+                req_id,
+                batch: vec![work],
+                sender,
+            });
+            store.dispatch(ExternalSnarkWorkerRemoteAction::WorkVerifyPending {
+                worker_id,
+                verify_id: req_id,
+            });
+        }
+        ExternalSnarkWorkerRemoteAction::WorkVerifyPending { .. } => {}
+        ExternalSnarkWorkerRemoteAction::WorkVerifyError { .. } => {}
+        ExternalSnarkWorkerRemoteAction::WorkVerifySuccess { .. } => {
+            // action for adding the verified snark to snark pool is
+            // called in snark/work_verify effects.
+        }
+        ExternalSnarkWorkerRemoteAction::WorkError { .. } => {}
+        ExternalSnarkWorkerRemoteAction::LeaseTimeout { .. } => {}
+        ExternalSnarkWorkerRemoteAction::LeaseRevoke { .. } => {}
+        ExternalSnarkWorkerRemoteAction::Evict { .. } => {}
+    }
+}
+
+/// Leases a job to the remote worker. Jobs left by the workers whose lease
+/// expired come first, then the jobs are picked by the snarker strategy.
+///
+/// Returns `false` if there is no job to lease.
+pub fn external_snark_worker_remote_lease<S: Service>(
+    store: &mut Store<S>,
+    worker_id: RemoteSnarkWorkerId,
+    time: Timestamp,
+) -> bool {
+    let state = store.state.get();
+    let Some(config) = state.config.snarker.as_ref() else {
+        return false;
+    };
+    let reassigned = state
+        .snark_pool
+        .jobs_iter()
+        .filter(|job| job.is_committed_by(config.public_key.as_ref()))
+        .filter(|job| is_job_leasable(state, &job.id))
+        .min_by_key(|job| job.order)
+        .map(|job| job.id.clone());
+
+    let job_id = match reassigned {
+        Some(job_id) => Some(job_id),
+        None => match config.strategy {
+            SnarkerStrategy::Sequential => state
+                .snark_pool
+                .available_jobs_with_highest_priority(1)
+                .into_iter()
+                .map(|job| job.id.clone())
+                .next(),
+            SnarkerStrategy::Random => {
+                let jobs = state.snark_pool.available_jobs_iter();
+                store.service.random_choose(jobs, 1).into_iter().next()
+            }
+            SnarkerStrategy::Profit => {
+                let proving_times = state.external_snark_worker.proving_times();
+                state
+                    .snark_pool
+                    .jobs_by_expected_profit(config.fee.0.as_u64(), time, |summary| {
+                        proving_times.estimate(summary)
+                    })
+                    .into_iter()
+                    .map(|job| job.id.clone())
+                    .next()
+            }
+        },
+    };
+    let Some(job_id) = job_id else {
+        return false;
+    };
+    let Some(summary) = store.state().snark_pool.job_summary(&job_id) else {
+        return false;
+    };
+    store.dispatch(ExternalSnarkWorkerRemoteAction::Lease {
+        worker_id,
+        job_id,
+        summary,
+    })
+}
@@ -0,0 +1,100 @@
+use super::{
+    ExternalSnarkWorkerRemoteAction, ExternalSnarkWorkerRemoteActionWithMetaRef,
+    RemoteSnarkWorkerLease, RemoteSnarkWorkerResult, RemoteSnarkWorkers,
+};
+
+impl RemoteSnarkWorkers {
+    pub fn reducer(&mut self, action: ExternalSnarkWorkerRemoteActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            ExternalSnarkWorkerRemoteAction::Register { worker_id } => {
+                self.register(worker_id.clone(), meta.time());
+            }
+            ExternalSnarkWorkerRemoteAction::Heartbeat { worker_id } => {
+                let Some(worker) = self.get_mut(worker_id) else {
+                    return;
+                };
+                worker.last_heartbeat_t = meta.time();
+            }
+            ExternalSnarkWorkerRemoteAction::Lease {
+                worker_id,
+                job_id,
+                summary,
+            } => {
+                let Some(worker) = self.get_mut(worker_id) else {
+                    return;
+                };
+                worker.last_heartbeat_t = meta.time();
+                worker.lease = Some(RemoteSnarkWorkerLease {
+                    job_id: job_id.clone(),
+                    summary: summary.clone(),
+                    leased_t: meta.time(),
+                });
+            }
+            ExternalSnarkWorkerRemoteAction::WorkResult { worker_id, result } => {
+                let Some(worker) = self.get_mut(worker_id) else {
+                    return;
+                };
+                worker.last_heartbeat_t = meta.time();
+                let Some(lease) = worker.lease.take() else {
+                    return;
+                };
+                worker.result = Some(RemoteSnarkWorkerResult {
+                    lease,
+                    proofs: result.clone(),
+                    received_t: meta.time(),
+                    verify_id: None,
+                });
+            }
+            ExternalSnarkWorkerRemoteAction::WorkVerifyNext => {}
+            ExternalSnarkWorkerRemoteAction::WorkVerifyPending {
+                worker_id,
+                verify_id,
+            } => {
+                let Some(result) = self.get_mut(worker_id).and_then(|w| w.result.as_mut()) else {
+                    return;
+                };
+                result.verify_id = Some(*verify_id);
+            }
+            ExternalSnarkWorkerRemoteAction::WorkVerifyError { worker_id, .. } => {
+                self.evict(worker_id);
+            }
+            ExternalSnarkWorkerRemoteAction::WorkVerifySuccess { worker_id, .. } => {
+                let Some(worker) = self.get_mut(worker_id) else {
+                    return;
+                };
+                let Some(result) = worker.result.take() else {
+                    return;
+                };
+                worker.stats.completed += 1;
+                if let Some(duration) = result.received_t.checked_sub(result.lease.leased_t) {
+                    worker.stats.proving_time += duration;
+                }
+            }
+            ExternalSnarkWorkerRemoteAction::WorkError { worker_id, .. } => {
+                let Some(worker) = self.get_mut(worker_id) else {
+                    return;
+                };
+                worker.last_heartbeat_t = meta.time();
+                worker.lease = None;
+                worker.stats.failed += 1;
+            }
+            ExternalSnarkWorkerRemoteAction::LeaseTimeout { worker_id } => {
+                let Some(worker) = self.get_mut(worker_id) else {
+                    return;
+                };
+                worker.lease = None;
+                worker.stats.expired += 1;
+            }
+            ExternalSnarkWorkerRemoteAction::LeaseRevoke { worker_id } => {
+                let Some(worker) = self.get_mut(worker_id) else {
+                    return;
+                };
+                worker.lease = None;
+            }
+            ExternalSnarkWorkerRemoteAction::Evict { worker_id } => {
+                self.evict(worker_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use openmina_core::snark::SnarkJobId;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::external_snark_worker::SnarkWorkResult;
+use crate::snark::work_verify::SnarkWorkVerifyId;
+use crate::snark_pool::JobSummary;
+
+/// Without heartbeats for that long, the lease of the remote worker expires
+/// and its job can be leased to another worker.
+pub const REMOTE_SNARK_WORKER_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Without heartbeats for that long, the remote worker is forgotten and has
+/// to register again.
+pub const REMOTE_SNARK_WORKER_EVICT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Maximum number of the registered remote workers.
+pub const MAX_REMOTE_SNARK_WORKERS: usize = 32;
+
+pub type RemoteSnarkWorkerId = String;
+
+/// Remote snark workers, leasing jobs from this node acting as their
+/// coordinator.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RemoteSnarkWorkers {
+    workers: BTreeMap<RemoteSnarkWorkerId, RemoteSnarkWorker>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteSnarkWorker {
+    pub registered_t: Timestamp,
+    pub last_heartbeat_t: Timestamp,
+    pub lease: Option<RemoteSnarkWorkerLease>,
+    /// Work returned for the last lease, waiting to be verified. The worker
+    /// gets no new lease until then.
+    pub result: Option<RemoteSnarkWorkerResult>,
+    pub stats: RemoteSnarkWorkerStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteSnarkWorkerLease {
+    pub job_id: SnarkJobId,
+    pub summary: JobSummary,
+    pub leased_t: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteSnarkWorkerResult {
+    pub lease: RemoteSnarkWorkerLease,
+    pub proofs: SnarkWorkResult,
+    pub received_t: Timestamp,
+    pub verify_id: Option<SnarkWorkVerifyId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RemoteSnarkWorkerStats {
+    /// Jobs whose work passed the verification.
+    pub completed: u64,
+    pub failed: u64,
+    /// Leases which expired because of the missing heartbeats.
+    pub expired: u64,
+    /// Lease duration summed over the completed jobs.
+    pub proving_time: Duration,
+}
+
+impl RemoteSnarkWorkers {
+    pub fn get(&self, worker_id: &str) -> Option<&RemoteSnarkWorker> {
+        self.workers.get(worker_id)
+    }
+
+    pub(super) fn get_mut(&mut self, worker_id: &str) -> Option<&mut RemoteSnarkWorker> {
+        self.workers.get_mut(worker_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&RemoteSnarkWorkerId, &RemoteSnarkWorker)> {
+        self.workers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workers.is_empty()
+    }
+
+    /// Registered workers can register again (after restart), new ones
+    /// only while there is room for them.
+    pub fn can_register(&self, worker_id: &str) -> bool {
+        self.workers.contains_key(worker_id) || self.workers.len() < MAX_REMOTE_SNARK_WORKERS
+    }
+
+    pub fn lease(&self, worker_id: &str) -> Option<&RemoteSnarkWorkerLease> {
+        self.get(worker_id)?.lease.as_ref()
+    }
+
+    pub fn result(&self, worker_id: &str) -> Option<&RemoteSnarkWorkerResult> {
+        self.get(worker_id)?.result.as_ref()
+    }
+
+    /// Jobs whose work is being verified are still leased, so that they
+    /// aren't given to another worker meanwhile.
+    pub fn is_job_leased(&self, job_id: &SnarkJobId) -> bool {
+        self.workers
+            .values()
+            .flat_map(|worker| {
+                let result_lease = worker.result.as_ref().map(|result| &result.lease);
+                worker.lease.iter().chain(result_lease)
+            })
+            .any(|lease| &lease.job_id == job_id)
+    }
+
+    /// Next worker whose work is waiting to be verified.
+    pub fn next_result_to_verify(
+        &self,
+    ) -> Option<(&RemoteSnarkWorkerId, &RemoteSnarkWorkerResult)> {
+        self.workers.iter().find_map(|(id, worker)| {
+            worker
+                .result
+                .as_ref()
+                .filter(|result| result.verify_id.is_none())
+                .map(|result| (id, result))
+        })
+    }
+
+    /// Worker whose work is being verified by the request.
+    pub fn result_verify_worker(
+        &self,
+        verify_id: &SnarkWorkVerifyId,
+    ) -> Option<&RemoteSnarkWorkerId> {
+        self.workers
+            .iter()
+            .find(|(_, worker)| {
+                worker
+                    .result
+                    .as_ref()
+                    .map_or(false, |result| result.verify_id.as_ref() == Some(verify_id))
+            })
+            .map(|(id, _)| id)
+    }
+
+    pub fn expired_leases_iter(
+        &self,
+        time_now: Timestamp,
+    ) -> impl Iterator<Item = &RemoteSnarkWorkerId> {
+        self.workers
+            .iter()
+            .filter(move |(_, worker)| worker.is_lease_expired(time_now))
+            .map(|(id, _)| id)
+    }
+
+    pub fn dead_workers_iter(
+        &self,
+        time_now: Timestamp,
+    ) -> impl Iterator<Item = &RemoteSnarkWorkerId> {
+        self.workers
+            .iter()
+            .filter(move |(_, worker)| worker.is_dead(time_now))
+            .map(|(id, _)| id)
+    }
+
+    pub(super) fn register(&mut self, worker_id: RemoteSnarkWorkerId, time: Timestamp) {
+        let worker = self
+            .workers
+            .entry(worker_id)
+            .or_insert_with(|| RemoteSnarkWorker {
+                registered_t: time,
+                last_heartbeat_t: time,
+                lease: None,
+                result: None,
+                stats: Default::default(),
+            });
+        // worker restarted, so the job it was working on is lost.
+        if worker.lease.take().is_some() {
+            worker.stats.failed += 1;
+        }
+        worker.last_heartbeat_t = time;
+    }
+
+    pub(super) fn evict(&mut self, worker_id: &str) {
+        self.workers.remove(worker_id);
+    }
+}
+
+impl RemoteSnarkWorker {
+    pub fn is_lease_expired(&self, time_now: Timestamp) -> bool {
+        self.lease.is_some()
+            && time_now
+                .checked_sub(self.last_heartbeat_t)
+                .map_or(false, |dur| dur > REMOTE_SNARK_WORKER_HEARTBEAT_TIMEOUT)
+    }
+
+    /// Worker whose work is being verified is kept until the verification
+    /// ends.
+    pub fn is_dead(&self, time_now: Timestamp) -> bool {
+        let is_verifying = self
+            .result
+            .as_ref()
+            .map_or(false, |result| result.verify_id.is_some());
+        !is_verifying
+            && time_now
+                .checked_sub(self.last_heartbeat_t)
+                .map_or(false, |dur| dur > REMOTE_SNARK_WORKER_EVICT_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ledger::dummy::dummy_transaction_proof;
+    use ledger::scan_state::currency::{Amount, Signed};
+    use ledger::scan_state::fee_excess::FeeExcess;
+    use ledger::scan_state::pending_coinbase::Stack;
+    use ledger::scan_state::scan_state::transaction_snark::{
+        LedgerProof, Registers, SokDigest, Statement,
+    };
+    use ledger::scan_state::transaction_logic::local_state::LocalState;
+    use mina_p2p_messages::v2::{LedgerHash, TransactionSnarkWorkTStableV2Proofs};
+    use redux::ActionMeta;
+
+    use super::super::ExternalSnarkWorkerRemoteAction;
+    use super::*;
+
+    fn job_id() -> SnarkJobId {
+        let hash = LedgerHash::from_fp(mina_hasher::Fp::from(0u64));
+        format!("{hash}_{hash}-{hash}_{hash}").parse().unwrap()
+    }
+
+    fn proofs() -> SnarkWorkResult {
+        let registers = || Registers {
+            first_pass_ledger: 0.into(),
+            second_pass_ledger: 0.into(),
+            pending_coinbase_stack: Stack::empty(),
+            local_state: LocalState::dummy(),
+        };
+        let statement = Statement {
+            source: registers(),
+            target: registers(),
+            connecting_ledger_left: 0.into(),
+            connecting_ledger_right: 0.into(),
+            supply_increase: Signed::<Amount>::zero(),
+            fee_excess: FeeExcess::empty(),
+            sok_digest: (),
+        };
+        let proof = LedgerProof::create(statement, SokDigest::default(), dummy_transaction_proof());
+        Arc::new(TransactionSnarkWorkTStableV2Proofs::One((&proof).into()))
+    }
+
+    #[test]
+    fn test_lease_expiration() {
+        let secs = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        let mut workers = RemoteSnarkWorkers::default();
+        workers.register("worker-1".to_owned(), secs(100));
+        assert!(workers.expired_leases_iter(secs(200)).next().is_none());
+
+        let worker = workers.get_mut("worker-1").unwrap();
+        worker.lease = Some(RemoteSnarkWorkerLease {
+            job_id: job_id(),
+            summary: JobSummary::Merge(1),
+            leased_t: secs(100),
+        });
+        worker.last_heartbeat_t = secs(120);
+        assert!(workers.expired_leases_iter(secs(150)).next().is_none());
+        assert_eq!(
+            workers.expired_leases_iter(secs(151)).collect::<Vec<_>>(),
+            vec!["worker-1"]
+        );
+
+        workers.register("worker-1".to_owned(), secs(160));
+        let worker = workers.get("worker-1").unwrap();
+        assert!(worker.lease.is_none());
+        assert_eq!(worker.stats.failed, 1);
+        assert_eq!(worker.registered_t, secs(100));
+    }
+
+    #[test]
+    fn test_register_limit_and_eviction() {
+        let secs = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        let mut workers = RemoteSnarkWorkers::default();
+        for i in 0..MAX_REMOTE_SNARK_WORKERS {
+            workers.register(format!("worker-{i}"), secs(100));
+        }
+        assert!(!workers.can_register("worker-new"));
+        assert!(workers.can_register("worker-0"));
+
+        workers.get_mut("worker-0").unwrap().last_heartbeat_t = secs(200);
+        assert!(workers.dead_workers_iter(secs(400)).next().is_none());
+        let dead = workers
+            .dead_workers_iter(secs(401))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(dead.len(), MAX_REMOTE_SNARK_WORKERS - 1);
+        assert!(!dead.contains(&"worker-0".to_owned()));
+
+        for worker_id in dead {
+            workers.evict(&worker_id);
+        }
+        assert_eq!(workers.len(), 1);
+        assert!(workers.can_register("worker-new"));
+    }
+
+    #[test]
+    fn test_result_verification() {
+        let secs = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        let reduce = |workers: &mut RemoteSnarkWorkers,
+                      time: u64,
+                      action: ExternalSnarkWorkerRemoteAction| {
+            workers.reducer(ActionMeta::zero_custom(secs(time)).with_action(&action));
+        };
+        let worker_id = |i: usize| format!("worker-{i}");
+        let verify_id = |i: usize| SnarkWorkVerifyId::new_unchecked(0, i);
+
+        let mut workers = RemoteSnarkWorkers::default();
+        for i in [1, 2] {
+            let worker_id = worker_id(i);
+            let actions = [
+                ExternalSnarkWorkerRemoteAction::Register {
+                    worker_id: worker_id.clone(),
+                },
+                ExternalSnarkWorkerRemoteAction::Lease {
+                    worker_id: worker_id.clone(),
+                    job_id: job_id(),
+                    summary: JobSummary::Merge(1),
+                },
+            ];
+            for action in actions {
+                reduce(&mut workers, 100, action);
+            }
+            let result = ExternalSnarkWorkerRemoteAction::WorkResult {
+                worker_id,
+                result: proofs(),
+            };
+            reduce(&mut workers, 100 + 10 * i as u64, result);
+        }
+
+        // jobs being verified aren't leased to other workers.
+        assert!(workers.is_job_leased(&job_id()));
+        assert!(workers.lease(&worker_id(1)).is_none());
+        let (next, _) = workers.next_result_to_verify().unwrap();
+        assert_eq!(next, &worker_id(1));
+        assert_eq!(workers.get(&worker_id(1)).unwrap().stats.completed, 0);
+
+        for i in [1, 2] {
+            let action = ExternalSnarkWorkerRemoteAction::WorkVerifyPending {
+                worker_id: worker_id(i),
+                verify_id: verify_id(i),
+            };
+            reduce(&mut workers, 130, action);
+        }
+        assert!(workers.next_result_to_verify().is_none());
+        assert_eq!(
+            workers.result_verify_worker(&verify_id(2)),
+            Some(&worker_id(2))
+        );
+        // verification isn't cut short by the missing heartbeats.
+        assert!(!workers.get(&worker_id(1)).unwrap().is_dead(secs(1000)));
+
+        // valid work is counted as completed.
+        let action = ExternalSnarkWorkerRemoteAction::WorkVerifySuccess {
+            worker_id: worker_id(1),
+            verify_id: verify_id(1),
+        };
+        reduce(&mut workers, 131, action);
+        let worker = workers.get(&worker_id(1)).unwrap();
+        assert!(worker.result.is_none());
+        assert_eq!(worker.stats.completed, 1);
+        assert_eq!(worker.stats.proving_time, Duration::from_secs(10));
+
+        // invalid work gets its worker evicted, without affecting the others.
+        let action = ExternalSnarkWorkerRemoteAction::WorkVerifyError {
+            worker_id: worker_id(2),
+            verify_id: verify_id(2),
+        };
+        reduce(&mut workers, 131, action);
+        assert!(workers.get(&worker_id(2)).is_none());
+        assert_eq!(workers.len(), 1);
+        assert!(!workers.is_job_leased(&job_id()));
+    }
+}
@@ -0,0 +1,10 @@
+mod external_snark_worker_remote_state;
+pub use external_snark_worker_remote_state::*;
+
+mod external_snark_worker_remote_actions;
+pub use external_snark_worker_remote_actions::*;
+
+mod external_snark_worker_remote_reducer;
+
+mod external_snark_worker_remote_effects;
+pub use external_snark_worker_remote_effects::*;
@@ -251,6 +251,36 @@ fn next_read_requests_init<S: redux::Service>(store: &mut Store<S>) {
             return;
         }
     }
+
+    let rpcs = store
+        .state()
+        .rpc
+        .epoch_ledger_accounts_rpc_ids()
+        .filter(|(.., status)| status.is_init())
+        .map(|(id, ..)| id)
+        .collect::<Vec<_>>();
+
+    for rpc_id in rpcs {
+        store.dispatch(RpcAction::EpochLedgerAccountsLedgerGetInit { rpc_id });
+        if !store.state().ledger.read.is_total_cost_under_limit() {
+            return;
+        }
+    }
+
+    let rpcs = store
+        .state()
+        .rpc
+        .delegation_payouts_rpc_ids()
+        .filter(|(.., status)| status.is_init())
+        .map(|(id, ..)| id)
+        .collect::<Vec<_>>();
+
+    for rpc_id in rpcs {
+        store.dispatch(RpcAction::DelegationPayoutsLedgerGetInit { rpc_id });
+        if !store.state().ledger.read.is_total_cost_under_limit() {
+            return;
+        }
+    }
 }
 
 fn find_peers_with_ledger_rpc(
@@ -412,5 +442,53 @@ fn propagate_read_response<S: redux::Service>(
             }
         }
         (_, LedgerReadResponse::ScanStateSummary(..)) => unreachable!(),
+        (
+            LedgerReadRequest::EpochLedgerAccounts(ledger_hash, offset, limit),
+            LedgerReadResponse::EpochLedgerAccounts(accounts),
+        ) => {
+            for rpc_id in store
+                .state()
+                .rpc
+                .epoch_ledger_accounts_rpc_ids()
+                .filter(|(_, query, hash, _)| {
+                    *hash == Some(ledger_hash) && query.offset == *offset && query.limit() == *limit
+                })
+                .map(|(id, ..)| id)
+                .collect::<Vec<_>>()
+            {
+                store.dispatch(RpcAction::EpochLedgerAccountsGetSuccess {
+                    rpc_id,
+                    accounts: accounts.clone(),
+                });
+            }
+        }
+        (_, LedgerReadResponse::EpochLedgerAccounts(..)) => unreachable!(),
+        (
+            LedgerReadRequest::PayoutDelegators(ledger_hash, producer),
+            LedgerReadResponse::PayoutDelegators(delegators),
+        ) => {
+            for rpc_id in store
+                .state()
+                .rpc
+                .delegation_payouts_rpc_ids()
+                .filter(|(_, query, hash, status)| {
+                    *hash == Some(ledger_hash) && query.producer == *producer && status.is_pending()
+                })
+                .map(|(id, ..)| id)
+                .collect::<Vec<_>>()
+            {
+                store.dispatch(RpcAction::DelegationPayoutsGetSuccess {
+                    rpc_id,
+                    delegators: delegators.clone(),
+                });
+            }
+        }
+        (_, LedgerReadResponse::PayoutDelegators(..)) => unreachable!(),
+        (_, LedgerReadResponse::BlockVrfVerify(block_hash, Some(false))) => {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "BlockVrfVerifyFailed",
+                summary = format!("vrf claim of block {block_hash} doesn't hold against its staking ledger"));
+        }
+        (_, LedgerReadResponse::BlockVrfVerify(..)) => {}
     }
 }
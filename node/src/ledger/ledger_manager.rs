@@ -10,7 +10,9 @@ use super::write::{LedgerWriteRequest, LedgerWriteResponse};
 use super::LedgerService;
 use crate::account::AccountPublicKey;
 use crate::ledger::LedgerAddress;
-use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService;
+use crate::transition_frontier::sync::ledger::snarked::{
+    SnarkedLedgerSyncCheckpoint, TransitionFrontierSyncLedgerSnarkedService,
+};
 use ledger::Mask;
 use mina_signer::CompressedPubKey;
 
@@ -26,6 +28,7 @@ pub(super) enum LedgerRequest {
     AccountsSet {
         snarked_ledger_hash: LedgerHash,
         parent: LedgerAddress,
+        expected_hash: LedgerHash,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
     }, // expected response: LedgerHash
     ChildHashesGet {
@@ -34,7 +37,7 @@ pub(super) enum LedgerRequest {
     }, // expected response: ChildHashes
     ComputeSnarkedLedgerHashes {
         snarked_ledger_hash: LedgerHash,
-    }, // expected response: Success
+    }, // expected response: MerkleRoot
     CopySnarkedLedgerContentsForSync {
         origin_snarked_ledger_hash: LedgerHash,
         target_snarked_ledger_hash: LedgerHash,
@@ -50,6 +53,15 @@ pub(super) enum LedgerRequest {
     InsertGenesisLedger {
         mask: Mask,
     },
+    EpochLedgersExport {
+        ledger_hashes: Vec<LedgerHash>,
+    }, // expected response: Success
+    SnarkedLedgerSyncCheckpointSave {
+        checkpoint: SnarkedLedgerSyncCheckpoint,
+    }, // expected response: Success
+    SnarkedLedgerSyncCheckpointRemove {
+        snarked_ledger_hash: LedgerHash,
+    }, // expected response: Success
     StagedLedgerReconstructResult {
         staged_ledger_hash: LedgerHash,
         result: Result<StagedLedger, String>,
@@ -62,6 +74,7 @@ pub enum LedgerResponse {
     Read(LedgerReadId, LedgerReadResponse),
     ChildHashes(Option<(LedgerHash, LedgerHash)>),
     AccountsSet(Result<LedgerHash, String>),
+    MerkleRoot(Result<LedgerHash, String>),
     LedgerMask(Option<(Mask, bool)>),
     #[allow(clippy::type_complexity)]
     ProducersWithDelegatesMap(
@@ -196,15 +209,30 @@ impl LedgerRequest {
                         let res = ledger_ctx.scan_state_summary(ledger_hash);
                         LedgerReadResponse::ScanStateSummary(res)
                     }
+                    LedgerReadRequest::EpochLedgerAccounts(ledger_hash, offset, limit) => {
+                        let res = ledger_ctx.epoch_ledger_accounts(&ledger_hash, offset, limit);
+                        LedgerReadResponse::EpochLedgerAccounts(res)
+                    }
+                    LedgerReadRequest::PayoutDelegators(ledger_hash, producer) => {
+                        let res = ledger_ctx.payout_delegators(&ledger_hash, &producer);
+                        LedgerReadResponse::PayoutDelegators(res)
+                    }
+                    LedgerReadRequest::BlockVrfVerify(data) => {
+                        let block_hash = data.block_hash.clone();
+                        let res = ledger_ctx.block_vrf_verify(data);
+                        LedgerReadResponse::BlockVrfVerify(block_hash, res)
+                    }
                 },
             ),
             LedgerRequest::AccountsSet {
                 snarked_ledger_hash,
                 parent,
+                expected_hash,
                 accounts,
             } => LedgerResponse::AccountsSet(ledger_ctx.accounts_set(
                 snarked_ledger_hash,
                 &parent,
+                &expected_hash,
                 accounts,
             )),
             LedgerRequest::ChildHashesGet {
@@ -216,12 +244,9 @@ impl LedgerRequest {
             }
             LedgerRequest::ComputeSnarkedLedgerHashes {
                 snarked_ledger_hash,
-            } => {
-                ledger_ctx
-                    .compute_snarked_ledger_hashes(&snarked_ledger_hash)
-                    .unwrap();
-                LedgerResponse::Success
-            }
+            } => LedgerResponse::MerkleRoot(
+                ledger_ctx.compute_snarked_ledger_hashes(&snarked_ledger_hash),
+            ),
             LedgerRequest::CopySnarkedLedgerContentsForSync {
                 origin_snarked_ledger_hash,
                 target_snarked_ledger_hash,
@@ -248,6 +273,20 @@ impl LedgerRequest {
                 ledger_ctx.insert_genesis_ledger(mask);
                 LedgerResponse::Success
             }
+            LedgerRequest::EpochLedgersExport { ledger_hashes } => {
+                ledger_ctx.epoch_ledgers_export(ledger_hashes);
+                LedgerResponse::Success
+            }
+            LedgerRequest::SnarkedLedgerSyncCheckpointSave { checkpoint } => {
+                ledger_ctx.snarked_ledger_sync_checkpoint_save(checkpoint);
+                LedgerResponse::Success
+            }
+            LedgerRequest::SnarkedLedgerSyncCheckpointRemove {
+                snarked_ledger_hash,
+            } => {
+                ledger_ctx.snarked_ledger_sync_checkpoint_remove(&snarked_ledger_hash);
+                LedgerResponse::Success
+            }
             LedgerRequest::StagedLedgerReconstructResult {
                 staged_ledger_hash,
                 result,
@@ -398,15 +437,15 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
     fn compute_snarked_ledger_hashes(
         &self,
         snarked_ledger_hash: &LedgerHash,
-    ) -> Result<(), String> {
+    ) -> Result<LedgerHash, String> {
         self.ledger_manager()
             .call_sync(LedgerRequest::ComputeSnarkedLedgerHashes {
                 snarked_ledger_hash: snarked_ledger_hash.clone(),
             })
             .map_err(|_| "compute_snarked_ledger_hashes responder dropped".to_owned())
             .and_then(|res| {
-                if let LedgerResponse::Success = res {
-                    Ok(())
+                if let LedgerResponse::MerkleRoot(res) = res {
+                    res
                 } else {
                     Err(format_response_error("compute_snarked_ledger_hashes", res))
                 }
@@ -462,12 +501,14 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
         &self,
         snarked_ledger_hash: LedgerHash,
         parent: &LedgerAddress,
+        expected_hash: LedgerHash,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
     ) -> Result<LedgerHash, String> {
         self.ledger_manager()
             .call_sync(LedgerRequest::AccountsSet {
                 snarked_ledger_hash,
                 parent: parent.clone(),
+                expected_hash,
                 accounts,
             })
             .map_err(|_| "accounts_set responder dropped".to_owned())
@@ -479,4 +520,24 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
                 }
             })
     }
+
+    fn snarked_ledger_sync_checkpoint_save(&self, checkpoint: SnarkedLedgerSyncCheckpoint) {
+        let request = LedgerRequest::SnarkedLedgerSyncCheckpointSave { checkpoint };
+        if self.force_sync_calls() {
+            let _ = self.ledger_manager().call_sync(request);
+        } else {
+            self.ledger_manager().call(request);
+        }
+    }
+
+    fn snarked_ledger_sync_checkpoint_remove(&self, snarked_ledger_hash: LedgerHash) {
+        let request = LedgerRequest::SnarkedLedgerSyncCheckpointRemove {
+            snarked_ledger_hash,
+        };
+        if self.force_sync_calls() {
+            let _ = self.ledger_manager().call_sync(request);
+        } else {
+            self.ledger_manager().call(request);
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -8,7 +8,10 @@ use super::ledger_manager::{LedgerManager, LedgerRequest};
 use ledger::{
     scan_state::{
         currency::Slot,
-        scan_state::{AvailableJobMessage, JobValueBase, JobValueMerge, JobValueWithIndex, Pass},
+        scan_state::{
+            transaction_snark::OneOrTwo, AvailableJobMessage, JobValueBase, JobValueMerge,
+            JobValueWithIndex, Pass, ScanState,
+        },
         transaction_logic::{
             local_state::LocalState,
             protocol_state::{protocol_state_view, ProtocolStateView},
@@ -23,11 +26,15 @@ use ledger::{
         validate_block::block_body_hash,
     },
     verifier::Verifier,
-    Account, BaseLedger, Database, Mask, UnregisterBehavior,
+    Account, AccountIndex, BaseLedger, Database, Mask, UnregisterBehavior,
 };
 use mina_hasher::Fp;
 use mina_p2p_messages::{
-    binprot::BinProtRead,
+    binprot::{
+        self,
+        macros::{BinProtRead, BinProtWrite},
+        BinProtRead, BinProtWrite,
+    },
     v2::{
         self, DataHashLibStateHashStableV1, LedgerHash, MinaBaseLedgerHash0StableV1,
         MinaBasePendingCoinbaseStableV2, MinaBasePendingCoinbaseWitnessStableV2,
@@ -42,15 +49,18 @@ use openmina_core::snark::{Snark, SnarkJobId};
 
 use mina_signer::CompressedPubKey;
 use openmina_core::block::ArcBlockWithHash;
+use vrf::VrfVerificationInput;
 
 use crate::account::AccountPublicKey;
+use crate::block_producer::payout::{unlocked_at_global_slot, PayoutDelegator};
 use crate::block_producer::StagedLedgerDiffCreateOutput;
 use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
 use crate::rpc::{
-    RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
+    RpcEpochLedgerAccount, RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
     RpcScanStateSummaryScanStateJobKind, RpcSnarkPoolJobSnarkWorkDone,
 };
 use crate::transition_frontier::sync::{
+    ledger::snarked::{LedgerAddressQuery, SnarkedLedgerSyncCheckpoint},
     ledger::staged::StagedLedgerAuxAndPendingCoinbasesValid,
     TransitionFrontierRootSnarkedLedgerUpdates,
 };
@@ -58,14 +68,19 @@ use crate::transition_frontier::sync::{
 use super::write::CommitResult;
 
 use super::{
-    ledger_empty_hash_at_depth, read::LedgerReadResponse, write::LedgerWriteResponse,
-    LedgerAddress, LedgerEvent, LEDGER_DEPTH,
+    hash_node_at_depth, ledger_empty_hash_at_depth, read::LedgerReadResponse,
+    tree_height_for_num_accounts, write::LedgerWriteResponse, LedgerAddress, LedgerEvent,
+    LEDGER_DEPTH,
 };
 use super::{
-    read::{LedgerReadId, LedgerReadRequest},
+    read::{LedgerReadBlockVrfVerify, LedgerReadId, LedgerReadRequest},
     write::LedgerWriteRequest,
 };
 
+/// Name of the file in the work directory, where the progress of the
+/// snarked ledger sync is persisted.
+pub const LEDGER_SYNC_CHECKPOINT_FILE_NAME: &str = "ledger_sync.bin";
+
 fn merkle_root(mask: &mut Mask) -> LedgerHash {
     MinaBaseLedgerHash0StableV1(mask.merkle_root().into()).into()
 }
@@ -79,6 +94,58 @@ pub struct LedgerCtx {
     sync: LedgerSyncState,
     event_sender:
         Option<openmina_core::channels::mpsc::UnboundedSender<crate::event_source::Event>>,
+    /// Directory where epoch ledgers are exported to, if enabled.
+    epoch_ledgers_export_dir: Option<PathBuf>,
+    /// Epoch ledgers that were already exported, or are being exported.
+    exported_epoch_ledgers: BTreeSet<LedgerHash>,
+    /// Worker writing the epoch ledger exports, spawned on the first export.
+    epoch_ledgers_exporter: Option<EpochLedgersExporter>,
+    /// File where the snarked ledger sync progress is persisted, if enabled.
+    ledger_sync_checkpoint_path: Option<PathBuf>,
+    /// Ledger whose sync progress is currently persisted.
+    ledger_sync_checkpoint_hash: Option<LedgerHash>,
+    /// Accounts set since the last checkpoint of the ledger being synced,
+    /// which the next checkpoint appends to the persisted ones.
+    ledger_sync_unsaved_accounts: Option<(LedgerHash, Vec<(u64, Account)>)>,
+}
+
+/// Writes epoch ledger exports one by one on a separate thread, as
+/// serializing a whole ledger takes a while and would block ledger requests.
+struct EpochLedgersExporter {
+    sender: std::sync::mpsc::Sender<(LedgerHash, Mask)>,
+    results: std::sync::mpsc::Receiver<(LedgerHash, std::io::Result<()>)>,
+}
+
+impl EpochLedgersExporter {
+    fn spawn(dir: PathBuf) -> std::io::Result<Self> {
+        let (sender, requests) = std::sync::mpsc::channel::<(LedgerHash, Mask)>();
+        let (results_sender, results) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("epoch-ledgers-export".to_owned())
+            .spawn(move || {
+                for (ledger_hash, mask) in requests {
+                    let result = epoch_ledger_export_to_dir(&dir, &ledger_hash, &mask);
+                    match &result {
+                        Ok(()) => {
+                            openmina_core::log::info!(openmina_core::log::system_time();
+                                kind = "LedgerEpochLedgerExported",
+                                summary = format!("epoch ledger {ledger_hash} exported"),
+                                dir = dir.display().to_string());
+                        }
+                        Err(err) => {
+                            openmina_core::log::error!(openmina_core::log::system_time();
+                                kind = "LedgerEpochLedgerExportError",
+                                summary = format!("failed to export epoch ledger {ledger_hash}"),
+                                error = err.to_string());
+                        }
+                    }
+                    if results_sender.send((ledger_hash, result)).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Self { sender, results })
+    }
 }
 
 #[derive(Default)]
@@ -102,17 +169,7 @@ impl LedgerCtx {
             .filter_map(|entry| {
                 let entry = entry.ok()?;
                 let hash = entry.file_name().to_str()?.parse().ok()?;
-                let mut file = fs::File::open(entry.path()).ok()?;
-
-                let _ = Option::<LedgerHash>::binprot_read(&mut file).ok()?;
-
-                let accounts = Vec::<Account>::binprot_read(&mut file).ok()?;
-                let mut mask = Mask::new_root(Database::create(35));
-                for account in accounts {
-                    let account_id = account.id();
-                    mask.get_or_create_account(account_id, account).unwrap();
-                }
-                Some((hash, mask))
+                Some((hash, snarked_ledger_read(&entry.path())?))
             })
             .collect();
 
@@ -131,6 +188,49 @@ impl LedgerCtx {
         self.event_sender = Some(event_sender);
     }
 
+    /// Enables exporting of the staking and next epoch ledgers to `dir`,
+    /// see [`LedgerCtx::epoch_ledgers_export`].
+    pub fn set_epoch_ledgers_export_dir(&mut self, dir: PathBuf) {
+        self.epoch_ledgers_export_dir = Some(dir);
+    }
+
+    /// Enables persisting of the snarked ledger sync progress into `path`.
+    ///
+    /// The accounts of the ledger whose sync was in progress in the previous
+    /// run are restored into the sync ledgers, and its sync progress is
+    /// returned so that the sync can be resumed.
+    pub fn set_ledger_sync_checkpoint_path(
+        &mut self,
+        path: PathBuf,
+    ) -> Option<SnarkedLedgerSyncCheckpoint> {
+        let restored = snarked_ledger_sync_checkpoint_load(&path).unwrap_or_else(|err| {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "LedgerSyncCheckpointError",
+                summary = "failed to load snarked ledger sync checkpoint",
+                path = path.display().to_string(),
+                error = err);
+            None
+        });
+        self.ledger_sync_checkpoint_path = Some(path);
+
+        let (checkpoint, accounts, is_intact) = restored?;
+        let snarked_ledger_hash = checkpoint.snarked_ledger_hash.clone();
+        self.sync
+            .snarked_ledger_mut(snarked_ledger_hash.clone())
+            .set_batch_accounts(&accounts);
+        openmina_core::log::info!(openmina_core::log::system_time();
+            kind = "LedgerSyncCheckpointRestored",
+            summary = format!("snarked ledger {snarked_ledger_hash} sync checkpoint restored"),
+            accounts = accounts.len(),
+            frontier = checkpoint.frontier.len());
+        // accounts can only be appended after the intact ones, otherwise
+        // the next checkpoint rewrites them.
+        if is_intact {
+            self.ledger_sync_checkpoint_hash = Some(snarked_ledger_hash);
+        }
+        Some(checkpoint)
+    }
+
     pub(super) fn send_event(&self, event: LedgerEvent) {
         if let Some(tx) = self.event_sender.as_ref() {
             let _ = tx.send(event.into());
@@ -216,10 +316,11 @@ impl LedgerCtx {
         Ok(true)
     }
 
+    /// Computes all the pending hashes of the ledger, returning its merkle root.
     pub fn compute_snarked_ledger_hashes(
         &mut self,
         snarked_ledger_hash: &LedgerHash,
-    ) -> Result<(), String> {
+    ) -> Result<LedgerHash, String> {
         let origin = self
             .snarked_ledgers
             .get_mut(snarked_ledger_hash)
@@ -231,9 +332,7 @@ impl LedgerCtx {
 
         // Our ledger is lazy when it comes to hashing, but retrieving the
         // merkle root hash forces all pending hashes to be computed.
-        let _force_hashing = origin.merkle_root();
-
-        Ok(())
+        Ok(merkle_root(origin))
     }
 
     /// Returns a mutable reference to the [StagedLedger] with the specified `hash` if it exists or `None` otherwise.
@@ -414,6 +513,201 @@ impl LedgerCtx {
         Some(producers)
     }
 
+    /// Returns the total number of accounts in the ledger, and default token
+    /// accounts at ledger positions `offset..offset + limit`.
+    pub fn epoch_ledger_accounts(
+        &self,
+        ledger_hash: &LedgerHash,
+        offset: usize,
+        limit: usize,
+    ) -> Option<(usize, Vec<RpcEpochLedgerAccount>)> {
+        let (mask, _) = self.mask(ledger_hash).filter(|(_, is_synced)| *is_synced)?;
+        let total = mask.num_accounts();
+        let end = offset.saturating_add(limit).min(total);
+
+        let accounts = (offset..end)
+            .filter_map(|index| mask.get_at_index(AccountIndex(index as u64)))
+            .filter(|account| account.token_id.is_default())
+            .map(|account| RpcEpochLedgerAccount {
+                public_key: account.public_key.clone().into(),
+                delegate: account.delegate.clone().map(Into::into),
+                balance: account.balance.as_u64(),
+                nonce: account.nonce.as_u32(),
+            })
+            .collect();
+        Some((total, accounts))
+    }
+
+    /// Returns default token accounts delegating to the `producer`, along
+    /// with the global slot since which they have no locked tokens.
+    pub fn payout_delegators(
+        &self,
+        ledger_hash: &LedgerHash,
+        producer: &AccountPublicKey,
+    ) -> Option<Vec<PayoutDelegator>> {
+        let mask = self
+            .mask(ledger_hash)
+            .filter(|(_, is_synced)| *is_synced)
+            .map(|(mask, _)| mask)
+            .or_else(|| {
+                // staking ledgers of past epochs are only in the exports.
+                let dir = self.epoch_ledgers_export_dir.as_ref()?;
+                snarked_ledger_read(&dir.join(ledger_hash.to_string()))
+            })?;
+        let producer = CompressedPubKey::from(producer.clone());
+        let mut delegators = Vec::new();
+
+        mask.iter(|account| {
+            if account.token_id.is_default() && account.delegate.as_ref() == Some(&producer) {
+                delegators.push(PayoutDelegator {
+                    public_key: account.public_key.clone().into(),
+                    stake: account.balance.as_u64(),
+                    unlocked_at_global_slot: unlocked_at_global_slot(&account.timing),
+                });
+            }
+        });
+        Some(delegators)
+    }
+
+    /// Checks the block's vrf claim against its staking ledger, if we have
+    /// the ledger.
+    pub fn block_vrf_verify(&self, data: LedgerReadBlockVrfVerify) -> Option<bool> {
+        let (mask, _) = self
+            .mask(&data.staking_ledger_hash)
+            .filter(|(_, is_synced)| *is_synced)?;
+        let input = VrfVerificationInput {
+            vrf_output: data.vrf_output,
+            stake_winner: data.stake_winner.into(),
+            block_creator: data.block_creator.into(),
+            total_currency: data.total_currency.into(),
+        };
+        vrf::verify_vrf(&mask, input).ok()
+    }
+
+    /// Writes epoch ledgers which weren't exported yet into the export
+    /// directory, both as binprot (`<ledger_hash>`, loadable as an additional
+    /// snarked ledger) and as JSON in the format of daemon's
+    /// `mina ledger export staking-epoch-ledger` (`<ledger_hash>.json`).
+    ///
+    /// Ledgers are written by a single worker thread. Ledgers that aren't
+    /// available yet, and the ones whose export failed, are exported once
+    /// requested again.
+    pub fn epoch_ledgers_export(&mut self, ledger_hashes: Vec<LedgerHash>) {
+        let Some(dir) = self.epoch_ledgers_export_dir.clone() else {
+            return;
+        };
+        if self.epoch_ledgers_exporter.is_none() {
+            match EpochLedgersExporter::spawn(dir) {
+                Ok(exporter) => self.epoch_ledgers_exporter = Some(exporter),
+                Err(err) => {
+                    openmina_core::log::error!(openmina_core::log::system_time();
+                        kind = "LedgerEpochLedgerExportError",
+                        summary = "failed to spawn the epoch ledgers export worker",
+                        error = err.to_string());
+                    return;
+                }
+            }
+        }
+        let Some(exporter) = self.epoch_ledgers_exporter.as_ref() else {
+            return;
+        };
+        for (ledger_hash, result) in exporter.results.try_iter() {
+            if result.is_err() {
+                self.exported_epoch_ledgers.remove(&ledger_hash);
+            }
+        }
+
+        for ledger_hash in ledger_hashes {
+            if self.exported_epoch_ledgers.contains(&ledger_hash) {
+                continue;
+            }
+            let Some((mask, _)) = self.mask(&ledger_hash).filter(|(_, is_synced)| *is_synced)
+            else {
+                openmina_core::log::info!(openmina_core::log::system_time();
+                    kind = "LedgerEpochLedgerExportSkipped",
+                    summary = format!("epoch ledger {ledger_hash} not available for export yet"));
+                continue;
+            };
+            let Some(exporter) = self.epoch_ledgers_exporter.as_ref() else {
+                return;
+            };
+            if exporter
+                .sender
+                .send((ledger_hash.clone(), mask.copy()))
+                .is_err()
+            {
+                // worker is gone, respawn it on the next export.
+                self.epoch_ledgers_exporter = None;
+                return;
+            }
+            self.exported_epoch_ledgers.insert(ledger_hash);
+        }
+    }
+
+    /// Persists the sync progress along with the accounts of the ledger
+    /// being synced, replacing the previously persisted one.
+    ///
+    /// Only the accounts set since the last checkpoint of the same ledger
+    /// are written. The first checkpoint of the ledger writes all of them,
+    /// as the ledger may start as a copy of another one.
+    pub fn snarked_ledger_sync_checkpoint_save(&mut self, checkpoint: SnarkedLedgerSyncCheckpoint) {
+        let Some(path) = self.ledger_sync_checkpoint_path.clone() else {
+            return;
+        };
+        let snarked_ledger_hash = checkpoint.snarked_ledger_hash.clone();
+        let is_append = self.ledger_sync_checkpoint_hash.as_ref() == Some(&snarked_ledger_hash);
+        let accounts = if is_append {
+            match self.ledger_sync_unsaved_accounts.take() {
+                Some((hash, accounts)) if hash == snarked_ledger_hash => accounts,
+                _ => vec![],
+            }
+        } else {
+            let Ok(mask) = self.pending_sync_snarked_ledger_mask(&snarked_ledger_hash) else {
+                return;
+            };
+            self.ledger_sync_unsaved_accounts = None;
+            // Accounts of the ledger are all within the subtree of this size.
+            let max_accounts = 1u64 << tree_height_for_num_accounts(checkpoint.num_accounts);
+            (0..max_accounts)
+                .filter_map(|index| Some((index, *mask.get_at_index(AccountIndex(index))?)))
+                .collect()
+        };
+        match snarked_ledger_sync_checkpoint_write(&path, checkpoint, accounts, is_append) {
+            Ok(()) => {
+                self.ledger_sync_checkpoint_hash = Some(snarked_ledger_hash);
+            }
+            Err(err) => {
+                // the unsaved accounts are lost, so all of them are
+                // written by the next checkpoint.
+                self.ledger_sync_checkpoint_hash = None;
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "LedgerSyncCheckpointError",
+                    summary = format!("failed to save snarked ledger {snarked_ledger_hash} sync checkpoint"),
+                    path = path.display().to_string(),
+                    error = err.to_string());
+            }
+        }
+    }
+
+    /// Removes the persisted sync progress of the ledger, if any.
+    pub fn snarked_ledger_sync_checkpoint_remove(&mut self, snarked_ledger_hash: &LedgerHash) {
+        if self.ledger_sync_checkpoint_hash.as_ref() != Some(snarked_ledger_hash) {
+            return;
+        }
+        self.ledger_sync_checkpoint_hash = None;
+        self.ledger_sync_unsaved_accounts = None;
+        let Some(path) = self.ledger_sync_checkpoint_path.as_ref() else {
+            return;
+        };
+        if let Err(err) = snarked_ledger_sync_checkpoint_remove(path) {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "LedgerSyncCheckpointError",
+                summary = "failed to remove snarked ledger sync checkpoint",
+                path = path.display().to_string(),
+                error = err.to_string());
+        }
+    }
+
     pub fn child_hashes_get(
         &mut self,
         snarked_ledger_hash: LedgerHash,
@@ -426,22 +720,47 @@ impl LedgerCtx {
         Ok((left_hash, right_hash))
     }
 
+    /// Sets the accounts under the subtree at `parent`, only if the hash
+    /// computed for that subtree matches `expected_hash`, so that the
+    /// invalid accounts never get into the ledger. Returns the computed hash.
     pub fn accounts_set(
         &mut self,
         snarked_ledger_hash: LedgerHash,
         parent: &LedgerAddress,
+        expected_hash: &LedgerHash,
         accounts: Vec<v2::MinaBaseAccountBinableArgStableV2>,
     ) -> Result<LedgerHash, String> {
         let mut mask = self.pending_sync_snarked_ledger_mask(&snarked_ledger_hash)?;
-        let accounts: Vec<_> = accounts
+        let accounts: Vec<Box<Account>> = accounts
             .into_iter()
             .map(|account| Box::new((&account).into()))
             .collect();
 
+        let computed_hash = accounts_subtree_hash(parent, &accounts)
+            .map(LedgerHash::from_fp)
+            .ok_or_else(|| format!("Too many accounts for the subtree at {parent:?}"))?;
+        if &computed_hash != expected_hash {
+            return Ok(computed_hash);
+        }
+
         mask.set_all_accounts_rooted_at(parent.clone(), &accounts)
             .map_err(|_| "Failed when setting accounts".to_owned())?;
 
-        let computed_hash = LedgerHash::from_fp(mask.get_inner_hash_at_addr(parent.clone())?);
+        if self.ledger_sync_checkpoint_path.is_some() {
+            let is_other_ledger = self
+                .ledger_sync_unsaved_accounts
+                .as_ref()
+                .map_or(true, |(hash, _)| hash != &snarked_ledger_hash);
+            if is_other_ledger {
+                self.ledger_sync_unsaved_accounts = Some((snarked_ledger_hash, vec![]));
+            }
+            if let Some((_, unsaved)) = self.ledger_sync_unsaved_accounts.as_mut() {
+                let indexes = parent
+                    .iter_children(LEDGER_DEPTH)
+                    .map(|address| address.to_index().0);
+                unsaved.extend(indexes.zip(accounts.into_iter().map(|account| *account)));
+            }
+        }
 
         Ok(computed_hash)
     }
@@ -675,18 +994,28 @@ impl LedgerCtx {
             })
             .unwrap_or_default();
 
-        let available_jobs = self
+        let (available_jobs, available_jobs_depth, next_block_jobs) = self
             .staged_ledger_mut(new_best_tip.staged_ledger_hash())
             .map(|l| {
-                l.scan_state()
+                let scan_state = l.scan_state();
+                let available_jobs = scan_state
                     .all_job_pairs_iter()
                     .map(|job| job.map(|single| AvailableJobMessage::from(single)))
-                    .collect()
+                    .collect::<Vec<_>>();
+                let available_jobs_depth = available_jobs_depth(scan_state, &available_jobs);
+                let next_block_jobs = scan_state
+                    .work_statements_for_new_diff()
+                    .iter()
+                    .map(SnarkJobId::from)
+                    .collect();
+                (available_jobs, available_jobs_depth, next_block_jobs)
             })
             .unwrap_or_default();
 
         CommitResult {
             available_jobs,
+            available_jobs_depth,
+            next_block_jobs,
             needed_protocol_states,
         }
     }
@@ -1038,6 +1367,63 @@ impl LedgerSyncState {
     }
 }
 
+/// Depth in the scan state tree (root being at 0) of the available jobs.
+fn available_jobs_depth(
+    scan_state: &ScanState,
+    available_jobs: &[OneOrTwo<AvailableJobMessage>],
+) -> BTreeMap<SnarkJobId, usize> {
+    use ledger::scan_state::scan_state::JobValue;
+    use mina_p2p_messages::v2::MinaStateBlockchainStateValueStableV2LedgerProofStatementSource as Source;
+
+    let to_job_id = |source: Source, target: Source| SnarkJobId::from((&source, &target));
+    let depths = scan_state
+        .view()
+        .flatten()
+        .filter_map(|job| {
+            let job_id = match &job.job {
+                JobValue::Leaf(JobValueBase::Full(base)) => {
+                    let stmt = &base.job.statement;
+                    to_job_id((&stmt.source).into(), (&stmt.target).into())
+                }
+                JobValue::Node(JobValueMerge::Full(merge)) => {
+                    let left = merge.left.proof.statement();
+                    let right = merge.right.proof.statement();
+                    to_job_id((&left.source).into(), (&right.target).into())
+                }
+                _ => return None,
+            };
+            Some((job_id, job.depth()))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let job_id = |job: &AvailableJobMessage| -> SnarkJobId {
+        match job {
+            AvailableJobMessage::Base(base) => {
+                (&base.statement.0.source, &base.statement.0.target).into()
+            }
+            AvailableJobMessage::Merge { left, right } => {
+                (&left.0 .0.statement.source, &right.0 .0.statement.target).into()
+            }
+        }
+    };
+    // Jobs of the pair can be at different depths, even in different trees,
+    // so the one closest to the root is used.
+    available_jobs
+        .iter()
+        .filter_map(|job| {
+            let depth = match job {
+                OneOrTwo::One(first) => depths.get(&job_id(first)).copied(),
+                OneOrTwo::Two((first, second)) => {
+                    let first = depths.get(&job_id(first)).copied();
+                    let second = depths.get(&job_id(second)).copied();
+                    first.into_iter().chain(second).min()
+                }
+            }?;
+            Some((SnarkJobId::from(job), depth))
+        })
+        .collect()
+}
+
 fn staged_ledger_reconstruct(
     snarked_ledger: Mask,
     snarked_ledger_hash: LedgerHash,
@@ -1096,6 +1482,236 @@ pub trait LedgerService: redux::Service {
             self.ledger_manager().call(request);
         }
     }
+
+    /// Exports epoch ledgers which weren't exported yet, if enabled.
+    fn epoch_ledgers_export(&mut self, ledger_hashes: Vec<LedgerHash>) {
+        let request = LedgerRequest::EpochLedgersExport { ledger_hashes };
+        if self.force_sync_calls() {
+            let _ = self.ledger_manager().call_sync(request);
+        } else {
+            self.ledger_manager().call(request);
+        }
+    }
+}
+
+/// Reads a ledger in the format of the epoch ledger exports.
+fn snarked_ledger_read(path: &Path) -> Option<Mask> {
+    let mut file = std::fs::File::open(path).ok()?;
+
+    let _ = Option::<LedgerHash>::binprot_read(&mut file).ok()?;
+
+    let accounts = Vec::<Account>::binprot_read(&mut file).ok()?;
+    let mut mask = Mask::new_root(Database::create(35));
+    for account in accounts {
+        let account_id = account.id();
+        mask.get_or_create_account(account_id, account).unwrap();
+    }
+    Some(mask)
+}
+
+fn epoch_ledger_export_to_dir(
+    dir: &Path,
+    ledger_hash: &LedgerHash,
+    mask: &Mask,
+) -> std::io::Result<()> {
+    use std::io::Write;
+
+    std::fs::create_dir_all(dir)?;
+    let accounts = mask.to_list();
+
+    let tmp_path = dir.join(format!("{ledger_hash}.tmp"));
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&tmp_path)?);
+    Some(ledger_hash.clone()).binprot_write(&mut file)?;
+    accounts.binprot_write(&mut file)?;
+    file.flush()?;
+    drop(file);
+    std::fs::rename(&tmp_path, dir.join(ledger_hash.to_string()))?;
+
+    let json_accounts = accounts
+        .iter()
+        .map(crate::daemon_json::Account::from)
+        .collect::<Vec<_>>();
+    let tmp_path = dir.join(format!("{ledger_hash}.json.tmp"));
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&tmp_path)?);
+    serde_json::to_writer_pretty(&mut file, &json_accounts)?;
+    file.flush()?;
+    drop(file);
+    std::fs::rename(&tmp_path, dir.join(format!("{ledger_hash}.json")))
+}
+
+/// Hash of the subtree at `parent`, with `accounts` as its leftmost leaves
+/// and the rest of the leaves empty. `None` if the accounts don't fit into it.
+fn accounts_subtree_hash(parent: &LedgerAddress, accounts: &[Box<Account>]) -> Option<Fp> {
+    let height = LEDGER_DEPTH.checked_sub(parent.length())?;
+    if accounts.len() as u64 > 1u64 << height {
+        return None;
+    }
+    let mut hashes = accounts
+        .iter()
+        .map(|account| account.hash())
+        .collect::<Vec<_>>();
+    for depth in (parent.length()..LEDGER_DEPTH).rev() {
+        let empty = ledger_empty_hash_at_depth(depth + 1).0.to_field();
+        hashes = hashes
+            .chunks(2)
+            .map(|pair| hash_node_at_depth(depth, pair[0], pair.get(1).copied().unwrap_or(empty)))
+            .collect();
+    }
+    Some(
+        hashes
+            .first()
+            .copied()
+            .unwrap_or_else(|| ledger_empty_hash_at_depth(parent.length()).0.to_field()),
+    )
+}
+
+/// Persisted [`SnarkedLedgerSyncCheckpoint`]. The accounts of the ledger
+/// being synced are persisted separately, see
+/// [`SnarkedLedgerSyncAccountsChunk`].
+#[derive(BinProtRead, BinProtWrite)]
+struct SnarkedLedgerSyncCheckpointFile {
+    snarked_ledger_hash: LedgerHash,
+    num_accounts: u64,
+    synced_accounts_count: u64,
+    synced_hashes_count: u64,
+    frontier: Vec<(v2::MerkleAddressBinableArgStableV1, LedgerHash)>,
+}
+
+/// Accounts, along with their indexes, appended to the accounts file by a
+/// checkpoint.
+#[derive(BinProtRead, BinProtWrite)]
+struct SnarkedLedgerSyncAccountsChunk {
+    snarked_ledger_hash: LedgerHash,
+    accounts: Vec<(u64, Account)>,
+}
+
+fn snarked_ledger_sync_accounts_path(path: &Path) -> PathBuf {
+    path.with_extension("accounts")
+}
+
+/// Appends the accounts to the accounts file (or replaces it, unless
+/// `is_append`) and then replaces the checkpoint file.
+///
+/// The checkpoint is written to a temporary file first and then renamed,
+/// so that the previous checkpoint stays intact if the node crashes
+/// meanwhile, and it never refers to the accounts which aren't written yet.
+fn snarked_ledger_sync_checkpoint_write(
+    path: &Path,
+    checkpoint: SnarkedLedgerSyncCheckpoint,
+    accounts: Vec<(u64, Account)>,
+    is_append: bool,
+) -> std::io::Result<()> {
+    use std::io::Write;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    if !is_append {
+        // previous checkpoint mustn't outlive the accounts it refers to.
+        snarked_ledger_sync_checkpoint_remove(path)?;
+    }
+    let accounts_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(is_append)
+        .write(true)
+        .truncate(!is_append)
+        .open(snarked_ledger_sync_accounts_path(path))?;
+    let mut writer = std::io::BufWriter::new(accounts_file);
+    let chunk = SnarkedLedgerSyncAccountsChunk {
+        snarked_ledger_hash: checkpoint.snarked_ledger_hash.clone(),
+        accounts,
+    };
+    chunk.binprot_write(&mut writer)?;
+    writer.flush()?;
+    drop(writer);
+
+    let file = SnarkedLedgerSyncCheckpointFile {
+        snarked_ledger_hash: checkpoint.snarked_ledger_hash,
+        num_accounts: checkpoint.num_accounts,
+        synced_accounts_count: checkpoint.synced_accounts_count,
+        synced_hashes_count: checkpoint.synced_hashes_count,
+        frontier: checkpoint
+            .frontier
+            .into_iter()
+            .map(|query| (query.address.into(), query.expected_hash))
+            .collect(),
+    };
+    let tmp_path = path.with_extension("tmp");
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(&tmp_path)?);
+    file.binprot_write(&mut writer)?;
+    writer.flush()?;
+    drop(writer);
+    std::fs::rename(tmp_path, path)
+}
+
+/// Reads the persisted checkpoint, a missing file means there is none.
+///
+/// Returns whether the accounts file is intact too. It isn't if the node
+/// crashed while appending to it, in which case the accounts appended
+/// after the last checkpoint are ignored.
+#[allow(clippy::type_complexity)]
+fn snarked_ledger_sync_checkpoint_load(
+    path: &Path,
+) -> Result<
+    Option<(
+        SnarkedLedgerSyncCheckpoint,
+        Vec<(LedgerAddress, Box<Account>)>,
+        bool,
+    )>,
+    String,
+> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+    let file = SnarkedLedgerSyncCheckpointFile::binprot_read(&mut bytes.as_slice())
+        .map_err(|err| err.to_string())?;
+    let accounts_bytes =
+        std::fs::read(snarked_ledger_sync_accounts_path(path)).map_err(|err| err.to_string())?;
+
+    let mut accounts = vec![];
+    let mut is_intact = true;
+    let mut reader = accounts_bytes.as_slice();
+    while !reader.is_empty() {
+        let Ok(chunk) = SnarkedLedgerSyncAccountsChunk::binprot_read(&mut reader) else {
+            is_intact = false;
+            break;
+        };
+        if chunk.snarked_ledger_hash == file.snarked_ledger_hash {
+            accounts.extend(chunk.accounts.into_iter().map(|(index, account)| {
+                let address = LedgerAddress::from_index(AccountIndex(index), LEDGER_DEPTH);
+                (address, Box::new(account))
+            }));
+        }
+    }
+
+    let checkpoint = SnarkedLedgerSyncCheckpoint {
+        snarked_ledger_hash: file.snarked_ledger_hash,
+        num_accounts: file.num_accounts,
+        synced_accounts_count: file.synced_accounts_count,
+        synced_hashes_count: file.synced_hashes_count,
+        frontier: file
+            .frontier
+            .into_iter()
+            .map(|(address, expected_hash)| LedgerAddressQuery {
+                address: address.into(),
+                expected_hash,
+            })
+            .collect(),
+    };
+    Ok(Some((checkpoint, accounts, is_intact)))
+}
+
+/// Removes the checkpoint and then the accounts it refers to.
+fn snarked_ledger_sync_checkpoint_remove(path: &Path) -> std::io::Result<()> {
+    for path in [path.to_owned(), snarked_ledger_sync_accounts_path(path)] {
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+    Ok(())
 }
 
 /// Save staged ledger and block to file, when the application fail.
@@ -1173,4 +1789,194 @@ mod tests {
             assert_eq!(hash.to_string(), expected_hash);
         });
     }
+    #[test]
+    fn test_accounts_subtree_hash() {
+        let parent = LedgerAddress::from_index(AccountIndex(1), LEDGER_DEPTH - 3);
+        let accounts = (0..5)
+            .map(|_| Box::new(Account::rand()))
+            .collect::<Vec<_>>();
+        let mut mask = Mask::create(LEDGER_DEPTH);
+        mask.set_all_accounts_rooted_at(parent.clone(), &accounts)
+            .unwrap();
+        let expected_hash = mask.get_inner_hash_at_addr(parent.clone()).unwrap();
+        assert_eq!(
+            accounts_subtree_hash(&parent, &accounts),
+            Some(expected_hash)
+        );
+
+        let empty_hash = ledger_empty_hash_at_depth(parent.length()).0.to_field();
+        assert_eq!(accounts_subtree_hash(&parent, &[]), Some(empty_hash));
+        let too_many = (0..9)
+            .map(|_| Box::new(Account::rand()))
+            .collect::<Vec<_>>();
+        assert_eq!(accounts_subtree_hash(&parent, &too_many), None);
+    }
+
+    #[test]
+    fn test_ledger_sync_checkpoint_roundtrip() {
+        let dir = std::env::temp_dir().join(format!(
+            "openmina-ledger-sync-checkpoint-{}",
+            std::process::id()
+        ));
+        let path = dir.join(LEDGER_SYNC_CHECKPOINT_FILE_NAME);
+        let snarked_ledger_hash = LedgerHash::from_fp(Fp::from(1u64));
+        // ledger of 4 subtrees, 4 accounts each.
+        let subtree = |index| LedgerAddress::from_index(AccountIndex(index), LEDGER_DEPTH - 2);
+        let checkpoint = |synced: u64| SnarkedLedgerSyncCheckpoint {
+            snarked_ledger_hash: snarked_ledger_hash.clone(),
+            num_accounts: 16,
+            synced_accounts_count: synced * 4,
+            synced_hashes_count: 6,
+            frontier: (synced..4)
+                .map(|index| LedgerAddressQuery {
+                    address: subtree(index),
+                    expected_hash: LedgerHash::from_fp(Fp::from(index)),
+                })
+                .collect(),
+        };
+        let accounts_set = |ledger: &mut LedgerCtx, index: u64, is_valid: bool| {
+            let accounts = (0..4)
+                .map(|_| Box::new(Account::rand()))
+                .collect::<Vec<_>>();
+            let mut expected_hash =
+                LedgerHash::from_fp(accounts_subtree_hash(&subtree(index), &accounts).unwrap());
+            if !is_valid {
+                expected_hash = LedgerHash::from_fp(Fp::from(0u64));
+            }
+            let result = ledger.accounts_set(
+                snarked_ledger_hash.clone(),
+                &subtree(index),
+                &expected_hash,
+                accounts.iter().map(|account| (&**account).into()).collect(),
+            );
+            assert_eq!(result.unwrap() == expected_hash, is_valid);
+            accounts
+        };
+        let account_hashes = |ledger: &LedgerCtx| {
+            let mask = ledger
+                .pending_sync_snarked_ledger_mask(&snarked_ledger_hash)
+                .unwrap();
+            (0..16)
+                .filter_map(|index| mask.get_at_index(AccountIndex(index)))
+                .map(|account| account.hash())
+                .collect::<Vec<_>>()
+        };
+
+        let mut ledger = LedgerCtx::default();
+        assert!(ledger
+            .set_ledger_sync_checkpoint_path(path.clone())
+            .is_none());
+        ledger.sync.snarked_ledger_mut(snarked_ledger_hash.clone());
+        accounts_set(&mut ledger, 0, true);
+        ledger.snarked_ledger_sync_checkpoint_save(checkpoint(1));
+        // only the accounts set since the last checkpoint are appended.
+        accounts_set(&mut ledger, 1, true);
+        ledger.snarked_ledger_sync_checkpoint_save(checkpoint(2));
+        // invalid accounts aren't set.
+        accounts_set(&mut ledger, 2, false);
+        let synced = account_hashes(&ledger);
+        assert_eq!(synced.len(), 8);
+
+        let mut restored = LedgerCtx::default();
+        let resumed = restored
+            .set_ledger_sync_checkpoint_path(path.clone())
+            .unwrap();
+        assert_eq!(resumed.snarked_ledger_hash, snarked_ledger_hash);
+        assert_eq!(resumed.num_accounts, 16);
+        assert_eq!(resumed.synced_accounts_count, 8);
+        assert_eq!(resumed.synced_hashes_count, 6);
+        assert_eq!(
+            resumed
+                .frontier
+                .iter()
+                .map(|query| (query.address.clone(), query.expected_hash.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (subtree(2), LedgerHash::from_fp(Fp::from(2u64))),
+                (subtree(3), LedgerHash::from_fp(Fp::from(3u64))),
+            ]
+        );
+        assert_eq!(account_hashes(&restored), synced);
+        assert_eq!(
+            restored.ledger_sync_checkpoint_hash.as_ref(),
+            Some(&snarked_ledger_hash)
+        );
+
+        // partially appended accounts are ignored, and the next checkpoint
+        // rewrites all of them.
+        {
+            use std::io::Write;
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(snarked_ledger_sync_accounts_path(&path))
+                .unwrap();
+            file.write_all(&[1, 2, 3]).unwrap();
+        }
+        let mut restored = LedgerCtx::default();
+        assert!(restored
+            .set_ledger_sync_checkpoint_path(path.clone())
+            .is_some());
+        assert_eq!(account_hashes(&restored), synced);
+        assert_eq!(restored.ledger_sync_checkpoint_hash, None);
+
+        ledger.snarked_ledger_sync_checkpoint_remove(&snarked_ledger_hash);
+        assert!(!path.exists());
+        assert!(!snarked_ledger_sync_accounts_path(&path).exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_epoch_ledgers_export_retry() {
+        let dir = std::env::temp_dir().join(format!(
+            "openmina-epoch-ledgers-export-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        // the export fails while the directory can't be created.
+        std::fs::write(&dir, b"").unwrap();
+
+        let mut mask = Mask::new_root(Database::create(35));
+        let account = Account::rand();
+        mask.get_or_create_account(account.id(), account.clone())
+            .unwrap();
+        let ledger_hash = merkle_root(&mut mask);
+        let mut ledger = LedgerCtx::default();
+        ledger.snarked_ledgers.insert(ledger_hash.clone(), mask);
+        ledger.set_epoch_ledgers_export_dir(dir.clone());
+
+        let wait_until = |ledger: &mut LedgerCtx, f: &dyn Fn(&LedgerCtx) -> bool| {
+            for _ in 0..200 {
+                // collects the results of the worker.
+                ledger.epoch_ledgers_export(vec![]);
+                if f(ledger) {
+                    return;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+            panic!("timed out waiting for the epoch ledger export");
+        };
+
+        let missing = LedgerHash::from_fp(Fp::from(1u64));
+        ledger.epoch_ledgers_export(vec![ledger_hash.clone(), missing.clone()]);
+        assert!(ledger.exported_epoch_ledgers.contains(&ledger_hash));
+        assert!(!ledger.exported_epoch_ledgers.contains(&missing));
+        // failed export is retried.
+        wait_until(&mut ledger, &|ledger| {
+            !ledger.exported_epoch_ledgers.contains(&ledger_hash)
+        });
+
+        std::fs::remove_file(&dir).unwrap();
+        ledger.epoch_ledgers_export(vec![ledger_hash.clone()]);
+        let json_path = dir.join(format!("{ledger_hash}.json"));
+        wait_until(&mut ledger, &|_| json_path.exists());
+        assert!(ledger.exported_epoch_ledgers.contains(&ledger_hash));
+
+        let mut file = std::fs::File::open(dir.join(ledger_hash.to_string())).unwrap();
+        let exported_hash = Option::<LedgerHash>::binprot_read(&mut file).unwrap();
+        let accounts = Vec::<Account>::binprot_read(&mut file).unwrap();
+        assert_eq!(exported_hash, Some(ledger_hash));
+        assert_eq!(accounts, vec![account]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
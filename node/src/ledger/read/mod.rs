@@ -10,13 +10,15 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use mina_p2p_messages::v2;
+use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use crate::account::AccountPublicKey;
+use crate::block_producer::payout::PayoutDelegator;
 use crate::block_producer::vrf_evaluator::DelegatorTable;
 use crate::ledger::LedgerAddress;
 use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
-use crate::rpc::RpcScanStateSummaryScanStateJob;
+use crate::rpc::{RpcEpochLedgerAccount, RpcScanStateSummaryScanStateJob};
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
 pub enum LedgerReadKind {
@@ -26,6 +28,9 @@ pub enum LedgerReadKind {
     GetChildAccountsAtAddr,
     GetStagedLedgerAuxAndPendingCoinbases,
     ScanStateSummary,
+    EpochLedgerAccounts,
+    PayoutDelegators,
+    BlockVrfVerify,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -39,6 +44,12 @@ pub enum LedgerReadRequest {
     GetStagedLedgerAuxAndPendingCoinbases(LedgerReadStagedLedgerAuxAndPendingCoinbases),
     // rpcs
     ScanStateSummary(v2::LedgerHash),
+    /// Page of epoch ledger accounts: ledger hash, offset and limit.
+    EpochLedgerAccounts(v2::LedgerHash, usize, usize),
+    /// Delegators of the producer in the epoch ledger, for payouts.
+    PayoutDelegators(v2::LedgerHash, AccountPublicKey),
+    /// Block's vrf claim to be checked against its staking ledger.
+    BlockVrfVerify(LedgerReadBlockVrfVerify),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -52,6 +63,10 @@ pub enum LedgerReadResponse {
     GetStagedLedgerAuxAndPendingCoinbases(Option<Arc<StagedLedgerAuxAndPendingCoinbases>>),
     // rpcs
     ScanStateSummary(Vec<Vec<RpcScanStateSummaryScanStateJob>>),
+    EpochLedgerAccounts(Option<(usize, Vec<RpcEpochLedgerAccount>)>),
+    PayoutDelegators(Option<Vec<PayoutDelegator>>),
+    /// `None` if the staking ledger isn't available.
+    BlockVrfVerify(v2::StateHash, Option<bool>),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -60,6 +75,31 @@ pub struct LedgerReadStagedLedgerAuxAndPendingCoinbases {
     pub protocol_states: BTreeMap<v2::StateHash, v2::MinaStateProtocolStateValueStableV2>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct LedgerReadBlockVrfVerify {
+    pub block_hash: v2::StateHash,
+    pub staking_ledger_hash: v2::LedgerHash,
+    pub total_currency: u64,
+    pub vrf_output: v2::ConsensusVrfOutputTruncatedStableV1,
+    pub stake_winner: AccountPublicKey,
+    pub block_creator: AccountPublicKey,
+}
+
+impl LedgerReadBlockVrfVerify {
+    pub fn new(block: &ArcBlockWithHash) -> Self {
+        let consensus_state = block.consensus_state();
+        let staking_ledger = &consensus_state.staking_epoch_data.ledger;
+        Self {
+            block_hash: block.hash().clone(),
+            staking_ledger_hash: staking_ledger.hash.clone(),
+            total_currency: staking_ledger.total_currency.as_u64(),
+            vrf_output: consensus_state.last_vrf_output.clone(),
+            stake_winner: consensus_state.block_stake_winner.clone().into(),
+            block_creator: consensus_state.block_creator.clone().into(),
+        }
+    }
+}
+
 impl LedgerReadRequest {
     pub fn kind(&self) -> LedgerReadKind {
         match self {
@@ -71,6 +111,9 @@ impl LedgerReadRequest {
                 LedgerReadKind::GetStagedLedgerAuxAndPendingCoinbases
             }
             Self::ScanStateSummary(..) => LedgerReadKind::ScanStateSummary,
+            Self::EpochLedgerAccounts(..) => LedgerReadKind::EpochLedgerAccounts,
+            Self::PayoutDelegators(..) => LedgerReadKind::PayoutDelegators,
+            Self::BlockVrfVerify(..) => LedgerReadKind::BlockVrfVerify,
         }
     }
 
@@ -86,6 +129,9 @@ impl LedgerReadRequest {
             Self::GetChildHashesAtAddr(..) => 1,
             Self::GetStagedLedgerAuxAndPendingCoinbases(..) => 100,
             Self::ScanStateSummary(..) => 100,
+            Self::EpochLedgerAccounts(_, _, limit) => limit / 4,
+            Self::PayoutDelegators(..) => 100,
+            Self::BlockVrfVerify(..) => 1,
         };
         cost.max(1)
     }
@@ -102,6 +148,9 @@ impl LedgerReadResponse {
                 LedgerReadKind::GetStagedLedgerAuxAndPendingCoinbases
             }
             Self::ScanStateSummary(..) => LedgerReadKind::ScanStateSummary,
+            Self::EpochLedgerAccounts(..) => LedgerReadKind::EpochLedgerAccounts,
+            Self::PayoutDelegators(..) => LedgerReadKind::PayoutDelegators,
+            Self::BlockVrfVerify(..) => LedgerReadKind::BlockVrfVerify,
         }
     }
 }
@@ -80,6 +80,10 @@ pub enum LedgerWriteResponse {
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct CommitResult {
     pub available_jobs: Vec<OneOrTwo<AvailableJobMessage>>,
+    /// Depth of the available jobs in the scan state tree, root being at 0.
+    pub available_jobs_depth: BTreeMap<SnarkJobId, usize>,
+    /// Jobs whose works are needed by the next block, in order.
+    pub next_block_jobs: Vec<SnarkJobId>,
     pub needed_protocol_states: BTreeSet<v2::StateHash>,
 }
 
@@ -54,11 +54,14 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             P2pAction::Disconnection(action) => action.action_event(&context),
             P2pAction::Discovery(action) => action.action_event(&context),
             P2pAction::Identify(action) => action.action_event(&context),
+            P2pAction::Nat(action) => action.action_event(&context),
+            P2pAction::AddressBook(action) => action.action_event(&context),
             P2pAction::Channels(action) => match action {
                 P2pChannelsAction::MessageReceived(action) => action.action_event(&context),
                 P2pChannelsAction::BestTip(action) => action.action_event(&context),
                 P2pChannelsAction::Snark(action) => action.action_event(&context),
                 P2pChannelsAction::SnarkJobCommitment(action) => action.action_event(&context),
+                P2pChannelsAction::Signaling(action) => action.action_event(&context),
                 P2pChannelsAction::Rpc(action) => action.action_event(&context),
             },
             P2pAction::Peer(action) => action.action_event(&context),
@@ -72,6 +75,10 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pNetworkAction::Kad(action) => action.action_event(&context),
                 P2pNetworkAction::Pubsub(action) => action.action_event(&context),
                 P2pNetworkAction::Identify(action) => action.action_event(&context),
+                P2pNetworkAction::NodeStatus(action) => action.action_event(&context),
+                P2pNetworkAction::Ping(action) => action.action_event(&context),
+                P2pNetworkAction::Relay(action) => action.action_event(&context),
+                P2pNetworkAction::Quic(action) => action.action_event(&context),
             },
         },
         Action::ExternalSnarkWorker(action) => action.action_event(&context),
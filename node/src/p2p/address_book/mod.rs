@@ -0,0 +1,7 @@
+pub use ::p2p::address_book::*;
+
+impl redux::EnablingCondition<crate::State> for P2pAddressBookAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        state.p2p.is_enabled(self, time)
+    }
+}
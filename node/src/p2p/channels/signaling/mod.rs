@@ -0,0 +1,3 @@
+pub use ::p2p::channels::signaling::*;
+
+mod p2p_channels_signaling_actions;
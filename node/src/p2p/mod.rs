@@ -1,9 +1,11 @@
 pub use ::p2p::*;
 
+pub mod address_book;
 pub mod channels;
 pub mod connection;
 pub mod disconnection;
 pub mod discovery;
+pub mod nat;
 pub mod network;
 pub mod peer;
 
@@ -77,9 +79,19 @@ impl_into_global_action!(disconnection::P2pDisconnectionAction);
 
 impl_into_global_action!(discovery::P2pDiscoveryAction);
 
+impl_into_global_action!(nat::P2pNatAction);
+
+impl_into_global_action!(address_book::P2pAddressBookAction);
+
+impl_into_global_action!(peer::P2pPeerAction);
+
 impl_into_global_action!(network::P2pNetworkSchedulerAction);
 impl_into_global_action!(network::kad::P2pNetworkKademliaAction);
 impl_into_global_action!(network::pubsub::P2pNetworkPubsubAction);
+impl_into_global_action!(network::node_status::P2pNetworkNodeStatusAction);
+impl_into_global_action!(network::ping::P2pNetworkPingAction);
+impl_into_global_action!(network::relay::P2pNetworkRelayAction);
+impl_into_global_action!(network::quic::P2pNetworkQuicAction);
 
 impl_into_global_action!(channels::P2pChannelsMessageReceivedAction);
 
@@ -89,4 +101,6 @@ impl_into_global_action!(channels::snark::P2pChannelsSnarkAction);
 
 impl_into_global_action!(channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction);
 
+impl_into_global_action!(channels::signaling::P2pChannelsSignalingAction);
+
 impl_into_global_action!(channels::rpc::P2pChannelsRpcAction);
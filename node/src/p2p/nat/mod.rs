@@ -0,0 +1,7 @@
+pub use ::p2p::nat::*;
+
+impl redux::EnablingCondition<crate::State> for P2pNatAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        state.p2p.is_enabled(self, time)
+    }
+}
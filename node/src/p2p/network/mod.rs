@@ -77,3 +77,27 @@ impl redux::EnablingCondition<crate::State> for P2pNetworkPubsubAction {
         state.p2p.is_enabled(self, time)
     }
 }
+
+impl redux::EnablingCondition<crate::State> for P2pNetworkNodeStatusAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        state.p2p.is_enabled(self, time)
+    }
+}
+
+impl redux::EnablingCondition<crate::State> for P2pNetworkPingAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        state.p2p.is_enabled(self, time)
+    }
+}
+
+impl redux::EnablingCondition<crate::State> for P2pNetworkRelayAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        state.p2p.is_enabled(self, time)
+    }
+}
+
+impl redux::EnablingCondition<crate::State> for P2pNetworkQuicAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        state.p2p.is_enabled(self, time)
+    }
+}
@@ -1,6 +1,12 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use mina_p2p_messages::list::List;
 use mina_p2p_messages::v2::{MinaLedgerSyncLedgerAnswerStableV2, StateHash};
 use openmina_core::block::BlockWithHash;
 use p2p::P2pInitializeAction;
+use redux::Timestamp;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 use crate::consensus::ConsensusAction;
 use crate::rpc::RpcAction;
@@ -12,7 +18,10 @@ use crate::transition_frontier::sync::ledger::snarked::{
 use crate::transition_frontier::sync::ledger::staged::{
     PeerStagedLedgerPartsFetchError, TransitionFrontierSyncLedgerStagedAction,
 };
-use crate::transition_frontier::sync::{PeerBlockFetchError, TransitionFrontierSyncAction};
+use crate::transition_frontier::sync::{
+    PeerBlockFetchError, TransitionFrontierSyncAction, TransitionFrontierSyncState,
+    BLOCKS_FETCH_BATCH_SIZE,
+};
 use crate::watched_accounts::{
     WatchedAccountLedgerInitialState, WatchedAccountsAction,
     WatchedAccountsLedgerInitialStateGetError,
@@ -20,16 +29,21 @@ use crate::watched_accounts::{
 use crate::{p2p_ready, Service, Store};
 
 use super::channels::best_tip::P2pChannelsBestTipAction;
-use super::channels::rpc::{BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse};
+use super::channels::rpc::{P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse};
 use super::channels::snark::P2pChannelsSnarkAction;
 use super::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
 use super::channels::P2pChannelsAction;
 use super::connection::incoming::P2pConnectionIncomingAction;
-use super::connection::outgoing::P2pConnectionOutgoingAction;
+use super::connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingInitOpts};
 use super::connection::{P2pConnectionAction, P2pConnectionResponse};
 use super::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
 use super::discovery::P2pDiscoveryAction;
+use super::network::{
+    libp2p_peer_id, P2pNetworkAction, P2pNetworkNodeStatus, P2pNetworkNodeStatusAction,
+    P2pNetworkNodeStatusPeer, P2pNetworkNodeSyncStatus, P2pNetworkService,
+};
 use super::peer::P2pPeerAction;
+use super::webrtc::SignalingMethod;
 use super::{P2pAction, P2pActionWithMeta};
 
 pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta) {
@@ -39,6 +53,7 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
         P2pAction::Initialization(P2pInitializeAction::Initialize { .. }) => {
             if store.state().p2p.ready().is_some() {
                 store.service().start_mio();
+                store.service().start_quic();
             }
         }
         P2pAction::Connection(action) => match action {
@@ -196,6 +211,8 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
         }
         P2pAction::Discovery(action) => action.effects(&meta, store),
         P2pAction::Identify(action) => action.effects(&meta, store),
+        P2pAction::Nat(action) => action.effects(&meta, store),
+        P2pAction::AddressBook(action) => action.effects(&meta, store),
         P2pAction::Channels(action) => match action {
             P2pChannelsAction::MessageReceived(action) => {
                 action.effects(&meta, store);
@@ -244,6 +261,9 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
                     });
                 }
             }
+            P2pChannelsAction::Signaling(action) => {
+                action.effects(&meta, store);
+            }
             P2pChannelsAction::Rpc(action) => {
                 // TODO: does the order matter here? if not this clone can be removed
                 action.clone().effects(&meta, store);
@@ -314,7 +334,8 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
                                     },
                                 );
                             }
-                            Some(P2pRpcResponse::BestTipWithProof(resp)) => {
+                            Some(P2pRpcResponse::BestTipWithProof(resp))
+                            | Some(P2pRpcResponse::Ancestry(resp)) => {
                                 let (body_hashes, root_block) = &resp.proof;
                                 let best_tip = BlockWithHash::new(resp.best_tip.clone());
                                 let root_block = BlockWithHash::new(root_block.clone());
@@ -402,7 +423,26 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
                                     TransitionFrontierSyncAction::BlocksPeerQuerySuccess {
                                         peer_id,
                                         rpc_id: id,
-                                        response: block,
+                                        response: vec![block],
+                                    },
+                                );
+                            }
+                            Some(P2pRpcResponse::TransitionChain(blocks)) => {
+                                let blocks = blocks.iter().cloned().map(BlockWithHash::new);
+                                store.dispatch(
+                                    TransitionFrontierSyncAction::BlocksPeerQuerySuccess {
+                                        peer_id,
+                                        rpc_id: id,
+                                        response: blocks.collect(),
+                                    },
+                                );
+                            }
+                            Some(P2pRpcResponse::TransitionChainProof(proof)) => {
+                                store.dispatch(
+                                    TransitionFrontierSyncAction::BlocksPeerQueryProofSuccess {
+                                        peer_id,
+                                        rpc_id: id,
+                                        proof: proof.clone(),
                                     },
                                 );
                             }
@@ -418,6 +458,7 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
                                     peers: peers.iter().cloned().collect(),
                                 });
                             }
+                            Some(P2pRpcResponse::TransitionKnowledge(_)) => {}
                         }
                         store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
                         store.dispatch(
@@ -432,22 +473,34 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
                     } => {
                         match request {
                             P2pRpcRequest::BestTipWithProof => {
-                                let best_chain = &store.state().transition_frontier.best_chain;
-                                let response = None.or_else(|| {
-                                    let best_tip = best_chain.last()?;
-                                    let mut chain_iter = best_chain.iter();
-                                    let root_block = chain_iter.next()?;
-                                    // TODO(binier): cache body hashes
-                                    let body_hashes = chain_iter
-                                        .map(|b| b.block.header.protocol_state.body.hash())
-                                        .collect();
-
-                                    Some(BestTipWithProof {
-                                        best_tip: best_tip.block.clone(),
-                                        proof: (body_hashes, root_block.block.clone()),
-                                    })
+                                let response = store
+                                    .state()
+                                    .transition_frontier
+                                    .best_tip_with_proof()
+                                    .map(P2pRpcResponse::BestTipWithProof);
+                                store.dispatch(P2pChannelsRpcAction::ResponseSend {
+                                    peer_id,
+                                    id,
+                                    response,
+                                });
+                            }
+                            P2pRpcRequest::Ancestry(consensus_state, hash) => {
+                                let response = store
+                                    .state()
+                                    .transition_frontier
+                                    .ancestry(&consensus_state, &hash)
+                                    .map(P2pRpcResponse::Ancestry);
+                                store.dispatch(P2pChannelsRpcAction::ResponseSend {
+                                    peer_id,
+                                    id,
+                                    response,
                                 });
-                                let response = response.map(P2pRpcResponse::BestTipWithProof);
+                            }
+                            P2pRpcRequest::TransitionKnowledge => {
+                                let best_chain = &store.state().transition_frontier.best_chain;
+                                let hashes = best_chain.iter().map(|block| block.hash.clone());
+                                let response =
+                                    Some(P2pRpcResponse::TransitionKnowledge(hashes.collect()));
                                 store.dispatch(P2pChannelsRpcAction::ResponseSend {
                                     peer_id,
                                     id,
@@ -468,6 +521,48 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
                                     response,
                                 });
                             }
+                            P2pRpcRequest::TransitionChain(hashes) => {
+                                let best_chain = &store.state().transition_frontier.best_chain;
+                                // Every block must be found, same as in the OCaml node.
+                                // Larger requests than ours are refused, so that a peer
+                                // can't make us send the whole chain at once.
+                                let response = Some(hashes)
+                                    .filter(|v| {
+                                        !v.is_empty()
+                                            && v.len() <= BLOCKS_FETCH_BATCH_SIZE
+                                            && v.len() <= best_chain.len()
+                                    })
+                                    .and_then(|hashes| {
+                                        hashes
+                                            .iter()
+                                            .map(|hash| {
+                                                best_chain
+                                                    .iter()
+                                                    .rev()
+                                                    .find(|block| &block.hash == hash)
+                                                    .map(|block| block.block.clone())
+                                            })
+                                            .collect::<Option<List<_>>>()
+                                    })
+                                    .map(P2pRpcResponse::TransitionChain);
+                                store.dispatch(P2pChannelsRpcAction::ResponseSend {
+                                    peer_id,
+                                    id,
+                                    response,
+                                });
+                            }
+                            P2pRpcRequest::TransitionChainProof(hash) => {
+                                let response = store
+                                    .state()
+                                    .transition_frontier
+                                    .transition_chain_proof(&hash)
+                                    .map(P2pRpcResponse::TransitionChainProof);
+                                store.dispatch(P2pChannelsRpcAction::ResponseSend {
+                                    peer_id,
+                                    id,
+                                    response,
+                                });
+                            }
                             P2pRpcRequest::LedgerQuery(..) => {
                                 // async ledger request will be triggered
                                 // by `LedgerReadAction::FindTodos`.
@@ -491,10 +586,30 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
                             }
                             P2pRpcRequest::InitialPeers => {
                                 let p2p = p2p_ready!(store.state().p2p, meta.time());
+                                let my_id = p2p.my_id();
                                 let peers = p2p
                                     .peers
                                     .iter()
-                                    .filter_map(|(_, v)| v.dial_opts.clone())
+                                    .filter_map(|(id, v)| match v.dial_opts.clone() {
+                                        // Peer connected to us over WebRTC may be reachable
+                                        // only through a relay, so offer ourselves as the
+                                        // relay if we can signal to it.
+                                        None
+                                        | Some(P2pConnectionOutgoingInitOpts::WebRTC {
+                                            signaling: SignalingMethod::P2p { .. },
+                                            ..
+                                        }) => {
+                                            let ready = v.status.as_ready()?;
+                                            (!v.is_libp2p() && ready.channels.signaling.is_ready())
+                                                .then_some(P2pConnectionOutgoingInitOpts::WebRTC {
+                                                    peer_id: *id,
+                                                    signaling: SignalingMethod::P2p {
+                                                        relay_peer_id: my_id,
+                                                    },
+                                                })
+                                        }
+                                        opts => opts,
+                                    })
                                     .collect();
                                 let response = Some(P2pRpcResponse::InitialPeers(peers));
 
@@ -518,17 +633,132 @@ pub fn node_p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithM
             P2pPeerAction::Discovered { .. } | P2pPeerAction::Ready { .. } => {
                 action.effects(&meta, store);
             }
-            P2pPeerAction::BestTipUpdate { best_tip, .. } => {
+            P2pPeerAction::BestTipUpdate { peer_id, best_tip } => {
                 store.dispatch(ConsensusAction::BlockReceived {
                     hash: best_tip.hash,
                     block: best_tip.block,
                     chain_proof: None,
+                    sender: peer_id,
                 });
                 store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
                 store.dispatch(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
                 store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
             }
+            P2pPeerAction::RttUpdate { .. }
+            | P2pPeerAction::UsefulBlockReceived { .. }
+            | P2pPeerAction::IceCandidatePairSelected { .. } => {}
         },
-        P2pAction::Network(action) => action.effects(&meta, store),
+        P2pAction::Network(action) => {
+            let request = match &action {
+                P2pNetworkAction::NodeStatus(P2pNetworkNodeStatusAction::RequestReceived {
+                    addr,
+                    peer_id,
+                    stream_id,
+                }) => Some((*addr, *peer_id, *stream_id)),
+                _ => None,
+            };
+            action.effects(&meta, store);
+            if let Some((addr, peer_id, stream_id)) = request {
+                if let Some(status) = node_status(store, meta.time()) {
+                    store.dispatch(P2pNetworkNodeStatusAction::ResponseSend {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        status: Box::new(status),
+                    });
+                }
+            }
+        }
     }
 }
+
+/// Status of the node, served to the peers requesting it with the node status protocol.
+fn node_status<S: Service>(store: &mut Store<S>, time: Timestamp) -> Option<P2pNetworkNodeStatus> {
+    let listen_ip = store
+        .state()
+        .p2p
+        .ready()?
+        .network
+        .scheduler
+        .listeners
+        .iter()
+        .map(|addr| addr.ip())
+        .find(|ip| !ip.is_unspecified());
+    let node_ip_addr = match listen_ip {
+        Some(ip) => ip,
+        None => store
+            .service()
+            .detect_local_ip()
+            .ok()
+            .and_then(|ips| ips.into_iter().find(|ip| !ip.is_loopback()))
+            .unwrap_or(Ipv4Addr::UNSPECIFIED.into()),
+    };
+
+    let state = store.state();
+    let p2p = state.p2p.ready()?;
+    // the status is served even before the frontier is initialized
+    let best_tip = state.transition_frontier.best_tip();
+    let protocol_state_hash = best_tip
+        .map(|block| block.hash().clone())
+        .or_else(|| {
+            let genesis = state
+                .transition_frontier
+                .genesis
+                .block_with_real_or_dummy_proof()?;
+            Some(genesis.hash().clone())
+        })
+        .unwrap_or_else(StateHash::zero);
+
+    let sync_status = match &state.transition_frontier.sync {
+        TransitionFrontierSyncState::Synced { .. } => P2pNetworkNodeSyncStatus::Synced,
+        TransitionFrontierSyncState::Idle if state.p2p.ready_peers_iter().next().is_none() => {
+            P2pNetworkNodeSyncStatus::Connecting
+        }
+        TransitionFrontierSyncState::Idle => P2pNetworkNodeSyncStatus::Listening,
+        _ if state.transition_frontier.best_chain.is_empty() => P2pNetworkNodeSyncStatus::Bootstrap,
+        _ => P2pNetworkNodeSyncStatus::Catchup,
+    };
+    let peers = state
+        .p2p
+        .ready_peers_iter()
+        .filter_map(|(peer_id, _)| {
+            let opts = p2p.peers.get(peer_id)?.dial_opts.as_ref()?.libp2p_opts()?;
+            Some(P2pNetworkNodeStatusPeer::new(
+                *peer_id,
+                opts.host.to_string(),
+                opts.port,
+            ))
+        })
+        .collect();
+    let k_block_hashes_and_timestamps = state
+        .transition_frontier
+        .best_chain
+        .iter()
+        .filter_map(|block| {
+            let time =
+                OffsetDateTime::from_unix_timestamp_nanos(u64::from(block.timestamp()) as i128)
+                    .ok()?;
+            Some((block.hash().clone(), time.format(&Rfc3339).ok()?))
+        })
+        .collect();
+    let uptime = Duration::from_nanos(u64::from(time)).saturating_sub(p2p.config.initial_time);
+
+    Some(P2pNetworkNodeStatus {
+        node_ip_addr,
+        node_peer_id: libp2p_peer_id(p2p.my_id()),
+        sync_status,
+        peers,
+        block_producers: state
+            .block_producer
+            .config()
+            .map(|config| config.pub_key.clone())
+            .into_iter()
+            .collect(),
+        protocol_state_hash,
+        ban_statuses: Vec::new(),
+        k_block_hashes_and_timestamps,
+        git_commit: state.config.build.git.commit_hash.clone(),
+        uptime_minutes: uptime.as_secs() / 60,
+        block_height_opt: best_tip.map(|block| block.height()),
+    })
+}
@@ -5,8 +5,10 @@ use mina_p2p_messages::v2::{
     MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseTransactionStatusStableV2,
     MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2,
     SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TransactionHash,
+    TransactionSnarkWorkTStableV2Proofs,
 };
 use p2p::bootstrap::P2pNetworkKadBootstrapStats;
+use p2p::{P2pNetworkBandwidthStats, P2pNetworkNodeStatus};
 pub use rpc_state::*;
 
 mod rpc_actions;
@@ -26,18 +28,23 @@ pub use openmina_core::requests::{RpcId, RpcIdType};
 
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
-use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
+use mina_p2p_messages::v2::{CurrencyFeeStableV1, LedgerHash, NonZeroCurvePoint};
 use openmina_core::snark::SnarkJobId;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use crate::account::AccountPublicKey;
+use crate::block_producer::payout::{DelegationPayouts, PayoutConfig};
+use crate::block_producer::BlockProductionTimeEstimate;
+use crate::external_snark_worker::remote::{RemoteSnarkWorkerId, RemoteSnarkWorkerStats};
 use crate::external_snark_worker::{
     ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
 };
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::p2p::webrtc::IceCandidatePair;
 use crate::p2p::PeerId;
-use crate::snark_pool::{JobCommitment, JobSummary};
+use crate::snark_pool::{JobCommitment, JobSummary, SnarkPoolFeeStats};
 use crate::stats::actions::{ActionStatsForBlock, ActionStatsSnapshot};
 use crate::stats::block_producer::{BlockProductionAttempt, BlockProductionAttemptWonSlot};
 use crate::stats::sync::SyncStatsSnapshot;
@@ -55,16 +62,30 @@ pub enum RpcRequest {
     P2pConnectionOutgoing(P2pConnectionOutgoingInitOpts),
     P2pConnectionIncoming(P2pConnectionIncomingInitOpts),
     ScanStateSummaryGet(RpcScanStateSummaryGetQuery),
+    EpochLedgerAccountsGet(RpcEpochLedgerAccountsGetQuery),
+    DelegationPayoutsGet(RpcDelegationPayoutsGetQuery),
     SnarkPoolGet,
-    SnarkPoolJobGet { job_id: SnarkJobId },
+    SnarkPoolJobGet {
+        job_id: SnarkJobId,
+    },
+    SnarkPoolFeeStatsGet,
     SnarkerConfig,
-    SnarkerJobCommit { job_id: SnarkJobId },
-    SnarkerJobSpec { job_id: SnarkJobId },
+    SnarkerJobCommit {
+        job_id: SnarkJobId,
+    },
+    SnarkerJobSpec {
+        job_id: SnarkJobId,
+    },
     SnarkerWorkers,
+    SnarkerRemoteWorker {
+        worker_id: RemoteSnarkWorkerId,
+        request: RpcSnarkerRemoteWorkerRequest,
+    },
     HealthCheck,
     ReadinessCheck,
     DiscoveryRoutingTable,
     DiscoveryBoostrapStats,
+    P2pBandwidthStatsGet,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -86,6 +107,47 @@ pub enum RpcScanStateSummaryGetQuery {
     ForBlockWithHeight(u32),
 }
 
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcEpochLedgerKind {
+    Staking,
+    Next,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcEpochLedgerAccountsGetQuery {
+    pub ledger: RpcEpochLedgerKind,
+    /// Index of the first account (ledger position) to return.
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl RpcEpochLedgerAccountsGetQuery {
+    pub const DEFAULT_LIMIT: usize = 1_000;
+    pub const MAX_LIMIT: usize = 10_000;
+
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(Self::DEFAULT_LIMIT)
+            .clamp(1, Self::MAX_LIMIT)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcDelegationPayoutsGetQuery {
+    pub producer: AccountPublicKey,
+    pub epoch: u32,
+    /// Staking ledger of the epoch. Only needed if it can't be found from
+    /// the won blocks record or the blocks in the transition frontier.
+    pub staking_ledger_hash: Option<LedgerHash>,
+    pub config: PayoutConfig,
+    /// Compute payouts even if not all blocks of the epoch are known,
+    /// instead of failing. The response is then marked as incomplete.
+    #[serde(default)]
+    pub allow_incomplete: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "kind")]
 pub enum ActionStatsResponse {
@@ -110,6 +172,10 @@ pub struct RpcPeerInfo {
     pub connection_status: PeerConnectionStatus,
     pub address: Option<String>,
     pub time: u64,
+    /// Candidate pair of the WebRTC connection, tells if it is relayed.
+    pub ice_candidate_pair: Option<IceCandidatePair>,
+    /// Latest status reported by the peer with the node status protocol.
+    pub node_status: Option<P2pNetworkNodeStatus>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -184,6 +250,26 @@ pub enum RpcScanStateSummaryScanStateJobStatus {
     Done,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcEpochLedgerAccounts {
+    pub epoch: u32,
+    pub ledger_hash: LedgerHash,
+    /// Total number of accounts in the ledger.
+    pub total: usize,
+    /// Offset to query next, `None` if this is the last page.
+    pub next_offset: Option<usize>,
+    /// Default token accounts, in ledger order.
+    pub accounts: Vec<RpcEpochLedgerAccount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcEpochLedgerAccount {
+    pub public_key: AccountPublicKey,
+    pub delegate: Option<AccountPublicKey>,
+    pub balance: u64,
+    pub nonce: u32,
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct RpcSnarkPoolJobSummary {
     pub time: Timestamp,
@@ -232,6 +318,45 @@ pub enum RpcSnarkerJobSpecResponse {
     JobNotFound,
 }
 
+/// Request of the remote snark worker to the coordinator.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcSnarkerRemoteWorkerRequest {
+    Register,
+    /// Keeps the lease alive.
+    Heartbeat,
+    Lease,
+    Result {
+        proofs: TransactionSnarkWorkTStableV2Proofs,
+    },
+    Error {
+        error: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcSnarkerRemoteWorkerResponse {
+    Ok,
+    Lease {
+        job_id: SnarkJobId,
+        spec: SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    },
+    NoJob,
+    /// Lease expired or the job is no longer needed, the worker should
+    /// drop the job and lease a new one.
+    LeaseLost,
+    /// Worker needs to register first.
+    UnknownWorker,
+    /// Maximum number of the remote workers is registered.
+    TooManyWorkers,
+    /// Node doesn't coordinate remote workers.
+    Disabled,
+    SpecError {
+        error: SnarkWorkSpecError,
+    },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcMessageProgressResponse {
     pub messages_stats: BTreeMap<PeerId, MessagesStats>,
@@ -273,8 +398,11 @@ pub type RpcBlockProducerStatsGetResponse = Option<RpcBlockProducerStats>;
 pub type RpcPeersGetResponse = Vec<RpcPeerInfo>;
 pub type RpcP2pConnectionOutgoingResponse = Result<(), String>;
 pub type RpcScanStateSummaryGetResponse = Option<RpcScanStateSummary>;
+pub type RpcEpochLedgerAccountsGetResponse = Option<RpcEpochLedgerAccounts>;
+pub type RpcDelegationPayoutsGetResponse = Result<DelegationPayouts, String>;
 pub type RpcSnarkPoolGetResponse = Vec<RpcSnarkPoolJobSummary>;
 pub type RpcSnarkPoolJobGetResponse = Option<RpcSnarkPoolJobFull>;
+pub type RpcSnarkPoolFeeStatsGetResponse = SnarkPoolFeeStats;
 pub type RpcSnarkerConfigGetResponse = Option<RpcSnarkerConfig>;
 
 #[derive(Serialize, Debug, Clone)]
@@ -318,6 +446,7 @@ pub struct RpcBlockProducerStats {
     pub epoch_end: Option<u32>,
     pub attempts: Vec<BlockProductionAttempt>,
     pub future_won_slots: Vec<BlockProductionAttemptWonSlot>,
+    pub production_time_estimate: Option<BlockProductionTimeEstimate>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -331,6 +460,8 @@ pub struct RpcSnarkWorker {
     pub time: Option<Timestamp>,
     pub id: Option<String>,
     pub status: RpcSnarkWorkerStatus,
+    /// Only for remote workers.
+    pub stats: Option<RemoteSnarkWorkerStats>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -394,6 +525,7 @@ pub type RpcReadinessCheckResponse = Result<(), String>;
 
 pub type RpcDiscoveryRoutingTableResponse = Option<discovery::RpcDiscoveryRoutingTable>;
 pub type RpcDiscoveryBoostrapStatsResponse = Option<P2pNetworkKadBootstrapStats>;
+pub type RpcP2pBandwidthStatsGetResponse = Option<P2pNetworkBandwidthStats>;
 
 pub mod discovery {
     use p2p::{
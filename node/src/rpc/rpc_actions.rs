@@ -1,15 +1,19 @@
+use mina_p2p_messages::v2::LedgerHash;
 use openmina_core::block::ArcBlockWithHash;
 use openmina_core::snark::SnarkJobId;
 use serde::{Deserialize, Serialize};
 
+use crate::block_producer::payout::PayoutDelegator;
+use crate::external_snark_worker::remote::RemoteSnarkWorkerId;
 use crate::external_snark_worker::SnarkWorkId;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
 use crate::p2p::connection::P2pConnectionResponse;
 
 use super::{
-    ActionStatsQuery, RpcId, RpcScanStateSummaryGetQuery, RpcScanStateSummaryScanStateJob,
-    SyncStatsQuery,
+    ActionStatsQuery, RpcDelegationPayoutsGetQuery, RpcEpochLedgerAccount,
+    RpcEpochLedgerAccountsGetQuery, RpcId, RpcScanStateSummaryGetQuery,
+    RpcScanStateSummaryScanStateJob, RpcSnarkerRemoteWorkerRequest, SyncStatsQuery,
 };
 
 pub type RpcActionWithMeta = redux::ActionWithMeta<RpcAction>;
@@ -97,6 +101,44 @@ pub enum RpcAction {
         scan_state: Vec<Vec<RpcScanStateSummaryScanStateJob>>,
     },
 
+    EpochLedgerAccountsGetInit {
+        rpc_id: RpcId,
+        query: RpcEpochLedgerAccountsGetQuery,
+    },
+    EpochLedgerAccountsLedgerGetInit {
+        rpc_id: RpcId,
+    },
+    EpochLedgerAccountsGetPending {
+        rpc_id: RpcId,
+        epoch: u32,
+        ledger_hash: LedgerHash,
+    },
+    EpochLedgerAccountsGetSuccess {
+        rpc_id: RpcId,
+        /// Total number of accounts in the ledger and the requested page,
+        /// `None` if the ledger isn't available.
+        accounts: Option<(usize, Vec<RpcEpochLedgerAccount>)>,
+    },
+
+    DelegationPayoutsGetInit {
+        rpc_id: RpcId,
+        query: RpcDelegationPayoutsGetQuery,
+    },
+    DelegationPayoutsLedgerGetInit {
+        rpc_id: RpcId,
+    },
+    DelegationPayoutsGetPending {
+        rpc_id: RpcId,
+        epoch: u32,
+        ledger_hash: LedgerHash,
+    },
+    DelegationPayoutsGetSuccess {
+        rpc_id: RpcId,
+        /// Delegators of the producer in the staking ledger, `None` if the
+        /// ledger isn't available.
+        delegators: Option<Vec<PayoutDelegator>>,
+    },
+
     SnarkPoolAvailableJobsGet {
         rpc_id: RpcId,
     },
@@ -104,6 +146,9 @@ pub enum RpcAction {
         job_id: SnarkWorkId,
         rpc_id: RpcId,
     },
+    SnarkPoolFeeStatsGet {
+        rpc_id: RpcId,
+    },
 
     SnarkerConfigGet {
         rpc_id: RpcId,
@@ -120,6 +165,11 @@ pub enum RpcAction {
     SnarkerWorkersGet {
         rpc_id: RpcId,
     },
+    SnarkerRemoteWorker {
+        rpc_id: RpcId,
+        worker_id: RemoteSnarkWorkerId,
+        request: RpcSnarkerRemoteWorkerRequest,
+    },
 
     HealthCheck {
         rpc_id: RpcId,
@@ -134,6 +184,9 @@ pub enum RpcAction {
     DiscoveryBoostrapStats {
         rpc_id: RpcId,
     },
+    P2pBandwidthStatsGet {
+        rpc_id: RpcId,
+    },
 
     Finish {
         rpc_id: RpcId,
@@ -207,16 +260,51 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
                 .requests
                 .get(rpc_id)
                 .map_or(false, |v| v.status.is_pending()),
+            RpcAction::EpochLedgerAccountsGetInit { .. } => true,
+            RpcAction::EpochLedgerAccountsLedgerGetInit { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init()),
+            RpcAction::EpochLedgerAccountsGetPending { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init()),
+            RpcAction::EpochLedgerAccountsGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init() || v.status.is_pending()),
+            RpcAction::DelegationPayoutsGetInit { .. } => true,
+            RpcAction::DelegationPayoutsLedgerGetInit { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init()),
+            RpcAction::DelegationPayoutsGetPending { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init()),
+            RpcAction::DelegationPayoutsGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init() || v.status.is_pending()),
             RpcAction::SnarkPoolAvailableJobsGet { .. } => true,
             RpcAction::SnarkPoolJobGet { .. } => true,
+            RpcAction::SnarkPoolFeeStatsGet { .. } => true,
             RpcAction::SnarkerConfigGet { .. } => true,
             RpcAction::SnarkerJobCommit { .. } => true,
             RpcAction::SnarkerJobSpec { .. } => true,
             RpcAction::SnarkerWorkersGet { .. } => true,
+            RpcAction::SnarkerRemoteWorker { .. } => true,
             RpcAction::HealthCheck { .. } => true,
             RpcAction::ReadinessCheck { .. } => true,
             RpcAction::DiscoveryRoutingTable { .. } => true,
             RpcAction::DiscoveryBoostrapStats { .. } => true,
+            RpcAction::P2pBandwidthStatsGet { .. } => true,
             RpcAction::Finish { rpc_id } => state
                 .rpc
                 .requests
@@ -1,30 +1,45 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use mina_p2p_messages::rpc_kernel::QueryHeader;
-use mina_p2p_messages::v2::MinaBaseTransactionStatusStableV2;
+use mina_p2p_messages::v2::{
+    MinaBaseTransactionStatusStableV2, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0,
+};
 use openmina_core::block::ArcBlockWithHash;
 
-use crate::block_producer::BlockProducerWonSlot;
-use crate::external_snark_worker::available_job_to_snark_worker_spec;
+use crate::account::AccountPublicKey;
+use crate::block_producer::payout::{
+    best_chain_covers_epoch, delegation_payouts, PayoutBlock, PayoutError,
+};
+use crate::block_producer::{BlockProducerService, BlockProducerWonSlot};
+use crate::external_snark_worker::remote::{
+    external_snark_worker_remote_lease, ExternalSnarkWorkerRemoteAction, RemoteSnarkWorkerId,
+};
+use crate::external_snark_worker::{available_job_to_snark_worker_spec, SnarkWorkSpecError};
 use crate::ledger::read::{LedgerReadAction, LedgerReadRequest};
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
 use crate::p2p::connection::P2pConnectionResponse;
 use crate::rpc::{PeerConnectionStatus, RpcPeerInfo};
-use crate::snark_pool::SnarkPoolAction;
+use crate::snark_pool::{JobState, SnarkPoolAction};
+use crate::stats::block_producer::BlockProductionStatus;
 use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerState;
 use crate::transition_frontier::sync::TransitionFrontierSyncState;
-use crate::{p2p_ready, Service, Store};
+use crate::transition_frontier::TransitionFrontierState;
+use crate::{p2p_ready, Service, SnarkerConfig, Store};
 
 use super::{
     ActionStatsQuery, ActionStatsResponse, CurrentMessageProgress, MessagesStats, RpcAction,
-    RpcActionWithMeta, RpcBlockProducerStats, RpcMessageProgressResponse, RpcNodeStatus,
-    RpcNodeStatusTransitionFrontier, RpcNodeStatusTransitionFrontierBlockSummary,
-    RpcNodeStatusTransitionFrontierSync, RpcRequest, RpcRequestExtraData, RpcScanStateSummary,
-    RpcScanStateSummaryBlock, RpcScanStateSummaryBlockTransaction,
-    RpcScanStateSummaryBlockTransactionKind, RpcScanStateSummaryGetQuery,
-    RpcScanStateSummaryScanStateJob, RpcSnarkPoolJobFull, RpcSnarkPoolJobSnarkWork,
-    RpcSnarkPoolJobSummary, RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse,
+    RpcActionWithMeta, RpcBlockProducerStats, RpcEpochLedgerAccounts, RpcEpochLedgerKind,
+    RpcMessageProgressResponse, RpcNodeStatus, RpcNodeStatusTransitionFrontier,
+    RpcNodeStatusTransitionFrontierBlockSummary, RpcNodeStatusTransitionFrontierSync, RpcRequest,
+    RpcRequestExtraData, RpcScanStateSummary, RpcScanStateSummaryBlock,
+    RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryBlockTransactionKind,
+    RpcScanStateSummaryGetQuery, RpcScanStateSummaryScanStateJob, RpcSnarkPoolJobFull,
+    RpcSnarkPoolJobSnarkWork, RpcSnarkPoolJobSummary, RpcSnarkWorker, RpcSnarkerJobCommitResponse,
+    RpcSnarkerJobSpecResponse, RpcSnarkerRemoteWorkerRequest, RpcSnarkerRemoteWorkerResponse,
 };
 
 macro_rules! respond_or_log {
@@ -115,6 +130,8 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
 
                 let stats = store.service.stats()?;
                 let attempts = stats.block_producer().collect_attempts();
+                let production_time_estimate =
+                    stats.block_producer().production_time_estimate().cloned();
                 let future_slot = attempts.last().map_or(0, |v| v.won_slot.global_slot + 1);
 
                 let cur_global_slot = state.cur_global_slot();
@@ -138,6 +155,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                             (&won_slot).into()
                         })
                         .collect(),
+                    production_time_estimate,
                 })
             });
             let _ = store.service.respond_block_producer_stats_get(rpc_id, resp);
@@ -420,6 +438,168 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             });
             let _ = store.service.respond_scan_state_summary_get(rpc_id, res);
         }
+        RpcAction::EpochLedgerAccountsGetInit { rpc_id, .. } => {
+            store.dispatch(RpcAction::EpochLedgerAccountsLedgerGetInit { rpc_id });
+        }
+        RpcAction::EpochLedgerAccountsLedgerGetInit { rpc_id } => {
+            let Some(query) = None.or_else(|| {
+                let req = store.state().rpc.requests.get(&rpc_id)?;
+                match &req.req {
+                    RpcRequest::EpochLedgerAccountsGet(query) => Some(query.clone()),
+                    _ => None,
+                }
+            }) else {
+                return;
+            };
+            let Some(best_tip) = store.state().transition_frontier.best_tip() else {
+                store.dispatch(RpcAction::EpochLedgerAccountsGetSuccess {
+                    rpc_id,
+                    accounts: None,
+                });
+                return;
+            };
+            let staking_epoch = best_tip.consensus_state().epoch_count.as_u32();
+            let (epoch, ledger_hash) = match query.ledger {
+                RpcEpochLedgerKind::Staking => {
+                    (staking_epoch, best_tip.staking_epoch_ledger_hash().clone())
+                }
+                RpcEpochLedgerKind::Next => {
+                    (staking_epoch + 1, best_tip.next_epoch_ledger_hash().clone())
+                }
+            };
+            if store.dispatch(LedgerReadAction::Init {
+                request: LedgerReadRequest::EpochLedgerAccounts(
+                    ledger_hash.clone(),
+                    query.offset,
+                    query.limit(),
+                ),
+            }) {
+                store.dispatch(RpcAction::EpochLedgerAccountsGetPending {
+                    rpc_id,
+                    epoch,
+                    ledger_hash,
+                });
+            }
+        }
+        RpcAction::EpochLedgerAccountsGetPending { .. } => {}
+        RpcAction::EpochLedgerAccountsGetSuccess { rpc_id, accounts } => {
+            let req = store.state().rpc.requests.get(&rpc_id);
+            let res = None.or_else(|| {
+                let (epoch, ledger_hash) = match &req?.data {
+                    RpcRequestExtraData::EpochLedger { epoch, ledger_hash } => {
+                        (*epoch, ledger_hash.clone())
+                    }
+                    _ => return None,
+                };
+                let query = match &req?.req {
+                    RpcRequest::EpochLedgerAccountsGet(query) => query,
+                    _ => return None,
+                };
+                let (total, accounts) = accounts?;
+                let next_offset = query.offset.saturating_add(query.limit());
+                Some(RpcEpochLedgerAccounts {
+                    epoch,
+                    ledger_hash,
+                    total,
+                    next_offset: (next_offset < total).then_some(next_offset),
+                    accounts,
+                })
+            });
+            respond_or_log!(
+                store.service.respond_epoch_ledger_accounts_get(rpc_id, res),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcAction::DelegationPayoutsGetInit { rpc_id, .. } => {
+            store.dispatch(RpcAction::DelegationPayoutsLedgerGetInit { rpc_id });
+        }
+        RpcAction::DelegationPayoutsLedgerGetInit { rpc_id } => {
+            let Some(query) = None.or_else(|| {
+                let req = store.state().rpc.requests.get(&rpc_id)?;
+                match &req.req {
+                    RpcRequest::DelegationPayoutsGet(query) => Some(query.clone()),
+                    _ => None,
+                }
+            }) else {
+                return;
+            };
+            let ledger_hash = query
+                .staking_ledger_hash
+                .clone()
+                .or_else(|| {
+                    let record = store.service.won_blocks(query.epoch)?;
+                    Some(record.staking_ledger_hash).filter(|_| record.producer == query.producer)
+                })
+                .or_else(|| {
+                    store
+                        .state()
+                        .transition_frontier
+                        .best_chain
+                        .iter()
+                        .find(|block| block.consensus_state().epoch_count.as_u32() == query.epoch)
+                        .map(|block| block.staking_epoch_ledger_hash().clone())
+                });
+            let Some(ledger_hash) = ledger_hash else {
+                store.dispatch(RpcAction::DelegationPayoutsGetSuccess {
+                    rpc_id,
+                    delegators: None,
+                });
+                return;
+            };
+            if store.dispatch(LedgerReadAction::Init {
+                request: LedgerReadRequest::PayoutDelegators(
+                    ledger_hash.clone(),
+                    query.producer.clone(),
+                ),
+            }) {
+                store.dispatch(RpcAction::DelegationPayoutsGetPending {
+                    rpc_id,
+                    epoch: query.epoch,
+                    ledger_hash,
+                });
+            }
+        }
+        RpcAction::DelegationPayoutsGetPending { .. } => {}
+        RpcAction::DelegationPayoutsGetSuccess { rpc_id, delegators } => {
+            let Some(query) = None.or_else(|| {
+                let req = store.state().rpc.requests.get(&rpc_id)?;
+                match &req.req {
+                    RpcRequest::DelegationPayoutsGet(query) => Some(query.clone()),
+                    _ => None,
+                }
+            }) else {
+                return;
+            };
+            let res = match delegators {
+                None => Err(format!(
+                    "staking ledger of epoch {} is not available",
+                    query.epoch
+                )),
+                Some(delegators) => {
+                    let (blocks, complete) =
+                        delegation_payout_blocks(store, &query.producer, query.epoch);
+                    if !complete && !query.allow_incomplete {
+                        Err(PayoutError::IncompleteEpoch(query.epoch).to_string())
+                    } else {
+                        delegation_payouts(
+                            query.producer,
+                            query.epoch,
+                            blocks,
+                            complete,
+                            &delegators,
+                            &query.config,
+                        )
+                        .map_err(|err| err.to_string())
+                    }
+                }
+            };
+            respond_or_log!(
+                store.service.respond_delegation_payouts_get(rpc_id, res),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
         RpcAction::SnarkPoolAvailableJobsGet { rpc_id } => {
             let resp = store
                 .state()
@@ -460,6 +640,15 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             });
             let _ = store.service().respond_snark_pool_job_get(rpc_id, resp);
         }
+        RpcAction::SnarkPoolFeeStatsGet { rpc_id } => {
+            let response = store.state().snark_pool.fee_stats();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_snark_pool_fee_stats_get(rpc_id, response),
+                meta.time()
+            );
+        }
         RpcAction::SnarkerConfigGet { rpc_id } => {
             let config =
                 store
@@ -508,26 +697,13 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 }
                 return;
             };
-            let input = available_job_to_snark_worker_spec(
-                job.job.clone(),
-                &store.state().transition_frontier,
-            );
             // TODO(binier): maybe don't require snarker to be enabled here.
             let Some(config) = store.state.get().config.snarker.as_ref() else {
                 return;
             };
-            let public_key = config.public_key.clone().into();
-            let fee = config.fee.clone();
-            let input = match input {
-                Ok(instances) => RpcSnarkerJobSpecResponse::Ok(
-                    mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
-                        mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0 {
-                            instances,
-                            fee,
-                        },
-                        public_key,
-                    )))
-                ),
+            let input = match snark_worker_job_spec(job, config, &store.state().transition_frontier)
+            {
+                Ok(spec) => RpcSnarkerJobSpecResponse::Ok(spec),
                 Err(err) => RpcSnarkerJobSpecResponse::Err(err),
             };
 
@@ -535,12 +711,26 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             let _ = store.service().respond_snarker_job_spec(rpc_id, input);
         }
         RpcAction::SnarkerWorkersGet { rpc_id } => {
-            let the_only = store.state().external_snark_worker.0.clone();
+            let workers = &store.state().external_snark_worker;
+            let the_only = RpcSnarkWorker::from(workers.0.clone());
+            let remote = workers.remote().iter().map(RpcSnarkWorker::from);
+            let response = std::iter::once(the_only).chain(remote).collect();
 
             // TODO: handle potential errors
-            let _ = store
-                .service()
-                .respond_snarker_workers(rpc_id, vec![the_only.into()]);
+            let _ = store.service().respond_snarker_workers(rpc_id, response);
+        }
+        RpcAction::SnarkerRemoteWorker {
+            rpc_id,
+            worker_id,
+            request,
+        } => {
+            let response = snarker_remote_worker_request(store, worker_id, request, meta.time());
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_snarker_remote_worker(rpc_id, response),
+                meta.time()
+            );
         }
         RpcAction::HealthCheck { rpc_id } => {
             let some_peers = store
@@ -629,6 +819,19 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 meta.time()
             );
         }
+        RpcAction::P2pBandwidthStatsGet { rpc_id } => {
+            let response = store
+                .state()
+                .p2p
+                .ready()
+                .map(|p2p| p2p.network.bandwidth.stats.clone());
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_p2p_bandwidth_stats_get(rpc_id, response),
+                meta.time()
+            );
+        }
         RpcAction::Finish { .. } => {}
     }
 }
@@ -664,8 +867,176 @@ fn collect_rpc_peers_info(state: &crate::State) -> Vec<RpcPeerInfo> {
                     best_tip_global_slot: best_tip.map(|bt| bt.global_slot_since_genesis()),
                     best_tip_timestamp: best_tip.map(|bt| bt.timestamp().into()),
                     time,
+                    ice_candidate_pair: state.status.as_ready().and_then(|r| r.ice_candidate_pair),
+                    node_status: p2p
+                        .network
+                        .scheduler
+                        .node_status_state
+                        .peer_statuses
+                        .get(peer_id)
+                        .map(|received| received.status.clone()),
                 }
             })
             .collect()
     })
 }
+
+/// Blocks won by the `producer` in the `epoch`, and whether those are all
+/// of them.
+///
+/// Own blocks are taken from the won blocks record, which is complete once
+/// the node followed the whole epoch. Without a record, blocks are taken
+/// from the transition frontier and the block producer stats, which are
+/// only complete while the best chain covers the whole epoch.
+fn delegation_payout_blocks<S: Service>(
+    store: &mut Store<S>,
+    producer: &AccountPublicKey,
+    epoch: u32,
+) -> (Vec<PayoutBlock>, bool) {
+    if let Some(record) = store
+        .service
+        .won_blocks(epoch)
+        .filter(|record| record.producer == *producer)
+    {
+        let complete = record.is_complete();
+        return (record.blocks, complete);
+    }
+
+    let state = store.state.get();
+    let complete = best_chain_covers_epoch(&state.transition_frontier.best_chain, epoch);
+    let mut blocks = state
+        .transition_frontier
+        .best_chain
+        .iter()
+        .filter(|block| block.consensus_state().epoch_count.as_u32() == epoch)
+        .filter(|block| {
+            AccountPublicKey::from(block.consensus_state().block_creator.clone()) == *producer
+        })
+        .map(|block| (block.hash().clone(), PayoutBlock::from(block)))
+        .collect::<BTreeMap<_, _>>();
+
+    let is_own_producer = state.block_producer.config().map_or(false, |config| {
+        AccountPublicKey::from(config.pub_key.clone()) == *producer
+    });
+    if let Some(stats) = store.service.stats().filter(|_| is_own_producer) {
+        let canonical = stats
+            .block_producer()
+            .collect_attempts()
+            .into_iter()
+            .filter(|attempt| attempt.won_slot.epoch == epoch)
+            .filter(|attempt| matches!(attempt.status, BlockProductionStatus::Canonical { .. }))
+            .filter_map(|attempt| attempt.block);
+        for block in canonical {
+            blocks
+                .entry(block.hash.clone())
+                .or_insert_with(|| PayoutBlock::from(&block));
+        }
+    }
+    let mut blocks = blocks.into_values().collect::<Vec<_>>();
+    blocks.sort_by_key(|block| block.height);
+    (blocks, complete)
+}
+
+fn snark_worker_job_spec(
+    job: &JobState,
+    config: &SnarkerConfig,
+    transition_frontier: &TransitionFrontierState,
+) -> Result<SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, SnarkWorkSpecError> {
+    let instances = available_job_to_snark_worker_spec(job.job.clone(), transition_frontier)?;
+    Ok(SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0 {
+            instances,
+            fee: config.fee.clone(),
+        },
+        config.public_key.clone().into(),
+    ))))
+}
+
+fn snarker_remote_worker_request<S: Service>(
+    store: &mut Store<S>,
+    worker_id: RemoteSnarkWorkerId,
+    request: RpcSnarkerRemoteWorkerRequest,
+    time: redux::Timestamp,
+) -> RpcSnarkerRemoteWorkerResponse {
+    let state = store.state();
+    let Some(config) = state.config.snarker.as_ref().filter(|c| c.coordinator) else {
+        return RpcSnarkerRemoteWorkerResponse::Disabled;
+    };
+    let lease_job_id = match state.external_snark_worker.remote().get(&worker_id) {
+        Some(worker) => worker.lease.as_ref().map(|lease| lease.job_id.clone()),
+        None if matches!(request, RpcSnarkerRemoteWorkerRequest::Register) => None,
+        None => return RpcSnarkerRemoteWorkerResponse::UnknownWorker,
+    };
+
+    match request {
+        RpcSnarkerRemoteWorkerRequest::Register => {
+            if store.dispatch(ExternalSnarkWorkerRemoteAction::Register { worker_id }) {
+                RpcSnarkerRemoteWorkerResponse::Ok
+            } else {
+                RpcSnarkerRemoteWorkerResponse::TooManyWorkers
+            }
+        }
+        RpcSnarkerRemoteWorkerRequest::Heartbeat => {
+            // job is still needed if no snark was received for it, and no
+            // other snarker outbid our commitment (which might have timed out).
+            let is_needed = lease_job_id.map_or(true, |job_id| {
+                state.snark_pool.get(&job_id).map_or(false, |job| {
+                    job.snark.is_none()
+                        && (job.commitment.is_none()
+                            || job.is_committed_by(config.public_key.as_ref()))
+                })
+            });
+            if is_needed {
+                store.dispatch(ExternalSnarkWorkerRemoteAction::Heartbeat { worker_id });
+                RpcSnarkerRemoteWorkerResponse::Ok
+            } else {
+                store.dispatch(ExternalSnarkWorkerRemoteAction::LeaseRevoke { worker_id });
+                RpcSnarkerRemoteWorkerResponse::LeaseLost
+            }
+        }
+        RpcSnarkerRemoteWorkerRequest::Lease => {
+            // the same job is returned if the worker already has a lease.
+            if lease_job_id.is_none()
+                && !external_snark_worker_remote_lease(store, worker_id.clone(), time)
+            {
+                // worker polling for a job is alive.
+                store.dispatch(ExternalSnarkWorkerRemoteAction::Heartbeat { worker_id });
+                return RpcSnarkerRemoteWorkerResponse::NoJob;
+            }
+            let state = store.state();
+            let Some(lease) = state.external_snark_worker.remote().lease(&worker_id) else {
+                return RpcSnarkerRemoteWorkerResponse::NoJob;
+            };
+            let job_id = lease.job_id.clone();
+            let Some((job, config)) = state
+                .snark_pool
+                .get(&job_id)
+                .zip(state.config.snarker.as_ref())
+            else {
+                return RpcSnarkerRemoteWorkerResponse::NoJob;
+            };
+            match snark_worker_job_spec(job, config, &state.transition_frontier) {
+                Ok(spec) => RpcSnarkerRemoteWorkerResponse::Lease { job_id, spec },
+                Err(error) => {
+                    store.dispatch(ExternalSnarkWorkerRemoteAction::LeaseRevoke { worker_id });
+                    RpcSnarkerRemoteWorkerResponse::SpecError { error }
+                }
+            }
+        }
+        RpcSnarkerRemoteWorkerRequest::Result { proofs } => {
+            let result = Arc::new(proofs);
+            if store.dispatch(ExternalSnarkWorkerRemoteAction::WorkResult { worker_id, result }) {
+                RpcSnarkerRemoteWorkerResponse::Ok
+            } else {
+                RpcSnarkerRemoteWorkerResponse::LeaseLost
+            }
+        }
+        RpcSnarkerRemoteWorkerRequest::Error { error } => {
+            if store.dispatch(ExternalSnarkWorkerRemoteAction::WorkError { worker_id, error }) {
+                RpcSnarkerRemoteWorkerResponse::Ok
+            } else {
+                RpcSnarkerRemoteWorkerResponse::LeaseLost
+            }
+        }
+    }
+}
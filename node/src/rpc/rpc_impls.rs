@@ -1,3 +1,4 @@
+use crate::external_snark_worker::remote::{RemoteSnarkWorker, RemoteSnarkWorkerId};
 use crate::external_snark_worker::{ExternalSnarkWorker, ExternalSnarkWorkerState};
 
 use super::{RpcSnarkWorker, RpcSnarkWorkerStatus};
@@ -8,6 +9,28 @@ impl From<ExternalSnarkWorker> for RpcSnarkWorker {
             time: Some(source.timestamp),
             id: Some("single".into()),
             status: source.state.into(),
+            stats: None,
+        }
+    }
+}
+
+impl From<(&RemoteSnarkWorkerId, &RemoteSnarkWorker)> for RpcSnarkWorker {
+    fn from((id, source): (&RemoteSnarkWorkerId, &RemoteSnarkWorker)) -> Self {
+        let status = match (&source.lease, &source.result) {
+            (Some(lease), _) => RpcSnarkWorkerStatus::Working {
+                job_id: lease.job_id.clone(),
+                summary: lease.summary.clone(),
+            },
+            (None, Some(result)) => RpcSnarkWorkerStatus::WorkReady {
+                job_id: result.lease.job_id.clone(),
+            },
+            (None, None) => RpcSnarkWorkerStatus::Idle,
+        };
+        Self {
+            time: Some(source.last_heartbeat_t),
+            id: Some(id.clone()),
+            status,
+            stats: Some(source.stats.clone()),
         }
     }
 }
@@ -95,16 +95,77 @@ impl RpcState {
                 };
                 rpc.status = RpcRequestStatus::Success { time: meta.time() };
             }
+            RpcAction::EpochLedgerAccountsGetInit { rpc_id, query } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::EpochLedgerAccountsGet(query.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                self.requests.insert(*rpc_id, rpc_state);
+            }
+            RpcAction::EpochLedgerAccountsLedgerGetInit { .. } => {}
+            RpcAction::EpochLedgerAccountsGetPending {
+                rpc_id,
+                epoch,
+                ledger_hash,
+            } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+                rpc.data = RpcRequestExtraData::EpochLedger {
+                    epoch: *epoch,
+                    ledger_hash: ledger_hash.clone(),
+                };
+            }
+            RpcAction::EpochLedgerAccountsGetSuccess { rpc_id, .. } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+            }
+            RpcAction::DelegationPayoutsGetInit { rpc_id, query } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::DelegationPayoutsGet(query.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                self.requests.insert(*rpc_id, rpc_state);
+            }
+            RpcAction::DelegationPayoutsLedgerGetInit { .. } => {}
+            RpcAction::DelegationPayoutsGetPending {
+                rpc_id,
+                epoch,
+                ledger_hash,
+            } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+                rpc.data = RpcRequestExtraData::EpochLedger {
+                    epoch: *epoch,
+                    ledger_hash: ledger_hash.clone(),
+                };
+            }
+            RpcAction::DelegationPayoutsGetSuccess { rpc_id, .. } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+            }
             RpcAction::SnarkPoolAvailableJobsGet { .. } => {}
             RpcAction::SnarkPoolJobGet { .. } => {}
+            RpcAction::SnarkPoolFeeStatsGet { .. } => {}
             RpcAction::SnarkerConfigGet { .. } => {}
             RpcAction::SnarkerJobCommit { .. } => {}
             RpcAction::SnarkerJobSpec { .. } => {}
             RpcAction::SnarkerWorkersGet { .. } => {}
+            RpcAction::SnarkerRemoteWorker { .. } => {}
             RpcAction::HealthCheck { .. } => {}
             RpcAction::ReadinessCheck { .. } => {}
             RpcAction::DiscoveryRoutingTable { .. } => {}
             RpcAction::DiscoveryBoostrapStats { .. } => {}
+            RpcAction::P2pBandwidthStatsGet { .. } => {}
             RpcAction::Finish { rpc_id } => {
                 self.requests.remove(rpc_id);
             }
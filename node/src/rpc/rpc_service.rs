@@ -5,11 +5,13 @@ use crate::p2p::connection::P2pConnectionResponse;
 use crate::State;
 
 use super::{
-    RpcActionStatsGetResponse, RpcBlockProducerStatsGetResponse, RpcDiscoveryBoostrapStatsResponse,
-    RpcDiscoveryRoutingTableResponse, RpcHealthCheckResponse, RpcId, RpcMessageProgressResponse,
-    RpcP2pConnectionOutgoingResponse, RpcPeersGetResponse, RpcReadinessCheckResponse,
-    RpcScanStateSummaryGetResponse, RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse,
-    RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse,
+    RpcActionStatsGetResponse, RpcBlockProducerStatsGetResponse, RpcDelegationPayoutsGetResponse,
+    RpcDiscoveryBoostrapStatsResponse, RpcDiscoveryRoutingTableResponse,
+    RpcEpochLedgerAccountsGetResponse, RpcHealthCheckResponse, RpcId, RpcMessageProgressResponse,
+    RpcP2pBandwidthStatsGetResponse, RpcP2pConnectionOutgoingResponse, RpcPeersGetResponse,
+    RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse, RpcSnarkPoolFeeStatsGetResponse,
+    RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse, RpcSnarkerJobCommitResponse,
+    RpcSnarkerJobSpecResponse, RpcSnarkerRemoteWorkerResponse, RpcSnarkerWorkersResponse,
     RpcStatusGetResponse, RpcSyncStatsGetResponse,
 };
 
@@ -93,6 +95,16 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcScanStateSummaryGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_epoch_ledger_accounts_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcEpochLedgerAccountsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_delegation_payouts_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcDelegationPayoutsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_snark_pool_get(
         &mut self,
         rpc_id: RpcId,
@@ -103,6 +115,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcSnarkPoolJobGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snark_pool_fee_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkPoolFeeStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_snarker_config_get(
         &mut self,
         rpc_id: RpcId,
@@ -123,6 +140,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcSnarkerWorkersResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snarker_remote_worker(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerRemoteWorkerResponse,
+    ) -> Result<(), RespondError>;
     fn respond_health_check(
         &mut self,
         rpc_id: RpcId,
@@ -138,6 +160,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcDiscoveryBoostrapStatsResponse,
     ) -> Result<(), RespondError>;
+    fn respond_p2p_bandwidth_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pBandwidthStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_readiness_check(
         &mut self,
         rpc_id: RpcId,
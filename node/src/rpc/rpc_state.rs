@@ -4,7 +4,7 @@ use mina_p2p_messages::v2;
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
-use super::{RpcId, RpcRequest};
+use super::{RpcDelegationPayoutsGetQuery, RpcEpochLedgerAccountsGetQuery, RpcId, RpcRequest};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcRequestState {
@@ -35,6 +35,10 @@ pub enum RpcRequestStatus {
 pub enum RpcRequestExtraData {
     None,
     FullBlockOpt(Option<ArcBlockWithHash>),
+    EpochLedger {
+        epoch: u32,
+        ledger_hash: v2::LedgerHash,
+    },
 }
 
 impl RpcRequestStatus {
@@ -75,6 +79,54 @@ impl RpcState {
                 Some((*id, block.staged_ledger_hash(), &req.status))
             })
     }
+
+    /// Epoch ledger accounts rpcs, with the ledger hash they were resolved
+    /// to once pending.
+    pub fn epoch_ledger_accounts_rpc_ids(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            RpcId,
+            &RpcEpochLedgerAccountsGetQuery,
+            Option<&v2::LedgerHash>,
+            &RpcRequestStatus,
+        ),
+    > {
+        self.requests.iter().filter_map(|(id, req)| {
+            let RpcRequest::EpochLedgerAccountsGet(query) = &req.req else {
+                return None;
+            };
+            let ledger_hash = match &req.data {
+                RpcRequestExtraData::EpochLedger { ledger_hash, .. } => Some(ledger_hash),
+                _ => None,
+            };
+            Some((*id, query, ledger_hash, &req.status))
+        })
+    }
+
+    /// Delegation payouts rpcs, with the staking ledger hash they were
+    /// resolved to once pending.
+    pub fn delegation_payouts_rpc_ids(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            RpcId,
+            &RpcDelegationPayoutsGetQuery,
+            Option<&v2::LedgerHash>,
+            &RpcRequestStatus,
+        ),
+    > {
+        self.requests.iter().filter_map(|(id, req)| {
+            let RpcRequest::DelegationPayoutsGet(query) = &req.req else {
+                return None;
+            };
+            let ledger_hash = match &req.data {
+                RpcRequestExtraData::EpochLedger { ledger_hash, .. } => Some(ledger_hash),
+                _ => None,
+            };
+            Some((*id, query, ledger_hash, &req.status))
+        })
+    }
 }
 
 impl Default for RpcRequestExtraData {
@@ -3,11 +3,14 @@ pub use crate::block_producer::BlockProducerService;
 pub use crate::event_source::EventSourceService;
 use crate::external_snark_worker::ExternalSnarkWorkerService;
 pub use crate::ledger::LedgerService;
+pub use crate::p2p::address_book::P2pAddressBookService;
 pub use crate::p2p::channels::P2pChannelsService;
 pub use crate::p2p::connection::P2pConnectionService;
 pub use crate::p2p::disconnection::P2pDisconnectionService;
+pub use crate::p2p::nat::P2pNatService;
 use crate::p2p::P2pCryptoService;
 use crate::p2p::P2pMioService;
+use crate::p2p::P2pQuicService;
 pub use crate::recorder::Recorder;
 pub use crate::rpc::RpcService;
 pub use crate::snark::block_verify::SnarkBlockVerifyService;
@@ -29,8 +32,11 @@ pub trait Service:
     + P2pDisconnectionService
     + P2pChannelsService
     + P2pMioService
+    + P2pQuicService
     + P2pCryptoService
     + P2pNetworkService
+    + P2pNatService
+    + P2pAddressBookService
     + LedgerService
     + TransitionFrontierGenesisService
     + TransitionFrontierSyncLedgerSnarkedService
@@ -1,4 +1,5 @@
 use crate::consensus::ConsensusAction;
+use crate::external_snark_worker::remote::ExternalSnarkWorkerRemoteAction;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::SnarkPoolAction;
 use crate::{Service, Store};
@@ -30,32 +31,71 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
         SnarkAction::WorkVerify(a) => {
             match a {
                 SnarkWorkVerifyAction::Error { req_id, .. } => {
-                    let req = store.state().snark.work_verify.jobs.get(req_id);
-                    let Some(req) = req else { return };
-                    let sender = req.sender().parse().unwrap();
+                    let state = store.state();
+                    let remote_worker = state
+                        .external_snark_worker
+                        .remote()
+                        .result_verify_worker(&req_id)
+                        .cloned();
+                    if state.snark_pool.is_restore_verify(&req_id) {
+                        // invalid restored snark is just dropped.
+                        store.dispatch(SnarkPoolAction::RestoreVerifyError { verify_id: req_id });
+                    } else if let Some(worker_id) = remote_worker {
+                        store.dispatch(ExternalSnarkWorkerRemoteAction::WorkVerifyError {
+                            worker_id,
+                            verify_id: req_id,
+                        });
+                    } else {
+                        let req = state.snark.work_verify.jobs.get(req_id);
+                        let Some(req) = req else { return };
+                        let sender = req.sender().parse().unwrap();
 
-                    store.dispatch(SnarkPoolCandidateAction::WorkVerifyError {
-                        peer_id: sender,
-                        verify_id: req_id,
-                    });
+                        store.dispatch(SnarkPoolCandidateAction::WorkVerifyError {
+                            peer_id: sender,
+                            verify_id: req_id,
+                        });
+                    }
                 }
                 SnarkWorkVerifyAction::Success { req_id } => {
-                    let req = store.state().snark.work_verify.jobs.get(req_id);
+                    let state = store.state();
+                    let req = state.snark.work_verify.jobs.get(req_id);
                     let Some(req) = req else { return };
-                    let sender = req.sender().parse().unwrap();
                     let batch = req.batch().to_vec();
+                    let remote_worker = state
+                        .external_snark_worker
+                        .remote()
+                        .result_verify_worker(&req_id)
+                        .cloned();
 
-                    store.dispatch(SnarkPoolCandidateAction::WorkVerifySuccess {
-                        peer_id: sender,
-                        verify_id: req_id,
-                    });
+                    let sender = if state.snark_pool.is_restore_verify(&req_id) {
+                        let sender = state.p2p.my_id();
+                        store.dispatch(SnarkPoolAction::RestoreVerifySuccess { verify_id: req_id });
+                        sender
+                    } else if let Some(worker_id) = remote_worker {
+                        let sender = state.p2p.my_id();
+                        store.dispatch(ExternalSnarkWorkerRemoteAction::WorkVerifySuccess {
+                            worker_id,
+                            verify_id: req_id,
+                        });
+                        sender
+                    } else {
+                        let sender = req.sender().parse().unwrap();
+                        store.dispatch(SnarkPoolCandidateAction::WorkVerifySuccess {
+                            peer_id: sender,
+                            verify_id: req_id,
+                        });
+                        sender
+                    };
                     for snark in batch {
                         store.dispatch(SnarkPoolAction::WorkAdd { snark, sender });
                     }
                 }
                 SnarkWorkVerifyAction::Init { .. } => {}
                 SnarkWorkVerifyAction::Pending { .. } => {}
-                SnarkWorkVerifyAction::Finish { .. } => {}
+                SnarkWorkVerifyAction::Finish { .. } => {
+                    store.dispatch(SnarkPoolAction::RestoreVerifyNext);
+                    store.dispatch(ExternalSnarkWorkerRemoteAction::WorkVerifyNext);
+                }
             }
             a.effects(&meta, store);
         }
@@ -68,6 +68,18 @@ impl SnarkPoolCandidatesState {
             .unwrap_or(&EMPTY_PEER_WORK_CANDIDATES)
     }
 
+    /// Lowest fee among the snarks for the job received from peers, which
+    /// weren't found to be invalid.
+    pub fn lowest_fee(&self, job_id: &SnarkJobId) -> Option<u64> {
+        self.by_job_id
+            .get(job_id)?
+            .iter()
+            .filter_map(|peer_id| self.get(*peer_id, job_id))
+            .filter(|s| !matches!(s, SnarkPoolCandidateState::WorkVerifyError { .. }))
+            .map(|s| s.fee())
+            .min()
+    }
+
     pub fn jobs_from_peer_iter(
         &self,
         peer_id: PeerId,
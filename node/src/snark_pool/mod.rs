@@ -6,6 +6,9 @@ pub use snark_pool_config::*;
 mod snark_pool_state;
 pub use snark_pool_state::*;
 
+mod snark_pool_fee_stats;
+pub use snark_pool_fee_stats::*;
+
 mod snark_pool_actions;
 pub use snark_pool_actions::*;
 
@@ -1,9 +1,13 @@
+use std::collections::BTreeMap;
+
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
 use openmina_core::snark::{Snark, SnarkJobCommitment, SnarkJobId};
 use serde::{Deserialize, Serialize};
 
 use crate::p2p::PeerId;
+use crate::snark::work_verify::SnarkWorkVerifyId;
+use crate::SnarkerStrategy;
 
 use super::candidate::SnarkPoolCandidateAction;
 use super::SnarkWork;
@@ -17,8 +21,27 @@ pub enum SnarkPoolAction {
 
     JobsUpdate {
         jobs: Vec<OneOrTwo<AvailableJobMessage>>,
+        jobs_depth: BTreeMap<SnarkJobId, usize>,
+        next_block_jobs: Vec<SnarkJobId>,
         orphaned_snarks: Vec<SnarkWork>,
     },
+    /// Verifies and adds the snarks persisted by the previous run, the ones
+    /// for jobs which are no longer in the scan state are dropped.
+    Restore {
+        snarks: Vec<Snark>,
+    },
+    /// Restored snarks are verified one at a time, so that the invalid one
+    /// is just dropped, without failing the others.
+    RestoreVerifyNext,
+    RestoreVerifyPending {
+        verify_id: SnarkWorkVerifyId,
+    },
+    RestoreVerifyError {
+        verify_id: SnarkWorkVerifyId,
+    },
+    RestoreVerifySuccess {
+        verify_id: SnarkWorkVerifyId,
+    },
     AutoCreateCommitment,
     CommitmentCreate {
         job_id: SnarkJobId,
@@ -39,6 +62,8 @@ pub enum SnarkPoolAction {
     JobCommitmentTimeout {
         job_id: SnarkJobId,
     },
+    /// Persists the verified snarks of the pool.
+    Save,
 }
 
 impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
@@ -51,7 +76,16 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
                 .as_ref()
                 .map_or(false, |v| v.auto_commit),
             SnarkPoolAction::CommitmentCreate { job_id } => {
-                state.config.snarker.is_some() && state.snark_pool.should_create_commitment(job_id)
+                state
+                    .config
+                    .snarker
+                    .as_ref()
+                    .map_or(false, |config| match config.strategy {
+                        SnarkerStrategy::Profit => state
+                            .snark_pool
+                            .can_outbid_commitment(job_id, config.fee.0.as_u64()),
+                        _ => state.snark_pool.should_create_commitment(job_id),
+                    })
             }
             SnarkPoolAction::CommitmentAdd { commitment, .. } => state
                 .snark_pool
@@ -104,6 +138,19 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
             SnarkPoolAction::JobCommitmentTimeout { job_id } => {
                 state.snark_pool.is_commitment_timed_out(job_id, time)
             }
+            SnarkPoolAction::Restore { .. } => !state.snark_pool.restored_snarks().is_empty(),
+            SnarkPoolAction::RestoreVerifyNext => {
+                state.snark_pool.next_snark_to_restore().is_some()
+                    && state.snark.work_verify.jobs.is_empty()
+            }
+            SnarkPoolAction::RestoreVerifyPending { .. } => {
+                state.snark_pool.next_snark_to_restore().is_some()
+            }
+            SnarkPoolAction::RestoreVerifyError { verify_id }
+            | SnarkPoolAction::RestoreVerifySuccess { verify_id } => {
+                state.snark_pool.is_restore_verify(verify_id)
+            }
+            SnarkPoolAction::Save => state.snark_pool.should_save(time),
             SnarkPoolAction::JobsUpdate { .. } => true,
             SnarkPoolAction::P2pSendAll => true,
         }
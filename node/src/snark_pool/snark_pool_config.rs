@@ -1,4 +1,15 @@
+use std::time::Duration;
+
+use openmina_core::snark::Snark;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct SnarkPoolConfig {}
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkPoolConfig {
+    /// Verified snarks persisted by the previous run. They are restored
+    /// into the pool once the jobs of the current scan state are known.
+    #[serde(skip)]
+    pub persisted_snarks: Vec<Snark>,
+    /// Interval between the saves of the verified snarks, `None` disables
+    /// the persistence.
+    pub save_interval: Option<Duration>,
+}
@@ -2,6 +2,7 @@ use openmina_core::snark::SnarkJobCommitment;
 use p2p::channels::snark::P2pChannelsSnarkAction;
 
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
+use crate::snark::work_verify::SnarkWorkVerifyAction;
 use crate::{ExternalSnarkWorkerAction, Service, SnarkerStrategy, State, Store};
 
 use super::candidate::snark_pool_candidate_effects;
@@ -15,6 +16,9 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             snark_pool_candidate_effects(store, meta.with_action(action))
         }
         SnarkPoolAction::JobsUpdate { .. } => {
+            let snarks = store.state().snark_pool.restored_snarks().to_vec();
+            store.dispatch(SnarkPoolAction::Restore { snarks });
+
             let state = store.state();
             if let Some(job_id) = state.external_snark_worker.working_job_id() {
                 if !state.snark_pool.contains(job_id) {
@@ -25,6 +29,32 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
                 store.dispatch(SnarkPoolAction::AutoCreateCommitment);
             }
         }
+        SnarkPoolAction::Restore { .. } => {
+            store.dispatch(SnarkPoolAction::RestoreVerifyNext);
+        }
+        SnarkPoolAction::RestoreVerifyNext => {
+            // persisted snarks aren't trusted, so they are verified
+            // before being added to the pool.
+            let state = store.state();
+            let Some(snark) = state.snark_pool.next_snark_to_restore() else {
+                return;
+            };
+            let req_id = state.snark.work_verify.next_req_id();
+            let batch = vec![snark.clone()];
+            let sender = state.p2p.my_id().to_string();
+            store.dispatch(SnarkWorkVerifyAction::Init {
+                req_id,
+                batch,
+                sender,
+            });
+            store.dispatch(SnarkPoolAction::RestoreVerifyPending { verify_id: req_id });
+        }
+        SnarkPoolAction::RestoreVerifyPending { .. } => {}
+        SnarkPoolAction::RestoreVerifyError { .. } => {}
+        SnarkPoolAction::RestoreVerifySuccess { .. } => {
+            // action for adding the verified snark to snark pool is
+            // called in snark/work_verify effects, same as for candidates.
+        }
         SnarkPoolAction::AutoCreateCommitment { .. } => {
             let state = store.state.get();
             let Some(snarker_config) = &state.config.snarker else {
@@ -47,6 +77,20 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
                         let jobs = state.snark_pool.available_jobs_iter();
                         store.service.random_choose(jobs, available_workers)
                     }
+                    SnarkerStrategy::Profit => {
+                        let proving_times = state.external_snark_worker.proving_times();
+                        state
+                            .snark_pool
+                            .jobs_by_expected_profit(
+                                snarker_config.fee.0.as_u64(),
+                                meta.time(),
+                                |summary| proving_times.estimate(summary),
+                            )
+                            .into_iter()
+                            .map(|job| job.id.clone())
+                            .take(available_workers)
+                            .collect()
+                    }
                 };
 
                 for job_id in job_ids {
@@ -162,6 +206,15 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
         SnarkPoolAction::JobCommitmentTimeout { .. } => {
             store.dispatch(SnarkPoolAction::AutoCreateCommitment);
         }
+        SnarkPoolAction::Save => {
+            let snarks = store
+                .state()
+                .snark_pool
+                .completed_snarks_iter()
+                .cloned()
+                .collect();
+            store.service.snark_pool_save(snarks);
+        }
     }
 }
 
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use mina_p2p_messages::v2::NonZeroCurvePoint;
+use openmina_core::snark::SnarkJobId;
+use serde::{Deserialize, Serialize};
+
+use super::{JobState, JobSummary, SnarkPoolState};
+
+/// Fee market of the snark pool. All the fees are in nanomina.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkPoolFeeStats {
+    /// Fee distribution per job kind and depth in the scan state tree.
+    pub distribution: Vec<SnarkPoolFeeDistribution>,
+    pub jobs: Vec<SnarkPoolJobFeeStats>,
+    /// Cheapest works, among the ones in the pool, needed by the next block.
+    pub next_block: SnarkPoolNextBlockWorks,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SnarkPoolJobKind {
+    Tx,
+    Merge,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkPoolFeeDistribution {
+    pub kind: SnarkPoolJobKind,
+    pub depth: Option<usize>,
+    pub jobs: usize,
+    /// `None` if none of the jobs has a snark yet.
+    pub fees: Option<SnarkPoolFeeSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SnarkPoolFeeSummary {
+    pub count: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: u64,
+    pub median: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkPoolJobFeeStats {
+    pub id: SnarkJobId,
+    pub kind: SnarkPoolJobKind,
+    pub depth: Option<usize>,
+    pub fee: Option<u64>,
+    pub time_to_first_snark: Option<Duration>,
+    pub competing_snarks: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkPoolNextBlockWorks {
+    /// Works in the order the jobs need to be done.
+    pub works: Vec<SnarkPoolNextBlockWork>,
+    /// Whether there is a snark for each of the jobs.
+    pub complete: bool,
+    /// Sum of the fees of the available works.
+    pub total_fee: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkPoolNextBlockWork {
+    pub job_id: SnarkJobId,
+    pub snarker: Option<NonZeroCurvePoint>,
+    pub fee: Option<u64>,
+}
+
+impl SnarkPoolState {
+    pub fn fee_stats(&self) -> SnarkPoolFeeStats {
+        let mut groups = BTreeMap::<_, (usize, Vec<u64>)>::new();
+        for job in self.jobs_iter() {
+            let (jobs, fees) = groups.entry((job.kind(), job.depth)).or_default();
+            *jobs += 1;
+            fees.extend(job.fee());
+        }
+        let distribution = groups
+            .into_iter()
+            .map(|((kind, depth), (jobs, fees))| SnarkPoolFeeDistribution {
+                kind,
+                depth,
+                jobs,
+                fees: SnarkPoolFeeSummary::new(fees),
+            })
+            .collect();
+
+        let jobs = self
+            .jobs_iter()
+            .map(|job| SnarkPoolJobFeeStats {
+                id: job.id.clone(),
+                kind: job.kind(),
+                depth: job.depth,
+                fee: job.fee(),
+                time_to_first_snark: job.first_snark_t.and_then(|t| t.checked_sub(job.time)),
+                competing_snarks: job.competing_provers.len(),
+            })
+            .collect();
+
+        let works = self
+            .next_block_jobs()
+            .iter()
+            .map(|job_id| {
+                let snark = self.get(job_id).and_then(|job| job.snark.as_ref());
+                SnarkPoolNextBlockWork {
+                    job_id: job_id.clone(),
+                    snarker: snark.map(|snark| snark.work.snarker.clone()),
+                    fee: snark.map(|snark| snark.work.fee.0.as_u64()),
+                }
+            })
+            .collect::<Vec<_>>();
+        let next_block = SnarkPoolNextBlockWorks {
+            complete: works.iter().all(|work| work.fee.is_some()),
+            total_fee: works.iter().filter_map(|work| work.fee).sum(),
+            works,
+        };
+
+        SnarkPoolFeeStats {
+            distribution,
+            jobs,
+            next_block,
+        }
+    }
+}
+
+impl JobState {
+    pub fn kind(&self) -> SnarkPoolJobKind {
+        match self.summary() {
+            JobSummary::Tx(_) => SnarkPoolJobKind::Tx,
+            JobSummary::Merge(_) => SnarkPoolJobKind::Merge,
+        }
+    }
+
+    fn fee(&self) -> Option<u64> {
+        self.snark.as_ref().map(|snark| snark.work.fee.0.as_u64())
+    }
+}
+
+impl SnarkPoolFeeSummary {
+    pub fn new(mut fees: Vec<u64>) -> Option<Self> {
+        if fees.is_empty() {
+            return None;
+        }
+        fees.sort_unstable();
+        let count = fees.len();
+        let mid = count / 2;
+        let median = if count % 2 == 0 {
+            (fees[mid - 1] + fees[mid]) / 2
+        } else {
+            fees[mid]
+        };
+        Some(Self {
+            count,
+            min: fees[0],
+            max: fees[count - 1],
+            mean: fees.iter().sum::<u64>() / count as u64,
+            median,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_summary() {
+        assert_eq!(SnarkPoolFeeSummary::new(vec![]), None);
+
+        let summary = SnarkPoolFeeSummary::new(vec![30, 10, 20]).unwrap();
+        assert_eq!(
+            summary,
+            SnarkPoolFeeSummary {
+                count: 3,
+                min: 10,
+                max: 30,
+                mean: 20,
+                median: 20,
+            }
+        );
+
+        let summary = SnarkPoolFeeSummary::new(vec![40, 10, 20, 100]).unwrap();
+        assert_eq!((summary.median, summary.mean), (30, 42));
+    }
+}
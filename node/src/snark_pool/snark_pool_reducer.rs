@@ -4,6 +4,7 @@ use openmina_core::snark::SnarkJobId;
 
 use crate::snark_pool::JobCommitment;
 
+use super::candidate::SnarkPoolCandidateAction;
 use super::{JobState, SnarkPoolAction, SnarkPoolActionWithMetaRef, SnarkPoolState, SnarkWork};
 
 impl SnarkPoolState {
@@ -11,10 +12,29 @@ impl SnarkPoolState {
         let (action, meta) = action.split();
         match action {
             SnarkPoolAction::Candidate(action) => {
+                let competing_snark = match action {
+                    SnarkPoolCandidateAction::InfoReceived { info, .. } => {
+                        Some((info.job_id.clone(), &info.prover))
+                    }
+                    // work can be received without its info being received first.
+                    SnarkPoolCandidateAction::WorkReceived { work, .. } => {
+                        Some((work.job_id(), &work.snarker))
+                    }
+                    _ => None,
+                };
+                if let Some((job_id, prover)) = competing_snark {
+                    if let Some(job) = self.get_mut(&job_id) {
+                        job.competing_provers.insert(prover.clone());
+                        job.first_snark_t.get_or_insert(meta.time());
+                    }
+                }
+
                 self.candidates.reducer(meta.with_action(action));
             }
             SnarkPoolAction::JobsUpdate {
                 jobs,
+                jobs_depth,
+                next_block_jobs,
                 orphaned_snarks,
             } => {
                 let mut jobs_map = jobs
@@ -23,8 +43,12 @@ impl SnarkPoolState {
                     .map(|(index, job)| (SnarkJobId::from(job), (index, job.clone())))
                     .collect::<BTreeMap<_, _>>();
 
+                let snarks_count = self.completed_snarks_iter().count();
                 self.retain(|id| jobs_map.remove(id).map(|(order, _)| order));
+                // snarks are only removed along with their jobs.
+                let mut snarks_changed = self.completed_snarks_iter().count() != snarks_count;
                 for (id, (order, job)) in jobs_map {
+                    let depth = jobs_depth.get(&id).copied();
                     self.insert(JobState {
                         time: meta.time(),
                         id,
@@ -32,8 +56,12 @@ impl SnarkPoolState {
                         commitment: None,
                         snark: None,
                         order,
+                        depth,
+                        first_snark_t: None,
+                        competing_provers: Default::default(),
                     });
                 }
+                self.next_block_jobs = next_block_jobs.clone();
 
                 let orphaned_snarks = orphaned_snarks
                     .iter()
@@ -46,13 +74,39 @@ impl SnarkPoolState {
                         .map_or(true, |old_snark| snark.work > old_snark.work);
                     if take {
                         if let Some(mut job) = self.remove(&id) {
+                            job.first_snark_t.get_or_insert(meta.time());
                             job.snark = Some(snark.clone());
                             self.insert(job);
+                            snarks_changed = true;
                         }
                     }
                 }
 
+                let restore_queue = std::mem::take(&mut self.restore_queue);
+                self.restore_queue = restore_queue
+                    .into_iter()
+                    .filter(|snark| self.contains(&snark.job_id()))
+                    .collect();
+
                 self.candidates_prune();
+                self.unsaved_changes |= snarks_changed;
+            }
+            SnarkPoolAction::Restore { snarks } => {
+                self.restored_snarks.clear();
+                self.restore_queue = snarks
+                    .iter()
+                    .filter(|snark| self.contains(&snark.job_id()))
+                    .cloned()
+                    .collect();
+            }
+            SnarkPoolAction::RestoreVerifyNext => {}
+            SnarkPoolAction::RestoreVerifyPending { verify_id } => {
+                self.restore_queue.pop_front();
+                self.restore_verify_id = Some(*verify_id);
+            }
+            SnarkPoolAction::RestoreVerifyError { .. }
+            | SnarkPoolAction::RestoreVerifySuccess { .. } => {
+                self.restore_verify_id = None;
             }
             SnarkPoolAction::AutoCreateCommitment => {}
             SnarkPoolAction::CommitmentCreate { .. } => {}
@@ -72,6 +126,7 @@ impl SnarkPoolState {
                 let Some(mut job) = self.remove(&job_id) else {
                     return;
                 };
+                job.first_snark_t.get_or_insert(meta.time());
                 job.snark = Some(SnarkWork {
                     work: snark.clone(),
                     received_t: meta.time(),
@@ -79,6 +134,7 @@ impl SnarkPoolState {
                 });
                 self.insert(job);
                 self.candidates.remove_inferior_snarks(snark);
+                self.unsaved_changes = true;
             }
             SnarkPoolAction::P2pSendAll { .. } => {}
             SnarkPoolAction::P2pSend { .. } => {}
@@ -88,6 +144,10 @@ impl SnarkPoolState {
             SnarkPoolAction::JobCommitmentTimeout { job_id } => {
                 self.remove_commitment(job_id);
             }
+            SnarkPoolAction::Save => {
+                self.unsaved_changes = false;
+                self.last_saved = Some(meta.time());
+            }
         }
     }
 }
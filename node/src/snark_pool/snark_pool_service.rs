@@ -1,4 +1,4 @@
-use crate::core::snark::SnarkJobId;
+use crate::core::snark::{Snark, SnarkJobId};
 
 use super::JobState;
 
@@ -8,4 +8,8 @@ pub trait SnarkPoolService: redux::Service {
         iter: impl Iterator<Item = &'a JobState>,
         n: usize,
     ) -> Vec<SnarkJobId>;
+
+    /// Persists the verified snarks, so that they can be restored into the
+    /// pool after a restart.
+    fn snark_pool_save(&mut self, snarks: Vec<Snark>);
 }
@@ -1,12 +1,18 @@
 use std::time::Duration;
-use std::{collections::BTreeMap, fmt, ops::RangeBounds};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt,
+    ops::RangeBounds,
+};
 
 use ledger::scan_state::scan_state::{transaction_snark::OneOrTwo, AvailableJobMessage};
+use mina_p2p_messages::v2::NonZeroCurvePoint;
 use openmina_core::snark::{Snark, SnarkInfo, SnarkJobCommitment, SnarkJobId};
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::p2p::PeerId;
+use crate::snark::work_verify::SnarkWorkVerifyId;
 
 use super::candidate::SnarkPoolCandidatesState;
 use super::SnarkPoolConfig;
@@ -19,6 +25,19 @@ pub struct SnarkPoolState {
     by_ledger_hash_index: BTreeMap<SnarkJobId, u64>,
     pub candidates: SnarkPoolCandidatesState,
     pub(super) last_check_timeouts: Timestamp,
+    /// Persisted snarks waiting for the jobs to be known, to be validated
+    /// against them.
+    pub(super) restored_snarks: Vec<Snark>,
+    /// Restored snarks for the jobs still in the scan state, waiting to be
+    /// verified.
+    pub(super) restore_queue: VecDeque<Snark>,
+    /// Verification of the restored snark in progress.
+    pub(super) restore_verify_id: Option<SnarkWorkVerifyId>,
+    /// Snarks in the pool changed since the last save.
+    pub(super) unsaved_changes: bool,
+    pub(super) last_saved: Option<Timestamp>,
+    /// Jobs whose works are needed by the next block, in order.
+    pub(super) next_block_jobs: Vec<SnarkJobId>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -30,6 +49,14 @@ pub struct JobState {
     pub snark: Option<SnarkWork>,
     /// Lower order has higher priority to be done as it represents older job.
     pub order: usize,
+    /// Depth in the scan state tree, root being at 0.
+    pub depth: Option<usize>,
+    /// When the first snark for the job was received, either from a peer
+    /// or from the local snark worker.
+    pub first_snark_t: Option<Timestamp>,
+    /// Provers of the snarks for the job received from peers, including
+    /// the ones inferior to the snark in the pool.
+    pub competing_provers: BTreeSet<NonZeroCurvePoint>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -55,19 +82,26 @@ pub enum JobSummary {
 
 impl Default for SnarkPoolState {
     fn default() -> Self {
-        Self::new()
+        Self::new(SnarkPoolConfig::default())
     }
 }
 
 impl SnarkPoolState {
-    pub fn new() -> Self {
+    pub fn new(mut config: SnarkPoolConfig) -> Self {
+        let restored_snarks = std::mem::take(&mut config.persisted_snarks);
         Self {
-            config: SnarkPoolConfig {},
+            config,
             counter: 0,
             list: Default::default(),
             by_ledger_hash_index: Default::default(),
             candidates: SnarkPoolCandidatesState::new(),
             last_check_timeouts: Timestamp::ZERO,
+            restored_snarks,
+            restore_queue: Default::default(),
+            restore_verify_id: None,
+            unsaved_changes: false,
+            last_saved: None,
+            next_block_jobs: Vec::new(),
         }
     }
 
@@ -94,6 +128,11 @@ impl SnarkPoolState {
         Self::get_by_job_id(&self.by_ledger_hash_index, &self.list, id)
     }
 
+    pub(super) fn get_mut(&mut self, id: &SnarkJobId) -> Option<&mut JobState> {
+        let index = self.by_ledger_hash_index.get(id)?;
+        self.list.get_mut(index)
+    }
+
     pub fn insert(&mut self, job: JobState) {
         let id = job.id.clone();
         self.list.insert(self.counter, job);
@@ -144,6 +183,17 @@ impl SnarkPoolState {
         self.get(job_id).map_or(false, |s| s.is_available())
     }
 
+    /// Whether the job has no snark yet and our commitment would replace
+    /// the existing one, if any, by having a lower fee.
+    pub fn can_outbid_commitment(&self, job_id: &SnarkJobId, fee: u64) -> bool {
+        self.get(job_id).map_or(false, |job| {
+            job.snark.is_none()
+                && job
+                    .commitment_msg()
+                    .map_or(true, |commitment| fee < commitment.fee.0.as_u64())
+        })
+    }
+
     pub fn is_commitment_timed_out(&self, id: &SnarkJobId, time_now: Timestamp) -> bool {
         self.by_ledger_hash_index.get(id).map_or(false, |i| {
             self.is_commitment_timed_out_by_index(i, time_now)
@@ -200,6 +250,37 @@ impl SnarkPoolState {
             })
     }
 
+    /// Jobs which can be committed to with the `fee`, ordered by the
+    /// expected profit: jobs needed by the next block first, then by the
+    /// expected reward per proving time, then by priority.
+    pub fn jobs_by_expected_profit<F>(
+        &self,
+        fee: u64,
+        time_now: Timestamp,
+        proving_time: F,
+    ) -> Vec<&JobState>
+    where
+        F: Fn(&JobSummary) -> Duration,
+    {
+        let mut jobs = self
+            .jobs_iter()
+            .filter(|job| self.can_outbid_commitment(&job.id, fee))
+            .filter_map(|job| {
+                let lowest_candidate_fee = self.candidates.lowest_fee(&job.id);
+                let profit = JobExpectedProfit::new(
+                    fee,
+                    job.win_chance(fee, lowest_candidate_fee, time_now),
+                    proving_time(&job.summary()),
+                    self.next_block_jobs.contains(&job.id),
+                    job.order,
+                )?;
+                Some((profit, job))
+            })
+            .collect::<Vec<_>>();
+        jobs.sort_by(|(profit1, _), (profit2, _)| profit1.cmp(profit2));
+        jobs.into_iter().map(|(_, job)| job).collect()
+    }
+
     pub fn completed_snarks_iter(&self) -> impl '_ + Iterator<Item = &'_ Snark> {
         self.list
             .iter()
@@ -207,6 +288,47 @@ impl SnarkPoolState {
             .map(|snark| &snark.work)
     }
 
+    pub fn next_block_jobs(&self) -> &[SnarkJobId] {
+        &self.next_block_jobs
+    }
+
+    pub fn restored_snarks(&self) -> &[Snark] {
+        &self.restored_snarks
+    }
+
+    /// Next restored snark to be verified, if no other one is being
+    /// verified.
+    pub fn next_snark_to_restore(&self) -> Option<&Snark> {
+        if self.restore_verify_id.is_some() {
+            return None;
+        }
+        self.restore_queue.front()
+    }
+
+    pub fn is_restore_verify(&self, verify_id: &SnarkWorkVerifyId) -> bool {
+        self.restore_verify_id.as_ref() == Some(verify_id)
+    }
+
+    /// Whether the persisted snarks are still being restored.
+    fn is_restoring(&self) -> bool {
+        !self.restored_snarks.is_empty()
+            || !self.restore_queue.is_empty()
+            || self.restore_verify_id.is_some()
+    }
+
+    /// Restored snarks must be added to the pool before it is saved,
+    /// otherwise they would be overwritten.
+    pub fn should_save(&self, now: Timestamp) -> bool {
+        let Some(interval) = self.config.save_interval else {
+            return false;
+        };
+        self.unsaved_changes
+            && !self.is_restoring()
+            && self.last_saved.map_or(true, |saved| {
+                now.checked_sub(saved).map_or(false, |dur| dur >= interval)
+            })
+    }
+
     pub(super) fn job_summary(&self, id: &SnarkJobId) -> Option<JobSummary> {
         self.get(id).map(|job| job.summary())
     }
@@ -243,6 +365,11 @@ impl JobState {
         self.commitment.as_ref().map(|v| &v.commitment)
     }
 
+    pub fn is_committed_by(&self, snarker: &NonZeroCurvePoint) -> bool {
+        self.commitment_msg()
+            .map_or(false, |commitment| &commitment.snarker == snarker)
+    }
+
     pub fn snark_msg(&self) -> Option<SnarkInfo> {
         self.snark.as_ref().map(|v| v.work.info())
     }
@@ -280,6 +407,77 @@ impl JobState {
     pub fn estimated_duration(&self) -> Duration {
         self.summary().estimated_duration()
     }
+
+    /// Chance, in permille, that our snark with the `fee` ends up in the
+    /// pool. Snarks from peers with the same or lower fee make it zero,
+    /// while an existing commitment lowers it the closer its snark is to be
+    /// delivered.
+    fn win_chance(&self, fee: u64, lowest_candidate_fee: Option<u64>, time_now: Timestamp) -> u64 {
+        let commitment_age = self.commitment_msg().map(|commitment| {
+            time_now
+                .checked_sub(commitment.timestamp())
+                .unwrap_or_default()
+        });
+        win_chance(
+            fee,
+            lowest_candidate_fee,
+            commitment_age,
+            self.estimated_duration(),
+        )
+    }
+}
+
+fn win_chance(
+    fee: u64,
+    lowest_candidate_fee: Option<u64>,
+    commitment_age: Option<Duration>,
+    estimated_duration: Duration,
+) -> u64 {
+    if lowest_candidate_fee.map_or(false, |candidate_fee| candidate_fee <= fee) {
+        return 0;
+    }
+    let mut chance = 1000;
+    if lowest_candidate_fee.is_some() {
+        // might be included by producers before ours replaces it.
+        chance = chance * 3 / 4;
+    }
+    if let Some(age) = commitment_age {
+        let age = age.as_millis() as u64;
+        let duration = estimated_duration.as_millis().max(1) as u64;
+        chance -= chance * age.min(duration) / duration / 2;
+    }
+    chance
+}
+
+/// Ordering of the jobs from the most profitable one.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct JobExpectedProfit {
+    /// The job isn't needed by the next block.
+    later: bool,
+    /// Expected reward per millisecond of proving.
+    reward_rate: std::cmp::Reverse<u128>,
+    order: usize,
+}
+
+impl JobExpectedProfit {
+    /// Jobs with no chance to win aren't profitable at all.
+    fn new(
+        fee: u64,
+        win_chance: u64,
+        proving_time: Duration,
+        needed_next: bool,
+        order: usize,
+    ) -> Option<Self> {
+        if win_chance == 0 {
+            return None;
+        }
+        let proving_time = proving_time.as_millis().max(1);
+        Some(Self {
+            later: !needed_next,
+            reward_rate: std::cmp::Reverse((fee as u128 * win_chance as u128) / proving_time),
+            order,
+        })
+    }
 }
 
 impl JobSummary {
@@ -292,6 +490,238 @@ impl JobSummary {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ledger::dummy::dummy_transaction_proof;
+    use ledger::scan_state::currency::{Amount, Signed};
+    use ledger::scan_state::fee_excess::FeeExcess;
+    use ledger::scan_state::pending_coinbase::Stack;
+    use ledger::scan_state::scan_state::transaction_snark::{
+        LedgerProof, Registers, SokDigest, Statement,
+    };
+    use ledger::scan_state::transaction_logic::local_state::LocalState;
+    use mina_p2p_messages::v2::{
+        CurrencyFeeStableV1, LedgerProofProdStableV2, MinaBaseSokMessageStableV1,
+        TransactionSnarkScanStateLedgerProofWithSokMessageStableV2,
+        TransactionSnarkWorkTStableV2Proofs, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+    };
+    use redux::ActionMeta;
+
+    use crate::account::AccountSecretKey;
+    use crate::snark_pool::SnarkPoolAction;
+
+    use super::*;
+
+    fn ledger_proof(source: u64, target: u64) -> LedgerProofProdStableV2 {
+        let registers = |hash: u64| Registers {
+            first_pass_ledger: hash.into(),
+            second_pass_ledger: hash.into(),
+            pending_coinbase_stack: Stack::empty(),
+            local_state: LocalState::dummy(),
+        };
+        let statement = Statement {
+            source: registers(source),
+            target: registers(target),
+            connecting_ledger_left: 0.into(),
+            connecting_ledger_right: 0.into(),
+            supply_increase: Signed::<Amount>::zero(),
+            fee_excess: FeeExcess::empty(),
+            sok_digest: (),
+        };
+        let proof = LedgerProof::create(statement, SokDigest::default(), dummy_transaction_proof());
+        (&proof).into()
+    }
+
+    fn fee(fee: u64) -> CurrencyFeeStableV1 {
+        CurrencyFeeStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+            fee.into(),
+        ))
+    }
+
+    fn merge_job(
+        proof: &LedgerProofProdStableV2,
+        prover: &NonZeroCurvePoint,
+    ) -> OneOrTwo<AvailableJobMessage> {
+        let sok_message = MinaBaseSokMessageStableV1 {
+            fee: fee(0),
+            prover: prover.clone(),
+        };
+        let proof =
+            TransactionSnarkScanStateLedgerProofWithSokMessageStableV2(proof.clone(), sok_message);
+        OneOrTwo::One(AvailableJobMessage::Merge {
+            left: proof.clone(),
+            right: proof,
+        })
+    }
+
+    fn snark(proof: &LedgerProofProdStableV2, snarker: &NonZeroCurvePoint) -> Snark {
+        Snark {
+            snarker: snarker.clone(),
+            fee: fee(10),
+            proofs: Arc::new(TransactionSnarkWorkTStableV2Proofs::One(proof.clone())),
+        }
+    }
+
+    #[test]
+    fn test_restore_revalidation() {
+        let secs = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        let reduce = |pool: &mut SnarkPoolState, time: u64, action: SnarkPoolAction| {
+            pool.reducer(ActionMeta::zero_custom(secs(time)).with_action(&action));
+        };
+        let jobs_update = |jobs| SnarkPoolAction::JobsUpdate {
+            jobs,
+            jobs_depth: Default::default(),
+            next_block_jobs: vec![],
+            orphaned_snarks: vec![],
+        };
+
+        let snarker: NonZeroCurvePoint = AccountSecretKey::rand().public_key().into();
+        let proofs = [ledger_proof(1, 2), ledger_proof(3, 4), ledger_proof(5, 6)];
+        let jobs = proofs
+            .iter()
+            .map(|proof| merge_job(proof, &snarker))
+            .collect::<Vec<_>>();
+        // snarks loaded from the file persisted by the previous run.
+        let persisted = proofs
+            .iter()
+            .map(|proof| snark(proof, &snarker))
+            .collect::<Vec<_>>();
+        let mut pool = SnarkPoolState::new(SnarkPoolConfig {
+            persisted_snarks: persisted.clone(),
+            save_interval: Some(Duration::from_secs(60)),
+        });
+
+        // the last job is no longer in the scan state.
+        reduce(&mut pool, 1, jobs_update(jobs[..2].to_vec()));
+        assert!(!pool.unsaved_changes, "no snarks were added or removed");
+        assert_eq!(pool.restored_snarks().len(), 3);
+
+        let snarks = pool.restored_snarks().to_vec();
+        reduce(&mut pool, 1, SnarkPoolAction::Restore { snarks });
+        assert!(pool.restored_snarks().is_empty());
+        let queued = pool
+            .restore_queue
+            .iter()
+            .map(|snark| snark.job_id())
+            .collect::<Vec<_>>();
+        assert_eq!(queued, [persisted[0].job_id(), persisted[1].job_id()]);
+
+        // the first snark is valid.
+        let verify_id = SnarkWorkVerifyId::new_unchecked(0, 1);
+        reduce(
+            &mut pool,
+            2,
+            SnarkPoolAction::RestoreVerifyPending { verify_id },
+        );
+        assert!(pool.is_restore_verify(&verify_id));
+        assert!(pool.next_snark_to_restore().is_none());
+        reduce(
+            &mut pool,
+            3,
+            SnarkPoolAction::RestoreVerifySuccess { verify_id },
+        );
+        let sender = PeerId::from_bytes([0; 32]);
+        let snark = persisted[0].clone();
+        reduce(&mut pool, 3, SnarkPoolAction::WorkAdd { snark, sender });
+        assert!(pool.unsaved_changes);
+        assert!(
+            !pool.should_save(secs(100)),
+            "pool isn't saved until all the snarks are restored"
+        );
+
+        // the second one is invalid, so it is dropped.
+        let verify_id = SnarkWorkVerifyId::new_unchecked(0, 2);
+        reduce(
+            &mut pool,
+            4,
+            SnarkPoolAction::RestoreVerifyPending { verify_id },
+        );
+        reduce(
+            &mut pool,
+            5,
+            SnarkPoolAction::RestoreVerifyError { verify_id },
+        );
+        assert!(pool.next_snark_to_restore().is_none());
+        assert!(pool.should_save(secs(100)));
+        let restored = pool
+            .completed_snarks_iter()
+            .map(|snark| snark.job_id())
+            .collect::<Vec<_>>();
+        assert_eq!(restored, [persisted[0].job_id()]);
+
+        reduce(&mut pool, 100, SnarkPoolAction::Save);
+        reduce(&mut pool, 101, jobs_update(jobs[..2].to_vec()));
+        assert!(!pool.unsaved_changes, "snarks are the same");
+        reduce(&mut pool, 102, jobs_update(jobs[1..].to_vec()));
+        assert!(pool.unsaved_changes, "job of the restored snark is gone");
+    }
+
+    #[test]
+    fn test_should_save() {
+        let mut pool = SnarkPoolState::new(SnarkPoolConfig {
+            persisted_snarks: vec![],
+            save_interval: Some(Duration::from_secs(60)),
+        });
+        let secs = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        assert!(!pool.should_save(secs(1000)));
+
+        pool.unsaved_changes = true;
+        assert!(pool.should_save(secs(1000)));
+
+        pool.last_saved = Some(secs(1000));
+        assert!(!pool.should_save(secs(1030)));
+        assert!(pool.should_save(secs(1060)));
+
+        let pool = SnarkPoolState::default();
+        assert!(!pool.should_save(secs(1000)));
+    }
+
+    #[test]
+    fn test_win_chance() {
+        let duration = Duration::from_secs(20);
+        assert_eq!(win_chance(10, None, None, duration), 1000);
+
+        // peers already have snarks with the same or a lower fee
+        assert_eq!(win_chance(10, Some(10), None, duration), 0);
+        assert_eq!(win_chance(10, Some(5), None, duration), 0);
+        // ours would replace a more expensive one
+        assert_eq!(win_chance(10, Some(11), None, duration), 750);
+
+        // the older the commitment, the sooner its snark is delivered
+        let age = |secs| Some(Duration::from_secs(secs));
+        assert_eq!(win_chance(10, None, age(0), duration), 1000);
+        assert_eq!(win_chance(10, None, age(10), duration), 750);
+        assert_eq!(win_chance(10, None, age(20), duration), 500);
+        assert_eq!(win_chance(10, None, age(100), duration), 500);
+        assert_eq!(win_chance(10, Some(11), age(10), duration), 563);
+    }
+
+    #[test]
+    fn test_jobs_by_expected_profit_order() {
+        let secs = Duration::from_secs;
+        let profit = |chance, proving_time, needed_next, order| {
+            JobExpectedProfit::new(10, chance, proving_time, needed_next, order)
+        };
+        assert_eq!(profit(0, secs(10), true, 0), None);
+
+        let mut jobs = vec![
+            (profit(1000, secs(10), false, 0), "slow"),
+            (profit(1000, secs(5), false, 2), "fast"),
+            (profit(500, secs(5), false, 1), "contested"),
+            (profit(1000, secs(5), false, 1), "fast_older"),
+            (profit(500, secs(20), true, 3), "needed_next"),
+        ];
+        jobs.sort();
+        let jobs = jobs.into_iter().map(|(_, name)| name).collect::<Vec<_>>();
+        assert_eq!(
+            jobs,
+            ["needed_next", "fast_older", "fast", "slow", "contested"]
+        );
+    }
+}
+
 mod ser {
     use super::*;
     use serde::ser::SerializeStruct;
@@ -303,6 +733,18 @@ mod ser {
         list: BTreeMap<u64, JobState>,
         candidates: SnarkPoolCandidatesState,
         last_check_timeouts: Timestamp,
+        #[serde(default)]
+        restored_snarks: Vec<Snark>,
+        #[serde(default)]
+        restore_queue: VecDeque<Snark>,
+        #[serde(default)]
+        restore_verify_id: Option<SnarkWorkVerifyId>,
+        #[serde(default)]
+        unsaved_changes: bool,
+        #[serde(default)]
+        last_saved: Option<Timestamp>,
+        #[serde(default)]
+        next_block_jobs: Vec<SnarkJobId>,
     }
 
     impl Serialize for super::SnarkPoolState {
@@ -310,12 +752,18 @@ mod ser {
         where
             S: serde::Serializer,
         {
-            let mut s = serializer.serialize_struct("SnarkPool", 5)?;
+            let mut s = serializer.serialize_struct("SnarkPool", 11)?;
             s.serialize_field("config", &self.config)?;
             s.serialize_field("counter", &self.counter)?;
             s.serialize_field("list", &self.list)?;
             s.serialize_field("candidates", &self.candidates)?;
             s.serialize_field("last_check_timeouts", &self.last_check_timeouts)?;
+            s.serialize_field("restored_snarks", &self.restored_snarks)?;
+            s.serialize_field("restore_queue", &self.restore_queue)?;
+            s.serialize_field("restore_verify_id", &self.restore_verify_id)?;
+            s.serialize_field("unsaved_changes", &self.unsaved_changes)?;
+            s.serialize_field("last_saved", &self.last_saved)?;
+            s.serialize_field("next_block_jobs", &self.next_block_jobs)?;
             s.end()
         }
     }
@@ -333,6 +781,12 @@ mod ser {
                 by_ledger_hash_index,
                 candidates: v.candidates,
                 last_check_timeouts: v.last_check_timeouts,
+                restored_snarks: v.restored_snarks,
+                restore_queue: v.restore_queue,
+                restore_verify_id: v.restore_verify_id,
+                unsaved_changes: v.unsaved_changes,
+                last_saved: v.last_saved,
+                next_block_jobs: v.next_block_jobs,
             })
         }
     }
@@ -44,7 +44,7 @@ impl State {
         Self {
             p2p: P2p::Pending(config.p2p),
             ledger: LedgerState::new(config.ledger),
-            snark_pool: SnarkPoolState::new(),
+            snark_pool: SnarkPoolState::new(config.snark_pool),
             snark: SnarkState::new(config.snark),
             consensus: ConsensusState::new(),
             transition_frontier: TransitionFrontierState::new(config.transition_frontier),
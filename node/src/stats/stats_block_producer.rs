@@ -5,7 +5,10 @@ use mina_p2p_messages::v2;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    block_producer::{BlockProducerWonSlot, BlockProducerWonSlotDiscardReason, BlockWithoutProof},
+    block_producer::{
+        BlockProducerWonSlot, BlockProducerWonSlotDiscardReason, BlockProductionTimeEstimate,
+        BlockWithoutProof,
+    },
     core::block::{Block, BlockHash, BlockWithHash},
 };
 
@@ -14,6 +17,9 @@ const MAX_HISTORY: usize = 2048;
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct BlockProducerStats {
     pub(super) attempts: VecDeque<BlockProductionAttempt>,
+    /// Latest estimate of how long block production takes, based on
+    /// measured staged ledger diff creation and proving times.
+    pub(super) production_time_estimate: Option<BlockProductionTimeEstimate>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -75,6 +81,8 @@ pub enum BlockProductionStatus {
 pub struct ProducedBlock {
     pub hash: BlockHash,
     pub height: u32,
+    pub global_slot_since_genesis: u32,
+    pub supercharge_coinbase: bool,
     pub transactions: ProducedBlockTransactions,
     pub coinbase: u64,
     pub fees: u64,
@@ -100,6 +108,14 @@ impl BlockProducerStats {
         self.attempts.iter().cloned().collect()
     }
 
+    pub fn production_time_estimate(&self) -> Option<&BlockProductionTimeEstimate> {
+        self.production_time_estimate.as_ref()
+    }
+
+    pub fn production_time_estimate_update(&mut self, estimate: &BlockProductionTimeEstimate) {
+        self.production_time_estimate = Some(estimate.clone());
+    }
+
     pub fn new_best_chain<T: AsRef<Block>>(
         &mut self,
         time: redux::Timestamp,
@@ -337,6 +353,17 @@ impl From<(&BlockHash, &BlockWithoutProof)> for ProducedBlock {
                 .consensus_state
                 .blockchain_length
                 .as_u32(),
+            global_slot_since_genesis: block
+                .protocol_state
+                .body
+                .consensus_state
+                .global_slot_since_genesis
+                .as_u32(),
+            supercharge_coinbase: block
+                .protocol_state
+                .body
+                .consensus_state
+                .supercharge_coinbase,
             transactions: block.into(),
             coinbase: block.body.coinbase_sum(),
             fees: block.body.fees_sum(),
@@ -5,6 +5,7 @@ use openmina_core::block::ArcBlockWithHash;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use crate::ledger::LedgerAddress;
 use crate::transition_frontier::sync::{
     ledger::SyncLedgerTargetKind, TransitionFrontierSyncBlockState,
 };
@@ -114,6 +115,18 @@ pub struct SyncSnarkedLedger {
     pub fetch_hashes_end: Option<Timestamp>,
     pub fetch_accounts_start: Option<Timestamp>,
     pub fetch_accounts_end: Option<Timestamp>,
+    /// Progress of the sync per address range.
+    #[serde(default)]
+    pub ranges: Vec<SyncSnarkedLedgerRange>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SyncSnarkedLedgerRange {
+    pub address: LedgerAddress,
+    pub synced_hashes: u64,
+    pub synced_accounts: u64,
+    pub queued: usize,
+    pub pending: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -160,6 +173,9 @@ pub enum SyncingLedger {
         start: Timestamp,
         end: Timestamp,
     },
+    FetchRanges {
+        ranges: Vec<SyncSnarkedLedgerRange>,
+    },
     FetchParts {
         start: Timestamp,
         end: Option<Timestamp>,
@@ -267,6 +283,9 @@ impl SyncStats {
                 let cur_end = ledger.snarked.fetch_accounts_end.get_or_insert(end);
                 *cur_end = end.max(*cur_end);
             }
+            SyncingLedger::FetchRanges { ranges } => {
+                ledger.snarked.ranges = ranges;
+            }
             SyncingLedger::FetchParts { start, end } => {
                 ledger.staged.fetch_parts_start.get_or_insert(start);
                 if let Some(end) = end {
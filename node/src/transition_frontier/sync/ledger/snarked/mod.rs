@@ -40,4 +40,6 @@ pub enum PeerLedgerQueryError {
     Timeout,
     Disconnected,
     DataUnavailable,
+    /// Response didn't match the expected hash.
+    InvalidData,
 }
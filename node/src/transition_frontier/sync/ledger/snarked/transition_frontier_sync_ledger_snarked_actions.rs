@@ -9,7 +9,7 @@ use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerState;
 
 use super::{
     LedgerAddressQuery, PeerLedgerQueryError, PeerLedgerQueryResponse, PeerRpcState,
-    TransitionFrontierSyncLedgerSnarkedState,
+    SnarkedLedgerSyncCheckpoint, TransitionFrontierSyncLedgerSnarkedState,
 };
 
 /// Once we reach subtrees of this height, we begin performing
@@ -69,6 +69,13 @@ pub enum TransitionFrontierSyncLedgerSnarkedAction {
     },
 
     MerkleTreeSyncPending,
+    /// Resumes the sync from the progress persisted by the previous run,
+    /// instead of querying the number of accounts first.
+    MerkleTreeSyncResume {
+        checkpoint: SnarkedLedgerSyncCheckpoint,
+    },
+    /// Persists the sync progress.
+    MerkleTreeSyncCheckpoint,
 
     // For child hashes and content queries
     PeerQueryAddressInit {
@@ -127,11 +134,17 @@ pub enum TransitionFrontierSyncLedgerSnarkedAction {
     },
 
     MerkleTreeSyncSuccess,
+    /// Merkle root of the synced ledger doesn't match the target ledger
+    /// hash, so the sync starts over, keeping the accounts synced so far.
+    #[action_event(level = warn, fields(display(merkle_root)))]
+    MerkleTreeSyncRootMismatch {
+        merkle_root: LedgerHash,
+    },
     Success,
 }
 
 impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnarkedAction {
-    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
         match self {
             TransitionFrontierSyncLedgerSnarkedAction::Pending => {
                 state.transition_frontier.sync.ledger().map_or(false, |s| {
@@ -143,7 +156,8 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                 // - there are available peers to query
                 // - there is a snarked ledger to sync
                 // - there are either queued num_accounts or address queries
-                //   or queries to retry
+                //   or queries to retry, and the limit of address queries
+                //   in flight isn't reached
                 let peers_available = state
                     .p2p
                     .ready_peers_iter()
@@ -155,8 +169,9 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                     .and_then(|s| s.snarked())
                     .map_or(false, |s| {
                         s.is_num_accounts_query_next()
-                            || s.sync_address_next().is_some()
-                            || s.sync_address_retry_iter().next().is_some()
+                            || (s.can_query_address()
+                                && (s.sync_address_next().is_some()
+                                    || s.sync_address_retry_iter().next().is_some()))
                     });
                 peers_available && sync_next_available
             }
@@ -270,6 +285,39 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                         TransitionFrontierSyncLedgerSnarkedState::NumAccountsSuccess { .. }
                     )
                 }),
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncResume { checkpoint } => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.snarked())
+                .map_or(false, |s| match s {
+                    TransitionFrontierSyncLedgerSnarkedState::NumAccountsPending {
+                        target,
+                        pending_num_accounts,
+                        ..
+                    } => {
+                        target.snarked_ledger_hash == checkpoint.snarked_ledger_hash
+                            && pending_num_accounts.attempts.is_empty()
+                    }
+                    _ => false,
+                }),
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncCheckpoint => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.snarked())
+                .map_or(false, |s| s.is_checkpoint_due(time)),
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncRootMismatch { .. } => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.snarked())
+                .map_or(false, |s| {
+                    matches!(
+                        s,
+                        TransitionFrontierSyncLedgerSnarkedState::MerkleTreeSyncSuccess { .. }
+                    )
+                }),
             TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncSuccess => state
                 .transition_frontier
                 .sync
@@ -314,9 +362,10 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                     };
 
                     let peer = state.p2p.get_ready_peer(peer_id)?;
-                    let check_peer_available = check_peer_available(peer, target, target_best_tip);
+                    let check_peer_available = check_peer_available(peer, target, target_best_tip)
+                        && check_peer_valid(ledger, peer_id);
 
-                    Some(check_next_addr && check_peer_available)
+                    Some(check_next_addr && check_peer_available && ledger.can_query_address())
                 })
                 .unwrap_or(false)
             }
@@ -329,19 +378,17 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                     let ledger = state.transition_frontier.sync.ledger()?.snarked()?;
                     let target = ledger.target();
 
-                    // This is true if there is next retry address and it
-                    // matches the one requested in this action.
-                    let check_next_addr = state
-                        .transition_frontier
-                        .sync
-                        .ledger()
-                        .and_then(|s| s.snarked()?.sync_address_retry_iter().next())
-                        .map_or(false, |addr| &addr == address);
+                    // This is true if the address requested in this action
+                    // needs to be retried.
+                    let check_retry_addr = ledger
+                        .sync_address_retry_iter()
+                        .any(|addr| &addr == address);
 
                     let peer = state.p2p.get_ready_peer(peer_id)?;
-                    let check_peer_available = check_peer_available(peer, target, target_best_tip);
+                    let check_peer_available = check_peer_available(peer, target, target_best_tip)
+                        && check_peer_valid(ledger, peer_id);
 
-                    Some(check_next_addr && check_peer_available)
+                    Some(check_retry_addr && check_peer_available && ledger.can_query_address())
                 })
                 .unwrap_or(false)
             }
@@ -384,9 +431,20 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                     .and_then(|s| s.snarked())
                     .map_or(false, |s| {
                         // TODO(binier): check if expected response kind is correct.
+                        // Late responses to the queries that timed out are
+                        // still accepted, unless the address got synced meanwhile.
                         s.peer_address_query_get(peer_id, *rpc_id)
                             .and_then(|(_, s)| s.attempts.get(peer_id))
-                            .map_or(false, |s| matches!(s, PeerRpcState::Pending { .. }))
+                            .map_or(false, |s| {
+                                matches!(
+                                    s,
+                                    PeerRpcState::Pending { .. }
+                                        | PeerRpcState::Error {
+                                            error: PeerLedgerQueryError::Timeout,
+                                            ..
+                                        }
+                                )
+                            })
                     })
             }
             TransitionFrontierSyncLedgerSnarkedAction::ChildHashesReceived {
@@ -483,6 +541,13 @@ fn check_peer_available(
     .unwrap_or(false)
 }
 
+/// Peers that sent data which failed verification aren't queried anymore.
+fn check_peer_valid(ledger: &TransitionFrontierSyncLedgerSnarkedState, peer_id: &PeerId) -> bool {
+    ledger
+        .peer_stats(peer_id)
+        .map_or(true, |stats| !stats.is_invalid())
+}
+
 use crate::transition_frontier::{
     sync::{ledger::TransitionFrontierSyncLedgerAction, TransitionFrontierSyncAction},
     TransitionFrontierAction,
@@ -1,6 +1,8 @@
+use std::collections::BTreeSet;
+
 use mina_p2p_messages::v2::MinaLedgerSyncLedgerQueryStableV1;
 use p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcRequest};
-use p2p::PeerId;
+use p2p::{P2pNetworkKadRecordKey, P2pNetworkKademliaAction, PeerId};
 use redux::ActionMeta;
 
 use crate::ledger::{hash_node_at_depth, LedgerAddress, LEDGER_DEPTH};
@@ -11,6 +13,13 @@ use super::{
     TransitionFrontierSyncLedgerSnarkedService, ACCOUNT_SUBTREE_HEIGHT,
 };
 
+fn ledger_providers_key(state: &crate::State) -> Option<P2pNetworkKadRecordKey> {
+    let ledger = state.transition_frontier.sync.ledger()?;
+    Some(P2pNetworkKadRecordKey::ledger(
+        ledger.snarked()?.ledger_hash(),
+    ))
+}
+
 fn peer_query_num_accounts_init<S: redux::Service>(store: &mut Store<S>, peer_id: PeerId) {
     let Some((ledger_hash, rpc_id)) = None.or_else(|| {
         let state = store.state();
@@ -82,24 +91,62 @@ fn peer_query_address_init<S: redux::Service>(
 }
 
 impl TransitionFrontierSyncLedgerSnarkedAction {
-    pub fn effects<S>(&self, _: &ActionMeta, store: &mut Store<S>)
+    pub fn effects<S>(&self, meta: &ActionMeta, store: &mut Store<S>)
     where
         S: redux::Service + TransitionFrontierSyncLedgerSnarkedService,
     {
         match self {
             TransitionFrontierSyncLedgerSnarkedAction::Pending => {
+                // look for the peers that announced they serve this ledger
+                if let Some(key) = ledger_providers_key(store.state()) {
+                    store.dispatch(P2pNetworkKademliaAction::GetProviders { key });
+                }
+                if let Some(checkpoint) = store
+                    .state()
+                    .transition_frontier
+                    .ledger_sync_checkpoint
+                    .clone()
+                {
+                    store.dispatch(
+                        TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncResume {
+                            checkpoint,
+                        },
+                    );
+                }
                 store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeersQuery => {
-                // TODO(binier): make sure they have the ledger we want to query.
                 let mut peer_ids = store
                     .state()
                     .p2p
-                    .ready_peers_iter()
-                    .filter(|(_, p)| p.channels.rpc.can_send_request())
-                    .map(|(id, p)| (*id, p.connected_since))
-                    .collect::<Vec<_>>();
-                peer_ids.sort_by(|(_, t1), (_, t2)| t2.cmp(t1));
+                    .ready()
+                    .map(|p2p| p2p.ready_rpc_peers_by_rtt())
+                    .unwrap_or_default();
+                let providers = store
+                    .state()
+                    .p2p
+                    .ready()
+                    .zip(ledger_providers_key(store.state()))
+                    .map(|(p2p, key)| p2p.kad_providers(&key, meta.time()))
+                    .unwrap_or_default();
+                let snarked = store
+                    .state()
+                    .transition_frontier
+                    .sync
+                    .ledger()
+                    .and_then(|s| s.snarked());
+                // query the known providers of the ledger first, then the peers
+                // that answered reliably by their latency, keeping the rtt order.
+                peer_ids.sort_by_key(|(peer_id, _)| {
+                    let stats = snarked.and_then(|s| s.peer_stats(peer_id));
+                    let latency = stats.and_then(|s| s.latency);
+                    (
+                        !providers.contains(peer_id),
+                        stats.map_or(false, |s| s.is_unreliable()),
+                        latency.is_none(),
+                        latency,
+                    )
+                });
 
                 // If this dispatches, we can avoid even trying the following steps because we will
                 // not query address unless we have completed the Num_accounts request first.
@@ -117,35 +164,62 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
                     }
                 }
 
+                // Failed addresses, along with the peers that already
+                // attempted them.
                 let mut retry_addresses = store
                     .state()
                     .transition_frontier
                     .sync
                     .ledger()
                     .and_then(|s| s.snarked())
-                    .map_or(vec![], |s| s.sync_address_retry_iter().collect());
-                retry_addresses.reverse();
+                    .map_or(vec![], |s| {
+                        let pending = s.fetch_pending();
+                        s.sync_address_retry_iter()
+                            .map(|addr| {
+                                let attempted = pending
+                                    .and_then(|pending| pending.get(&addr))
+                                    .map(|s| s.attempts.keys().copied().collect::<BTreeSet<_>>())
+                                    .unwrap_or_default();
+                                (addr, attempted)
+                            })
+                            .collect()
+                    });
 
                 for (peer_id, _) in peer_ids {
-                    if let Some(address) = retry_addresses.last() {
+                    let Some(snarked) = store
+                        .state()
+                        .transition_frontier
+                        .sync
+                        .ledger()
+                        .and_then(|s| s.snarked())
+                    else {
+                        break;
+                    };
+                    if !snarked.can_query_address() {
+                        break;
+                    }
+                    let address = snarked.sync_address_next();
+
+                    // Retry with a peer that didn't attempt the address yet,
+                    // or with any peer once there is nothing new to query.
+                    let retry_index = retry_addresses
+                        .iter()
+                        .position(|(_, attempted)| !attempted.contains(&peer_id))
+                        .or_else(|| {
+                            (address.is_none() && !retry_addresses.is_empty()).then_some(0)
+                        });
+                    if let Some(i) = retry_index {
                         if store.dispatch(
                             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressRetry {
                                 peer_id,
-                                address: address.clone(),
+                                address: retry_addresses[i].0.clone(),
                             },
                         ) {
-                            retry_addresses.pop();
+                            retry_addresses.remove(i);
                             continue;
                         }
                     }
 
-                    let address = store
-                        .state()
-                        .transition_frontier
-                        .sync
-                        .ledger()
-                        .and_then(|s| s.snarked())
-                        .and_then(|s| s.sync_address_next());
                     match address {
                         Some((address, expected_hash)) => {
                             // This dispatch here will pop from the queue and update sync_next
@@ -255,14 +329,66 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
                 store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncPending);
             }
 
-            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncPending => {
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncPending
+            | TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncResume { .. } => {
                 if !store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery) {
                     store
                         .dispatch(TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncSuccess);
                 }
             }
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncCheckpoint => {
+                let Some(checkpoint) = None.or_else(|| {
+                    let snarked = store.state().transition_frontier.sync.ledger()?.snarked()?;
+                    snarked.checkpoint()
+                }) else {
+                    return;
+                };
+                store
+                    .service()
+                    .snarked_ledger_sync_checkpoint_save(checkpoint);
+            }
             TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncSuccess => {
-                store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::Success);
+                let Some(snarked_ledger_hash) = None.or_else(|| {
+                    let snarked = store.state().transition_frontier.sync.ledger()?.snarked()?;
+                    Some(snarked.ledger_hash().clone())
+                }) else {
+                    return;
+                };
+                // Every synced subtree was verified against its parent hash,
+                // but the whole ledger is verified once more before it is used.
+                let merkle_root = store
+                    .service()
+                    .compute_snarked_ledger_hashes(&snarked_ledger_hash);
+                match merkle_root {
+                    Ok(merkle_root) if merkle_root == snarked_ledger_hash => {
+                        store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::Success);
+                    }
+                    Ok(merkle_root) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncRootMismatch {
+                                merkle_root,
+                            },
+                        );
+                    }
+                    Err(error) => {
+                        openmina_core::log::error!(meta.time();
+                            kind = "LedgerSyncRootError",
+                            summary = format!("failed to compute the merkle root of the synced ledger {snarked_ledger_hash}"),
+                            error = error);
+                    }
+                }
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncRootMismatch { .. } => {
+                let Some(snarked_ledger_hash) = None.or_else(|| {
+                    let snarked = store.state().transition_frontier.sync.ledger()?.snarked()?;
+                    Some(snarked.ledger_hash().clone())
+                }) else {
+                    return;
+                };
+                store
+                    .service()
+                    .snarked_ledger_sync_checkpoint_remove(snarked_ledger_hash);
+                store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
             }
 
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressInit {
@@ -398,15 +524,19 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
                     return;
                 };
 
-                // After setting the accounts, we get the new computed hash.
-                // It must be equal to the parent node hash, otherwise we got
-                // bad data from the peer.
-                let computed_hash = store
+                // The accounts are only set if their computed hash is equal
+                // to the parent node hash, otherwise we got bad data from the peer.
+                let is_valid = store
                     .service
-                    .accounts_set(snarked_ledger_hash.clone(), address, accounts.clone())
-                    .unwrap();
+                    .accounts_set(
+                        snarked_ledger_hash.clone(),
+                        address,
+                        parent_hash.clone(),
+                        accounts.clone(),
+                    )
+                    .map_or(false, |computed_hash| computed_hash == parent_hash);
 
-                if computed_hash != parent_hash {
+                if !is_valid {
                     store.dispatch(
                         TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected {
                             address: address.clone(),
@@ -445,7 +575,17 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
                 store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressPending { .. } => {}
-            TransitionFrontierSyncLedgerSnarkedAction::Success => {}
+            TransitionFrontierSyncLedgerSnarkedAction::Success => {
+                let Some(snarked_ledger_hash) = None.or_else(|| {
+                    let snarked = store.state().transition_frontier.sync.ledger()?.snarked()?;
+                    Some(snarked.ledger_hash().clone())
+                }) else {
+                    return;
+                };
+                store
+                    .service()
+                    .snarked_ledger_sync_checkpoint_remove(snarked_ledger_hash);
+            }
         }
     }
 }
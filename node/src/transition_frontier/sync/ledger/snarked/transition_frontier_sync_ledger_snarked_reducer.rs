@@ -1,11 +1,15 @@
+use std::collections::BTreeMap;
 use std::iter;
 
-use crate::ledger::{ledger_empty_hash_at_depth, tree_height_for_num_accounts, LEDGER_DEPTH};
+use crate::ledger::{
+    ledger_empty_hash_at_depth, tree_height_for_num_accounts, LedgerAddress, LEDGER_DEPTH,
+};
 
 use super::{
-    LedgerAddressQuery, LedgerAddressQueryPending, PeerRpcState,
-    TransitionFrontierSyncLedgerSnarkedAction,
+    LedgerAddressQuery, LedgerAddressQueryPending, LedgerSyncRangeProgress, PeerLedgerQueryError,
+    PeerRpcState, TransitionFrontierSyncLedgerSnarkedAction,
     TransitionFrontierSyncLedgerSnarkedActionWithMetaRef, TransitionFrontierSyncLedgerSnarkedState,
+    LEDGER_SYNC_IN_FLIGHT_INIT, LEDGER_SYNC_IN_FLIGHT_MAX, LEDGER_SYNC_IN_FLIGHT_MIN,
 };
 
 impl TransitionFrontierSyncLedgerSnarkedState {
@@ -140,8 +144,48 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                     synced_hashes_count: 0,
                     queue: iter::once(first_query).collect(),
                     pending_addresses: Default::default(),
+                    peers: Default::default(),
+                    max_in_flight: LEDGER_SYNC_IN_FLIGHT_INIT,
+                    ranges: Default::default(),
+                    checkpoint_time: meta.time(),
+                };
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncResume { checkpoint } => {
+                let Self::NumAccountsPending { target, .. } = self else {
+                    return;
+                };
+
+                let mut ranges = BTreeMap::new();
+                for query in &checkpoint.frontier {
+                    if let Some(range) =
+                        range_get_mut(&mut ranges, &query.address, checkpoint.num_accounts)
+                    {
+                        range.queued += 1;
+                    }
+                }
+
+                *self = Self::MerkleTreeSyncPending {
+                    time: meta.time(),
+                    target: target.clone(),
+                    total_accounts_expected: checkpoint.num_accounts,
+                    synced_accounts_count: checkpoint.synced_accounts_count,
+                    synced_hashes_count: checkpoint.synced_hashes_count,
+                    queue: checkpoint.frontier.iter().cloned().collect(),
+                    pending_addresses: Default::default(),
+                    peers: Default::default(),
+                    max_in_flight: LEDGER_SYNC_IN_FLIGHT_INIT,
+                    ranges,
+                    checkpoint_time: meta.time(),
                 };
             }
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncCheckpoint => {
+                if let Self::MerkleTreeSyncPending {
+                    checkpoint_time, ..
+                } = self
+                {
+                    *checkpoint_time = meta.time();
+                }
+            }
             TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncSuccess => {
                 let Self::MerkleTreeSyncPending { target, .. } = self else {
                     return;
@@ -151,6 +195,12 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                     target: target.clone(),
                 };
             }
+            TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncRootMismatch { .. } => {
+                let Self::MerkleTreeSyncSuccess { target, .. } = self else {
+                    return;
+                };
+                *self = Self::pending(meta.time(), target.clone());
+            }
 
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressInit {
                 address,
@@ -158,14 +208,21 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 peer_id,
             } => {
                 if let Self::MerkleTreeSyncPending {
+                    total_accounts_expected,
                     queue,
                     pending_addresses: pending,
+                    ranges,
                     ..
                 } = self
                 {
                     let _next = queue.pop_front();
                     //debug_assert_eq!(next.as_ref().map(|p| &p.0), Some(address));
 
+                    if let Some(range) = range_get_mut(ranges, address, *total_accounts_expected) {
+                        range.queued = range.queued.saturating_sub(1);
+                        range.pending += 1;
+                    }
+
                     pending.insert(
                         address.clone(),
                         LedgerAddressQueryPending {
@@ -229,12 +286,36 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 else {
                     return;
                 };
+                let sent_time = match rpc_state {
+                    PeerRpcState::Pending { time, .. } => Some(*time),
+                    _ => None,
+                };
 
                 *rpc_state = PeerRpcState::Error {
                     time: meta.time(),
                     rpc_id: *rpc_id,
                     error: error.clone(),
                 };
+
+                let Self::MerkleTreeSyncPending {
+                    peers,
+                    max_in_flight,
+                    ..
+                } = self
+                else {
+                    return;
+                };
+                let peer = peers.entry(*peer_id).or_default();
+                peer.errors += 1;
+                if let PeerLedgerQueryError::Timeout = error {
+                    // The peer took at least this long, so let its
+                    // latency (and timeout) grow accordingly.
+                    if let Some(latency) = sent_time.and_then(|t| meta.time().checked_sub(t)) {
+                        peer.latency_update(latency);
+                    }
+                    peer.timeouts += 1;
+                    *max_in_flight = (*max_in_flight / 2).max(LEDGER_SYNC_IN_FLIGHT_MIN);
+                }
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressSuccess {
                 peer_id,
@@ -245,10 +326,31 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 else {
                     return;
                 };
+                // Late responses, received after the query timed out,
+                // don't update the latency, the timeout already did.
+                let sent_time = match rpc_state {
+                    PeerRpcState::Pending { time, .. } => Some(*time),
+                    _ => None,
+                };
                 *rpc_state = PeerRpcState::Success {
                     time: meta.time(),
                     rpc_id: *rpc_id,
                 };
+
+                let Self::MerkleTreeSyncPending {
+                    peers,
+                    max_in_flight,
+                    ..
+                } = self
+                else {
+                    return;
+                };
+                let peer = peers.entry(*peer_id).or_default();
+                peer.successes += 1;
+                if let Some(latency) = sent_time.and_then(|t| meta.time().checked_sub(t)) {
+                    peer.latency_update(latency);
+                }
+                *max_in_flight = (*max_in_flight + 1).min(LEDGER_SYNC_IN_FLIGHT_MAX);
             }
             TransitionFrontierSyncLedgerSnarkedAction::ChildHashesReceived { .. } => {}
             TransitionFrontierSyncLedgerSnarkedAction::ChildHashesAccepted {
@@ -258,9 +360,11 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 ..
             } => {
                 let Self::MerkleTreeSyncPending {
+                    total_accounts_expected,
                     queue,
                     pending_addresses: pending,
                     synced_hashes_count: num_hashes_accepted,
+                    ranges,
                     ..
                 } = self
                 else {
@@ -269,6 +373,9 @@ impl TransitionFrontierSyncLedgerSnarkedState {
 
                 // Once hashes are accepted, we can consider this query fulfilled
                 pending.remove(address);
+                if let Some(range) = range_get_mut(ranges, address, *total_accounts_expected) {
+                    range.pending = range.pending.saturating_sub(1);
+                }
 
                 let (left, right) = hashes;
                 let (previous_left, previous_right) = previous_hashes;
@@ -282,21 +389,58 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 let empty = ledger_empty_hash_at_depth(address.length() + 1);
                 *num_hashes_accepted += (*left != empty) as u64 + (*right != empty) as u64;
 
-                if left != previous_left {
-                    queue.push_back(LedgerAddressQuery {
-                        address: address.child_left(),
-                        expected_hash: left.clone(),
-                    });
-                }
-                if right != previous_right {
-                    queue.push_back(LedgerAddressQuery {
-                        address: address.child_right(),
-                        expected_hash: right.clone(),
-                    });
+                let children = [
+                    (address.child_left(), left, previous_left),
+                    (address.child_right(), right, previous_right),
+                ];
+                for (child, hash, previous_hash) in children {
+                    if let Some(range) = range_get_mut(ranges, &child, *total_accounts_expected) {
+                        range.synced_hashes += (*hash != empty) as u64;
+                        range.queued += (hash != previous_hash) as usize;
+                    }
+                    if hash != previous_hash {
+                        queue.push_back(LedgerAddressQuery {
+                            address: child,
+                            expected_hash: hash.clone(),
+                        });
+                    }
                 }
             }
-            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected { .. } => {
-                // TODO(tizoc): should this be reflected in the state somehow?
+            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected {
+                address,
+                sender,
+                ..
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected {
+                address,
+                sender,
+            } => {
+                let Self::MerkleTreeSyncPending {
+                    pending_addresses: pending,
+                    peers,
+                    ..
+                } = self
+                else {
+                    return;
+                };
+                peers.entry(*sender).or_default().invalid += 1;
+
+                // Mark the attempt as failed, so that the address gets
+                // retried with another peer.
+                let Some(rpc_state) = pending
+                    .get_mut(address)
+                    .and_then(|s| s.attempts.get_mut(sender))
+                else {
+                    return;
+                };
+                let Some(rpc_id) = rpc_state.rpc_id() else {
+                    return;
+                };
+                *rpc_state = PeerRpcState::Error {
+                    time: meta.time(),
+                    rpc_id,
+                    error: PeerLedgerQueryError::InvalidData,
+                };
             }
             TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsReceived { .. } => {}
             TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsAccepted {
@@ -305,8 +449,10 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 ..
             } => {
                 let Self::MerkleTreeSyncPending {
+                    total_accounts_expected,
                     pending_addresses: pending,
                     synced_accounts_count,
+                    ranges,
                     ..
                 } = self
                 else {
@@ -315,9 +461,11 @@ impl TransitionFrontierSyncLedgerSnarkedState {
 
                 *synced_accounts_count += count;
                 pending.remove(address);
-            }
-            TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected { .. } => {
-                // TODO(tizoc): should this be reflected in the state somehow?
+
+                if let Some(range) = range_get_mut(ranges, address, *total_accounts_expected) {
+                    range.synced_accounts += count;
+                    range.pending = range.pending.saturating_sub(1);
+                }
             }
             TransitionFrontierSyncLedgerSnarkedAction::Success => {
                 let Self::MerkleTreeSyncSuccess { target, .. } = self else {
@@ -331,3 +479,12 @@ impl TransitionFrontierSyncLedgerSnarkedState {
         }
     }
 }
+
+fn range_get_mut<'a>(
+    ranges: &'a mut BTreeMap<LedgerAddress, LedgerSyncRangeProgress>,
+    address: &LedgerAddress,
+    num_accounts: u64,
+) -> Option<&'a mut LedgerSyncRangeProgress> {
+    let range = LedgerSyncRangeProgress::range_address(address, num_accounts)?;
+    Some(ranges.entry(range).or_default())
+}
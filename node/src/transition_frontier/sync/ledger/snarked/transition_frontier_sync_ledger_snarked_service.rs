@@ -2,11 +2,15 @@ use mina_p2p_messages::v2::{LedgerHash, MinaBaseAccountBinableArgStableV2};
 
 use crate::ledger::LedgerAddress;
 
+use super::SnarkedLedgerSyncCheckpoint;
+
 pub trait TransitionFrontierSyncLedgerSnarkedService: redux::Service {
     /// For the given ledger, compute the merkle root hash, forcing
     /// all pending hashes to be computed too.
-    fn compute_snarked_ledger_hashes(&self, snarked_ledger_hash: &LedgerHash)
-        -> Result<(), String>;
+    fn compute_snarked_ledger_hashes(
+        &self,
+        snarked_ledger_hash: &LedgerHash,
+    ) -> Result<LedgerHash, String>;
 
     /// Creates a new copy of the ledger stored under the `origin` hash
     /// and stores it under the `target` hash. If `overwrite` is false,
@@ -27,12 +31,21 @@ pub trait TransitionFrontierSyncLedgerSnarkedService: redux::Service {
     ) -> Result<(LedgerHash, LedgerHash), String>;
 
     /// For the given ledger, sets all accounts in `accounts` under
-    /// the subtree starting at the `parent` address. The result
-    /// is the hash computed for that subtree.
+    /// the subtree starting at the `parent` address, if the hash computed
+    /// for that subtree matches `expected_hash`. The result is the
+    /// computed hash.
     fn accounts_set(
         &self,
         snarked_ledger_hash: LedgerHash,
         parent: &LedgerAddress,
+        expected_hash: LedgerHash,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
     ) -> Result<LedgerHash, String>;
+
+    /// Persists the sync progress along with the accounts synced so far,
+    /// replacing the previous checkpoint.
+    fn snarked_ledger_sync_checkpoint_save(&self, checkpoint: SnarkedLedgerSyncCheckpoint);
+
+    /// Removes the persisted checkpoint of the ledger, once it is synced.
+    fn snarked_ledger_sync_checkpoint_remove(&self, snarked_ledger_hash: LedgerHash);
 }
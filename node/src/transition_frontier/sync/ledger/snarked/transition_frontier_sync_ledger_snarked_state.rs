@@ -1,10 +1,11 @@
 use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
 
 use mina_p2p_messages::v2::LedgerHash;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use crate::ledger::{tree_height_for_num_accounts, LedgerAddress};
+use crate::ledger::{tree_height_for_num_accounts, LedgerAddress, LEDGER_DEPTH};
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
 use crate::rpc::LedgerSyncProgress;
@@ -12,6 +13,24 @@ use crate::transition_frontier::sync::ledger::SyncLedgerTarget;
 
 use super::{PeerLedgerQueryError, ACCOUNT_SUBTREE_HEIGHT};
 
+/// Initial limit of the address queries in flight.
+pub const LEDGER_SYNC_IN_FLIGHT_INIT: usize = 8;
+pub const LEDGER_SYNC_IN_FLIGHT_MIN: usize = 2;
+pub const LEDGER_SYNC_IN_FLIGHT_MAX: usize = 128;
+
+/// Address query times out after the peer's average latency multiplied by
+/// this factor, but no sooner than [`LEDGER_QUERY_TIMEOUT_MIN`] and no
+/// later than the p2p `ledger_query` timeout.
+const LEDGER_QUERY_TIMEOUT_LATENCY_FACTOR: u32 = 4;
+pub const LEDGER_QUERY_TIMEOUT_MIN: Duration = Duration::from_millis(500);
+
+/// Depth, relative to the root of the queried tree, of the address
+/// ranges for which the sync progress is tracked.
+pub const LEDGER_SYNC_RANGE_DEPTH: usize = 3;
+
+/// Interval between the checkpoints of the snarked ledger sync.
+pub const LEDGER_SYNC_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
 static SYNC_PENDING_EMPTY: BTreeMap<LedgerAddress, LedgerAddressQueryPending> = BTreeMap::new();
 
 #[serde_with::serde_as]
@@ -45,6 +64,16 @@ pub enum TransitionFrontierSyncLedgerSnarkedState {
         /// Pending ongoing address queries and their attempts
         #[serde_as(as = "Vec<(_, _)>")]
         pending_addresses: BTreeMap<LedgerAddress, LedgerAddressQueryPending>,
+        /// Latency and validity of the responses of the queried peers.
+        peers: BTreeMap<PeerId, LedgerSyncPeerStats>,
+        /// Limit of the address queries in flight. Grows with each
+        /// successful query and is halved on a timeout.
+        max_in_flight: usize,
+        /// Sync progress per address range.
+        #[serde_as(as = "Vec<(_, _)>")]
+        ranges: BTreeMap<LedgerAddress, LedgerSyncRangeProgress>,
+        /// Time of the last checkpoint, or of the sync start.
+        checkpoint_time: Timestamp,
     },
     MerkleTreeSyncSuccess {
         time: Timestamp,
@@ -62,6 +91,20 @@ pub struct LedgerAddressQuery {
     pub expected_hash: LedgerHash,
 }
 
+/// Progress of the snarked ledger sync, persisted so that the sync of the
+/// same ledger can be resumed after a restart. The synced accounts are
+/// persisted along with it by the ledger service.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkedLedgerSyncCheckpoint {
+    pub snarked_ledger_hash: LedgerHash,
+    pub num_accounts: u64,
+    pub synced_accounts_count: u64,
+    pub synced_hashes_count: u64,
+    /// Addresses which aren't synced yet, along with their hashes, which
+    /// were verified against the hashes of their parents.
+    pub frontier: Vec<LedgerAddressQuery>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LedgerAddressQueryPending {
     pub time: Timestamp,
@@ -69,6 +112,27 @@ pub struct LedgerAddressQueryPending {
     pub attempts: BTreeMap<PeerId, PeerRpcState>,
 }
 
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct LedgerSyncPeerStats {
+    /// Moving average of the time it takes the peer to answer a query.
+    pub latency: Option<Duration>,
+    pub successes: u32,
+    pub errors: u32,
+    pub timeouts: u32,
+    /// Responses that didn't match the expected hash.
+    pub invalid: u32,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct LedgerSyncRangeProgress {
+    pub synced_hashes: u64,
+    pub synced_accounts: u64,
+    /// Address queries waiting in the queue.
+    pub queued: usize,
+    /// Address queries sent to peers.
+    pub pending: usize,
+}
+
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct LedgerNumAccountsQueryPending {
     pub attempts: BTreeMap<PeerId, PeerRpcState>,
@@ -124,6 +188,50 @@ impl PeerRpcState {
     }
 }
 
+impl LedgerSyncPeerStats {
+    pub fn latency_update(&mut self, latency: Duration) {
+        self.latency = Some(match self.latency {
+            None => latency,
+            Some(avg) => (avg * 3 + latency) / 4,
+        });
+    }
+
+    /// Peer sent data that failed verification, so it is not queried again.
+    pub fn is_invalid(&self) -> bool {
+        self.invalid > 0
+    }
+
+    /// Peers that fail more often than they succeed are queried last.
+    pub fn is_unreliable(&self) -> bool {
+        self.errors > self.successes
+    }
+
+    pub fn query_timeout(&self, max: Duration) -> Duration {
+        self.latency.map_or(max, |latency| {
+            (latency * LEDGER_QUERY_TIMEOUT_LATENCY_FACTOR)
+                .max(LEDGER_QUERY_TIMEOUT_MIN)
+                .min(max)
+        })
+    }
+}
+
+impl LedgerSyncRangeProgress {
+    /// Address of the range containing `address`, if `address` isn't
+    /// above the ranges' depth.
+    pub fn range_address(address: &LedgerAddress, num_accounts: u64) -> Option<LedgerAddress> {
+        let root_depth = LEDGER_DEPTH - tree_height_for_num_accounts(num_accounts);
+        let depth = (root_depth + LEDGER_SYNC_RANGE_DEPTH)
+            .min(LEDGER_DEPTH - ACCOUNT_SUBTREE_HEIGHT)
+            .max(root_depth);
+        let shift = address.length().checked_sub(depth)?;
+        let index = address.to_index().0 >> shift;
+        Some(LedgerAddress::from_index(
+            ledger::AccountIndex(index),
+            depth,
+        ))
+    }
+}
+
 impl TransitionFrontierSyncLedgerSnarkedState {
     pub fn pending(time: Timestamp, target: SyncLedgerTarget) -> Self {
         Self::NumAccountsPending {
@@ -193,6 +301,105 @@ impl TransitionFrontierSyncLedgerSnarkedState {
             .map(|(addr, _)| addr.clone())
     }
 
+    pub fn peer_stats(&self, peer_id: &PeerId) -> Option<&LedgerSyncPeerStats> {
+        match self {
+            Self::MerkleTreeSyncPending { peers, .. } => peers.get(peer_id),
+            _ => None,
+        }
+    }
+
+    pub fn ranges(&self) -> Option<&BTreeMap<LedgerAddress, LedgerSyncRangeProgress>> {
+        match self {
+            Self::MerkleTreeSyncPending { ranges, .. } => Some(ranges),
+            _ => None,
+        }
+    }
+
+    /// Number of address queries sent, or about to be sent, to peers.
+    pub fn address_queries_in_flight(&self) -> usize {
+        self.fetch_pending().map_or(0, |pending| {
+            pending
+                .values()
+                .flat_map(|s| s.attempts.values())
+                .filter(|s| matches!(s, PeerRpcState::Init { .. } | PeerRpcState::Pending { .. }))
+                .count()
+        })
+    }
+
+    /// Whether another address query can be sent without exceeding the
+    /// limit of queries in flight.
+    pub fn can_query_address(&self) -> bool {
+        match self {
+            Self::MerkleTreeSyncPending { max_in_flight, .. } => {
+                self.address_queries_in_flight() < *max_in_flight
+            }
+            _ => false,
+        }
+    }
+
+    /// Pending address queries that the peer didn't answer within its
+    /// adaptive timeout.
+    pub fn address_query_timeouts_iter(
+        &self,
+        now: Timestamp,
+        max_timeout: Duration,
+    ) -> impl '_ + Iterator<Item = (PeerId, P2pRpcId)> {
+        let pending = self.fetch_pending().unwrap_or(&SYNC_PENDING_EMPTY);
+        pending
+            .values()
+            .flat_map(|s| s.attempts.iter())
+            .filter_map(move |(peer_id, s)| match s {
+                PeerRpcState::Pending { time, rpc_id } => {
+                    let timeout = self
+                        .peer_stats(peer_id)
+                        .map_or(max_timeout, |stats| stats.query_timeout(max_timeout));
+                    (now.checked_sub(*time)? >= timeout).then_some((*peer_id, *rpc_id))
+                }
+                _ => None,
+            })
+    }
+
+    /// Whether the progress is due to be persisted.
+    pub fn is_checkpoint_due(&self, now: Timestamp) -> bool {
+        match self {
+            Self::MerkleTreeSyncPending {
+                checkpoint_time, ..
+            } => now
+                .checked_sub(*checkpoint_time)
+                .map_or(false, |d| d >= LEDGER_SYNC_CHECKPOINT_INTERVAL),
+            _ => false,
+        }
+    }
+
+    pub fn checkpoint(&self) -> Option<SnarkedLedgerSyncCheckpoint> {
+        match self {
+            Self::MerkleTreeSyncPending {
+                target,
+                total_accounts_expected,
+                synced_accounts_count,
+                synced_hashes_count,
+                queue,
+                pending_addresses,
+                ..
+            } => {
+                let pending = pending_addresses
+                    .iter()
+                    .map(|(address, s)| LedgerAddressQuery {
+                        address: address.clone(),
+                        expected_hash: s.expected_hash.clone(),
+                    });
+                Some(SnarkedLedgerSyncCheckpoint {
+                    snarked_ledger_hash: target.snarked_ledger_hash.clone(),
+                    num_accounts: *total_accounts_expected,
+                    synced_accounts_count: *synced_accounts_count,
+                    synced_hashes_count: *synced_hashes_count,
+                    frontier: pending.chain(queue.iter().cloned()).collect(),
+                })
+            }
+            _ => None,
+        }
+    }
+
     pub fn sync_address_next(&self) -> Option<(LedgerAddress, LedgerHash)> {
         match self {
             Self::MerkleTreeSyncPending { queue, .. } => match queue.front().cloned() {
@@ -363,3 +570,405 @@ impl TransitionFrontierSyncLedgerSnarkedState {
             .and_then(|(_, s)| s.pending_rpc_id())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use redux::ActionMeta;
+
+    use crate::transition_frontier::sync::ledger::SyncLedgerTargetKind;
+
+    use super::super::{
+        PeerLedgerQueryResponse, TransitionFrontierSyncLedgerSnarkedAction as Action,
+    };
+    use super::*;
+
+    const NUM_ACCOUNTS: u64 = 1 << 16;
+
+    fn ms(ms: u64) -> Timestamp {
+        Timestamp::new(ms * 1_000_000)
+    }
+
+    fn hash(n: u64) -> LedgerHash {
+        LedgerHash::from_fp(mina_hasher::Fp::from(n))
+    }
+
+    fn peer(n: u8) -> PeerId {
+        PeerId::from_bytes([n; 32])
+    }
+
+    fn address(index: u64) -> LedgerAddress {
+        let depth = LEDGER_DEPTH - tree_height_for_num_accounts(NUM_ACCOUNTS) + 8;
+        LedgerAddress::from_index(ledger::AccountIndex(index), depth)
+    }
+
+    fn dispatch(state: &mut TransitionFrontierSyncLedgerSnarkedState, time: u64, action: Action) {
+        state.reducer(ActionMeta::zero_custom(ms(time)).with_action(&action));
+    }
+
+    /// Merkle tree sync, resumed with `frontier_len` addresses to query.
+    fn sync_pending(frontier_len: u64) -> TransitionFrontierSyncLedgerSnarkedState {
+        let target = SyncLedgerTarget {
+            kind: SyncLedgerTargetKind::Root,
+            snarked_ledger_hash: hash(0),
+            staged: None,
+        };
+        let mut state = TransitionFrontierSyncLedgerSnarkedState::pending(ms(0), target);
+        let checkpoint = SnarkedLedgerSyncCheckpoint {
+            snarked_ledger_hash: hash(0),
+            num_accounts: NUM_ACCOUNTS,
+            synced_accounts_count: 0,
+            synced_hashes_count: 0,
+            frontier: (0..frontier_len)
+                .map(|i| LedgerAddressQuery {
+                    address: address(i),
+                    expected_hash: hash(i + 1),
+                })
+                .collect(),
+        };
+        dispatch(&mut state, 0, Action::MerkleTreeSyncResume { checkpoint });
+        state
+    }
+
+    /// Sends the next address query to the peer.
+    fn query_next(
+        state: &mut TransitionFrontierSyncLedgerSnarkedState,
+        time: u64,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+    ) -> LedgerAddress {
+        let (address, expected_hash) = state.sync_address_next().unwrap();
+        dispatch(
+            state,
+            time,
+            Action::PeerQueryAddressInit {
+                address: address.clone(),
+                expected_hash,
+                peer_id,
+            },
+        );
+        dispatch(
+            state,
+            time,
+            Action::PeerQueryAddressPending {
+                address: address.clone(),
+                peer_id,
+                rpc_id,
+            },
+        );
+        address
+    }
+
+    fn max_in_flight(state: &TransitionFrontierSyncLedgerSnarkedState) -> usize {
+        match state {
+            TransitionFrontierSyncLedgerSnarkedState::MerkleTreeSyncPending {
+                max_in_flight,
+                ..
+            } => *max_in_flight,
+            _ => panic!("merkle tree sync should be pending"),
+        }
+    }
+
+    #[test]
+    fn test_in_flight_limit() {
+        let mut state = sync_pending(200);
+        let mut rpc_ids = 0..;
+        let mut in_flight = vec![];
+        while state.can_query_address() {
+            let rpc_id = rpc_ids.next().unwrap();
+            query_next(&mut state, 0, peer(1), rpc_id);
+            in_flight.push(rpc_id);
+        }
+        assert_eq!(in_flight.len(), LEDGER_SYNC_IN_FLIGHT_INIT);
+        assert_eq!(
+            state.address_queries_in_flight(),
+            LEDGER_SYNC_IN_FLIGHT_INIT
+        );
+
+        // success lets one more query in flight.
+        let response = PeerLedgerQueryResponse::ChildHashes(hash(0), hash(0));
+        let rpc_id = in_flight.remove(0);
+        dispatch(
+            &mut state,
+            100,
+            Action::PeerQueryAddressSuccess {
+                peer_id: peer(1),
+                rpc_id,
+                response: response.clone(),
+            },
+        );
+        assert_eq!(max_in_flight(&state), LEDGER_SYNC_IN_FLIGHT_INIT + 1);
+        assert_eq!(state.address_queries_in_flight(), in_flight.len());
+        let latency = state.peer_stats(&peer(1)).unwrap().latency;
+        assert_eq!(latency, Some(Duration::from_millis(100)));
+
+        // timeouts halve the limit, down to the minimum.
+        for (i, rpc_id) in in_flight.drain(..).enumerate() {
+            dispatch(
+                &mut state,
+                1_000,
+                Action::PeerQueryAddressError {
+                    peer_id: peer(1),
+                    rpc_id,
+                    error: PeerLedgerQueryError::Timeout,
+                },
+            );
+            let expected =
+                ((LEDGER_SYNC_IN_FLIGHT_INIT + 1) >> (i + 1)).max(LEDGER_SYNC_IN_FLIGHT_MIN);
+            assert_eq!(max_in_flight(&state), expected);
+        }
+        let stats = state.peer_stats(&peer(1)).unwrap();
+        assert_eq!(stats.timeouts, LEDGER_SYNC_IN_FLIGHT_INIT as u32 - 1);
+        assert!(stats.latency.unwrap() > Duration::from_millis(100));
+        assert_eq!(state.address_queries_in_flight(), 0);
+
+        // successes grow the limit, up to the maximum.
+        for rpc_id in rpc_ids.by_ref().take(LEDGER_SYNC_IN_FLIGHT_MAX + 10) {
+            query_next(&mut state, 2_000, peer(2), rpc_id);
+            dispatch(
+                &mut state,
+                2_010,
+                Action::PeerQueryAddressSuccess {
+                    peer_id: peer(2),
+                    rpc_id,
+                    response: response.clone(),
+                },
+            );
+        }
+        assert_eq!(max_in_flight(&state), LEDGER_SYNC_IN_FLIGHT_MAX);
+    }
+
+    #[test]
+    fn test_query_timeout() {
+        let max = Duration::from_secs(8);
+        let mut stats = LedgerSyncPeerStats::default();
+        assert_eq!(stats.query_timeout(max), max);
+
+        stats.latency_update(Duration::from_millis(10));
+        assert_eq!(stats.query_timeout(max), LEDGER_QUERY_TIMEOUT_MIN);
+
+        stats.latency = Some(Duration::from_secs(1));
+        assert_eq!(stats.query_timeout(max), Duration::from_secs(4));
+        stats.latency_update(Duration::from_secs(5));
+        assert_eq!(stats.latency, Some(Duration::from_secs(2)));
+        assert_eq!(stats.query_timeout(max), max);
+    }
+
+    #[test]
+    fn test_address_query_timeouts_iter() {
+        let max_timeout = Duration::from_secs(10);
+        let mut state = sync_pending(10);
+
+        // peer 1 answers within 200ms.
+        query_next(&mut state, 0, peer(1), 0);
+        dispatch(
+            &mut state,
+            200,
+            Action::PeerQueryAddressSuccess {
+                peer_id: peer(1),
+                rpc_id: 0,
+                response: PeerLedgerQueryResponse::ChildHashes(hash(0), hash(0)),
+            },
+        );
+
+        query_next(&mut state, 1_000, peer(1), 1);
+        query_next(&mut state, 1_000, peer(2), 2);
+        let timeouts = |state: &TransitionFrontierSyncLedgerSnarkedState, time| {
+            state
+                .address_query_timeouts_iter(ms(time), max_timeout)
+                .collect::<Vec<_>>()
+        };
+        assert!(timeouts(&state, 1_799).is_empty());
+        assert_eq!(timeouts(&state, 1_800), vec![(peer(1), 1)]);
+        // peer without the latency gets the maximum timeout.
+        assert_eq!(timeouts(&state, 10_999), vec![(peer(1), 1)]);
+        let mut all = timeouts(&state, 11_000);
+        all.sort();
+        assert_eq!(all, vec![(peer(1), 1), (peer(2), 2)]);
+    }
+
+    #[test]
+    fn test_address_query_retry_with_another_peer() {
+        let mut state = sync_pending(10);
+        let address = query_next(&mut state, 0, peer(1), 0);
+        assert!(state.sync_address_retry_iter().next().is_none());
+
+        dispatch(
+            &mut state,
+            100,
+            Action::PeerQueryAddressError {
+                peer_id: peer(1),
+                rpc_id: 0,
+                error: PeerLedgerQueryError::DataUnavailable,
+            },
+        );
+        assert_eq!(
+            state.sync_address_retry_iter().collect::<Vec<_>>(),
+            vec![address.clone()]
+        );
+        assert_eq!(state.address_queries_in_flight(), 0);
+
+        dispatch(
+            &mut state,
+            200,
+            Action::PeerQueryAddressRetry {
+                address: address.clone(),
+                peer_id: peer(2),
+            },
+        );
+        assert!(state.sync_address_retry_iter().next().is_none());
+        assert_eq!(state.address_queries_in_flight(), 1);
+        let attempts = &state.fetch_pending().unwrap()[&address].attempts;
+        assert!(attempts[&peer(1)].is_error());
+        assert!(matches!(attempts[&peer(2)], PeerRpcState::Init { .. }));
+
+        // invalid response makes the address to be retried again, and the
+        // peer is marked as invalid.
+        dispatch(
+            &mut state,
+            200,
+            Action::PeerQueryAddressPending {
+                address: address.clone(),
+                peer_id: peer(2),
+                rpc_id: 1,
+            },
+        );
+        dispatch(
+            &mut state,
+            300,
+            Action::ChildHashesRejected {
+                address: address.clone(),
+                hashes: (hash(0), hash(0)),
+                sender: peer(2),
+            },
+        );
+        assert_eq!(
+            state.sync_address_retry_iter().collect::<Vec<_>>(),
+            vec![address]
+        );
+        assert!(state.peer_stats(&peer(2)).unwrap().is_invalid());
+        assert!(!state.peer_stats(&peer(1)).unwrap().is_invalid());
+    }
+
+    #[test]
+    fn test_range_address() {
+        let num_accounts = 1000;
+        let range_address =
+            |addr: &LedgerAddress| LedgerSyncRangeProgress::range_address(addr, num_accounts);
+        let root_depth = LEDGER_DEPTH - tree_height_for_num_accounts(num_accounts);
+        let root = LedgerAddress::first(root_depth);
+        assert_eq!(range_address(&root), None);
+        assert_eq!(range_address(&root.child_right()), None);
+
+        let range = LedgerAddress::from_index(
+            ledger::AccountIndex(5),
+            root_depth + LEDGER_SYNC_RANGE_DEPTH,
+        );
+        assert_eq!(range_address(&range), Some(range.clone()));
+        assert_eq!(
+            range_address(&range.child_right().child_left()),
+            Some(range.clone())
+        );
+        assert_eq!(
+            range_address(&range.child_left().child_right()),
+            Some(range)
+        );
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let mut state = sync_pending(10);
+        query_next(&mut state, 0, peer(1), 0);
+        // addresses in flight are persisted along with the queued ones.
+        let checkpoint = SnarkedLedgerSyncCheckpoint {
+            synced_accounts_count: 64,
+            synced_hashes_count: 100,
+            ..state.checkpoint().unwrap()
+        };
+        let frontier = |checkpoint: &SnarkedLedgerSyncCheckpoint| {
+            checkpoint
+                .frontier
+                .iter()
+                .map(|query| (query.address.clone(), query.expected_hash.clone()))
+                .collect::<Vec<_>>()
+        };
+        let expected_frontier = (0..10)
+            .map(|i| (address(i), hash(i + 1)))
+            .collect::<Vec<_>>();
+        assert_eq!(frontier(&checkpoint), expected_frontier);
+
+        let mut resumed =
+            TransitionFrontierSyncLedgerSnarkedState::pending(ms(500), state.target().clone());
+        dispatch(
+            &mut resumed,
+            1_000,
+            Action::MerkleTreeSyncResume {
+                checkpoint: checkpoint.clone(),
+            },
+        );
+        let TransitionFrontierSyncLedgerSnarkedState::MerkleTreeSyncPending {
+            total_accounts_expected,
+            synced_accounts_count,
+            synced_hashes_count,
+            queue,
+            pending_addresses,
+            max_in_flight,
+            ranges,
+            checkpoint_time,
+            ..
+        } = &resumed
+        else {
+            panic!("merkle tree sync should be pending");
+        };
+        assert_eq!(*total_accounts_expected, NUM_ACCOUNTS);
+        assert_eq!(*synced_accounts_count, 64);
+        assert_eq!(*synced_hashes_count, 100);
+        assert_eq!(queue.len(), 10);
+        assert!(pending_addresses.is_empty());
+        assert_eq!(*max_in_flight, LEDGER_SYNC_IN_FLIGHT_INIT);
+        assert_eq!(*checkpoint_time, ms(1_000));
+        // all the addresses are within the first range.
+        let range = LedgerSyncRangeProgress::range_address(&address(0), NUM_ACCOUNTS).unwrap();
+        assert_eq!(ranges.keys().collect::<Vec<_>>(), vec![&range]);
+        assert_eq!(ranges[&range].queued, 10);
+        assert_eq!(ranges[&range].pending, 0);
+
+        assert_eq!(frontier(&resumed.checkpoint().unwrap()), expected_frontier);
+        assert_eq!(resumed.sync_address_next(), Some((address(0), hash(1))));
+    }
+
+    #[test]
+    fn test_is_checkpoint_due() {
+        let target = sync_pending(0).target().clone();
+        let state = TransitionFrontierSyncLedgerSnarkedState::pending(ms(0), target);
+        assert!(!state.is_checkpoint_due(ms(60_000)));
+
+        let mut state = sync_pending(1);
+        assert!(!state.is_checkpoint_due(ms(29_999)));
+        assert!(state.is_checkpoint_due(ms(30_000)));
+
+        dispatch(&mut state, 30_000, Action::MerkleTreeSyncCheckpoint);
+        assert!(!state.is_checkpoint_due(ms(59_999)));
+        assert!(state.is_checkpoint_due(ms(60_000)));
+    }
+
+    #[test]
+    fn test_root_mismatch_restarts_sync() {
+        let mut state = sync_pending(0);
+        dispatch(&mut state, 100, Action::MerkleTreeSyncSuccess);
+        assert!(matches!(
+            state,
+            TransitionFrontierSyncLedgerSnarkedState::MerkleTreeSyncSuccess { .. }
+        ));
+
+        dispatch(
+            &mut state,
+            200,
+            Action::MerkleTreeSyncRootMismatch {
+                merkle_root: hash(1),
+            },
+        );
+        assert!(state.is_num_accounts_query_next());
+        assert_eq!(state.ledger_hash(), &hash(0));
+        assert!(state.num_accounts_pending().unwrap().attempts.is_empty());
+    }
+}
@@ -25,7 +25,7 @@ impl TransitionFrontierSyncLedgerStagedAction {
                 let block_hash = staged_ledger.target().staged.block_hash.clone();
 
                 let ready_peers = staged_ledger
-                    .filter_available_peers(p2p.ready_rpc_peers_iter())
+                    .filter_available_peers(p2p.ready_rpc_peers_by_rtt().into_iter())
                     .collect::<Vec<_>>();
 
                 for (peer_id, rpc_id) in ready_peers {
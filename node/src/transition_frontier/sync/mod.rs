@@ -17,4 +17,5 @@ pub enum PeerBlockFetchError {
     Timeout,
     Disconnected,
     DataUnavailable,
+    InvalidChainProof,
 }
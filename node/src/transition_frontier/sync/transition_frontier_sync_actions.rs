@@ -5,7 +5,7 @@ use openmina_core::ActionEvent;
 use serde::{Deserialize, Serialize};
 
 use crate::ledger::write::CommitResult;
-use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::channels::rpc::{P2pRpcId, TransitionChainProof};
 use crate::p2p::PeerId;
 use crate::transition_frontier::sync::TransitionFrontierSyncLedgerPending;
 use crate::TransitionFrontierAction;
@@ -58,16 +58,30 @@ pub enum TransitionFrontierSyncAction {
     LedgerRootSuccess,
     BlocksPending,
     BlocksPeersQuery,
+    /// Fetch the batch of blocks from the peer, starting with the
+    /// transition chain proof for them.
     BlocksPeerQueryInit {
-        hash: StateHash,
+        hashes: Vec<StateHash>,
         peer_id: PeerId,
     },
     BlocksPeerQueryRetry {
-        hash: StateHash,
+        hashes: Vec<StateHash>,
+        peer_id: PeerId,
+    },
+    BlocksPeerQueryProofPending {
+        hashes: Vec<StateHash>,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+    },
+    /// Transition chain proof received. Blocks are requested from the peer
+    /// only if it proves that they are part of the chain.
+    BlocksPeerQueryProofSuccess {
         peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        proof: TransitionChainProof,
     },
     BlocksPeerQueryPending {
-        hash: StateHash,
+        hashes: Vec<StateHash>,
         peer_id: PeerId,
         rpc_id: P2pRpcId,
     },
@@ -79,7 +93,7 @@ pub enum TransitionFrontierSyncAction {
     BlocksPeerQuerySuccess {
         peer_id: PeerId,
         rpc_id: P2pRpcId,
-        response: ArcBlockWithHash,
+        response: Vec<ArcBlockWithHash>,
     },
     BlocksFetchSuccess {
         hash: StateHash,
@@ -236,12 +250,13 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
                     && (sync.blocks_fetch_next().is_some()
                         || sync.blocks_fetch_retry_iter().next().is_some())
             }
-            TransitionFrontierSyncAction::BlocksPeerQueryInit { hash, peer_id } => {
-                let check_next_hash = state
-                    .transition_frontier
-                    .sync
-                    .blocks_fetch_next()
-                    .map_or(false, |expected| &expected == hash);
+            TransitionFrontierSyncAction::BlocksPeerQueryInit { hashes, peer_id } => {
+                let check_next_hash = !hashes.is_empty()
+                    && &state
+                        .transition_frontier
+                        .sync
+                        .blocks_fetch_next_batch(peer_id)
+                        == hashes;
 
                 let check_peer_available = state
                     .p2p
@@ -255,13 +270,13 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
 
                 check_next_hash && check_peer_available
             }
-            TransitionFrontierSyncAction::BlocksPeerQueryRetry { hash, peer_id } => {
-                let check_next_hash = state
-                    .transition_frontier
-                    .sync
-                    .blocks_fetch_retry_iter()
-                    .next()
-                    .map_or(false, |expected| &expected == hash);
+            TransitionFrontierSyncAction::BlocksPeerQueryRetry { hashes, peer_id } => {
+                let check_next_hash = !hashes.is_empty()
+                    && &state
+                        .transition_frontier
+                        .sync
+                        .blocks_fetch_retry_batch(peer_id)
+                        == hashes;
 
                 let check_peer_available = state
                     .p2p
@@ -275,28 +290,68 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
 
                 check_next_hash && check_peer_available
             }
-            TransitionFrontierSyncAction::BlocksPeerQueryPending { hash, peer_id, .. } => state
+            TransitionFrontierSyncAction::BlocksPeerQueryProofPending {
+                hashes, peer_id, ..
+            } => {
+                !hashes.is_empty()
+                    && hashes.iter().all(|hash| {
+                        state
+                            .transition_frontier
+                            .sync
+                            .block_state(hash)
+                            .map_or(false, |b| b.is_fetch_init_from_peer(peer_id))
+                    })
+            }
+            TransitionFrontierSyncAction::BlocksPeerQueryProofSuccess {
+                peer_id, rpc_id, ..
+            } => state
                 .transition_frontier
                 .sync
-                .block_state(hash)
-                .map_or(false, |b| b.is_fetch_init_from_peer(peer_id)),
+                .blocks_proof_pending_from_peer(peer_id, *rpc_id)
+                .next()
+                .is_some(),
+            TransitionFrontierSyncAction::BlocksPeerQueryPending {
+                hashes, peer_id, ..
+            } => {
+                let sync = &state.transition_frontier.sync;
+                // Blocks are requested right away from the peers which
+                // don't serve the transition chain proof.
+                let is_per_block = sync.is_per_block_fetch_peer(peer_id);
+                !hashes.is_empty()
+                    && (!is_per_block || hashes.len() == 1)
+                    && hashes.iter().all(|hash| {
+                        sync.block_state(hash)
+                            .and_then(|b| b.fetch_pending_from_peer(peer_id))
+                            .map_or(false, |s| {
+                                if is_per_block {
+                                    s.is_fetch_init()
+                                } else {
+                                    s.is_proof_pending()
+                                }
+                            })
+                    })
+            }
             TransitionFrontierSyncAction::BlocksPeerQueryError {
                 peer_id, rpc_id, ..
             } => state
                 .transition_frontier
                 .sync
                 .blocks_iter()
-                .any(|s| s.is_fetch_pending_from_peer(peer_id, *rpc_id)),
+                .any(|s| s.is_pending_from_peer(peer_id, *rpc_id)),
             TransitionFrontierSyncAction::BlocksPeerQuerySuccess {
                 peer_id,
                 rpc_id,
                 response,
-            } => state
-                .transition_frontier
-                .sync
-                .block_state(&response.hash)
-                .filter(|s| s.is_fetch_pending_from_peer(peer_id, *rpc_id))
-                .map_or(false, |s| s.block_hash() == &response.hash),
+            } => {
+                !response.is_empty()
+                    && response.iter().all(|block| {
+                        state
+                            .transition_frontier
+                            .sync
+                            .block_state(&block.hash)
+                            .map_or(false, |s| s.is_fetch_pending_from_peer(peer_id, *rpc_id))
+                    })
+            }
             TransitionFrontierSyncAction::BlocksFetchSuccess { hash } => state
                 .transition_frontier
                 .sync
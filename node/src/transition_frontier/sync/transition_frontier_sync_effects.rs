@@ -10,7 +10,10 @@ use crate::{p2p_ready, Service, Store};
 use super::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
 use super::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
 use super::ledger::{SyncLedgerTarget, TransitionFrontierSyncLedgerAction};
-use super::{TransitionFrontierSyncAction, TransitionFrontierSyncState};
+use super::{
+    transition_chain_proof_verify, PeerBlockFetchError, TransitionFrontierSyncAction,
+    TransitionFrontierSyncState,
+};
 
 impl TransitionFrontierSyncAction {
     pub fn effects<S>(&self, meta: &ActionMeta, store: &mut Store<S>)
@@ -109,45 +112,34 @@ impl TransitionFrontierSyncAction {
             TransitionFrontierSyncAction::BlocksPeersQuery => {
                 let p2p = p2p_ready!(store.state().p2p, meta.time());
                 // TODO(binier): make sure they have the ledger we want to query.
-                let mut peer_ids = p2p
-                    .ready_peers_iter()
-                    .filter(|(_, p)| p.channels.rpc.can_send_request())
-                    .map(|(id, p)| (*id, p.connected_since))
-                    .collect::<Vec<_>>();
-                peer_ids.sort_by(|(_, t1), (_, t2)| t2.cmp(t1));
-
-                let mut retry_hashes = store
-                    .state()
-                    .transition_frontier
-                    .sync
-                    .blocks_fetch_retry_iter()
-                    .collect::<Vec<_>>();
-                retry_hashes.reverse();
+                let peer_ids = p2p.ready_rpc_peers_by_rtt();
 
                 for (peer_id, _) in peer_ids {
-                    if let Some(hash) = retry_hashes.last() {
-                        if store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryRetry {
+                    let sync = &store.state().transition_frontier.sync;
+                    let retry_hashes = sync.blocks_fetch_retry_batch(&peer_id);
+                    if !retry_hashes.is_empty()
+                        && store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryRetry {
                             peer_id,
-                            hash: hash.clone(),
-                        }) {
-                            retry_hashes.pop();
-                            continue;
-                        }
+                            hashes: retry_hashes,
+                        })
+                    {
+                        continue;
                     }
 
-                    match store.state().transition_frontier.sync.blocks_fetch_next() {
-                        Some(hash) => {
-                            store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryInit {
-                                peer_id,
-                                hash,
-                            });
-                        }
-                        None if retry_hashes.is_empty() => break,
-                        None => {}
+                    let sync = &store.state().transition_frontier.sync;
+                    let hashes = sync.blocks_fetch_next_batch(&peer_id);
+                    if !hashes.is_empty() {
+                        store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryInit {
+                            peer_id,
+                            hashes,
+                        });
+                    } else if sync.blocks_fetch_retry_iter().next().is_none() {
+                        break;
                     }
                 }
             }
-            TransitionFrontierSyncAction::BlocksPeerQueryInit { hash, peer_id } => {
+            TransitionFrontierSyncAction::BlocksPeerQueryInit { hashes, peer_id }
+            | TransitionFrontierSyncAction::BlocksPeerQueryRetry { hashes, peer_id } => {
                 let p2p = p2p_ready!(store.state().p2p, meta.time());
                 let Some(rpc_id) = p2p
                     .get_ready_peer(peer_id)
@@ -155,38 +147,91 @@ impl TransitionFrontierSyncAction {
                 else {
                     return;
                 };
+                let Some(target_hash) = hashes.last() else {
+                    return;
+                };
 
-                if store.dispatch(P2pChannelsRpcAction::RequestSend {
+                // Peer doesn't serve the transition chain, so the batch
+                // consists of a single block fetched with the `Block` RPC.
+                if store
+                    .state()
+                    .transition_frontier
+                    .sync
+                    .is_per_block_fetch_peer(peer_id)
+                {
+                    if store.dispatch(P2pChannelsRpcAction::RequestSend {
+                        peer_id: *peer_id,
+                        id: rpc_id,
+                        request: P2pRpcRequest::Block(target_hash.clone()),
+                    }) {
+                        store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryPending {
+                            hashes: hashes.clone(),
+                            peer_id: *peer_id,
+                            rpc_id,
+                        });
+                    }
+                } else if store.dispatch(P2pChannelsRpcAction::RequestSend {
                     peer_id: *peer_id,
                     id: rpc_id,
-                    request: P2pRpcRequest::Block(hash.clone()),
+                    request: P2pRpcRequest::TransitionChainProof(target_hash.clone()),
                 }) {
-                    store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryPending {
-                        hash: hash.clone(),
+                    store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryProofPending {
+                        hashes: hashes.clone(),
                         peer_id: *peer_id,
                         rpc_id,
                     });
                 }
             }
-            TransitionFrontierSyncAction::BlocksPeerQueryRetry { hash, peer_id } => {
-                let p2p = p2p_ready!(store.state().p2p, meta.time());
-                let Some(rpc_id) = p2p
-                    .get_ready_peer(peer_id)
-                    .map(|v| v.channels.rpc.next_local_rpc_id())
-                else {
-                    return;
-                };
+            TransitionFrontierSyncAction::BlocksPeerQueryProofPending { .. } => {}
+            TransitionFrontierSyncAction::BlocksPeerQueryProofSuccess {
+                peer_id,
+                rpc_id,
+                proof,
+            } => {
+                let sync = &store.state().transition_frontier.sync;
+                let hashes = sync
+                    .blocks_proof_pending_from_peer(peer_id, *rpc_id)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let max_len = sync
+                    .best_tip()
+                    .map_or(0, |b| b.constants().k.as_u32() as usize);
 
-                if store.dispatch(P2pChannelsRpcAction::RequestSend {
-                    peer_id: *peer_id,
-                    id: rpc_id,
-                    request: P2pRpcRequest::Block(hash.clone()),
-                }) {
-                    store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryPending {
-                        hash: hash.clone(),
+                if !transition_chain_proof_verify(&hashes, proof, max_len) {
+                    store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryError {
                         peer_id: *peer_id,
-                        rpc_id,
+                        rpc_id: *rpc_id,
+                        error: PeerBlockFetchError::InvalidChainProof,
                     });
+                    return;
+                }
+
+                let p2p = p2p_ready!(store.state().p2p, meta.time());
+                let next_rpc_id = p2p
+                    .get_ready_peer(peer_id)
+                    .map(|v| v.channels.rpc.next_local_rpc_id());
+                let is_sent = next_rpc_id.map_or(false, |next_rpc_id| {
+                    store.dispatch(P2pChannelsRpcAction::RequestSend {
+                        peer_id: *peer_id,
+                        id: next_rpc_id,
+                        request: P2pRpcRequest::TransitionChain(hashes.iter().cloned().collect()),
+                    })
+                });
+                match next_rpc_id.filter(|_| is_sent) {
+                    Some(next_rpc_id) => {
+                        store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryPending {
+                            hashes,
+                            peer_id: *peer_id,
+                            rpc_id: next_rpc_id,
+                        });
+                    }
+                    None => {
+                        store.dispatch(TransitionFrontierSyncAction::BlocksPeerQueryError {
+                            peer_id: *peer_id,
+                            rpc_id: *rpc_id,
+                            error: PeerBlockFetchError::Disconnected,
+                        });
+                    }
                 }
             }
             TransitionFrontierSyncAction::BlocksPeerQueryPending { .. } => {}
@@ -195,9 +240,11 @@ impl TransitionFrontierSyncAction {
             }
             TransitionFrontierSyncAction::BlocksPeerQuerySuccess { response, .. } => {
                 store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
-                store.dispatch(TransitionFrontierSyncAction::BlocksFetchSuccess {
-                    hash: response.hash.clone(),
-                });
+                for block in response {
+                    store.dispatch(TransitionFrontierSyncAction::BlocksFetchSuccess {
+                        hash: block.hash.clone(),
+                    });
+                }
             }
             TransitionFrontierSyncAction::BlocksFetchSuccess { .. } => {
                 let _ = store;
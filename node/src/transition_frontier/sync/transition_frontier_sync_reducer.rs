@@ -8,9 +8,9 @@ use super::{
         snarked::TransitionFrontierSyncLedgerSnarkedState, SyncLedgerTarget, SyncLedgerTargetKind,
         TransitionFrontierSyncLedgerState,
     },
-    PeerRpcState, TransitionFrontierSyncAction, TransitionFrontierSyncActionWithMetaRef,
-    TransitionFrontierSyncBlockState, TransitionFrontierSyncLedgerPending,
-    TransitionFrontierSyncState,
+    PeerBlockFetchError, PeerRpcState, TransitionFrontierSyncAction,
+    TransitionFrontierSyncActionWithMetaRef, TransitionFrontierSyncBlockState,
+    TransitionFrontierSyncLedgerPending, TransitionFrontierSyncState,
 };
 
 impl TransitionFrontierSyncState {
@@ -229,6 +229,7 @@ impl TransitionFrontierSyncState {
                             chain,
                             root_snarked_ledger_updates: Default::default(),
                             needed_protocol_states: Default::default(),
+                            per_block_fetch_peers: Default::default(),
                         };
                     } else {
                         *self = next_required_ledger_to_sync(
@@ -435,78 +436,122 @@ impl TransitionFrontierSyncState {
                     chain,
                     root_snarked_ledger_updates: Default::default(),
                     needed_protocol_states: std::mem::take(needed_protocol_states),
+                    per_block_fetch_peers: Default::default(),
                 };
             }
             TransitionFrontierSyncAction::BlocksPeersQuery => {}
-            TransitionFrontierSyncAction::BlocksPeerQueryInit { hash, peer_id } => {
-                let Some(block_state) = self.block_state_mut(hash) else {
-                    return;
-                };
-                let Some(attempts) = block_state.fetch_pending_attempts_mut() else {
-                    return;
-                };
-                attempts.insert(*peer_id, PeerRpcState::Init { time: meta.time() });
+            TransitionFrontierSyncAction::BlocksPeerQueryInit { hashes, peer_id }
+            | TransitionFrontierSyncAction::BlocksPeerQueryRetry { hashes, peer_id } => {
+                for hash in hashes {
+                    let Some(block_state) = self.block_state_mut(hash) else {
+                        continue;
+                    };
+                    let Some(attempts) = block_state.fetch_pending_attempts_mut() else {
+                        continue;
+                    };
+                    attempts.insert(*peer_id, PeerRpcState::Init { time: meta.time() });
+                }
             }
-            TransitionFrontierSyncAction::BlocksPeerQueryRetry { hash, peer_id } => {
-                let Some(block_state) = self.block_state_mut(hash) else {
-                    return;
-                };
-                let Some(attempts) = block_state.fetch_pending_attempts_mut() else {
-                    return;
-                };
-                attempts.insert(*peer_id, PeerRpcState::Init { time: meta.time() });
+            TransitionFrontierSyncAction::BlocksPeerQueryProofPending {
+                hashes,
+                peer_id,
+                rpc_id,
+            } => {
+                for hash in hashes {
+                    let Some(block_state) = self.block_state_mut(hash) else {
+                        continue;
+                    };
+                    let Some(peer_state) = block_state.fetch_pending_from_peer_mut(peer_id) else {
+                        continue;
+                    };
+                    *peer_state = PeerRpcState::ProofPending {
+                        time: meta.time(),
+                        rpc_id: *rpc_id,
+                    };
+                }
             }
+            TransitionFrontierSyncAction::BlocksPeerQueryProofSuccess { .. } => {}
             TransitionFrontierSyncAction::BlocksPeerQueryPending {
-                hash,
+                hashes,
                 peer_id,
                 rpc_id,
             } => {
-                let Some(block_state) = self.block_state_mut(hash) else {
-                    return;
-                };
-                let Some(peer_state) = block_state.fetch_pending_from_peer_mut(peer_id) else {
-                    return;
-                };
-                *peer_state = PeerRpcState::Pending {
-                    time: meta.time(),
-                    rpc_id: *rpc_id,
-                };
+                for hash in hashes {
+                    let Some(block_state) = self.block_state_mut(hash) else {
+                        continue;
+                    };
+                    let Some(peer_state) = block_state.fetch_pending_from_peer_mut(peer_id) else {
+                        continue;
+                    };
+                    *peer_state = PeerRpcState::Pending {
+                        time: meta.time(),
+                        rpc_id: *rpc_id,
+                    };
+                }
             }
             TransitionFrontierSyncAction::BlocksPeerQueryError {
                 peer_id,
                 rpc_id,
                 error,
             } => {
-                let Self::BlocksPending { chain, .. } = self else {
-                    return;
-                };
-                let Some(peer_state) = chain.iter_mut().find_map(|b| {
-                    b.fetch_pending_from_peer_mut(peer_id)
-                        .filter(|peer_rpc_state| {
-                            matches!(peer_rpc_state, PeerRpcState::Pending { .. })
-                        })
-                }) else {
+                let Self::BlocksPending {
+                    chain,
+                    per_block_fetch_peers,
+                    ..
+                } = self
+                else {
                     return;
                 };
-                *peer_state = PeerRpcState::Error {
-                    time: meta.time(),
-                    rpc_id: *rpc_id,
-                    error: error.clone(),
-                };
+                // Peer couldn't serve the transition chain or its proof,
+                // so fall back to fetching blocks from it one by one.
+                if matches!(error, PeerBlockFetchError::DataUnavailable) {
+                    per_block_fetch_peers.insert(*peer_id);
+                }
+                let peer_states = chain
+                    .iter_mut()
+                    .filter_map(|b| b.fetch_pending_from_peer_mut(peer_id))
+                    .filter(|s| s.pending_rpc_id() == Some(*rpc_id));
+                for peer_state in peer_states {
+                    *peer_state = PeerRpcState::Error {
+                        time: meta.time(),
+                        rpc_id: *rpc_id,
+                        error: error.clone(),
+                    };
+                }
             }
             TransitionFrontierSyncAction::BlocksPeerQuerySuccess {
-                peer_id, response, ..
+                peer_id,
+                rpc_id,
+                response,
             } => {
-                let Some(block_state) = self.block_state_mut(&response.hash) else {
-                    return;
-                };
-                let Some(peer_state) = block_state.fetch_pending_from_peer_mut(peer_id) else {
+                for block in response {
+                    let Some(block_state) = self.block_state_mut(&block.hash) else {
+                        continue;
+                    };
+                    let Some(peer_state) = block_state.fetch_pending_from_peer_mut(peer_id) else {
+                        continue;
+                    };
+                    *peer_state = PeerRpcState::Success {
+                        time: meta.time(),
+                        block: block.clone(),
+                    };
+                }
+
+                // Blocks of the batch that weren't included in the response.
+                let Self::BlocksPending { chain, .. } = self else {
                     return;
                 };
-                *peer_state = PeerRpcState::Success {
-                    time: meta.time(),
-                    block: response.clone(),
-                };
+                let peer_states = chain
+                    .iter_mut()
+                    .filter_map(|b| b.fetch_pending_from_peer_mut(peer_id))
+                    .filter(|s| s.fetch_pending_rpc_id() == Some(*rpc_id));
+                for peer_state in peer_states {
+                    *peer_state = PeerRpcState::Error {
+                        time: meta.time(),
+                        rpc_id: *rpc_id,
+                        error: PeerBlockFetchError::DataUnavailable,
+                    };
+                }
             }
             TransitionFrontierSyncAction::BlocksFetchSuccess { hash } => {
                 let Some(block_state) = self.block_state_mut(hash) else {
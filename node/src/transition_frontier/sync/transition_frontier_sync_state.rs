@@ -1,17 +1,22 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
+use ledger::proofs::transition_chain;
+use mina_hasher::Fp;
 use mina_p2p_messages::v2::{LedgerHash, MinaStateProtocolStateValueStableV2, StateHash};
 use openmina_core::block::ArcBlockWithHash;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
-use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::channels::rpc::{P2pRpcId, TransitionChainProof};
 use crate::p2p::PeerId;
 
 use super::ledger::{SyncLedgerTarget, SyncLedgerTargetKind, TransitionFrontierSyncLedgerState};
 use super::PeerBlockFetchError;
 
+/// Maximum number of blocks fetched from a peer with a single request.
+pub const BLOCKS_FETCH_BATCH_SIZE: usize = 16;
+
 #[derive(Serialize, Deserialize, Display, Debug, Clone)]
 pub enum TransitionFrontierSyncState {
     Idle,
@@ -57,6 +62,10 @@ pub enum TransitionFrontierSyncState {
         /// the `value` is more info required to construct that ledger.
         root_snarked_ledger_updates: TransitionFrontierRootSnarkedLedgerUpdates,
         needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+        /// Peers which failed to serve the transition chain or its proof,
+        /// e.g. because they don't support those RPCs. Blocks are fetched
+        /// from them one by one with the `Block` RPC.
+        per_block_fetch_peers: BTreeSet<PeerId>,
     },
     BlocksSuccess {
         time: Timestamp,
@@ -131,6 +140,12 @@ pub enum PeerRpcState {
     Init {
         time: Timestamp,
     },
+    /// Waiting for the transition chain proof, which is verified
+    /// before the blocks are requested from the peer.
+    ProofPending {
+        time: Timestamp,
+        rpc_id: P2pRpcId,
+    },
     Pending {
         time: Timestamp,
         rpc_id: P2pRpcId,
@@ -268,16 +283,67 @@ impl TransitionFrontierSyncState {
     }
 
     pub fn blocks_fetch_next(&self) -> Option<StateHash> {
-        self.blocks_iter().find_map(|s| match s {
+        self.blocks_fetch_next_iter().next().cloned()
+    }
+
+    fn blocks_fetch_next_iter(&self) -> impl '_ + Iterator<Item = &StateHash> {
+        self.blocks_iter().filter_map(|s| match s {
             TransitionFrontierSyncBlockState::FetchPending {
                 block_hash,
                 attempts,
                 ..
-            } => Some(block_hash).filter(|_| attempts.is_empty()).cloned(),
+            } => Some(block_hash).filter(|_| attempts.is_empty()),
             _ => None,
         })
     }
 
+    /// Whether blocks are fetched from the peer one by one with the
+    /// `Block` RPC, instead of in batches with the transition chain RPCs.
+    pub fn is_per_block_fetch_peer(&self, peer_id: &PeerId) -> bool {
+        match self {
+            Self::BlocksPending {
+                per_block_fetch_peers,
+                ..
+            } => per_block_fetch_peers.contains(peer_id),
+            _ => false,
+        }
+    }
+
+    fn blocks_fetch_batch_size(&self, peer_id: &PeerId) -> usize {
+        if self.is_per_block_fetch_peer(peer_id) {
+            1
+        } else {
+            BLOCKS_FETCH_BATCH_SIZE
+        }
+    }
+
+    /// Next blocks to be fetched from the peer with a single request.
+    pub fn blocks_fetch_next_batch(&self, peer_id: &PeerId) -> Vec<StateHash> {
+        self.blocks_fetch_next_iter()
+            .take(self.blocks_fetch_batch_size(peer_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Blocks to be fetched again from the peer with a single request.
+    pub fn blocks_fetch_retry_batch(&self, peer_id: &PeerId) -> Vec<StateHash> {
+        self.blocks_fetch_retry_iter()
+            .take(self.blocks_fetch_batch_size(peer_id))
+            .collect()
+    }
+
+    /// Blocks for which the transition chain proof is being fetched
+    /// from the peer with the given rpc.
+    pub fn blocks_proof_pending_from_peer<'a>(
+        &'a self,
+        peer_id: &'a PeerId,
+        rpc_id: P2pRpcId,
+    ) -> impl 'a + Iterator<Item = &'a StateHash> {
+        self.blocks_iter()
+            .filter(move |s| s.is_proof_pending_from_peer(peer_id, rpc_id))
+            .map(|s| s.block_hash())
+    }
+
     pub fn block_state(&self, hash: &StateHash) -> Option<&TransitionFrontierSyncBlockState> {
         self.blocks_iter().find(|s| s.block_hash() == hash)
     }
@@ -392,10 +458,15 @@ impl TransitionFrontierSyncBlockState {
     }
 
     pub fn fetch_pending_from_peer_rpc_id(&self, peer_id: &PeerId) -> Option<P2pRpcId> {
+        self.fetch_pending_from_peer(peer_id)
+            .and_then(|v| v.pending_rpc_id())
+    }
+
+    pub fn fetch_pending_from_peer(&self, peer_id: &PeerId) -> Option<&PeerRpcState> {
         let Self::FetchPending { attempts, .. } = self else {
             return None;
         };
-        attempts.get(peer_id).and_then(|v| v.fetch_pending_rpc_id())
+        attempts.get(peer_id)
     }
 
     pub fn is_fetch_init_from_peer(&self, peer_id: &PeerId) -> bool {
@@ -406,15 +477,24 @@ impl TransitionFrontierSyncBlockState {
     }
 
     pub fn is_fetch_pending_from_peer(&self, peer_id: &PeerId, rpc_id: P2pRpcId) -> bool {
-        let Self::FetchPending { attempts, .. } = self else {
-            return false;
-        };
-        attempts
-            .get(peer_id)
+        self.fetch_pending_from_peer(peer_id)
             .and_then(|s| s.fetch_pending_rpc_id())
             .map_or(false, |expected| expected == rpc_id)
     }
 
+    pub fn is_proof_pending_from_peer(&self, peer_id: &PeerId, rpc_id: P2pRpcId) -> bool {
+        self.fetch_pending_from_peer(peer_id)
+            .and_then(|s| s.proof_pending_rpc_id())
+            .map_or(false, |expected| expected == rpc_id)
+    }
+
+    /// Whether either the proof or the block itself is being fetched
+    /// from the peer with the given rpc.
+    pub fn is_pending_from_peer(&self, peer_id: &PeerId, rpc_id: P2pRpcId) -> bool {
+        self.fetch_pending_from_peer_rpc_id(peer_id)
+            .map_or(false, |expected| expected == rpc_id)
+    }
+
     pub fn fetch_pending_attempts_mut(&mut self) -> Option<&mut BTreeMap<PeerId, PeerRpcState>> {
         match self {
             Self::FetchPending { attempts, .. } => Some(attempts),
@@ -450,6 +530,10 @@ impl PeerRpcState {
         matches!(self, Self::Success { .. })
     }
 
+    pub fn is_proof_pending(&self) -> bool {
+        matches!(self, Self::ProofPending { .. })
+    }
+
     pub fn fetch_pending_rpc_id(&self) -> Option<P2pRpcId> {
         match self {
             Self::Pending { rpc_id, .. } => Some(*rpc_id),
@@ -457,9 +541,23 @@ impl PeerRpcState {
         }
     }
 
+    pub fn proof_pending_rpc_id(&self) -> Option<P2pRpcId> {
+        match self {
+            Self::ProofPending { rpc_id, .. } => Some(*rpc_id),
+            _ => None,
+        }
+    }
+
+    pub fn pending_rpc_id(&self) -> Option<P2pRpcId> {
+        match self {
+            Self::ProofPending { rpc_id, .. } | Self::Pending { rpc_id, .. } => Some(*rpc_id),
+            _ => None,
+        }
+    }
+
     pub fn fetch_pending_since(&self) -> Option<Timestamp> {
         match self {
-            Self::Pending { time, .. } => Some(*time),
+            Self::ProofPending { time, .. } | Self::Pending { time, .. } => Some(*time),
             _ => None,
         }
     }
@@ -472,6 +570,34 @@ impl PeerRpcState {
     }
 }
 
+/// Verifies that the blocks with the given hashes, ordered from the oldest,
+/// are part of the chain proven by the transition chain proof for the last
+/// of them. Proofs longer than `max_len` are rejected.
+pub fn transition_chain_proof_verify(
+    hashes: &[StateHash],
+    proof: &TransitionChainProof,
+    max_len: usize,
+) -> bool {
+    let Some(target_hash) = hashes.last() else {
+        return false;
+    };
+    if proof.body_hashes.len() > max_len {
+        return false;
+    }
+    let body_hashes: Vec<Fp> = proof.body_hashes.iter().map(|h| h.to_field()).collect();
+    let Some(chain) = transition_chain::verify(
+        target_hash.to_field(),
+        (proof.init_state_hash.to_field(), body_hashes),
+    ) else {
+        return false;
+    };
+    let chain = chain
+        .into_iter()
+        .map(StateHash::from_fp)
+        .collect::<BTreeSet<_>>();
+    hashes.iter().all(|hash| chain.contains(hash))
+}
+
 impl TransitionFrontierRootSnarkedLedgerUpdates {
     pub fn get(
         &self,
@@ -531,3 +657,130 @@ impl TransitionFrontierRootSnarkedLedgerUpdates {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::MinaBaseStateBodyHashStableV1;
+
+    use super::super::TransitionFrontierSyncAction;
+    use super::*;
+
+    #[test]
+    fn test_transition_chain_proof_verify() {
+        let init_state_hash = StateHash::from_fp(Fp::from(1u64));
+        let body_hashes = (2..5u64)
+            .map(|i| MinaBaseStateBodyHashStableV1(Fp::from(i).into()))
+            .collect::<Vec<_>>();
+        let hashes = body_hashes
+            .iter()
+            .scan(init_state_hash.clone(), |pred_hash, body_hash| {
+                *pred_hash = StateHash::from_hashes(pred_hash, body_hash);
+                Some(pred_hash.clone())
+            })
+            .collect::<Vec<_>>();
+        let proof = TransitionChainProof {
+            init_state_hash: init_state_hash.clone(),
+            body_hashes: body_hashes.into_iter().collect(),
+        };
+
+        assert!(transition_chain_proof_verify(&hashes, &proof, 3));
+        assert!(transition_chain_proof_verify(&hashes[1..], &proof, 3));
+        assert!(!transition_chain_proof_verify(&hashes, &proof, 2));
+        assert!(!transition_chain_proof_verify(&hashes[..2], &proof, 3));
+        assert!(!transition_chain_proof_verify(&[], &proof, 3));
+
+        let with_init = vec![init_state_hash, hashes[2].clone()];
+        assert!(transition_chain_proof_verify(&with_init, &proof, 3));
+    }
+
+    #[test]
+    fn test_per_block_fetch_fallback() {
+        let hash = |n: u64| StateHash::from_fp(Fp::from(n));
+        let time = Timestamp::new(0);
+        let mut state = TransitionFrontierSyncState::BlocksPending {
+            time,
+            chain: (0..20)
+                .map(|i| TransitionFrontierSyncBlockState::FetchPending {
+                    time,
+                    block_hash: hash(i),
+                    attempts: Default::default(),
+                })
+                .collect(),
+            root_snarked_ledger_updates: Default::default(),
+            needed_protocol_states: Default::default(),
+            per_block_fetch_peers: Default::default(),
+        };
+        let dispatch = |state: &mut TransitionFrontierSyncState,
+                        action: TransitionFrontierSyncAction| {
+            state.reducer(
+                redux::ActionMeta::zero_custom(time).with_action(&action),
+                &[],
+            );
+        };
+        let (peer1, peer2) = (PeerId::from_bytes([1; 32]), PeerId::from_bytes([2; 32]));
+
+        let batch = (0..BLOCKS_FETCH_BATCH_SIZE as u64)
+            .map(hash)
+            .collect::<Vec<_>>();
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncAction::BlocksPeerQueryInit {
+                hashes: batch.clone(),
+                peer_id: peer1,
+            },
+        );
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncAction::BlocksPeerQueryProofPending {
+                hashes: batch.clone(),
+                peer_id: peer1,
+                rpc_id: 0,
+            },
+        );
+        // invalid proof isn't a reason to fall back.
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncAction::BlocksPeerQueryError {
+                peer_id: peer1,
+                rpc_id: 0,
+                error: PeerBlockFetchError::InvalidChainProof,
+            },
+        );
+        assert!(!state.is_per_block_fetch_peer(&peer1));
+        assert_eq!(state.blocks_fetch_retry_batch(&peer1), batch);
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncAction::BlocksPeerQueryRetry {
+                hashes: batch.clone(),
+                peer_id: peer1,
+            },
+        );
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncAction::BlocksPeerQueryProofPending {
+                hashes: batch.clone(),
+                peer_id: peer1,
+                rpc_id: 1,
+            },
+        );
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncAction::BlocksPeerQueryError {
+                peer_id: peer1,
+                rpc_id: 1,
+                error: PeerBlockFetchError::DataUnavailable,
+            },
+        );
+        assert!(state.is_per_block_fetch_peer(&peer1));
+        assert!(!state.is_per_block_fetch_peer(&peer2));
+
+        assert_eq!(state.blocks_fetch_retry_batch(&peer1), vec![hash(0)]);
+        assert_eq!(state.blocks_fetch_retry_batch(&peer2), batch);
+        assert_eq!(state.blocks_fetch_next_batch(&peer1), vec![hash(16)]);
+        assert_eq!(
+            state.blocks_fetch_next_batch(&peer2),
+            (16..20).map(hash).collect::<Vec<_>>()
+        );
+    }
+}
@@ -3,14 +3,22 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
 use super::genesis::TransitionFrontierGenesisConfig;
+use super::sync::ledger::snarked::SnarkedLedgerSyncCheckpoint;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierConfig {
     pub genesis: Arc<TransitionFrontierGenesisConfig>,
+    /// Snarked ledger sync progress persisted by the previous run. The sync
+    /// of the same ledger is resumed from it.
+    #[serde(skip)]
+    pub ledger_sync_checkpoint: Option<SnarkedLedgerSyncCheckpoint>,
 }
 
 impl TransitionFrontierConfig {
     pub fn new(genesis: Arc<TransitionFrontierGenesisConfig>) -> Self {
-        TransitionFrontierConfig { genesis }
+        TransitionFrontierConfig {
+            genesis,
+            ledger_sync_checkpoint: None,
+        }
     }
 }
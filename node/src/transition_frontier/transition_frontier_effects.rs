@@ -1,11 +1,12 @@
 use redux::Timestamp;
 
-use crate::block_producer::BlockProducerAction;
+use crate::block_producer::{BlockProducerAction, BlockProducerService};
 use crate::consensus::ConsensusAction;
-use crate::ledger::LEDGER_DEPTH;
+use crate::ledger::{LedgerService, LEDGER_DEPTH};
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::p2p::network::kad::{P2pNetworkKadRecordKey, P2pNetworkKademliaAction};
 use crate::snark_pool::{SnarkPoolAction, SnarkWork};
-use crate::stats::sync::SyncingLedger;
+use crate::stats::sync::{SyncSnarkedLedgerRange, SyncingLedger};
 use crate::Store;
 
 use super::genesis::TransitionFrontierGenesisAction;
@@ -154,15 +155,18 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 TransitionFrontierSyncAction::BlocksPeersQuery => {}
                 TransitionFrontierSyncAction::BlocksPeerQueryInit { .. } => {}
                 TransitionFrontierSyncAction::BlocksPeerQueryRetry { .. } => {}
-                TransitionFrontierSyncAction::BlocksPeerQueryPending { ref hash, .. } => {
+                TransitionFrontierSyncAction::BlocksPeerQueryProofPending {
+                    ref hashes, ..
+                }
+                | TransitionFrontierSyncAction::BlocksPeerQueryPending { ref hashes, .. } => {
                     if let Some(stats) = store.service.stats() {
-                        if let Some(state) =
-                            store.state.get().transition_frontier.sync.block_state(hash)
-                        {
+                        let sync = &store.state.get().transition_frontier.sync;
+                        for state in hashes.iter().filter_map(|hash| sync.block_state(hash)) {
                             stats.syncing_block_update(state);
                         }
                     }
                 }
+                TransitionFrontierSyncAction::BlocksPeerQueryProofSuccess { .. } => {}
                 TransitionFrontierSyncAction::BlocksPeerQueryError { .. } => {}
                 TransitionFrontierSyncAction::BlocksPeerQuerySuccess { .. } => {}
                 TransitionFrontierSyncAction::BlocksFetchSuccess { ref hash } => {
@@ -237,6 +241,8 @@ pub fn transition_frontier_effects<S: crate::Service>(
                     });
                     store.dispatch(SnarkPoolAction::JobsUpdate {
                         jobs: result.available_jobs,
+                        jobs_depth: result.available_jobs_depth,
+                        next_block_jobs: result.next_block_jobs,
                         orphaned_snarks,
                     });
                     return;
@@ -264,6 +270,7 @@ fn synced_effects<S: crate::Service>(
     if let Some(stats) = store.service.stats() {
         stats.new_best_chain(meta.time(), best_chain);
     }
+    store.service.won_blocks_record(best_chain);
 
     // publish new best tip.
     let best_tip = best_tip.clone();
@@ -274,6 +281,41 @@ fn synced_effects<S: crate::Service>(
         });
     }
 
+    // export epoch ledgers for the (possibly new) epoch. Already exported
+    // ledgers are skipped by the service.
+    store.service.epoch_ledgers_export(vec![
+        best_tip.staking_epoch_ledger_hash().clone(),
+        best_tip.next_epoch_ledger_hash().clone(),
+    ]);
+
+    // announce that we serve the epoch ledgers, and stop announcing the
+    // ones from the previous epoch.
+    let provided = [
+        P2pNetworkKadRecordKey::ledger(best_tip.staking_epoch_ledger_hash()),
+        P2pNetworkKadRecordKey::ledger(best_tip.next_epoch_ledger_hash()),
+    ];
+    let outdated = store
+        .state()
+        .p2p
+        .ready()
+        .and_then(|p2p| p2p.network.scheduler.discovery_state())
+        .map(|discovery_state| {
+            discovery_state
+                .records
+                .local_providers
+                .keys()
+                .filter(|key| key.is_ledger() && !provided.contains(*key))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for key in outdated {
+        store.dispatch(P2pNetworkKademliaAction::StopProviding { key });
+    }
+    for key in provided {
+        store.dispatch(P2pNetworkKademliaAction::StartProviding { key });
+    }
+
     store.dispatch(ConsensusAction::Prune);
     store.dispatch(BlockProducerAction::BestTipUpdate { best_tip });
 }
@@ -320,6 +362,11 @@ fn handle_transition_frontier_sync_ledger_action<S: crate::Service>(
                             }
                         }
                     }
+                    sync_ledger_ranges_stats(store);
+                }
+                TransitionFrontierSyncLedgerSnarkedAction::ChildHashesAccepted { .. }
+                | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsAccepted { .. } => {
+                    sync_ledger_ranges_stats(store);
                 }
                 TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressSuccess {
                     peer_id,
@@ -448,3 +495,33 @@ fn handle_transition_frontier_sync_ledger_action<S: crate::Service>(
         }
     }
 }
+
+fn sync_ledger_ranges_stats<S: crate::Service>(store: &mut Store<S>) {
+    let Some(stats) = store.service.stats() else {
+        return;
+    };
+    let Some((kind, ranges)) = None.or_else(|| {
+        let snarked = store
+            .state
+            .get()
+            .transition_frontier
+            .sync
+            .ledger()?
+            .snarked()?;
+        let ranges = snarked
+            .ranges()?
+            .iter()
+            .map(|(address, progress)| SyncSnarkedLedgerRange {
+                address: address.clone(),
+                synced_hashes: progress.synced_hashes,
+                synced_accounts: progress.synced_accounts,
+                queued: progress.queued,
+                pending: progress.pending,
+            })
+            .collect();
+        Some((snarked.target().kind, ranges))
+    }) else {
+        return;
+    };
+    stats.syncing_ledger(kind, SyncingLedger::FetchRanges { ranges });
+}
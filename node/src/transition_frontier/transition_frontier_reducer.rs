@@ -1,4 +1,6 @@
-use super::sync::TransitionFrontierSyncState;
+use super::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
+use super::sync::ledger::TransitionFrontierSyncLedgerAction;
+use super::sync::{TransitionFrontierSyncAction, TransitionFrontierSyncState};
 use super::{
     TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierState,
 };
@@ -18,6 +20,14 @@ impl TransitionFrontierState {
                 }
             }
             TransitionFrontierAction::Sync(a) => {
+                if let TransitionFrontierSyncAction::Ledger(
+                    TransitionFrontierSyncLedgerAction::Snarked(
+                        TransitionFrontierSyncLedgerSnarkedAction::MerkleTreeSyncResume { .. },
+                    ),
+                ) = a
+                {
+                    self.ledger_sync_checkpoint = None;
+                }
                 self.sync.reducer(meta.with_action(a), &self.best_chain);
             }
             TransitionFrontierAction::Synced {
@@ -1,12 +1,17 @@
 use std::collections::BTreeMap;
 
 use mina_p2p_messages::v2::{
-    MinaStateProtocolStateBodyValueStableV2, MinaStateProtocolStateValueStableV2, StateHash,
+    ConsensusProofOfStakeDataConsensusStateValueStableV2, MinaStateProtocolStateBodyValueStableV2,
+    MinaStateProtocolStateValueStableV2, StateHash,
 };
 use openmina_core::block::ArcBlockWithHash;
+use openmina_core::consensus::consensus_take;
 use serde::{Deserialize, Serialize};
 
+use crate::p2p::channels::rpc::{BestTipWithProof, TransitionChainProof};
+
 use super::genesis::TransitionFrontierGenesisState;
+use super::sync::ledger::snarked::SnarkedLedgerSyncCheckpoint;
 use super::sync::TransitionFrontierSyncState;
 use super::TransitionFrontierConfig;
 
@@ -22,16 +27,22 @@ pub struct TransitionFrontierState {
     pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
     /// Transition frontier synchronization state
     pub sync: TransitionFrontierSyncState,
+    /// Snarked ledger sync progress persisted by the previous run, until
+    /// the sync of that ledger is resumed.
+    #[serde(default)]
+    pub ledger_sync_checkpoint: Option<SnarkedLedgerSyncCheckpoint>,
 }
 
 impl TransitionFrontierState {
-    pub fn new(config: TransitionFrontierConfig) -> Self {
+    pub fn new(mut config: TransitionFrontierConfig) -> Self {
+        let ledger_sync_checkpoint = config.ledger_sync_checkpoint.take();
         Self {
             config,
             genesis: TransitionFrontierGenesisState::Idle,
             best_chain: Vec::with_capacity(290),
             needed_protocol_states: Default::default(),
             sync: TransitionFrontierSyncState::Idle,
+            ledger_sync_checkpoint,
         }
     }
 
@@ -48,25 +59,232 @@ impl TransitionFrontierState {
         &self,
         hash: &StateHash,
     ) -> Option<&MinaStateProtocolStateBodyValueStableV2> {
+        self.get_protocol_state(hash).map(|state| &state.body)
+    }
+
+    /// Looks up protocol state by state hash, in the best chain and in
+    /// the protocol states needed by the root.
+    pub fn get_protocol_state(
+        &self,
+        hash: &StateHash,
+    ) -> Option<&MinaStateProtocolStateValueStableV2> {
         self.best_chain
             .iter()
-            .find_map(|block_with_hash| {
-                if &block_with_hash.hash == hash {
-                    Some(&block_with_hash.block.header.protocol_state.body)
-                } else {
-                    None
-                }
+            .rev()
+            .find(|block| &block.hash == hash)
+            .map(|block| &block.block.header.protocol_state)
+            .or_else(|| self.needed_protocol_states.get(hash))
+    }
+
+    /// Best tip along with the body hashes of the blocks between the root
+    /// and the best tip, and the root block itself.
+    pub fn best_tip_with_proof(&self) -> Option<BestTipWithProof> {
+        let best_tip = self.best_tip()?;
+        let mut chain_iter = self.best_chain.iter();
+        let root_block = chain_iter.next()?;
+        // TODO(binier): cache body hashes
+        let body_hashes = chain_iter
+            .map(|b| b.block.header.protocol_state.body.hash())
+            .collect();
+
+        Some(BestTipWithProof {
+            best_tip: best_tip.block.clone(),
+            proof: (body_hashes, root_block.block.clone()),
+        })
+    }
+
+    /// Answer to the ancestry query for the peer's block. Same as the
+    /// OCaml node, answer only if the peer's block wouldn't be selected
+    /// over our best tip.
+    pub fn ancestry(
+        &self,
+        consensus_state: &ConsensusProofOfStakeDataConsensusStateValueStableV2,
+        hash: &StateHash,
+    ) -> Option<BestTipWithProof> {
+        self.best_tip()
+            .filter(|best_tip| {
+                !consensus_take(
+                    best_tip.consensus_state(),
+                    consensus_state,
+                    best_tip.hash(),
+                    hash,
+                )
             })
-            .or_else(|| {
-                self.needed_protocol_states
-                    .iter()
-                    .find_map(|(block_hash, state)| {
-                        if block_hash == hash {
-                            Some(&state.body)
-                        } else {
-                            None
-                        }
-                    })
+            .and_then(|_| self.best_tip_with_proof())
+    }
+
+    /// Proof that the block with the given hash extends the oldest block
+    /// known to us, going at most `k` blocks back, same as the OCaml node.
+    pub fn transition_chain_proof(&self, hash: &StateHash) -> Option<TransitionChainProof> {
+        let k = self.best_tip()?.constants().k.as_u32() as usize;
+        let mut state_hash = hash.clone();
+        let mut state = self.get_protocol_state(hash)?;
+        let mut body_hashes = Vec::new();
+
+        while body_hashes.len() < k {
+            let Some(pred_state) = self.get_protocol_state(&state.previous_state_hash) else {
+                break;
+            };
+            body_hashes.push(state.body.hash());
+            state_hash = state.previous_state_hash.clone();
+            state = pred_state;
+        }
+        body_hashes.reverse();
+
+        Some(TransitionChainProof {
+            init_state_hash: state_hash,
+            body_hashes: body_hashes.into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::dummy::dummy_blockchain_proof;
+    use mina_p2p_messages::v2;
+    use openmina_core::constants::PROTOCOL_VERSION;
+
+    use crate::config::BERKELEY_CONFIG;
+    use crate::transition_frontier::genesis::empty_block_body;
+
+    use super::*;
+
+    macro_rules! fork_state {
+        ($name:expr, $tip:expr, $cnd:expr, $suffix:expr) => {
+            serde_json::from_str::<MinaStateProtocolStateValueStableV2>(include_str!(concat!(
+                "../../../tests/files/forks/",
+                $name,
+                "-",
+                $tip,
+                "-",
+                $cnd,
+                "-",
+                $suffix,
+                ".json"
+            )))
+            .unwrap()
+        };
+    }
+
+    fn block(protocol_state: MinaStateProtocolStateValueStableV2) -> ArcBlockWithHash {
+        ArcBlockWithHash::new(
+            v2::MinaBlockBlockStableV2 {
+                header: v2::MinaBlockHeaderStableV2 {
+                    delta_block_chain_proof: (protocol_state.hash(), std::iter::empty().collect()),
+                    protocol_state,
+                    protocol_state_proof: (*dummy_blockchain_proof()).clone(),
+                    current_protocol_version: PROTOCOL_VERSION.clone(),
+                    proposed_protocol_version_opt: None,
+                },
+                body: v2::StagedLedgerDiffBodyStableV1 {
+                    staged_ledger_diff: empty_block_body(),
+                },
+            }
+            .into(),
+        )
+    }
+
+    fn frontier(best_chain: Vec<ArcBlockWithHash>) -> TransitionFrontierState {
+        let config = TransitionFrontierConfig::new(BERKELEY_CONFIG.clone());
+        let mut state = TransitionFrontierState::new(config);
+        state.best_chain = best_chain;
+        state
+    }
+
+    /// Chain of `len` protocol states with `k` set to 3.
+    fn chain(len: u32) -> Vec<MinaStateProtocolStateValueStableV2> {
+        let mut state = fork_state!(
+            "short-take-length-60-61",
+            "3NLQEb5mXqXCL34rueHrMkUVyWSQ7aYjvi6K98ZdpEnTozef69uR",
+            "3NKuw8mvieV9RLpdRmHb4kxg7NWR83TfwzNkVmJCeHUmVWFdUQCp",
+            "tip"
+        );
+        state.body.constants.k = 3u32.into();
+        (0..len)
+            .map(|i| {
+                state.body.consensus_state.blockchain_length = (100 + i).into();
+                let next = state.clone();
+                state.previous_state_hash = next.hash();
+                next
             })
+            .collect()
+    }
+
+    #[test]
+    fn test_transition_chain_proof() {
+        let states = chain(6);
+        let hashes = states.iter().map(|s| s.hash()).collect::<Vec<_>>();
+        let body_hashes = states.iter().map(|s| s.body.hash()).collect::<Vec<_>>();
+
+        // the oldest states are only known as the states needed by the root.
+        let mut state = frontier(states[4..].iter().cloned().map(block).collect());
+        state.needed_protocol_states = states[..4].iter().map(|s| (s.hash(), s.clone())).collect();
+
+        // at most `k` blocks back.
+        let proof = state.transition_chain_proof(&hashes[5]).unwrap();
+        assert_eq!(proof.init_state_hash, hashes[2]);
+        assert_eq!(
+            proof.body_hashes.iter().collect::<Vec<_>>(),
+            body_hashes[3..6].iter().collect::<Vec<_>>()
+        );
+
+        // up to the oldest known state.
+        let proof = state.transition_chain_proof(&hashes[2]).unwrap();
+        assert_eq!(proof.init_state_hash, hashes[0]);
+        assert_eq!(
+            proof.body_hashes.iter().collect::<Vec<_>>(),
+            body_hashes[1..3].iter().collect::<Vec<_>>()
+        );
+
+        let proof = state.transition_chain_proof(&hashes[0]).unwrap();
+        assert_eq!(proof.init_state_hash, hashes[0]);
+        assert!(proof.body_hashes.is_empty());
+
+        let unknown = chain(7).pop().unwrap().hash();
+        assert!(state.transition_chain_proof(&unknown).is_none());
+        assert!(frontier(vec![])
+            .transition_chain_proof(&hashes[0])
+            .is_none());
+    }
+
+    #[test]
+    fn test_ancestry() {
+        macro_rules! ancestry {
+            ($name:expr, $tip:expr, $cnd:expr) => {{
+                let tip = fork_state!($name, $tip, $cnd, "tip");
+                let cnd = fork_state!($name, $tip, $cnd, "cnd");
+                let best_tip = block(tip);
+                let state = frontier(vec![best_tip.clone()]);
+                let cnd_hash = $cnd.parse::<StateHash>().unwrap();
+                let response = state.ancestry(&cnd.body.consensus_state, &cnd_hash);
+                if let Some(response) = &response {
+                    assert_eq!(response.best_tip, best_tip.block);
+                    assert!(response.proof.0.is_empty());
+                }
+                response.is_some()
+            }};
+        }
+
+        // our best tip is kept, so we answer.
+        assert!(ancestry!(
+            "long-keep-density-161-166",
+            "3NKY1kxHMRfjBbjfAA5fsasUCWFF9B7YqYFfNH4JFku6ZCUUXyLG",
+            "3NLFoBQ6y3nku79LQqPgKBmuo5Ngnpr7rfZygzdRrcPtz2gewRFC"
+        ));
+        // peer's block would be taken, so we refuse.
+        assert!(!ancestry!(
+            "long-take-density-92-97",
+            "3NLESd9gzU52bDWSXL5uUAYbCojHXSVdeBX4sCMF3V8Ns9D1Sriy",
+            "3NLQfKJ4kBagLgmiwyiVw9zbi53tiNy8TNu2ua1jmCyEecgbBJoN"
+        ));
+        assert!(!ancestry!(
+            "short-take-length-60-61",
+            "3NLQEb5mXqXCL34rueHrMkUVyWSQ7aYjvi6K98ZdpEnTozef69uR",
+            "3NKuw8mvieV9RLpdRmHb4kxg7NWR83TfwzNkVmJCeHUmVWFdUQCp"
+        ));
+        let cnd = chain(1).pop().unwrap();
+        assert!(frontier(vec![])
+            .ancestry(&cnd.body.consensus_state, &cnd.hash())
+            .is_none());
     }
 }
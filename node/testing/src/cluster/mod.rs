@@ -34,7 +34,7 @@ use node::{
     p2p::{channels::ChannelId, identity::SecretKey as P2pSecretKey},
     service::{Recorder, Service},
     snark::{get_srs, get_verifier_index, VerifierKind},
-    BuildEnv, Config, GlobalConfig, LedgerConfig, P2pConfig, SnarkConfig, State,
+    BuildEnv, Config, GlobalConfig, LedgerConfig, P2pConfig, SnarkConfig, SnarkPoolConfig, State,
     TransitionFrontierConfig,
 };
 use openmina_node_invariants::{InvariantResult, Invariants};
@@ -275,12 +275,17 @@ impl Cluster {
             },
             p2p: P2pConfig {
                 libp2p_port: Some(libp2p_port),
+                quic_port: None,
                 listen_port: http_port,
                 identity_pub_key: pub_key,
                 initial_peers,
+                address_book: Vec::new(),
                 ask_initial_peers_interval: testing_config.ask_initial_peers_interval,
                 enabled_channels: ChannelId::iter_all().collect(),
                 peer_discovery: true,
+                nat_port_mapping: false,
+                relay_hop: false,
+                relays: Vec::new(),
                 timeouts: testing_config.timeouts,
                 limits: P2pLimits::default().with_max_peers(Some(testing_config.max_peers)),
                 initial_time: testing_config
@@ -288,6 +293,7 @@ impl Cluster {
                     .checked_sub(redux::Timestamp::ZERO)
                     .unwrap_or_default(),
             },
+            snark_pool: SnarkPoolConfig::default(),
             transition_frontier: TransitionFrontierConfig::new(testing_config.genesis),
             block_producer: block_producer_config,
         };
@@ -299,6 +305,7 @@ impl Cluster {
 
         let p2p_service_ctx = <NodeService as P2pServiceWebrtcWithLibp2p>::init(
             secret_key.clone(),
+            Default::default(),
             p2p_task_spawner::P2pTaskSpawner::new(shutdown_tx.clone()),
         );
 
@@ -321,7 +328,7 @@ impl Cluster {
                 let task = async {
                     tokio::select! {
                         _ = shutdown.closed() => {}
-                        _ = http_server::run(http_port, rpc_sender) => {}
+                        _ = http_server::run(http_port, rpc_sender, None) => {}
                     }
                 };
                 local_set.block_on(&runtime, task);
@@ -344,7 +351,11 @@ impl Cluster {
             peers,
             #[cfg(feature = "p2p-libp2p")]
             mio: p2p_service_ctx.mio,
+            #[cfg(feature = "p2p-libp2p")]
+            quic: p2p_service_ctx.quic,
             network: Default::default(),
+            address_book_path: None,
+            snark_pool_path: None,
             block_producer: None,
             keypair,
             snark_worker_sender: None,
@@ -355,7 +366,7 @@ impl Cluster {
             invariants_state: Default::default(),
         };
         if let Some(producer_key) = block_producer_sec_key {
-            real_service.block_producer_start(producer_key);
+            real_service.block_producer_start(producer_key, None);
         }
         let mut service = NodeTestingService::new(real_service, node_id, shutdown_rx);
         service.set_proof_kind(self.config.proof_kind());
@@ -28,6 +28,7 @@ impl NonDeterministicEvent {
                     _ => return None,
                 },
                 P2pEvent::Channel(_) => return None,
+                P2pEvent::Nat(_) => return None,
                 #[cfg(feature = "p2p-libp2p")]
                 P2pEvent::MioEvent(_) => return None,
             },
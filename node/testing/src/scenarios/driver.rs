@@ -457,24 +457,27 @@ impl PeerPredicate for (ClusterNodeId, &mut BTreeSet<PeerId>) {
 /// Returns connection peer_id iff the connection is finalized, i.e. multiplexing protocol is
 /// negotiated.
 fn is_network_connection_finalized(conn_state: &P2pNetworkConnectionState) -> Option<&PeerId> {
-    if let P2pNetworkConnectionState {
-        auth:
-            Some(P2pNetworkAuthState::Noise(P2pNetworkNoiseState {
-                inner: Some(P2pNetworkNoiseStateInner::Done { remote_peer_id, .. }),
-                ..
-            })),
-        mux:
-            Some(P2pNetworkConnectionMuxState::Yamux(P2pNetworkYamuxState {
-                terminated: None,
-                init: true,
-                ..
-            })),
-        ..
-    } = conn_state
-    {
-        Some(remote_peer_id)
-    } else {
-        None
+    match conn_state {
+        P2pNetworkConnectionState {
+            auth:
+                Some(P2pNetworkAuthState::Noise(P2pNetworkNoiseState {
+                    inner: Some(P2pNetworkNoiseStateInner::Done { remote_peer_id, .. }),
+                    ..
+                })),
+            mux:
+                Some(P2pNetworkConnectionMuxState::Yamux(P2pNetworkYamuxState {
+                    terminated: None,
+                    init: true,
+                    ..
+                })),
+            ..
+        }
+        | P2pNetworkConnectionState {
+            auth: Some(P2pNetworkAuthState::Tls(remote_peer_id)),
+            mux: Some(P2pNetworkConnectionMuxState::Quic(_)),
+            ..
+        } => Some(remote_peer_id),
+        _ => None,
     }
 }
 
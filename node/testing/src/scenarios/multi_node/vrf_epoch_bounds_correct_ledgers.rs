@@ -80,6 +80,7 @@ impl MultiNodeVrfEpochBoundsCorrectLedger {
                 )),
                 strategy: SnarkerStrategy::Sequential,
                 auto_commit: true,
+                coordinator: false,
                 // TODO(binier): fix if we want to use real snarker.
                 path: "".into(),
             }),
@@ -66,6 +66,7 @@ impl MultiNodeVrfEpochBoundsEvaluation {
                 )),
                 strategy: SnarkerStrategy::Sequential,
                 auto_commit: true,
+                coordinator: false,
                 // TODO(binier): fix if we want to use real snarker.
                 path: "".into(),
             }),
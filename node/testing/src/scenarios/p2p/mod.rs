@@ -3,3 +3,4 @@ pub mod basic_incoming_connections;
 pub mod basic_outgoing_connections;
 pub mod kademlia;
 pub mod pubsub;
+pub mod signaling;
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use node::{
+    p2p::{
+        connection::outgoing::P2pConnectionOutgoingInitOpts, webrtc::SignalingMethod,
+        P2pPeerStatus, PeerId,
+    },
+    State,
+};
+
+use crate::{
+    node::RustNodeTestingConfig,
+    scenario::{ListenerNode, ScenarioStep},
+    scenarios::{connection_finalized_event, peer_is_ready, ClusterRunner, Driver},
+};
+
+/// Nodes connected to the same relay over WebRTC should be able to connect
+/// to each other, with the relay forwarding the signaling messages.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct P2pSignalingRelayed;
+
+impl P2pSignalingRelayed {
+    pub async fn run(self, runner: ClusterRunner<'_>) {
+        let mut driver = Driver::new(runner);
+
+        let (relay, relay_peer_id) =
+            driver.add_rust_node(RustNodeTestingConfig::berkeley_default());
+        let (node1, peer_id1) = driver.add_rust_node(RustNodeTestingConfig::berkeley_default());
+        let (node2, peer_id2) = driver.add_rust_node(RustNodeTestingConfig::berkeley_default());
+        assert!(
+            driver
+                .inner()
+                .node(relay)
+                .unwrap()
+                .service()
+                .rust_to_rust_use_webrtc(),
+            "nodes should connect over WebRTC"
+        );
+
+        // wait for the relay to be ready to accept connections
+        driver
+            .run(Duration::from_secs(5))
+            .await
+            .expect("cluster should be running");
+
+        for node in [node1, node2] {
+            driver
+                .exec_step(ScenarioStep::ConnectNodes {
+                    dialer: node,
+                    listener: ListenerNode::Rust(relay),
+                })
+                .await
+                .expect("connect event should be dispatched");
+        }
+
+        // the relay can forward the signaling between the nodes
+        let signaling_ready = |state: &State, peer_id: &PeerId| {
+            let Some(P2pPeerStatus::Ready(ready)) = state.p2p.get_peer(peer_id).map(|p| &p.status)
+            else {
+                return false;
+            };
+            ready.channels.signaling.is_ready()
+        };
+        let ready = driver
+            .run_until(Duration::from_secs(60), |node_id, _, state| {
+                node_id == relay
+                    && signaling_ready(state, &peer_id1)
+                    && signaling_ready(state, &peer_id2)
+            })
+            .await
+            .unwrap();
+        assert!(ready, "signaling channels should be ready");
+        assert!(
+            peer_is_ready(driver.inner(), node1, &relay_peer_id),
+            "node should be connected to the relay"
+        );
+
+        let relayed = P2pConnectionOutgoingInitOpts::WebRTC {
+            peer_id: peer_id2,
+            signaling: SignalingMethod::P2p { relay_peer_id },
+        };
+        driver
+            .exec_step(ScenarioStep::ConnectNodes {
+                dialer: node1,
+                listener: ListenerNode::Custom(relayed),
+            })
+            .await
+            .expect("connect event should be dispatched");
+
+        let connected = driver
+            .wait_for(
+                Duration::from_secs(60),
+                connection_finalized_event(|node_id, peer_id| {
+                    node_id == node1 && peer_id == &peer_id2
+                }),
+            )
+            .await
+            .unwrap();
+        assert!(
+            connected.is_some(),
+            "nodes should connect through the relay"
+        );
+    }
+}
@@ -17,11 +17,14 @@ use mina_p2p_messages::v2::{
     StateHash, TransactionSnarkStableV2, TransactionSnarkWorkTStableV2Proofs,
 };
 use node::account::{AccountPublicKey, AccountSecretKey};
+use node::block_producer::payout::EpochWonBlocks;
 use node::block_producer::vrf_evaluator::VrfEvaluatorInput;
 use node::block_producer::BlockProducerEvent;
+use node::core::block::ArcBlockWithHash;
 use node::core::channels::mpsc;
 use node::core::snark::{Snark, SnarkJobId};
 use node::external_snark_worker::ExternalSnarkWorkerEvent;
+use node::p2p::address_book::{P2pAddressBookEntry, P2pAddressBookService};
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
 use node::p2p::{P2pCryptoService, P2pNetworkService, P2pNetworkServiceError};
 use node::recorder::Recorder;
@@ -307,6 +310,12 @@ impl P2pNetworkService for NodeTestingService {
     }
 }
 
+impl P2pAddressBookService for NodeTestingService {
+    fn address_book_save(&mut self, entries: Vec<P2pAddressBookEntry>) {
+        self.real.address_book_save(entries)
+    }
+}
+
 impl node::ledger::LedgerService for NodeTestingService {
     fn ledger_manager(&self) -> &node::ledger::LedgerManager {
         &self.real.ledger_manager
@@ -367,6 +376,10 @@ impl P2pServiceWebrtcWithLibp2p for NodeTestingService {
     fn mio(&mut self) -> &mut node::p2p::service_impl::mio::MioService {
         self.real.mio()
     }
+
+    fn quic(&mut self) -> &mut node::p2p::service_impl::quic::QuicService {
+        self.real.quic()
+    }
 }
 
 impl SnarkBlockVerifyService for NodeTestingService {
@@ -429,6 +442,10 @@ impl SnarkPoolService for NodeTestingService {
     ) -> Vec<SnarkJobId> {
         self.real.random_choose(iter, n)
     }
+
+    fn snark_pool_save(&mut self, snarks: Vec<Snark>) {
+        self.real.snark_pool_save(snarks)
+    }
 }
 
 impl BlockProducerVrfEvaluatorService for NodeTestingService {
@@ -497,6 +514,14 @@ impl BlockProducerService for NodeTestingService {
             }
         }
     }
+
+    fn won_blocks_record(&mut self, best_chain: &[ArcBlockWithHash]) {
+        BlockProducerService::won_blocks_record(&mut self.real, best_chain)
+    }
+
+    fn won_blocks(&self, epoch: u32) -> Option<EpochWonBlocks> {
+        BlockProducerService::won_blocks(&self.real, epoch)
+    }
 }
 
 impl ExternalSnarkWorkerService for NodeTestingService {
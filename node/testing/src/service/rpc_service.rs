@@ -43,11 +43,23 @@ impl RpcService for super::NodeTestingService {
         respond_scan_state_summary_get,
         node::rpc::RpcScanStateSummaryGetResponse,
     );
+    to_real!(
+        respond_epoch_ledger_accounts_get,
+        node::rpc::RpcEpochLedgerAccountsGetResponse,
+    );
+    to_real!(
+        respond_delegation_payouts_get,
+        node::rpc::RpcDelegationPayoutsGetResponse,
+    );
     to_real!(respond_snark_pool_get, node::rpc::RpcSnarkPoolGetResponse,);
     to_real!(
         respond_snark_pool_job_get,
         node::rpc::RpcSnarkPoolJobGetResponse,
     );
+    to_real!(
+        respond_snark_pool_fee_stats_get,
+        node::rpc::RpcSnarkPoolFeeStatsGetResponse,
+    );
     to_real!(
         respond_snarker_job_commit,
         node::rpc::RpcSnarkerJobCommitResponse,
@@ -60,6 +72,10 @@ impl RpcService for super::NodeTestingService {
         respond_snarker_workers,
         node::rpc::RpcSnarkerWorkersResponse,
     );
+    to_real!(
+        respond_snarker_remote_worker,
+        node::rpc::RpcSnarkerRemoteWorkerResponse,
+    );
     to_real!(
         respond_snarker_config_get,
         node::rpc::RpcSnarkerConfigGetResponse,
@@ -77,4 +93,8 @@ impl RpcService for super::NodeTestingService {
         respond_discovery_bootstrap_stats,
         node::rpc::RpcDiscoveryBoostrapStatsResponse
     );
+    to_real!(
+        respond_p2p_bandwidth_stats_get,
+        node::rpc::RpcP2pBandwidthStatsGetResponse
+    );
 }
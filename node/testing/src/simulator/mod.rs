@@ -148,6 +148,7 @@ impl Simulator {
                     )),
                     strategy: SnarkerStrategy::Sequential,
                     auto_commit: true,
+                    coordinator: false,
                     // TODO(binier): fix if we want to use real snarker.
                     path: "".into(),
                 }),
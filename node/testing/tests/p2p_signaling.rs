@@ -0,0 +1,21 @@
+#[cfg(feature = "p2p-webrtc")]
+use openmina_node_testing::{
+    cluster::{Cluster, ClusterConfig},
+    scenarios::{p2p::signaling::P2pSignalingRelayed, ClusterRunner},
+    setup,
+};
+
+#[cfg(feature = "p2p-webrtc")]
+#[test]
+fn relayed_signaling() {
+    let rt = setup();
+    let config = ClusterConfig::new(None)
+        .unwrap()
+        .set_all_rust_to_rust_use_webrtc();
+
+    rt.block_on(async {
+        let mut cluster = Cluster::new(config);
+        let runner = ClusterRunner::new(&mut cluster, |_| {});
+        P2pSignalingRelayed.run(runner).await;
+    });
+}
@@ -5,8 +5,14 @@ fn main() {
         &[
             "src/network/pubsub/message.proto",
             "src/network/identify/p2p_network_identify_message.proto",
+            "src/network/relay/p2p_network_relay_message.proto",
+            "src/network/relay/p2p_network_dcutr_message.proto",
+        ],
+        &[
+            "src/network/pubsub",
+            "src/network/identify",
+            "src/network/relay",
         ],
-        &["src/network/pubsub", "src/network/identify"],
     )
     .unwrap();
 }
@@ -0,0 +1,12 @@
+mod p2p_address_book_state;
+pub use p2p_address_book_state::*;
+
+mod p2p_address_book_actions;
+pub use p2p_address_book_actions::*;
+
+mod p2p_address_book_reducer;
+
+mod p2p_address_book_effects;
+
+mod p2p_address_book_service;
+pub use p2p_address_book_service::*;
@@ -0,0 +1,21 @@
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::P2pState;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = debug)]
+pub enum P2pAddressBookAction {
+    /// Removes the expired entries and persists the address book.
+    Save,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pAddressBookAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        match self {
+            P2pAddressBookAction::Save => {
+                state.address_book.should_save(time, &state.config.timeouts)
+            }
+        }
+    }
+}
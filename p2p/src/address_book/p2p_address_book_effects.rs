@@ -0,0 +1,20 @@
+use redux::ActionMeta;
+
+use crate::P2pStore;
+
+use super::{P2pAddressBookAction, P2pAddressBookService};
+
+impl P2pAddressBookAction {
+    pub fn effects<Store, S>(self, _meta: &ActionMeta, store: &mut Store)
+    where
+        Store: P2pStore<S>,
+        Store::Service: P2pAddressBookService,
+    {
+        match self {
+            P2pAddressBookAction::Save => {
+                let entries = store.state().address_book.best_entries().cloned().collect();
+                store.service().address_book_save(entries);
+            }
+        }
+    }
+}
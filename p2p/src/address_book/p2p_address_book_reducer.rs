@@ -0,0 +1,22 @@
+use redux::ActionWithMeta;
+
+use crate::P2pTimeouts;
+
+use super::{P2pAddressBook, P2pAddressBookAction};
+
+impl P2pAddressBook {
+    pub fn reducer(
+        &mut self,
+        action: ActionWithMeta<&P2pAddressBookAction>,
+        timeouts: &P2pTimeouts,
+    ) {
+        let (action, meta) = action.split();
+        match action {
+            P2pAddressBookAction::Save => {
+                self.prune(meta.time(), timeouts.address_book_entry_ttl);
+                self.saved = Some(meta.time());
+                self.changed = false;
+            }
+        }
+    }
+}
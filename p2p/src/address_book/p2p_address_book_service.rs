@@ -0,0 +1,7 @@
+use super::P2pAddressBookEntry;
+
+pub trait P2pAddressBookService: redux::Service {
+    /// Persists the address book, so that the node can reconnect to the
+    /// known peers after a restart.
+    fn address_book_save(&mut self, entries: Vec<P2pAddressBookEntry>);
+}
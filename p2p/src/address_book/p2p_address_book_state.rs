@@ -0,0 +1,258 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use multiaddr::Multiaddr;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connection::outgoing::P2pConnectionOutgoingInitOpts, is_time_passed,
+    socket_addr_try_from_multiaddr, P2pTimeouts, PeerId,
+};
+
+/// Maximum number of peers kept in the address book.
+const ADDRESS_BOOK_MAX_ENTRIES: usize = 1000;
+
+/// Peers the node connected to before, persisted so that the node can
+/// reconnect to them after a restart without relying on the seeds.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pAddressBook {
+    pub entries: BTreeMap<PeerId, P2pAddressBookEntry>,
+    /// Time when the address book was last saved.
+    pub saved: Option<Timestamp>,
+    /// Entries are changed since the last save.
+    pub changed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pAddressBookEntry {
+    pub peer_id: PeerId,
+    /// Transport and address used to dial the peer.
+    pub dial_opts: P2pConnectionOutgoingInitOpts,
+    /// Addresses the peer listens on, as reported by the identify protocol.
+    #[serde(default)]
+    pub listen_addrs: Vec<Multiaddr>,
+    /// Last time the peer was connected.
+    pub last_seen: Timestamp,
+    /// Number of successful connections to the peer.
+    pub successes: u32,
+    /// Number of failed outgoing connections to the peer.
+    pub failures: u32,
+}
+
+impl P2pAddressBookEntry {
+    /// Entries that failed more often than they succeeded go last.
+    fn score(&self) -> (i64, Timestamp) {
+        (
+            i64::from(self.successes) - i64::from(self.failures),
+            self.last_seen,
+        )
+    }
+}
+
+impl P2pAddressBook {
+    /// Creates the address book from the persisted entries,
+    /// the expired ones are dropped.
+    pub fn new(
+        entries: impl IntoIterator<Item = P2pAddressBookEntry>,
+        now: Timestamp,
+        timeouts: &P2pTimeouts,
+    ) -> Self {
+        let mut address_book = Self {
+            entries: entries
+                .into_iter()
+                .map(|entry| (entry.peer_id, entry))
+                .collect(),
+            ..Default::default()
+        };
+        address_book.prune(now, timeouts.address_book_entry_ttl);
+        address_book.changed = false;
+        address_book
+    }
+
+    /// Entries sorted from the most to the least reliable one.
+    pub fn best_entries(&self) -> impl Iterator<Item = &P2pAddressBookEntry> {
+        let mut entries = self.entries.values().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score()));
+        entries.into_iter()
+    }
+
+    pub fn should_save(&self, now: Timestamp, timeouts: &P2pTimeouts) -> bool {
+        let Some(interval) = timeouts.address_book_save_interval else {
+            return false;
+        };
+        self.changed
+            && self
+                .saved
+                .map_or(true, |saved| is_time_passed(now, saved, Some(interval)))
+    }
+
+    pub(crate) fn peer_connected(
+        &mut self,
+        peer_id: PeerId,
+        dial_opts: &P2pConnectionOutgoingInitOpts,
+        time: Timestamp,
+    ) {
+        let entry = self
+            .entries
+            .entry(peer_id)
+            .or_insert_with(|| P2pAddressBookEntry {
+                peer_id,
+                dial_opts: dial_opts.clone(),
+                listen_addrs: Vec::new(),
+                last_seen: time,
+                successes: 0,
+                failures: 0,
+            });
+        entry.dial_opts = dial_opts.clone();
+        entry.last_seen = time;
+        entry.successes = entry.successes.saturating_add(1);
+        self.changed = true;
+        self.evict_excess();
+    }
+
+    pub(crate) fn peer_failed(&mut self, peer_id: &PeerId) {
+        if let Some(entry) = self.entries.get_mut(peer_id) {
+            entry.failures = entry.failures.saturating_add(1);
+            self.changed = true;
+        }
+    }
+
+    pub(crate) fn peer_seen(&mut self, peer_id: &PeerId, time: Timestamp) {
+        if let Some(entry) = self.entries.get_mut(peer_id) {
+            entry.last_seen = time;
+            self.changed = true;
+        }
+    }
+
+    /// Updates the addresses the peer listens on. The peer that connected to
+    /// us is added to the address book if it listens on a public address.
+    pub(crate) fn peer_identified(
+        &mut self,
+        peer_id: PeerId,
+        listen_addrs: &[Multiaddr],
+        time: Timestamp,
+    ) {
+        if let Some(entry) = self.entries.get_mut(&peer_id) {
+            if entry.listen_addrs != listen_addrs {
+                entry.listen_addrs = listen_addrs.to_vec();
+                self.changed = true;
+            }
+            return;
+        }
+        let Some(addr) = listen_addrs
+            .iter()
+            .filter_map(|maddr| socket_addr_try_from_multiaddr(maddr).ok())
+            .find(|addr| !addr.ip().is_loopback() && !addr.ip().is_unspecified())
+        else {
+            return;
+        };
+        let dial_opts = P2pConnectionOutgoingInitOpts::from_libp2p_socket_addr(peer_id, addr);
+        self.peer_connected(peer_id, &dial_opts, time);
+        if let Some(entry) = self.entries.get_mut(&peer_id) {
+            entry.listen_addrs = listen_addrs.to_vec();
+        }
+    }
+
+    /// Removes the entries that weren't seen for `ttl`.
+    pub(crate) fn prune(&mut self, now: Timestamp, ttl: Option<Duration>) {
+        let len = self.entries.len();
+        self.entries
+            .retain(|_, entry| !is_time_passed(now, entry.last_seen, ttl));
+        self.changed |= self.entries.len() != len;
+    }
+
+    fn evict_excess(&mut self) {
+        while self.entries.len() > ADDRESS_BOOK_MAX_ENTRIES {
+            let worst = self
+                .entries
+                .values()
+                .min_by_key(|entry| entry.score())
+                .map(|entry| entry.peer_id);
+            match worst {
+                Some(peer_id) => self.entries.remove(&peer_id),
+                None => break,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(seed: u8, last_seen: Timestamp) -> P2pAddressBookEntry {
+        let peer_id = PeerId::from_bytes([seed; 32]);
+        P2pAddressBookEntry {
+            peer_id,
+            dial_opts: P2pConnectionOutgoingInitOpts::from_libp2p_socket_addr(
+                peer_id,
+                ([1, 2, 3, seed], 8302).into(),
+            ),
+            listen_addrs: Vec::new(),
+            last_seen,
+            successes: 1,
+            failures: 0,
+        }
+    }
+
+    fn time(secs: u64) -> Timestamp {
+        Timestamp::new(secs * 1_000_000_000)
+    }
+
+    #[test]
+    fn test_expired_entries_are_dropped() {
+        let timeouts = P2pTimeouts {
+            address_book_entry_ttl: Some(Duration::from_secs(100)),
+            ..Default::default()
+        };
+        let address_book = P2pAddressBook::new(
+            [entry(1, time(10)), entry(2, time(150))],
+            time(200),
+            &timeouts,
+        );
+        assert_eq!(address_book.entries.len(), 1);
+        assert!(!address_book.changed);
+    }
+
+    #[test]
+    fn test_incoming_peer_is_added_by_listen_addrs() {
+        let mut address_book = P2pAddressBook::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let local: Multiaddr = "/ip4/127.0.0.1/tcp/8302".parse().unwrap();
+        address_book.peer_identified(peer_id, &[local.clone()], time(10));
+        assert!(address_book.entries.is_empty());
+
+        let public: Multiaddr = "/ip4/1.2.3.4/tcp/8302".parse().unwrap();
+        let listen_addrs = [local, public];
+        address_book.peer_identified(peer_id, &listen_addrs, time(20));
+        let entry = &address_book.entries[&peer_id];
+        assert_eq!(
+            entry.dial_opts,
+            P2pConnectionOutgoingInitOpts::from_libp2p_socket_addr(
+                peer_id,
+                ([1, 2, 3, 4], 8302).into()
+            )
+        );
+        assert_eq!(entry.listen_addrs, listen_addrs);
+        assert!(address_book.changed);
+    }
+
+    #[test]
+    fn test_failing_entries_are_ranked_last() {
+        let mut address_book = P2pAddressBook::default();
+        let (a, b) = (entry(1, time(10)), entry(2, time(20)));
+        address_book.peer_connected(a.peer_id, &a.dial_opts, a.last_seen);
+        address_book.peer_connected(b.peer_id, &b.dial_opts, b.last_seen);
+        assert_eq!(
+            address_book.best_entries().next().unwrap().peer_id,
+            b.peer_id
+        );
+
+        address_book.peer_failed(&b.peer_id);
+        address_book.peer_failed(&b.peer_id);
+        assert_eq!(
+            address_book.best_entries().next().unwrap().peer_id,
+            a.peer_id
+        );
+    }
+}
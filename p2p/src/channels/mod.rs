@@ -1,5 +1,6 @@
 pub mod best_tip;
 pub mod rpc;
+pub mod signaling;
 pub mod snark;
 pub mod snark_job_commitment;
 
@@ -24,6 +25,7 @@ use strum_macros::EnumIter;
 
 use self::best_tip::BestTipPropagationChannelMsg;
 use self::rpc::RpcChannelMsg;
+use self::signaling::SignalingChannelMsg;
 use self::snark::SnarkPropagationChannelMsg;
 use self::snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg;
 
@@ -33,6 +35,7 @@ pub enum ChannelId {
     BestTipPropagation = 2,
     SnarkPropagation = 4,
     SnarkJobCommitmentPropagation = 5,
+    Signaling = 6,
     Rpc = 100,
 }
 
@@ -52,6 +55,7 @@ impl ChannelId {
             Self::BestTipPropagation => "best_tip/propagation",
             Self::SnarkPropagation => "snark/propagation",
             Self::SnarkJobCommitmentPropagation => "snark_job_commitment/propagation",
+            Self::Signaling => "signaling",
             Self::Rpc => "rpc",
         }
     }
@@ -61,6 +65,7 @@ impl ChannelId {
             Self::BestTipPropagation => true,
             Self::SnarkPropagation => true,
             Self::SnarkJobCommitmentPropagation => false,
+            Self::Signaling => false,
             Self::Rpc => true,
         }
     }
@@ -72,6 +77,7 @@ impl ChannelId {
             Self::BestTipPropagation => 32 * 1024 * 1024, // 32MB
             Self::SnarkPropagation => 1024,               // 1KB - just snark info.
             Self::SnarkJobCommitmentPropagation => 2 * 1024, // 2KB,
+            Self::Signaling => 16 * 1024,                 // 16KB - sdp with ice candidates.
             Self::Rpc => 256 * 1024 * 1024,               // 256MB,
         }
     }
@@ -109,6 +115,7 @@ pub enum ChannelMsg {
     BestTipPropagation(BestTipPropagationChannelMsg),
     SnarkPropagation(SnarkPropagationChannelMsg),
     SnarkJobCommitmentPropagation(SnarkJobCommitmentPropagationChannelMsg),
+    Signaling(SignalingChannelMsg),
     Rpc(RpcChannelMsg),
 }
 
@@ -118,6 +125,7 @@ impl ChannelMsg {
             Self::BestTipPropagation(_) => ChannelId::BestTipPropagation,
             Self::SnarkPropagation(_) => ChannelId::SnarkPropagation,
             Self::SnarkJobCommitmentPropagation(_) => ChannelId::SnarkJobCommitmentPropagation,
+            Self::Signaling(_) => ChannelId::Signaling,
             Self::Rpc(_) => ChannelId::Rpc,
         }
     }
@@ -130,6 +138,7 @@ impl ChannelMsg {
             Self::BestTipPropagation(v) => v.binprot_write(w),
             Self::SnarkPropagation(v) => v.binprot_write(w),
             Self::SnarkJobCommitmentPropagation(v) => v.binprot_write(w),
+            Self::Signaling(v) => v.binprot_write(w),
             Self::Rpc(v) => v.binprot_write(w),
         }
     }
@@ -149,6 +158,7 @@ impl ChannelMsg {
             ChannelId::SnarkJobCommitmentPropagation => {
                 SnarkJobCommitmentPropagationChannelMsg::binprot_read(r).map(|v| v.into())
             }
+            ChannelId::Signaling => SignalingChannelMsg::binprot_read(r).map(|v| v.into()),
             ChannelId::Rpc => RpcChannelMsg::binprot_read(r).map(|v| v.into()),
         }
     }
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 use crate::{P2pState, PeerId};
 
 use super::{
-    best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction, snark::P2pChannelsSnarkAction,
+    best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction,
+    signaling::P2pChannelsSignalingAction, snark::P2pChannelsSnarkAction,
     snark_job_commitment::P2pChannelsSnarkJobCommitmentAction, ChannelMsg,
 };
 
@@ -17,6 +18,7 @@ pub enum P2pChannelsAction {
     BestTip(P2pChannelsBestTipAction),
     Snark(P2pChannelsSnarkAction),
     SnarkJobCommitment(P2pChannelsSnarkJobCommitmentAction),
+    Signaling(P2pChannelsSignalingAction),
     Rpc(P2pChannelsRpcAction),
 }
 
@@ -27,6 +29,7 @@ impl P2pChannelsAction {
             Self::BestTip(v) => Some(v.peer_id()),
             Self::Snark(v) => v.peer_id(),
             Self::SnarkJobCommitment(v) => Some(v.peer_id()),
+            Self::Signaling(v) => Some(v.peer_id()),
             Self::Rpc(v) => Some(v.peer_id()),
         }
     }
@@ -6,6 +6,7 @@ use crate::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
 use super::{
     best_tip::{BestTipPropagationChannelMsg, P2pChannelsBestTipAction},
     rpc::{P2pChannelsRpcAction, RpcChannelMsg},
+    signaling::{P2pChannelsSignalingAction, SignalingChannelMsg},
     snark::{P2pChannelsSnarkAction, SnarkPropagationChannelMsg},
     snark_job_commitment::{
         P2pChannelsSnarkJobCommitmentAction, SnarkJobCommitmentPropagationChannelMsg,
@@ -64,6 +65,19 @@ impl P2pChannelsMessageReceivedAction {
                     })
                 }
             },
+            ChannelMsg::Signaling(msg) => match msg {
+                SignalingChannelMsg::Offer(offer) => {
+                    store.dispatch(P2pChannelsSignalingAction::OfferReceived { peer_id, offer })
+                }
+                SignalingChannelMsg::Answer { from, to, response } => {
+                    store.dispatch(P2pChannelsSignalingAction::AnswerReceived {
+                        peer_id,
+                        from,
+                        to,
+                        response,
+                    })
+                }
+            },
             ChannelMsg::Rpc(msg) => match msg {
                 RpcChannelMsg::Request(id, request) => {
                     store.dispatch(P2pChannelsRpcAction::RequestReceived {
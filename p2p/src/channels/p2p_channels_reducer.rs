@@ -14,6 +14,9 @@ impl P2pChannelsState {
             P2pChannelsAction::SnarkJobCommitment(action) => {
                 self.snark_job_commitment.reducer(meta.with_action(action));
             }
+            P2pChannelsAction::Signaling(action) => {
+                self.signaling.reducer(meta.with_action(action));
+            }
             P2pChannelsAction::Rpc(action) => {
                 self.rpc.reducer(meta.with_action(action));
             }
@@ -19,7 +19,8 @@ use mina_p2p_messages::{
         RpcMethod, RpcResult,
     },
     v2::{
-        LedgerHash, MerkleAddressBinableArgStableV1, MinaBasePendingCoinbaseStableV2,
+        ConsensusProofOfStakeDataConsensusStateValueStableV2, LedgerHash,
+        MerkleAddressBinableArgStableV1, MinaBasePendingCoinbaseStableV2,
         MinaBaseStateBodyHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
         MinaLedgerSyncLedgerQueryStableV1, MinaStateProtocolStateValueStableV2, StateHash,
         TransactionSnarkScanStateStableV2,
@@ -58,6 +59,10 @@ pub enum P2pRpcKind {
     Block,
     Snark,
     InitialPeers,
+    TransitionChain,
+    TransitionChainProof,
+    Ancestry,
+    TransitionKnowledge,
 }
 
 impl P2pRpcKind {
@@ -71,6 +76,10 @@ impl P2pRpcKind {
             Self::Block => config.block,
             Self::Snark => config.snark,
             Self::InitialPeers => config.initial_peers,
+            Self::TransitionChain => config.transition_chain,
+            Self::TransitionChainProof => config.transition_chain_proof,
+            Self::Ancestry => config.ancestry,
+            Self::TransitionKnowledge => config.transition_knowledge,
         }
     }
 
@@ -82,6 +91,10 @@ impl P2pRpcKind {
             Self::Block => true,
             Self::Snark => false,
             Self::InitialPeers => true,
+            Self::TransitionChain => true,
+            Self::TransitionChainProof => true,
+            Self::Ancestry => true,
+            Self::TransitionKnowledge => true,
         }
     }
 }
@@ -94,6 +107,18 @@ pub enum P2pRpcRequest {
     Block(StateHash),
     Snark(SnarkJobId),
     InitialPeers,
+    /// Blocks with the given hashes, in the same order.
+    TransitionChain(List<StateHash>),
+    /// Proof that the block with the given hash extends the chain.
+    TransitionChainProof(StateHash),
+    /// Best tip with proof, if our best tip is not worse than the block
+    /// with the given consensus state and hash.
+    Ancestry(
+        Box<ConsensusProofOfStakeDataConsensusStateValueStableV2>,
+        StateHash,
+    ),
+    /// Hashes of the blocks in our best chain.
+    TransitionKnowledge,
 }
 
 impl P2pRpcRequest {
@@ -107,6 +132,10 @@ impl P2pRpcRequest {
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::InitialPeers => P2pRpcKind::InitialPeers,
+            Self::TransitionChain(_) => P2pRpcKind::TransitionChain,
+            Self::TransitionChainProof(_) => P2pRpcKind::TransitionChainProof,
+            Self::Ancestry(..) => P2pRpcKind::Ancestry,
+            Self::TransitionKnowledge => P2pRpcKind::TransitionKnowledge,
         }
     }
 }
@@ -153,13 +182,19 @@ impl std::fmt::Display for P2pRpcRequest {
                 write!(f, "ledger: {ledger_hash}")
             }
             Self::StagedLedgerAuxAndPendingCoinbasesAtBlock(block_hash)
-            | Self::Block(block_hash) => {
+            | Self::Block(block_hash)
+            | Self::TransitionChainProof(block_hash)
+            | Self::Ancestry(_, block_hash) => {
                 write!(f, ", {block_hash}")
             }
+            Self::TransitionChain(hashes) => match (hashes.first(), hashes.last()) {
+                (Some(first), Some(last)) => write!(f, ", {first}..{last}"),
+                _ => Ok(()),
+            },
             Self::Snark(job_id) => {
                 write!(f, ", {job_id}")
             }
-            Self::InitialPeers => Ok(()),
+            Self::InitialPeers | Self::TransitionKnowledge => Ok(()),
         }
     }
 }
@@ -170,6 +205,14 @@ pub struct BestTipWithProof {
     pub proof: (List<MinaBaseStateBodyHashStableV1>, ArcBlock),
 }
 
+/// Hash of the block from which the chain starts and body hashes of the
+/// subsequent blocks, from which the hashes of those blocks can be computed.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionChainProof {
+    pub init_state_hash: StateHash,
+    pub body_hashes: List<MinaBaseStateBodyHashStableV1>,
+}
+
 /// Pieces required to reconstruct staged ledger from snarked ledger.
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
 pub struct StagedLedgerAuxAndPendingCoinbases {
@@ -187,6 +230,10 @@ pub enum P2pRpcResponse {
     Block(ArcBlock),
     Snark(Snark),
     InitialPeers(List<P2pConnectionOutgoingInitOpts>),
+    TransitionChain(List<ArcBlock>),
+    TransitionChainProof(TransitionChainProof),
+    Ancestry(BestTipWithProof),
+    TransitionKnowledge(List<StateHash>),
 }
 
 impl P2pRpcResponse {
@@ -200,6 +247,10 @@ impl P2pRpcResponse {
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::InitialPeers(_) => P2pRpcKind::InitialPeers,
+            Self::TransitionChain(_) => P2pRpcKind::TransitionChain,
+            Self::TransitionChainProof(_) => P2pRpcKind::TransitionChainProof,
+            Self::Ancestry(_) => P2pRpcKind::Ancestry,
+            Self::TransitionKnowledge(_) => P2pRpcKind::TransitionKnowledge,
         }
     }
 }
@@ -287,6 +338,65 @@ fn internal_response_into_libp2p(
                 .collect();
             let r = RpcResult(Ok(NeedsLength(r)));
 
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&r, &mut v).unwrap_or_default();
+            Some((ResponseHeader { id: id as _ }, v.into()))
+        }
+        P2pRpcResponse::TransitionChain(blocks) => {
+            type Method = rpc::GetTransitionChainV2;
+            type Payload = ResponsePayload<<Method as RpcMethod>::Response>;
+
+            let blocks = blocks.iter().map(|block| block.as_ref().clone()).collect();
+            let r = RpcResult(Ok(NeedsLength(Some(blocks))));
+
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&r, &mut v).unwrap_or_default();
+            Some((ResponseHeader { id: id as _ }, v.into()))
+        }
+        P2pRpcResponse::TransitionChainProof(proof) => {
+            type Method = rpc::GetTransitionChainProofV1ForV2;
+            type Payload = ResponsePayload<<Method as RpcMethod>::Response>;
+
+            let body_hashes = proof
+                .body_hashes
+                .iter()
+                .map(|hash| hash.0.clone())
+                .collect();
+            let r = RpcResult(Ok(NeedsLength(Some((
+                proof.init_state_hash.0.clone(),
+                body_hashes,
+            )))));
+
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&r, &mut v).unwrap_or_default();
+            Some((ResponseHeader { id: id as _ }, v.into()))
+        }
+        P2pRpcResponse::Ancestry(r) => {
+            type Method = rpc::GetAncestryV2;
+            type Payload = ResponsePayload<<Method as RpcMethod>::Response>;
+
+            let BestTipWithProof {
+                best_tip,
+                proof: (middle, block),
+            } = r;
+
+            let middle = middle.iter().map(|hash| hash.0.clone()).collect();
+            let r = RpcResult(Ok(NeedsLength(Some(rpc::ProofCarryingDataWithHashV1 {
+                data: best_tip.as_ref().clone(),
+                proof: (middle, block.as_ref().clone()),
+            }))));
+
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&r, &mut v).unwrap_or_default();
+            Some((ResponseHeader { id: id as _ }, v.into()))
+        }
+        P2pRpcResponse::TransitionKnowledge(hashes) => {
+            type Method = rpc::GetTransitionKnowledgeV1ForV2;
+            type Payload = ResponsePayload<<Method as RpcMethod>::Response>;
+
+            let hashes = hashes.iter().map(|hash| hash.0.clone()).collect();
+            let r = RpcResult(Ok(NeedsLength(hashes)));
+
             let mut v = vec![];
             <Payload as BinProtWrite>::binprot_write(&r, &mut v).unwrap_or_default();
             Some((ResponseHeader { id: id as _ }, v.into()))
@@ -294,6 +404,53 @@ fn internal_response_into_libp2p(
     }
 }
 
+/// Empty response of the queried method, sent when the node refuses to
+/// answer the query or doesn't have the data, so that the peer doesn't
+/// wait for the response until the timeout.
+fn internal_none_response_into_libp2p(query: &QueryHeader) -> Option<(ResponseHeader, Data)> {
+    use binprot::BinProtWrite;
+
+    fn encode<M: RpcMethod>(response: M::Response, id: QueryID) -> Option<(ResponseHeader, Data)> {
+        let r: ResponsePayload<M::Response> = RpcResult(Ok(NeedsLength(response)));
+
+        let mut v = vec![];
+        r.binprot_write(&mut v).unwrap_or_default();
+        Some((ResponseHeader { id }, v.into()))
+    }
+
+    let id = query.id;
+    match (query.tag.as_ref(), query.version) {
+        (rpc::GetBestTipV2::NAME, rpc::GetBestTipV2::VERSION) => {
+            encode::<rpc::GetBestTipV2>(None, id)
+        }
+        (rpc::AnswerSyncLedgerQueryV2::NAME, rpc::AnswerSyncLedgerQueryV2::VERSION) => {
+            let error = mina_p2p_messages::core::Info::new("ledger not available");
+            encode::<rpc::AnswerSyncLedgerQueryV2>(RpcResult(Err(error)), id)
+        }
+        (
+            rpc::GetStagedLedgerAuxAndPendingCoinbasesAtHashV2::NAME,
+            rpc::GetStagedLedgerAuxAndPendingCoinbasesAtHashV2::VERSION,
+        ) => encode::<rpc::GetStagedLedgerAuxAndPendingCoinbasesAtHashV2>(None, id),
+        (rpc::GetTransitionChainV2::NAME, rpc::GetTransitionChainV2::VERSION) => {
+            encode::<rpc::GetTransitionChainV2>(None, id)
+        }
+        (
+            rpc::GetTransitionChainProofV1ForV2::NAME,
+            rpc::GetTransitionChainProofV1ForV2::VERSION,
+        ) => encode::<rpc::GetTransitionChainProofV1ForV2>(None, id),
+        (rpc::GetAncestryV2::NAME, rpc::GetAncestryV2::VERSION) => {
+            encode::<rpc::GetAncestryV2>(None, id)
+        }
+        (rpc::GetTransitionKnowledgeV1ForV2::NAME, rpc::GetTransitionKnowledgeV1ForV2::VERSION) => {
+            encode::<rpc::GetTransitionKnowledgeV1ForV2>(List::new(), id)
+        }
+        (rpc::GetSomeInitialPeersV1ForV2::NAME, rpc::GetSomeInitialPeersV1ForV2::VERSION) => {
+            encode::<rpc::GetSomeInitialPeersV1ForV2>(List::new(), id)
+        }
+        _ => None,
+    }
+}
+
 fn internal_request_into_libp2p(
     request: P2pRpcRequest,
     id: P2pRpcId,
@@ -376,6 +533,74 @@ fn internal_request_into_libp2p(
             type Method = rpc::GetSomeInitialPeersV1ForV2;
             type Payload = QueryPayload<<Method as RpcMethod>::Query>;
 
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&NeedsLength(()), &mut v).unwrap_or_default();
+            Some((
+                QueryHeader {
+                    tag: Method::NAME.into(),
+                    version: Method::VERSION,
+                    id: id as _,
+                },
+                v.into(),
+            ))
+        }
+        P2pRpcRequest::TransitionChain(hashes) => {
+            type Method = rpc::GetTransitionChainV2;
+            type Payload = QueryPayload<<Method as RpcMethod>::Query>;
+
+            let hashes = hashes.iter().map(|hash| hash.0.clone()).collect();
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&NeedsLength(hashes), &mut v)
+                .unwrap_or_default();
+            Some((
+                QueryHeader {
+                    tag: Method::NAME.into(),
+                    version: Method::VERSION,
+                    id: id as _,
+                },
+                v.into(),
+            ))
+        }
+        P2pRpcRequest::TransitionChainProof(hash) => {
+            type Method = rpc::GetTransitionChainProofV1ForV2;
+            type Payload = QueryPayload<<Method as RpcMethod>::Query>;
+
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&NeedsLength(hash.0.clone()), &mut v)
+                .unwrap_or_default();
+            Some((
+                QueryHeader {
+                    tag: Method::NAME.into(),
+                    version: Method::VERSION,
+                    id: id as _,
+                },
+                v.into(),
+            ))
+        }
+        P2pRpcRequest::Ancestry(consensus_state, hash) => {
+            type Method = rpc::GetAncestryV2;
+            type Payload = QueryPayload<<Method as RpcMethod>::Query>;
+
+            let query = rpc::WithHashV1 {
+                data: consensus_state.as_ref().clone(),
+                hash: hash.0.clone(),
+            };
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&NeedsLength(query), &mut v)
+                .unwrap_or_default();
+            Some((
+                QueryHeader {
+                    tag: Method::NAME.into(),
+                    version: Method::VERSION,
+                    id: id as _,
+                },
+                v.into(),
+            ))
+        }
+        P2pRpcRequest::TransitionKnowledge => {
+            type Method = rpc::GetTransitionKnowledgeV1ForV2;
+            type Payload = QueryPayload<<Method as RpcMethod>::Query>;
+
             let mut v = vec![];
             <Payload as BinProtWrite>::binprot_write(&NeedsLength(()), &mut v).unwrap_or_default();
             Some((
@@ -132,10 +132,12 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsRpcAction {
             },
             P2pChannelsRpcAction::ResponseSend { peer_id, id, response } => {
                 if !cfg!(feature = "p2p-libp2p") {
-                    let Some(response) = response.as_ref() else {
-                        return false;
-                    };
-                    return if !response.kind().supported_by_libp2p() {
+                    // Refusals are answered with the empty response of the
+                    // queried method.
+                    let is_supported = response
+                        .as_ref()
+                        .map_or(true, |response| response.kind().supported_by_libp2p());
+                    return if !is_supported {
                         false
                     } else if let Some(streams) = state
                         .network
@@ -47,16 +47,27 @@ impl P2pChannelsRpcAction {
                 id,
                 response,
             } => {
-                if let Some(response) = response {
-                    if let Some((response, data)) =
-                        super::internal_response_into_libp2p(response, id)
-                    {
-                        store.dispatch(P2pNetworkRpcAction::OutgoingResponse {
-                            peer_id,
-                            response,
-                            data,
-                        });
-                    }
+                let response = match response {
+                    Some(response) => super::internal_response_into_libp2p(response, id),
+                    None => store
+                        .state()
+                        .network
+                        .scheduler
+                        .rpc_incoming_streams
+                        .get(&peer_id)
+                        .and_then(|streams| {
+                            streams
+                                .values()
+                                .find_map(|s| s.pending.as_ref().filter(|query| query.id == id))
+                        })
+                        .and_then(super::internal_none_response_into_libp2p),
+                };
+                if let Some((response, data)) = response {
+                    store.dispatch(P2pNetworkRpcAction::OutgoingResponse {
+                        peer_id,
+                        response,
+                        data,
+                    });
                 }
             }
             P2pChannelsRpcAction::Pending { .. }
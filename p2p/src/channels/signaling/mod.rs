@@ -0,0 +1,43 @@
+mod p2p_channels_signaling_state;
+pub use p2p_channels_signaling_state::*;
+
+mod p2p_channels_signaling_actions;
+pub use p2p_channels_signaling_actions::*;
+
+mod p2p_channels_signaling_reducer;
+
+mod p2p_channels_signaling_effects;
+
+use binprot_derive::{BinProtRead, BinProtWrite};
+use serde::{Deserialize, Serialize};
+
+use crate::{connection::RejectionReason, webrtc, PeerId};
+
+/// Channel used to relay webrtc offers and answers between the peers,
+/// that aren't directly connected, through a peer connected to both.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub enum SignalingChannelMsg {
+    /// Offer to connect to the `offer.target_peer_id`.
+    ///
+    /// - If we are the target, we respond with `Answer`.
+    /// - Otherwise we relay it to the target if we are connected to it,
+    ///   or respond with `SignalingResponse::InternalError` if we can't.
+    Offer(webrtc::Offer),
+    /// Response of the `from` peer to the offer of the `to` peer.
+    ///
+    /// - If we are `to`, it's the response to our offer.
+    /// - Otherwise it must be sent by `from` and we relay it to `to`,
+    ///   only if we relayed the offer from `to` to `from` before.
+    Answer {
+        from: PeerId,
+        to: PeerId,
+        response: SignalingResponse,
+    },
+}
+
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub enum SignalingResponse {
+    Accepted(webrtc::Answer),
+    Rejected(RejectionReason),
+    InternalError,
+}
@@ -0,0 +1,134 @@
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{webrtc, P2pState, PeerId};
+
+use super::{P2pChannelsSignalingState, SignalingResponse};
+
+pub type P2pChannelsSignalingActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a P2pChannelsSignalingAction>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
+#[action_event(fields(display(peer_id), display(from), display(to)))]
+pub enum P2pChannelsSignalingAction {
+    Init {
+        peer_id: PeerId,
+    },
+    Pending {
+        peer_id: PeerId,
+    },
+    Ready {
+        peer_id: PeerId,
+    },
+    /// Send our offer to the relay `peer_id`.
+    OfferSend {
+        peer_id: PeerId,
+        offer: webrtc::Offer,
+    },
+    OfferReceived {
+        peer_id: PeerId,
+        offer: webrtc::Offer,
+    },
+    /// Relay the offer received from `peer_id` to its target.
+    OfferRelay {
+        peer_id: PeerId,
+        offer: webrtc::Offer,
+    },
+    AnswerSend {
+        peer_id: PeerId,
+        from: PeerId,
+        to: PeerId,
+        response: SignalingResponse,
+    },
+    AnswerReceived {
+        peer_id: PeerId,
+        from: PeerId,
+        to: PeerId,
+        response: SignalingResponse,
+    },
+    /// Relay the answer from `from` to the offerer `peer_id`.
+    AnswerRelay {
+        peer_id: PeerId,
+        from: PeerId,
+        response: SignalingResponse,
+    },
+}
+
+impl P2pChannelsSignalingAction {
+    pub fn peer_id(&self) -> &PeerId {
+        match self {
+            Self::Init { peer_id }
+            | Self::Pending { peer_id }
+            | Self::Ready { peer_id }
+            | Self::OfferSend { peer_id, .. }
+            | Self::OfferReceived { peer_id, .. }
+            | Self::OfferRelay { peer_id, .. }
+            | Self::AnswerSend { peer_id, .. }
+            | Self::AnswerReceived { peer_id, .. }
+            | Self::AnswerRelay { peer_id, .. } => peer_id,
+        }
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsSignalingAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        match self {
+            P2pChannelsSignalingAction::Init { peer_id } => {
+                state.get_ready_peer(peer_id).map_or(false, |p| {
+                    matches!(&p.channels.signaling, P2pChannelsSignalingState::Enabled)
+                })
+            }
+            P2pChannelsSignalingAction::Pending { peer_id } => {
+                state.get_ready_peer(peer_id).map_or(false, |p| {
+                    matches!(
+                        &p.channels.signaling,
+                        P2pChannelsSignalingState::Init { .. }
+                    )
+                })
+            }
+            P2pChannelsSignalingAction::Ready { peer_id } => {
+                state.get_ready_peer(peer_id).map_or(false, |p| {
+                    matches!(
+                        &p.channels.signaling,
+                        P2pChannelsSignalingState::Pending { .. }
+                    )
+                })
+            }
+            P2pChannelsSignalingAction::OfferSend { peer_id, offer } => {
+                offer.target_peer_id != *peer_id
+                    && state
+                        .get_ready_peer(peer_id)
+                        .map_or(false, |p| p.channels.signaling.is_ready())
+            }
+            P2pChannelsSignalingAction::OfferReceived { peer_id, .. }
+            | P2pChannelsSignalingAction::AnswerSend { peer_id, .. }
+            | P2pChannelsSignalingAction::AnswerReceived { peer_id, .. } => state
+                .get_ready_peer(peer_id)
+                .map_or(false, |p| p.channels.signaling.is_ready()),
+            P2pChannelsSignalingAction::OfferRelay { peer_id, offer } => {
+                let target = &offer.target_peer_id;
+                // only the offerer itself can ask us to relay its offer.
+                offer.identity_pub_key.peer_id() == *peer_id
+                    && target != peer_id
+                    && *target != state.my_id()
+                    && state.get_ready_peer(peer_id).map_or(false, |p| {
+                        p.channels.signaling.can_relay_offer(target, time)
+                    })
+                    && state
+                        .get_ready_peer(target)
+                        .map_or(false, |p| p.channels.signaling.is_ready())
+            }
+            P2pChannelsSignalingAction::AnswerRelay { peer_id, from, .. } => state
+                .get_ready_peer(peer_id)
+                .map_or(false, |p| p.channels.signaling.is_offer_relayed_to(from)),
+        }
+    }
+}
+
+use crate::channels::P2pChannelsAction;
+
+impl From<P2pChannelsSignalingAction> for crate::P2pAction {
+    fn from(action: P2pChannelsSignalingAction) -> Self {
+        Self::Channels(P2pChannelsAction::Signaling(action))
+    }
+}
@@ -0,0 +1,168 @@
+use redux::ActionMeta;
+
+use crate::{
+    channels::{ChannelId, MsgId, P2pChannelsService},
+    connection::{
+        incoming::{
+            IncomingSignalingMethod, P2pConnectionIncomingAction, P2pConnectionIncomingInitOpts,
+        },
+        outgoing::{
+            P2pConnectionOutgoingAction, P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingState,
+        },
+        P2pConnectionErrorResponse,
+    },
+    webrtc::SignalingMethod,
+};
+
+use super::{P2pChannelsSignalingAction, SignalingChannelMsg, SignalingResponse};
+
+impl P2pChannelsSignalingAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+    {
+        match self {
+            P2pChannelsSignalingAction::Init { peer_id } => {
+                store.service().channel_open(peer_id, ChannelId::Signaling);
+                store.dispatch(P2pChannelsSignalingAction::Pending { peer_id });
+            }
+            P2pChannelsSignalingAction::OfferSend { peer_id, offer } => {
+                let msg = SignalingChannelMsg::Offer(offer);
+                store
+                    .service()
+                    .channel_send(peer_id, MsgId::first(), msg.into());
+            }
+            P2pChannelsSignalingAction::OfferRelay { offer, .. } => {
+                let target = offer.target_peer_id;
+                let msg = SignalingChannelMsg::Offer(offer);
+                store
+                    .service()
+                    .channel_send(target, MsgId::first(), msg.into());
+            }
+            P2pChannelsSignalingAction::OfferReceived { peer_id, offer } => {
+                let my_id = store.state().my_id();
+                let offerer = offer.identity_pub_key.peer_id();
+                if offer.target_peer_id != my_id {
+                    let target = offer.target_peer_id;
+                    if !store.dispatch(P2pChannelsSignalingAction::OfferRelay { peer_id, offer }) {
+                        store.dispatch(P2pChannelsSignalingAction::AnswerSend {
+                            peer_id,
+                            from: target,
+                            to: offerer,
+                            response: SignalingResponse::InternalError,
+                        });
+                    }
+                    return;
+                }
+                match store.state().incoming_accept(offerer, &offer) {
+                    Ok(()) => {
+                        store.dispatch(P2pConnectionIncomingAction::Init {
+                            opts: P2pConnectionIncomingInitOpts {
+                                peer_id: offerer,
+                                signaling: IncomingSignalingMethod::P2p {
+                                    relay_peer_id: peer_id,
+                                },
+                                offer,
+                            },
+                            rpc_id: None,
+                        });
+                    }
+                    Err(reason) => {
+                        store.dispatch(P2pChannelsSignalingAction::AnswerSend {
+                            peer_id,
+                            from: my_id,
+                            to: offerer,
+                            response: SignalingResponse::Rejected(reason),
+                        });
+                    }
+                }
+            }
+            P2pChannelsSignalingAction::AnswerSend {
+                peer_id,
+                from,
+                to,
+                response,
+            } => {
+                let msg = SignalingChannelMsg::Answer { from, to, response };
+                store
+                    .service()
+                    .channel_send(peer_id, MsgId::first(), msg.into());
+            }
+            P2pChannelsSignalingAction::AnswerReceived {
+                peer_id,
+                from,
+                to,
+                response,
+            } => {
+                if to != store.state().my_id() {
+                    // only the answerer itself can ask us to relay its answer.
+                    if from == peer_id {
+                        store.dispatch(P2pChannelsSignalingAction::AnswerRelay {
+                            peer_id: to,
+                            from,
+                            response,
+                        });
+                    }
+                    return;
+                }
+                // answer must come through the relay we sent the offer to.
+                let is_expected_relay = store
+                    .state()
+                    .peers
+                    .get(&from)
+                    .and_then(|peer| peer.status.as_connecting()?.as_outgoing())
+                    .map_or(false, |s| match s {
+                        P2pConnectionOutgoingState::AnswerRecvPending {
+                            opts:
+                                P2pConnectionOutgoingInitOpts::WebRTC {
+                                    signaling: SignalingMethod::P2p { relay_peer_id },
+                                    ..
+                                },
+                            ..
+                        } => *relay_peer_id == peer_id,
+                        _ => false,
+                    });
+                if !is_expected_relay {
+                    return;
+                }
+                match response {
+                    SignalingResponse::Accepted(answer) => {
+                        store.dispatch(P2pConnectionOutgoingAction::AnswerRecvSuccess {
+                            peer_id: from,
+                            answer,
+                        });
+                    }
+                    SignalingResponse::Rejected(reason) => {
+                        store.dispatch(P2pConnectionOutgoingAction::AnswerRecvError {
+                            peer_id: from,
+                            error: P2pConnectionErrorResponse::Rejected(reason),
+                        });
+                    }
+                    SignalingResponse::InternalError => {
+                        store.dispatch(P2pConnectionOutgoingAction::AnswerRecvError {
+                            peer_id: from,
+                            error: P2pConnectionErrorResponse::InternalError,
+                        });
+                    }
+                }
+            }
+            P2pChannelsSignalingAction::AnswerRelay {
+                peer_id,
+                from,
+                response,
+            } => {
+                let msg = SignalingChannelMsg::Answer {
+                    from,
+                    to: peer_id,
+                    response,
+                };
+                store
+                    .service()
+                    .channel_send(peer_id, MsgId::first(), msg.into());
+            }
+            P2pChannelsSignalingAction::Pending { .. } => {}
+            P2pChannelsSignalingAction::Ready { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use super::{
+    P2pChannelsSignalingAction, P2pChannelsSignalingActionWithMetaRef, P2pChannelsSignalingState,
+};
+
+impl P2pChannelsSignalingState {
+    pub fn reducer(&mut self, action: P2pChannelsSignalingActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            P2pChannelsSignalingAction::Init { .. } => {
+                *self = Self::Init { time: meta.time() };
+            }
+            P2pChannelsSignalingAction::Pending { .. } => {
+                *self = Self::Pending { time: meta.time() };
+            }
+            P2pChannelsSignalingAction::Ready { .. } => {
+                *self = Self::Ready {
+                    time: meta.time(),
+                    relayed_offers: Default::default(),
+                };
+            }
+            P2pChannelsSignalingAction::OfferRelay { offer, .. } => {
+                self.offer_relayed(offer.target_peer_id, meta.time());
+            }
+            P2pChannelsSignalingAction::AnswerRelay { from, .. } => {
+                let Self::Ready { relayed_offers, .. } = self else {
+                    return;
+                };
+                relayed_offers.remove(from);
+            }
+            P2pChannelsSignalingAction::OfferSend { .. }
+            | P2pChannelsSignalingAction::OfferReceived { .. }
+            | P2pChannelsSignalingAction::AnswerSend { .. }
+            | P2pChannelsSignalingAction::AnswerReceived { .. } => {}
+        }
+    }
+}
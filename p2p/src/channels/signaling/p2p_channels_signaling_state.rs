@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{is_time_passed, PeerId};
+
+/// Maximum number of offers from a single peer, that we relayed and
+/// are waiting for an answer to.
+const MAX_RELAYED_OFFERS: usize = 8;
+/// Relayed offer which wasn't answered in this time doesn't count
+/// towards the limit anymore.
+const RELAYED_OFFER_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pChannelsSignalingState {
+    Disabled,
+    Enabled,
+    Init {
+        time: redux::Timestamp,
+    },
+    Pending {
+        time: redux::Timestamp,
+    },
+    Ready {
+        time: redux::Timestamp,
+        /// Offers from this peer which we relayed to other peers,
+        /// mapped by the target peer id to the time of the relay.
+        relayed_offers: BTreeMap<PeerId, redux::Timestamp>,
+    },
+}
+
+impl P2pChannelsSignalingState {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready { .. })
+    }
+
+    /// Whether we can relay one more offer from this peer to the `target`.
+    pub fn can_relay_offer(&self, target: &PeerId, now: redux::Timestamp) -> bool {
+        match self {
+            Self::Ready { relayed_offers, .. } => {
+                relayed_offers
+                    .iter()
+                    .filter(|(peer_id, time)| {
+                        *peer_id != target
+                            && !is_time_passed(now, **time, Some(RELAYED_OFFER_TIMEOUT))
+                    })
+                    .count()
+                    < MAX_RELAYED_OFFERS
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether we relayed an offer from this peer to the `target`,
+    /// which wasn't answered yet.
+    pub fn is_offer_relayed_to(&self, target: &PeerId) -> bool {
+        match self {
+            Self::Ready { relayed_offers, .. } => relayed_offers.contains_key(target),
+            _ => false,
+        }
+    }
+
+    /// Remembers the relayed offer, so that we relay the answer to it too.
+    pub(super) fn offer_relayed(&mut self, target: PeerId, now: redux::Timestamp) {
+        let Self::Ready { relayed_offers, .. } = self else {
+            return;
+        };
+        relayed_offers.retain(|_, time| !is_time_passed(now, *time, Some(RELAYED_OFFER_TIMEOUT)));
+        relayed_offers.insert(target, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relayed_offers_limit() {
+        let time = |secs: u64| redux::Timestamp::new(secs * 1_000_000_000);
+        let target = |seed: u8| PeerId::from_bytes([seed; 32]);
+        let relayed_offers = (0..MAX_RELAYED_OFFERS as u8)
+            .map(|seed| (target(seed), time(10)))
+            .collect();
+        let state = P2pChannelsSignalingState::Ready {
+            time: time(0),
+            relayed_offers,
+        };
+
+        assert!(!state.can_relay_offer(&target(100), time(20)));
+        // offer to the same target replaces the previous one.
+        assert!(state.can_relay_offer(&target(0), time(20)));
+        // unanswered offers expire.
+        assert!(state.can_relay_offer(&target(100), time(100)));
+        assert!(state.is_offer_relayed_to(&target(1)));
+        assert!(!state.is_offer_relayed_to(&target(100)));
+    }
+}
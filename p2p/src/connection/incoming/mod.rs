@@ -8,6 +8,8 @@ mod p2p_connection_incoming_reducer;
 
 mod p2p_connection_incoming_effects;
 
+use std::net::SocketAddr;
+
 use serde::{Deserialize, Serialize};
 
 use crate::connection::RejectionReason;
@@ -24,6 +26,10 @@ pub struct P2pConnectionIncomingInitOpts {
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub enum IncomingSignalingMethod {
     Http,
+    /// Offer was relayed by an already connected peer.
+    P2p {
+        relay_peer_id: PeerId,
+    },
 }
 
 impl P2pState {
@@ -55,22 +61,19 @@ impl P2pState {
             return Err(RejectionReason::AlreadyConnected);
         }
 
-        if self.already_has_max_ready_peers() {
-            return Err(RejectionReason::PeerCapacityFull);
-        }
-
-        Ok(())
+        // remote address is not known for connections signaled over http
+        self.incoming_accept_limits(&peer_id, false, None)
     }
 
-    pub fn libp2p_incoming_accept(&self, peer_id: PeerId) -> Result<(), RejectionReason> {
+    pub fn libp2p_incoming_accept(
+        &self,
+        peer_id: PeerId,
+        addr: SocketAddr,
+    ) -> Result<(), RejectionReason> {
         if peer_id == self.my_id() {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
-        if self.already_has_max_ready_peers() {
-            return Err(RejectionReason::PeerCapacityFull);
-        }
-
-        Ok(())
+        self.incoming_accept_limits(&peer_id, true, Some(addr.ip()))
     }
 }
@@ -1,13 +1,17 @@
 use openmina_core::{debug, error, warn};
 use redux::ActionMeta;
 
+use crate::channels::signaling::{P2pChannelsSignalingAction, SignalingResponse};
 use crate::connection::RejectionReason;
 use crate::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
 use crate::peer::P2pPeerAction;
 use crate::P2pNetworkSchedulerAction;
 use crate::{connection::P2pConnectionService, webrtc};
 
-use super::{P2pConnectionIncomingAction, P2pConnectionIncomingError, P2pConnectionIncomingState};
+use super::{
+    IncomingSignalingMethod, P2pConnectionIncomingAction, P2pConnectionIncomingError,
+    P2pConnectionIncomingState,
+};
 
 impl P2pConnectionIncomingAction {
     pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store)
@@ -36,7 +40,32 @@ impl P2pConnectionIncomingAction {
                 store.dispatch(P2pConnectionIncomingAction::AnswerReady { peer_id, answer });
             }
             P2pConnectionIncomingAction::AnswerReady { peer_id, answer } => {
-                store.service().set_answer(peer_id, answer);
+                store.service().set_answer(peer_id, answer.clone());
+
+                let relay_peer_id = store
+                    .state()
+                    .peers
+                    .get(&peer_id)
+                    .and_then(|peer| peer.status.as_connecting()?.as_incoming())
+                    .and_then(|s| match s {
+                        P2pConnectionIncomingState::AnswerReady {
+                            signaling: IncomingSignalingMethod::P2p { relay_peer_id },
+                            ..
+                        } => Some(*relay_peer_id),
+                        _ => None,
+                    });
+                // answers for http signaling are sent by the node's http server.
+                if let Some(relay_peer_id) = relay_peer_id {
+                    let my_id = store.state().my_id();
+                    if store.dispatch(P2pChannelsSignalingAction::AnswerSend {
+                        peer_id: relay_peer_id,
+                        from: my_id,
+                        to: peer_id,
+                        response: SignalingResponse::Accepted(answer),
+                    }) {
+                        store.dispatch(P2pConnectionIncomingAction::AnswerSendSuccess { peer_id });
+                    }
+                }
             }
             P2pConnectionIncomingAction::AnswerSendSuccess { peer_id } => {
                 store.dispatch(P2pConnectionIncomingAction::FinalizePending { peer_id });
@@ -90,7 +119,7 @@ impl P2pConnectionIncomingAction {
                     .as_connecting()
                     .and_then(|connecting| connecting.as_incoming())
                 {
-                    if let Err(reason) = store.state().libp2p_incoming_accept(peer_id) {
+                    if let Err(reason) = store.state().libp2p_incoming_accept(peer_id, addr) {
                         warn!(meta.time(); node_id = display(store.state().my_id()), summary = "rejecting incoming conection", peer_id = display(peer_id), reason = display(&reason));
                         store.dispatch(P2pDisconnectionAction::Init {
                             peer_id,
@@ -13,11 +13,17 @@ pub use p2p_connection_reducer::*;
 mod p2p_connection_service;
 pub use p2p_connection_service::*;
 
+mod p2p_connection_manager;
+pub use p2p_connection_manager::*;
+
+use binprot_derive::{BinProtRead, BinProtWrite};
 use serde::{Deserialize, Serialize};
 
 use crate::webrtc;
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, thiserror::Error)]
+#[derive(
+    BinProtWrite, BinProtRead, Serialize, Deserialize, Eq, PartialEq, Debug, Clone, thiserror::Error,
+)]
 pub enum RejectionReason {
     #[error("peer_id does not match peer's public key")]
     PeerIdAndPublicKeyMismatch,
@@ -29,6 +35,14 @@ pub enum RejectionReason {
     AlreadyConnected,
     #[error("self connection detected")]
     ConnectingToSelf,
+    #[error("too many incoming peers")]
+    InboundCapacityFull,
+    #[error("too many incoming peers using the same transport")]
+    TransportCapacityFull,
+    #[error("too many peers from the same IP address")]
+    TooManyPeersFromIp,
+    #[error("too many peers from the same subnet")]
+    TooManyPeersFromSubnet,
 }
 
 impl RejectionReason {
@@ -39,6 +53,10 @@ impl RejectionReason {
             Self::PeerCapacityFull => false,
             Self::AlreadyConnected => true,
             Self::ConnectingToSelf => false,
+            Self::InboundCapacityFull => false,
+            Self::TransportCapacityFull => false,
+            Self::TooManyPeersFromIp => false,
+            Self::TooManyPeersFromSubnet => false,
         }
     }
 }
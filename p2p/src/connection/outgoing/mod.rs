@@ -35,6 +35,10 @@ pub enum P2pConnectionOutgoingInitOpts {
     },
     #[cfg(not(target_arch = "wasm32"))]
     LibP2P(P2pConnectionOutgoingInitLibp2pOpts),
+    /// Libp2p peer reachable with the QUIC transport (`/udp/<port>/quic-v1`).
+    #[cfg(not(target_arch = "wasm32"))]
+    #[from(ignore)]
+    LibP2PQuic(P2pConnectionOutgoingInitLibp2pOpts),
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -131,7 +135,16 @@ mod libp2p_opts {
 impl P2pConnectionOutgoingInitOpts {
     #[cfg(not(target_arch = "wasm32"))]
     pub fn is_libp2p(&self) -> bool {
-        matches!(self, Self::LibP2P(_))
+        matches!(self, Self::LibP2P(_) | Self::LibP2PQuic(_))
+    }
+
+    /// Libp2p options of the peer, regardless of the transport.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn libp2p_opts(&self) -> Option<&P2pConnectionOutgoingInitLibp2pOpts> {
+        match self {
+            Self::LibP2P(opts) | Self::LibP2PQuic(opts) => Some(opts),
+            Self::WebRTC { .. } => None,
+        }
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -144,6 +157,8 @@ impl P2pConnectionOutgoingInitOpts {
             Self::WebRTC { peer_id, .. } => peer_id,
             #[cfg(not(target_arch = "wasm32"))]
             Self::LibP2P(v) => &v.peer_id,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::LibP2PQuic(v) => &v.peer_id,
         }
     }
 
@@ -152,6 +167,8 @@ impl P2pConnectionOutgoingInitOpts {
             Self::WebRTC { .. } => "webrtc",
             #[cfg(not(target_arch = "wasm32"))]
             Self::LibP2P(_) => "libp2p",
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::LibP2PQuic(_) => "libp2p-quic",
         }
     }
 
@@ -214,6 +231,8 @@ impl P2pConnectionOutgoingInitOpts {
                         .into(),
                 ),
             }),
+            // the OCaml node only dials TCP
+            P2pConnectionOutgoingInitOpts::LibP2PQuic(_) => None,
             P2pConnectionOutgoingInitOpts::WebRTC { peer_id, signaling } => match signaling {
                 SignalingMethod::Http(info) => Some(v2::NetworkPeerPeerStableV1 {
                     host: format!("http://{}", info.host).as_bytes().into(),
@@ -229,6 +248,8 @@ impl P2pConnectionOutgoingInitOpts {
                         (*peer_id).to_string().into_bytes().into(),
                     ),
                 }),
+                // only reachable through the relay peer
+                SignalingMethod::P2p { .. } => None,
             },
         }
     }
@@ -243,6 +264,19 @@ impl P2pConnectionOutgoingInitLibp2pOpts {
     pub fn to_maddr(&self) -> multiaddr::Multiaddr {
         self.into()
     }
+
+    /// Multiaddr of the peer for the QUIC transport.
+    pub fn to_quic_maddr(&self) -> multiaddr::Multiaddr {
+        use multiaddr::Protocol;
+
+        self.to_maddr()
+            .into_iter()
+            .flat_map(|protocol| match protocol {
+                Protocol::Tcp(port) => vec![Protocol::Udp(port), Protocol::QuicV1],
+                protocol => vec![protocol],
+            })
+            .collect()
+    }
 }
 
 impl fmt::Display for P2pConnectionOutgoingInitOpts {
@@ -255,6 +289,10 @@ impl fmt::Display for P2pConnectionOutgoingInitOpts {
             Self::LibP2P(v) => {
                 write!(f, "{}", v.to_maddr())
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::LibP2PQuic(v) => {
+                write!(f, "{}", v.to_quic_maddr())
+            }
         }
     }
 }
@@ -286,9 +324,7 @@ impl FromStr for P2pConnectionOutgoingInitOpts {
             let maddr = multiaddr::Multiaddr::from_str(s)
                 .map_err(|e| P2pConnectionOutgoingInitOptsParseError::Other(e.to_string()))?;
 
-            let opts = (&maddr).try_into()?;
-
-            return Ok(Self::LibP2P(opts));
+            return (&maddr).try_into();
         }
         #[cfg(target_arch = "wasm32")]
         if is_libp2p_maddr {
@@ -357,7 +393,12 @@ impl TryFrom<&multiaddr::Multiaddr> for P2pConnectionOutgoingInitOpts {
     type Error = P2pConnectionOutgoingInitOptsParseError;
 
     fn try_from(value: &multiaddr::Multiaddr) -> Result<Self, Self::Error> {
-        Ok(Self::LibP2P(value.try_into()?))
+        let (opts, quic) = parse_libp2p_maddr(value)?;
+        Ok(if quic {
+            Self::LibP2PQuic(opts)
+        } else {
+            Self::LibP2P(opts)
+        })
     }
 }
 
@@ -366,7 +407,7 @@ impl TryFrom<multiaddr::Multiaddr> for P2pConnectionOutgoingInitOpts {
     type Error = P2pConnectionOutgoingInitOptsParseError;
 
     fn try_from(value: multiaddr::Multiaddr) -> Result<Self, Self::Error> {
-        Ok(Self::LibP2P((&value).try_into()?))
+        (&value).try_into()
     }
 }
 
@@ -375,58 +416,85 @@ impl TryFrom<&multiaddr::Multiaddr> for P2pConnectionOutgoingInitLibp2pOpts {
     type Error = P2pConnectionOutgoingInitOptsParseError;
 
     fn try_from(maddr: &multiaddr::Multiaddr) -> Result<Self, Self::Error> {
-        use multiaddr::Protocol;
+        match parse_libp2p_maddr(maddr)? {
+            (opts, false) => Ok(opts),
+            (_, true) => Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                "unexpected QUIC multiaddr! expected tcp port".to_string(),
+            )),
+        }
+    }
+}
 
-        let mut iter = maddr.iter();
-        Ok(P2pConnectionOutgoingInitLibp2pOpts {
-            host: match iter.next() {
-                Some(Protocol::Ip4(v)) => Host::Ipv4(v),
-                Some(Protocol::Dns(v) | Protocol::Dns4(v) | Protocol::Dns6(v)) => {
-                    Host::Domain(v.into_owned())
-                }
-                Some(_) => {
-                    return Err(P2pConnectionOutgoingInitOptsParseError::Other(
-                        "unexpected part in multiaddr! expected host".to_string(),
-                    ));
-                }
-                None => {
-                    return Err(P2pConnectionOutgoingInitOptsParseError::Other(
-                        "missing host part from multiaddr".to_string(),
-                    ));
-                }
-            },
-            port: match iter.next() {
-                Some(Protocol::Tcp(port)) => port,
-                Some(_) => {
-                    return Err(P2pConnectionOutgoingInitOptsParseError::Other(
-                        "unexpected part in multiaddr! expected port".to_string(),
-                    ));
-                }
-                None => {
-                    return Err(P2pConnectionOutgoingInitOptsParseError::Other(
-                        "missing port part from multiaddr".to_string(),
-                    ));
-                }
-            },
-            peer_id: match iter.next() {
-                Some(Protocol::P2p(hash)) => libp2p_identity::PeerId::from_multihash(hash.into())
-                    .map_err(|_| {
-                        P2pConnectionOutgoingInitOptsParseError::Other(
-                            "invalid peer_id multihash".to_string(),
-                        )
-                    })?
-                    .into(),
-                Some(_) => {
-                    return Err(P2pConnectionOutgoingInitOptsParseError::Other(
-                        "unexpected part in multiaddr! expected peer_id".to_string(),
-                    ));
+/// Parses `/<host>/tcp/<port>/p2p/<peer_id>` or `/<host>/udp/<port>/quic-v1/p2p/<peer_id>`,
+/// the flag is set for the latter.
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_libp2p_maddr(
+    maddr: &multiaddr::Multiaddr,
+) -> Result<(P2pConnectionOutgoingInitLibp2pOpts, bool), P2pConnectionOutgoingInitOptsParseError> {
+    use multiaddr::Protocol;
+
+    let mut iter = maddr.iter();
+    let mut quic = false;
+    let opts = P2pConnectionOutgoingInitLibp2pOpts {
+        host: match iter.next() {
+            Some(Protocol::Ip4(v)) => Host::Ipv4(v),
+            Some(Protocol::Dns(v) | Protocol::Dns4(v) | Protocol::Dns6(v)) => {
+                Host::Domain(v.into_owned())
+            }
+            Some(_) => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "unexpected part in multiaddr! expected host".to_string(),
+                ));
+            }
+            None => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "missing host part from multiaddr".to_string(),
+                ));
+            }
+        },
+        port: match iter.next() {
+            Some(Protocol::Tcp(port)) => port,
+            Some(Protocol::Udp(port)) => match iter.next() {
+                Some(Protocol::QuicV1) => {
+                    quic = true;
+                    port
                 }
-                None => {
+                _ => {
                     return Err(P2pConnectionOutgoingInitOptsParseError::Other(
-                        "peer_id not set in multiaddr. Missing `../p2p/<peer_id>`".to_string(),
+                        "unexpected part in multiaddr! expected quic-v1".to_string(),
                     ));
                 }
             },
-        })
-    }
+            Some(_) => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "unexpected part in multiaddr! expected port".to_string(),
+                ));
+            }
+            None => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "missing port part from multiaddr".to_string(),
+                ));
+            }
+        },
+        peer_id: match iter.next() {
+            Some(Protocol::P2p(hash)) => libp2p_identity::PeerId::from_multihash(hash.into())
+                .map_err(|_| {
+                    P2pConnectionOutgoingInitOptsParseError::Other(
+                        "invalid peer_id multihash".to_string(),
+                    )
+                })?
+                .into(),
+            Some(_) => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "unexpected part in multiaddr! expected peer_id".to_string(),
+                ));
+            }
+            None => {
+                return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                    "peer_id not set in multiaddr. Missing `../p2p/<peer_id>`".to_string(),
+                ));
+            }
+        },
+    };
+    Ok((opts, quic))
 }
@@ -118,10 +118,10 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
     fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
         match self {
             P2pConnectionOutgoingAction::RandomInit => {
-                !state.already_has_min_peers() && state.disconnected_peers().next().is_some()
+                state.needs_outgoing_peers() && state.disconnected_peers().next().is_some()
             }
             P2pConnectionOutgoingAction::Init { opts, .. } => {
-                !state.already_has_min_peers() &&
+                state.needs_outgoing_peers() &&
                 &state.my_id() != opts.peer_id() &&
                 state
                     .peers
@@ -129,7 +129,7 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
                     .map_or(true, |peer| !peer.status.is_connected_or_connecting())
             }
             P2pConnectionOutgoingAction::Reconnect { opts, .. } => {
-                !state.already_has_min_peers()
+                state.needs_outgoing_peers()
                     && state.peers.get(opts.peer_id()).map_or(false, |peer| {
                         peer.can_reconnect(time, &state.config.timeouts)
                     })
@@ -2,11 +2,14 @@ use std::net::SocketAddr;
 
 use redux::ActionMeta;
 
+use crate::channels::signaling::P2pChannelsSignalingAction;
 use crate::connection::{P2pConnectionErrorResponse, P2pConnectionState};
 use crate::peer::P2pPeerAction;
 use crate::webrtc::Host;
 use crate::{connection::P2pConnectionService, webrtc};
-use crate::{P2pNetworkKadRequestAction, P2pNetworkSchedulerAction, P2pPeerStatus};
+use crate::{
+    P2pNetworkKadRequestAction, P2pNetworkQuicAction, P2pNetworkSchedulerAction, P2pPeerStatus,
+};
 
 use super::libp2p_opts::P2pConnectionOutgoingInitLibp2pOptsTryToSocketAddrError;
 use super::{
@@ -32,8 +35,13 @@ impl P2pConnectionOutgoingAction {
             P2pConnectionOutgoingAction::Init { opts, .. }
             | P2pConnectionOutgoingAction::Reconnect { opts, .. } => {
                 let peer_id = *opts.peer_id();
-                if let P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts) = &opts {
+                if let Some(libp2p_opts) = opts.libp2p_opts() {
                     match SocketAddr::try_from(libp2p_opts) {
+                        Ok(addr)
+                            if matches!(opts, P2pConnectionOutgoingInitOpts::LibP2PQuic(_)) =>
+                        {
+                            store.dispatch(P2pNetworkQuicAction::OutgoingConnect { addr, peer_id });
+                        }
                         Ok(addr) => {
                             store.dispatch(P2pNetworkSchedulerAction::OutgoingConnect { addr });
                         }
@@ -70,8 +78,7 @@ impl P2pConnectionOutgoingAction {
                 store.dispatch(P2pConnectionOutgoingAction::OfferReady { peer_id, offer });
             }
             P2pConnectionOutgoingAction::OfferReady { peer_id, offer } => {
-                let (state, service) = store.state_and_service();
-                let Some(peer) = state.peers.get(&peer_id) else {
+                let Some(peer) = store.state().peers.get(&peer_id) else {
                     return;
                 };
                 let P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(
@@ -81,19 +88,33 @@ impl P2pConnectionOutgoingAction {
                     return;
                 };
                 let signaling_method = match opts {
-                    P2pConnectionOutgoingInitOpts::WebRTC { signaling, .. } => signaling,
+                    P2pConnectionOutgoingInitOpts::WebRTC { signaling, .. } => signaling.clone(),
                     #[allow(unreachable_patterns)]
                     _ => return,
                 };
-                match signaling_method {
+                let offer_sent = match signaling_method {
                     webrtc::SignalingMethod::Http(_) | webrtc::SignalingMethod::Https(_) => {
                         let Some(url) = signaling_method.http_url() else {
                             return;
                         };
-                        service.http_signaling_request(url, offer);
+                        store.service().http_signaling_request(url, offer);
+                        true
                     }
-                }
+                    webrtc::SignalingMethod::P2p { relay_peer_id } => {
+                        store.dispatch(P2pChannelsSignalingAction::OfferSend {
+                            peer_id: relay_peer_id,
+                            offer,
+                        })
+                    }
+                };
                 store.dispatch(P2pConnectionOutgoingAction::OfferSendSuccess { peer_id });
+                if !offer_sent {
+                    // relay isn't connected anymore.
+                    store.dispatch(P2pConnectionOutgoingAction::AnswerRecvError {
+                        peer_id,
+                        error: P2pConnectionErrorResponse::InternalError,
+                    });
+                }
             }
             P2pConnectionOutgoingAction::OfferSendSuccess { peer_id } => {
                 store.dispatch(P2pConnectionOutgoingAction::AnswerRecvPending { peer_id });
@@ -0,0 +1,449 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use redux::Timestamp;
+
+use crate::{is_circuit_ip, Limit, P2pPeerState, P2pPeerStatus, P2pState, PeerId};
+
+use super::{P2pConnectionState, RejectionReason};
+
+/// Subnet used to limit the number of peers from the same network, `/24` for
+/// IPv4 and `/48` for IPv6.
+pub fn peer_subnet(ip: &IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            Ipv4Addr::new(a, b, c, 0).into()
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            Ipv6Addr::new(a, b, c, 0, 0, 0, 0, 0).into()
+        }
+    }
+}
+
+/// Address that is taken into account for the peer diversity. Loopback
+/// addresses (local clusters) and relayed connections are not.
+fn diversity_ip(ip: IpAddr) -> Option<IpAddr> {
+    (!ip.is_loopback() && !is_circuit_ip(&ip)).then_some(ip)
+}
+
+fn excess(count: usize, limit: Limit<usize>) -> usize {
+    match limit {
+        Limit::Some(limit) => count.saturating_sub(limit),
+        Limit::Unlimited => 0,
+    }
+}
+
+impl P2pPeerState {
+    /// The connection with the peer is initiated by the peer.
+    pub fn is_incoming(&self) -> bool {
+        match &self.status {
+            P2pPeerStatus::Ready(ready) => ready.is_incoming,
+            P2pPeerStatus::Connecting(P2pConnectionState::Incoming(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl P2pState {
+    /// The peer is one of the initial peers (seeds).
+    pub fn is_seed(&self, peer_id: &PeerId) -> bool {
+        self.config
+            .initial_peers
+            .iter()
+            .any(|opts| opts.peer_id() == peer_id)
+    }
+
+    pub fn outbound_peers_count(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|p| p.status.is_connected_or_connecting() && !p.is_incoming())
+            .count()
+    }
+
+    /// The node should initiate more connections, either because it doesn't
+    /// have enough peers, or because the slots reserved for outgoing
+    /// connections are not filled.
+    pub fn needs_outgoing_peers(&self) -> bool {
+        !self.already_has_min_peers()
+            || (!self.already_has_max_peers()
+                && self.outbound_peers_count() < self.config.limits.outbound_peers_reserved())
+    }
+
+    /// Remote IP addresses of the peers connected over libp2p.
+    fn peer_ips(&self, incoming_only: bool) -> BTreeMap<PeerId, IpAddr> {
+        self.network
+            .scheduler
+            .connections
+            .iter()
+            .filter(|(_, conn)| conn.closed.is_none() && (conn.incoming || !incoming_only))
+            .filter_map(|(addr, conn)| Some((*conn.peer_id()?, diversity_ip(addr.ip())?)))
+            .collect()
+    }
+
+    /// Checks the connection manager policies for the incoming connection
+    /// from the peer. `ip` is `None` if the remote address is not known.
+    pub fn incoming_accept_limits(
+        &self,
+        peer_id: &PeerId,
+        is_libp2p: bool,
+        ip: Option<IpAddr>,
+    ) -> Result<(), RejectionReason> {
+        if self.already_has_max_ready_peers() {
+            return Err(RejectionReason::PeerCapacityFull);
+        }
+
+        // seeds help us to find other peers, they are trusted
+        if self.is_seed(peer_id) {
+            return Ok(());
+        }
+
+        let limits = &self.config.limits;
+        let (inbound, same_transport) = self
+            .peers
+            .iter()
+            .filter(|(id, p)| {
+                *id != peer_id && p.status.is_connected_or_connecting() && p.is_incoming()
+            })
+            .fold((0, 0), |(inbound, same_transport), (_, p)| {
+                let same = usize::from(p.is_libp2p() == is_libp2p);
+                (inbound + 1, same_transport + same)
+            });
+        if inbound >= limits.max_inbound_peers() {
+            return Err(RejectionReason::InboundCapacityFull);
+        }
+        if same_transport >= limits.max_inbound_peers_per_transport() {
+            return Err(RejectionReason::TransportCapacityFull);
+        }
+
+        let Some(ip) = ip.and_then(diversity_ip) else {
+            return Ok(());
+        };
+        let subnet = peer_subnet(&ip);
+        let (same_ip, same_subnet) = self
+            .peer_ips(true)
+            .into_iter()
+            .filter(|(id, _)| id != peer_id)
+            .fold((0, 0), |(same_ip, same_subnet), (_, peer_ip)| {
+                (
+                    same_ip + usize::from(peer_ip == ip),
+                    same_subnet + usize::from(peer_subnet(&peer_ip) == subnet),
+                )
+            });
+        if same_ip >= limits.max_inbound_peers_per_ip() {
+            return Err(RejectionReason::TooManyPeersFromIp);
+        }
+        if same_subnet >= limits.max_inbound_peers_per_subnet() {
+            return Err(RejectionReason::TooManyPeersFromSubnet);
+        }
+
+        Ok(())
+    }
+
+    /// Ready peers that should be disconnected to get back within the limits.
+    ///
+    /// Seeds and peers that recently delivered a useful block are never
+    /// pruned. Otherwise peers from crowded subnets go first, then the ones
+    /// that never delivered a useful block, then the slowest and the most
+    /// recently connected ones.
+    pub fn peers_to_prune(&self, now: Timestamp) -> Vec<PeerId> {
+        let limits = &self.config.limits;
+        let timeouts = &self.config.timeouts;
+
+        let ready = self.ready_peers_iter().collect::<Vec<_>>();
+        let inbound = ready.iter().filter(|(_, p)| p.is_incoming).count();
+        let mut outbound = ready.len() - inbound;
+        let mut excess_total = excess(ready.len(), limits.max_peers());
+        let mut excess_inbound = excess(inbound, limits.max_inbound_peers());
+        if excess_total == 0 && excess_inbound == 0 {
+            return Vec::new();
+        }
+
+        let ips = self.peer_ips(false);
+        let mut subnets = BTreeMap::<IpAddr, usize>::new();
+        for ip in ips.values() {
+            *subnets.entry(peer_subnet(ip)).or_default() += 1;
+        }
+
+        let mut candidates = ready
+            .into_iter()
+            .filter(|(peer_id, p)| !self.is_seed(peer_id) && !p.is_protected(now, timeouts))
+            .map(|(peer_id, p)| {
+                let subnet_peers = ips
+                    .get(peer_id)
+                    .and_then(|ip| subnets.get(&peer_subnet(ip)))
+                    .copied()
+                    .unwrap_or(1);
+                let rtt = self.peers.get(peer_id).and_then(|p| p.rtt);
+                let value = (
+                    Reverse(subnet_peers),
+                    p.last_useful_block,
+                    rtt.map(Reverse),
+                    Reverse(p.connected_since),
+                );
+                (value, *peer_id, p.is_incoming)
+            })
+            .collect::<Vec<_>>();
+        candidates.sort();
+
+        let mut pruned = Vec::new();
+        for (_, peer_id, is_incoming) in candidates {
+            if excess_total == 0 && excess_inbound == 0 {
+                break;
+            }
+            if is_incoming {
+                excess_inbound = excess_inbound.saturating_sub(1);
+            } else if excess_total > excess_inbound && outbound > limits.outbound_peers_reserved() {
+                // pruning incoming peers reduces both numbers, so outgoing
+                // ones are pruned only if that is not enough
+                outbound -= 1;
+            } else {
+                continue;
+            }
+            excess_total = excess_total.saturating_sub(1);
+            pruned.push(peer_id);
+        }
+        pruned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    use crate::{
+        connection::outgoing::P2pConnectionOutgoingInitOpts, identity::SecretKey, P2pConfig,
+        P2pLimits, P2pNetworkAuthState, P2pNetworkConnectionState, P2pNetworkPnetState,
+        P2pNetworkSelectState, P2pPeerStatusReady, P2pTimeouts,
+    };
+
+    use super::*;
+
+    fn secs(secs: u64) -> Timestamp {
+        Timestamp::new(Duration::from_secs(secs).as_nanos() as u64)
+    }
+
+    fn state(limits: P2pLimits, seeds: &[PeerId]) -> P2pState {
+        let config = P2pConfig {
+            libp2p_port: None,
+            quic_port: None,
+            listen_port: 3000,
+            identity_pub_key: SecretKey::rand().public_key(),
+            initial_peers: seeds
+                .iter()
+                .map(|peer_id| {
+                    let addr = "1.1.1.1:8302".parse::<std::net::SocketAddr>().unwrap();
+                    P2pConnectionOutgoingInitOpts::LibP2P((*peer_id, addr).into())
+                })
+                .collect(),
+            address_book: Vec::new(),
+            ask_initial_peers_interval: Duration::from_secs(10),
+            enabled_channels: BTreeSet::new(),
+            timeouts: P2pTimeouts::default(),
+            limits,
+            peer_discovery: false,
+            nat_port_mapping: false,
+            relay_hop: false,
+            relays: Vec::new(),
+            initial_time: Duration::ZERO,
+        };
+        P2pState::new(config, &openmina_core::BERKELEY_CHAIN_ID)
+    }
+
+    fn add_peer(
+        state: &mut P2pState,
+        is_incoming: bool,
+        is_libp2p: bool,
+        connected_since: Timestamp,
+    ) -> PeerId {
+        let peer_id = SecretKey::rand().public_key().peer_id();
+        add_peer_with_id(state, peer_id, is_incoming, is_libp2p, connected_since);
+        peer_id
+    }
+
+    fn add_peer_with_id(
+        state: &mut P2pState,
+        peer_id: PeerId,
+        is_incoming: bool,
+        is_libp2p: bool,
+        connected_since: Timestamp,
+    ) {
+        let ready = P2pPeerStatusReady::new(is_incoming, connected_since, &BTreeSet::new());
+        let peer = state.peers.entry(peer_id).or_insert_with(|| P2pPeerState {
+            is_libp2p,
+            dial_opts: None,
+            status: P2pPeerStatus::Disconnected {
+                time: Timestamp::ZERO,
+            },
+            identify: None,
+            rtt: None,
+        });
+        peer.is_libp2p = is_libp2p;
+        peer.status = P2pPeerStatus::Ready(ready);
+    }
+
+    fn add_connection(state: &mut P2pState, peer_id: PeerId, addr: &str) {
+        let pnet_key = state.network.scheduler.pnet_key;
+        state.network.scheduler.connections.insert(
+            addr.parse().unwrap(),
+            P2pNetworkConnectionState {
+                incoming: true,
+                quic: true,
+                pnet: P2pNetworkPnetState::new(pnet_key),
+                select_auth: P2pNetworkSelectState::default(),
+                auth: Some(P2pNetworkAuthState::Tls(peer_id)),
+                select_mux: P2pNetworkSelectState::default(),
+                mux: None,
+                streams: Default::default(),
+                closed: None,
+                limit: P2pNetworkConnectionState::INITIAL_LIMIT,
+            },
+        );
+    }
+
+    fn new_peer_id() -> PeerId {
+        SecretKey::rand().public_key().peer_id()
+    }
+
+    #[test]
+    fn incoming_accept_transport_and_inbound_limits() {
+        // 8 peers: 2 slots reserved for outgoing connections, 6 inbound
+        // slots, at most 4 of them for the same transport
+        let seed = new_peer_id();
+        let limits = P2pLimits::default().with_max_peers(Some(8));
+        let mut state = state(limits, &[seed]);
+
+        for _ in 0..4 {
+            add_peer(&mut state, true, true, secs(1));
+        }
+        assert_eq!(
+            state.incoming_accept_limits(&new_peer_id(), true, None),
+            Err(RejectionReason::TransportCapacityFull)
+        );
+        assert_eq!(
+            state.incoming_accept_limits(&new_peer_id(), false, None),
+            Ok(())
+        );
+
+        for _ in 0..2 {
+            add_peer(&mut state, true, false, secs(1));
+        }
+        assert_eq!(
+            state.incoming_accept_limits(&new_peer_id(), false, None),
+            Err(RejectionReason::InboundCapacityFull)
+        );
+        // seeds are trusted
+        assert_eq!(state.incoming_accept_limits(&seed, true, None), Ok(()));
+
+        for _ in 0..2 {
+            add_peer(&mut state, false, true, secs(1));
+        }
+        assert_eq!(
+            state.incoming_accept_limits(&seed, true, None),
+            Err(RejectionReason::PeerCapacityFull)
+        );
+    }
+
+    #[test]
+    fn incoming_accept_ip_and_subnet_limits() {
+        let limits = P2pLimits::default()
+            .with_max_inbound_peers_per_ip(Some(1))
+            .with_max_inbound_peers_per_subnet(Some(2));
+        let mut state = state(limits, &[]);
+        let ip = |s: &str| Some(s.parse::<IpAddr>().unwrap());
+
+        let peer_id = add_peer(&mut state, true, true, secs(1));
+        add_connection(&mut state, peer_id, "1.2.3.4:10000");
+        assert_eq!(
+            state.incoming_accept_limits(&new_peer_id(), true, ip("1.2.3.4")),
+            Err(RejectionReason::TooManyPeersFromIp)
+        );
+        // the peer itself is not counted
+        assert_eq!(
+            state.incoming_accept_limits(&peer_id, true, ip("1.2.3.4")),
+            Ok(())
+        );
+
+        let peer_id = add_peer(&mut state, true, true, secs(1));
+        add_connection(&mut state, peer_id, "1.2.3.5:10000");
+        assert_eq!(
+            state.incoming_accept_limits(&new_peer_id(), true, ip("1.2.3.6")),
+            Err(RejectionReason::TooManyPeersFromSubnet)
+        );
+        assert_eq!(
+            state.incoming_accept_limits(&new_peer_id(), true, ip("1.2.4.6")),
+            Ok(())
+        );
+        // local clusters are not limited
+        let peer_id = add_peer(&mut state, true, true, secs(1));
+        add_connection(&mut state, peer_id, "127.0.0.1:10000");
+        assert_eq!(
+            state.incoming_accept_limits(&new_peer_id(), true, ip("127.0.0.1")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn peers_to_prune_within_limits() {
+        let limits = P2pLimits::default().with_max_peers(Some(4));
+        let mut state = state(limits, &[]);
+        for i in 0..4 {
+            add_peer(&mut state, i % 2 == 0, true, secs(i));
+        }
+        assert!(state.peers_to_prune(secs(10)).is_empty());
+    }
+
+    #[test]
+    fn peers_to_prune_prefers_incoming_and_recent_peers() {
+        // 4 peers: 1 slot reserved for outgoing connections, 3 inbound
+        let seed = new_peer_id();
+        let limits = P2pLimits::default().with_max_peers(Some(4));
+        let mut state = state(limits, &[seed]);
+        let now = secs(100);
+
+        let _outgoing = (1..=3)
+            .map(|i| add_peer(&mut state, false, true, secs(i)))
+            .collect::<Vec<_>>();
+        let incoming = (4..=5)
+            .map(|i| add_peer(&mut state, true, true, secs(i)))
+            .collect::<Vec<_>>();
+        add_peer_with_id(&mut state, seed, true, true, secs(6));
+        let useful = add_peer(&mut state, true, true, secs(7));
+        if let P2pPeerStatus::Ready(ready) = &mut state.peers.get_mut(&useful).unwrap().status {
+            ready.last_useful_block = Some(now);
+        }
+
+        // 7 peers, 3 excess, the seed and the useful peer are kept,
+        // so the most recent incoming peers are pruned first
+        let pruned = state.peers_to_prune(now);
+        assert_eq!(pruned.len(), 3);
+        assert_eq!(&pruned[..2], &[incoming[1], incoming[0]]);
+        assert!(!pruned.contains(&seed));
+        assert!(!pruned.contains(&useful));
+    }
+
+    #[test]
+    fn peers_to_prune_keeps_reserved_outgoing_slots() {
+        let limits = P2pLimits::default().with_max_peers(Some(4));
+        let mut state = state(limits, &[]);
+        let now = secs(100);
+
+        let outgoing = (1..=3)
+            .map(|i| add_peer(&mut state, false, true, secs(i)))
+            .collect::<Vec<_>>();
+        for i in 4..=6 {
+            let peer_id = add_peer(&mut state, true, true, secs(i));
+            if let P2pPeerStatus::Ready(ready) = &mut state.peers.get_mut(&peer_id).unwrap().status
+            {
+                ready.last_useful_block = Some(now);
+            }
+        }
+
+        // incoming peers are protected, so the most recent outgoing ones are
+        // pruned, keeping the reserved outgoing slot
+        assert_eq!(state.peers_to_prune(now), vec![outgoing[2], outgoing[1]]);
+    }
+}
@@ -37,4 +37,13 @@ pub enum P2pDisconnectionReason {
 
     #[error("timeout")]
     Timeout,
+
+    #[error("ping timeout")]
+    PingTimeout,
+
+    #[error("relayed connection is upgraded to a direct one")]
+    DirectConnectionUpgrade,
+
+    #[error("pruned to get back within the peer limits")]
+    Pruned,
 }
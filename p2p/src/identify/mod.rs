@@ -2,3 +2,4 @@ mod p2p_identify_actions;
 pub use self::p2p_identify_actions::*;
 
 mod p2p_identify_effects;
+pub(crate) use p2p_identify_effects::identify_push;
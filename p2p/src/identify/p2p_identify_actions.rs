@@ -9,6 +9,9 @@ use std::net::SocketAddr;
 pub enum P2pIdentifyAction {
     /// Open a new yamux stream to the remote peer to request its identity
     NewRequest { peer_id: PeerId, addr: SocketAddr },
+    /// Open a new yamux stream to the remote peer to push our identity,
+    /// when our listen addresses are changed.
+    Push { peer_id: PeerId, addr: SocketAddr },
     /// Updates the P2P peer information based on the Identify message sent to us.
     UpdatePeerInformation {
         peer_id: PeerId,
@@ -19,7 +22,9 @@ pub enum P2pIdentifyAction {
 impl redux::EnablingCondition<P2pState> for P2pIdentifyAction {
     fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
         match self {
-            Self::NewRequest { peer_id, .. } => state.get_ready_peer(peer_id).is_some(),
+            Self::NewRequest { peer_id, .. } | Self::Push { peer_id, .. } => {
+                state.get_ready_peer(peer_id).is_some()
+            }
             Self::UpdatePeerInformation { peer_id, .. } => state.get_ready_peer(peer_id).is_some(),
         }
     }
@@ -1,6 +1,8 @@
+use std::net::SocketAddr;
+
 use crate::{
-    connection::P2pConnectionService, P2pNetworkConnectionMuxState, P2pNetworkKademliaAction,
-    P2pNetworkYamuxAction, P2pStore,
+    connection::P2pConnectionService, nat::P2pNatAction, socket_addr_try_from_multiaddr,
+    P2pNetworkKademliaAction, P2pNetworkYamuxAction, P2pStore,
 };
 use openmina_core::error;
 use redux::ActionMeta;
@@ -14,7 +16,17 @@ impl P2pIdentifyAction {
         Store::Service: P2pConnectionService,
     {
         match self {
-            P2pIdentifyAction::NewRequest { addr, .. } => {
+            P2pIdentifyAction::NewRequest { addr, .. } | P2pIdentifyAction::Push { addr, .. } => {
+                let (yamux_stream_kind, protocol) = match self {
+                    P2pIdentifyAction::Push { .. } => (
+                        crate::YamuxStreamKind::IdentifyPush,
+                        crate::token::IdentifyAlgorithm::IdentifyPush1_0_0,
+                    ),
+                    _ => (
+                        crate::YamuxStreamKind::Identify,
+                        crate::token::IdentifyAlgorithm::Identify1_0_0,
+                    ),
+                };
                 let scheduler = &store.state().network.scheduler;
                 let stream_id = scheduler
                     .connections
@@ -26,9 +38,8 @@ impl P2pIdentifyAction {
                             .map(|mux| (mux, conn.incoming))
                             .ok_or_else(|| format!("multiplexing is not ready for {addr}"))
                     })
-                    .and_then(|(P2pNetworkConnectionMuxState::Yamux(yamux), incoming)| {
-                        yamux
-                            .next_stream_id(crate::YamuxStreamKind::Identify, incoming)
+                    .and_then(|(mux, incoming)| {
+                        mux.next_stream_id(yamux_stream_kind, incoming)
                             .ok_or_else(|| format!("cannot get next stream for {addr}"))
                     });
 
@@ -37,9 +48,7 @@ impl P2pIdentifyAction {
                         store.dispatch(P2pNetworkYamuxAction::OpenStream {
                             addr,
                             stream_id,
-                            stream_kind: crate::token::StreamKind::Identify(
-                                crate::token::IdentifyAlgorithm::Identify1_0_0,
-                            ),
+                            stream_kind: crate::token::StreamKind::Identify(protocol),
                         });
                     }
                     Err(e) => {
@@ -48,11 +57,53 @@ impl P2pIdentifyAction {
                 }
             }
             P2pIdentifyAction::UpdatePeerInformation { peer_id, info } => {
+                let observed_addr = info
+                    .observed_addr
+                    .as_ref()
+                    .and_then(|maddr| socket_addr_try_from_multiaddr(maddr).ok());
                 store.dispatch(P2pNetworkKademliaAction::UpdateRoutingTable {
                     peer_id,
                     addrs: info.listen_addrs,
                 });
+                let state = store.state();
+                let observer = state
+                    .network
+                    .scheduler
+                    .connections
+                    .iter()
+                    .find(|(_, conn)| conn.peer_id() == Some(&peer_id) && conn.closed.is_none())
+                    .map(|(addr, _)| addr.ip());
+                if let (Some(observed_addr), Some(observer), Some(port)) =
+                    (observed_addr, observer, state.config.libp2p_port)
+                {
+                    // the peer observes the port of the connection, which is
+                    // different from the listening one if we dialed the peer
+                    store.dispatch(P2pNatAction::ObservedAddr {
+                        peer_id,
+                        observer,
+                        addr: SocketAddr::new(observed_addr.ip(), port),
+                    });
+                }
             }
         }
     }
 }
+
+/// Notifies the connected peers about the change of our listen addresses.
+pub(crate) fn identify_push<Store, S>(store: &mut Store)
+where
+    Store: crate::P2pStore<S>,
+{
+    let peers = store
+        .state()
+        .network
+        .scheduler
+        .connections
+        .iter()
+        .filter(|(_, conn)| conn.mux.is_some() && conn.closed.is_none())
+        .filter_map(|(addr, conn)| Some((*addr, *conn.peer_id()?)))
+        .collect::<Vec<_>>();
+    for (addr, peer_id) in peers {
+        store.dispatch(P2pIdentifyAction::Push { peer_id, addr });
+    }
+}
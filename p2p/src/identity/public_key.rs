@@ -1,5 +1,6 @@
 use std::{fmt, str::FromStr};
 
+use binprot::{BinProtRead, BinProtWrite};
 use ed25519_dalek::VerifyingKey as Ed25519PublicKey;
 use serde::{Deserialize, Serialize};
 
@@ -98,3 +99,25 @@ impl<'de> serde::Deserialize<'de> for PublicKey {
         }
     }
 }
+
+impl BinProtWrite for PublicKey {
+    fn binprot_write<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        for b in self.to_bytes() {
+            b.binprot_write(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl BinProtRead for PublicKey {
+    fn binprot_read<R: std::io::Read + ?Sized>(r: &mut R) -> Result<Self, binprot::Error>
+    where
+        Self: Sized,
+    {
+        let mut bytes = [0; 32];
+        for b in &mut bytes {
+            *b = u8::binprot_read(r)?;
+        }
+        Self::from_bytes(bytes).map_err(|err| binprot::Error::CustomError(err.into()))
+    }
+}
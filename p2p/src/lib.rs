@@ -1,10 +1,12 @@
 ///#![feature(trivial_bounds)]
+pub mod address_book;
 pub mod channels;
 pub mod connection;
 pub mod disconnection;
 pub mod discovery;
 pub mod identify;
 pub mod identity;
+pub mod nat;
 pub mod peer;
 pub use identity::PeerId;
 
@@ -0,0 +1,63 @@
+use std::net::{IpAddr, SocketAddr};
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use super::{is_public_ip, P2pNatPortMapping, P2pNatPortMappingState, P2pNatTransport};
+use crate::{P2pState, PeerId};
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = info, fields(display(transport), display(internal), display(addr), display(peer_id), display(observer), debug(mapping), display(error)))]
+pub enum P2pNatAction {
+    /// Asks the gateway to map the port of the `transport` to the `internal` address.
+    MapPort {
+        transport: P2pNatTransport,
+        internal: SocketAddr,
+    },
+    /// The gateway created the port mapping.
+    MapPortSuccess { mapping: P2pNatPortMapping },
+    /// Neither UPnP nor NAT-PMP gateway managed to map the port.
+    #[action_event(level = warn)]
+    MapPortError {
+        transport: P2pNatTransport,
+        error: String,
+    },
+    /// The peer, connected from the `observer` address, reported the address
+    /// it observes us at.
+    #[action_event(level = debug)]
+    ObservedAddr {
+        peer_id: PeerId,
+        observer: IpAddr,
+        addr: SocketAddr,
+    },
+    /// The address is confirmed to be our external address,
+    /// so it is advertised to other peers.
+    ExternalAddrConfirmed { addr: SocketAddr },
+    /// The address wasn't confirmed for too long, so it isn't advertised anymore.
+    ExternalAddrExpired { addr: SocketAddr },
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNatAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        let nat = &state.nat;
+        match self {
+            P2pNatAction::MapPort { transport, .. } => {
+                nat.should_map_port(*transport, time, &state.config)
+            }
+            P2pNatAction::MapPortSuccess {
+                mapping: P2pNatPortMapping { transport, .. },
+            }
+            | P2pNatAction::MapPortError { transport, .. } => matches!(
+                nat.port_mapping_state(*transport),
+                P2pNatPortMappingState::Pending { .. }
+            ),
+            P2pNatAction::ObservedAddr { peer_id, addr, .. } => {
+                state.get_ready_peer(peer_id).is_some() && is_public_ip(&addr.ip())
+            }
+            P2pNatAction::ExternalAddrConfirmed { addr } => !nat.external_addrs.contains_key(addr),
+            P2pNatAction::ExternalAddrExpired { addr } => nat
+                .expired_external_addrs(time, &state.config.timeouts)
+                .any(|expired| &expired == addr),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use redux::ActionMeta;
+
+use crate::{identify::identify_push, P2pStore};
+
+use super::{
+    P2pNatAction, P2pNatPortMappingState, P2pNatService, P2pNatTransport,
+    NAT_PORT_MAPPING_LIFETIME, OBSERVED_ADDR_CONFIRMATIONS,
+};
+
+impl P2pNatAction {
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store)
+    where
+        Store: P2pStore<S>,
+        Store::Service: P2pNatService,
+    {
+        match self {
+            P2pNatAction::MapPort {
+                transport,
+                internal,
+            } => {
+                store
+                    .service()
+                    .nat_map_port(transport, internal, NAT_PORT_MAPPING_LIFETIME);
+            }
+            P2pNatAction::MapPortSuccess { mapping } => match mapping.transport {
+                P2pNatTransport::Tcp => {
+                    store.dispatch(P2pNatAction::ExternalAddrConfirmed {
+                        addr: mapping.external,
+                    });
+                }
+                // renewals happen once per half of the mapping lifetime,
+                // so the peers are simply notified about each of them
+                P2pNatTransport::Udp => identify_push(store),
+            },
+            P2pNatAction::MapPortError { transport, .. } => {
+                let expired = matches!(
+                    store.state().nat.port_mapping_state(transport),
+                    P2pNatPortMappingState::Error {
+                        previous: Some(_),
+                        ..
+                    }
+                );
+                if transport == P2pNatTransport::Udp && expired {
+                    identify_push(store);
+                }
+            }
+            P2pNatAction::ObservedAddr { addr, .. } => {
+                let state = store.state();
+                let count = state
+                    .nat
+                    .observed_count(&addr, meta.time(), &state.config.timeouts);
+                if count >= OBSERVED_ADDR_CONFIRMATIONS {
+                    store.dispatch(P2pNatAction::ExternalAddrConfirmed { addr });
+                }
+            }
+            // external addresses are added to the identify and kademlia
+            // messages, so the peers need to learn about the change
+            P2pNatAction::ExternalAddrConfirmed { .. }
+            | P2pNatAction::ExternalAddrExpired { .. } => {
+                identify_push(store);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use redux::ActionWithMeta;
+
+use crate::P2pTimeouts;
+
+use super::{
+    P2pNatAction, P2pNatPortMappingState, P2pNatState, P2pNatTransport, OBSERVED_ADDR_CONFIRMATIONS,
+};
+
+impl P2pNatState {
+    pub fn reducer(&mut self, action: ActionWithMeta<&P2pNatAction>, timeouts: &P2pTimeouts) {
+        let (action, meta) = action.split();
+        match action {
+            P2pNatAction::MapPort {
+                transport,
+                internal,
+            } => {
+                let previous = self.mapping(*transport).cloned();
+                *self.port_mapping_state_mut(*transport) = P2pNatPortMappingState::Pending {
+                    time: meta.time(),
+                    internal: *internal,
+                    previous,
+                };
+            }
+            P2pNatAction::MapPortSuccess { mapping } => {
+                // the QUIC mapping is advertised directly from its state,
+                // only the TCP one goes through the external addresses
+                if mapping.transport == P2pNatTransport::Tcp {
+                    // the gateway might have assigned another address on renewal
+                    if let Some(previous) = self.previous_mapping(mapping.transport) {
+                        if previous.external != mapping.external {
+                            let previous = previous.external;
+                            self.external_addrs.remove(&previous);
+                        }
+                    }
+                    // renewed mapping confirms the address again
+                    if let Some(time) = self.external_addrs.get_mut(&mapping.external) {
+                        *time = meta.time();
+                    }
+                }
+                *self.port_mapping_state_mut(mapping.transport) = P2pNatPortMappingState::Mapped {
+                    time: meta.time(),
+                    mapping: mapping.clone(),
+                };
+            }
+            P2pNatAction::MapPortError { transport, error } => {
+                // the mapping is going to expire, so it shouldn't be advertised anymore
+                let previous = self.previous_mapping(*transport).cloned();
+                if let Some(previous) = previous
+                    .as_ref()
+                    .filter(|previous| previous.transport == P2pNatTransport::Tcp)
+                {
+                    self.external_addrs.remove(&previous.external);
+                }
+                *self.port_mapping_state_mut(*transport) = P2pNatPortMappingState::Error {
+                    time: meta.time(),
+                    error: error.clone(),
+                    previous,
+                };
+            }
+            P2pNatAction::ObservedAddr {
+                peer_id,
+                observer,
+                addr,
+            } => {
+                self.add_observed_addr(*peer_id, *observer, *addr, meta.time());
+                // fresh observations confirm the already known address again
+                let confirmed =
+                    self.observed_count(addr, meta.time(), timeouts) >= OBSERVED_ADDR_CONFIRMATIONS;
+                if let Some(time) = self.external_addrs.get_mut(addr).filter(|_| confirmed) {
+                    *time = meta.time();
+                }
+            }
+            P2pNatAction::ExternalAddrConfirmed { addr } => {
+                self.external_addrs.insert(*addr, meta.time());
+            }
+            P2pNatAction::ExternalAddrExpired { addr } => {
+                self.external_addrs.remove(addr);
+            }
+        }
+    }
+}
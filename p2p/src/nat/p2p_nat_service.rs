@@ -0,0 +1,54 @@
+use std::{net::SocketAddr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Lifetime of the port mapping requested from the gateway,
+/// the mapping is renewed when half of it is passed.
+pub const NAT_PORT_MAPPING_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum P2pNatProtocol {
+    #[display(fmt = "UPnP")]
+    Upnp,
+    #[display(fmt = "NAT-PMP")]
+    NatPmp,
+}
+
+/// Transport protocol of the mapped port.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum P2pNatTransport {
+    /// Port of the libp2p TCP transport.
+    #[display(fmt = "TCP")]
+    Tcp,
+    /// Port of the QUIC transport.
+    #[display(fmt = "UDP")]
+    Udp,
+}
+
+impl P2pNatTransport {
+    pub const ALL: [Self; 2] = [Self::Tcp, Self::Udp];
+}
+
+/// Port mapping created on the gateway.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct P2pNatPortMapping {
+    pub protocol: P2pNatProtocol,
+    pub transport: P2pNatTransport,
+    /// Local address the gateway forwards the connections to.
+    pub internal: SocketAddr,
+    /// Address the remote peers can connect to.
+    pub external: SocketAddr,
+    /// Lifetime of the mapping granted by the gateway.
+    pub lifetime: Duration,
+}
+
+pub trait P2pNatService: redux::Service {
+    /// Asks the gateway to forward the `transport` port to the `internal` address,
+    /// the result is reported with the `P2pNatEvent::PortMapped` event.
+    fn nat_map_port(
+        &mut self,
+        transport: P2pNatTransport,
+        internal: SocketAddr,
+        lifetime: Duration,
+    );
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use multiaddr::Multiaddr;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{is_circuit_ip, is_time_passed, P2pConfig, P2pTimeouts, PeerId};
+
+use super::{P2pNatPortMapping, P2pNatTransport};
+
+/// Number of peers from distinct subnets that should report the same
+/// observed address before it is considered to be our external address.
+pub const OBSERVED_ADDR_CONFIRMATIONS: usize = 4;
+
+/// Maximum number of peers whose observed addresses are tracked.
+const OBSERVED_ADDRS_MAX: usize = 64;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNatState {
+    /// Mapping of the libp2p TCP port.
+    pub port_mapping: P2pNatPortMappingState,
+    /// Mapping of the QUIC UDP port.
+    pub quic_port_mapping: P2pNatPortMappingState,
+    /// Our addresses as observed by the remote peers.
+    pub observed_addrs: BTreeMap<PeerId, P2pNatObservedAddr>,
+    /// Addresses confirmed either by the gateway or by enough peers,
+    /// they are advertised to other peers until they expire.
+    ///
+    /// The value is the time when the address was last confirmed.
+    pub external_addrs: BTreeMap<SocketAddr, Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub enum P2pNatPortMappingState {
+    #[default]
+    Idle,
+    Pending {
+        time: Timestamp,
+        internal: SocketAddr,
+        /// Mapping that is being renewed.
+        previous: Option<P2pNatPortMapping>,
+    },
+    Mapped {
+        time: Timestamp,
+        mapping: P2pNatPortMapping,
+    },
+    Error {
+        time: Timestamp,
+        error: String,
+        /// Mapping that failed to be renewed, it is no longer advertised.
+        previous: Option<P2pNatPortMapping>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNatObservedAddr {
+    pub time: Timestamp,
+    pub addr: SocketAddr,
+    /// Subnet of the peer that reported the address.
+    pub observer_subnet: IpAddr,
+}
+
+impl P2pNatState {
+    pub fn port_mapping_state(&self, transport: P2pNatTransport) -> &P2pNatPortMappingState {
+        match transport {
+            P2pNatTransport::Tcp => &self.port_mapping,
+            P2pNatTransport::Udp => &self.quic_port_mapping,
+        }
+    }
+
+    pub(super) fn port_mapping_state_mut(
+        &mut self,
+        transport: P2pNatTransport,
+    ) -> &mut P2pNatPortMappingState {
+        match transport {
+            P2pNatTransport::Tcp => &mut self.port_mapping,
+            P2pNatTransport::Udp => &mut self.quic_port_mapping,
+        }
+    }
+
+    /// Checks if the port mapping should be (re)created.
+    pub fn should_map_port(
+        &self,
+        transport: P2pNatTransport,
+        now: Timestamp,
+        config: &P2pConfig,
+    ) -> bool {
+        let port = match transport {
+            P2pNatTransport::Tcp => config.libp2p_port,
+            P2pNatTransport::Udp => config.quic_port,
+        };
+        if !config.nat_port_mapping || port.is_none() {
+            return false;
+        }
+        match self.port_mapping_state(transport) {
+            P2pNatPortMappingState::Idle => true,
+            P2pNatPortMappingState::Pending { .. } => false,
+            P2pNatPortMappingState::Mapped { time, mapping } => {
+                is_time_passed(now, *time, Some(mapping.lifetime / 2))
+            }
+            P2pNatPortMappingState::Error { time, .. } => {
+                is_time_passed(now, *time, config.timeouts.nat_port_mapping_retry)
+            }
+        }
+    }
+
+    pub fn mapping(&self, transport: P2pNatTransport) -> Option<&P2pNatPortMapping> {
+        match self.port_mapping_state(transport) {
+            P2pNatPortMappingState::Mapped { mapping, .. } => Some(mapping),
+            _ => None,
+        }
+    }
+
+    pub(super) fn previous_mapping(
+        &self,
+        transport: P2pNatTransport,
+    ) -> Option<&P2pNatPortMapping> {
+        match self.port_mapping_state(transport) {
+            P2pNatPortMappingState::Pending { previous, .. } => previous.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Number of distinct subnets with peers that recently observed us at the address.
+    ///
+    /// Peers from the same subnet are counted once, so a single party can't
+    /// make us advertise an arbitrary address.
+    pub fn observed_count(
+        &self,
+        addr: &SocketAddr,
+        now: Timestamp,
+        timeouts: &P2pTimeouts,
+    ) -> usize {
+        self.observed_addrs
+            .values()
+            .filter(|observed| &observed.addr == addr)
+            .filter(|observed| !is_time_passed(now, observed.time, timeouts.nat_external_addr_ttl))
+            .map(|observed| observed.observer_subnet)
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+
+    /// External addresses that weren't confirmed for too long.
+    pub fn expired_external_addrs<'a>(
+        &'a self,
+        now: Timestamp,
+        timeouts: &'a P2pTimeouts,
+    ) -> impl 'a + Iterator<Item = SocketAddr> {
+        self.external_addrs
+            .iter()
+            .filter(move |(_, time)| is_time_passed(now, **time, timeouts.nat_external_addr_ttl))
+            .map(|(addr, _)| *addr)
+    }
+
+    pub(super) fn add_observed_addr(
+        &mut self,
+        peer_id: PeerId,
+        observer: IpAddr,
+        addr: SocketAddr,
+        time: Timestamp,
+    ) {
+        if self.observed_addrs.len() >= OBSERVED_ADDRS_MAX
+            && !self.observed_addrs.contains_key(&peer_id)
+        {
+            let oldest = self
+                .observed_addrs
+                .iter()
+                .min_by_key(|(_, observed)| observed.time)
+                .map(|(peer_id, _)| *peer_id);
+            if let Some(oldest) = oldest {
+                self.observed_addrs.remove(&oldest);
+            }
+        }
+        self.observed_addrs.insert(
+            peer_id,
+            P2pNatObservedAddr {
+                time,
+                addr,
+                observer_subnet: subnet(&observer),
+            },
+        );
+    }
+
+    /// External addresses of the TCP transport, and the one of the QUIC
+    /// transport if its port is mapped by the gateway.
+    pub fn external_multiaddrs(&self) -> impl '_ + Iterator<Item = Multiaddr> {
+        let quic = self.mapping(P2pNatTransport::Udp).map(|mapping| {
+            Multiaddr::from(mapping.external.ip())
+                .with(multiaddr::Protocol::Udp(mapping.external.port()))
+                .with(multiaddr::Protocol::QuicV1)
+        });
+        self.external_addrs
+            .keys()
+            .map(|addr| Multiaddr::from(addr.ip()).with(multiaddr::Protocol::Tcp(addr.port())))
+            .chain(quic)
+    }
+}
+
+/// Network prefix of the address, /16 for IPv4 and /32 for IPv6.
+pub fn subnet(ip: &IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            IpAddr::V4(Ipv4Addr::new(a, b, 0, 0))
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, ..] = ip.segments();
+            IpAddr::V6(Ipv6Addr::new(a, b, 0, 0, 0, 0, 0, 0))
+        }
+    }
+}
+
+/// Checks if the address is reachable from the internet.
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation())
+        }
+        IpAddr::V6(ip) => {
+            // unique local (fc00::/7) and link local (fe80::/10) addresses
+            let segment = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || is_circuit_ip(&IpAddr::V6(*ip))
+                || (segment & 0xfe00) == 0xfc00
+                || (segment & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn secs(secs: u64) -> Timestamp {
+        Timestamp::new(Duration::from_secs(secs).as_nanos() as u64)
+    }
+
+    #[test]
+    fn observers_from_same_subnet_are_counted_once() {
+        let timeouts = P2pTimeouts::default();
+        let addr: SocketAddr = "1.2.3.4:8302".parse().unwrap();
+        let mut nat = P2pNatState::default();
+        for seed in 0..4 {
+            let observer = IpAddr::V4(Ipv4Addr::new(5, 6, 7, seed));
+            nat.add_observed_addr(PeerId::from_bytes([seed; 32]), observer, addr, secs(1));
+        }
+        assert_eq!(nat.observed_count(&addr, secs(1), &timeouts), 1);
+
+        for seed in 4..7 {
+            let observer = IpAddr::V4(Ipv4Addr::new(seed + 10, 6, 7, 8));
+            nat.add_observed_addr(PeerId::from_bytes([seed; 32]), observer, addr, secs(1));
+        }
+        assert_eq!(
+            nat.observed_count(&addr, secs(1), &timeouts),
+            OBSERVED_ADDR_CONFIRMATIONS
+        );
+    }
+
+    #[test]
+    fn stale_observations_and_external_addrs_expire() {
+        let timeouts = P2pTimeouts::default();
+        let ttl = timeouts.nat_external_addr_ttl.unwrap().as_secs();
+        let addr: SocketAddr = "1.2.3.4:8302".parse().unwrap();
+        let mut nat = P2pNatState::default();
+        let observer = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+        nat.add_observed_addr(PeerId::from_bytes([1; 32]), observer, addr, secs(1));
+        nat.external_addrs.insert(addr, secs(1));
+
+        assert_eq!(nat.observed_count(&addr, secs(ttl), &timeouts), 1);
+        assert_eq!(nat.expired_external_addrs(secs(ttl), &timeouts).count(), 0);
+
+        assert_eq!(nat.observed_count(&addr, secs(ttl + 2), &timeouts), 0);
+        assert_eq!(
+            nat.expired_external_addrs(secs(ttl + 2), &timeouts)
+                .collect::<Vec<_>>(),
+            vec![addr]
+        );
+    }
+
+    #[test]
+    fn mapped_quic_port_is_advertised() {
+        let tcp: SocketAddr = "1.2.3.4:8302".parse().unwrap();
+        let mut nat = P2pNatState::default();
+        nat.external_addrs.insert(tcp, secs(1));
+        nat.quic_port_mapping = P2pNatPortMappingState::Mapped {
+            time: secs(1),
+            mapping: P2pNatPortMapping {
+                protocol: crate::nat::P2pNatProtocol::Upnp,
+                transport: P2pNatTransport::Udp,
+                internal: "192.168.1.10:8303".parse().unwrap(),
+                external: "1.2.3.4:8303".parse().unwrap(),
+                lifetime: Duration::from_secs(3600),
+            },
+        };
+        assert_eq!(
+            nat.external_multiaddrs().collect::<Vec<_>>(),
+            [
+                "/ip4/1.2.3.4/tcp/8302".parse::<Multiaddr>().unwrap(),
+                "/ip4/1.2.3.4/udp/8303/quic-v1".parse().unwrap(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,2 @@
+mod p2p_network_bandwidth_state;
+pub use self::p2p_network_bandwidth_state::*;
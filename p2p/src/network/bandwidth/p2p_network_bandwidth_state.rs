@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{token, Limit, P2pLimits, P2pState, PeerId, StreamId};
+
+/// Protocol that the traffic is accounted to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum P2pNetworkBandwidthProtocol {
+    /// Protocol negotiation, before the stream protocol is known.
+    Select,
+    Status,
+    Bitswap,
+    Ping,
+    Identify,
+    Discovery,
+    Broadcast,
+    Rpc,
+    Relay,
+    Dcutr,
+}
+
+impl P2pNetworkBandwidthProtocol {
+    pub fn new(negotiated: Option<&token::Protocol>) -> Self {
+        match negotiated {
+            Some(token::Protocol::Stream(kind)) => match kind {
+                token::StreamKind::Status(_) => Self::Status,
+                token::StreamKind::Bitswap(_) => Self::Bitswap,
+                token::StreamKind::Ping(_) => Self::Ping,
+                token::StreamKind::Identify(_) => Self::Identify,
+                token::StreamKind::Discovery(_) => Self::Discovery,
+                token::StreamKind::Broadcast(_) => Self::Broadcast,
+                token::StreamKind::Rpc(_) => Self::Rpc,
+                token::StreamKind::Relay(_) => Self::Relay,
+                token::StreamKind::Dcutr(_) => Self::Dcutr,
+            },
+            _ => Self::Select,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct P2pNetworkBandwidthCounters {
+    pub received: u64,
+    pub sent: u64,
+}
+
+/// Bytes of stream data exchanged over libp2p connections, excluding the
+/// transport overhead (encryption and multiplexing).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNetworkBandwidthStats {
+    pub total: P2pNetworkBandwidthCounters,
+    pub protocols: BTreeMap<P2pNetworkBandwidthProtocol, P2pNetworkBandwidthCounters>,
+    /// Counters of the connected peers.
+    pub peers: BTreeMap<PeerId, BTreeMap<P2pNetworkBandwidthProtocol, P2pNetworkBandwidthCounters>>,
+}
+
+/// Token bucket refilled with `rate` tokens per second, up to `burst` tokens.
+///
+/// Consuming more tokens than available puts the bucket into debt, so a
+/// large message delays the following ones instead of being dropped.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNetworkTokenBucket {
+    pub tokens: i64,
+    /// Time of the last refill, `None` if the bucket is not used yet (full).
+    pub refilled: Option<Timestamp>,
+}
+
+impl P2pNetworkTokenBucket {
+    fn available(&self, now: Timestamp, rate: usize, burst: Limit<usize>) -> i64 {
+        let burst = match burst {
+            Limit::Some(burst) => i64::try_from(burst).unwrap_or(i64::MAX),
+            Limit::Unlimited => i64::MAX,
+        };
+        let Some(refilled) = self.refilled else {
+            return burst;
+        };
+        let elapsed = now.checked_sub(refilled).unwrap_or_default();
+        let refill = elapsed.as_nanos().saturating_mul(rate as u128) / 1_000_000_000;
+        let refill = i64::try_from(refill).unwrap_or(i64::MAX);
+        self.tokens.saturating_add(refill).min(burst)
+    }
+
+    pub fn consume(
+        &mut self,
+        amount: usize,
+        now: Timestamp,
+        rate: Limit<usize>,
+        burst: Limit<usize>,
+    ) {
+        let Limit::Some(rate) = rate else {
+            return;
+        };
+        let available = self.available(now, rate, burst);
+        if available != self.tokens || self.refilled.is_none() {
+            // fractions of a token are kept by not moving the refill time
+            self.tokens = available;
+            self.refilled = Some(now);
+        }
+        self.tokens = self
+            .tokens
+            .saturating_sub(i64::try_from(amount).unwrap_or(i64::MAX));
+    }
+
+    pub fn is_exhausted(&self, now: Timestamp, rate: Limit<usize>, burst: Limit<usize>) -> bool {
+        let Limit::Some(rate) = rate else {
+            return false;
+        };
+        self.available(now, rate, burst) <= 0
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNetworkBandwidthState {
+    pub stats: P2pNetworkBandwidthStats,
+    /// Budget of incoming RPC requests of each peer.
+    pub rpc_requests: BTreeMap<PeerId, P2pNetworkTokenBucket>,
+}
+
+impl P2pNetworkBandwidthState {
+    fn counters(
+        &mut self,
+        peer_id: PeerId,
+        protocol: P2pNetworkBandwidthProtocol,
+    ) -> [&mut P2pNetworkBandwidthCounters; 3] {
+        let stats = &mut self.stats;
+        [
+            &mut stats.total,
+            stats.protocols.entry(protocol).or_default(),
+            stats
+                .peers
+                .entry(peer_id)
+                .or_default()
+                .entry(protocol)
+                .or_default(),
+        ]
+    }
+
+    pub fn received(&mut self, peer_id: PeerId, protocol: P2pNetworkBandwidthProtocol, len: usize) {
+        for counters in self.counters(peer_id, protocol) {
+            counters.received += len as u64;
+        }
+    }
+
+    pub fn sent(&mut self, peer_id: PeerId, protocol: P2pNetworkBandwidthProtocol, len: usize) {
+        for counters in self.counters(peer_id, protocol) {
+            counters.sent += len as u64;
+        }
+    }
+
+    pub fn rpc_request_received(&mut self, peer_id: PeerId, now: Timestamp, limits: &P2pLimits) {
+        self.rpc_requests.entry(peer_id).or_default().consume(
+            1,
+            now,
+            limits.rpc_incoming_requests_rate(),
+            limits.rpc_incoming_requests_burst(),
+        );
+    }
+
+    /// The peer exceeded its budget of RPC requests and shouldn't be allowed
+    /// to send more for now.
+    pub fn is_rpc_throttled(&self, peer_id: &PeerId, now: Timestamp, limits: &P2pLimits) -> bool {
+        self.rpc_requests.get(peer_id).map_or(false, |bucket| {
+            bucket.is_exhausted(
+                now,
+                limits.rpc_incoming_requests_rate(),
+                limits.rpc_incoming_requests_burst(),
+            )
+        })
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.stats.peers.remove(peer_id);
+        self.rpc_requests.remove(peer_id);
+    }
+}
+
+impl P2pState {
+    /// The peer should be back-pressured on the stream.
+    pub fn is_stream_throttled(
+        &self,
+        addr: &SocketAddr,
+        peer_id: &PeerId,
+        stream_id: &StreamId,
+        now: Timestamp,
+    ) -> bool {
+        self.network.scheduler.stream_protocol(addr, stream_id) == P2pNetworkBandwidthProtocol::Rpc
+            && self
+                .network
+                .bandwidth
+                .is_rpc_throttled(peer_id, now, &self.config.limits)
+    }
+
+    /// Streams whose window was withheld while the peer was throttled, and
+    /// that can be resumed now.
+    pub fn yamux_streams_to_resume(&self, now: Timestamp) -> Vec<(SocketAddr, StreamId)> {
+        let mut streams = Vec::new();
+        for (addr, cn) in &self.network.scheduler.connections {
+            let (Some(peer_id), Some(crate::P2pNetworkConnectionMuxState::Yamux(yamux))) =
+                (cn.peer_id(), &cn.mux)
+            else {
+                continue;
+            };
+            for (stream_id, stream) in &yamux.streams {
+                if stream.needs_window_update()
+                    && self.network.scheduler.stream_protocol(addr, stream_id)
+                        == P2pNetworkBandwidthProtocol::Rpc
+                    && !self.is_stream_throttled(addr, peer_id, stream_id, now)
+                {
+                    streams.push((*addr, *stream_id));
+                }
+            }
+        }
+        streams
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Timestamp {
+        Timestamp::new(millis * 1_000_000)
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let rate = Limit::Some(10);
+        let burst = Limit::Some(20);
+
+        // initially the bucket is full
+        let mut bucket = P2pNetworkTokenBucket::default();
+        bucket.consume(20, millis(100_000), rate, burst);
+        assert!(bucket.is_exhausted(millis(100_000), rate, burst));
+        assert!(!bucket.is_exhausted(millis(100_500), rate, burst));
+
+        // the debt is paid off before new requests are allowed
+        bucket.consume(15, millis(100_500), rate, burst);
+        assert!(bucket.is_exhausted(millis(101_400), rate, burst));
+        assert!(!bucket.is_exhausted(millis(101_600), rate, burst));
+
+        let mut unlimited = P2pNetworkTokenBucket::default();
+        unlimited.consume(usize::MAX, millis(100_000), Limit::Unlimited, burst);
+        assert!(!unlimited.is_exhausted(millis(100_000), Limit::Unlimited, burst));
+    }
+}
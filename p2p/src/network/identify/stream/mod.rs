@@ -7,3 +7,4 @@ pub use self::p2p_network_identify_stream_actions::*;
 mod p2p_network_identify_stream_reducer;
 
 mod p2p_network_identify_stream_effects;
+pub(crate) use self::p2p_network_identify_stream_effects::get_addrs;
@@ -1,4 +1,4 @@
-use crate::{Data, P2pAction, P2pState, PeerId, StreamId};
+use crate::{token::IdentifyAlgorithm, Data, P2pAction, P2pState, PeerId, StreamId};
 use openmina_core::ActionEvent;
 use redux::EnablingCondition;
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,7 @@ pub enum P2pNetworkIdentifyStreamAction {
         peer_id: PeerId,
         stream_id: StreamId,
         incoming: bool,
+        protocol: IdentifyAlgorithm,
     },
     /// Handles incoming data from the stream.
     IncomingData {
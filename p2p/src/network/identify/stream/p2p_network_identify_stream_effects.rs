@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 
 use multiaddr::Multiaddr;
 use openmina_core::{error, log::system_time, warn};
@@ -9,19 +9,48 @@ use super::{
     P2pNetworkIdentifyStreamAction,
 };
 use crate::{
-    identify::P2pIdentifyAction, network::identify::stream::P2pNetworkIdentifyStreamError, token,
-    Data, P2pNetworkSchedulerAction, P2pNetworkService, P2pNetworkYamuxAction,
+    identify::P2pIdentifyAction, is_circuit_addr,
+    network::identify::stream::P2pNetworkIdentifyStreamError, token, Data,
+    P2pNetworkSchedulerAction, P2pNetworkService, P2pNetworkYamuxAction,
 };
 
-fn get_addrs<I, S>(addr: &SocketAddr, net_svc: &mut S) -> I
+pub(crate) fn get_addrs<I, S>(addr: &SocketAddr, net_svc: &mut S) -> I
 where
     S: P2pNetworkService,
     I: FromIterator<Multiaddr>,
 {
     let port = addr.port();
+    local_ips(addr, net_svc)
+        .into_iter()
+        .map(|addr| Multiaddr::from(addr).with(multiaddr::Protocol::Tcp(port)))
+        .collect()
+}
+
+/// Addresses of the QUIC listener, `/ip4/<ip>/udp/<port>/quic-v1`.
+fn get_quic_addrs<I, S>(addr: &SocketAddr, net_svc: &mut S) -> I
+where
+    S: P2pNetworkService,
+    I: FromIterator<Multiaddr>,
+{
+    local_ips(addr, net_svc)
+        .into_iter()
+        .map(|ip| quic_maddr(&SocketAddr::new(ip, addr.port())))
+        .collect()
+}
+
+fn quic_maddr(addr: &SocketAddr) -> Multiaddr {
+    Multiaddr::from(addr.ip())
+        .with(multiaddr::Protocol::Udp(addr.port()))
+        .with(multiaddr::Protocol::QuicV1)
+}
+
+fn local_ips<S>(addr: &SocketAddr, net_svc: &mut S) -> Vec<IpAddr>
+where
+    S: P2pNetworkService,
+{
     let ip = addr.ip();
     let is_ipv6 = ip.is_ipv6();
-    let ip_addrs = if ip.is_unspecified() {
+    if ip.is_unspecified() {
         match net_svc.detect_local_ip() {
             Err(err) => {
                 error!(system_time(); "error getting node addresses: {err}");
@@ -31,11 +60,7 @@ where
         }
     } else {
         vec![ip]
-    };
-    ip_addrs
-        .into_iter()
-        .map(|addr| Multiaddr::from(addr).with(multiaddr::Protocol::Tcp(port)))
-        .collect()
+    }
 }
 
 impl P2pNetworkIdentifyStreamAction {
@@ -64,10 +89,19 @@ impl P2pNetworkIdentifyStreamAction {
             A::New {
                 addr,
                 peer_id,
-                incoming: true,
                 stream_id,
+                ..
             } => {
-                if let S::SendIdentify = state {
+                if let S::RecvIdentify = state {
+                    Ok(())
+                } else if let S::SendIdentify = state {
+                    let is_quic = store
+                        .state()
+                        .network
+                        .scheduler
+                        .connections
+                        .get(&addr)
+                        .map_or(false, |conn| conn.quic);
                     let mut listen_addrs = Vec::new();
                     for addr in store
                         .state()
@@ -80,38 +114,53 @@ impl P2pNetworkIdentifyStreamAction {
                     {
                         listen_addrs.extend(get_addrs::<Vec<_>, _>(&addr, store.service()))
                     }
+                    if let Some(addr) = store.state().network.scheduler.quic_state.listener() {
+                        listen_addrs.extend(get_quic_addrs::<Vec<_>, _>(&addr, store.service()));
+                    }
+
+                    listen_addrs.extend(store.state().nat.external_multiaddrs());
 
                     let public_key = Some(store.state().config.identity_pub_key.clone());
 
                     let mut protocols = vec![
                         // token::StreamKind::Broadcast(token::BroadcastAlgorithm::Floodsub1_0_0),
                         token::StreamKind::Identify(token::IdentifyAlgorithm::Identify1_0_0),
-                        // token::StreamKind::Identify(
-                        //     token::IdentifyAlgorithm::IdentifyPush1_0_0,
-                        // ),
+                        token::StreamKind::Identify(token::IdentifyAlgorithm::IdentifyPush1_0_0),
                         // token::StreamKind::Broadcast(token::BroadcastAlgorithm::Meshsub1_0_0),
                         token::StreamKind::Broadcast(token::BroadcastAlgorithm::Meshsub1_1_0),
-                        // token::StreamKind::Ping(token::PingAlgorithm::Ping1_0_0),
+                        token::StreamKind::Ping(token::PingAlgorithm::Ping1_0_0),
                         // token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap),
                         // token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap1_0_0),
                         // token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap1_1_0),
                         // token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap1_2_0),
-                        // token::StreamKind::Status(token::StatusAlgorithm::MinaNodeStatus),
+                        token::StreamKind::Status(token::StatusAlgorithm::MinaNodeStatus),
                         token::StreamKind::Rpc(token::RpcAlgorithm::Rpc0_0_1),
+                        token::StreamKind::Relay(token::RelayAlgorithm::Stop0_2_0),
+                        token::StreamKind::Dcutr(token::DcutrAlgorithm::Dcutr),
                     ];
                     if store.state().network.scheduler.discovery_state.is_some() {
                         protocols.push(token::StreamKind::Discovery(
                             token::DiscoveryAlgorithm::Kademlia1_0_0,
                         ));
                     }
+                    if store.state().config.relay_hop {
+                        protocols.push(token::StreamKind::Relay(token::RelayAlgorithm::Hop0_2_0));
+                    }
                     let identify_msg = P2pNetworkIdentify {
                         protocol_version: Some("ipfs/0.1.0".to_string()),
                         // TODO: include build info from GlobalConfig (?)
                         agent_version: Some("openmina".to_owned()),
                         public_key,
                         listen_addrs,
-                        // TODO: other peers seem to report inaccurate information, should we implement this?
-                        observed_addr: None,
+                        // the relayed connection address is not a real one
+                        observed_addr: (!is_circuit_addr(&addr)).then(|| {
+                            if is_quic {
+                                quic_maddr(&addr)
+                            } else {
+                                Multiaddr::from(addr.ip())
+                                    .with(multiaddr::Protocol::Tcp(addr.port()))
+                            }
+                        }),
                         protocols,
                     };
 
@@ -145,15 +194,6 @@ impl P2pNetworkIdentifyStreamAction {
                     unreachable!()
                 }
             }
-            A::New {
-                incoming: false, ..
-            } => {
-                if let S::RecvIdentify = state {
-                    Ok(())
-                } else {
-                    unreachable!()
-                }
-            }
             A::IncomingData {
                 addr,
                 peer_id,
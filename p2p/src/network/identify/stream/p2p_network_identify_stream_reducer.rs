@@ -22,8 +22,11 @@ impl P2pNetworkIdentifyStreamState {
         //println!("=== IDENTIFY action: {action:?}");
         match &self {
             S::Default => {
-                if let A::New { incoming, .. } = action {
-                    let kind = P2pNetworkIdentifyStreamKind::from(*incoming);
+                if let A::New {
+                    incoming, protocol, ..
+                } = action
+                {
+                    let kind = P2pNetworkIdentifyStreamKind::new(*incoming, *protocol);
 
                     *self = match kind {
                         // For incoming streams we prepare to send the Identify message
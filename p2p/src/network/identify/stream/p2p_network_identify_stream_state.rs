@@ -1,5 +1,6 @@
 use crate::{
     network::identify::{P2pNetworkIdentify, P2pNetworkIdentifyFromMessageError},
+    token::IdentifyAlgorithm,
     P2pNetworkStreamProtobufError,
 };
 use serde::{Deserialize, Serialize};
@@ -20,6 +21,17 @@ impl From<bool> for P2pNetworkIdentifyStreamKind {
     }
 }
 
+impl P2pNetworkIdentifyStreamKind {
+    /// Identify-push streams are opened by the peer sending its identity,
+    /// so they are handled as the opposite identify streams.
+    pub fn new(incoming: bool, protocol: IdentifyAlgorithm) -> Self {
+        match protocol {
+            IdentifyAlgorithm::Identify1_0_0 => incoming.into(),
+            IdentifyAlgorithm::IdentifyPush1_0_0 => (!incoming).into(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum P2pNetworkIdentifyStreamState {
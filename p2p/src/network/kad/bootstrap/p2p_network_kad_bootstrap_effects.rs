@@ -1,6 +1,9 @@
 use redux::ActionMeta;
 
-use crate::{request::P2pNetworkKadRequestAction, P2pNetworkKademliaAction, P2pStore};
+use crate::{
+    request::P2pNetworkKadRequestAction, P2pNetworkKademliaAction, P2pNetworkKademliaRpcRequest,
+    P2pStore,
+};
 
 use super::P2pNetworkKadBootstrapAction;
 
@@ -27,7 +30,7 @@ impl P2pNetworkKadBootstrapAction {
                     store.dispatch(P2pNetworkKademliaAction::BootstrapFinished {});
                 } else {
                     // start FIND_NODE request for each address if there is no such request already.
-                    let key = bootstrap_state.key;
+                    let request = P2pNetworkKademliaRpcRequest::find_node(bootstrap_state.key);
                     bootstrap_state
                         .requests
                         .iter()
@@ -38,7 +41,11 @@ impl P2pNetworkKadBootstrapAction {
                         .collect::<Vec<_>>()
                         .into_iter()
                         .for_each(|(peer_id, addr)| {
-                            store.dispatch(P2pNetworkKadRequestAction::New { addr, peer_id, key });
+                            store.dispatch(P2pNetworkKadRequestAction::New {
+                                addr,
+                                peer_id,
+                                request: request.clone(),
+                            });
                         });
                 }
                 Ok(())
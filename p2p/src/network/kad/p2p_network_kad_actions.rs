@@ -7,7 +7,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     kad::stream::P2pNetworkKademliaStreamAction, request::P2pNetworkKadRequestAction, P2pAction,
-    P2pNetworkAction, P2pNetworkKadEntry, P2pState, PeerId, StreamId,
+    P2pNetworkAction, P2pNetworkKadEntry, P2pNetworkKadRecord, P2pNetworkKadRecordKey, P2pState,
+    PeerId, StreamId,
 };
 
 use super::bootstrap::P2pNetworkKadBootstrapAction;
@@ -46,7 +47,8 @@ impl From<P2pNetworkKadAction> for P2pAction {
     stream_id,
     display(key),
     debug(closest_peers),
-    debug(addrs)
+    debug(addrs),
+    debug(providers)
 ))]
 pub enum P2pNetworkKademliaAction {
     /// Answer `FIND_NODE` request.
@@ -80,6 +82,82 @@ pub enum P2pNetworkKademliaAction {
         peer_id: PeerId,
         addrs: Vec<Multiaddr>,
     },
+
+    /// Answer `GET_PROVIDERS` request with the known providers for the key
+    /// and the peers closest to it.
+    AnswerGetProvidersRequest {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        key: P2pNetworkKadRecordKey,
+    },
+    /// Answer `GET_VALUE` request with the stored value, if any, and the
+    /// peers closest to the key.
+    AnswerGetValueRequest {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        key: P2pNetworkKadRecordKey,
+    },
+    /// Store the record from `PUT_VALUE` request and confirm it.
+    AnswerPutValueRequest {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        record: P2pNetworkKadRecord,
+    },
+    /// Store the providers from `ADD_PROVIDER` request. Only the sending
+    /// peer can announce itself as a provider.
+    AddProviders {
+        peer_id: PeerId,
+        key: P2pNetworkKadRecordKey,
+        providers: Vec<P2pNetworkKadEntry>,
+    },
+    /// Update result of scheduled outgoing `GET_PROVIDERS`.
+    UpdateGetProvidersRequest {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        providers: Vec<P2pNetworkKadEntry>,
+        closest_peers: Vec<P2pNetworkKadEntry>,
+    },
+    /// Update result of scheduled outgoing `GET_VALUE`.
+    UpdateGetValueRequest {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        record: Option<P2pNetworkKadRecord>,
+        closest_peers: Vec<P2pNetworkKadEntry>,
+    },
+
+    /// Announce the current node as a provider for the key. The provider
+    /// record is republished until [`P2pNetworkKademliaAction::StopProviding`].
+    #[action_event(level = info)]
+    StartProviding { key: P2pNetworkKadRecordKey },
+    /// Stop announcing the current node as a provider for the key.
+    #[action_event(level = info)]
+    StopProviding { key: P2pNetworkKadRecordKey },
+    /// Store the value on the peers closest to its key. The record is
+    /// republished periodically.
+    #[action_event(level = info)]
+    PutValue { record: P2pNetworkKadRecord },
+    /// Ask the peers closest to the key for its providers. Found providers
+    /// are stored in the record store.
+    #[action_event(level = info)]
+    GetProviders { key: P2pNetworkKadRecordKey },
+    /// Ask the peers closest to the key for its value. Found value is
+    /// stored in the record store.
+    #[action_event(level = info)]
+    GetValue { key: P2pNetworkKadRecordKey },
+    /// Publish local records that are not published yet or need to be
+    /// republished.
+    RepublishRecords,
+    /// Send the provider record for the key to the peers closest to it.
+    PublishProvider { key: P2pNetworkKadRecordKey },
+    /// Send the value record for the key to the peers closest to it.
+    PublishValue { key: P2pNetworkKadRecordKey },
+    /// Remove expired records.
+    PruneRecords,
 }
 
 impl EnablingCondition<P2pState> for P2pNetworkKademliaAction {
@@ -114,7 +192,65 @@ impl EnablingCondition<P2pState> for P2pNetworkKademliaAction {
                     super::P2pNetworkKadStatus::Bootstrapping(_)
                 )
             }
-            P2pNetworkKademliaAction::UpdateRoutingTable { .. } => true,
+            // our own entry is managed locally, peers can't change it
+            P2pNetworkKademliaAction::UpdateRoutingTable { peer_id, .. } => {
+                peer_id != &state.my_id()
+            }
+            P2pNetworkKademliaAction::AnswerGetProvidersRequest {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkKademliaAction::AnswerGetValueRequest {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkKademliaAction::AnswerPutValueRequest {
+                peer_id, stream_id, ..
+            } => discovery_state
+                .find_kad_stream_state(peer_id, stream_id)
+                .is_some(),
+            P2pNetworkKademliaAction::AddProviders { .. } => true,
+            P2pNetworkKademliaAction::UpdateGetProvidersRequest {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkKademliaAction::UpdateGetValueRequest {
+                peer_id, stream_id, ..
+            } => {
+                discovery_state
+                    .find_kad_stream_state(peer_id, stream_id)
+                    .is_some()
+                    && discovery_state.request(peer_id).is_some()
+            }
+            P2pNetworkKademliaAction::StartProviding { key } => {
+                !discovery_state.records.is_providing(key)
+            }
+            P2pNetworkKademliaAction::StopProviding { key } => {
+                discovery_state.records.is_providing(key)
+            }
+            P2pNetworkKademliaAction::PutValue { .. }
+            | P2pNetworkKademliaAction::GetProviders { .. }
+            | P2pNetworkKademliaAction::GetValue { .. } => true,
+            P2pNetworkKademliaAction::RepublishRecords => {
+                let republish = state.config.timeouts.kademlia_republish;
+                discovery_state.is_bootstrapped()
+                    && (discovery_state
+                        .records
+                        .providers_to_publish(time, republish)
+                        .next()
+                        .is_some()
+                        || discovery_state
+                            .records
+                            .values_to_publish(time, republish)
+                            .next()
+                            .is_some())
+            }
+            P2pNetworkKademliaAction::PublishProvider { key } => {
+                discovery_state.records.is_providing(key)
+            }
+            P2pNetworkKademliaAction::PublishValue { key } => {
+                discovery_state.records.local_values.contains_key(key)
+            }
+            P2pNetworkKademliaAction::PruneRecords => discovery_state
+                .records
+                .has_expired(time, state.config.timeouts.kademlia_record_ttl),
         }
     }
 }
@@ -8,7 +8,14 @@ use super::{
 
 use super::P2pNetworkKadAction;
 
-use crate::P2pNetworkKademliaRpcReply;
+use crate::{
+    connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingInitOpts},
+    P2pNetworkKadEntry, P2pNetworkKadKey, P2pNetworkKadRecord, P2pNetworkKademliaRpcReply,
+    P2pNetworkKademliaRpcRequest, P2pState,
+};
+
+/// Maximal number of providers dialed after a `GET_PROVIDERS` reply.
+const MAX_PROVIDERS_TO_DIAL: usize = 3;
 
 impl P2pNetworkKadAction {
     pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store) -> Result<(), String>
@@ -96,6 +103,266 @@ impl P2pNetworkKademliaAction {
             }
             (BootstrapFinished {}, _) => Ok(()),
             (UpdateRoutingTable { .. }, _) => Ok(()),
+            (
+                AnswerGetProvidersRequest {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    key,
+                },
+                _,
+            ) => {
+                let ttl = store.state().config.timeouts.kademlia_record_ttl;
+                let mut provider_peers = state
+                    .records
+                    .providers(&key, meta.time(), ttl)
+                    .collect::<Vec<_>>();
+                if state.records.is_providing(&key) {
+                    provider_peers.extend(this_entry(store.state()));
+                }
+                let kad_key = P2pNetworkKadKey::from(&key);
+                let closer_peers = state
+                    .routing_table
+                    .find_node(&kad_key)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let message = P2pNetworkKademliaRpcReply::GetProviders {
+                    provider_peers,
+                    closer_peers,
+                };
+                store.dispatch(P2pNetworkKademliaStreamAction::SendResponse {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    data: message,
+                });
+                Ok(())
+            }
+            (
+                AnswerGetValueRequest {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    key,
+                },
+                _,
+            ) => {
+                let ttl = store.state().config.timeouts.kademlia_record_ttl;
+                let record = state.records.value(&key, meta.time(), ttl);
+                let kad_key = P2pNetworkKadKey::from(&key);
+                let closer_peers = state
+                    .routing_table
+                    .find_node(&kad_key)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let message = P2pNetworkKademliaRpcReply::GetValue {
+                    record,
+                    closer_peers,
+                };
+                store.dispatch(P2pNetworkKademliaStreamAction::SendResponse {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    data: message,
+                });
+                Ok(())
+            }
+            (
+                AnswerPutValueRequest {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    record,
+                },
+                _,
+            ) => {
+                let stored = state
+                    .records
+                    .values
+                    .get(&record.key)
+                    .map_or(false, |stored| {
+                        stored.publisher == peer_id && stored.data == record.value
+                    });
+                if stored {
+                    store.dispatch(P2pNetworkKademliaStreamAction::SendResponse {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        data: P2pNetworkKademliaRpcReply::PutValue { record },
+                    });
+                } else {
+                    // rejected records are not confirmed
+                    store.dispatch(P2pNetworkKademliaStreamAction::Close {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    });
+                }
+                Ok(())
+            }
+            (AddProviders { .. }, _) => Ok(()),
+            (
+                UpdateGetProvidersRequest {
+                    peer_id,
+                    stream_id,
+                    mut providers,
+                    closest_peers,
+                    ..
+                },
+                _,
+            ) => {
+                let my_id = store.state().my_id();
+                let peers = &store.state().peers;
+                // providers are needed right away, so they are dialed instead
+                // of waiting for them to be picked among the discovered peers
+                let dial_opts = providers
+                    .iter()
+                    .filter(|provider| provider.peer_id != my_id)
+                    .filter(|provider| {
+                        peers
+                            .get(&provider.peer_id)
+                            .map_or(true, |peer| !peer.status.is_connected_or_connecting())
+                    })
+                    .filter_map(|provider| {
+                        let addr = state.dialable_addr(provider)?;
+                        Some(P2pConnectionOutgoingInitOpts::LibP2P(
+                            (provider.peer_id, addr).into(),
+                        ))
+                    })
+                    .take(MAX_PROVIDERS_TO_DIAL)
+                    .collect::<Vec<_>>();
+
+                // providers are also discovered as peers
+                providers.extend(closest_peers);
+                store.dispatch(P2pNetworkKadRequestAction::ReplyReceived {
+                    peer_id,
+                    stream_id,
+                    data: providers,
+                });
+                for opts in dial_opts {
+                    store.dispatch(P2pConnectionOutgoingAction::Init { opts, rpc_id: None });
+                }
+                Ok(())
+            }
+            (
+                UpdateGetValueRequest {
+                    peer_id,
+                    stream_id,
+                    closest_peers,
+                    ..
+                },
+                _,
+            ) => {
+                store.dispatch(P2pNetworkKadRequestAction::ReplyReceived {
+                    peer_id,
+                    stream_id,
+                    data: closest_peers,
+                });
+                Ok(())
+            }
+            (StartProviding { .. } | PutValue { .. }, _) => {
+                store.dispatch(RepublishRecords);
+                Ok(())
+            }
+            (StopProviding { .. }, _) => Ok(()),
+            (GetProviders { key }, _) => {
+                let peers = state.closest_peer_addrs(&P2pNetworkKadKey::from(&key));
+                for (peer_id, addr) in peers {
+                    store.dispatch(P2pNetworkKadRequestAction::New {
+                        peer_id,
+                        addr,
+                        request: P2pNetworkKademliaRpcRequest::GetProviders { key: key.clone() },
+                    });
+                }
+                Ok(())
+            }
+            (GetValue { key }, _) => {
+                let peers = state.closest_peer_addrs(&P2pNetworkKadKey::from(&key));
+                for (peer_id, addr) in peers {
+                    store.dispatch(P2pNetworkKadRequestAction::New {
+                        peer_id,
+                        addr,
+                        request: P2pNetworkKademliaRpcRequest::GetValue { key: key.clone() },
+                    });
+                }
+                Ok(())
+            }
+            (RepublishRecords, _) => {
+                let republish = store.state().config.timeouts.kademlia_republish;
+                let providers = state
+                    .records
+                    .providers_to_publish(meta.time(), republish)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let values = state
+                    .records
+                    .values_to_publish(meta.time(), republish)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for key in providers {
+                    store.dispatch(PublishProvider { key });
+                }
+                for key in values {
+                    store.dispatch(PublishValue { key });
+                }
+                Ok(())
+            }
+            (PublishProvider { key }, _) => {
+                let Some(this_entry) = this_entry(store.state()) else {
+                    return Err(String::from("no routing table entry for this node"));
+                };
+                let peers = state.closest_peer_addrs(&P2pNetworkKadKey::from(&key));
+                let request = P2pNetworkKademliaRpcRequest::AddProvider {
+                    key,
+                    providers: vec![this_entry],
+                };
+                for (peer_id, addr) in peers {
+                    store.dispatch(P2pNetworkKadRequestAction::New {
+                        peer_id,
+                        addr,
+                        request: request.clone(),
+                    });
+                }
+                Ok(())
+            }
+            (PublishValue { key }, _) => {
+                let Some(value) = state.records.local_values.get(&key) else {
+                    return Err(format!("no local value for {key}"));
+                };
+                let peers = state.closest_peer_addrs(&P2pNetworkKadKey::from(&key));
+                let request = P2pNetworkKademliaRpcRequest::PutValue {
+                    record: P2pNetworkKadRecord {
+                        key,
+                        value: value.data.clone(),
+                    },
+                };
+                for (peer_id, addr) in peers {
+                    store.dispatch(P2pNetworkKadRequestAction::New {
+                        peer_id,
+                        addr,
+                        request: request.clone(),
+                    });
+                }
+                Ok(())
+            }
+            (PruneRecords, _) => Ok(()),
+        }
+    }
+}
+
+/// Routing table entry of this node, extended with the external addresses
+/// discovered behind NAT.
+fn this_entry(state: &P2pState) -> Option<P2pNetworkKadEntry> {
+    let mut entry = state
+        .network
+        .scheduler
+        .discovery_state()?
+        .this_entry()?
+        .clone();
+    for addr in state.nat.external_multiaddrs() {
+        if !entry.addrs.contains(&addr) {
+            entry.addrs.push(addr);
         }
     }
+    Some(entry)
 }
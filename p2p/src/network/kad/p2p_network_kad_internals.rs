@@ -1,17 +1,22 @@
 use std::{
+    collections::BTreeMap,
     fmt::Debug,
     ops::{Add, Shr, Sub},
+    time::Duration,
 };
 
 use crypto_bigint::{ArrayEncoding, Encoding, U256};
 use derive_more::From;
 //use libp2p_identity::PeerId;
+use mina_p2p_messages::v2::LedgerHash;
 use multiaddr::Multiaddr;
+use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 use crate::{
-    ConnectionType, P2pNetworkKademliaMultiaddrError, P2pNetworkKademliaPeerIdError, PeerId,
+    is_time_passed, ConnectionType, P2pNetworkKademliaMultiaddrError,
+    P2pNetworkKademliaPeerIdError, PeerId,
 };
 
 mod u256_serde {
@@ -564,6 +569,330 @@ impl<'a, const K: usize> IntoIterator for &'a P2pNetworkKadBucket<K> {
     }
 }
 
+/// Maximal number of value records stored for remote peers, also the maximal
+/// number of keys with provider records.
+pub const MAX_RECORDS: usize = 1024;
+/// Maximal number of value records stored for a single remote peer.
+pub const MAX_RECORDS_PER_PEER: usize = 64;
+/// Maximal number of providers stored for a single key.
+pub const MAX_PROVIDERS_PER_KEY: usize = 20;
+/// Maximal length of a record key.
+pub const MAX_RECORD_KEY_LEN: usize = 256;
+/// Maximal length of a value stored for a remote peer.
+pub const MAX_RECORD_VALUE_LEN: usize = 64 * 1024;
+
+const LEDGER_KEY_PREFIX: &str = "/mina/ledger/";
+
+/// Key of a Kademlia record, either a value record or a provider record. Its
+/// Kademlia key used to find the closest peers is sha256 of the bytes.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, From)]
+pub struct P2pNetworkKadRecordKey(Vec<u8>);
+
+impl P2pNetworkKadRecordKey {
+    /// Key for the providers of the ledger with the specified hash.
+    pub fn ledger(hash: &LedgerHash) -> Self {
+        P2pNetworkKadRecordKey(format!("{LEDGER_KEY_PREFIX}{hash}").into_bytes())
+    }
+
+    pub fn is_ledger(&self) -> bool {
+        self.0.starts_with(LEDGER_KEY_PREFIX.as_bytes())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.0.is_empty() && self.0.len() <= MAX_RECORD_KEY_LEN
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<&P2pNetworkKadRecordKey> for P2pNetworkKadKey {
+    fn from(value: &P2pNetworkKadRecordKey) -> Self {
+        P2pNetworkKadKey(U256::from_be_byte_array(Sha256::digest(&value.0)))
+    }
+}
+
+impl std::fmt::Display for P2pNetworkKadRecordKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match std::str::from_utf8(&self.0) {
+            Ok(s) => f.write_str(s),
+            Err(_) => f.write_str(&hex::encode(&self.0)),
+        }
+    }
+}
+
+impl Debug for P2pNetworkKadRecordKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("P2pNetworkKadRecordKey")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+/// Kademlia value record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct P2pNetworkKadRecord {
+    pub key: P2pNetworkKadRecordKey,
+    pub value: Vec<u8>,
+}
+
+impl P2pNetworkKadRecord {
+    /// Checks if the record received from a remote peer can be stored.
+    ///
+    /// Ledger keys are reserved for provider records.
+    pub fn is_valid(&self) -> bool {
+        self.key.is_valid() && !self.key.is_ledger() && self.value.len() <= MAX_RECORD_VALUE_LEN
+    }
+}
+
+/// Record received from a remote peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct P2pNetworkKadStoredRecord<T> {
+    pub data: T,
+    /// Peer the record was received from.
+    pub publisher: PeerId,
+    /// Time when the record was received, used to expire it.
+    pub time: Timestamp,
+}
+
+/// Record published by the current node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct P2pNetworkKadLocalRecord<T> {
+    pub data: T,
+    /// Time of the latest publication, `None` if it is not published yet.
+    pub published: Option<Timestamp>,
+}
+
+impl<T> P2pNetworkKadLocalRecord<T> {
+    fn new(data: T) -> Self {
+        P2pNetworkKadLocalRecord {
+            data,
+            published: None,
+        }
+    }
+
+    fn should_publish(&self, now: Timestamp, republish: Option<Duration>) -> bool {
+        self.published
+            .map_or(true, |time| is_time_passed(now, time, republish))
+    }
+}
+
+/// Storage for Kademlia value and provider records.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct P2pNetworkKadRecordStore {
+    /// Values stored for other peers.
+    pub values: BTreeMap<P2pNetworkKadRecordKey, P2pNetworkKadStoredRecord<Vec<u8>>>,
+    /// Providers announced by other peers, with their addresses.
+    pub providers: BTreeMap<
+        P2pNetworkKadRecordKey,
+        BTreeMap<PeerId, P2pNetworkKadStoredRecord<Vec<Multiaddr>>>,
+    >,
+    /// Values published by the current node.
+    pub local_values: BTreeMap<P2pNetworkKadRecordKey, P2pNetworkKadLocalRecord<Vec<u8>>>,
+    /// Keys the current node is a provider for.
+    pub local_providers: BTreeMap<P2pNetworkKadRecordKey, P2pNetworkKadLocalRecord<()>>,
+}
+
+impl P2pNetworkKadRecordStore {
+    /// Stores the record received from the `publisher`. Returns `false` if
+    /// the record is invalid, the publisher has too many records stored, or
+    /// there is no space for a new record.
+    ///
+    /// When the store is full, the record with the key farthest from
+    /// `this_key` is evicted, if the new one is closer.
+    pub fn put_value(
+        &mut self,
+        record: P2pNetworkKadRecord,
+        publisher: PeerId,
+        this_key: &P2pNetworkKadKey,
+        now: Timestamp,
+    ) -> bool {
+        if !record.is_valid() {
+            return false;
+        }
+        let existing = self.values.get(&record.key);
+        if existing.map_or(true, |existing| existing.publisher != publisher) {
+            let published = self
+                .values
+                .values()
+                .filter(|stored| stored.publisher == publisher)
+                .count();
+            if published >= MAX_RECORDS_PER_PEER {
+                return false;
+            }
+        }
+        if existing.is_none() && self.values.len() >= MAX_RECORDS {
+            let dist = this_key - P2pNetworkKadKey::from(&record.key);
+            let farthest = self
+                .values
+                .keys()
+                .map(|key| (this_key - P2pNetworkKadKey::from(key), key))
+                .max_by(|(dist1, _), (dist2, _)| dist1.cmp(dist2));
+            match farthest {
+                Some((farthest_dist, key)) if farthest_dist > dist => {
+                    let key = key.clone();
+                    self.values.remove(&key);
+                }
+                _ => return false,
+            }
+        }
+        let P2pNetworkKadRecord { key, value } = record;
+        self.values.insert(
+            key,
+            P2pNetworkKadStoredRecord {
+                data: value,
+                publisher,
+                time: now,
+            },
+        );
+        true
+    }
+
+    /// Returns the value for the `key`, unless it is expired.
+    pub fn value(
+        &self,
+        key: &P2pNetworkKadRecordKey,
+        now: Timestamp,
+        ttl: Option<Duration>,
+    ) -> Option<P2pNetworkKadRecord> {
+        self.local_values
+            .get(key)
+            .map(|record| &record.data)
+            .or_else(|| {
+                self.values
+                    .get(key)
+                    .filter(|record| !is_time_passed(now, record.time, ttl))
+                    .map(|record| &record.data)
+            })
+            .map(|value| P2pNetworkKadRecord {
+                key: key.clone(),
+                value: value.clone(),
+            })
+    }
+
+    /// Stores the `provider` for the `key`, received from the `publisher`.
+    /// Returns `false` if the key is invalid, or there are too many keys or
+    /// providers for this key already.
+    pub fn add_provider(
+        &mut self,
+        key: P2pNetworkKadRecordKey,
+        provider: P2pNetworkKadEntry,
+        publisher: PeerId,
+        now: Timestamp,
+    ) -> bool {
+        if !key.is_valid()
+            || (self.providers.len() >= MAX_RECORDS && !self.providers.contains_key(&key))
+        {
+            return false;
+        }
+        let providers = self.providers.entry(key).or_default();
+        if providers.len() >= MAX_PROVIDERS_PER_KEY && !providers.contains_key(&provider.peer_id) {
+            return false;
+        }
+        providers.insert(
+            provider.peer_id,
+            P2pNetworkKadStoredRecord {
+                data: provider.addrs,
+                publisher,
+                time: now,
+            },
+        );
+        true
+    }
+
+    /// Returns not expired providers for the `key`, excluding the current node.
+    pub fn providers<'a>(
+        &'a self,
+        key: &P2pNetworkKadRecordKey,
+        now: Timestamp,
+        ttl: Option<Duration>,
+    ) -> impl 'a + Iterator<Item = P2pNetworkKadEntry> {
+        self.providers
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter(move |(_, record)| !is_time_passed(now, record.time, ttl))
+            .map(|(peer_id, record)| P2pNetworkKadEntry::new(*peer_id, record.data.clone()))
+    }
+
+    pub fn is_providing(&self, key: &P2pNetworkKadRecordKey) -> bool {
+        self.local_providers.contains_key(key)
+    }
+
+    pub fn start_providing(&mut self, key: P2pNetworkKadRecordKey) {
+        self.local_providers
+            .insert(key, P2pNetworkKadLocalRecord::new(()));
+    }
+
+    pub fn stop_providing(&mut self, key: &P2pNetworkKadRecordKey) {
+        self.local_providers.remove(key);
+    }
+
+    pub fn put_local_value(&mut self, record: P2pNetworkKadRecord) {
+        self.local_values
+            .insert(record.key, P2pNetworkKadLocalRecord::new(record.value));
+    }
+
+    /// Marks the local provider record for the `key` as published.
+    pub fn provider_published(&mut self, key: &P2pNetworkKadRecordKey, now: Timestamp) {
+        if let Some(record) = self.local_providers.get_mut(key) {
+            record.published = Some(now);
+        }
+    }
+
+    /// Marks the local value record for the `key` as published.
+    pub fn value_published(&mut self, key: &P2pNetworkKadRecordKey, now: Timestamp) {
+        if let Some(record) = self.local_values.get_mut(key) {
+            record.published = Some(now);
+        }
+    }
+
+    /// Keys of the local provider records that should be (re)published.
+    pub fn providers_to_publish(
+        &self,
+        now: Timestamp,
+        republish: Option<Duration>,
+    ) -> impl '_ + Iterator<Item = &'_ P2pNetworkKadRecordKey> {
+        self.local_providers
+            .iter()
+            .filter(move |(_, record)| record.should_publish(now, republish))
+            .map(|(key, _)| key)
+    }
+
+    /// Keys of the local value records that should be (re)published.
+    pub fn values_to_publish(
+        &self,
+        now: Timestamp,
+        republish: Option<Duration>,
+    ) -> impl '_ + Iterator<Item = &'_ P2pNetworkKadRecordKey> {
+        self.local_values
+            .iter()
+            .filter(move |(_, record)| record.should_publish(now, republish))
+            .map(|(key, _)| key)
+    }
+
+    pub fn has_expired(&self, now: Timestamp, ttl: Option<Duration>) -> bool {
+        let expired = |record: &P2pNetworkKadStoredRecord<_>| is_time_passed(now, record.time, ttl);
+        self.values.values().any(expired)
+            || self
+                .providers
+                .values()
+                .flat_map(BTreeMap::values)
+                .any(expired)
+    }
+
+    /// Removes expired records received from other peers.
+    pub fn prune(&mut self, now: Timestamp, ttl: Option<Duration>) {
+        self.values
+            .retain(|_, record| !is_time_passed(now, record.time, ttl));
+        self.providers.retain(|_, providers| {
+            providers.retain(|_, record| !is_time_passed(now, record.time, ttl));
+            !providers.is_empty()
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -759,6 +1088,117 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_record_store_expiry() {
+        use std::time::Duration;
+
+        use redux::Timestamp;
+
+        use super::{P2pNetworkKadRecord, P2pNetworkKadRecordKey, P2pNetworkKadRecordStore};
+
+        let secs = |s: u64| Timestamp::new(s * 1_000_000_000);
+        let ttl = Some(Duration::from_secs(10));
+        let key = P2pNetworkKadRecordKey::from(b"key".to_vec());
+        let provider = entry_with_peer_id(peer_id_rand());
+
+        let mut store = P2pNetworkKadRecordStore::default();
+        assert!(store.add_provider(key.clone(), provider.clone(), provider.peer_id, secs(0)));
+        assert!(store.put_value(
+            P2pNetworkKadRecord {
+                key: key.clone(),
+                value: b"value".to_vec(),
+            },
+            provider.peer_id,
+            &this_key(),
+            secs(5),
+        ));
+
+        assert_eq!(
+            store
+                .providers(&key, secs(9), ttl)
+                .map(|e| e.peer_id)
+                .collect::<Vec<_>>(),
+            vec![provider.peer_id]
+        );
+        assert!(store.value(&key, secs(9), ttl).is_some());
+        assert!(!store.has_expired(secs(9), ttl));
+
+        assert_eq!(store.providers(&key, secs(10), ttl).count(), 0);
+        assert!(store.value(&key, secs(10), ttl).is_some());
+        assert!(store.has_expired(secs(10), ttl));
+
+        store.prune(secs(10), ttl);
+        assert!(store.providers.is_empty());
+        assert!(!store.values.is_empty());
+        store.prune(secs(15), ttl);
+        assert!(store.values.is_empty());
+    }
+
+    #[test]
+    fn test_record_store_limits() {
+        use redux::Timestamp;
+
+        use super::{
+            P2pNetworkKadRecord, P2pNetworkKadRecordKey, P2pNetworkKadRecordStore, MAX_RECORDS,
+            MAX_RECORDS_PER_PEER, MAX_RECORD_VALUE_LEN,
+        };
+
+        let record = |key: &str, len: usize| P2pNetworkKadRecord {
+            key: P2pNetworkKadRecordKey::from(key.as_bytes().to_vec()),
+            value: vec![0; len],
+        };
+        let publisher = peer_id_rand();
+        let mut store = P2pNetworkKadRecordStore::default();
+
+        // invalid records
+        for record in [
+            record("", 1),
+            record(&"k".repeat(257), 1),
+            record("key", MAX_RECORD_VALUE_LEN + 1),
+            record("/mina/ledger/hash", 1),
+        ] {
+            assert!(!store.put_value(record, publisher, &this_key(), Timestamp::ZERO));
+        }
+        assert!(store.values.is_empty());
+
+        // per peer quota, updating own records is still allowed
+        for i in 0..MAX_RECORDS_PER_PEER {
+            let record = record(&format!("key{i}"), 1);
+            assert!(store.put_value(record, publisher, &this_key(), Timestamp::ZERO));
+        }
+        let next = record(&format!("key{MAX_RECORDS_PER_PEER}"), 1);
+        assert!(!store.put_value(next, publisher, &this_key(), Timestamp::ZERO));
+        assert!(store.put_value(record("key0", 2), publisher, &this_key(), Timestamp::ZERO));
+
+        // when full, the record farthest from this node is evicted
+        while store.values.len() < MAX_RECORDS {
+            let record = record(&format!("key{}", store.values.len()), 1);
+            assert!(store.put_value(record, peer_id_rand(), &this_key(), Timestamp::ZERO));
+        }
+        let dist = |key: &P2pNetworkKadRecordKey| this_key() - P2pNetworkKadKey::from(key);
+        let farthest = store
+            .values
+            .keys()
+            .max_by_key(|key| dist(key))
+            .unwrap()
+            .clone();
+        let new_record = |closer: bool| {
+            (0..)
+                .map(|i| record(&format!("new{i}"), 1))
+                .find(|record| (dist(&record.key) < dist(&farthest)) == closer)
+                .unwrap()
+        };
+
+        let farther = new_record(false);
+        assert!(!store.put_value(farther, peer_id_rand(), &this_key(), Timestamp::ZERO));
+
+        let closer = new_record(true);
+        assert!(store.put_value(closer.clone(), peer_id_rand(), &this_key(), Timestamp::ZERO));
+        assert_eq!(store.values.len(), MAX_RECORDS);
+        assert!(store.values.contains_key(&closer.key));
+        assert!(!store.values.contains_key(&farthest));
+    }
+
     /// Tests that `find_node` returns entries in order of increasing distance.
     #[test]
     fn test_closest_peers_rand() {
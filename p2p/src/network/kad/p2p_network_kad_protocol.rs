@@ -6,7 +6,9 @@ use std::{
 use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 
-use super::{P2pNetworkKadEntry, P2pNetworkKadEntryTryFromError};
+use super::{
+    P2pNetworkKadEntry, P2pNetworkKadEntryTryFromError, P2pNetworkKadRecord, P2pNetworkKadRecordKey,
+};
 use crate::{mod_Message::MessageType, PeerId};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Serialize, Deserialize)]
@@ -41,7 +43,22 @@ impl From<ConnectionType> for super::mod_Message::ConnectionType {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum P2pNetworkKademliaRpcRequest {
-    FindNode { key: PeerId },
+    FindNode {
+        key: PeerId,
+    },
+    PutValue {
+        record: P2pNetworkKadRecord,
+    },
+    GetValue {
+        key: P2pNetworkKadRecordKey,
+    },
+    AddProvider {
+        key: P2pNetworkKadRecordKey,
+        providers: Vec<P2pNetworkKadEntry>,
+    },
+    GetProviders {
+        key: P2pNetworkKadRecordKey,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -49,12 +66,28 @@ pub enum P2pNetworkKademliaRpcReply {
     FindNode {
         closer_peers: Vec<P2pNetworkKadEntry>,
     },
+    PutValue {
+        record: P2pNetworkKadRecord,
+    },
+    GetValue {
+        record: Option<P2pNetworkKadRecord>,
+        closer_peers: Vec<P2pNetworkKadEntry>,
+    },
+    GetProviders {
+        provider_peers: Vec<P2pNetworkKadEntry>,
+        closer_peers: Vec<P2pNetworkKadEntry>,
+    },
 }
 
 impl P2pNetworkKademliaRpcRequest {
     pub fn find_node(key: PeerId) -> Self {
         P2pNetworkKademliaRpcRequest::FindNode { key }
     }
+
+    /// `ADD_PROVIDER` is the only request the remote peer doesn't reply to.
+    pub fn expects_reply(&self) -> bool {
+        !matches!(self, P2pNetworkKademliaRpcRequest::AddProvider { .. })
+    }
 }
 
 #[derive(Clone, Debug, Serialize, PartialEq, Deserialize, thiserror::Error)]
@@ -123,6 +156,31 @@ pub enum P2pNetworkKademliaRpcFromMessageError {
     Peer(#[from] P2pNetworkKadEntryTryFromError),
     #[error("unsupported RPC kind: {0}")]
     Unsupported(String),
+    #[error("missing record in {0} message")]
+    MissingRecord(String),
+}
+
+fn record_from_message(record: Option<super::Record<'_>>) -> Option<P2pNetworkKadRecord> {
+    record.map(|record| P2pNetworkKadRecord {
+        key: record.key.into_owned().into(),
+        value: record.value.into_owned(),
+    })
+}
+
+impl<'a> From<&'a P2pNetworkKadRecord> for super::Record<'a> {
+    fn from(value: &'a P2pNetworkKadRecord) -> Self {
+        super::Record {
+            key: value.key.as_bytes().into(),
+            value: value.value.as_slice().into(),
+            ..Default::default()
+        }
+    }
+}
+
+fn entries_try_from_message(
+    peers: Vec<super::mod_Message::Peer<'_>>,
+) -> Result<Vec<P2pNetworkKadEntry>, P2pNetworkKadEntryTryFromError> {
+    peers.into_iter().map(TryFrom::try_from).collect()
 }
 
 impl<'a> TryFrom<super::Message<'a>> for P2pNetworkKademliaRpcRequest {
@@ -134,6 +192,22 @@ impl<'a> TryFrom<super::Message<'a>> for P2pNetworkKademliaRpcRequest {
                 let key = peer_id_try_from_bytes(value.key)?;
                 Ok(P2pNetworkKademliaRpcRequest::FindNode { key })
             }
+            MessageType::PUT_VALUE => {
+                let record = record_from_message(value.record).ok_or_else(|| {
+                    P2pNetworkKademliaRpcFromMessageError::MissingRecord("PUT_VALUE".to_string())
+                })?;
+                Ok(P2pNetworkKademliaRpcRequest::PutValue { record })
+            }
+            MessageType::GET_VALUE => Ok(P2pNetworkKademliaRpcRequest::GetValue {
+                key: value.key.into_owned().into(),
+            }),
+            MessageType::ADD_PROVIDER => Ok(P2pNetworkKademliaRpcRequest::AddProvider {
+                key: value.key.into_owned().into(),
+                providers: entries_try_from_message(value.providerPeers)?,
+            }),
+            MessageType::GET_PROVIDERS => Ok(P2pNetworkKademliaRpcRequest::GetProviders {
+                key: value.key.into_owned().into(),
+            }),
             _ => Err(P2pNetworkKademliaRpcFromMessageError::Unsupported(format!(
                 "{:?}",
                 value.type_pb
@@ -148,13 +222,23 @@ impl<'a> TryFrom<super::Message<'a>> for P2pNetworkKademliaRpcReply {
     fn try_from(value: super::Message<'a>) -> Result<Self, Self::Error> {
         match value.type_pb {
             MessageType::FIND_NODE => {
-                let closer_peers = value
-                    .closerPeers
-                    .into_iter()
-                    .map(TryFrom::try_from)
-                    .collect::<Result<_, _>>()?;
+                let closer_peers = entries_try_from_message(value.closerPeers)?;
                 Ok(P2pNetworkKademliaRpcReply::FindNode { closer_peers })
             }
+            MessageType::PUT_VALUE => {
+                let record = record_from_message(value.record).ok_or_else(|| {
+                    P2pNetworkKademliaRpcFromMessageError::MissingRecord("PUT_VALUE".to_string())
+                })?;
+                Ok(P2pNetworkKademliaRpcReply::PutValue { record })
+            }
+            MessageType::GET_VALUE => Ok(P2pNetworkKademliaRpcReply::GetValue {
+                record: record_from_message(value.record),
+                closer_peers: entries_try_from_message(value.closerPeers)?,
+            }),
+            MessageType::GET_PROVIDERS => Ok(P2pNetworkKademliaRpcReply::GetProviders {
+                provider_peers: entries_try_from_message(value.providerPeers)?,
+                closer_peers: entries_try_from_message(value.closerPeers)?,
+            }),
             _ => Err(P2pNetworkKademliaRpcFromMessageError::Unsupported(format!(
                 "{:?}",
                 value.type_pb
@@ -172,6 +256,28 @@ impl<'a> From<&'a P2pNetworkKademliaRpcRequest> for super::Message<'a> {
                 key: key.into(),
                 ..Default::default()
             },
+            P2pNetworkKademliaRpcRequest::PutValue { record } => super::Message {
+                type_pb: MessageType::PUT_VALUE,
+                key: record.key.as_bytes().into(),
+                record: Some(record.into()),
+                ..Default::default()
+            },
+            P2pNetworkKademliaRpcRequest::GetValue { key } => super::Message {
+                type_pb: MessageType::GET_VALUE,
+                key: key.as_bytes().into(),
+                ..Default::default()
+            },
+            P2pNetworkKademliaRpcRequest::AddProvider { key, providers } => super::Message {
+                type_pb: MessageType::ADD_PROVIDER,
+                key: key.as_bytes().into(),
+                providerPeers: providers.iter().map(Into::into).collect(),
+                ..Default::default()
+            },
+            P2pNetworkKademliaRpcRequest::GetProviders { key } => super::Message {
+                type_pb: MessageType::GET_PROVIDERS,
+                key: key.as_bytes().into(),
+                ..Default::default()
+            },
         }
     }
 }
@@ -185,6 +291,33 @@ impl<'a> From<&'a P2pNetworkKademliaRpcReply> for super::Message<'a> {
                 closerPeers: closer_peers.iter().map(Into::into).collect(),
                 ..Default::default()
             },
+            P2pNetworkKademliaRpcReply::PutValue { record } => super::Message {
+                type_pb: MessageType::PUT_VALUE,
+                key: record.key.as_bytes().into(),
+                record: Some(record.into()),
+                ..Default::default()
+            },
+            P2pNetworkKademliaRpcReply::GetValue {
+                record,
+                closer_peers,
+            } => super::Message {
+                type_pb: MessageType::GET_VALUE,
+                key: record
+                    .as_ref()
+                    .map_or_else(Default::default, |record| record.key.as_bytes().into()),
+                record: record.as_ref().map(Into::into),
+                closerPeers: closer_peers.iter().map(Into::into).collect(),
+                ..Default::default()
+            },
+            P2pNetworkKademliaRpcReply::GetProviders {
+                provider_peers,
+                closer_peers,
+            } => super::Message {
+                type_pb: MessageType::GET_PROVIDERS,
+                providerPeers: provider_peers.iter().map(Into::into).collect(),
+                closerPeers: closer_peers.iter().map(Into::into).collect(),
+                ..Default::default()
+            },
         }
     }
 }
@@ -295,13 +428,46 @@ pub mod tests {
         let message = super::P2pNetworkKademliaRpcRequest::try_from(protobuf_message)
             .expect("should be able to convert");
 
-        let P2pNetworkKademliaRpcRequest::FindNode { key } = message;
+        let P2pNetworkKademliaRpcRequest::FindNode { key } = message else {
+            panic!("FIND_NODE request is expected");
+        };
         assert_eq!(
             &key.to_libp2p_string(),
             "12D3KooWNXARF5S7qTRZZuoTZwSda7XA7fBh4oz1vZadHnaFv1nL"
         );
     }
 
+    #[test]
+    fn get_providers_reply_roundtrip() {
+        use crate::{P2pNetworkKadEntry, P2pNetworkKademliaRpcReply};
+
+        let peer_id = "2bEgBrPTzL8wov2D4Kz34WVLCxR4uCarsBmHYXWKQA5wvBQzd9H"
+            .parse::<PeerId>()
+            .unwrap();
+        let addr = "/ip4/198.51.100.1/tcp/80".parse::<Multiaddr>().unwrap();
+        let reply = P2pNetworkKademliaRpcReply::GetProviders {
+            provider_peers: vec![P2pNetworkKadEntry::new(peer_id, vec![addr])],
+            closer_peers: vec![],
+        };
+
+        let bytes = quick_protobuf::serialize_into_vec(&super::super::Message::from(&reply))
+            .expect("should be able to encode");
+        let protobuf_message = BytesReader::from_bytes(&bytes)
+            .read_message::<super::super::Message>(&bytes)
+            .expect("should be able to decode");
+        let P2pNetworkKademliaRpcReply::GetProviders {
+            provider_peers,
+            closer_peers,
+        } = P2pNetworkKademliaRpcReply::try_from(protobuf_message)
+            .expect("should be able to convert")
+        else {
+            panic!("GET_PROVIDERS reply is expected");
+        };
+        assert!(closer_peers.is_empty());
+        assert_eq!(provider_peers.len(), 1);
+        assert_eq!(provider_peers[0].peer_id, peer_id);
+    }
+
     #[test]
     fn find_nodes_from_wire_len() {
         let input = "2c0804500a1226002408011220bcbfc53faa51a1410b7599c1e4411d5ac45ed5a1ffdc4673c1a6e2b9e9125c4d";
@@ -318,7 +484,9 @@ pub mod tests {
         let message = super::P2pNetworkKademliaRpcRequest::try_from(protobuf_message)
             .expect("should be able to convert");
 
-        let P2pNetworkKademliaRpcRequest::FindNode { key } = message;
+        let P2pNetworkKademliaRpcRequest::FindNode { key } = message else {
+            panic!("FIND_NODE request is expected");
+        };
         assert_eq!(
             &key.to_libp2p_string(),
             "12D3KooWNXARF5S7qTRZZuoTZwSda7XA7fBh4oz1vZadHnaFv1nL"
@@ -1,6 +1,6 @@
 use redux::ActionWithMeta;
 
-use crate::{P2pLimits, P2pNetworkKadEntry};
+use crate::{P2pLimits, P2pNetworkKadEntry, P2pNetworkKademliaRpcRequest, P2pTimeouts};
 
 use super::{P2pNetworkKadAction, P2pNetworkKadLatestRequestPeerKind, P2pNetworkKadStatus};
 
@@ -11,10 +11,13 @@ impl super::P2pNetworkKadState {
         &mut self,
         action: ActionWithMeta<&P2pNetworkKadAction>,
         limits: &P2pLimits,
+        timeouts: &P2pTimeouts,
     ) -> Result<(), String> {
         let (action, meta) = action.split();
         match action {
-            P2pNetworkKadAction::System(action) => self.system_reducer(meta.with_action(action)),
+            P2pNetworkKadAction::System(action) => {
+                self.system_reducer(meta.with_action(action), timeouts)
+            }
             P2pNetworkKadAction::Bootstrap(action) => {
                 if let P2pNetworkKadStatus::Bootstrapping(state) = &mut self.status {
                     state.reducer(
@@ -27,9 +30,13 @@ impl super::P2pNetworkKadState {
                 }
             }
             P2pNetworkKadAction::Request(
-                action @ super::request::P2pNetworkKadRequestAction::New { addr, peer_id, key },
+                action @ super::request::P2pNetworkKadRequestAction::New {
+                    addr,
+                    peer_id,
+                    request,
+                },
             ) => self
-                .create_request(*addr, *peer_id, *key)
+                .create_request(*addr, *peer_id, request.clone())
                 .map_err(|_request| format!("kademlia request to {addr} is already in progress"))
                 .and_then(|request| request.reducer(meta.with_action(action))),
             P2pNetworkKadAction::Request(super::request::P2pNetworkKadRequestAction::Prune {
@@ -68,6 +75,7 @@ impl super::P2pNetworkKadState {
     pub fn system_reducer(
         &mut self,
         action: ActionWithMeta<&super::P2pNetworkKademliaAction>,
+        timeouts: &P2pTimeouts,
     ) -> Result<(), String> {
         use super::P2pNetworkKadStatus::*;
         use super::P2pNetworkKademliaAction::*;
@@ -105,6 +113,104 @@ impl super::P2pNetworkKadState {
                     .insert(P2pNetworkKadEntry::new(*peer_id, addrs.clone()));
                 Ok(())
             }
+            (_, AnswerGetProvidersRequest { .. } | AnswerGetValueRequest { .. }) => Ok(()),
+            (
+                _,
+                AnswerPutValueRequest {
+                    peer_id, record, ..
+                },
+            ) => {
+                let this_key = &self.routing_table.this_key;
+                self.records
+                    .put_value(record.clone(), *peer_id, this_key, meta.time());
+                Ok(())
+            }
+            (
+                _,
+                AddProviders {
+                    peer_id,
+                    key,
+                    providers,
+                },
+            ) => {
+                for provider in providers.iter().filter(|p| &p.peer_id == peer_id) {
+                    self.records
+                        .add_provider(key.clone(), provider.clone(), *peer_id, meta.time());
+                }
+                Ok(())
+            }
+            (
+                _,
+                UpdateGetProvidersRequest {
+                    peer_id,
+                    providers,
+                    closest_peers,
+                    ..
+                },
+            ) => {
+                let Some(P2pNetworkKademliaRpcRequest::GetProviders { key }) =
+                    self.request(peer_id).map(|request| &request.request)
+                else {
+                    return Err(format!("no GET_PROVIDERS request to {peer_id}"));
+                };
+                let key = key.clone();
+                for provider in providers {
+                    self.records
+                        .add_provider(key.clone(), provider.clone(), *peer_id, meta.time());
+                }
+                self.routing_table.extend(closest_peers.iter().cloned());
+                Ok(())
+            }
+            (
+                _,
+                UpdateGetValueRequest {
+                    peer_id,
+                    record,
+                    closest_peers,
+                    ..
+                },
+            ) => {
+                let Some(P2pNetworkKademliaRpcRequest::GetValue { key }) =
+                    self.request(peer_id).map(|request| &request.request)
+                else {
+                    return Err(format!("no GET_VALUE request to {peer_id}"));
+                };
+                let key = key.clone();
+                // the peer can only answer with the value for the requested key
+                if let Some(record) = record.as_ref().filter(|record| record.key == key) {
+                    let this_key = &self.routing_table.this_key;
+                    self.records
+                        .put_value(record.clone(), *peer_id, this_key, meta.time());
+                }
+                self.routing_table.extend(closest_peers.iter().cloned());
+                Ok(())
+            }
+            (_, StartProviding { key }) => {
+                self.records.start_providing(key.clone());
+                Ok(())
+            }
+            (_, StopProviding { key }) => {
+                self.records.stop_providing(key);
+                Ok(())
+            }
+            (_, PutValue { record }) => {
+                self.records.put_local_value(record.clone());
+                Ok(())
+            }
+            (_, GetProviders { .. } | GetValue { .. } | RepublishRecords) => Ok(()),
+            (_, PublishProvider { key }) => {
+                self.records.provider_published(key, meta.time());
+                Ok(())
+            }
+            (_, PublishValue { key }) => {
+                self.records.value_published(key, meta.time());
+                Ok(())
+            }
+            (_, PruneRecords) => {
+                self.records
+                    .prune(meta.time(), timeouts.kademlia_record_ttl);
+                Ok(())
+            }
             (state, action) => Err(format!("invalid action {action:?} for state {state:?}")),
         }
     }
@@ -5,11 +5,13 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     bootstrap::P2pNetworkKadBootstrapState, request::P2pNetworkKadRequestState,
-    stream::P2pNetworkKadStreamState, P2pNetworkKadRoutingTable,
+    stream::P2pNetworkKadStreamState, P2pNetworkKadEntry, P2pNetworkKadKey,
+    P2pNetworkKadRecordStore, P2pNetworkKadRoutingTable,
 };
 use crate::{
     bootstrap::{P2pNetworkKadBootstrapRequestStat, P2pNetworkKadBootstrapStats},
-    is_time_passed, P2pTimeouts, PeerId, StreamId,
+    is_time_passed, socket_addr_try_from_multiaddr, P2pNetworkKademliaRpcRequest, P2pTimeouts,
+    PeerId, StreamId,
 };
 
 /// Kademlia status.
@@ -57,6 +59,8 @@ pub struct P2pNetworkKadState {
     pub streams: crate::network::scheduler::StreamState<P2pNetworkKadStreamState>,
     pub status: P2pNetworkKadStatus,
     pub filter_addrs: bool,
+    /// Value and provider records.
+    pub records: P2pNetworkKadRecordStore,
 }
 
 impl Default for P2pNetworkKadState {
@@ -71,6 +75,7 @@ impl Default for P2pNetworkKadState {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(true),
+            records: Default::default(),
         }
     }
 }
@@ -104,6 +109,36 @@ impl P2pNetworkKadState {
         }
     }
 
+    /// Routing table entry of the current node.
+    pub fn this_entry(&self) -> Option<&P2pNetworkKadEntry> {
+        self.routing_table.look_up(&self.routing_table.this_key)
+    }
+
+    /// Peers closest to the `key`, with an address to send a request to.
+    pub fn closest_peer_addrs(&self, key: &P2pNetworkKadKey) -> Vec<(PeerId, SocketAddr)> {
+        self.routing_table
+            .find_node(key)
+            .filter_map(|entry| Some((entry.peer_id, self.dialable_addr(entry)?)))
+            .collect()
+    }
+
+    /// Address of the entry that can be dialed, private ones are skipped
+    /// unless address filtering is disabled.
+    pub fn dialable_addr(&self, entry: &P2pNetworkKadEntry) -> Option<SocketAddr> {
+        entry
+            .addrs
+            .iter()
+            .map(socket_addr_try_from_multiaddr)
+            .filter_map(Result::ok)
+            .find(|addr| {
+                !self.filter_addrs
+                    || match addr.ip() {
+                        std::net::IpAddr::V4(ipv4) => !(ipv4.is_loopback() || ipv4.is_private()),
+                        std::net::IpAddr::V6(ipv6) => !ipv6.is_loopback(),
+                    }
+            })
+    }
+
     pub fn request(&self, peer_id: &PeerId) -> Option<&P2pNetworkKadRequestState> {
         self.requests.get(peer_id)
     }
@@ -112,13 +147,13 @@ impl P2pNetworkKadState {
         &mut self,
         addr: SocketAddr,
         peer_id: PeerId,
-        key: PeerId,
+        request: P2pNetworkKademliaRpcRequest,
     ) -> Result<&mut P2pNetworkKadRequestState, &P2pNetworkKadRequestState> {
         match self.requests.entry(peer_id) {
             std::collections::btree_map::Entry::Vacant(v) => {
                 Ok(v.insert(P2pNetworkKadRequestState {
                     peer_id,
-                    key,
+                    request,
                     addr,
                     status: crate::request::P2pNetworkKadRequestStatus::Default,
                 }))
@@ -5,8 +5,8 @@ use redux::ActionMeta;
 use crate::{
     connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingInitOpts},
     peer::P2pPeerAction,
-    socket_addr_try_from_multiaddr, P2pNetworkConnectionMuxState, P2pNetworkKadBootstrapAction,
-    P2pNetworkYamuxAction, P2pPeerState,
+    socket_addr_try_from_multiaddr, P2pNetworkKadBootstrapAction, P2pNetworkYamuxAction,
+    P2pPeerState,
 };
 
 use super::{super::stream::P2pNetworkKademliaStreamAction, P2pNetworkKadRequestAction};
@@ -63,14 +63,9 @@ impl P2pNetworkKadRequestAction {
                                 .map(|(_, s)| s)
                         ));
                     };
-                    if let Some(stream_id) = conn_state.mux.as_ref().and_then(
-                        |P2pNetworkConnectionMuxState::Yamux(yamux)| {
-                            yamux.next_stream_id(
-                                crate::YamuxStreamKind::Kademlia,
-                                conn_state.incoming,
-                            )
-                        },
-                    ) {
+                    if let Some(stream_id) = conn_state.mux.as_ref().and_then(|mux| {
+                        mux.next_stream_id(crate::YamuxStreamKind::Kademlia, conn_state.incoming)
+                    }) {
                         // multiplexing is ready, open a stream
                         store.dispatch(P2pNetworkYamuxAction::OpenStream {
                             addr,
@@ -112,9 +107,8 @@ impl P2pNetworkKadRequestAction {
                             .map(|mux| (mux, conn.incoming))
                             .ok_or_else(|| format!("multiplexing is not ready for {addr}"))
                     })
-                    .and_then(|(P2pNetworkConnectionMuxState::Yamux(yamux), incoming)| {
-                        yamux
-                            .next_stream_id(crate::YamuxStreamKind::Kademlia, incoming)
+                    .and_then(|(mux, incoming)| {
+                        mux.next_stream_id(crate::YamuxStreamKind::Kademlia, incoming)
                             .ok_or_else(|| format!("cannot get next stream for {addr}"))
                     })?;
                 store.dispatch(P2pNetworkYamuxAction::OpenStream {
@@ -132,7 +126,8 @@ impl P2pNetworkKadRequestAction {
                 stream_id,
                 addr,
             } => {
-                let data = crate::P2pNetworkKademliaRpcRequest::find_node(request_state.key);
+                let data = request_state.request.clone();
+                let expects_reply = data.expects_reply();
                 store.dispatch(P2pNetworkKademliaStreamAction::SendRequest {
                     addr,
                     peer_id,
@@ -140,6 +135,9 @@ impl P2pNetworkKadRequestAction {
                     data,
                 });
                 store.dispatch(A::RequestSent { peer_id });
+                if !expects_reply {
+                    store.dispatch(A::Prune { peer_id });
+                }
             }
             A::RequestSent { .. } => {}
             A::ReplyReceived {
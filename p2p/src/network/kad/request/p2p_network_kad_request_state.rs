@@ -2,14 +2,15 @@ use std::net::SocketAddr;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{P2pNetworkKadEntry, PeerId, StreamId};
+use crate::{P2pNetworkKadEntry, P2pNetworkKademliaRpcRequest, PeerId, StreamId};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct P2pNetworkKadRequestState {
     /// ID of the peer we want to send request to.
     pub peer_id: PeerId,
-    /// Request key, resulting entries will be those that closest to it.
-    pub key: PeerId,
+    /// Request to send, for `FIND_NODE` resulting entries will be those that
+    /// closest to its key.
+    pub request: P2pNetworkKademliaRpcRequest,
     /// Address
     pub addr: SocketAddr,
     /// Request status.
@@ -2,6 +2,7 @@ use openmina_core::warn;
 use redux::ActionMeta;
 
 use crate::{
+    request::P2pNetworkKadRequestAction,
     stream::{P2pNetworkKadIncomingStreamError, P2pNetworkKadOutgoingStreamError},
     Data, P2pNetworkKademliaAction, P2pNetworkSchedulerAction, P2pNetworkYamuxAction,
 };
@@ -65,6 +66,65 @@ impl P2pNetworkKademliaStreamAction {
                 });
                 Ok(())
             }
+            (
+                A::IncomingData {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    ..
+                },
+                D::Incoming(I::RequestIsReady { data }),
+            ) => {
+                let data = data.clone();
+                if let P2pNetworkKademliaRpcRequest::AddProvider { key, providers } = data {
+                    // no reply is expected, wait for the next request
+                    store.dispatch(A::WaitIncoming {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    });
+                    store.dispatch(P2pNetworkKademliaAction::AddProviders {
+                        peer_id,
+                        key,
+                        providers,
+                    });
+                    return Ok(());
+                }
+                store.dispatch(A::WaitOutgoing {
+                    addr,
+                    peer_id,
+                    stream_id,
+                });
+                match data {
+                    P2pNetworkKademliaRpcRequest::GetProviders { key } => {
+                        store.dispatch(P2pNetworkKademliaAction::AnswerGetProvidersRequest {
+                            addr,
+                            peer_id,
+                            stream_id,
+                            key,
+                        });
+                    }
+                    P2pNetworkKademliaRpcRequest::GetValue { key } => {
+                        store.dispatch(P2pNetworkKademliaAction::AnswerGetValueRequest {
+                            addr,
+                            peer_id,
+                            stream_id,
+                            key,
+                        });
+                    }
+                    P2pNetworkKademliaRpcRequest::PutValue { record } => {
+                        store.dispatch(P2pNetworkKademliaAction::AnswerPutValueRequest {
+                            addr,
+                            peer_id,
+                            stream_id,
+                            record,
+                        });
+                    }
+                    P2pNetworkKademliaRpcRequest::FindNode { .. }
+                    | P2pNetworkKademliaRpcRequest::AddProvider { .. } => unreachable!(),
+                }
+                Ok(())
+            }
             (
                 A::IncomingData {
                     addr,
@@ -90,10 +150,84 @@ impl P2pNetworkKademliaStreamAction {
                 });
                 Ok(())
             }
+            (
+                A::IncomingData {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    ..
+                },
+                D::Outgoing(O::ResponseIsReady { data }),
+            ) => {
+                let data = data.clone();
+                store.dispatch(A::WaitOutgoing {
+                    addr,
+                    peer_id,
+                    stream_id,
+                });
+                match data {
+                    P2pNetworkKademliaRpcReply::GetProviders {
+                        provider_peers,
+                        closer_peers,
+                    } => {
+                        store.dispatch(P2pNetworkKademliaAction::UpdateGetProvidersRequest {
+                            addr,
+                            peer_id,
+                            stream_id,
+                            providers: provider_peers,
+                            closest_peers: closer_peers,
+                        });
+                    }
+                    P2pNetworkKademliaRpcReply::GetValue {
+                        record,
+                        closer_peers,
+                    } => {
+                        store.dispatch(P2pNetworkKademliaAction::UpdateGetValueRequest {
+                            addr,
+                            peer_id,
+                            stream_id,
+                            record,
+                            closest_peers: closer_peers,
+                        });
+                    }
+                    P2pNetworkKademliaRpcReply::PutValue { .. } => {
+                        store.dispatch(P2pNetworkKadRequestAction::ReplyReceived {
+                            peer_id,
+                            stream_id,
+                            data: Vec::new(),
+                        });
+                    }
+                    P2pNetworkKademliaRpcReply::FindNode { .. } => unreachable!(),
+                }
+                Ok(())
+            }
             (
                 A::WaitOutgoing { .. },
                 D::Incoming(I::WaitingForReply { .. }) | D::Outgoing(O::WaitingForRequest { .. }),
             ) => Ok(()),
+            (
+                A::SendRequest {
+                    addr,
+                    peer_id,
+                    stream_id,
+                    data: P2pNetworkKademliaRpcRequest::AddProvider { .. },
+                },
+                D::Outgoing(O::RequestBytesAreReady { bytes }),
+            ) => {
+                // send data to the network and close the stream, as no reply is expected
+                store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                    addr,
+                    stream_id,
+                    data: bytes.clone().into(),
+                    fin: false,
+                });
+                store.dispatch(A::Close {
+                    addr,
+                    peer_id,
+                    stream_id,
+                });
+                Ok(())
+            }
             (
                 A::SendRequest {
                     addr,
@@ -72,6 +72,11 @@ impl P2pNetworkKadIncomingStreamState {
                 *self = S::WaitingForReply;
                 Ok(())
             }
+            (S::RequestIsReady { .. }, A::WaitIncoming { .. }) => {
+                // no reply is sent for `ADD_PROVIDER`
+                *self = S::WaitingForRequest { expect_close: true };
+                Ok(())
+            }
             (S::WaitingForReply, A::SendResponse { data, .. }) => {
                 let message = Message::from(data);
                 let bytes = serialize_into_vec(&message).map_err(|e| format!("{e}"))?;
@@ -145,6 +150,11 @@ impl P2pNetworkKadOutgoingStreamState {
                 *self = S::WaitingForReply;
                 Ok(())
             }
+            (S::RequestBytesAreReady { .. }, A::Close { .. }) => {
+                // request bytes are already sent, no reply is expected
+                *self = S::RequestBytesAreReady { bytes: Vec::new() };
+                Ok(())
+            }
 
             (S::WaitingForReply { .. }, A::IncomingData { data, .. }) => {
                 let data = &data.0;
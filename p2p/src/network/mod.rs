@@ -44,6 +44,21 @@ pub use self::pubsub::*;
 pub mod rpc;
 pub use self::rpc::*;
 
+pub mod node_status;
+pub use self::node_status::*;
+
+pub mod ping;
+pub use self::ping::*;
+
+pub mod relay;
+pub use self::relay::*;
+
+pub mod quic;
+pub use self::quic::*;
+
+pub mod bandwidth;
+pub use self::bandwidth::*;
+
 pub use self::data::{Data, DataSized};
 mod data {
     use std::{fmt, ops};
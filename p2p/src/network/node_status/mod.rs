@@ -0,0 +1,12 @@
+mod p2p_network_node_status_message;
+pub use self::p2p_network_node_status_message::*;
+
+mod p2p_network_node_status_actions;
+pub use self::p2p_network_node_status_actions::*;
+
+mod p2p_network_node_status_state;
+pub use self::p2p_network_node_status_state::*;
+
+mod p2p_network_node_status_reducer;
+
+mod p2p_network_node_status_effects;
@@ -0,0 +1,113 @@
+use std::net::SocketAddr;
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use super::{P2pNetworkNodeStatus, P2pNetworkNodeStatusStreamState};
+use crate::{Data, P2pAction, P2pNetworkAction, P2pState, PeerId, StreamId};
+
+/// Node status actions.
+#[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
+#[action_event(fields(display(addr), display(peer_id), stream_id, incoming))]
+pub enum P2pNetworkNodeStatusAction {
+    /// Creates a new stream state.
+    NewStream {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        incoming: bool,
+    },
+    /// Remote peer requested our status, to be provided by the node
+    /// with [`P2pNetworkNodeStatusAction::ResponseSend`].
+    RequestReceived {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+    /// Sends our status to the remote peer and closes the stream.
+    ResponseSend {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        status: Box<P2pNetworkNodeStatus>,
+    },
+    /// Opens an outgoing stream to request the status of the peer.
+    Request { addr: SocketAddr, peer_id: PeerId },
+    /// Handles incoming data from the stream.
+    #[action_event(level = trace)]
+    IncomingData {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        data: Data,
+    },
+    /// Remote peer sent FIN to close the stream.
+    RemoteClose {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+    /// Status of the remote peer is received.
+    ResponseReceived {
+        peer_id: PeerId,
+        stream_id: StreamId,
+        status: Box<P2pNetworkNodeStatus>,
+    },
+    /// Removes the closed stream from the state.
+    Prune {
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+}
+
+impl From<P2pNetworkNodeStatusAction> for P2pAction {
+    fn from(value: P2pNetworkNodeStatusAction) -> Self {
+        P2pAction::Network(P2pNetworkAction::NodeStatus(value))
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNetworkNodeStatusAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        let node_status = &state.network.scheduler.node_status_state;
+        match self {
+            P2pNetworkNodeStatusAction::NewStream {
+                peer_id, stream_id, ..
+            } => node_status.find_stream(peer_id, stream_id).is_none(),
+            P2pNetworkNodeStatusAction::RequestReceived {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkNodeStatusAction::ResponseSend {
+                peer_id, stream_id, ..
+            } => matches!(
+                node_status.find_stream(peer_id, stream_id),
+                Some(P2pNetworkNodeStatusStreamState::WaitingForStatus)
+            ),
+            P2pNetworkNodeStatusAction::Request { addr, peer_id } => {
+                !node_status.is_requesting(peer_id)
+                    && state
+                        .network
+                        .scheduler
+                        .connections
+                        .get(addr)
+                        .map_or(false, |conn| {
+                            conn.peer_id() == Some(peer_id) && conn.closed.is_none()
+                        })
+            }
+            P2pNetworkNodeStatusAction::IncomingData {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkNodeStatusAction::RemoteClose {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkNodeStatusAction::Prune { peer_id, stream_id } => {
+                node_status.find_stream(peer_id, stream_id).is_some()
+            }
+            P2pNetworkNodeStatusAction::ResponseReceived {
+                peer_id, stream_id, ..
+            } => matches!(
+                node_status.find_stream(peer_id, stream_id),
+                Some(P2pNetworkNodeStatusStreamState::StatusReceived { .. })
+            ),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::net::SocketAddr;
+
+use openmina_core::warn;
+use redux::ActionMeta;
+
+use crate::{token, Data, P2pNetworkYamuxAction, PeerId, StreamId, YamuxStreamKind};
+
+use super::{P2pNetworkNodeStatusAction, P2pNetworkNodeStatusStreamState};
+
+impl P2pNetworkNodeStatusAction {
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store) -> Result<(), String>
+    where
+        Store: crate::P2pStore<S>,
+    {
+        let node_status = &store.state().network.scheduler.node_status_state;
+        match self {
+            P2pNetworkNodeStatusAction::NewStream {
+                addr,
+                peer_id,
+                stream_id,
+                incoming: true,
+            } => {
+                store.dispatch(P2pNetworkNodeStatusAction::RequestReceived {
+                    addr,
+                    peer_id,
+                    stream_id,
+                });
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::ResponseSend {
+                addr,
+                peer_id,
+                stream_id,
+                status,
+            } => {
+                let data = serde_json::to_vec(&status)
+                    .map_err(|e| format!("error serializing node status: {e}"))?;
+                store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                    addr,
+                    stream_id,
+                    data: Data::from(data),
+                    fin: true,
+                });
+                store.dispatch(P2pNetworkNodeStatusAction::Prune { peer_id, stream_id });
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::Request { addr, .. } => {
+                let Some(conn) = store.state().network.scheduler.connections.get(&addr) else {
+                    return Err(format!("connection with {addr} not found"));
+                };
+                let stream_id = conn
+                    .mux
+                    .as_ref()
+                    .and_then(|mux| mux.next_stream_id(YamuxStreamKind::NodeStatus, conn.incoming))
+                    .ok_or_else(|| format!("cannot get next stream for {addr}"))?;
+                if conn.streams.contains_key(&stream_id) {
+                    // the stream is being negotiated
+                    return Ok(());
+                }
+                store.dispatch(P2pNetworkYamuxAction::OpenStream {
+                    addr,
+                    stream_id,
+                    stream_kind: token::StreamKind::Status(token::StatusAlgorithm::MinaNodeStatus),
+                });
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::IncomingData {
+                addr,
+                peer_id,
+                stream_id,
+                ..
+            } => {
+                if let Some(P2pNetworkNodeStatusStreamState::Error(err)) =
+                    node_status.find_stream(&peer_id, &stream_id)
+                {
+                    warn!(meta.time(); summary = "error handling node status action", error = display(err));
+                    close(store, addr, peer_id, stream_id);
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::RemoteClose {
+                addr,
+                peer_id,
+                stream_id,
+            } => {
+                match node_status.find_stream(&peer_id, &stream_id) {
+                    Some(P2pNetworkNodeStatusStreamState::StatusReceived { status }) => {
+                        let status = status.clone();
+                        store.dispatch(P2pNetworkNodeStatusAction::ResponseReceived {
+                            peer_id,
+                            stream_id,
+                            status,
+                        });
+                        close(store, addr, peer_id, stream_id);
+                    }
+                    Some(P2pNetworkNodeStatusStreamState::Error(err)) => {
+                        warn!(meta.time(); summary = "error handling node status action", error = display(err));
+                        close(store, addr, peer_id, stream_id);
+                    }
+                    // the peer may half-close the stream right after opening it,
+                    // the status is still sent to it
+                    _ => {}
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::NewStream { .. }
+            | P2pNetworkNodeStatusAction::RequestReceived { .. }
+            | P2pNetworkNodeStatusAction::ResponseReceived { .. }
+            | P2pNetworkNodeStatusAction::Prune { .. } => Ok(()),
+        }
+    }
+}
+
+/// Sends FIN to the remote peer and removes the stream.
+fn close<Store, S>(store: &mut Store, addr: SocketAddr, peer_id: PeerId, stream_id: StreamId)
+where
+    Store: crate::P2pStore<S>,
+{
+    store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+        addr,
+        stream_id,
+        data: Data(Box::new([])),
+        fin: true,
+    });
+    store.dispatch(P2pNetworkNodeStatusAction::Prune { peer_id, stream_id });
+}
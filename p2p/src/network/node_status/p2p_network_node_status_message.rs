@@ -0,0 +1,103 @@
+use std::net::IpAddr;
+
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+/// Node status, as served by the Mina nodes to the network crawlers.
+///
+/// Serialized as JSON, using the same field names and layout as the OCaml
+/// implementation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct P2pNetworkNodeStatus {
+    pub node_ip_addr: IpAddr,
+    /// Peer id in the libp2p format.
+    pub node_peer_id: String,
+    pub sync_status: P2pNetworkNodeSyncStatus,
+    pub peers: Vec<P2pNetworkNodeStatusPeer>,
+    pub block_producers: Vec<NonZeroCurvePoint>,
+    pub protocol_state_hash: StateHash,
+    /// Peers banned by the trust system, with their trust status.
+    pub ban_statuses: Vec<(P2pNetworkNodeStatusPeer, serde_json::Value)>,
+    /// Hashes of the best chain blocks, with their timestamps.
+    pub k_block_hashes_and_timestamps: Vec<(StateHash, String)>,
+    pub git_commit: String,
+    pub uptime_minutes: u64,
+    pub block_height_opt: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum P2pNetworkNodeSyncStatus {
+    Connecting,
+    Listening,
+    Offline,
+    Bootstrap,
+    Synced,
+    Catchup,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct P2pNetworkNodeStatusPeer {
+    pub host: String,
+    pub libp2p_port: u16,
+    /// Peer id in the libp2p format.
+    pub peer_id: String,
+}
+
+impl P2pNetworkNodeStatusPeer {
+    pub fn new(peer_id: PeerId, host: String, libp2p_port: u16) -> Self {
+        Self {
+            host,
+            libp2p_port,
+            peer_id: libp2p_peer_id(peer_id),
+        }
+    }
+}
+
+/// Formats the peer id the way libp2p does, which is what the Mina nodes
+/// expect.
+pub fn libp2p_peer_id(peer_id: PeerId) -> String {
+    #[cfg(not(target_arch = "wasm32"))]
+    return peer_id.to_libp2p_string();
+    #[cfg(target_arch = "wasm32")]
+    return peer_id.to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_status_from_json() {
+        let json = r#"{
+            "node_ip_addr": "1.2.3.4",
+            "node_peer_id": "12D3KooWEiGVAFC7curXWXiGZyMWnZK9h8BKr88U8D5PKV3dXciv",
+            "sync_status": "Synced",
+            "peers": [
+                {
+                    "host": "5.6.7.8",
+                    "libp2p_port": 8302,
+                    "peer_id": "12D3KooWKG1ZakvjwDPBoJDTh6aQQgN3ou1G2rMbHkKxZi5jZTaR"
+                }
+            ],
+            "block_producers": [],
+            "protocol_state_hash": "3NKxUSAJE3wqJkrtBhMYhwzrMq3B5sKjPJQRyXz1YrPWA7761opD",
+            "ban_statuses": [],
+            "k_block_hashes_and_timestamps": [
+                ["3NKxUSAJE3wqJkrtBhMYhwzrMq3B5sKjPJQRyXz1YrPWA7761opD", "2024-03-13 09:00:00.000000Z"]
+            ],
+            "git_commit": "abcdef",
+            "uptime_minutes": 42,
+            "block_height_opt": 1234
+        }"#;
+        let status: P2pNetworkNodeStatus = serde_json::from_str(json).unwrap();
+        assert_eq!(status.sync_status, P2pNetworkNodeSyncStatus::Synced);
+        assert_eq!(status.peers[0].libp2p_port, 8302);
+        assert_eq!(status.block_height_opt, Some(1234));
+
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(value["sync_status"], "Synced");
+        assert_eq!(value["node_ip_addr"], "1.2.3.4");
+    }
+}
@@ -0,0 +1,100 @@
+use redux::ActionWithMeta;
+
+use crate::P2pLimits;
+
+use super::{
+    P2pNetworkNodeStatusAction, P2pNetworkNodeStatusReceived, P2pNetworkNodeStatusState,
+    P2pNetworkNodeStatusStreamError, P2pNetworkNodeStatusStreamState,
+};
+
+impl P2pNetworkNodeStatusState {
+    pub fn reducer(
+        &mut self,
+        action: ActionWithMeta<&P2pNetworkNodeStatusAction>,
+        limits: &P2pLimits,
+    ) -> Result<(), String> {
+        let (action, meta) = action.split();
+        match action {
+            P2pNetworkNodeStatusAction::NewStream {
+                peer_id,
+                stream_id,
+                incoming,
+                ..
+            } => {
+                self.streams
+                    .entry(*peer_id)
+                    .or_default()
+                    .insert(*stream_id, P2pNetworkNodeStatusStreamState::new(*incoming));
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::Request { peer_id, .. } => {
+                self.requested.insert(*peer_id, meta.time());
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::RequestReceived { .. }
+            | P2pNetworkNodeStatusAction::ResponseSend { .. } => Ok(()),
+            P2pNetworkNodeStatusAction::IncomingData {
+                peer_id,
+                stream_id,
+                data,
+                ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("node status stream not found for action {action:?}"))?;
+                // the requesting peer is not supposed to send anything
+                if let P2pNetworkNodeStatusStreamState::RecvStatus { data: buf } = stream {
+                    buf.extend_from_slice(data);
+                    if buf.len() > limits.node_status_message() {
+                        *stream = P2pNetworkNodeStatusStreamState::Error(
+                            P2pNetworkNodeStatusStreamError::Limit(
+                                buf.len(),
+                                limits.node_status_message(),
+                            ),
+                        );
+                    }
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::RemoteClose {
+                peer_id, stream_id, ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("node status stream not found for action {action:?}"))?;
+                if let P2pNetworkNodeStatusStreamState::RecvStatus { data } = stream {
+                    *stream = match serde_json::from_slice(data) {
+                        Ok(status) => P2pNetworkNodeStatusStreamState::StatusReceived { status },
+                        Err(err) => P2pNetworkNodeStatusStreamState::Error(
+                            P2pNetworkNodeStatusStreamError::Json(err.to_string()),
+                        ),
+                    };
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::ResponseReceived {
+                peer_id, status, ..
+            } => {
+                self.peer_statuses.insert(
+                    *peer_id,
+                    P2pNetworkNodeStatusReceived {
+                        time: meta.time(),
+                        status: *status.clone(),
+                    },
+                );
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::Prune { peer_id, stream_id } => {
+                let streams = self
+                    .streams
+                    .get_mut(peer_id)
+                    .ok_or_else(|| format!("node status streams not found for {peer_id}"))?;
+                streams.remove(stream_id);
+                if streams.is_empty() {
+                    self.streams.remove(peer_id);
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{is_time_passed, network::scheduler::StreamState, P2pTimeouts, PeerId, StreamId};
+
+use super::P2pNetworkNodeStatus;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct P2pNetworkNodeStatusState {
+    pub streams: StreamState<P2pNetworkNodeStatusStreamState>,
+    /// Latest statuses received from the peers.
+    pub peer_statuses: BTreeMap<PeerId, P2pNetworkNodeStatusReceived>,
+    /// Time of the latest status request sent to the peer.
+    pub requested: BTreeMap<PeerId, Timestamp>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum P2pNetworkNodeStatusStreamState {
+    /// Incoming stream, waiting for the node to provide its status.
+    WaitingForStatus,
+    /// Outgoing stream, receiving the status of the remote peer.
+    RecvStatus { data: Vec<u8> },
+    /// Status of the remote peer is received.
+    StatusReceived { status: Box<P2pNetworkNodeStatus> },
+    /// Error handling the stream.
+    Error(P2pNetworkNodeStatusStreamError),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P2pNetworkNodeStatusReceived {
+    pub time: Timestamp,
+    pub status: P2pNetworkNodeStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error, Serialize, Deserialize)]
+pub enum P2pNetworkNodeStatusStreamError {
+    #[error("node status message is too long: {0} > {1}")]
+    Limit(usize, usize),
+    #[error("error decoding node status: {0}")]
+    Json(String),
+}
+
+impl P2pNetworkNodeStatusState {
+    pub fn find_stream(
+        &self,
+        peer_id: &PeerId,
+        stream_id: &StreamId,
+    ) -> Option<&P2pNetworkNodeStatusStreamState> {
+        self.streams.get(peer_id)?.get(stream_id)
+    }
+
+    pub fn find_stream_mut(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: &StreamId,
+    ) -> Option<&mut P2pNetworkNodeStatusStreamState> {
+        self.streams.get_mut(peer_id)?.get_mut(stream_id)
+    }
+
+    /// Checks if the status of the peer should be requested (again).
+    pub fn should_request(&self, peer_id: &PeerId, now: Timestamp, timeouts: &P2pTimeouts) -> bool {
+        timeouts.node_status_interval.is_some()
+            && !self.is_requesting(peer_id)
+            && self.requested.get(peer_id).map_or(true, |time| {
+                is_time_passed(now, *time, timeouts.node_status_interval)
+            })
+    }
+
+    /// Checks if the status of the peer is being requested.
+    pub fn is_requesting(&self, peer_id: &PeerId) -> bool {
+        self.streams.get(peer_id).map_or(false, |streams| {
+            streams.values().any(|stream| !stream.is_incoming())
+        })
+    }
+}
+
+impl P2pNetworkNodeStatusStreamState {
+    pub fn new(incoming: bool) -> Self {
+        if incoming {
+            Self::WaitingForStatus
+        } else {
+            Self::RecvStatus { data: Vec::new() }
+        }
+    }
+
+    pub fn is_incoming(&self) -> bool {
+        matches!(self, Self::WaitingForStatus)
+    }
+}
@@ -2,7 +2,8 @@ use openmina_core::ActionEvent;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    identify::*, kad::*, noise::*, pnet::*, pubsub::*, rpc::*, scheduler::*, select::*, yamux::*,
+    identify::*, kad::*, node_status::*, noise::*, ping::*, pnet::*, pubsub::*, quic::*, relay::*,
+    rpc::*, scheduler::*, select::*, yamux::*,
 };
 
 use crate::P2pState;
@@ -18,6 +19,10 @@ pub enum P2pNetworkAction {
     Kad(P2pNetworkKadAction),
     Pubsub(P2pNetworkPubsubAction),
     Rpc(P2pNetworkRpcAction),
+    NodeStatus(P2pNetworkNodeStatusAction),
+    Ping(P2pNetworkPingAction),
+    Relay(P2pNetworkRelayAction),
+    Quic(P2pNetworkQuicAction),
 }
 
 impl redux::EnablingCondition<P2pState> for P2pNetworkAction {
@@ -32,6 +37,10 @@ impl redux::EnablingCondition<P2pState> for P2pNetworkAction {
             Self::Kad(v) => v.is_enabled(state, time),
             Self::Pubsub(v) => v.is_enabled(state, time),
             Self::Rpc(v) => v.is_enabled(state, time),
+            Self::NodeStatus(v) => v.is_enabled(state, time),
+            Self::Ping(v) => v.is_enabled(state, time),
+            Self::Relay(v) => v.is_enabled(state, time),
+            Self::Quic(v) => v.is_enabled(state, time),
         }
     }
 }
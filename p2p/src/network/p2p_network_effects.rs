@@ -6,7 +6,7 @@ impl P2pNetworkAction {
     pub fn effects<Store, S>(self, meta: &redux::ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
-        Store::Service: P2pMioService + P2pCryptoService + P2pNetworkService,
+        Store::Service: P2pMioService + P2pQuicService + P2pCryptoService + P2pNetworkService,
     {
         match self {
             Self::Scheduler(v) => v.effects(meta, store),
@@ -24,6 +24,22 @@ impl P2pNetworkAction {
             },
             Self::Pubsub(v) => v.effects(meta, store),
             Self::Rpc(v) => v.effects(meta, store),
+            Self::NodeStatus(v) => match v.effects(meta, store) {
+                Ok(_) => {}
+                Err(e) => error!(meta.time(); "error dispatching NodeStatus action: {e}"),
+            },
+            Self::Ping(v) => match v.effects(meta, store) {
+                Ok(_) => {}
+                Err(e) => error!(meta.time(); "error dispatching Ping action: {e}"),
+            },
+            Self::Relay(v) => match v.effects(meta, store) {
+                Ok(_) => {}
+                Err(e) => error!(meta.time(); "error dispatching Relay action: {e}"),
+            },
+            Self::Quic(v) => match v.effects(meta, store) {
+                Ok(_) => {}
+                Err(e) => error!(meta.time(); "error dispatching Quic action: {e}"),
+            },
         }
     }
 }
@@ -1,7 +1,7 @@
 use multiaddr::Multiaddr;
 use openmina_core::{error, ChainId};
 
-use crate::{identity::PublicKey, P2pLimits, PeerId};
+use crate::{identity::PublicKey, P2pLimits, P2pTimeouts, PeerId};
 
 use super::*;
 
@@ -41,7 +41,12 @@ impl P2pNetworkState {
                 discovery_state,
                 rpc_incoming_streams: Default::default(),
                 rpc_outgoing_streams: Default::default(),
+                node_status_state: Default::default(),
+                ping_state: Default::default(),
+                relay_state: Default::default(),
+                quic_state: Default::default(),
             },
+            bandwidth: Default::default(),
         }
     }
 }
@@ -51,8 +56,10 @@ impl P2pNetworkState {
         &mut self,
         action: redux::ActionWithMeta<&P2pNetworkAction>,
         limits: &P2pLimits,
+        timeouts: &P2pTimeouts,
     ) {
         let (action, meta) = action.split();
+        self.bandwidth_reducer(action, meta.time(), limits);
         match action {
             P2pNetworkAction::Scheduler(a) => self.scheduler.reducer(meta.with_action(a)),
             P2pNetworkAction::Pnet(a) => {
@@ -107,7 +114,7 @@ impl P2pNetworkState {
                     return;
                 };
                 let time = meta.time();
-                if let Err(err) = state.reducer(meta.with_action(a), limits) {
+                if let Err(err) = state.reducer(meta.with_action(a), limits, timeouts) {
                     error!(time; "{err}");
                 }
             }
@@ -119,6 +126,102 @@ impl P2pNetworkState {
                     state.reducer(meta.with_action(a), limits)
                 }
             }
+            P2pNetworkAction::NodeStatus(a) => {
+                let time = meta.time();
+                if let Err(err) = self
+                    .scheduler
+                    .node_status_state
+                    .reducer(meta.with_action(a), limits)
+                {
+                    error!(time; "{err}");
+                }
+            }
+            P2pNetworkAction::Ping(a) => {
+                let time = meta.time();
+                if let Err(err) = self.scheduler.ping_state.reducer(meta.with_action(a)) {
+                    error!(time; "{err}");
+                }
+            }
+            P2pNetworkAction::Relay(a) => {
+                let time = meta.time();
+                if let Err(err) = self
+                    .scheduler
+                    .relay_state
+                    .reducer(meta.with_action(a), limits)
+                {
+                    error!(time; "{err}");
+                }
+            }
+            P2pNetworkAction::Quic(a) => {
+                let time = meta.time();
+                let scheduler = &mut self.scheduler;
+                if let Err(err) = scheduler.quic_state.reducer(
+                    &mut scheduler.connections,
+                    scheduler.pnet_key,
+                    meta.with_action(a),
+                ) {
+                    error!(time; "{err}");
+                }
+            }
+        }
+    }
+
+    /// Accounts the stream data and the incoming RPC requests.
+    fn bandwidth_reducer(
+        &mut self,
+        action: &P2pNetworkAction,
+        now: redux::Timestamp,
+        limits: &P2pLimits,
+    ) {
+        match action {
+            P2pNetworkAction::Select(P2pNetworkSelectAction::IncomingData {
+                addr,
+                kind: SelectKind::Stream(peer_id, stream_id),
+                data,
+                ..
+            }) => {
+                let protocol = self.scheduler.stream_protocol(addr, stream_id);
+                self.bandwidth.received(*peer_id, protocol, data.len());
+            }
+            P2pNetworkAction::Yamux(P2pNetworkYamuxAction::OutgoingData {
+                addr,
+                stream_id,
+                data,
+                ..
+            }) => {
+                let Some(peer_id) = self
+                    .scheduler
+                    .connections
+                    .get(addr)
+                    .and_then(|cn| cn.peer_id().copied())
+                else {
+                    return;
+                };
+                let protocol = self.scheduler.stream_protocol(addr, stream_id);
+                self.bandwidth.sent(peer_id, protocol, data.len());
+            }
+            P2pNetworkAction::Rpc(P2pNetworkRpcAction::IncomingMessage {
+                peer_id,
+                message: RpcMessage::Query { .. },
+                ..
+            }) => {
+                self.bandwidth.rpc_request_received(*peer_id, now, limits);
+            }
+            P2pNetworkAction::Scheduler(P2pNetworkSchedulerAction::Prune { addr }) => {
+                let connections = &self.scheduler.connections;
+                let Some(peer_id) = connections.get(addr).and_then(|cn| cn.peer_id()).copied()
+                else {
+                    return;
+                };
+                // the peer can have another connection
+                if !connections
+                    .iter()
+                    .any(|(a, cn)| a != addr && cn.peer_id() == Some(&peer_id))
+                {
+                    self.bandwidth.remove_peer(&peer_id);
+                }
+            }
+            _ => {}
         }
     }
 
@@ -1,5 +1,7 @@
 use std::net::{IpAddr, SocketAddr};
 
+use crate::{PeerId, StreamId};
+
 /// The state machine sends commands to the service.
 pub enum MioCmd {
     /// Bind a new listener to a new socket on the interface
@@ -25,6 +27,27 @@ pub trait P2pMioService: redux::Service {
     fn send_mio_cmd(&mut self, cmd: MioCmd);
 }
 
+/// The state machine sends commands to the QUIC service.
+pub enum QuicCmd {
+    /// Bind the QUIC endpoint to the UDP socket, the endpoint accepts
+    /// incoming connections and is used for the outgoing ones.
+    ListenOn(SocketAddr),
+    /// Create a new outgoing connection, the TLS handshake verifies
+    /// that the remote peer has the expected id.
+    Connect(SocketAddr, PeerId),
+    /// Open a new bidirectional stream with the id chosen by the state machine.
+    OpenStream(SocketAddr, StreamId),
+    /// Send the data in the stream, finish the stream if the flag is set.
+    Send(SocketAddr, StreamId, Box<[u8]>, bool),
+    /// Close the connection.
+    Disconnect(SocketAddr),
+}
+
+pub trait P2pQuicService: redux::Service {
+    fn start_quic(&mut self);
+    fn send_quic_cmd(&mut self, cmd: QuicCmd);
+}
+
 pub trait P2pCryptoService: redux::Service {
     fn generate_random_nonce(&mut self) -> [u8; 24];
 
@@ -5,4 +5,5 @@ use super::*;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pNetworkState {
     pub scheduler: P2pNetworkSchedulerState,
+    pub bandwidth: P2pNetworkBandwidthState,
 }
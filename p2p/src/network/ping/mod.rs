@@ -0,0 +1,9 @@
+mod p2p_network_ping_actions;
+pub use self::p2p_network_ping_actions::*;
+
+mod p2p_network_ping_state;
+pub use self::p2p_network_ping_state::*;
+
+mod p2p_network_ping_reducer;
+
+mod p2p_network_ping_effects;
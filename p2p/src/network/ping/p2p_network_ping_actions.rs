@@ -0,0 +1,100 @@
+use std::{net::SocketAddr, time::Duration};
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{Data, P2pAction, P2pNetworkAction, P2pState, PeerId, StreamId};
+
+/// Ping actions.
+#[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
+#[action_event(fields(display(addr), display(peer_id), stream_id, incoming, debug(rtt)))]
+pub enum P2pNetworkPingAction {
+    /// Creates a new stream state.
+    NewStream {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        incoming: bool,
+    },
+    /// Handles incoming data from the stream.
+    #[action_event(level = trace)]
+    IncomingData {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        data: Data,
+    },
+    /// Sends the received payloads back to the peer.
+    #[action_event(level = trace)]
+    Echo {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        data: Data,
+    },
+    /// Sends a ping to the peer, opening the outgoing stream if needed.
+    Ping { addr: SocketAddr, peer_id: PeerId },
+    /// The peer responded to the ping.
+    ///
+    /// `rtt` is `None` if the response doesn't match the ping.
+    Pong {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        rtt: Option<Duration>,
+    },
+    /// The peer didn't respond to the ping in time.
+    Timeout { peer_id: PeerId },
+    /// Remote peer sent FIN to close the stream.
+    RemoteClose {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+}
+
+impl From<P2pNetworkPingAction> for P2pAction {
+    fn from(value: P2pNetworkPingAction) -> Self {
+        P2pAction::Network(P2pNetworkAction::Ping(value))
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNetworkPingAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        let ping = &state.network.scheduler.ping_state;
+        match self {
+            P2pNetworkPingAction::NewStream {
+                peer_id, stream_id, ..
+            } => ping.find_stream(peer_id, stream_id).is_none(),
+            P2pNetworkPingAction::IncomingData {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkPingAction::Echo {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkPingAction::Pong {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkPingAction::RemoteClose {
+                peer_id, stream_id, ..
+            } => ping.find_stream(peer_id, stream_id).is_some(),
+            P2pNetworkPingAction::Ping { addr, peer_id } => {
+                ping.peers
+                    .get(peer_id)
+                    .map_or(true, |peer| peer.pending.is_none())
+                    && state
+                        .network
+                        .scheduler
+                        .connections
+                        .get(addr)
+                        .map_or(false, |conn| {
+                            conn.peer_id() == Some(peer_id) && conn.closed.is_none()
+                        })
+            }
+            P2pNetworkPingAction::Timeout { peer_id } => ping
+                .peers
+                .get(peer_id)
+                .map_or(false, |peer| peer.pending.is_some()),
+        }
+    }
+}
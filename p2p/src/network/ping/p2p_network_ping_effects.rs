@@ -0,0 +1,156 @@
+use openmina_core::warn;
+use redux::ActionMeta;
+
+use crate::{
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    peer::P2pPeerAction,
+    token, Data, P2pNetworkYamuxAction, YamuxStreamKind,
+};
+
+use super::{P2pNetworkPingAction, PING_PAYLOAD_LEN};
+
+impl P2pNetworkPingAction {
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store) -> Result<(), String>
+    where
+        Store: crate::P2pStore<S>,
+    {
+        let ping = &store.state().network.scheduler.ping_state;
+        match self {
+            P2pNetworkPingAction::NewStream {
+                addr,
+                peer_id,
+                stream_id,
+                incoming: false,
+            } => {
+                if let Some(pending) = ping.peers.get(&peer_id).and_then(|p| p.pending.as_ref()) {
+                    let data = Data::from(pending.payload.to_vec());
+                    store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                        addr,
+                        stream_id,
+                        data,
+                        fin: false,
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkPingAction::IncomingData {
+                addr,
+                peer_id,
+                stream_id,
+                ..
+            } => {
+                let stream = ping
+                    .find_stream(&peer_id, &stream_id)
+                    .ok_or_else(|| format!("ping stream not found for action {self:?}"))?;
+                if stream.buffer.len() < PING_PAYLOAD_LEN {
+                    return Ok(());
+                }
+                if stream.incoming {
+                    let len = stream.buffer.len() / PING_PAYLOAD_LEN * PING_PAYLOAD_LEN;
+                    let data = Data::from(stream.buffer[..len].to_vec());
+                    store.dispatch(P2pNetworkPingAction::Echo {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        data,
+                    });
+                } else {
+                    let rtt = ping
+                        .peers
+                        .get(&peer_id)
+                        .and_then(|peer| peer.pending.as_ref())
+                        .filter(|pending| pending.payload[..] == stream.buffer[..PING_PAYLOAD_LEN])
+                        .map(|pending| meta.time().checked_sub(pending.time).unwrap_or_default());
+                    store.dispatch(P2pNetworkPingAction::Pong {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        rtt,
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkPingAction::Echo {
+                addr,
+                stream_id,
+                data,
+                ..
+            } => {
+                store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                    addr,
+                    stream_id,
+                    data,
+                    fin: false,
+                });
+                Ok(())
+            }
+            P2pNetworkPingAction::Ping { addr, peer_id } => {
+                let payload = ping
+                    .peers
+                    .get(&peer_id)
+                    .and_then(|peer| peer.pending.as_ref())
+                    .map(|pending| pending.payload)
+                    .ok_or_else(|| format!("no pending ping for action {self:?}"))?;
+                if let Some((stream_id, _)) = ping.outgoing_stream(&peer_id) {
+                    let stream_id = *stream_id;
+                    store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                        addr,
+                        stream_id,
+                        data: Data::from(payload.to_vec()),
+                        fin: false,
+                    });
+                    return Ok(());
+                }
+
+                let Some(conn) = store.state().network.scheduler.connections.get(&addr) else {
+                    return Err(format!("connection with {addr} not found"));
+                };
+                let stream_id = conn
+                    .mux
+                    .as_ref()
+                    .and_then(|mux| mux.next_stream_id(YamuxStreamKind::Ping, conn.incoming))
+                    .ok_or_else(|| format!("cannot get next stream for {addr}"))?;
+                if conn.streams.contains_key(&stream_id) {
+                    // the stream is being negotiated, the ping is sent once it is ready
+                    return Ok(());
+                }
+                store.dispatch(P2pNetworkYamuxAction::OpenStream {
+                    addr,
+                    stream_id,
+                    stream_kind: token::StreamKind::Ping(token::PingAlgorithm::Ping1_0_0),
+                });
+                Ok(())
+            }
+            P2pNetworkPingAction::Pong { peer_id, rtt, .. } => {
+                match rtt {
+                    Some(rtt) => {
+                        store.dispatch(P2pPeerAction::RttUpdate { peer_id, rtt });
+                    }
+                    None => {
+                        warn!(meta.time(); summary = "unexpected ping response", peer_id = display(peer_id));
+                    }
+                }
+                Ok(())
+            }
+            P2pNetworkPingAction::Timeout { peer_id } => {
+                store.dispatch(P2pDisconnectionAction::Init {
+                    peer_id,
+                    reason: P2pDisconnectionReason::PingTimeout,
+                });
+                Ok(())
+            }
+            P2pNetworkPingAction::RemoteClose {
+                addr, stream_id, ..
+            } => {
+                store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                    addr,
+                    stream_id,
+                    data: Data(Box::new([])),
+                    fin: true,
+                });
+                Ok(())
+            }
+            P2pNetworkPingAction::NewStream { .. } => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use redux::ActionWithMeta;
+
+use super::{
+    P2pNetworkPingAction, P2pNetworkPingPending, P2pNetworkPingState, P2pNetworkPingStreamState,
+    PING_PAYLOAD_LEN,
+};
+
+impl P2pNetworkPingState {
+    pub fn reducer(&mut self, action: ActionWithMeta<&P2pNetworkPingAction>) -> Result<(), String> {
+        let (action, meta) = action.split();
+        match action {
+            P2pNetworkPingAction::NewStream {
+                addr,
+                peer_id,
+                stream_id,
+                incoming,
+            } => {
+                self.streams
+                    .entry(*peer_id)
+                    .or_default()
+                    .insert(*stream_id, P2pNetworkPingStreamState::new(*addr, *incoming));
+                if !*incoming {
+                    // the time spent opening the stream is not a part of rtt
+                    if let Some(pending) = self
+                        .peers
+                        .get_mut(peer_id)
+                        .and_then(|peer| peer.pending.as_mut())
+                    {
+                        pending.time = meta.time();
+                    }
+                }
+                Ok(())
+            }
+            P2pNetworkPingAction::IncomingData {
+                peer_id,
+                stream_id,
+                data,
+                ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("ping stream not found for action {action:?}"))?;
+                stream.buffer.extend_from_slice(data);
+                Ok(())
+            }
+            P2pNetworkPingAction::Echo {
+                peer_id,
+                stream_id,
+                data,
+                ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("ping stream not found for action {action:?}"))?;
+                stream.buffer.drain(..data.len());
+                Ok(())
+            }
+            P2pNetworkPingAction::Ping { peer_id, .. } => {
+                self.peers.entry(*peer_id).or_default().pending =
+                    Some(P2pNetworkPingPending::new(meta.time()));
+                Ok(())
+            }
+            P2pNetworkPingAction::Pong {
+                peer_id,
+                stream_id,
+                rtt,
+                ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("ping stream not found for action {action:?}"))?;
+                stream.buffer.drain(..PING_PAYLOAD_LEN);
+                if let (Some(rtt), Some(peer)) = (rtt, self.peers.get_mut(peer_id)) {
+                    peer.pending = None;
+                    peer.last_pong = Some(meta.time());
+                    peer.rtt = Some(*rtt);
+                }
+                Ok(())
+            }
+            P2pNetworkPingAction::Timeout { peer_id } => {
+                self.peers.remove(peer_id);
+                Ok(())
+            }
+            P2pNetworkPingAction::RemoteClose {
+                peer_id, stream_id, ..
+            } => self
+                .streams
+                .get_mut(peer_id)
+                .and_then(|streams| streams.remove(stream_id))
+                .map(|_| ())
+                .ok_or_else(|| format!("ping stream not found for action {action:?}")),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::{collections::BTreeMap, net::SocketAddr, time::Duration};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{is_time_passed, network::scheduler::StreamState, P2pTimeouts, PeerId, StreamId};
+
+/// Length of the ping payload, echoed back by the peer.
+pub const PING_PAYLOAD_LEN: usize = 32;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct P2pNetworkPingState {
+    pub streams: StreamState<P2pNetworkPingStreamState>,
+    pub peers: BTreeMap<PeerId, P2pNetworkPingPeerState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P2pNetworkPingStreamState {
+    pub addr: SocketAddr,
+    pub incoming: bool,
+    pub buffer: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct P2pNetworkPingPeerState {
+    /// Ping waiting for the response.
+    pub pending: Option<P2pNetworkPingPending>,
+    /// Time of the latest response.
+    pub last_pong: Option<Timestamp>,
+    /// Latest measured round-trip time.
+    pub rtt: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P2pNetworkPingPending {
+    pub time: Timestamp,
+    pub payload: [u8; PING_PAYLOAD_LEN],
+}
+
+impl P2pNetworkPingState {
+    pub fn find_stream(
+        &self,
+        peer_id: &PeerId,
+        stream_id: &StreamId,
+    ) -> Option<&P2pNetworkPingStreamState> {
+        self.streams.get(peer_id)?.get(stream_id)
+    }
+
+    pub fn find_stream_mut(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: &StreamId,
+    ) -> Option<&mut P2pNetworkPingStreamState> {
+        self.streams.get_mut(peer_id)?.get_mut(stream_id)
+    }
+
+    /// Stream used to send pings to the peer.
+    pub fn outgoing_stream(
+        &self,
+        peer_id: &PeerId,
+    ) -> Option<(&StreamId, &P2pNetworkPingStreamState)> {
+        self.streams
+            .get(peer_id)?
+            .iter()
+            .find(|(_, stream)| !stream.incoming)
+    }
+}
+
+impl P2pNetworkPingStreamState {
+    pub fn new(addr: SocketAddr, incoming: bool) -> Self {
+        Self {
+            addr,
+            incoming,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl P2pNetworkPingPeerState {
+    /// Checks if the peer didn't respond to the ping in time.
+    pub fn is_timed_out(&self, now: Timestamp, timeouts: &P2pTimeouts) -> bool {
+        self.pending.as_ref().map_or(false, |pending| {
+            is_time_passed(now, pending.time, timeouts.ping_timeout)
+        })
+    }
+
+    /// Checks if the next ping should be sent to the peer.
+    pub fn should_ping(&self, now: Timestamp, timeouts: &P2pTimeouts) -> bool {
+        timeouts.ping_interval.is_some()
+            && self.pending.is_none()
+            && self.last_pong.map_or(true, |time| {
+                is_time_passed(now, time, timeouts.ping_interval)
+            })
+    }
+}
+
+impl P2pNetworkPingPending {
+    /// The payload only has to be unique for the peer, so it is derived from
+    /// the time the ping is sent.
+    pub fn new(time: Timestamp) -> Self {
+        let mut payload = [0; PING_PAYLOAD_LEN];
+        for chunk in payload.chunks_mut(8) {
+            chunk.copy_from_slice(&u64::from(time).to_le_bytes());
+        }
+        Self { time, payload }
+    }
+}
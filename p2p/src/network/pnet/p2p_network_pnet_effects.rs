@@ -29,10 +29,15 @@ impl P2pNetworkPnetAction {
             },
             P2pNetworkPnetAction::OutgoingData { addr, .. } => match &state.outgoing {
                 Half::Done { to_send, .. } if !to_send.is_empty() => {
-                    service.send_mio_cmd(crate::MioCmd::Send(
-                        addr,
-                        to_send.clone().into_boxed_slice(),
-                    ));
+                    let data = to_send.clone().into_boxed_slice();
+                    if is_circuit_addr(&addr) {
+                        store.dispatch(P2pNetworkRelayAction::CircuitOutgoingData {
+                            addr,
+                            data: data.into(),
+                        });
+                    } else {
+                        service.send_mio_cmd(crate::MioCmd::Send(addr, data));
+                    }
                 }
                 _ => {}
             },
@@ -41,7 +46,15 @@ impl P2pNetworkPnetAction {
                 nonce,
                 incoming,
             } => {
-                service.send_mio_cmd(crate::MioCmd::Send(addr, nonce.to_vec().into_boxed_slice()));
+                if is_circuit_addr(&addr) {
+                    store.dispatch(P2pNetworkRelayAction::CircuitOutgoingData {
+                        addr,
+                        data: nonce.clone(),
+                    });
+                } else {
+                    service
+                        .send_mio_cmd(crate::MioCmd::Send(addr, nonce.to_vec().into_boxed_slice()));
+                }
                 store.dispatch(P2pNetworkSelectAction::Init {
                     addr,
                     kind: SelectKind::Authentication,
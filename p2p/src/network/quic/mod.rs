@@ -0,0 +1,9 @@
+mod p2p_network_quic_actions;
+pub use self::p2p_network_quic_actions::*;
+
+mod p2p_network_quic_state;
+pub use self::p2p_network_quic_state::*;
+
+mod p2p_network_quic_reducer;
+
+mod p2p_network_quic_effects;
@@ -0,0 +1,144 @@
+use std::net::SocketAddr;
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use super::P2pNetworkQuicConnectionState;
+use crate::{
+    token, Data, P2pAction, P2pNetworkAction, P2pNetworkConnectionMuxState,
+    P2pNetworkConnectionState, P2pState, PeerId, StreamId,
+};
+
+/// QUIC transport actions.
+#[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
+#[action_event(fields(
+    display(listener),
+    display(addr),
+    display(peer_id),
+    stream_id,
+    debug(stream_kind),
+    fin,
+    debug(result),
+    error
+))]
+pub enum P2pNetworkQuicAction {
+    /// Binds the QUIC endpoint to the UDP socket.
+    Listen { listener: SocketAddr },
+    /// The endpoint accepts the incoming connections.
+    ListenerReady { listener: SocketAddr },
+    /// Error binding the endpoint.
+    ListenerError { listener: SocketAddr, error: String },
+    /// Initiates the outgoing connection.
+    OutgoingConnect { addr: SocketAddr, peer_id: PeerId },
+    /// The outgoing connection is established, the TLS handshake
+    /// authenticated the remote peer.
+    OutgoingDidConnect {
+        addr: SocketAddr,
+        result: Result<PeerId, String>,
+    },
+    /// The remote peer connected to us.
+    IncomingDidAccept {
+        addr: SocketAddr,
+        result: Result<PeerId, String>,
+    },
+    /// Opens a new stream, the protocol is negotiated on it with multistream-select.
+    OpenStream {
+        addr: SocketAddr,
+        stream_id: StreamId,
+        stream_kind: token::StreamKind,
+    },
+    /// The remote peer opened a new stream.
+    IncomingStream {
+        addr: SocketAddr,
+        stream_id: StreamId,
+    },
+    /// Handles incoming data from the stream.
+    #[action_event(level = trace)]
+    IncomingData {
+        addr: SocketAddr,
+        stream_id: StreamId,
+        data: Data,
+        fin: bool,
+    },
+    /// Sends the data in the stream.
+    #[action_event(level = trace)]
+    OutgoingData {
+        addr: SocketAddr,
+        stream_id: StreamId,
+        data: Data,
+        fin: bool,
+    },
+    /// The connection is closed by the remote peer or because of an error.
+    ConnectionClosed {
+        addr: SocketAddr,
+        result: Result<(), String>,
+    },
+}
+
+impl P2pNetworkQuicAction {
+    pub fn addr(&self) -> Option<&SocketAddr> {
+        match self {
+            Self::Listen { .. } | Self::ListenerReady { .. } | Self::ListenerError { .. } => None,
+            Self::OutgoingConnect { addr, .. }
+            | Self::OutgoingDidConnect { addr, .. }
+            | Self::IncomingDidAccept { addr, .. }
+            | Self::OpenStream { addr, .. }
+            | Self::IncomingStream { addr, .. }
+            | Self::IncomingData { addr, .. }
+            | Self::OutgoingData { addr, .. }
+            | Self::ConnectionClosed { addr, .. } => Some(addr),
+        }
+    }
+}
+
+impl From<P2pNetworkQuicAction> for P2pAction {
+    fn from(value: P2pNetworkQuicAction) -> Self {
+        P2pAction::Network(P2pNetworkAction::Quic(value))
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNetworkQuicAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        let scheduler = &state.network.scheduler;
+        let connection = |addr: &SocketAddr| {
+            scheduler
+                .connections
+                .get(addr)
+                .filter(|conn| conn.quic && conn.closed.is_none())
+        };
+        let streams = |conn: &P2pNetworkConnectionState| match &conn.mux {
+            Some(P2pNetworkConnectionMuxState::Quic(quic)) => Some(quic),
+            _ => None,
+        };
+        match self {
+            P2pNetworkQuicAction::Listen { .. } => {
+                state.config.quic_port.is_some() && scheduler.quic_state.listener.is_none()
+            }
+            P2pNetworkQuicAction::ListenerReady { .. }
+            | P2pNetworkQuicAction::ListenerError { .. } => scheduler.quic_state.listener.is_some(),
+            P2pNetworkQuicAction::OutgoingConnect { addr, .. } => {
+                !scheduler.connections.contains_key(addr)
+            }
+            P2pNetworkQuicAction::OutgoingDidConnect { addr, .. } => {
+                connection(addr).map_or(false, |conn| !conn.incoming && conn.mux.is_none())
+            }
+            // the connection state is created when the incoming connection is accepted
+            P2pNetworkQuicAction::IncomingDidAccept { .. } => true,
+            P2pNetworkQuicAction::OpenStream { addr, .. }
+            | P2pNetworkQuicAction::IncomingStream { addr, .. } => {
+                connection(addr).and_then(streams).is_some()
+            }
+            P2pNetworkQuicAction::IncomingData {
+                addr, stream_id, ..
+            }
+            | P2pNetworkQuicAction::OutgoingData {
+                addr, stream_id, ..
+            } => connection(addr)
+                .and_then(streams)
+                .map_or(false, |quic: &P2pNetworkQuicConnectionState| {
+                    quic.streams.contains_key(stream_id)
+                }),
+            P2pNetworkQuicAction::ConnectionClosed { addr, .. } => connection(addr).is_some(),
+        }
+    }
+}
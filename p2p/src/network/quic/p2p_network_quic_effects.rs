@@ -0,0 +1,170 @@
+use redux::ActionMeta;
+
+use crate::{
+    connection::incoming::{P2pConnectionIncomingAction, P2pConnectionIncomingState},
+    identify::identify_push,
+    P2pNetworkConnectionError, P2pNetworkSchedulerAction, P2pNetworkSelectAction, P2pQuicService,
+    QuicCmd, SelectKind,
+};
+
+use super::P2pNetworkQuicAction;
+
+impl P2pNetworkQuicAction {
+    pub fn effects<Store, S>(self, _meta: &ActionMeta, store: &mut Store) -> Result<(), String>
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pQuicService,
+    {
+        match self {
+            P2pNetworkQuicAction::Listen { listener } => {
+                store.service().send_quic_cmd(QuicCmd::ListenOn(listener));
+                Ok(())
+            }
+            P2pNetworkQuicAction::ListenerReady { .. } => {
+                identify_push(store);
+                Ok(())
+            }
+            P2pNetworkQuicAction::ListenerError { listener, error } => {
+                Err(format!("error listening on {listener}: {error}"))
+            }
+            P2pNetworkQuicAction::OutgoingConnect { addr, peer_id } => {
+                store
+                    .service()
+                    .send_quic_cmd(QuicCmd::Connect(addr, peer_id));
+                Ok(())
+            }
+            P2pNetworkQuicAction::OutgoingDidConnect { addr, result } => {
+                match result {
+                    Ok(peer_id) => {
+                        store.dispatch(P2pNetworkSchedulerAction::YamuxDidInit { addr, peer_id });
+                    }
+                    Err(error) => {
+                        store.dispatch(P2pNetworkSchedulerAction::Error {
+                            addr,
+                            error: P2pNetworkConnectionError::QuicError(error),
+                        });
+                    }
+                }
+                Ok(())
+            }
+            P2pNetworkQuicAction::IncomingDidAccept { addr, result } => {
+                let Ok(peer_id) = result else {
+                    // the connection state is not created for failed handshakes
+                    return Ok(());
+                };
+                let state = store.state();
+                let is_ours = state
+                    .network
+                    .scheduler
+                    .connections
+                    .get(&addr)
+                    .map_or(false, |conn| conn.quic && conn.peer_id() == Some(&peer_id));
+                if !is_ours {
+                    store.service().send_quic_cmd(QuicCmd::Disconnect(addr));
+                    return Err(format!(
+                        "address {addr} is already used by another connection"
+                    ));
+                }
+                if state.network.scheduler.connections.len() > state.config.limits.max_connections()
+                {
+                    store.dispatch(P2pNetworkSchedulerAction::Error {
+                        addr,
+                        error: P2pNetworkConnectionError::QuicError(
+                            "too many connections".to_owned(),
+                        ),
+                    });
+                    return Ok(());
+                }
+
+                store
+                    .dispatch(P2pConnectionIncomingAction::FinalizePendingLibp2p { peer_id, addr });
+                // check that peer management decide to accept this connection
+                let this_connection_is_kept = store
+                    .state()
+                    .peers
+                    .get(&peer_id)
+                    .and_then(|peer_state| peer_state.status.as_connecting())
+                    .and_then(|connecting| connecting.as_incoming())
+                    .map_or(false, |incoming| matches!(incoming, P2pConnectionIncomingState::FinalizePendingLibp2p { addr: a, .. } if a == &addr));
+                if this_connection_is_kept {
+                    store.dispatch(P2pNetworkSchedulerAction::YamuxDidInit { addr, peer_id });
+                }
+                Ok(())
+            }
+            P2pNetworkQuicAction::OpenStream {
+                addr, stream_id, ..
+            } => {
+                let peer_id = peer_id(store, &addr)?;
+                store
+                    .service()
+                    .send_quic_cmd(QuicCmd::OpenStream(addr, stream_id));
+                store.dispatch(P2pNetworkSelectAction::Init {
+                    addr,
+                    kind: SelectKind::Stream(peer_id, stream_id),
+                    incoming: false,
+                    send_handshake: true,
+                });
+                Ok(())
+            }
+            P2pNetworkQuicAction::IncomingStream { addr, stream_id } => {
+                let peer_id = peer_id(store, &addr)?;
+                store.dispatch(P2pNetworkSelectAction::Init {
+                    addr,
+                    kind: SelectKind::Stream(peer_id, stream_id),
+                    incoming: true,
+                    send_handshake: true,
+                });
+                Ok(())
+            }
+            P2pNetworkQuicAction::IncomingData {
+                addr,
+                stream_id,
+                data,
+                fin,
+            } => {
+                let peer_id = peer_id(store, &addr)?;
+                store.dispatch(P2pNetworkSelectAction::IncomingData {
+                    addr,
+                    kind: SelectKind::Stream(peer_id, stream_id),
+                    data,
+                    fin,
+                });
+                Ok(())
+            }
+            P2pNetworkQuicAction::OutgoingData {
+                addr,
+                stream_id,
+                data,
+                fin,
+            } => {
+                store
+                    .service()
+                    .send_quic_cmd(QuicCmd::Send(addr, stream_id, data.0, fin));
+                Ok(())
+            }
+            P2pNetworkQuicAction::ConnectionClosed { addr, result } => {
+                let error = match result {
+                    Ok(()) => P2pNetworkConnectionError::RemoteClosed,
+                    Err(error) => P2pNetworkConnectionError::QuicError(error),
+                };
+                store.dispatch(P2pNetworkSchedulerAction::Error { addr, error });
+                Ok(())
+            }
+        }
+    }
+}
+
+fn peer_id<Store, S>(store: &Store, addr: &std::net::SocketAddr) -> Result<crate::PeerId, String>
+where
+    Store: crate::P2pStore<S>,
+{
+    store
+        .state()
+        .network
+        .scheduler
+        .connections
+        .get(addr)
+        .and_then(|conn| conn.peer_id())
+        .copied()
+        .ok_or_else(|| format!("quic connection {addr} is not authenticated"))
+}
@@ -0,0 +1,163 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    net::SocketAddr,
+};
+
+use redux::ActionWithMeta;
+
+use super::{
+    P2pNetworkQuicAction, P2pNetworkQuicConnectionState, P2pNetworkQuicListenerState,
+    P2pNetworkQuicState, P2pNetworkQuicStreamState,
+};
+use crate::{
+    P2pNetworkAuthState, P2pNetworkConnectionMuxState, P2pNetworkConnectionState,
+    P2pNetworkPnetState, P2pNetworkSelectState, P2pNetworkStreamState, PeerId,
+};
+
+impl P2pNetworkQuicState {
+    pub fn reducer(
+        &mut self,
+        connections: &mut BTreeMap<SocketAddr, P2pNetworkConnectionState>,
+        pnet_key: [u8; 32],
+        action: ActionWithMeta<&P2pNetworkQuicAction>,
+    ) -> Result<(), String> {
+        let (action, _meta) = action.split();
+        match action {
+            P2pNetworkQuicAction::Listen { listener } => {
+                self.listener = Some(P2pNetworkQuicListenerState::Pending { addr: *listener });
+                Ok(())
+            }
+            P2pNetworkQuicAction::ListenerReady { listener } => {
+                self.listener = Some(P2pNetworkQuicListenerState::Ready { addr: *listener });
+                Ok(())
+            }
+            P2pNetworkQuicAction::ListenerError { listener, error } => {
+                self.listener = Some(P2pNetworkQuicListenerState::Error {
+                    addr: *listener,
+                    error: error.clone(),
+                });
+                Ok(())
+            }
+            P2pNetworkQuicAction::OutgoingConnect { addr, .. } => {
+                connections.insert(*addr, new_connection(false, pnet_key));
+                Ok(())
+            }
+            P2pNetworkQuicAction::OutgoingDidConnect { addr, result } => {
+                let Ok(peer_id) = result else {
+                    return Ok(());
+                };
+                let conn = connections
+                    .get_mut(addr)
+                    .ok_or_else(|| format!("quic connection {addr} not found"))?;
+                authenticated(conn, *peer_id);
+                Ok(())
+            }
+            P2pNetworkQuicAction::IncomingDidAccept { addr, result } => {
+                let Ok(peer_id) = result else {
+                    return Ok(());
+                };
+                // the address might be occupied by a TCP connection,
+                // effects will close the QUIC one in that case
+                if let Entry::Vacant(entry) = connections.entry(*addr) {
+                    authenticated(entry.insert(new_connection(true, pnet_key)), *peer_id);
+                }
+                Ok(())
+            }
+            P2pNetworkQuicAction::OpenStream {
+                addr,
+                stream_id,
+                stream_kind,
+            } => {
+                let (conn, quic) = find_connection(connections, addr)?;
+                quic.streams
+                    .insert(*stream_id, P2pNetworkQuicStreamState::default());
+                conn.insert(*stream_id, P2pNetworkStreamState::new(*stream_kind));
+                Ok(())
+            }
+            P2pNetworkQuicAction::IncomingStream { addr, stream_id } => {
+                let (conn, quic) = find_connection(connections, addr)?;
+                quic.streams.insert(
+                    *stream_id,
+                    P2pNetworkQuicStreamState {
+                        incoming: true,
+                        ..Default::default()
+                    },
+                );
+                conn.insert(*stream_id, P2pNetworkStreamState::new_incoming());
+                Ok(())
+            }
+            P2pNetworkQuicAction::IncomingData {
+                addr,
+                stream_id,
+                fin,
+                ..
+            } => {
+                if !fin {
+                    return Ok(());
+                }
+                let (_, quic) = find_connection(connections, addr)?;
+                if let Some(stream) = quic.streams.get_mut(stream_id) {
+                    stream.fin_received = true;
+                }
+                quic.stream_finished(*stream_id);
+                Ok(())
+            }
+            P2pNetworkQuicAction::OutgoingData {
+                addr,
+                stream_id,
+                fin,
+                ..
+            } => {
+                if !fin {
+                    return Ok(());
+                }
+                let (streams, quic) = find_connection(connections, addr)?;
+                // the same as yamux does, the stream is not used after we finish it
+                streams.remove(stream_id);
+                if let Some(stream) = quic.streams.get_mut(stream_id) {
+                    stream.fin_sent = true;
+                }
+                quic.stream_finished(*stream_id);
+                Ok(())
+            }
+            P2pNetworkQuicAction::ConnectionClosed { .. } => Ok(()),
+        }
+    }
+}
+
+fn new_connection(incoming: bool, pnet_key: [u8; 32]) -> P2pNetworkConnectionState {
+    P2pNetworkConnectionState {
+        incoming,
+        quic: true,
+        pnet: P2pNetworkPnetState::new(pnet_key),
+        select_auth: P2pNetworkSelectState::default(),
+        auth: None,
+        select_mux: P2pNetworkSelectState::default(),
+        mux: None,
+        streams: BTreeMap::default(),
+        closed: None,
+        limit: P2pNetworkConnectionState::INITIAL_LIMIT,
+    }
+}
+
+fn authenticated(conn: &mut P2pNetworkConnectionState, peer_id: PeerId) {
+    conn.auth = Some(P2pNetworkAuthState::Tls(peer_id));
+    conn.mux = Some(P2pNetworkConnectionMuxState::Quic(
+        P2pNetworkQuicConnectionState::default(),
+    ));
+}
+
+type Streams = BTreeMap<crate::StreamId, P2pNetworkStreamState>;
+
+fn find_connection<'a>(
+    connections: &'a mut BTreeMap<SocketAddr, P2pNetworkConnectionState>,
+    addr: &SocketAddr,
+) -> Result<(&'a mut Streams, &'a mut P2pNetworkQuicConnectionState), String> {
+    let conn = connections
+        .get_mut(addr)
+        .ok_or_else(|| format!("quic connection {addr} not found"))?;
+    match &mut conn.mux {
+        Some(P2pNetworkConnectionMuxState::Quic(quic)) => Ok((&mut conn.streams, quic)),
+        _ => Err(format!("connection {addr} is not a quic connection")),
+    }
+}
@@ -0,0 +1,76 @@
+use std::{collections::BTreeMap, net::SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{StreamId, YamuxStreamKind, YAMUX_DYNAMIC_STREAM_ID};
+
+/// State of the local QUIC endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNetworkQuicState {
+    pub listener: Option<P2pNetworkQuicListenerState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pNetworkQuicListenerState {
+    Pending { addr: SocketAddr },
+    Ready { addr: SocketAddr },
+    Error { addr: SocketAddr, error: String },
+}
+
+impl P2pNetworkQuicState {
+    /// Local address where the endpoint accepts the connections.
+    pub fn listener(&self) -> Option<SocketAddr> {
+        match &self.listener {
+            Some(P2pNetworkQuicListenerState::Ready { addr }) => Some(*addr),
+            _ => None,
+        }
+    }
+}
+
+/// QUIC connection is authenticated with TLS 1.3 and provides native streams,
+/// so it replaces pnet, noise and yamux of the TCP connection.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNetworkQuicConnectionState {
+    pub streams: BTreeMap<StreamId, P2pNetworkQuicStreamState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pNetworkQuicStreamState {
+    pub incoming: bool,
+    pub fin_sent: bool,
+    pub fin_received: bool,
+}
+
+impl P2pNetworkQuicConnectionState {
+    /// Streams use the same IDs as yamux ones, so the protocols running
+    /// on top of them don't depend on the transport.
+    pub fn next_stream_id(&self, kind: YamuxStreamKind, incoming: bool) -> Option<StreamId> {
+        Some(kind.stream_id(incoming))
+    }
+
+    pub fn next_dynamic_stream_id(&self, incoming: bool) -> Option<StreamId> {
+        // dialer uses odd IDs, listener uses even ones
+        let first = YAMUX_DYNAMIC_STREAM_ID + 1 + (incoming as StreamId);
+        (first..)
+            .step_by(2)
+            .find(|stream_id| !self.streams.contains_key(stream_id))
+    }
+
+    /// QUIC doesn't send our stream IDs over the wire, so the streams
+    /// opened by the remote peer are numbered locally, with the parity
+    /// of the remote side, so they never clash with the ones we open.
+    pub fn remote_stream_id(incoming: bool, index: u64) -> Option<StreamId> {
+        let first = u64::from(YAMUX_DYNAMIC_STREAM_ID) + 1 + u64::from(!incoming);
+        StreamId::try_from(first + 2 * index).ok()
+    }
+
+    pub(super) fn stream_finished(&mut self, stream_id: StreamId) {
+        if self
+            .streams
+            .get(&stream_id)
+            .map_or(false, |stream| stream.fin_sent && stream.fin_received)
+        {
+            self.streams.remove(&stream_id);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+mod pb {
+    pub mod circuit {
+        include!(concat!(env!("OUT_DIR"), "/circuit.rs"));
+    }
+    pub mod holepunch {
+        include!(concat!(env!("OUT_DIR"), "/holepunch.rs"));
+    }
+}
+
+mod p2p_network_relay_message;
+pub use self::p2p_network_relay_message::*;
+
+mod p2p_network_relay_actions;
+pub use self::p2p_network_relay_actions::*;
+
+mod p2p_network_relay_state;
+pub use self::p2p_network_relay_state::*;
+
+mod p2p_network_relay_reducer;
+
+mod p2p_network_relay_effects;
@@ -0,0 +1,263 @@
+use std::{net::SocketAddr, time::Duration};
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    is_circuit_addr, P2pNetworkRelayMessage, P2pNetworkRelayProtocol, P2pNetworkRelayStatus,
+};
+use crate::{Data, P2pAction, P2pNetworkAction, P2pState, PeerId, StreamId};
+
+/// Circuit relay v2 and DCUtR actions.
+#[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
+#[action_event(fields(
+    display(addr),
+    display(peer_id),
+    stream_id,
+    display(relay_peer_id),
+    incoming
+))]
+pub enum P2pNetworkRelayAction {
+    /// Creates a new stream state.
+    NewStream {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        protocol: P2pNetworkRelayProtocol,
+        incoming: bool,
+    },
+    /// Handles incoming data from the stream.
+    #[action_event(level = trace)]
+    IncomingData {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        data: Data,
+    },
+    /// Handles a message decoded from the stream.
+    IncomingMessage {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        message: P2pNetworkRelayMessage,
+    },
+    /// Sends the message to the stream.
+    OutgoingMessage {
+        addr: SocketAddr,
+        stream_id: StreamId,
+        message: P2pNetworkRelayMessage,
+    },
+    /// Remote peer sent FIN to close the stream.
+    RemoteClose {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+    /// Sends FIN to close the stream, the stream spliced with it and the
+    /// relayed connection carried by it are closed as well.
+    Close {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+    /// Removes the closed stream.
+    Prune {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+    /// Closes the streams of the closed connection, or the stream carrying
+    /// it if the connection is relayed.
+    ConnectionClosed { addr: SocketAddr },
+
+    /// Reserves a slot on the relay, so other peers can connect to us through it.
+    Reserve { relay_peer_id: PeerId },
+    /// The relay accepted the reservation.
+    ReserveSuccess {
+        relay_peer_id: PeerId,
+        /// Unix time when the reservation expires, in seconds.
+        expire: u64,
+    },
+    /// The relay refused the reservation.
+    ReserveError {
+        relay_peer_id: PeerId,
+        error: P2pNetworkRelayStatus,
+    },
+    /// Connects to the peer through the relay.
+    Connect {
+        relay_peer_id: PeerId,
+        peer_id: PeerId,
+    },
+    /// The relay failed to connect to the peer.
+    ConnectError {
+        relay_peer_id: PeerId,
+        peer_id: PeerId,
+        error: P2pNetworkRelayStatus,
+    },
+    /// The stream becomes a relayed connection to the remote peer.
+    CircuitOpen {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        remote_peer_id: PeerId,
+        incoming: bool,
+    },
+    /// Sends data of the relayed connection through the relay.
+    #[action_event(level = trace)]
+    CircuitOutgoingData { addr: SocketAddr, data: Data },
+
+    /// Relay: grants the reservation to the peer.
+    HopReserve {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+    /// Relay: asks the destination peer to accept the circuit.
+    HopConnect {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        dst: PeerId,
+    },
+    /// Relay: the destination peer accepted the circuit, streams are spliced
+    /// and the data is forwarded between them.
+    HopSplice {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+
+    /// DCUtR: closes the relayed connection to the peer, so it can be
+    /// replaced with the direct one. The initiator dials the peer's address
+    /// after the half of the round-trip time, when the peer is expected to
+    /// close the relayed connection too.
+    #[action_event(fields(display(peer_id), debug(addr), debug(rtt)))]
+    DcutrUpgrade {
+        peer_id: PeerId,
+        addr: Option<SocketAddr>,
+        rtt: Duration,
+    },
+    /// DCUtR: dials the peer directly.
+    DcutrDial { peer_id: PeerId, addr: SocketAddr },
+}
+
+impl From<P2pNetworkRelayAction> for P2pAction {
+    fn from(value: P2pNetworkRelayAction) -> Self {
+        P2pAction::Network(P2pNetworkAction::Relay(value))
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNetworkRelayAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        let scheduler = &state.network.scheduler;
+        let relay = &scheduler.relay_state;
+        let has_yamux = |peer_id: &PeerId| {
+            scheduler.find_peer(peer_id).map_or(false, |(_, conn)| {
+                conn.closed.is_none() && conn.mux.as_ref().map_or(false, |mux| mux.is_ready())
+            })
+        };
+        match self {
+            P2pNetworkRelayAction::NewStream {
+                peer_id, stream_id, ..
+            } => relay.find_stream(peer_id, stream_id).is_none(),
+            P2pNetworkRelayAction::IncomingData {
+                peer_id, stream_id, ..
+            }
+            | P2pNetworkRelayAction::RemoteClose {
+                peer_id, stream_id, ..
+            } => relay
+                .find_stream(peer_id, stream_id)
+                .map_or(false, |stream| !stream.closed),
+            P2pNetworkRelayAction::IncomingMessage {
+                peer_id, stream_id, ..
+            } => relay
+                .find_stream(peer_id, stream_id)
+                .map_or(false, |stream| !stream.closed && stream.message.is_some()),
+            P2pNetworkRelayAction::OutgoingMessage { .. } => true,
+            P2pNetworkRelayAction::Close {
+                peer_id, stream_id, ..
+            } => relay
+                .find_stream(peer_id, stream_id)
+                .map_or(false, |stream| !stream.closed),
+            P2pNetworkRelayAction::Prune {
+                peer_id, stream_id, ..
+            } => relay
+                .find_stream(peer_id, stream_id)
+                .map_or(false, |stream| stream.closed),
+            P2pNetworkRelayAction::ConnectionClosed { .. } => true,
+            P2pNetworkRelayAction::Reserve { relay_peer_id } => {
+                has_yamux(relay_peer_id)
+                    && relay.should_reserve(relay_peer_id, time)
+                    && !relay.queued.get(relay_peer_id).map_or(false, |queued| {
+                        queued.contains(&super::P2pNetworkRelayRequest::Reserve)
+                    })
+            }
+            P2pNetworkRelayAction::ReserveSuccess { relay_peer_id, .. }
+            | P2pNetworkRelayAction::ReserveError { relay_peer_id, .. } => {
+                relay.reservations.contains_key(relay_peer_id)
+            }
+            P2pNetworkRelayAction::Connect {
+                relay_peer_id,
+                peer_id,
+            } => {
+                has_yamux(relay_peer_id)
+                    && peer_id != relay_peer_id
+                    && peer_id != &state.my_id()
+                    && relay.circuit_to_peer(peer_id).is_none()
+                    && state
+                        .peers
+                        .get(peer_id)
+                        .map_or(true, |peer| !peer.status.is_connected_or_connecting())
+            }
+            P2pNetworkRelayAction::ConnectError { .. } => true,
+            P2pNetworkRelayAction::CircuitOpen {
+                peer_id, stream_id, ..
+            } => relay
+                .find_stream(peer_id, stream_id)
+                .map_or(false, |stream| !stream.closed && !stream.is_passthrough()),
+            P2pNetworkRelayAction::CircuitOutgoingData { addr, .. } => {
+                relay.circuits.contains_key(addr)
+            }
+            P2pNetworkRelayAction::HopReserve {
+                peer_id, stream_id, ..
+            } => {
+                state.config.relay_hop
+                    && relay.find_stream(peer_id, stream_id).is_some()
+                    && (relay.reserved.contains_key(peer_id)
+                        || relay.reserved.len() < state.config.limits.relay_reservations())
+            }
+            P2pNetworkRelayAction::HopConnect {
+                peer_id,
+                stream_id,
+                dst,
+                ..
+            } => {
+                state.config.relay_hop
+                    && relay.find_stream(peer_id, stream_id).is_some()
+                    && relay.is_reserved(dst, time)
+                    && has_yamux(dst)
+                    && relay.relayed_circuits() < state.config.limits.relay_circuits()
+            }
+            P2pNetworkRelayAction::HopSplice {
+                peer_id, stream_id, ..
+            } => relay.find_stream(peer_id, stream_id).map_or(false, |stream| {
+                matches!(
+                    &stream.status,
+                    super::P2pNetworkRelayStreamStatus::Request(
+                        super::P2pNetworkRelayRequest::Stop { peer_id, stream_id, .. }
+                    ) if relay.find_stream(peer_id, stream_id).map_or(false, |source| !source.closed)
+                )
+            }),
+            P2pNetworkRelayAction::DcutrUpgrade { peer_id, .. } => scheduler
+                .find_peer(peer_id)
+                .map_or(false, |(addr, _)| is_circuit_addr(addr)),
+            P2pNetworkRelayAction::DcutrDial { peer_id, addr } => {
+                relay.upgrades.get(peer_id).map_or(false, |upgrade| {
+                    &upgrade.addr == addr
+                        && upgrade.time <= time
+                        && scheduler.find_peer(peer_id).is_none()
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,791 @@
+use std::net::SocketAddr;
+
+use multiaddr::{Multiaddr, Protocol};
+use openmina_core::warn;
+use redux::ActionMeta;
+
+use crate::{
+    connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingInitOpts},
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    nat::is_public_ip,
+    network::identify::stream::get_addrs,
+    Data, P2pNetworkConnectionError, P2pNetworkSchedulerAction, P2pNetworkService,
+    P2pNetworkYamuxAction, PeerId,
+};
+
+use super::{
+    is_circuit_ip, P2pNetworkRelayAction, P2pNetworkRelayLimit, P2pNetworkRelayMessage,
+    P2pNetworkRelayProtocol, P2pNetworkRelayRequest, P2pNetworkRelayStatus,
+    P2pNetworkRelayStreamStatus, RELAY_CIRCUIT_DATA, RELAY_CIRCUIT_DURATION,
+};
+
+impl P2pNetworkRelayAction {
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store) -> Result<(), String>
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pNetworkService,
+    {
+        let relay = &store.state().network.scheduler.relay_state;
+        match self {
+            P2pNetworkRelayAction::NewStream {
+                addr,
+                peer_id,
+                stream_id,
+                incoming: false,
+                ..
+            } => {
+                let stream = relay
+                    .find_stream(&peer_id, &stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {self:?}"))?;
+                let message = match stream.status.clone() {
+                    P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Reserve) => {
+                        P2pNetworkRelayMessage::Reserve
+                    }
+                    P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Connect {
+                        peer_id,
+                    }) => P2pNetworkRelayMessage::Connect { peer_id },
+                    P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Stop {
+                        peer_id,
+                        ..
+                    }) => P2pNetworkRelayMessage::StopConnect {
+                        peer_id,
+                        limit: Some(circuit_limit()),
+                    },
+                    P2pNetworkRelayStreamStatus::DcutrConnect { .. } => {
+                        P2pNetworkRelayMessage::HolePunchConnect {
+                            addrs: local_addrs(store),
+                        }
+                    }
+                    _ => {
+                        // the request is already served by another stream
+                        store.dispatch(P2pNetworkRelayAction::Close {
+                            addr,
+                            peer_id,
+                            stream_id,
+                        });
+                        return Ok(());
+                    }
+                };
+                store.dispatch(P2pNetworkRelayAction::OutgoingMessage {
+                    addr,
+                    stream_id,
+                    message,
+                });
+                Ok(())
+            }
+            P2pNetworkRelayAction::NewStream { .. } => Ok(()),
+            P2pNetworkRelayAction::IncomingData {
+                addr,
+                peer_id,
+                stream_id,
+                data,
+            } => {
+                let stream = relay
+                    .find_stream(&peer_id, &stream_id)
+                    .ok_or_else(|| format!("relay stream {stream_id} of {peer_id} not found"))?;
+                if let Some(err) = stream.error.clone() {
+                    warn!(meta.time(); summary = "error reading relay stream", error = display(err), peer_id = display(peer_id));
+                    store.dispatch(P2pNetworkRelayAction::Close {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    });
+                    return Ok(());
+                }
+                let message = stream.message.clone();
+                match stream.status.clone() {
+                    P2pNetworkRelayStreamStatus::Spliced {
+                        addr: dst_addr,
+                        stream_id: dst_stream_id,
+                        data: relayed,
+                        ..
+                    } => {
+                        if relayed > RELAY_CIRCUIT_DATA {
+                            store.dispatch(P2pNetworkRelayAction::Close {
+                                addr,
+                                peer_id,
+                                stream_id,
+                            });
+                        } else {
+                            store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                                addr: dst_addr,
+                                stream_id: dst_stream_id,
+                                data,
+                                fin: false,
+                            });
+                        }
+                    }
+                    P2pNetworkRelayStreamStatus::Circuit { addr } => {
+                        store.dispatch(P2pNetworkSchedulerAction::IncomingDataDidReceive {
+                            addr,
+                            result: Ok(data),
+                        });
+                    }
+                    _ => {
+                        if let Some(message) = message {
+                            store.dispatch(P2pNetworkRelayAction::IncomingMessage {
+                                addr,
+                                peer_id,
+                                stream_id,
+                                message,
+                            });
+                        }
+                    }
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::IncomingMessage {
+                addr,
+                peer_id,
+                stream_id,
+                message,
+            } => {
+                let stream = relay
+                    .find_stream(&peer_id, &stream_id)
+                    .ok_or_else(|| format!("relay stream {stream_id} of {peer_id} not found"))?;
+                let status = stream.status.clone();
+                let reply = |store: &mut Store, message: P2pNetworkRelayMessage| {
+                    store.dispatch(P2pNetworkRelayAction::OutgoingMessage {
+                        addr,
+                        stream_id,
+                        message,
+                    });
+                };
+                let close = |store: &mut Store| {
+                    store.dispatch(P2pNetworkRelayAction::Close {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    });
+                };
+
+                match (status, message) {
+                    (P2pNetworkRelayStreamStatus::Idle, P2pNetworkRelayMessage::Reserve) => {
+                        if !store.dispatch(P2pNetworkRelayAction::HopReserve {
+                            addr,
+                            peer_id,
+                            stream_id,
+                        }) {
+                            let status = if store.state().config.relay_hop {
+                                P2pNetworkRelayStatus::ReservationRefused
+                            } else {
+                                P2pNetworkRelayStatus::PermissionDenied
+                            };
+                            reply(store, P2pNetworkRelayMessage::hop_status(status));
+                            close(store);
+                        }
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::Idle,
+                        P2pNetworkRelayMessage::Connect { peer_id: dst },
+                    ) => {
+                        let state = store.state();
+                        let relay = &state.network.scheduler.relay_state;
+                        let status = if !state.config.relay_hop {
+                            Some(P2pNetworkRelayStatus::PermissionDenied)
+                        } else if !relay.is_reserved(&dst, meta.time())
+                            || state.network.scheduler.find_peer(&dst).is_none()
+                        {
+                            Some(P2pNetworkRelayStatus::NoReservation)
+                        } else if relay.relayed_circuits() >= state.config.limits.relay_circuits() {
+                            Some(P2pNetworkRelayStatus::ResourceLimitExceeded)
+                        } else if !store.dispatch(P2pNetworkRelayAction::HopConnect {
+                            addr,
+                            peer_id,
+                            stream_id,
+                            dst,
+                        }) {
+                            Some(P2pNetworkRelayStatus::ConnectionFailed)
+                        } else {
+                            None
+                        };
+                        if let Some(status) = status {
+                            reply(store, P2pNetworkRelayMessage::hop_status(status));
+                            close(store);
+                        }
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::Idle,
+                        P2pNetworkRelayMessage::StopConnect { peer_id: src, .. },
+                    ) => {
+                        let state = store.state();
+                        let accept = state.network.scheduler.connections.len()
+                            < state.config.limits.max_connections()
+                            && src != state.my_id()
+                            && state
+                                .peers
+                                .get(&src)
+                                .map_or(true, |peer| !peer.status.is_connected_or_connecting());
+                        if accept {
+                            reply(
+                                store,
+                                P2pNetworkRelayMessage::StopStatus {
+                                    status: P2pNetworkRelayStatus::Ok,
+                                },
+                            );
+                            store.dispatch(P2pNetworkRelayAction::CircuitOpen {
+                                addr,
+                                peer_id,
+                                stream_id,
+                                remote_peer_id: src,
+                                incoming: true,
+                            });
+                        } else {
+                            reply(
+                                store,
+                                P2pNetworkRelayMessage::StopStatus {
+                                    status: P2pNetworkRelayStatus::ResourceLimitExceeded,
+                                },
+                            );
+                            close(store);
+                        }
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Reserve),
+                        P2pNetworkRelayMessage::HopStatus { status, expire, .. },
+                    ) => {
+                        match (status, expire) {
+                            (P2pNetworkRelayStatus::Ok, Some(expire)) => {
+                                store.dispatch(P2pNetworkRelayAction::ReserveSuccess {
+                                    relay_peer_id: peer_id,
+                                    expire,
+                                });
+                            }
+                            (P2pNetworkRelayStatus::Ok, None) => {
+                                store.dispatch(P2pNetworkRelayAction::ReserveError {
+                                    relay_peer_id: peer_id,
+                                    error: P2pNetworkRelayStatus::MalformedMessage,
+                                });
+                            }
+                            (error, _) => {
+                                store.dispatch(P2pNetworkRelayAction::ReserveError {
+                                    relay_peer_id: peer_id,
+                                    error,
+                                });
+                            }
+                        }
+                        close(store);
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Connect {
+                            peer_id: remote_peer_id,
+                        }),
+                        P2pNetworkRelayMessage::HopStatus { status, .. },
+                    ) => {
+                        if status == P2pNetworkRelayStatus::Ok {
+                            store.dispatch(P2pNetworkRelayAction::CircuitOpen {
+                                addr,
+                                peer_id,
+                                stream_id,
+                                remote_peer_id,
+                                incoming: false,
+                            });
+                        } else {
+                            store.dispatch(P2pNetworkRelayAction::ConnectError {
+                                relay_peer_id: peer_id,
+                                peer_id: remote_peer_id,
+                                error: status,
+                            });
+                            close(store);
+                        }
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Stop {
+                            ..
+                        }),
+                        P2pNetworkRelayMessage::StopStatus { status },
+                    ) => {
+                        if status != P2pNetworkRelayStatus::Ok
+                            || !store.dispatch(P2pNetworkRelayAction::HopSplice {
+                                addr,
+                                peer_id,
+                                stream_id,
+                            })
+                        {
+                            // the source is notified when the stop stream is closed
+                            close(store);
+                        }
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::DcutrSync,
+                        P2pNetworkRelayMessage::HolePunchConnect { .. },
+                    ) => {
+                        let addrs = local_addrs(store);
+                        reply(store, P2pNetworkRelayMessage::HolePunchConnect { addrs });
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::DcutrConnect { time },
+                        P2pNetworkRelayMessage::HolePunchConnect { addrs },
+                    ) => {
+                        reply(store, P2pNetworkRelayMessage::HolePunchSync);
+                        close(store);
+                        store.dispatch(P2pNetworkRelayAction::DcutrUpgrade {
+                            peer_id,
+                            addr: direct_addr(&addrs),
+                            rtt: meta.time().checked_sub(time).unwrap_or_default(),
+                        });
+                    }
+                    (
+                        P2pNetworkRelayStreamStatus::DcutrSync,
+                        P2pNetworkRelayMessage::HolePunchSync,
+                    ) => {
+                        close(store);
+                        store.dispatch(P2pNetworkRelayAction::DcutrUpgrade {
+                            peer_id,
+                            addr: None,
+                            rtt: Default::default(),
+                        });
+                    }
+                    (_, message) => {
+                        warn!(meta.time(); summary = "unexpected relay message", message = debug(&message), peer_id = display(peer_id));
+                        if message.protocol() != P2pNetworkRelayProtocol::Dcutr {
+                            reply(
+                                store,
+                                P2pNetworkRelayMessage::hop_status(
+                                    P2pNetworkRelayStatus::UnexpectedMessage,
+                                ),
+                            );
+                        }
+                        close(store);
+                    }
+                }
+
+                // the data received along with the message might contain the next one
+                let next = store
+                    .state()
+                    .network
+                    .scheduler
+                    .relay_state
+                    .find_stream(&peer_id, &stream_id)
+                    .filter(|stream| !stream.closed)
+                    .and_then(|stream| stream.message.clone());
+                if let Some(message) = next {
+                    store.dispatch(P2pNetworkRelayAction::IncomingMessage {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        message,
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::OutgoingMessage {
+                addr,
+                stream_id,
+                message,
+            } => {
+                let data = message.encode_length_delimited()?;
+                store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                    addr,
+                    stream_id,
+                    data: Data::from(data),
+                    fin: false,
+                });
+                Ok(())
+            }
+            P2pNetworkRelayAction::RemoteClose {
+                addr,
+                peer_id,
+                stream_id,
+            } => {
+                store.dispatch(P2pNetworkRelayAction::Close {
+                    addr,
+                    peer_id,
+                    stream_id,
+                });
+                Ok(())
+            }
+            P2pNetworkRelayAction::Close {
+                addr,
+                peer_id,
+                stream_id,
+            } => {
+                let stream = relay
+                    .find_stream(&peer_id, &stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {self:?}"))?;
+                let status = stream.status.clone();
+                if store
+                    .state()
+                    .network
+                    .scheduler
+                    .connections
+                    .contains_key(&addr)
+                {
+                    store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                        addr,
+                        stream_id,
+                        data: Data(Box::new([])),
+                        fin: true,
+                    });
+                }
+                match status {
+                    P2pNetworkRelayStreamStatus::Spliced {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        ..
+                    } => {
+                        store.dispatch(P2pNetworkRelayAction::Close {
+                            addr,
+                            peer_id,
+                            stream_id,
+                        });
+                    }
+                    P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Stop {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    }) if store
+                        .state()
+                        .network
+                        .scheduler
+                        .relay_state
+                        .find_stream(&peer_id, &stream_id)
+                        .map_or(false, |source| !source.closed) =>
+                    {
+                        store.dispatch(P2pNetworkRelayAction::OutgoingMessage {
+                            addr,
+                            stream_id,
+                            message: P2pNetworkRelayMessage::hop_status(
+                                P2pNetworkRelayStatus::ConnectionFailed,
+                            ),
+                        });
+                        store.dispatch(P2pNetworkRelayAction::Close {
+                            addr,
+                            peer_id,
+                            stream_id,
+                        });
+                    }
+                    P2pNetworkRelayStreamStatus::Circuit { addr } => {
+                        store.dispatch(P2pNetworkSchedulerAction::Error {
+                            addr,
+                            error: P2pNetworkConnectionError::RelayCircuitClosed,
+                        });
+                    }
+                    _ => {}
+                }
+                store.dispatch(P2pNetworkRelayAction::Prune {
+                    addr,
+                    peer_id,
+                    stream_id,
+                });
+                Ok(())
+            }
+            P2pNetworkRelayAction::Prune { .. } => Ok(()),
+            P2pNetworkRelayAction::ConnectionClosed { addr } => {
+                let mut streams = relay
+                    .streams
+                    .iter()
+                    .flat_map(|(peer_id, streams)| {
+                        streams
+                            .iter()
+                            .filter(|(_, stream)| stream.addr == addr && !stream.closed)
+                            .map(|(stream_id, _)| (addr, *peer_id, *stream_id))
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(circuit) = relay.circuits.get(&addr) {
+                    streams.push((circuit.addr, circuit.relay_peer_id, circuit.stream_id));
+                }
+                for (addr, peer_id, stream_id) in streams {
+                    store.dispatch(P2pNetworkRelayAction::Close {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::Reserve { relay_peer_id } => {
+                open_stream(store, &relay_peer_id, P2pNetworkRelayProtocol::Hop)
+            }
+            P2pNetworkRelayAction::ReserveSuccess { .. } => Ok(()),
+            P2pNetworkRelayAction::ReserveError {
+                relay_peer_id,
+                error,
+            } => {
+                warn!(meta.time(); summary = "relay reservation failed", relay_peer_id = display(relay_peer_id), error = display(error));
+                Ok(())
+            }
+            P2pNetworkRelayAction::Connect { relay_peer_id, .. } => {
+                open_stream(store, &relay_peer_id, P2pNetworkRelayProtocol::Hop)
+            }
+            P2pNetworkRelayAction::ConnectError {
+                relay_peer_id,
+                peer_id,
+                error,
+            } => {
+                warn!(meta.time(); summary = "relayed connection failed", relay_peer_id = display(relay_peer_id), peer_id = display(peer_id), error = display(error));
+                Ok(())
+            }
+            P2pNetworkRelayAction::CircuitOpen {
+                addr,
+                peer_id,
+                stream_id,
+                remote_peer_id,
+                incoming,
+            } => {
+                let Some(P2pNetworkRelayStreamStatus::Circuit { addr: circuit }) = relay
+                    .find_stream(&peer_id, &stream_id)
+                    .map(|stream| &stream.status)
+                else {
+                    return Err(format!("relay stream is not a circuit for action {self:?}"));
+                };
+                let circuit = *circuit;
+                if incoming {
+                    store.dispatch(P2pNetworkSchedulerAction::IncomingDidAccept {
+                        addr: Some(circuit),
+                        result: Ok(()),
+                    });
+                } else {
+                    let opts =
+                        P2pConnectionOutgoingInitOpts::LibP2P((remote_peer_id, circuit).into());
+                    store.dispatch(P2pConnectionOutgoingAction::Init { opts, rpc_id: None });
+                }
+
+                if !store
+                    .state()
+                    .network
+                    .scheduler
+                    .connections
+                    .contains_key(&circuit)
+                {
+                    store.dispatch(P2pNetworkRelayAction::Close {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    });
+                    return Ok(());
+                }
+
+                // the data that is received after the status belongs to the relayed connection
+                let buffer = store
+                    .state()
+                    .network
+                    .scheduler
+                    .relay_state
+                    .find_stream(&peer_id, &stream_id)
+                    .map(|stream| stream.buffer.clone())
+                    .unwrap_or_default();
+                if !buffer.is_empty() {
+                    store.dispatch(P2pNetworkRelayAction::IncomingData {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        data: Data::from(buffer),
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::CircuitOutgoingData { addr, data } => {
+                let circuit = relay
+                    .circuits
+                    .get(&addr)
+                    .ok_or_else(|| format!("relay circuit {addr} not found"))?;
+                store.dispatch(P2pNetworkYamuxAction::OutgoingData {
+                    addr: circuit.addr,
+                    stream_id: circuit.stream_id,
+                    data,
+                    fin: false,
+                });
+                Ok(())
+            }
+            P2pNetworkRelayAction::HopReserve {
+                addr,
+                peer_id,
+                stream_id,
+            } => {
+                let expire = relay
+                    .reserved
+                    .get(&peer_id)
+                    .map(|expire| u64::from(*expire) / 1_000_000_000);
+                let message = P2pNetworkRelayMessage::HopStatus {
+                    status: P2pNetworkRelayStatus::Ok,
+                    expire,
+                    addrs: local_addrs(store),
+                    limit: Some(circuit_limit()),
+                };
+                store.dispatch(P2pNetworkRelayAction::OutgoingMessage {
+                    addr,
+                    stream_id,
+                    message,
+                });
+                store.dispatch(P2pNetworkRelayAction::Close {
+                    addr,
+                    peer_id,
+                    stream_id,
+                });
+                Ok(())
+            }
+            P2pNetworkRelayAction::HopConnect {
+                addr,
+                peer_id,
+                stream_id,
+                dst,
+            } => {
+                if let Err(err) = open_stream(store, &dst, P2pNetworkRelayProtocol::Stop) {
+                    warn!(meta.time(); summary = "cannot open relay stop stream", peer_id = display(dst), error = display(err));
+                    store.dispatch(P2pNetworkRelayAction::OutgoingMessage {
+                        addr,
+                        stream_id,
+                        message: P2pNetworkRelayMessage::hop_status(
+                            P2pNetworkRelayStatus::ConnectionFailed,
+                        ),
+                    });
+                    store.dispatch(P2pNetworkRelayAction::Close {
+                        addr,
+                        peer_id,
+                        stream_id,
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::HopSplice {
+                addr,
+                peer_id,
+                stream_id,
+            } => {
+                let Some(P2pNetworkRelayStreamStatus::Spliced {
+                    addr: src_addr,
+                    peer_id: src_peer_id,
+                    stream_id: src_stream_id,
+                    ..
+                }) = relay
+                    .find_stream(&peer_id, &stream_id)
+                    .map(|stream| stream.status.clone())
+                else {
+                    return Err(format!("relay stream is not spliced for action {self:?}"));
+                };
+                store.dispatch(P2pNetworkRelayAction::OutgoingMessage {
+                    addr: src_addr,
+                    stream_id: src_stream_id,
+                    message: P2pNetworkRelayMessage::HopStatus {
+                        status: P2pNetworkRelayStatus::Ok,
+                        expire: None,
+                        addrs: Vec::new(),
+                        limit: Some(circuit_limit()),
+                    },
+                });
+
+                // forward the data that is received before the streams are spliced
+                for (addr, peer_id, stream_id) in [
+                    (addr, peer_id, stream_id),
+                    (src_addr, src_peer_id, src_stream_id),
+                ] {
+                    let buffer = store
+                        .state()
+                        .network
+                        .scheduler
+                        .relay_state
+                        .find_stream(&peer_id, &stream_id)
+                        .map(|stream| stream.buffer.clone())
+                        .unwrap_or_default();
+                    if !buffer.is_empty() {
+                        store.dispatch(P2pNetworkRelayAction::IncomingData {
+                            addr,
+                            peer_id,
+                            stream_id,
+                            data: Data::from(buffer),
+                        });
+                    }
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::DcutrUpgrade { peer_id, .. } => {
+                store.dispatch(P2pDisconnectionAction::Init {
+                    peer_id,
+                    reason: P2pDisconnectionReason::DirectConnectionUpgrade,
+                });
+                Ok(())
+            }
+            P2pNetworkRelayAction::DcutrDial { peer_id, addr } => {
+                let opts = P2pConnectionOutgoingInitOpts::LibP2P((peer_id, addr).into());
+                store.dispatch(P2pConnectionOutgoingAction::Init { opts, rpc_id: None });
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Opens a new outgoing stream to the peer, the stream is assigned to the
+/// first queued request of the protocol once it is negotiated.
+fn open_stream<Store, S>(
+    store: &mut Store,
+    peer_id: &PeerId,
+    protocol: P2pNetworkRelayProtocol,
+) -> Result<(), String>
+where
+    Store: crate::P2pStore<S>,
+{
+    let (addr, conn) = store
+        .state()
+        .network
+        .scheduler
+        .find_peer(peer_id)
+        .ok_or_else(|| format!("connection with {peer_id} not found"))?;
+    let stream_id = conn
+        .mux
+        .as_ref()
+        .and_then(|mux| mux.next_dynamic_stream_id(conn.incoming))
+        .ok_or_else(|| format!("cannot get next stream for {addr}"))?;
+    let addr = *addr;
+    store.dispatch(P2pNetworkYamuxAction::OpenStream {
+        addr,
+        stream_id,
+        stream_kind: protocol.stream_kind(),
+    });
+    Ok(())
+}
+
+/// Addresses where this node can be reached directly.
+fn local_addrs<Store, S>(store: &mut Store) -> Vec<Multiaddr>
+where
+    Store: crate::P2pStore<S>,
+    Store::Service: P2pNetworkService,
+{
+    let listeners = store
+        .state()
+        .network
+        .scheduler
+        .listeners
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut addrs = Vec::new();
+    for addr in listeners {
+        addrs.extend(get_addrs::<Vec<_>, _>(&addr, store.service()));
+    }
+    addrs.extend(store.state().nat.external_multiaddrs());
+    addrs
+}
+
+/// Picks the address to dial the peer directly, preferring the public ones.
+fn direct_addr(addrs: &[Multiaddr]) -> Option<SocketAddr> {
+    let addrs = addrs
+        .iter()
+        .filter_map(|addr| {
+            let mut iter = addr.iter();
+            let ip = match iter.next()? {
+                Protocol::Ip4(ip) => ip.into(),
+                Protocol::Ip6(ip) => ip.into(),
+                _ => return None,
+            };
+            let Protocol::Tcp(port) = iter.next()? else {
+                return None;
+            };
+            Some(SocketAddr::new(ip, port))
+        })
+        .filter(|addr| !addr.ip().is_unspecified() && !is_circuit_ip(&addr.ip()))
+        .collect::<Vec<_>>();
+    addrs
+        .iter()
+        .find(|addr| is_public_ip(&addr.ip()))
+        .or_else(|| addrs.first())
+        .copied()
+}
+
+fn circuit_limit() -> P2pNetworkRelayLimit {
+    P2pNetworkRelayLimit {
+        duration: Some(RELAY_CIRCUIT_DURATION.as_secs() as u32),
+        data: Some(RELAY_CIRCUIT_DATA),
+    }
+}
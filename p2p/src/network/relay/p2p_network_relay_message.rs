@@ -0,0 +1,359 @@
+use multiaddr::Multiaddr;
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+use super::{pb, P2pNetworkRelayProtocol};
+
+/// Status of the circuit relay request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error, Serialize, Deserialize)]
+pub enum P2pNetworkRelayStatus {
+    #[error("ok")]
+    Ok,
+    #[error("reservation refused")]
+    ReservationRefused,
+    #[error("resource limit exceeded")]
+    ResourceLimitExceeded,
+    #[error("permission denied")]
+    PermissionDenied,
+    #[error("connection failed")]
+    ConnectionFailed,
+    #[error("no reservation")]
+    NoReservation,
+    #[error("malformed message")]
+    MalformedMessage,
+    #[error("unexpected message")]
+    UnexpectedMessage,
+}
+
+/// Limits applied by the relay to the relayed connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct P2pNetworkRelayLimit {
+    /// Maximum duration of the circuit, in seconds.
+    pub duration: Option<u32>,
+    /// Maximum number of bytes relayed in each direction.
+    pub data: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum P2pNetworkRelayMessage {
+    /// Hop: reserves a slot on the relay, so other peers can connect through it.
+    Reserve,
+    /// Hop: asks the relay to open a circuit to the peer.
+    Connect { peer_id: PeerId },
+    /// Hop: relay's response to the request.
+    HopStatus {
+        status: P2pNetworkRelayStatus,
+        /// Unix time when the reservation expires, in seconds.
+        expire: Option<u64>,
+        /// Relay addresses for the reserving peer.
+        addrs: Vec<Multiaddr>,
+        limit: Option<P2pNetworkRelayLimit>,
+    },
+    /// Stop: relay asks the peer to accept the circuit from the source peer.
+    StopConnect {
+        peer_id: PeerId,
+        limit: Option<P2pNetworkRelayLimit>,
+    },
+    /// Stop: peer's response to the relay.
+    StopStatus { status: P2pNetworkRelayStatus },
+    /// DCUtR: addresses where the peer can be reached directly.
+    HolePunchConnect { addrs: Vec<Multiaddr> },
+    /// DCUtR: the initiator starts dialing.
+    HolePunchSync,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error, Serialize, Deserialize)]
+pub enum P2pNetworkRelayFromMessageError {
+    #[error("unknown message type: {0}")]
+    Type(i32),
+    #[error("unknown status: {0}")]
+    Status(i32),
+    #[error("missing peer")]
+    MissingPeer,
+    #[error("missing status")]
+    MissingStatus,
+    #[error("invalid peer id: {0}")]
+    PeerId(String),
+    #[error("invalid multiaddr: {0}")]
+    Multiaddr(String),
+}
+
+impl P2pNetworkRelayMessage {
+    /// Protocol of the stream where the message is sent.
+    pub fn protocol(&self) -> P2pNetworkRelayProtocol {
+        match self {
+            Self::Reserve | Self::Connect { .. } | Self::HopStatus { .. } => {
+                P2pNetworkRelayProtocol::Hop
+            }
+            Self::StopConnect { .. } | Self::StopStatus { .. } => P2pNetworkRelayProtocol::Stop,
+            Self::HolePunchConnect { .. } | Self::HolePunchSync => P2pNetworkRelayProtocol::Dcutr,
+        }
+    }
+
+    pub fn hop_status(status: P2pNetworkRelayStatus) -> Self {
+        Self::HopStatus {
+            status,
+            expire: None,
+            addrs: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Encodes the message prefixed with its length.
+    pub fn encode_length_delimited(&self) -> Result<Vec<u8>, String> {
+        use pb::circuit::{hop_message, stop_message, HopMessage, Peer, Reservation, StopMessage};
+        use pb::holepunch::{hole_punch, HolePunch};
+
+        let mut buf = Vec::new();
+        let result = match self {
+            Self::Reserve => prost::Message::encode_length_delimited(
+                &HopMessage {
+                    r#type: hop_message::Type::Reserve as i32,
+                    ..Default::default()
+                },
+                &mut buf,
+            ),
+            Self::Connect { peer_id } => prost::Message::encode_length_delimited(
+                &HopMessage {
+                    r#type: hop_message::Type::Connect as i32,
+                    peer: Some(Peer {
+                        id: peer_id_to_bytes(peer_id),
+                        addrs: Vec::new(),
+                    }),
+                    ..Default::default()
+                },
+                &mut buf,
+            ),
+            Self::HopStatus {
+                status,
+                expire,
+                addrs,
+                limit,
+            } => prost::Message::encode_length_delimited(
+                &HopMessage {
+                    r#type: hop_message::Type::Status as i32,
+                    reservation: expire.map(|expire| Reservation {
+                        expire,
+                        addrs: addrs.iter().map(|addr| addr.to_vec()).collect(),
+                        voucher: None,
+                    }),
+                    limit: limit.map(Into::into),
+                    status: Some(pb::circuit::Status::from(*status) as i32),
+                    ..Default::default()
+                },
+                &mut buf,
+            ),
+            Self::StopConnect { peer_id, limit } => prost::Message::encode_length_delimited(
+                &StopMessage {
+                    r#type: stop_message::Type::Connect as i32,
+                    peer: Some(Peer {
+                        id: peer_id_to_bytes(peer_id),
+                        addrs: Vec::new(),
+                    }),
+                    limit: limit.map(Into::into),
+                    ..Default::default()
+                },
+                &mut buf,
+            ),
+            Self::StopStatus { status } => prost::Message::encode_length_delimited(
+                &StopMessage {
+                    r#type: stop_message::Type::Status as i32,
+                    status: Some(pb::circuit::Status::from(*status) as i32),
+                    ..Default::default()
+                },
+                &mut buf,
+            ),
+            Self::HolePunchConnect { addrs } => prost::Message::encode_length_delimited(
+                &HolePunch {
+                    r#type: hole_punch::Type::Connect as i32,
+                    obs_addrs: addrs.iter().map(|addr| addr.to_vec()).collect(),
+                },
+                &mut buf,
+            ),
+            Self::HolePunchSync => prost::Message::encode_length_delimited(
+                &HolePunch {
+                    r#type: hole_punch::Type::Sync as i32,
+                    obs_addrs: Vec::new(),
+                },
+                &mut buf,
+            ),
+        };
+        result
+            .map(|_| buf)
+            .map_err(|e| format!("error serializing relay message: {e}"))
+    }
+
+    /// Decodes the message received in the stream of the given protocol.
+    pub(super) fn decode(
+        protocol: P2pNetworkRelayProtocol,
+        bytes: &[u8],
+    ) -> Result<Result<Self, P2pNetworkRelayFromMessageError>, prost::DecodeError> {
+        use prost::Message;
+
+        Ok(match protocol {
+            P2pNetworkRelayProtocol::Hop => pb::circuit::HopMessage::decode(bytes)?.try_into(),
+            P2pNetworkRelayProtocol::Stop => pb::circuit::StopMessage::decode(bytes)?.try_into(),
+            P2pNetworkRelayProtocol::Dcutr => pb::holepunch::HolePunch::decode(bytes)?.try_into(),
+        })
+    }
+}
+
+impl TryFrom<pb::circuit::HopMessage> for P2pNetworkRelayMessage {
+    type Error = P2pNetworkRelayFromMessageError;
+
+    fn try_from(value: pb::circuit::HopMessage) -> Result<Self, Self::Error> {
+        use pb::circuit::hop_message::Type;
+
+        match Type::try_from(value.r#type) {
+            Ok(Type::Reserve) => Ok(Self::Reserve),
+            Ok(Type::Connect) => {
+                let peer = value
+                    .peer
+                    .ok_or(P2pNetworkRelayFromMessageError::MissingPeer)?;
+                Ok(Self::Connect {
+                    peer_id: peer_id_try_from_bytes(&peer.id)?,
+                })
+            }
+            Ok(Type::Status) => {
+                let (expire, addrs) = match value.reservation {
+                    Some(reservation) => (
+                        Some(reservation.expire),
+                        multiaddrs_try_from_bytes(reservation.addrs)?,
+                    ),
+                    None => (None, Vec::new()),
+                };
+                Ok(Self::HopStatus {
+                    status: status_try_from(value.status)?,
+                    expire,
+                    addrs,
+                    limit: value.limit.map(Into::into),
+                })
+            }
+            Err(_) => Err(P2pNetworkRelayFromMessageError::Type(value.r#type)),
+        }
+    }
+}
+
+impl TryFrom<pb::circuit::StopMessage> for P2pNetworkRelayMessage {
+    type Error = P2pNetworkRelayFromMessageError;
+
+    fn try_from(value: pb::circuit::StopMessage) -> Result<Self, Self::Error> {
+        use pb::circuit::stop_message::Type;
+
+        match Type::try_from(value.r#type) {
+            Ok(Type::Connect) => {
+                let peer = value
+                    .peer
+                    .ok_or(P2pNetworkRelayFromMessageError::MissingPeer)?;
+                Ok(Self::StopConnect {
+                    peer_id: peer_id_try_from_bytes(&peer.id)?,
+                    limit: value.limit.map(Into::into),
+                })
+            }
+            Ok(Type::Status) => Ok(Self::StopStatus {
+                status: status_try_from(value.status)?,
+            }),
+            Err(_) => Err(P2pNetworkRelayFromMessageError::Type(value.r#type)),
+        }
+    }
+}
+
+impl TryFrom<pb::holepunch::HolePunch> for P2pNetworkRelayMessage {
+    type Error = P2pNetworkRelayFromMessageError;
+
+    fn try_from(value: pb::holepunch::HolePunch) -> Result<Self, Self::Error> {
+        use pb::holepunch::hole_punch::Type;
+
+        match Type::try_from(value.r#type) {
+            Ok(Type::Connect) => Ok(Self::HolePunchConnect {
+                // unsupported addresses are skipped, the peer may listen on other transports
+                addrs: value
+                    .obs_addrs
+                    .into_iter()
+                    .filter_map(|addr| Multiaddr::try_from(addr).ok())
+                    .collect(),
+            }),
+            Ok(Type::Sync) => Ok(Self::HolePunchSync),
+            Err(_) => Err(P2pNetworkRelayFromMessageError::Type(value.r#type)),
+        }
+    }
+}
+
+impl From<P2pNetworkRelayStatus> for pb::circuit::Status {
+    fn from(value: P2pNetworkRelayStatus) -> Self {
+        match value {
+            P2pNetworkRelayStatus::Ok => Self::Ok,
+            P2pNetworkRelayStatus::ReservationRefused => Self::ReservationRefused,
+            P2pNetworkRelayStatus::ResourceLimitExceeded => Self::ResourceLimitExceeded,
+            P2pNetworkRelayStatus::PermissionDenied => Self::PermissionDenied,
+            P2pNetworkRelayStatus::ConnectionFailed => Self::ConnectionFailed,
+            P2pNetworkRelayStatus::NoReservation => Self::NoReservation,
+            P2pNetworkRelayStatus::MalformedMessage => Self::MalformedMessage,
+            P2pNetworkRelayStatus::UnexpectedMessage => Self::UnexpectedMessage,
+        }
+    }
+}
+
+impl From<P2pNetworkRelayLimit> for pb::circuit::Limit {
+    fn from(value: P2pNetworkRelayLimit) -> Self {
+        Self {
+            duration: value.duration,
+            data: value.data,
+        }
+    }
+}
+
+impl From<pb::circuit::Limit> for P2pNetworkRelayLimit {
+    fn from(value: pb::circuit::Limit) -> Self {
+        Self {
+            duration: value.duration,
+            data: value.data,
+        }
+    }
+}
+
+fn status_try_from(
+    status: Option<i32>,
+) -> Result<P2pNetworkRelayStatus, P2pNetworkRelayFromMessageError> {
+    use pb::circuit::Status;
+
+    let status = status.ok_or(P2pNetworkRelayFromMessageError::MissingStatus)?;
+    Ok(match Status::try_from(status) {
+        Ok(Status::Ok) => P2pNetworkRelayStatus::Ok,
+        Ok(Status::ReservationRefused) => P2pNetworkRelayStatus::ReservationRefused,
+        Ok(Status::ResourceLimitExceeded) => P2pNetworkRelayStatus::ResourceLimitExceeded,
+        Ok(Status::PermissionDenied) => P2pNetworkRelayStatus::PermissionDenied,
+        Ok(Status::ConnectionFailed) => P2pNetworkRelayStatus::ConnectionFailed,
+        Ok(Status::NoReservation) => P2pNetworkRelayStatus::NoReservation,
+        Ok(Status::MalformedMessage) => P2pNetworkRelayStatus::MalformedMessage,
+        Ok(Status::UnexpectedMessage) => P2pNetworkRelayStatus::UnexpectedMessage,
+        Ok(Status::Unused) | Err(_) => return Err(P2pNetworkRelayFromMessageError::Status(status)),
+    })
+}
+
+fn peer_id_to_bytes(peer_id: &PeerId) -> Vec<u8> {
+    libp2p_identity::PeerId::from(*peer_id).to_bytes()
+}
+
+fn peer_id_try_from_bytes(bytes: &[u8]) -> Result<PeerId, P2pNetworkRelayFromMessageError> {
+    let peer_id = libp2p_identity::PeerId::from_bytes(bytes)
+        .map_err(|e| P2pNetworkRelayFromMessageError::PeerId(e.to_string()))?;
+    (&peer_id)
+        .try_into()
+        .map_err(|e: crate::identity::PeerIdFromLibp2pPeerId| {
+            P2pNetworkRelayFromMessageError::PeerId(e.to_string())
+        })
+}
+
+fn multiaddrs_try_from_bytes(
+    addrs: Vec<Vec<u8>>,
+) -> Result<Vec<Multiaddr>, P2pNetworkRelayFromMessageError> {
+    addrs
+        .into_iter()
+        .map(|addr| {
+            Multiaddr::try_from(addr)
+                .map_err(|e| P2pNetworkRelayFromMessageError::Multiaddr(e.to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,332 @@
+use quick_protobuf::BytesReader;
+use redux::{ActionWithMeta, Timestamp};
+
+use crate::{P2pLimits, P2pNetworkStreamProtobufError};
+
+use super::{
+    circuit_addr, P2pNetworkRelayAction, P2pNetworkRelayCircuit, P2pNetworkRelayMessage,
+    P2pNetworkRelayProtocol, P2pNetworkRelayRequest, P2pNetworkRelayReservationState,
+    P2pNetworkRelayState, P2pNetworkRelayStreamState, P2pNetworkRelayStreamStatus,
+    P2pNetworkRelayUpgrade, RELAY_RESERVATION_TTL,
+};
+
+impl P2pNetworkRelayState {
+    pub fn reducer(
+        &mut self,
+        action: ActionWithMeta<&P2pNetworkRelayAction>,
+        limits: &P2pLimits,
+    ) -> Result<(), String> {
+        let (action, meta) = action.split();
+        match action {
+            P2pNetworkRelayAction::NewStream {
+                addr,
+                peer_id,
+                stream_id,
+                protocol,
+                incoming,
+            } => {
+                let status = match (protocol, incoming) {
+                    (_, true) => P2pNetworkRelayStreamStatus::Idle,
+                    (P2pNetworkRelayProtocol::Dcutr, false) => {
+                        P2pNetworkRelayStreamStatus::DcutrConnect { time: meta.time() }
+                    }
+                    (protocol, false) => {
+                        // outgoing streams of the same protocol are interchangeable,
+                        // so the stream serves the first queued request
+                        let queued = self.queued.get_mut(peer_id);
+                        let request = queued.and_then(|queued| {
+                            let index = queued
+                                .iter()
+                                .position(|request| request.protocol() == *protocol)?;
+                            queued.remove(index)
+                        });
+                        match request {
+                            Some(request) => P2pNetworkRelayStreamStatus::Request(request),
+                            None => P2pNetworkRelayStreamStatus::Done,
+                        }
+                    }
+                };
+                self.streams.entry(*peer_id).or_default().insert(
+                    *stream_id,
+                    P2pNetworkRelayStreamState::new(*addr, *protocol, *incoming, status),
+                );
+                Ok(())
+            }
+            P2pNetworkRelayAction::IncomingData {
+                peer_id,
+                stream_id,
+                data,
+                ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                match &mut stream.status {
+                    P2pNetworkRelayStreamStatus::Spliced { data: relayed, .. } => {
+                        *relayed += data.len() as u64;
+                        // the data left after the last message is forwarded now
+                        stream.buffer.clear();
+                    }
+                    P2pNetworkRelayStreamStatus::Circuit { .. } => stream.buffer.clear(),
+                    _ => {
+                        stream.buffer.extend_from_slice(data);
+                        stream.decode_message(limits);
+                    }
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::IncomingMessage {
+                peer_id,
+                stream_id,
+                message,
+                ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                stream.message = None;
+                // other transitions are made by the actions that handle the message
+                if let (
+                    P2pNetworkRelayStreamStatus::Idle,
+                    P2pNetworkRelayMessage::HolePunchConnect { .. },
+                ) = (&stream.status, message)
+                {
+                    stream.status = P2pNetworkRelayStreamStatus::DcutrSync;
+                    stream.decode_message(limits);
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::OutgoingMessage { .. } => Ok(()),
+            P2pNetworkRelayAction::RemoteClose { .. } => Ok(()),
+            P2pNetworkRelayAction::Close {
+                peer_id, stream_id, ..
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                stream.closed = true;
+                Ok(())
+            }
+            P2pNetworkRelayAction::Prune {
+                peer_id, stream_id, ..
+            } => {
+                let stream = self
+                    .streams
+                    .get_mut(peer_id)
+                    .and_then(|streams| streams.remove(stream_id))
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                if let P2pNetworkRelayStreamStatus::Circuit { addr } = stream.status {
+                    self.circuits.remove(&addr);
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::ConnectionClosed { .. } => Ok(()),
+            P2pNetworkRelayAction::Reserve { relay_peer_id } => {
+                self.reservations.insert(
+                    *relay_peer_id,
+                    P2pNetworkRelayReservationState::Pending { time: meta.time() },
+                );
+                self.queued
+                    .entry(*relay_peer_id)
+                    .or_default()
+                    .push_back(P2pNetworkRelayRequest::Reserve);
+                Ok(())
+            }
+            P2pNetworkRelayAction::ReserveSuccess {
+                relay_peer_id,
+                expire,
+            } => {
+                let now = meta.time();
+                let expire = Timestamp::new(expire.saturating_mul(1_000_000_000));
+                let ttl = expire.checked_sub(now).unwrap_or_default();
+                self.reservations.insert(
+                    *relay_peer_id,
+                    P2pNetworkRelayReservationState::Reserved { time: now, ttl },
+                );
+                Ok(())
+            }
+            P2pNetworkRelayAction::ReserveError {
+                relay_peer_id,
+                error,
+            } => {
+                self.reservations.insert(
+                    *relay_peer_id,
+                    P2pNetworkRelayReservationState::Error {
+                        time: meta.time(),
+                        error: error.to_string(),
+                    },
+                );
+                Ok(())
+            }
+            P2pNetworkRelayAction::Connect {
+                relay_peer_id,
+                peer_id,
+            } => {
+                self.queued
+                    .entry(*relay_peer_id)
+                    .or_default()
+                    .push_back(P2pNetworkRelayRequest::Connect { peer_id: *peer_id });
+                Ok(())
+            }
+            P2pNetworkRelayAction::ConnectError { .. } => Ok(()),
+            P2pNetworkRelayAction::CircuitOpen {
+                addr,
+                peer_id,
+                stream_id,
+                remote_peer_id,
+                incoming,
+            } => {
+                let circuit = circuit_addr(self.next_circuit_id);
+                self.next_circuit_id += 1;
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                stream.status = P2pNetworkRelayStreamStatus::Circuit { addr: circuit };
+                self.circuits.insert(
+                    circuit,
+                    P2pNetworkRelayCircuit {
+                        addr: *addr,
+                        relay_peer_id: *peer_id,
+                        stream_id: *stream_id,
+                        peer_id: *remote_peer_id,
+                        incoming: *incoming,
+                    },
+                );
+                Ok(())
+            }
+            P2pNetworkRelayAction::CircuitOutgoingData { .. } => Ok(()),
+            P2pNetworkRelayAction::HopReserve {
+                peer_id, stream_id, ..
+            } => {
+                let expire = u64::from(meta.time()) + RELAY_RESERVATION_TTL.as_nanos() as u64;
+                self.reserved.insert(*peer_id, Timestamp::new(expire));
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                stream.status = P2pNetworkRelayStreamStatus::Done;
+                Ok(())
+            }
+            P2pNetworkRelayAction::HopConnect {
+                addr,
+                peer_id,
+                stream_id,
+                dst,
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                stream.status = P2pNetworkRelayStreamStatus::HopPending { peer_id: *dst };
+                self.queued
+                    .entry(*dst)
+                    .or_default()
+                    .push_back(P2pNetworkRelayRequest::Stop {
+                        addr: *addr,
+                        peer_id: *peer_id,
+                        stream_id: *stream_id,
+                    });
+                Ok(())
+            }
+            P2pNetworkRelayAction::HopSplice {
+                addr,
+                peer_id,
+                stream_id,
+            } => {
+                let stream = self
+                    .find_stream_mut(peer_id, stream_id)
+                    .ok_or_else(|| format!("relay stream not found for action {action:?}"))?;
+                let P2pNetworkRelayStreamStatus::Request(P2pNetworkRelayRequest::Stop {
+                    addr: source_addr,
+                    peer_id: source_peer_id,
+                    stream_id: source_stream_id,
+                }) = stream.status
+                else {
+                    return Err(format!(
+                        "relay stream is not stopping for action {action:?}"
+                    ));
+                };
+                stream.status = P2pNetworkRelayStreamStatus::Spliced {
+                    addr: source_addr,
+                    peer_id: source_peer_id,
+                    stream_id: source_stream_id,
+                    time: meta.time(),
+                    data: 0,
+                };
+                let source = self
+                    .find_stream_mut(&source_peer_id, &source_stream_id)
+                    .ok_or_else(|| {
+                        format!("relay source stream not found for action {action:?}")
+                    })?;
+                source.status = P2pNetworkRelayStreamStatus::Spliced {
+                    addr: *addr,
+                    peer_id: *peer_id,
+                    stream_id: *stream_id,
+                    time: meta.time(),
+                    data: 0,
+                };
+                Ok(())
+            }
+            P2pNetworkRelayAction::DcutrUpgrade { peer_id, addr, rtt } => {
+                if let Some(addr) = addr {
+                    self.upgrades.insert(
+                        *peer_id,
+                        P2pNetworkRelayUpgrade {
+                            addr: *addr,
+                            time: Timestamp::new(
+                                u64::from(meta.time()) + (*rtt / 2).as_nanos() as u64,
+                            ),
+                        },
+                    );
+                }
+                Ok(())
+            }
+            P2pNetworkRelayAction::DcutrDial { peer_id, .. } => {
+                self.upgrades.remove(peer_id);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl P2pNetworkRelayStreamState {
+    /// Decodes the next length-delimited message, if it is expected.
+    ///
+    /// Only one message is decoded at a time, as the data that follows
+    /// it might belong to the relayed connection.
+    fn decode_message(&mut self, limits: &P2pLimits) {
+        if self.error.is_some() || self.message.is_some() || !self.expects_message() {
+            return;
+        }
+
+        let buf = &self.buffer[..];
+        if buf.is_empty() {
+            return;
+        }
+        let mut reader = BytesReader::from_bytes(buf);
+        let Ok(len) = reader.read_varint32(buf).map(|v| v as usize) else {
+            // the length itself may be incomplete
+            if buf.len() >= 5 {
+                self.error = Some(P2pNetworkStreamProtobufError::MessageLength.into());
+            }
+            return;
+        };
+        if len > limits.relay_message() {
+            self.error =
+                Some(P2pNetworkStreamProtobufError::Limit(len, limits.relay_message()).into());
+            return;
+        }
+        let header_len = buf.len() - reader.len();
+        if buf.len() < header_len + len {
+            return;
+        }
+
+        let message =
+            P2pNetworkRelayMessage::decode(self.protocol, &buf[header_len..(header_len + len)])
+                .map_err(|e| P2pNetworkStreamProtobufError::Message(e.to_string()))
+                .and_then(|message| message.map_err(P2pNetworkStreamProtobufError::Convert));
+        self.buffer = self.buffer[(header_len + len)..].to_vec();
+        match message {
+            Ok(message) => self.message = Some(message),
+            Err(err) => self.error = Some(err.into()),
+        }
+    }
+}
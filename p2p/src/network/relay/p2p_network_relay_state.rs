@@ -0,0 +1,319 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    is_time_passed, network::scheduler::StreamState, token, P2pNetworkStreamProtobufError, PeerId,
+    StreamId,
+};
+
+use super::{P2pNetworkRelayFromMessageError, P2pNetworkRelayMessage};
+
+/// Lifetime of the reservation granted by this relay.
+pub const RELAY_RESERVATION_TTL: Duration = Duration::from_secs(60 * 60);
+/// Time to wait before retrying the failed reservation.
+pub const RELAY_RESERVATION_RETRY: Duration = Duration::from_secs(60);
+/// Maximum duration of the relayed connection.
+pub const RELAY_CIRCUIT_DURATION: Duration = Duration::from_secs(2 * 60);
+/// Maximum number of bytes relayed in each direction.
+pub const RELAY_CIRCUIT_DATA: u64 = 1 << 17;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct P2pNetworkRelayState {
+    pub streams: StreamState<P2pNetworkRelayStreamState>,
+    /// Requests waiting for the outgoing stream to the peer to be opened.
+    pub queued: BTreeMap<PeerId, VecDeque<P2pNetworkRelayRequest>>,
+    /// Our reservations on the relays.
+    pub reservations: BTreeMap<PeerId, P2pNetworkRelayReservationState>,
+    /// Peers that have a reservation on this relay, with its expiration time.
+    pub reserved: BTreeMap<PeerId, Timestamp>,
+    /// Relayed connections, by their virtual address.
+    pub circuits: BTreeMap<SocketAddr, P2pNetworkRelayCircuit>,
+    pub next_circuit_id: u64,
+    /// Relayed connections that are replaced with the direct ones.
+    pub upgrades: BTreeMap<PeerId, P2pNetworkRelayUpgrade>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum P2pNetworkRelayProtocol {
+    Hop,
+    Stop,
+    Dcutr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum P2pNetworkRelayRequest {
+    Reserve,
+    Connect {
+        peer_id: PeerId,
+    },
+    /// Relay asks the destination peer to accept the circuit from the
+    /// source hop stream.
+    Stop {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum P2pNetworkRelayReservationState {
+    Pending { time: Timestamp },
+    Reserved { time: Timestamp, ttl: Duration },
+    Error { time: Timestamp, error: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P2pNetworkRelayCircuit {
+    /// Address of the connection to the relay.
+    pub addr: SocketAddr,
+    pub relay_peer_id: PeerId,
+    /// Stream that carries the relayed connection.
+    pub stream_id: StreamId,
+    pub peer_id: PeerId,
+    pub incoming: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P2pNetworkRelayUpgrade {
+    /// Direct address of the peer.
+    pub addr: SocketAddr,
+    /// Time when the peer is expected to be ready for the direct connection.
+    pub time: Timestamp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P2pNetworkRelayStreamState {
+    pub addr: SocketAddr,
+    pub protocol: P2pNetworkRelayProtocol,
+    pub incoming: bool,
+    pub buffer: Vec<u8>,
+    /// Decoded message, waiting to be handled.
+    pub message: Option<P2pNetworkRelayMessage>,
+    pub status: P2pNetworkRelayStreamStatus,
+    /// The stream is being closed, no more data is expected.
+    pub closed: bool,
+    pub error: Option<P2pNetworkRelayStreamError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum P2pNetworkRelayStreamStatus {
+    /// Incoming stream, waiting for the request.
+    Idle,
+    /// The request is sent, waiting for the status.
+    Request(P2pNetworkRelayRequest),
+    /// Relay: waiting for the destination peer to accept the circuit.
+    HopPending { peer_id: PeerId },
+    /// Relay: data is forwarded to the other stream of the circuit.
+    Spliced {
+        addr: SocketAddr,
+        peer_id: PeerId,
+        stream_id: StreamId,
+        time: Timestamp,
+        /// Number of bytes relayed from this stream.
+        data: u64,
+    },
+    /// Data is passed to the relayed connection with the given virtual address.
+    Circuit { addr: SocketAddr },
+    /// DCUtR initiator: our addresses are sent, waiting for the peer's ones.
+    DcutrConnect { time: Timestamp },
+    /// DCUtR responder: waiting for the initiator to start dialing.
+    DcutrSync,
+    /// Nothing else is expected from the stream.
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error, Serialize, Deserialize)]
+#[error("relay stream: {0}")]
+pub struct P2pNetworkRelayStreamError(
+    #[from] P2pNetworkStreamProtobufError<P2pNetworkRelayFromMessageError>,
+);
+
+impl P2pNetworkRelayProtocol {
+    pub fn stream_kind(self) -> token::StreamKind {
+        match self {
+            Self::Hop => token::StreamKind::Relay(token::RelayAlgorithm::Hop0_2_0),
+            Self::Stop => token::StreamKind::Relay(token::RelayAlgorithm::Stop0_2_0),
+            Self::Dcutr => token::StreamKind::Dcutr(token::DcutrAlgorithm::Dcutr),
+        }
+    }
+}
+
+impl From<token::RelayAlgorithm> for P2pNetworkRelayProtocol {
+    fn from(value: token::RelayAlgorithm) -> Self {
+        match value {
+            token::RelayAlgorithm::Hop0_2_0 => Self::Hop,
+            token::RelayAlgorithm::Stop0_2_0 => Self::Stop,
+        }
+    }
+}
+
+impl P2pNetworkRelayRequest {
+    pub fn protocol(&self) -> P2pNetworkRelayProtocol {
+        match self {
+            Self::Reserve | Self::Connect { .. } => P2pNetworkRelayProtocol::Hop,
+            Self::Stop { .. } => P2pNetworkRelayProtocol::Stop,
+        }
+    }
+}
+
+impl P2pNetworkRelayReservationState {
+    /// Checks if the reservation should be (re)created.
+    pub fn should_reserve(&self, now: Timestamp) -> bool {
+        match self {
+            Self::Pending { time } | Self::Error { time, .. } => {
+                is_time_passed(now, *time, Some(RELAY_RESERVATION_RETRY))
+            }
+            Self::Reserved { time, ttl } => is_time_passed(now, *time, Some(*ttl / 2)),
+        }
+    }
+}
+
+impl P2pNetworkRelayState {
+    pub fn find_stream(
+        &self,
+        peer_id: &PeerId,
+        stream_id: &StreamId,
+    ) -> Option<&P2pNetworkRelayStreamState> {
+        self.streams.get(peer_id)?.get(stream_id)
+    }
+
+    pub fn find_stream_mut(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: &StreamId,
+    ) -> Option<&mut P2pNetworkRelayStreamState> {
+        self.streams.get_mut(peer_id)?.get_mut(stream_id)
+    }
+
+    pub fn should_reserve(&self, relay_peer_id: &PeerId, now: Timestamp) -> bool {
+        self.reservations
+            .get(relay_peer_id)
+            .map_or(true, |reservation| reservation.should_reserve(now))
+    }
+
+    pub fn is_reserved(&self, peer_id: &PeerId, now: Timestamp) -> bool {
+        self.reserved
+            .get(peer_id)
+            .map_or(false, |expire| *expire > now)
+    }
+
+    /// Number of circuits relayed by this node, including pending ones.
+    pub fn relayed_circuits(&self) -> usize {
+        self.streams
+            .values()
+            .flat_map(|streams| streams.values())
+            .filter(|stream| {
+                stream.protocol == P2pNetworkRelayProtocol::Hop
+                    && matches!(
+                        stream.status,
+                        P2pNetworkRelayStreamStatus::HopPending { .. }
+                            | P2pNetworkRelayStreamStatus::Spliced { .. }
+                    )
+            })
+            .count()
+    }
+
+    /// Relayed streams that exceeded the duration limit.
+    pub fn expired_circuits(
+        &self,
+        now: Timestamp,
+    ) -> impl '_ + Iterator<Item = (SocketAddr, PeerId, StreamId)> {
+        self.streams.iter().flat_map(move |(peer_id, streams)| {
+            streams
+                .iter()
+                .filter_map(move |(stream_id, stream)| match &stream.status {
+                    P2pNetworkRelayStreamStatus::Spliced { time, .. }
+                        if !stream.closed
+                            && stream.protocol == P2pNetworkRelayProtocol::Hop
+                            && is_time_passed(now, *time, Some(RELAY_CIRCUIT_DURATION)) =>
+                    {
+                        Some((stream.addr, *peer_id, *stream_id))
+                    }
+                    _ => None,
+                })
+        })
+    }
+
+    pub fn circuit_of_stream(
+        &self,
+        addr: &SocketAddr,
+        stream_id: &StreamId,
+    ) -> Option<(&SocketAddr, &P2pNetworkRelayCircuit)> {
+        self.circuits
+            .iter()
+            .find(|(_, circuit)| &circuit.addr == addr && &circuit.stream_id == stream_id)
+    }
+
+    pub fn circuit_to_peer(
+        &self,
+        peer_id: &PeerId,
+    ) -> Option<(&SocketAddr, &P2pNetworkRelayCircuit)> {
+        self.circuits
+            .iter()
+            .find(|(_, circuit)| &circuit.peer_id == peer_id)
+    }
+}
+
+impl P2pNetworkRelayStreamState {
+    pub fn new(
+        addr: SocketAddr,
+        protocol: P2pNetworkRelayProtocol,
+        incoming: bool,
+        status: P2pNetworkRelayStreamStatus,
+    ) -> Self {
+        Self {
+            addr,
+            protocol,
+            incoming,
+            buffer: Vec::new(),
+            message: None,
+            status,
+            closed: false,
+            error: None,
+        }
+    }
+
+    /// Checks if a message is expected from the peer in the current status.
+    pub fn expects_message(&self) -> bool {
+        matches!(
+            self.status,
+            P2pNetworkRelayStreamStatus::Idle
+                | P2pNetworkRelayStreamStatus::Request(_)
+                | P2pNetworkRelayStreamStatus::DcutrConnect { .. }
+                | P2pNetworkRelayStreamStatus::DcutrSync
+        )
+    }
+
+    /// Checks if the stream data is forwarded without decoding.
+    pub fn is_passthrough(&self) -> bool {
+        matches!(
+            self.status,
+            P2pNetworkRelayStreamStatus::Spliced { .. }
+                | P2pNetworkRelayStreamStatus::Circuit { .. }
+        )
+    }
+}
+
+/// Virtual address of the relayed connection.
+///
+/// Relayed connections are handled by the same stack as the direct ones,
+/// so they are identified by an address from the discard-only prefix
+/// `100::/64` (RFC 6666) that is never used by real connections.
+pub fn circuit_addr(id: u64) -> SocketAddr {
+    let [a, b, c, d] = [id >> 48, id >> 32, id >> 16, id].map(|v| v as u16);
+    SocketAddr::new(Ipv6Addr::new(0x100, 0, 0, 0, a, b, c, d).into(), 0)
+}
+
+pub fn is_circuit_addr(addr: &SocketAddr) -> bool {
+    is_circuit_ip(&addr.ip())
+}
+
+pub fn is_circuit_ip(ip: &IpAddr) -> bool {
+    matches!(ip, IpAddr::V6(ip) if ip.segments()[..4] == [0x100, 0, 0, 0])
+}
@@ -14,7 +14,7 @@ use openmina_core::error;
 use crate::{
     channels::rpc::{
         BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse,
-        StagedLedgerAuxAndPendingCoinbases,
+        StagedLedgerAuxAndPendingCoinbases, TransitionChainProof,
     },
     connection::outgoing::P2pConnectionOutgoingInitOpts,
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
@@ -84,16 +84,47 @@ where
             });
         }
         (rpc::GetTransitionChainV2::NAME, rpc::GetTransitionChainV2::VERSION) => {
-            let hashes = rpc::GetTransitionChainV2::query_payload(&mut bytes)?;
-            for hash in hashes {
-                let hash = v2::StateHash::from(v2::DataHashLibStateHashStableV1(hash));
+            let hashes = rpc::GetTransitionChainV2::query_payload(&mut bytes)?
+                .into_iter()
+                .map(|hash| v2::StateHash::from(v2::DataHashLibStateHashStableV1(hash)))
+                .collect();
 
-                store.dispatch(P2pChannelsRpcAction::RequestReceived {
-                    peer_id,
-                    id,
-                    request: P2pRpcRequest::Block(hash),
-                });
-            }
+            store.dispatch(P2pChannelsRpcAction::RequestReceived {
+                peer_id,
+                id,
+                request: P2pRpcRequest::TransitionChain(hashes),
+            });
+        }
+        (
+            rpc::GetTransitionChainProofV1ForV2::NAME,
+            rpc::GetTransitionChainProofV1ForV2::VERSION,
+        ) => {
+            let hash = rpc::GetTransitionChainProofV1ForV2::query_payload(&mut bytes)?;
+            let hash = v2::StateHash::from(v2::DataHashLibStateHashStableV1(hash));
+
+            store.dispatch(P2pChannelsRpcAction::RequestReceived {
+                peer_id,
+                id,
+                request: P2pRpcRequest::TransitionChainProof(hash),
+            });
+        }
+        (rpc::GetAncestryV2::NAME, rpc::GetAncestryV2::VERSION) => {
+            let query = rpc::GetAncestryV2::query_payload(&mut bytes)?;
+            let hash = v2::StateHash::from(v2::DataHashLibStateHashStableV1(query.hash));
+
+            store.dispatch(P2pChannelsRpcAction::RequestReceived {
+                peer_id,
+                id,
+                request: P2pRpcRequest::Ancestry(Box::new(query.data), hash),
+            });
+        }
+        (rpc::GetTransitionKnowledgeV1ForV2::NAME, rpc::GetTransitionKnowledgeV1ForV2::VERSION) => {
+            let () = rpc::GetTransitionKnowledgeV1ForV2::query_payload(&mut bytes)?;
+            store.dispatch(P2pChannelsRpcAction::RequestReceived {
+                peer_id,
+                id,
+                request: P2pRpcRequest::TransitionKnowledge,
+            });
         }
         (rpc::GetSomeInitialPeersV1ForV2::NAME, rpc::GetSomeInitialPeersV1ForV2::VERSION) => {
             let () = rpc::GetSomeInitialPeersV1ForV2::query_payload(&mut bytes)?;
@@ -174,26 +205,69 @@ where
             });
         }
         (rpc::GetTransitionChainV2::NAME, rpc::GetTransitionChainV2::VERSION) => {
-            let response = rpc::GetTransitionChainV2::response_payload(&mut bytes)?;
-            match response {
-                Some(response) if !response.is_empty() => {
-                    for block in response {
-                        let response = Some(P2pRpcResponse::Block(Arc::new(block)));
-                        store.dispatch(P2pChannelsRpcAction::ResponseReceived {
-                            peer_id,
-                            id,
-                            response,
-                        });
-                    }
-                }
-                _ => {
-                    store.dispatch(P2pChannelsRpcAction::ResponseReceived {
-                        peer_id,
-                        id,
-                        response: None,
-                    });
-                }
-            }
+            let response = rpc::GetTransitionChainV2::response_payload(&mut bytes)?
+                .filter(|blocks| !blocks.is_empty())
+                .map(|blocks| blocks.into_iter().map(Arc::new).collect())
+                .map(P2pRpcResponse::TransitionChain);
+
+            store.dispatch(P2pChannelsRpcAction::ResponseReceived {
+                peer_id,
+                id,
+                response,
+            });
+        }
+        (
+            rpc::GetTransitionChainProofV1ForV2::NAME,
+            rpc::GetTransitionChainProofV1ForV2::VERSION,
+        ) => {
+            let response = rpc::GetTransitionChainProofV1ForV2::response_payload(&mut bytes)?
+                .map(|(init_state_hash, body_hashes)| TransitionChainProof {
+                    init_state_hash: v2::DataHashLibStateHashStableV1(init_state_hash).into(),
+                    body_hashes: body_hashes
+                        .into_iter()
+                        .map(v2::MinaBaseStateBodyHashStableV1)
+                        .collect(),
+                })
+                .map(P2pRpcResponse::TransitionChainProof);
+
+            store.dispatch(P2pChannelsRpcAction::ResponseReceived {
+                peer_id,
+                id,
+                response,
+            });
+        }
+        (rpc::GetAncestryV2::NAME, rpc::GetAncestryV2::VERSION) => {
+            let response = rpc::GetAncestryV2::response_payload(&mut bytes)?
+                .map(|resp| BestTipWithProof {
+                    best_tip: resp.data.into(),
+                    proof: (
+                        resp.proof
+                            .0
+                            .into_iter()
+                            .map(v2::MinaBaseStateBodyHashStableV1)
+                            .collect(),
+                        resp.proof.1.into(),
+                    ),
+                })
+                .map(P2pRpcResponse::Ancestry);
+
+            store.dispatch(P2pChannelsRpcAction::ResponseReceived {
+                peer_id,
+                id,
+                response,
+            });
+        }
+        (rpc::GetTransitionKnowledgeV1ForV2::NAME, rpc::GetTransitionKnowledgeV1ForV2::VERSION) => {
+            let hashes = rpc::GetTransitionKnowledgeV1ForV2::response_payload(&mut bytes)?
+                .into_iter()
+                .map(|hash| v2::StateHash::from(v2::DataHashLibStateHashStableV1(hash)))
+                .collect();
+
+            store.dispatch(P2pChannelsRpcAction::ResponseReceived {
+                peer_id,
+                id,
+                response: Some(P2pRpcResponse::TransitionKnowledge(hashes)),
+            });
         }
         (rpc::GetSomeInitialPeersV1ForV2::NAME, rpc::GetSomeInitialPeersV1ForV2::VERSION) => {
             let response = rpc::GetSomeInitialPeersV1ForV2::response_payload(&mut bytes)?;
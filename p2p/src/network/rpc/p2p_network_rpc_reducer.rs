@@ -118,6 +118,15 @@ impl P2pNetworkRpcState {
                     limits.rpc_get_transition_chain(),
                     GetTransitionChainV2::NAME,
                 ),
+                GetTransitionChainProofV1ForV2::NAME => (
+                    limits.rpc_get_transition_chain_proof(),
+                    GetTransitionChainProofV1ForV2::NAME,
+                ),
+                GetAncestryV2::NAME => (limits.rpc_get_ancestry(), GetAncestryV2::NAME),
+                GetTransitionKnowledgeV1ForV2::NAME => (
+                    limits.rpc_get_transition_knowledge(),
+                    GetTransitionKnowledgeV1ForV2::NAME,
+                ),
                 GetSomeInitialPeersV1ForV2::NAME => (
                     limits.rpc_get_some_initial_peers(),
                     GetSomeInitialPeersV1ForV2::NAME,
@@ -9,11 +9,11 @@ use crate::{
         P2pConnectionState,
     },
     disconnection::P2pDisconnectionAction,
-    identify::P2pIdentifyAction,
+    identify::{identify_push, P2pIdentifyAction},
     network::identify::P2pNetworkIdentifyStreamAction,
     request::{P2pNetworkKadRequestState, P2pNetworkKadRequestStatus},
-    token::{RpcAlgorithm, StreamKind},
-    MioCmd, P2pCryptoService, P2pMioService, P2pPeerStatus,
+    token::{DcutrAlgorithm, RpcAlgorithm, StreamKind},
+    MioCmd, P2pCryptoService, P2pMioService, P2pPeerStatus, P2pQuicService, QuicCmd,
 };
 
 use super::{super::*, *};
@@ -22,7 +22,7 @@ impl P2pNetworkSchedulerAction {
     pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
-        Store::Service: P2pMioService + P2pCryptoService,
+        Store::Service: P2pMioService + P2pQuicService + P2pCryptoService,
     {
         match self {
             Self::InterfaceDetected { ip, .. } => {
@@ -31,9 +31,18 @@ impl P2pNetworkSchedulerAction {
                         .service()
                         .send_mio_cmd(MioCmd::ListenOn(SocketAddr::new(ip, port)));
                 }
+                if let Some(port) = store.state().config.quic_port {
+                    store.dispatch(P2pNetworkQuicAction::Listen {
+                        listener: SocketAddr::new(ip, port),
+                    });
+                }
+            }
+            Self::InterfaceExpired { .. } => {
+                identify_push(store);
+            }
+            P2pNetworkSchedulerAction::ListenerReady { listener: _ } => {
+                identify_push(store);
             }
-            Self::InterfaceExpired { .. } => {}
-            P2pNetworkSchedulerAction::ListenerReady { listener: _ } => {}
             P2pNetworkSchedulerAction::ListenerError {
                 listener: _,
                 error: _,
@@ -63,7 +72,29 @@ impl P2pNetworkSchedulerAction {
                 });
             }
             Self::OutgoingConnect { addr } => {
-                store.service().send_mio_cmd(MioCmd::Connect(addr));
+                if is_circuit_addr(&addr) {
+                    // the relayed connection is already established by the relay
+                    if store
+                        .state()
+                        .network
+                        .scheduler
+                        .relay_state
+                        .circuits
+                        .contains_key(&addr)
+                    {
+                        store.dispatch(Self::OutgoingDidConnect {
+                            addr,
+                            result: Ok(()),
+                        });
+                    } else {
+                        store.dispatch(Self::Error {
+                            addr,
+                            error: P2pNetworkConnectionError::RelayCircuitClosed,
+                        });
+                    }
+                } else {
+                    store.service().send_mio_cmd(MioCmd::Connect(addr));
+                }
             }
             Self::OutgoingDidConnect { addr, result } => match result {
                 Ok(_) => {
@@ -158,25 +189,33 @@ impl P2pNetworkSchedulerAction {
                             return;
                         };
                         match kind {
-                            StreamKind::Status(_) => {
-                                //unimplemented!()
+                            StreamKind::Status(StatusAlgorithm::MinaNodeStatus) => {
+                                store.dispatch(P2pNetworkNodeStatusAction::NewStream {
+                                    addr,
+                                    peer_id,
+                                    stream_id,
+                                    incoming,
+                                });
                             }
                             StreamKind::Bitswap(_) => {
                                 //unimplemented!()
                             }
-                            StreamKind::Identify(IdentifyAlgorithm::Identify1_0_0) => {
+                            StreamKind::Identify(protocol) => {
                                 store.dispatch(P2pNetworkIdentifyStreamAction::New {
                                     addr,
                                     peer_id,
                                     stream_id,
                                     incoming,
+                                    protocol,
                                 });
                             }
-                            StreamKind::Identify(IdentifyAlgorithm::IdentifyPush1_0_0) => {
-                                //unimplemented!()
-                            }
                             StreamKind::Ping(PingAlgorithm::Ping1_0_0) => {
-                                //unimplemented!()
+                                store.dispatch(P2pNetworkPingAction::NewStream {
+                                    addr,
+                                    peer_id,
+                                    stream_id,
+                                    incoming,
+                                });
                             }
                             StreamKind::Broadcast(protocol) => {
                                 store.dispatch(P2pNetworkPubsubAction::NewStream {
@@ -217,6 +256,24 @@ impl P2pNetworkSchedulerAction {
                                     incoming,
                                 });
                             }
+                            StreamKind::Relay(protocol) => {
+                                store.dispatch(P2pNetworkRelayAction::NewStream {
+                                    addr,
+                                    peer_id,
+                                    stream_id,
+                                    protocol: protocol.into(),
+                                    incoming,
+                                });
+                            }
+                            StreamKind::Dcutr(DcutrAlgorithm::Dcutr) => {
+                                store.dispatch(P2pNetworkRelayAction::NewStream {
+                                    addr,
+                                    peer_id,
+                                    stream_id,
+                                    protocol: P2pNetworkRelayProtocol::Dcutr,
+                                    incoming,
+                                });
+                            }
                         }
                     }
                     None => {
@@ -256,7 +313,11 @@ impl P2pNetworkSchedulerAction {
                 // Close state is set by reducer for the non-stream case
                 if let Some(conn_state) = store.state().network.scheduler.connections.get(&addr) {
                     if let Some(reason) = conn_state.closed.clone() {
-                        store.service().send_mio_cmd(MioCmd::Disconnect(addr));
+                        if conn_state.quic {
+                            store.service().send_quic_cmd(QuicCmd::Disconnect(addr));
+                        } else if !is_circuit_addr(&addr) {
+                            store.service().send_mio_cmd(MioCmd::Disconnect(addr));
+                        }
                         store.dispatch(Self::Disconnected { addr, reason });
                     }
                 }
@@ -288,6 +349,15 @@ impl P2pNetworkSchedulerAction {
                     // TODO: open RPC and Kad connections only after identify reports support for it?
                     store.dispatch(P2pIdentifyAction::NewRequest { peer_id, addr });
 
+                    // DCUtR: the peer that accepted the relayed connection initiates the upgrade
+                    if incoming && is_circuit_addr(&addr) {
+                        store.dispatch(P2pNetworkYamuxAction::OpenStream {
+                            addr,
+                            stream_id: YamuxStreamKind::Dcutr.stream_id(incoming),
+                            stream_kind: StreamKind::Dcutr(DcutrAlgorithm::Dcutr),
+                        });
+                    }
+
                     // Kademlia: if the connection is initiated by Kademlia request, notify that it is ready.
                     if store
                         .state()
@@ -303,7 +373,11 @@ impl P2pNetworkSchedulerAction {
             Self::Disconnect { addr, .. } => {
                 if let Some(conn_state) = store.state().network.scheduler.connections.get(&addr) {
                     if let Some(reason) = conn_state.closed.clone() {
-                        store.service().send_mio_cmd(MioCmd::Disconnect(addr));
+                        if conn_state.quic {
+                            store.service().send_quic_cmd(QuicCmd::Disconnect(addr));
+                        } else if !is_circuit_addr(&addr) {
+                            store.service().send_mio_cmd(MioCmd::Disconnect(addr));
+                        }
                         store.dispatch(Self::Disconnected { addr, reason });
                     }
                 }
@@ -311,6 +385,10 @@ impl P2pNetworkSchedulerAction {
             Self::Error { addr, .. } => {
                 if let Some(conn_state) = store.state().network.scheduler.connections.get(&addr) {
                     if let Some(reason) = conn_state.closed.clone() {
+                        // mio drops the socket itself on errors, QUIC connection is to be closed explicitly
+                        if conn_state.quic {
+                            store.service().send_quic_cmd(QuicCmd::Disconnect(addr));
+                        }
                         store.dispatch(Self::Disconnected { addr, reason });
                     }
                 }
@@ -372,7 +450,10 @@ impl P2pNetworkSchedulerAction {
                     }
                 }
             }
-            Self::Prune { .. } | Self::PruneStreams { .. } => {}
+            Self::Prune { addr } => {
+                store.dispatch(P2pNetworkRelayAction::ConnectionClosed { addr });
+            }
+            Self::PruneStreams { .. } => {}
         }
     }
 }
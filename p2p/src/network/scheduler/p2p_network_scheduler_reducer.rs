@@ -29,6 +29,7 @@ impl P2pNetworkSchedulerState {
                     *addr,
                     P2pNetworkConnectionState {
                         incoming: true,
+                        quic: false,
                         pnet: P2pNetworkPnetState::new(self.pnet_key),
                         select_auth: P2pNetworkSelectState::default(),
                         auth: None,
@@ -45,6 +46,7 @@ impl P2pNetworkSchedulerState {
                     *addr,
                     P2pNetworkConnectionState {
                         incoming: false,
+                        quic: false,
                         pnet: P2pNetworkPnetState::new(self.pnet_key),
                         select_auth: P2pNetworkSelectState::initiator_auth(token::AuthKind::Noise),
                         auth: None,
@@ -125,6 +127,8 @@ impl P2pNetworkSchedulerState {
                             token::StreamKind::Identify(_) => {}
                             token::StreamKind::Discovery(_) => {}
                             token::StreamKind::Ping(_) => {}
+                            token::StreamKind::Relay(_) => {}
+                            token::StreamKind::Dcutr(_) => {}
                             token::StreamKind::Bitswap(_) => {}
                             token::StreamKind::Status(_) => {}
                         }
@@ -194,6 +198,15 @@ impl P2pNetworkSchedulerState {
                 if let Some(discovery_state) = self.discovery_state.as_mut() {
                     discovery_state.streams.remove(peer_id);
                 }
+                self.node_status_state.streams.remove(peer_id);
+                self.node_status_state.requested.remove(peer_id);
+                self.node_status_state.peer_statuses.remove(peer_id);
+                self.ping_state.streams.remove(peer_id);
+                self.ping_state.peers.remove(peer_id);
+                self.relay_state.streams.remove(peer_id);
+                self.relay_state.queued.remove(peer_id);
+                self.relay_state.reservations.remove(peer_id);
+                self.relay_state.reserved.remove(peer_id);
             }
         }
     }
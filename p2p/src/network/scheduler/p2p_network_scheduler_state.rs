@@ -25,6 +25,10 @@ pub struct P2pNetworkSchedulerState {
     pub discovery_state: Option<P2pNetworkKadState>,
     pub rpc_incoming_streams: StreamState<P2pNetworkRpcState>,
     pub rpc_outgoing_streams: StreamState<P2pNetworkRpcState>,
+    pub node_status_state: P2pNetworkNodeStatusState,
+    pub ping_state: P2pNetworkPingState,
+    pub relay_state: P2pNetworkRelayState,
+    pub quic_state: P2pNetworkQuicState,
 }
 
 impl P2pNetworkSchedulerState {
@@ -37,11 +41,28 @@ impl P2pNetworkSchedulerState {
             .iter()
             .find(|(_, conn_state)| conn_state.peer_id() == Some(peer_id))
     }
+
+    /// Protocol negotiated on the stream, used for bandwidth accounting.
+    pub fn stream_protocol(
+        &self,
+        addr: &SocketAddr,
+        stream_id: &StreamId,
+    ) -> P2pNetworkBandwidthProtocol {
+        P2pNetworkBandwidthProtocol::new(
+            self.connections
+                .get(addr)
+                .and_then(|cn| cn.streams.get(stream_id))
+                .and_then(|stream| stream.select.negotiated.as_ref())
+                .and_then(Option::as_ref),
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pNetworkConnectionState {
     pub incoming: bool,
+    /// The connection uses the QUIC transport, so pnet, noise and yamux are not used.
+    pub quic: bool,
     pub pnet: P2pNetworkPnetState,
     pub select_auth: P2pNetworkSelectState,
     pub auth: Option<P2pNetworkAuthState>,
@@ -110,17 +131,26 @@ pub enum P2pNetworkConnectionError {
     KademliaIncomingStreamError(#[from] P2pNetworkKadIncomingStreamError),
     #[error(transparent)]
     KademliaOutgoingStreamError(#[from] P2pNetworkKadOutgoingStreamError),
+    #[error("relayed connection is closed")]
+    RelayCircuitClosed,
+    #[error("quic error: {0}")]
+    QuicError(String),
+    #[error("yamux stream {0} received data exceeding its window")]
+    YamuxWindowExceeded(StreamId),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pNetworkAuthState {
     Noise(P2pNetworkNoiseState),
+    /// Peer authenticated by the TLS handshake of the QUIC connection.
+    Tls(PeerId),
 }
 
 impl P2pNetworkAuthState {
     fn peer_id(&self) -> Option<&PeerId> {
         match self {
             P2pNetworkAuthState::Noise(v) => v.peer_id(),
+            P2pNetworkAuthState::Tls(peer_id) => Some(peer_id),
         }
     }
 }
@@ -128,18 +158,44 @@ impl P2pNetworkAuthState {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pNetworkConnectionMuxState {
     Yamux(P2pNetworkYamuxState),
+    Quic(P2pNetworkQuicConnectionState),
 }
 
 impl P2pNetworkConnectionMuxState {
     pub fn consume(&mut self, len: usize) {
         match self {
             Self::Yamux(state) => state.consume(len),
+            // QUIC has its own flow control
+            Self::Quic(_) => {}
         }
     }
 
     fn limit(&self) -> usize {
         match self {
             Self::Yamux(state) => state.limit(),
+            Self::Quic(_) => 0,
+        }
+    }
+
+    /// Whether the multiplexer is ready to open streams.
+    pub fn is_ready(&self) -> bool {
+        match self {
+            Self::Yamux(state) => state.init,
+            Self::Quic(_) => true,
+        }
+    }
+
+    pub fn next_stream_id(&self, kind: YamuxStreamKind, incoming: bool) -> Option<StreamId> {
+        match self {
+            Self::Yamux(state) => state.next_stream_id(kind, incoming),
+            Self::Quic(state) => state.next_stream_id(kind, incoming),
+        }
+    }
+
+    pub fn next_dynamic_stream_id(&self, incoming: bool) -> Option<StreamId> {
+        match self {
+            Self::Yamux(state) => state.next_dynamic_stream_id(incoming),
+            Self::Quic(state) => state.next_dynamic_stream_id(incoming),
         }
     }
 }
@@ -152,7 +152,7 @@ impl P2pNetworkSelectAction {
                                             );
                                         }
                                     }
-                                    StreamKind::Identify(IdentifyAlgorithm::Identify1_0_0) => {
+                                    StreamKind::Identify(_) => {
                                         if !fin {
                                             //println!("==== {}", hex::encode(&a.data.0));
                                             store.dispatch(
@@ -173,9 +173,6 @@ impl P2pNetworkSelectAction {
                                             );
                                         }
                                     }
-                                    StreamKind::Identify(IdentifyAlgorithm::IdentifyPush1_0_0) => {
-                                        //unimplemented!()
-                                    }
                                     StreamKind::Broadcast(_) => {
                                         store.dispatch(P2pNetworkPubsubAction::IncomingData {
                                             peer_id,
@@ -185,13 +182,61 @@ impl P2pNetworkSelectAction {
                                         });
                                     }
                                     StreamKind::Ping(PingAlgorithm::Ping1_0_0) => {
-                                        //unimplemented!()
+                                        if !fin {
+                                            store.dispatch(P2pNetworkPingAction::IncomingData {
+                                                addr,
+                                                peer_id,
+                                                stream_id,
+                                                data: data.clone(),
+                                            });
+                                        } else {
+                                            store.dispatch(P2pNetworkPingAction::RemoteClose {
+                                                addr,
+                                                peer_id,
+                                                stream_id,
+                                            });
+                                        }
                                     }
                                     StreamKind::Bitswap(_) => {
                                         //unimplemented!()
                                     }
                                     StreamKind::Status(_) => {
-                                        //unimplemented!()
+                                        if !fin {
+                                            store.dispatch(
+                                                P2pNetworkNodeStatusAction::IncomingData {
+                                                    addr,
+                                                    peer_id,
+                                                    stream_id,
+                                                    data: data.clone(),
+                                                },
+                                            );
+                                        } else {
+                                            store.dispatch(
+                                                P2pNetworkNodeStatusAction::RemoteClose {
+                                                    addr,
+                                                    peer_id,
+                                                    stream_id,
+                                                },
+                                            );
+                                        }
+                                    }
+                                    StreamKind::Relay(_) | StreamKind::Dcutr(_) => {
+                                        // the last message of a relay stream may come with FIN
+                                        if !data.is_empty() {
+                                            store.dispatch(P2pNetworkRelayAction::IncomingData {
+                                                addr,
+                                                peer_id,
+                                                stream_id,
+                                                data: data.clone(),
+                                            });
+                                        }
+                                        if fin {
+                                            store.dispatch(P2pNetworkRelayAction::RemoteClose {
+                                                addr,
+                                                peer_id,
+                                                stream_id,
+                                            });
+                                        }
                                     }
                                     StreamKind::Rpc(RpcAlgorithm::Rpc0_0_1) => {
                                         store.dispatch(P2pNetworkRpcAction::IncomingData {
@@ -148,7 +148,9 @@ impl P2pNetworkSelectState {
                                     | token::StreamKind::Identify(_)
                                     | token::StreamKind::Ping(_)
                                     | token::StreamKind::Bitswap(_)
-                                    | token::StreamKind::Status(_),
+                                    | token::StreamKind::Status(_)
+                                    | token::StreamKind::Relay(_)
+                                    | token::StreamKind::Dcutr(_),
                                 ) => token::Token::Protocol(protocol),
                             };
                             let negotiated = if let token::Token::Protocol(p) = &reply {
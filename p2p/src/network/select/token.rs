@@ -68,6 +68,13 @@ impl Token {
             BroadcastAlgorithm::Meshsub1_1_0,
         ))),
         Token::Protocol(Protocol::Stream(StreamKind::Rpc(RpcAlgorithm::Rpc0_0_1))),
+        Token::Protocol(Protocol::Stream(StreamKind::Relay(
+            RelayAlgorithm::Hop0_2_0,
+        ))),
+        Token::Protocol(Protocol::Stream(StreamKind::Relay(
+            RelayAlgorithm::Stop0_2_0,
+        ))),
+        Token::Protocol(Protocol::Stream(StreamKind::Dcutr(DcutrAlgorithm::Dcutr))),
     ];
 }
 
@@ -142,6 +149,8 @@ pub enum StreamKind {
     Discovery(DiscoveryAlgorithm),
     Broadcast(BroadcastAlgorithm),
     Rpc(RpcAlgorithm),
+    Relay(RelayAlgorithm),
+    Dcutr(DcutrAlgorithm),
 }
 
 impl StreamKind {
@@ -154,6 +163,8 @@ impl StreamKind {
             Self::Discovery(v) => v.name(),
             Self::Broadcast(v) => v.name(),
             Self::Rpc(v) => v.name(),
+            Self::Relay(v) => v.name(),
+            Self::Dcutr(v) => v.name(),
         }
     }
 
@@ -166,6 +177,8 @@ impl StreamKind {
             Self::Discovery(v) => v.name_str(),
             Self::Broadcast(v) => v.name_str(),
             Self::Rpc(v) => v.name_str(),
+            Self::Relay(v) => v.name_str(),
+            Self::Dcutr(v) => v.name_str(),
         }
     }
 }
@@ -258,6 +271,47 @@ impl IdentifyAlgorithm {
     }
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum RelayAlgorithm {
+    Hop0_2_0,
+    Stop0_2_0,
+}
+
+impl RelayAlgorithm {
+    pub const fn name(&self) -> &'static [u8] {
+        match self {
+            Self::Hop0_2_0 => b"\x20/libp2p/circuit/relay/0.2.0/hop\n",
+            Self::Stop0_2_0 => b"\x21/libp2p/circuit/relay/0.2.0/stop\n",
+        }
+    }
+
+    pub const fn name_str(&self) -> &'static str {
+        match self {
+            Self::Hop0_2_0 => "/libp2p/circuit/relay/0.2.0/hop",
+            Self::Stop0_2_0 => "/libp2p/circuit/relay/0.2.0/stop",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum DcutrAlgorithm {
+    Dcutr,
+}
+
+impl DcutrAlgorithm {
+    pub const fn name(&self) -> &'static [u8] {
+        match self {
+            Self::Dcutr => b"\x0e/libp2p/dcutr\n",
+        }
+    }
+
+    pub const fn name_str(&self) -> &'static str {
+        match self {
+            Self::Dcutr => "/libp2p/dcutr",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum DiscoveryAlgorithm {
     Kademlia1_0_0,
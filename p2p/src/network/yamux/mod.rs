@@ -3,7 +3,8 @@ pub use self::p2p_network_yamux_actions::*;
 
 mod p2p_network_yamux_state;
 pub use self::p2p_network_yamux_state::{
-    P2pNetworkYamuxState, StreamId, YamuxPing, YamuxStreamKind,
+    P2pNetworkYamuxState, StreamId, YamuxFlags, YamuxFrame, YamuxFrameInner, YamuxPing,
+    YamuxStreamKind, YamuxStreamState, YAMUX_DYNAMIC_STREAM_ID,
 };
 
 mod p2p_network_yamux_reducer;
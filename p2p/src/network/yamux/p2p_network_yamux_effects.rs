@@ -1,12 +1,53 @@
-use self::p2p_network_yamux_state::{YamuxFlags, YamuxFrame, YamuxFrameInner};
+use self::p2p_network_yamux_state::{
+    YamuxFlags, YamuxFrame, YamuxFrameInner, YamuxSessionError, YamuxStreamState,
+};
 
 use super::{super::*, *};
 
 impl P2pNetworkYamuxAction {
-    pub fn effects<Store, S>(self, _meta: &redux::ActionMeta, store: &mut Store)
+    pub fn effects<Store, S>(self, meta: &redux::ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
     {
+        // QUIC connection has native streams, so the stream data bypasses yamux framing
+        if store
+            .state()
+            .network
+            .scheduler
+            .connections
+            .get(self.addr())
+            .map_or(false, |conn| conn.quic)
+        {
+            match self {
+                Self::OutgoingData {
+                    addr,
+                    stream_id,
+                    data,
+                    fin,
+                } => {
+                    store.dispatch(P2pNetworkQuicAction::OutgoingData {
+                        addr,
+                        stream_id,
+                        data,
+                        fin,
+                    });
+                }
+                Self::OpenStream {
+                    addr,
+                    stream_id,
+                    stream_kind,
+                } => {
+                    store.dispatch(P2pNetworkQuicAction::OpenStream {
+                        addr,
+                        stream_id,
+                        stream_kind,
+                    });
+                }
+                _ => {}
+            }
+            return;
+        }
+
         let state = store.state();
         let Some(state) = state.network.scheduler.connections.get(self.addr()) else {
             return;
@@ -30,6 +71,22 @@ impl P2pNetworkYamuxAction {
             }
             Self::IncomingFrame { addr, frame } => {
                 let frame = &frame;
+                // the reducer terminates the session if the data exceeds the window
+                if let (YamuxFrameInner::Data(data), Some(Ok(Err(YamuxSessionError::Protocol)))) =
+                    (&frame.inner, &state.terminated)
+                {
+                    if state
+                        .streams
+                        .get(&frame.stream_id)
+                        .map_or(false, |stream| (stream.window_ours as usize) < data.len())
+                    {
+                        store.dispatch(P2pNetworkSchedulerAction::Error {
+                            addr,
+                            error: P2pNetworkConnectionError::YamuxWindowExceeded(frame.stream_id),
+                        });
+                        return;
+                    }
+                }
                 let Some(stream) = state.streams.get(&frame.stream_id).cloned() else {
                     return;
                 };
@@ -44,14 +101,23 @@ impl P2pNetworkYamuxAction {
                 }
                 match &frame.inner {
                     YamuxFrameInner::Data(data) => {
-                        if stream.window_ours < 64 * 1024 {
+                        // the window is withheld from a throttled peer, so it
+                        // has to slow down instead of us dropping its data
+                        if stream.needs_window_update()
+                            && !store.state().is_stream_throttled(
+                                &addr,
+                                &peer_id,
+                                &frame.stream_id,
+                                meta.time(),
+                            )
+                        {
                             store.dispatch(P2pNetworkYamuxAction::OutgoingFrame {
                                 addr,
                                 frame: YamuxFrame {
                                     stream_id: frame.stream_id,
                                     flags: YamuxFlags::empty(),
                                     inner: YamuxFrameInner::WindowUpdate {
-                                        difference: 256 * 1024,
+                                        difference: YamuxStreamState::WINDOW_UPDATE,
                                     },
                                 },
                             });
@@ -133,9 +133,14 @@ impl P2pNetworkYamuxState {
                     match frame.inner {
                         YamuxFrameInner::Data(data) => {
                             if let Some(stream) = self.streams.get_mut(&frame.stream_id) {
-                                // must not underflow
-                                // TODO: check it and disconnect peer that violates flow rules
-                                stream.window_ours -= data.len() as u32;
+                                match u32::try_from(data.len())
+                                    .ok()
+                                    .and_then(|len| stream.window_ours.checked_sub(len))
+                                {
+                                    Some(window) => stream.window_ours = window,
+                                    // the peer violates the flow control
+                                    None => self.set_res(Err(YamuxSessionError::Protocol)),
+                                }
                             }
                         }
                         YamuxFrameInner::WindowUpdate { difference } => {
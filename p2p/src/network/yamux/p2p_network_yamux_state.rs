@@ -24,6 +24,19 @@ impl P2pNetworkYamuxState {
         }
     }
 
+    /// Returns the lowest unused stream ID for the streams that can be
+    /// opened more than once, e.g. relay streams.
+    pub fn next_dynamic_stream_id(&self, incoming: bool) -> Option<StreamId> {
+        if !self.init || self.terminated.is_some() {
+            return None;
+        }
+        // dialer uses odd IDs, listener uses even ones
+        let first = YAMUX_DYNAMIC_STREAM_ID + 1 + (incoming as StreamId);
+        (first..)
+            .step_by(2)
+            .find(|stream_id| !self.streams.contains_key(stream_id))
+    }
+
     // TODO:
     pub fn consume(&mut self, len: usize) {
         let _ = len;
@@ -61,6 +74,14 @@ impl Default for YamuxStreamState {
 }
 
 impl YamuxStreamState {
+    /// Window increment granted to the peer at once.
+    pub const WINDOW_UPDATE: i32 = 256 * 1024;
+
+    /// The peer is running out of the window to send more data.
+    pub fn needs_window_update(&self) -> bool {
+        self.window_ours < 64 * 1024
+    }
+
     pub fn incoming() -> Self {
         YamuxStreamState {
             incoming: true,
@@ -194,8 +215,15 @@ pub enum YamuxStreamKind {
     Gossipsub,
     Kademlia,
     Identify,
+    NodeStatus,
+    Ping,
+    IdentifyPush,
+    Dcutr,
 }
 
+/// Stream IDs below this one are reserved for [`YamuxStreamKind`].
+pub const YAMUX_DYNAMIC_STREAM_ID: StreamId = 64;
+
 impl YamuxStreamKind {
     pub fn stream_id(self, incoming: bool) -> StreamId {
         (self as StreamId) * 2 + 1 + (incoming as StreamId)
@@ -2,6 +2,7 @@ use openmina_macros::ActionEvent;
 use redux::EnablingCondition;
 use serde::{Deserialize, Serialize};
 
+use super::address_book::P2pAddressBookAction;
 use super::channels::P2pChannelsAction;
 use super::connection::P2pConnectionAction;
 use super::disconnection::P2pDisconnectionAction;
@@ -9,6 +10,7 @@ use super::discovery::P2pDiscoveryAction;
 use super::network::P2pNetworkAction;
 use super::peer::P2pPeerAction;
 use crate::identify::P2pIdentifyAction;
+use crate::nat::P2pNatAction;
 use crate::P2pState;
 
 pub type P2pActionWithMeta = redux::ActionWithMeta<P2pAction>;
@@ -22,6 +24,8 @@ pub enum P2pAction {
     Disconnection(P2pDisconnectionAction),
     Discovery(P2pDiscoveryAction),
     Identify(P2pIdentifyAction),
+    Nat(P2pNatAction),
+    AddressBook(P2pAddressBookAction),
     Channels(P2pChannelsAction),
     Peer(P2pPeerAction),
     Network(P2pNetworkAction),
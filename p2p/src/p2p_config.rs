@@ -3,19 +3,24 @@ use std::{collections::BTreeSet, time::Duration};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts, identity::PublicKey,
+    address_book::P2pAddressBookEntry, channels::ChannelId,
+    connection::outgoing::P2pConnectionOutgoingInitOpts, identity::PublicKey, PeerId,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pConfig {
     /// TCP port where libp2p is listening incoming connections.
     pub libp2p_port: Option<u16>,
+    /// UDP port where libp2p is listening incoming QUIC connections.
+    pub quic_port: Option<u16>,
     /// The HTTP port where signaling server is listening SDP offers and SDP answers.
     pub listen_port: u16,
     /// The public key used for authentication all p2p communication.
     pub identity_pub_key: PublicKey,
     /// A list addresses of seed nodes.
     pub initial_peers: Vec<P2pConnectionOutgoingInitOpts>,
+    /// Peers from the address book persisted by the previous run.
+    pub address_book: Vec<P2pAddressBookEntry>,
 
     /// The time interval that must elapse before the next peer discovery request.
     /// The node periodically polls peers for their connections to keep our list up to date.
@@ -30,6 +35,15 @@ pub struct P2pConfig {
     /// Use peers discovery.
     pub peer_discovery: bool,
 
+    /// Map the libp2p and QUIC ports on the gateway using UPnP or NAT-PMP.
+    pub nat_port_mapping: bool,
+
+    /// Act as a circuit relay for the peers that are not reachable directly.
+    pub relay_hop: bool,
+    /// Relays where the node keeps a reservation, so that it can be reached
+    /// through them.
+    pub relays: Vec<PeerId>,
+
     /// Unix time. Used as an initial nonce for pubsub.
     pub initial_time: Duration,
 }
@@ -47,9 +61,42 @@ pub struct P2pTimeouts {
     pub block: Option<Duration>,
     pub snark: Option<Duration>,
     pub initial_peers: Option<Duration>,
+    pub transition_chain: Option<Duration>,
+    pub transition_chain_proof: Option<Duration>,
+    pub ancestry: Option<Duration>,
+    pub transition_knowledge: Option<Duration>,
     pub kademlia_bootstrap: Option<Duration>,
     pub kademlia_initial_bootstrap: Option<Duration>,
+    /// Time after which a Kademlia record received from a peer expires,
+    /// `None` keeps the records forever.
+    pub kademlia_record_ttl: Option<Duration>,
+    /// Interval between republishing of the node's own Kademlia records,
+    /// `None` publishes them only once.
+    pub kademlia_republish: Option<Duration>,
     pub select: Option<Duration>,
+    /// Interval between the pings sent to a peer, `None` disables pinging.
+    pub ping_interval: Option<Duration>,
+    /// The peer is disconnected if it doesn't respond to the ping in time,
+    /// `None` disables liveness checks.
+    pub ping_timeout: Option<Duration>,
+    /// Interval between the status requests to a peer, `None` disables
+    /// requesting the statuses.
+    pub node_status_interval: Option<Duration>,
+    /// Time to wait before retrying the failed port mapping.
+    pub nat_port_mapping_retry: Option<Duration>,
+    /// External address is not advertised anymore if it wasn't confirmed
+    /// by the gateway or by the peers for this long, observations older than
+    /// that aren't taken into account. `None` keeps the addresses forever.
+    pub nat_external_addr_ttl: Option<Duration>,
+    /// A peer that delivered a block that became our best tip is protected
+    /// from pruning for this long, `None` protects it until it disconnects.
+    pub useful_peer_protection: Option<Duration>,
+    /// Address book entries of the peers that weren't seen for this long
+    /// are removed, `None` keeps them forever.
+    pub address_book_entry_ttl: Option<Duration>,
+    /// Minimal interval between saves of the address book,
+    /// `None` disables persisting it.
+    pub address_book_save_interval: Option<Duration>,
 }
 
 impl Default for P2pTimeouts {
@@ -66,9 +113,23 @@ impl Default for P2pTimeouts {
             block: Some(Duration::from_secs(5)),
             snark: Some(Duration::from_secs(5)),
             initial_peers: Some(Duration::from_secs(5)),
+            transition_chain: Some(Duration::from_secs(20)),
+            transition_chain_proof: Some(Duration::from_secs(5)),
+            ancestry: Some(Duration::from_secs(10)),
+            transition_knowledge: Some(Duration::from_secs(5)),
             kademlia_bootstrap: Some(Duration::from_secs(60)),
             kademlia_initial_bootstrap: Some(Duration::from_secs(5)),
+            kademlia_record_ttl: Some(Duration::from_secs(36 * 60 * 60)),
+            kademlia_republish: Some(Duration::from_secs(22 * 60 * 60)),
             select: Some(Duration::from_secs(5)),
+            ping_interval: Some(Duration::from_secs(15)),
+            ping_timeout: Some(Duration::from_secs(20)),
+            node_status_interval: Some(Duration::from_secs(10 * 60)),
+            nat_port_mapping_retry: Some(Duration::from_secs(5 * 60)),
+            nat_external_addr_ttl: Some(Duration::from_secs(2 * 60 * 60)),
+            useful_peer_protection: Some(Duration::from_secs(15 * 60)),
+            address_book_entry_ttl: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+            address_book_save_interval: Some(Duration::from_secs(60)),
         }
     }
 }
@@ -81,6 +142,10 @@ impl P2pTimeouts {
             staged_ledger_aux_and_pending_coinbases_at_block: None,
             block: None,
             snark: None,
+            transition_chain: None,
+            transition_chain_proof: None,
+            ancestry: None,
+            transition_knowledge: None,
             ..Default::default()
         }
     }
@@ -182,10 +247,20 @@ impls!(std::time::Duration);
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct P2pLimits {
     max_peers: Limit<usize>,
+    max_inbound_peers_per_ip: Limit<usize>,
+    max_inbound_peers_per_subnet: Limit<usize>,
 
     identify_message: Limit<usize>,
     kademlia_request: Limit<usize>,
     kademlia_response: Limit<usize>,
+    node_status_message: Limit<usize>,
+    relay_message: Limit<usize>,
+
+    relay_reservations: Limit<usize>,
+    relay_circuits: Limit<usize>,
+
+    rpc_incoming_requests_rate: Limit<usize>,
+    rpc_incoming_requests_burst: Limit<usize>,
 
     rpc_service_message: Limit<usize>,
     rpc_query: Limit<usize>,
@@ -193,6 +268,9 @@ pub struct P2pLimits {
     rpc_answer_sync_ledger_query: Limit<usize>,
     rpc_get_staged_ledger: Limit<usize>,
     rpc_get_transition_chain: Limit<usize>,
+    rpc_get_transition_chain_proof: Limit<usize>,
+    rpc_get_ancestry: Limit<usize>,
+    rpc_get_transition_knowledge: Limit<usize>,
     rpc_get_some_initial_peers: Limit<usize>,
 }
 
@@ -240,6 +318,37 @@ impl P2pLimits {
         max_connections(&self): self.max_peers.map(|v| v + 10)
     );
 
+    limit!(
+        /// Number of peer slots that incoming connections can't take, so that
+        /// the node always has room for peers it has chosen itself.
+        outbound_peers_reserved(&self): self.max_peers.map(|v| v / 4)
+    );
+    limit!(
+        /// Maximum number of peers connected to us.
+        max_inbound_peers(&self): match self.outbound_peers_reserved() {
+            Limit::Some(reserved) => self.max_peers.map(|v| v - reserved),
+            Limit::Unlimited => Limit::Unlimited,
+        }
+    );
+    limit!(
+        /// Maximum number of peers connected to us using the same transport
+        /// (libp2p or WebRTC).
+        max_inbound_peers_per_transport(&self): self.max_inbound_peers().map(|v| v - v / 3)
+    );
+    limit!(
+        /// Maximum number of peers connected to us from the same IP address.
+        max_inbound_peers_per_ip,
+        /// Sets maximum number of peers connected to us from the same IP address.
+        with_max_inbound_peers_per_ip
+    );
+    limit!(
+        /// Maximum number of peers connected to us from the same subnet
+        /// (`/24` for IPv4, `/48` for IPv6).
+        max_inbound_peers_per_subnet,
+        /// Sets maximum number of peers connected to us from the same subnet.
+        with_max_inbound_peers_per_subnet
+    );
+
     limit!(
         /// Maximum length of Identify message.
         identify_message
@@ -252,6 +361,36 @@ impl P2pLimits {
         /// Maximum length of Kademlia response message.
         kademlia_response
     );
+    limit!(
+        /// Maximum length of node status message.
+        node_status_message
+    );
+    limit!(
+        /// Maximum length of circuit relay and DCUtR messages.
+        relay_message
+    );
+
+    limit!(
+        /// Maximum number of peers with a reservation on this relay.
+        relay_reservations
+    );
+    limit!(
+        /// Maximum number of circuits relayed at the same time.
+        relay_circuits
+    );
+
+    limit!(
+        /// Maximum average number of RPC requests per second accepted from a peer.
+        rpc_incoming_requests_rate,
+        /// Sets maximum average number of RPC requests per second accepted from a peer.
+        with_rpc_incoming_requests_rate
+    );
+    limit!(
+        /// Number of RPC requests that a peer can send at once above the rate.
+        rpc_incoming_requests_burst,
+        /// Sets number of RPC requests that a peer can send at once above the rate.
+        with_rpc_incoming_requests_burst
+    );
 
     limit!(
         #[doc = "RPC service message"]
@@ -277,6 +416,18 @@ impl P2pLimits {
         #[doc = "RPC get_transition_chain"]
         rpc_get_transition_chain
     );
+    limit!(
+        #[doc = "RPC get_transition_chain_proof"]
+        rpc_get_transition_chain_proof
+    );
+    limit!(
+        #[doc = "RPC get_ancestry"]
+        rpc_get_ancestry
+    );
+    limit!(
+        #[doc = "RPC get_transition_knowledge"]
+        rpc_get_transition_knowledge
+    );
     limit!(
         #[doc = "RPC some_initial_peers"]
         rpc_get_some_initial_peers
@@ -286,24 +437,47 @@ impl P2pLimits {
 impl Default for P2pLimits {
     fn default() -> Self {
         let max_peers = Limit::Some(100);
+        let max_inbound_peers_per_ip = Limit::Some(4);
+        let max_inbound_peers_per_subnet = Limit::Some(8);
 
         let identify_message = Limit::Some(0x1000);
-        let kademlia_request = Limit::Some(50);
+        let kademlia_request = identify_message; // `ADD_PROVIDER` and `PUT_VALUE` carry addresses and values
         let kademlia_response = identify_message.map(|v| v * 20); // should be enough to fit 20 addresses supplied by identify
+        let node_status_message = Limit::Some(1024 * 1024);
+        let relay_message = Limit::Some(4096); // same as go-libp2p
+
+        let relay_reservations = Limit::Some(128);
+        let relay_circuits = Limit::Some(16);
+
+        let rpc_incoming_requests_rate = Limit::Some(50);
+        let rpc_incoming_requests_burst = Limit::Some(200);
 
         let rpc_service_message = Limit::Some(7); // 7 for handshake, 1 for heartbeat
         let rpc_query = Limit::Some(256); // max is 96
         let rpc_get_best_tip = Limit::Some(3_500_000); // 3182930 as observed, may vary
         let rpc_answer_sync_ledger_query = Limit::Some(200_000); // 124823 as observed
         let rpc_get_staged_ledger = Limit::Some(40_000_000); // 36371615 as observed, may vary
-        let rpc_get_transition_chain = Limit::Some(3_500_000); // 2979112 as observed
+        let rpc_get_transition_chain = Limit::Some(16 * 3_500_000); // 2979112 per block as observed, up to 16 blocks per request
+        let rpc_get_transition_chain_proof = Limit::Some(12_000); // up to `k` body hashes
+        let rpc_get_ancestry = rpc_get_best_tip; // same data as `get_best_tip`
+        let rpc_get_transition_knowledge = Limit::Some(12_000); // up to `k + 1` state hashes
         let rpc_get_some_initial_peers = Limit::Some(32_000); // TODO: calculate
         Self {
             max_peers,
+            max_inbound_peers_per_ip,
+            max_inbound_peers_per_subnet,
 
             identify_message,
             kademlia_request,
             kademlia_response,
+            node_status_message,
+            relay_message,
+
+            relay_reservations,
+            relay_circuits,
+
+            rpc_incoming_requests_rate,
+            rpc_incoming_requests_burst,
 
             rpc_service_message,
             rpc_query,
@@ -311,6 +485,9 @@ impl Default for P2pLimits {
             rpc_answer_sync_ledger_query,
             rpc_get_staged_ledger,
             rpc_get_transition_chain,
+            rpc_get_transition_chain_proof,
+            rpc_get_ancestry,
+            rpc_get_transition_knowledge,
             rpc_get_some_initial_peers,
         }
     }
@@ -332,4 +509,24 @@ mod tests {
         assert!(0 < unlimited);
         assert!(usize::MAX < unlimited);
     }
+
+    #[test]
+    fn test_peer_slots() {
+        let limits = super::P2pLimits::default().with_max_peers(Some(100));
+        assert_eq!(limits.outbound_peers_reserved(), 25);
+        assert_eq!(limits.max_inbound_peers(), 75);
+        assert_eq!(limits.max_inbound_peers_per_transport(), 50);
+
+        let limits = limits.with_max_peers(Some(1));
+        assert_eq!(limits.max_inbound_peers(), 1);
+        assert_eq!(limits.max_inbound_peers_per_transport(), 1);
+
+        let limits = limits.with_max_peers(Limit::Unlimited);
+        assert!(matches!(limits.outbound_peers_reserved(), Limit::Unlimited));
+        assert!(matches!(limits.max_inbound_peers(), Limit::Unlimited));
+        assert!(matches!(
+            limits.max_inbound_peers_per_transport(),
+            Limit::Unlimited
+        ));
+    }
 }
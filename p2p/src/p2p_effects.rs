@@ -1,13 +1,19 @@
+use std::net::{IpAddr, SocketAddr};
+
 use redux::{ActionMeta, ActionWithMeta};
 
 use crate::{
+    address_book::{P2pAddressBookAction, P2pAddressBookService},
     channels::{P2pChannelsAction, P2pChannelsService},
     connection::{
         outgoing::P2pConnectionOutgoingAction, P2pConnectionAction, P2pConnectionService,
     },
-    disconnection::P2pDisconnectionService,
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason, P2pDisconnectionService},
+    nat::{P2pNatAction, P2pNatService, P2pNatTransport},
     P2pAction, P2pCryptoService, P2pMioService, P2pNetworkKadKey, P2pNetworkKademliaAction,
-    P2pNetworkSelectAction, P2pNetworkService, P2pStore, PeerId,
+    P2pNetworkNodeStatusAction, P2pNetworkPingAction, P2pNetworkRelayAction,
+    P2pNetworkSelectAction, P2pNetworkService, P2pNetworkYamuxAction, P2pQuicService, P2pStore,
+    PeerId, YamuxFlags, YamuxFrame, YamuxFrameInner, YamuxStreamState,
 };
 
 pub fn p2p_timeout_effects<Store, S>(store: &mut Store, meta: &ActionMeta)
@@ -18,9 +24,17 @@ where
     store.dispatch(P2pConnectionOutgoingAction::RandomInit);
 
     p2p_try_reconnect_disconnected_peers(store, meta.time());
+    p2p_prune_peers(store, meta.time());
+    p2p_resume_throttled_streams(store, meta.time());
 
     p2p_discovery(store, meta);
     p2p_select_timeouts(store, meta);
+    p2p_ping(store, meta);
+    p2p_node_status(store, meta);
+    p2p_nat(store, meta);
+    p2p_relay(store, meta);
+
+    store.dispatch(P2pAddressBookAction::Save);
 
     let state = store.state();
     for (peer_id, id) in state.peer_rpc_timeouts(meta.time()) {
@@ -106,6 +120,129 @@ where
     }
 }
 
+fn p2p_ping<Store, S>(store: &mut Store, meta: &ActionMeta)
+where
+    Store: P2pStore<S>,
+{
+    let now = meta.time();
+    let state = store.state();
+    let timeouts = &state.config.timeouts;
+    let ping_state = &state.network.scheduler.ping_state;
+    let mut timed_out = Vec::new();
+    let mut to_ping = Vec::new();
+    for (addr, conn) in &state.network.scheduler.connections {
+        let Some(peer_id) = conn.peer_id() else {
+            continue;
+        };
+        if conn.mux.is_none() || conn.closed.is_some() || state.get_ready_peer(peer_id).is_none() {
+            continue;
+        }
+        match ping_state.peers.get(peer_id) {
+            Some(peer) if peer.is_timed_out(now, timeouts) => timed_out.push(*peer_id),
+            Some(peer) if !peer.should_ping(now, timeouts) => {}
+            _ if timeouts.ping_interval.is_none() => {}
+            _ => to_ping.push((*addr, *peer_id)),
+        }
+    }
+
+    for peer_id in timed_out {
+        store.dispatch(P2pNetworkPingAction::Timeout { peer_id });
+    }
+    for (addr, peer_id) in to_ping {
+        store.dispatch(P2pNetworkPingAction::Ping { addr, peer_id });
+    }
+}
+
+fn p2p_node_status<Store, S>(store: &mut Store, meta: &ActionMeta)
+where
+    Store: P2pStore<S>,
+{
+    let now = meta.time();
+    let state = store.state();
+    let timeouts = &state.config.timeouts;
+    let node_status = &state.network.scheduler.node_status_state;
+    let to_request = state
+        .network
+        .scheduler
+        .connections
+        .iter()
+        .filter(|(_, conn)| conn.mux.is_some() && conn.closed.is_none())
+        .filter_map(|(addr, conn)| Some((*addr, *conn.peer_id()?)))
+        .filter(|(_, peer_id)| state.get_ready_peer(peer_id).is_some())
+        .filter(|(_, peer_id)| node_status.should_request(peer_id, now, timeouts))
+        .collect::<Vec<_>>();
+    for (addr, peer_id) in to_request {
+        store.dispatch(P2pNetworkNodeStatusAction::Request { addr, peer_id });
+    }
+}
+
+fn p2p_nat<Store, S>(store: &mut Store, meta: &ActionMeta)
+where
+    Store: P2pStore<S>,
+{
+    let state = store.state();
+    let expired = state
+        .nat
+        .expired_external_addrs(meta.time(), &state.config.timeouts)
+        .collect::<Vec<_>>();
+    for addr in expired {
+        store.dispatch(P2pNatAction::ExternalAddrExpired { addr });
+    }
+
+    for transport in P2pNatTransport::ALL {
+        let state = store.state();
+        if !state
+            .nat
+            .should_map_port(transport, meta.time(), &state.config)
+        {
+            continue;
+        }
+        // the gateway can forward the port only to the address in its local network
+        let is_local = |addr: &SocketAddr| matches!(addr.ip(), IpAddr::V4(ip) if ip.is_private());
+        let scheduler = &state.network.scheduler;
+        let internal = match transport {
+            P2pNatTransport::Tcp => scheduler.listeners.iter().copied().find(is_local),
+            P2pNatTransport::Udp => scheduler.quic_state.listener().filter(is_local),
+        };
+        if let Some(internal) = internal {
+            store.dispatch(P2pNatAction::MapPort {
+                transport,
+                internal,
+            });
+        }
+    }
+}
+
+fn p2p_relay<Store, S>(store: &mut Store, meta: &ActionMeta)
+where
+    Store: P2pStore<S>,
+{
+    let now = meta.time();
+    let state = store.state();
+    let relay_state = &state.network.scheduler.relay_state;
+    let relays = state.config.relays.clone();
+    let expired = relay_state.expired_circuits(now).collect::<Vec<_>>();
+    let upgrades = relay_state
+        .upgrades
+        .iter()
+        .map(|(peer_id, upgrade)| (*peer_id, upgrade.addr))
+        .collect::<Vec<_>>();
+
+    for relay_peer_id in relays {
+        store.dispatch(P2pNetworkRelayAction::Reserve { relay_peer_id });
+    }
+    for (addr, peer_id, stream_id) in expired {
+        store.dispatch(P2pNetworkRelayAction::Close {
+            addr,
+            peer_id,
+            stream_id,
+        });
+    }
+    for (peer_id, addr) in upgrades {
+        store.dispatch(P2pNetworkRelayAction::DcutrDial { peer_id, addr });
+    }
+}
+
 fn p2p_connection_timeouts<Store, S>(store: &mut Store, meta: &ActionMeta)
 where
     Store: P2pStore<S>,
@@ -139,7 +276,7 @@ fn p2p_try_reconnect_disconnected_peers<Store, S>(store: &mut Store, now: redux:
 where
     Store: P2pStore<S>,
 {
-    if store.state().already_has_min_peers() {
+    if !store.state().needs_outgoing_peers() {
         return;
     }
     let timeouts = &store.state().config.timeouts;
@@ -161,6 +298,36 @@ where
     }
 }
 
+fn p2p_prune_peers<Store, S>(store: &mut Store, now: redux::Timestamp)
+where
+    Store: P2pStore<S>,
+{
+    for peer_id in store.state().peers_to_prune(now) {
+        store.dispatch(P2pDisconnectionAction::Init {
+            peer_id,
+            reason: P2pDisconnectionReason::Pruned,
+        });
+    }
+}
+
+fn p2p_resume_throttled_streams<Store, S>(store: &mut Store, now: redux::Timestamp)
+where
+    Store: P2pStore<S>,
+{
+    for (addr, stream_id) in store.state().yamux_streams_to_resume(now) {
+        store.dispatch(P2pNetworkYamuxAction::OutgoingFrame {
+            addr,
+            frame: YamuxFrame {
+                stream_id,
+                flags: YamuxFlags::empty(),
+                inner: YamuxFrameInner::WindowUpdate {
+                    difference: YamuxStreamState::WINDOW_UPDATE,
+                },
+            },
+        });
+    }
+}
+
 fn p2p_discovery<Store, S>(store: &mut Store, meta: &redux::ActionMeta)
 where
     Store: P2pStore<S>,
@@ -187,6 +354,9 @@ where
             store.dispatch(P2pNetworkKademliaAction::StartBootstrap { key });
         }
     }
+
+    store.dispatch(P2pNetworkKademliaAction::RepublishRecords);
+    store.dispatch(P2pNetworkKademliaAction::PruneRecords);
 }
 
 pub fn p2p_effects<Store, S>(store: &mut Store, action: ActionWithMeta<P2pAction>)
@@ -196,8 +366,11 @@ where
         + P2pDisconnectionService
         + P2pChannelsService
         + P2pMioService
+        + P2pQuicService
         + P2pCryptoService
-        + P2pNetworkService,
+        + P2pNetworkService
+        + P2pNatService
+        + P2pAddressBookService,
 {
     let (action, meta) = action.split();
     match action {
@@ -211,11 +384,14 @@ where
         P2pAction::Disconnection(action) => action.effects(&meta, store),
         P2pAction::Discovery(action) => action.effects(&meta, store),
         P2pAction::Identify(action) => action.effects(&meta, store),
+        P2pAction::Nat(action) => action.effects(&meta, store),
+        P2pAction::AddressBook(action) => action.effects(&meta, store),
         P2pAction::Channels(action) => match action {
             P2pChannelsAction::MessageReceived(action) => action.effects(&meta, store),
             P2pChannelsAction::BestTip(action) => action.effects(&meta, store),
             P2pChannelsAction::Snark(action) => action.effects(&meta, store),
             P2pChannelsAction::SnarkJobCommitment(action) => action.effects(&meta, store),
+            P2pChannelsAction::Signaling(action) => action.effects(&meta, store),
             P2pChannelsAction::Rpc(action) => action.effects(&meta, store),
         },
         P2pAction::Peer(action) => action.effects(&meta, store),
@@ -3,6 +3,9 @@ use std::fmt;
 #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
 use std::net::{IpAddr, SocketAddr};
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
+use crate::StreamId;
+
 use derive_more::From;
 use openmina_core::snark::Snark;
 use serde::{Deserialize, Serialize};
@@ -10,6 +13,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     channels::{ChannelId, ChannelMsg, MsgId},
     connection::P2pConnectionResponse,
+    nat::{P2pNatPortMapping, P2pNatTransport},
+    webrtc::IceCandidatePair,
     PeerId,
 };
 
@@ -18,8 +23,11 @@ use crate::{
 pub enum P2pEvent {
     Connection(P2pConnectionEvent),
     Channel(P2pChannelEvent),
+    Nat(P2pNatEvent),
     #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
     MioEvent(MioEvent),
+    #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
+    QuicEvent(QuicEvent),
 }
 
 /// The mio service reports events.
@@ -54,15 +62,45 @@ pub enum MioEvent {
     ConnectionDidClose(SocketAddr, Result<(), String>),
 }
 
+/// The QUIC service reports events.
+#[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum QuicEvent {
+    /// Started listening on a local UDP port.
+    ListenerReady { listener: SocketAddr },
+    /// Error listening on a local UDP port.
+    ListenerError { listener: SocketAddr, error: String },
+
+    /// The remote peer connected to us, its id is taken from the TLS certificate.
+    IncomingConnectionDidAccept(SocketAddr, Result<PeerId, String>),
+    /// We connected to the remote peer by the address.
+    OutgoingConnectionDidConnect(SocketAddr, Result<PeerId, String>),
+
+    /// The remote peer opened a new stream.
+    IncomingStreamDidAccept(SocketAddr, StreamId),
+    /// We received the data from the stream, the flag is set when the remote peer finished it.
+    IncomingDataDidReceive(SocketAddr, StreamId, Result<(crate::Data, bool), String>),
+
+    /// The remote peer is disconnected gracefully or with an error.
+    ConnectionDidClose(SocketAddr, Result<(), String>),
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pConnectionEvent {
     OfferSdpReady(PeerId, Result<String, String>),
     AnswerSdpReady(PeerId, Result<String, String>),
     AnswerReceived(PeerId, P2pConnectionResponse),
     Finalized(PeerId, Result<(), String>),
+    /// ICE selected the candidate pair used by the connection.
+    CandidatePairSelected(PeerId, IceCandidatePair),
     Closed(PeerId),
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pNatEvent {
+    PortMapped(P2pNatTransport, Result<P2pNatPortMapping, String>),
+}
+
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pChannelEvent {
     Opened(PeerId, ChannelId, Result<(), String>),
@@ -85,8 +123,11 @@ impl fmt::Display for P2pEvent {
         match self {
             Self::Connection(v) => v.fmt(f),
             Self::Channel(v) => v.fmt(f),
+            Self::Nat(v) => v.fmt(f),
             #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
             Self::MioEvent(v) => v.fmt(f),
+            #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
+            Self::QuicEvent(v) => v.fmt(f),
         }
     }
 }
@@ -113,15 +154,35 @@ impl fmt::Display for P2pConnectionEvent {
                 }
             },
             Self::Finalized(peer_id, res) => write!(f, "Finalized, {peer_id}, {}", res_kind(res)),
+            Self::CandidatePairSelected(peer_id, candidate_pair) => {
+                write!(f, "CandidatePairSelected, {peer_id}, {candidate_pair}")
+            }
             Self::Closed(peer_id) => write!(f, "Closed, {peer_id}"),
         }
     }
 }
 
+impl fmt::Display for P2pNatEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Nat, ")?;
+        match self {
+            Self::PortMapped(transport, Ok(mapping)) => write!(
+                f,
+                "PortMapped, {transport}, {}, {} -> {}",
+                mapping.protocol, mapping.external, mapping.internal
+            ),
+            Self::PortMapped(transport, Err(error)) => {
+                write!(f, "PortMapped, {transport}, Err, {error}")
+            }
+        }
+    }
+}
+
 impl fmt::Display for P2pChannelEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use crate::channels::best_tip::BestTipPropagationChannelMsg;
         use crate::channels::rpc::RpcChannelMsg;
+        use crate::channels::signaling::{SignalingChannelMsg, SignalingResponse};
         use crate::channels::snark::SnarkPropagationChannelMsg;
         use crate::channels::snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg;
 
@@ -199,6 +260,24 @@ impl fmt::Display for P2pChannelEvent {
                             commitment.job_id
                         ),
                     },
+                    ChannelMsg::Signaling(v) => match v {
+                        SignalingChannelMsg::Offer(offer) => write!(
+                            f,
+                            "Offer, from: {}, to: {}",
+                            offer.identity_pub_key.peer_id(),
+                            offer.target_peer_id
+                        ),
+                        SignalingChannelMsg::Answer { from, to, response } => {
+                            write!(f, "Answer, from: {from}, to: {to}, ")?;
+                            match response {
+                                SignalingResponse::Accepted(_) => write!(f, "Accepted"),
+                                SignalingResponse::Rejected(reason) => {
+                                    write!(f, "Rejected, {reason}")
+                                }
+                                SignalingResponse::InternalError => write!(f, "InternalError"),
+                            }
+                        }
+                    },
                     ChannelMsg::Rpc(v) => match v {
                         RpcChannelMsg::Request(id, req) => {
                             write!(f, "Request, id: {id}, {req}")
@@ -254,3 +333,35 @@ impl fmt::Display for MioEvent {
         }
     }
 }
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
+impl fmt::Display for QuicEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Quic, ")?;
+        match self {
+            Self::ListenerReady { listener } => write!(f, "ListenerReady, {listener}"),
+            Self::ListenerError { listener, error } => {
+                write!(f, "ListenerError, {listener}, {error}")
+            }
+            Self::IncomingConnectionDidAccept(addr, res) => {
+                write!(f, "IncomingConnectionDidAccept, {addr}, {}", res_kind(res))
+            }
+            Self::OutgoingConnectionDidConnect(addr, res) => {
+                write!(f, "OutgoingConnectionDidConnect, {addr}, {}", res_kind(res))
+            }
+            Self::IncomingStreamDidAccept(addr, stream_id) => {
+                write!(f, "IncomingStreamDidAccept, {addr}, {stream_id}")
+            }
+            Self::IncomingDataDidReceive(addr, stream_id, res) => {
+                write!(
+                    f,
+                    "IncomingDataDidReceive, {addr}, {stream_id}, {}",
+                    res_kind(res)
+                )
+            }
+            Self::ConnectionDidClose(addr, res) => {
+                write!(f, "ConnectionDidClose, {addr}, {}", res_kind(res))
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@ use crate::connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutg
 use crate::connection::{p2p_connection_reducer, P2pConnectionAction, P2pConnectionState};
 use crate::disconnection::P2pDisconnectionAction;
 use crate::discovery::p2p_discovery_reducer;
-use crate::peer::p2p_peer_reducer;
+use crate::peer::{p2p_peer_reducer, P2pPeerAction};
 use crate::webrtc::{HttpSignalingInfo, SignalingMethod};
 use crate::{P2pAction, P2pActionWithMetaRef, P2pPeerState, P2pPeerStatus, P2pState};
 
@@ -28,6 +28,7 @@ impl P2pState {
                         dial_opts: Some(opts.clone()),
                         status: P2pPeerStatus::Connecting(P2pConnectionState::outgoing_init(opts)),
                         identify: None,
+                        rtt: None,
                     }),
                     P2pConnectionAction::Incoming(P2pConnectionIncomingAction::Init {
                         opts,
@@ -42,6 +43,9 @@ impl P2pState {
                                         port: opts.offer.listen_port,
                                     })
                                 }
+                                IncomingSignalingMethod::P2p { relay_peer_id } => {
+                                    SignalingMethod::P2p { relay_peer_id }
+                                }
                             };
                             Some(P2pConnectionOutgoingInitOpts::WebRTC {
                                 peer_id: *peer_id,
@@ -50,6 +54,7 @@ impl P2pState {
                         },
                         status: P2pPeerStatus::Connecting(P2pConnectionState::incoming_init(opts)),
                         identify: None,
+                        rtt: None,
                     }),
                     P2pConnectionAction::Incoming(
                         P2pConnectionIncomingAction::FinalizePendingLibp2p { .. },
@@ -60,6 +65,7 @@ impl P2pState {
                             // correct status later set in the child reducer.
                             status: P2pPeerStatus::Disconnected { time: meta.time() },
                             identify: None,
+                            rtt: None,
                         })
                     }
                     _ => match self.peers.get_mut(peer_id) {
@@ -68,6 +74,15 @@ impl P2pState {
                     },
                 };
                 p2p_connection_reducer(peer, my_id, meta.with_action(action));
+
+                if let P2pConnectionAction::Outgoing(
+                    P2pConnectionOutgoingAction::Error { peer_id, .. }
+                    | P2pConnectionOutgoingAction::FinalizeError { peer_id, .. }
+                    | P2pConnectionOutgoingAction::Timeout { peer_id },
+                ) = action
+                {
+                    self.address_book.peer_failed(peer_id);
+                }
             }
             P2pAction::Disconnection(action) => match action {
                 P2pDisconnectionAction::Init { .. } => {}
@@ -86,10 +101,19 @@ impl P2pState {
                         return;
                     };
                     peer.status = P2pPeerStatus::Disconnected { time: meta.time() };
+                    self.address_book.peer_seen(peer_id, meta.time());
                 }
             },
             P2pAction::Peer(action) => {
                 p2p_peer_reducer(self, meta.with_action(action));
+
+                if let P2pPeerAction::Ready { peer_id, .. } = action {
+                    let dial_opts = self.peers.get(peer_id).and_then(|p| p.dial_opts.as_ref());
+                    if let Some(dial_opts) = dial_opts {
+                        self.address_book
+                            .peer_connected(*peer_id, dial_opts, meta.time());
+                    }
+                }
             }
             P2pAction::Channels(action) => {
                 let Some(peer_id) = action.peer_id() else {
@@ -104,18 +128,29 @@ impl P2pState {
                 p2p_discovery_reducer(self, meta.with_action(action));
             }
             P2pAction::Identify(action) => match action {
-                crate::identify::P2pIdentifyAction::NewRequest { .. } => {}
+                crate::identify::P2pIdentifyAction::NewRequest { .. }
+                | crate::identify::P2pIdentifyAction::Push { .. } => {}
                 crate::identify::P2pIdentifyAction::UpdatePeerInformation { peer_id, info } => {
                     if let Some(peer) = self.peers.get_mut(peer_id) {
                         peer.identify = Some(info.clone());
                     } else {
                         unreachable!()
                     }
+                    self.address_book
+                        .peer_identified(*peer_id, &info.listen_addrs, meta.time());
                 }
             },
-            P2pAction::Network(action) => self
-                .network
-                .reducer(meta.with_action(action), &self.config.limits),
+            P2pAction::Nat(action) => self
+                .nat
+                .reducer(meta.with_action(action), &self.config.timeouts),
+            P2pAction::AddressBook(action) => self
+                .address_book
+                .reducer(meta.with_action(action), &self.config.timeouts),
+            P2pAction::Network(action) => self.network.reducer(
+                meta.with_action(action),
+                &self.config.limits,
+                &self.config.timeouts,
+            ),
         }
     }
 }
@@ -3,16 +3,20 @@ use openmina_core::{block::ArcBlockWithHash, ChainId};
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
 use openmina_core::requests::RpcId;
 
+use crate::address_book::P2pAddressBook;
 use crate::channels::rpc::P2pRpcId;
 use crate::channels::{ChannelId, P2pChannelsState};
 use crate::connection::incoming::P2pConnectionIncomingState;
 use crate::connection::outgoing::{P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingState};
+use crate::nat::P2pNatState;
 use crate::network::identify::P2pNetworkIdentify;
 use crate::network::P2pNetworkState;
-use crate::{is_time_passed, Limit, P2pTimeouts, PeerId};
+use crate::webrtc::IceCandidatePair;
+use crate::{is_time_passed, Limit, P2pNetworkKadRecordKey, P2pTimeouts, PeerId};
 
 use super::connection::P2pConnectionState;
 use super::P2pConfig;
@@ -22,6 +26,8 @@ pub struct P2pState {
     pub config: P2pConfig,
     pub network: P2pNetworkState,
     pub peers: BTreeMap<PeerId, P2pPeerState>,
+    pub nat: P2pNatState,
+    pub address_book: P2pAddressBook,
 }
 
 impl P2pState {
@@ -33,9 +39,17 @@ impl P2pState {
             .collect();
 
         let my_id = config.identity_pub_key.peer_id();
-        let initial_peers = config
-            .initial_peers
-            .iter()
+        let address_book = P2pAddressBook::new(
+            config.address_book.iter().cloned(),
+            Timestamp::new(config.initial_time.as_nanos() as u64),
+            &config.timeouts,
+        );
+        // peers from the address book go first, so that the explicitly
+        // configured dial options of the same peer take precedence.
+        let initial_peers = address_book
+            .best_entries()
+            .map(|entry| &entry.dial_opts)
+            .chain(config.initial_peers.iter())
             .filter(|peer| peer.peer_id() != &my_id);
 
         let known_peers = initial_peers
@@ -47,6 +61,14 @@ impl P2pState {
                     None
                 }
             })
+            // addresses the peers reported before the restart
+            .chain(address_book.entries.values().flat_map(|entry| {
+                entry
+                    .listen_addrs
+                    .iter()
+                    .map(|maddr| (entry.peer_id, maddr.clone()))
+            }))
+            .filter(|(peer_id, _)| peer_id != &my_id)
             .collect();
 
         let peers = initial_peers
@@ -60,6 +82,7 @@ impl P2pState {
                             time: Timestamp::ZERO,
                         },
                         identify: None,
+                        rtt: None,
                     },
                 )
             })
@@ -76,6 +99,8 @@ impl P2pState {
             config,
             network,
             peers,
+            nat: Default::default(),
+            address_book,
         }
     }
 
@@ -132,6 +157,27 @@ impl P2pState {
             .map(|(peer_id, p)| (*peer_id, p.channels.rpc.next_local_rpc_id()))
     }
 
+    /// Ready peers that can be sent an RPC request, the ones with the lowest
+    /// round-trip time first.
+    pub fn ready_rpc_peers_by_rtt(&self) -> Vec<(PeerId, P2pRpcId)> {
+        let mut peers = self
+            .peers
+            .iter()
+            .filter_map(|(peer_id, p)| {
+                let ready = p.status.as_ready()?;
+                ready.channels.rpc.can_send_request().then(|| {
+                    let rpc_id = ready.channels.rpc.next_local_rpc_id();
+                    ((*peer_id, rpc_id), p.rtt, ready.connected_since)
+                })
+            })
+            .collect::<Vec<_>>();
+        // peers with unknown rtt go last, most recently connected first
+        peers.sort_by_key(|(_, rtt, connected_since)| {
+            (rtt.is_none(), *rtt, std::cmp::Reverse(*connected_since))
+        });
+        peers.into_iter().map(|(peer, ..)| peer).collect()
+    }
+
     pub fn ready_peers(&self) -> Vec<PeerId> {
         self.peers
             .iter()
@@ -212,6 +258,21 @@ impl P2pState {
         self.config.limits.min_peers()
     }
 
+    /// Peers that announced themselves as providers for the `key` via Kademlia.
+    pub fn kad_providers(&self, key: &P2pNetworkKadRecordKey, now: Timestamp) -> BTreeSet<PeerId> {
+        self.network
+            .scheduler
+            .discovery_state()
+            .map(|discovery_state| {
+                discovery_state
+                    .records
+                    .providers(key, now, self.config.timeouts.kademlia_record_ttl)
+                    .map(|entry| entry.peer_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Peer with libp2p connection identified by `conn_id`.
     pub fn peer_with_connection(
         &self,
@@ -219,11 +280,14 @@ impl P2pState {
     ) -> Option<(PeerId, P2pPeerState)> {
         self.peers
             .iter()
-            .find(|(_, peer_state)| match &peer_state.dial_opts {
-                Some(P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts)) => {
-                    libp2p_opts.matches_socket_addr(conn_id)
-                }
-                _ => false,
+            .find(|(_, peer_state)| {
+                peer_state
+                    .dial_opts
+                    .as_ref()
+                    .and_then(P2pConnectionOutgoingInitOpts::libp2p_opts)
+                    .map_or(false, |libp2p_opts| {
+                        libp2p_opts.matches_socket_addr(conn_id)
+                    })
             })
             .or_else(|| {
                 self.network
@@ -246,6 +310,8 @@ pub struct P2pPeerState {
     pub dial_opts: Option<P2pConnectionOutgoingInitOpts>,
     pub status: P2pPeerStatus,
     pub identify: Option<P2pNetworkIdentify>,
+    /// Round-trip time measured by the latest ping.
+    pub rtt: Option<Duration>,
 }
 
 impl P2pPeerState {
@@ -341,6 +407,10 @@ pub struct P2pPeerStatusReady {
     pub connected_since: redux::Timestamp,
     pub channels: P2pChannelsState,
     pub best_tip: Option<ArcBlockWithHash>,
+    /// Last time the peer delivered a block that became our best tip.
+    pub last_useful_block: Option<redux::Timestamp>,
+    /// Candidate pair selected by ICE, only for WebRTC connections.
+    pub ice_candidate_pair: Option<IceCandidatePair>,
 }
 
 impl P2pPeerStatusReady {
@@ -354,6 +424,15 @@ impl P2pPeerStatusReady {
             connected_since: time,
             channels: P2pChannelsState::new(enabled_channels),
             best_tip: None,
+            last_useful_block: None,
+            ice_candidate_pair: None,
         }
     }
+
+    /// The peer recently delivered a useful block, so it shouldn't be pruned.
+    pub fn is_protected(&self, now: redux::Timestamp, timeouts: &P2pTimeouts) -> bool {
+        self.last_useful_block.map_or(false, |time| {
+            !is_time_passed(now, time, timeouts.useful_peer_protection)
+        })
+    }
 }
@@ -1,13 +1,17 @@
+use std::time::Duration;
+
 use openmina_core::{block::ArcBlockWithHash, ActionEvent};
 use serde::{Deserialize, Serialize};
 
-use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, P2pState, PeerId};
+use crate::{
+    connection::outgoing::P2pConnectionOutgoingInitOpts, webrtc::IceCandidatePair, P2pState, PeerId,
+};
 
 pub type P2pPeerActionWithMeta = redux::ActionWithMeta<P2pPeerAction>;
 pub type P2pPeerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pPeerAction>;
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
-#[action_event(level = info, fields(display(peer_id), best_tip = display(&best_tip.hash), incoming))]
+#[action_event(level = info, fields(display(peer_id), best_tip = display(&best_tip.hash), incoming, debug(rtt)))]
 pub enum P2pPeerAction {
     /// Peer is discovered.
     Discovered {
@@ -21,6 +25,15 @@ pub enum P2pPeerAction {
         peer_id: PeerId,
         best_tip: ArcBlockWithHash,
     },
+    /// Round-trip time to the peer is measured.
+    RttUpdate { peer_id: PeerId, rtt: Duration },
+    /// Peer delivered a block that became our best tip.
+    UsefulBlockReceived { peer_id: PeerId },
+    /// ICE candidate pair of the WebRTC connection to the peer is selected.
+    IceCandidatePairSelected {
+        peer_id: PeerId,
+        candidate_pair: IceCandidatePair,
+    },
 }
 
 impl P2pPeerAction {
@@ -29,6 +42,9 @@ impl P2pPeerAction {
             Self::Discovered { peer_id, .. } => peer_id,
             Self::Ready { peer_id, .. } => peer_id,
             Self::BestTipUpdate { peer_id, .. } => peer_id,
+            Self::RttUpdate { peer_id, .. } => peer_id,
+            Self::UsefulBlockReceived { peer_id } => peer_id,
+            Self::IceCandidatePairSelected { peer_id, .. } => peer_id,
         }
     }
 }
@@ -49,6 +65,11 @@ impl redux::EnablingCondition<P2pState> for P2pPeerAction {
                 // best tip.
                 state.get_ready_peer(peer_id).is_some()
             }
+            Self::RttUpdate { peer_id, .. }
+            | Self::UsefulBlockReceived { peer_id }
+            | Self::IceCandidatePairSelected { peer_id, .. } => {
+                state.get_ready_peer(peer_id).is_some()
+            }
         }
     }
 }
@@ -1,7 +1,8 @@
 use redux::ActionMeta;
 
 use crate::channels::{
-    best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction, snark::P2pChannelsSnarkAction,
+    best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction,
+    signaling::P2pChannelsSignalingAction, snark::P2pChannelsSnarkAction,
     snark_job_commitment::P2pChannelsSnarkJobCommitmentAction, ChannelId,
 };
 
@@ -30,13 +31,19 @@ impl P2pPeerAction {
                         ChannelId::SnarkJobCommitmentPropagation => {
                             store.dispatch(P2pChannelsSnarkJobCommitmentAction::Init { peer_id });
                         }
+                        ChannelId::Signaling => {
+                            store.dispatch(P2pChannelsSignalingAction::Init { peer_id });
+                        }
                         ChannelId::Rpc => {
                             store.dispatch(P2pChannelsRpcAction::Init { peer_id });
                         }
                     }
                 }
             }
-            P2pPeerAction::BestTipUpdate { .. } => {}
+            P2pPeerAction::BestTipUpdate { .. }
+            | P2pPeerAction::RttUpdate { .. }
+            | P2pPeerAction::UsefulBlockReceived { .. }
+            | P2pPeerAction::IceCandidatePairSelected { .. } => {}
         }
     }
 }
@@ -13,6 +13,7 @@ pub fn p2p_peer_reducer(state: &mut P2pState, action: P2pPeerActionWithMetaRef<'
                 is_libp2p: true,
                 dial_opts: dial_opts.clone(),
                 identify: None,
+                rtt: None,
                 status: P2pPeerStatus::Disconnected {
                     time: Timestamp::ZERO,
                 },
@@ -37,5 +38,26 @@ pub fn p2p_peer_reducer(state: &mut P2pState, action: P2pPeerActionWithMetaRef<'
             };
             peer.best_tip = Some(best_tip.clone());
         }
+        P2pPeerAction::RttUpdate { peer_id, rtt } => {
+            let Some(peer) = state.peers.get_mut(peer_id) else {
+                return;
+            };
+            peer.rtt = Some(*rtt);
+        }
+        P2pPeerAction::UsefulBlockReceived { peer_id } => {
+            let Some(peer) = state.get_ready_peer_mut(peer_id) else {
+                return;
+            };
+            peer.last_useful_block = Some(meta.time());
+        }
+        P2pPeerAction::IceCandidatePairSelected {
+            peer_id,
+            candidate_pair,
+        } => {
+            let Some(peer) = state.get_ready_peer_mut(peer_id) else {
+                return;
+            };
+            peer.ice_candidate_pair = Some(*candidate_pair);
+        }
     }
 }
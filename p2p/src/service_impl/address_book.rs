@@ -0,0 +1,37 @@
+//! Stores the address book as a JSON file.
+
+use std::{fs, io, path::Path};
+
+use crate::address_book::P2pAddressBookEntry;
+
+/// Name of the address book file in the node's work dir.
+pub const ADDRESS_BOOK_FILE_NAME: &str = "p2p_address_book.json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum AddressBookError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid address book: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Reads the address book, a missing file is treated as an empty one.
+pub fn load(path: &Path) -> Result<Vec<P2pAddressBookEntry>, AddressBookError> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Writes the address book to a temporary file first and then renames it,
+/// so that the previous version stays intact if the node crashes meanwhile.
+pub fn save(path: &Path, entries: &[P2pAddressBookEntry]) -> Result<(), AddressBookError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(entries)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
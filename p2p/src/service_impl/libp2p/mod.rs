@@ -38,7 +38,7 @@ use libp2p_rpc_behaviour::{BehaviourBuilder, Event as RpcBehaviourEvent, StreamI
 use crate::channels::best_tip::BestTipPropagationChannelMsg;
 use crate::channels::rpc::{
     BestTipWithProof, P2pRpcRequest, P2pRpcResponse, RpcChannelMsg,
-    StagedLedgerAuxAndPendingCoinbases,
+    StagedLedgerAuxAndPendingCoinbases, TransitionChainProof,
 };
 use crate::channels::ChannelMsg;
 use crate::connection::outgoing::{
@@ -213,7 +213,7 @@ impl Libp2pService {
                 use mina_p2p_messages::rpc::{
                     AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2,
                     GetStagedLedgerAuxAndPendingCoinbasesAtHashV2, GetTransitionChainProofV1ForV2,
-                    GetTransitionChainV2,
+                    GetTransitionChainV2, GetTransitionKnowledgeV1ForV2,
                 };
 
                 BehaviourBuilder::default()
@@ -223,6 +223,7 @@ impl Libp2pService {
                     .register_method::<AnswerSyncLedgerQueryV2>()
                     .register_method::<GetTransitionChainV2>()
                     .register_method::<GetTransitionChainProofV1ForV2>()
+                    .register_method::<GetTransitionKnowledgeV1ForV2>()
                     .build()
             },
             identify,
@@ -357,6 +358,9 @@ impl Libp2pService {
                 ChannelMsg::SnarkJobCommitmentPropagation(_) => {
                     // unsupported
                 }
+                ChannelMsg::Signaling(_) => {
+                    // unsupported
+                }
                 ChannelMsg::BestTipPropagation(msg) => match msg {
                     BestTipPropagationChannelMsg::GetNext => {
                         // TODO(binier): mark that peer can send us
@@ -414,10 +418,12 @@ impl Libp2pService {
     ) -> Result<(), binprot::Error> {
         use mina_p2p_messages::{
             core::Info,
+            list::List,
             rpc::{
                 AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2,
-                GetStagedLedgerAuxAndPendingCoinbasesAtHashV2, GetTransitionChainV2,
-                ProofCarryingDataStableV1, ProofCarryingDataWithHashV1,
+                GetStagedLedgerAuxAndPendingCoinbasesAtHashV2, GetTransitionChainProofV1ForV2,
+                GetTransitionChainV2, GetTransitionKnowledgeV1ForV2, ProofCarryingDataStableV1,
+                ProofCarryingDataWithHashV1, WithHashV1,
             },
             rpc_kernel::{RpcMethod, RpcResult},
         };
@@ -458,6 +464,32 @@ impl Libp2pService {
                         b.ongoing.insert(key, (T::NAME, T::VERSION));
                         b.rpc.query::<T>(peer_id, stream_id, id, ())?;
                     }
+                    P2pRpcRequest::TransitionChain(hashes) => {
+                        type T = GetTransitionChainV2;
+                        b.ongoing.insert(key, (T::NAME, T::VERSION));
+                        let query = hashes.iter().map(|hash| hash.0.clone()).collect();
+                        b.rpc.query::<T>(peer_id, stream_id, id, query)?;
+                    }
+                    P2pRpcRequest::TransitionChainProof(hash) => {
+                        type T = GetTransitionChainProofV1ForV2;
+                        b.ongoing.insert(key, (T::NAME, T::VERSION));
+                        let query = hash.0.clone();
+                        b.rpc.query::<T>(peer_id, stream_id, id, query)?;
+                    }
+                    P2pRpcRequest::Ancestry(consensus_state, hash) => {
+                        type T = GetAncestryV2;
+                        b.ongoing.insert(key, (T::NAME, T::VERSION));
+                        let query = WithHashV1 {
+                            data: *consensus_state,
+                            hash: hash.0.clone(),
+                        };
+                        b.rpc.query::<T>(peer_id, stream_id, id, query)?;
+                    }
+                    P2pRpcRequest::TransitionKnowledge => {
+                        type T = GetTransitionKnowledgeV1ForV2;
+                        b.ongoing.insert(key, (T::NAME, T::VERSION));
+                        b.rpc.query::<T>(peer_id, stream_id, id, ())?;
+                    }
                 };
             }
             RpcChannelMsg::Response(id, resp) => {
@@ -493,6 +525,21 @@ impl Libp2pService {
                                 type T = GetTransitionChainV2;
                                 b.rpc.respond::<T>(peer_id, stream_id, id, Ok(None))?
                             }
+                            (
+                                GetTransitionChainProofV1ForV2::NAME,
+                                GetTransitionChainProofV1ForV2::VERSION,
+                            ) => {
+                                type T = GetTransitionChainProofV1ForV2;
+                                b.rpc.respond::<T>(peer_id, stream_id, id, Ok(None))?
+                            }
+                            (
+                                GetTransitionKnowledgeV1ForV2::NAME,
+                                GetTransitionKnowledgeV1ForV2::VERSION,
+                            ) => {
+                                type T = GetTransitionKnowledgeV1ForV2;
+                                b.rpc
+                                    .respond::<T>(peer_id, stream_id, id, Ok(List::new()))?
+                            }
                             (
                                 GetSomeInitialPeersV1ForV2::NAME,
                                 GetSomeInitialPeersV1ForV2::VERSION,
@@ -503,22 +550,21 @@ impl Libp2pService {
                             _ => {}
                         },
                         Some(P2pRpcResponse::BestTipWithProof(msg)) => {
-                            if tag.as_bytes() == GetAncestryV2::NAME {
-                                type T = GetAncestryV2;
-                                let v = msg.proof.0.iter().map(|x| x.0.clone()).collect();
-                                let r = Ok(Some(ProofCarryingDataWithHashV1 {
-                                    data: (*msg.best_tip).clone(),
-                                    proof: (v, (*msg.proof.1).clone()),
-                                }));
-                                b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
-                            } else {
-                                type T = GetBestTipV2;
-                                let r = Ok(Some(ProofCarryingDataStableV1 {
-                                    data: (*msg.best_tip).clone(),
-                                    proof: (msg.proof.0, (*msg.proof.1).clone()),
-                                }));
-                                b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
-                            }
+                            type T = GetBestTipV2;
+                            let r = Ok(Some(ProofCarryingDataStableV1 {
+                                data: (*msg.best_tip).clone(),
+                                proof: (msg.proof.0, (*msg.proof.1).clone()),
+                            }));
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
+                        }
+                        Some(P2pRpcResponse::Ancestry(msg)) => {
+                            type T = GetAncestryV2;
+                            let v = msg.proof.0.iter().map(|x| x.0.clone()).collect();
+                            let r = Ok(Some(ProofCarryingDataWithHashV1 {
+                                data: (*msg.best_tip).clone(),
+                                proof: (v, (*msg.proof.1).clone()),
+                            }));
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
                         }
                         Some(P2pRpcResponse::LedgerQuery(msg)) => {
                             type T = AnswerSyncLedgerQueryV2;
@@ -550,6 +596,25 @@ impl Libp2pService {
                                 .collect());
                             b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
                         }
+                        Some(P2pRpcResponse::TransitionChain(blocks)) => {
+                            type T = GetTransitionChainV2;
+                            let r = Ok(Some(blocks.iter().map(|b| (**b).clone()).collect()));
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
+                        }
+                        Some(P2pRpcResponse::TransitionChainProof(proof)) => {
+                            type T = GetTransitionChainProofV1ForV2;
+                            let body_hashes = proof.body_hashes.iter().map(|h| h.0.clone());
+                            let r = Ok(Some((
+                                proof.init_state_hash.0.clone(),
+                                body_hashes.collect(),
+                            )));
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
+                        }
+                        Some(P2pRpcResponse::TransitionKnowledge(hashes)) => {
+                            type T = GetTransitionKnowledgeV1ForV2;
+                            let r = Ok(hashes.iter().map(|hash| hash.0.clone()).collect());
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
+                        }
                     }
                 }
             }
@@ -906,6 +971,7 @@ impl Libp2pService {
                         AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2,
                         GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
                         GetTransitionChainProofV1ForV2, GetTransitionChainV2,
+                        GetTransitionKnowledgeV1ForV2,
                     },
                     rpc_kernel::{
                         Error as RpcError, NeedsLength, QueryHeader, QueryPayload, ResponseHeader,
@@ -961,12 +1027,10 @@ impl Libp2pService {
                             }
                             (GetAncestryV2::NAME, GetAncestryV2::VERSION) => {
                                 match parse_q::<GetAncestryV2>(bytes) {
-                                    Ok(query) => {
-                                        // TODO (vlad9486): Check query
-                                        let _ = query.data; // must be equal current best tip consensus state
-                                        let _ = query.hash; // must be equal best_tip.data.header.protocol_state.hash()
-                                        send(P2pRpcRequest::BestTipWithProof)
-                                    }
+                                    Ok(query) => send(P2pRpcRequest::Ancestry(
+                                        Box::new(query.data),
+                                        v2::DataHashLibStateHashStableV1(query.hash).into(),
+                                    )),
                                     Err(err) => send_error(err),
                                 };
                             }
@@ -995,29 +1059,33 @@ impl Libp2pService {
                             }
                             (GetTransitionChainV2::NAME, GetTransitionChainV2::VERSION) => {
                                 match parse_q::<GetTransitionChainV2>(bytes) {
-                                    Ok(hashes) => {
-                                        for hash in hashes {
-                                            send(P2pRpcRequest::Block(
-                                                v2::DataHashLibStateHashStableV1(hash).into(),
-                                            ));
-                                        }
-                                    }
+                                    Ok(hashes) => send(P2pRpcRequest::TransitionChain(
+                                        hashes
+                                            .into_iter()
+                                            .map(|hash| {
+                                                v2::DataHashLibStateHashStableV1(hash).into()
+                                            })
+                                            .collect(),
+                                    )),
                                     Err(err) => send_error(err),
                                 }
                             }
                             (
                                 GetTransitionChainProofV1ForV2::NAME,
                                 GetTransitionChainProofV1ForV2::VERSION,
-                            ) => swarm
-                                .behaviour_mut()
-                                .rpc
-                                .respond::<GetTransitionChainProofV1ForV2>(
-                                    peer_id,
-                                    stream_id,
-                                    id,
-                                    Ok(None),
-                                )
-                                .unwrap(),
+                            ) => match parse_q::<GetTransitionChainProofV1ForV2>(bytes) {
+                                Ok(hash) => send(P2pRpcRequest::TransitionChainProof(
+                                    v2::DataHashLibStateHashStableV1(hash).into(),
+                                )),
+                                Err(err) => send_error(err),
+                            },
+                            (
+                                GetTransitionKnowledgeV1ForV2::NAME,
+                                GetTransitionKnowledgeV1ForV2::VERSION,
+                            ) => match parse_q::<GetTransitionKnowledgeV1ForV2>(bytes) {
+                                Ok(()) => send(P2pRpcRequest::TransitionKnowledge),
+                                Err(err) => send_error(err),
+                            },
                             (
                                 GetSomeInitialPeersV1ForV2::NAME,
                                 GetSomeInitialPeersV1ForV2::VERSION,
@@ -1102,18 +1170,86 @@ impl Libp2pService {
                             (GetTransitionChainV2::NAME, GetTransitionChainV2::VERSION) => {
                                 match parse_r::<GetTransitionChainV2>(bytes) {
                                     Ok(response) => {
-                                        let response = response.ok().flatten().unwrap_or_default();
-                                        if response.is_empty() {
-                                            send(None)
-                                        } else {
-                                            for block in response {
-                                                send(Some(P2pRpcResponse::Block(Arc::new(block))));
+                                        let response = response
+                                            .ok()
+                                            .flatten()
+                                            .filter(|blocks| !blocks.is_empty())
+                                            .map(|blocks| {
+                                                blocks.into_iter().map(Arc::new).collect()
+                                            })
+                                            .map(P2pRpcResponse::TransitionChain);
+                                        send(response)
+                                    }
+                                    Err(err) => send_error(err),
+                                }
+                            }
+                            (
+                                GetTransitionChainProofV1ForV2::NAME,
+                                GetTransitionChainProofV1ForV2::VERSION,
+                            ) => match parse_r::<GetTransitionChainProofV1ForV2>(bytes) {
+                                Ok(response) => {
+                                    let response = response
+                                        .ok()
+                                        .flatten()
+                                        .map(|(init_state_hash, body_hashes)| {
+                                            TransitionChainProof {
+                                                init_state_hash: v2::DataHashLibStateHashStableV1(
+                                                    init_state_hash,
+                                                )
+                                                .into(),
+                                                body_hashes: body_hashes
+                                                    .into_iter()
+                                                    .map(v2::MinaBaseStateBodyHashStableV1)
+                                                    .collect(),
                                             }
-                                        }
+                                        })
+                                        .map(P2pRpcResponse::TransitionChainProof);
+                                    send(response)
+                                }
+                                Err(err) => send_error(err),
+                            },
+                            (GetAncestryV2::NAME, GetAncestryV2::VERSION) => {
+                                match parse_r::<GetAncestryV2>(bytes) {
+                                    Ok(response) => {
+                                        let response = response
+                                            .ok()
+                                            .flatten()
+                                            .map(|resp| BestTipWithProof {
+                                                best_tip: resp.data.into(),
+                                                proof: (
+                                                    resp.proof
+                                                        .0
+                                                        .into_iter()
+                                                        .map(v2::MinaBaseStateBodyHashStableV1)
+                                                        .collect(),
+                                                    resp.proof.1.into(),
+                                                ),
+                                            })
+                                            .map(P2pRpcResponse::Ancestry);
+                                        send(response)
                                     }
                                     Err(err) => send_error(err),
                                 }
                             }
+                            (
+                                GetTransitionKnowledgeV1ForV2::NAME,
+                                GetTransitionKnowledgeV1ForV2::VERSION,
+                            ) => match parse_r::<GetTransitionKnowledgeV1ForV2>(bytes) {
+                                Ok(response) => {
+                                    let response = response.ok().map(|hashes| {
+                                        P2pRpcResponse::TransitionKnowledge(
+                                            hashes
+                                                .into_iter()
+                                                .map(|hash| {
+                                                    v2::DataHashLibStateHashStableV1(hash).into()
+                                                })
+                                                .collect(),
+                                        )
+                                    });
+                                    send(response)
+                                }
+                                Err(err) => send_error(err),
+                            },
                             (
                                 GetSomeInitialPeersV1ForV2::NAME,
                                 GetSomeInitialPeersV1ForV2::VERSION,
@@ -1,5 +1,11 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod address_book;
 #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
 pub mod mio;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod nat;
+#[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
+pub mod quic;
 #[cfg(feature = "p2p-webrtc")]
 pub mod webrtc;
 #[cfg(not(target_arch = "wasm32"))]
@@ -52,7 +58,11 @@ pub mod webrtc {
 
         fn peers(&mut self) -> &mut BTreeMap<PeerId, PeerState>;
 
-        fn init<S: TaskSpawner>(_secret_key: SecretKey, _spawner: S) -> P2pServiceCtx {
+        fn init<S: TaskSpawner>(
+            _secret_key: SecretKey,
+            _ice_config: webrtc::P2pIceConfig,
+            _spawner: S,
+        ) -> P2pServiceCtx {
             let (cmd_sender, _) = mpsc::unbounded_channel();
             P2pServiceCtx {
                 cmd_sender,
@@ -0,0 +1,75 @@
+//! Maps the port on the gateway, UPnP IGD is tried first, then NAT-PMP.
+
+mod natpmp;
+mod upnp;
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+use crate::nat::{P2pNatPortMapping, P2pNatTransport};
+
+/// Time to wait for the gateway to respond to UPnP requests.
+const UPNP_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, thiserror::Error)]
+pub enum NatError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+    #[error("gateway error: {0}")]
+    Gateway(String),
+    #[error("gateway doesn't respond")]
+    Timeout,
+    #[error("only IPv4 addresses can be mapped")]
+    NotIpv4,
+}
+
+/// Asks the gateway to forward the `transport` port to the `internal` address.
+///
+/// Blocks until the gateway responds, so it should be run in a separate thread.
+pub fn map_port(
+    transport: P2pNatTransport,
+    internal: SocketAddr,
+    lifetime: Duration,
+) -> Result<P2pNatPortMapping, String> {
+    let SocketAddr::V4(internal) = internal else {
+        return Err(NatError::NotIpv4.to_string());
+    };
+    let upnp_error = match upnp::Gateway::discover(*internal.ip(), UPNP_TIMEOUT)
+        .and_then(|gateway| gateway.map_port(transport, internal, lifetime))
+    {
+        Ok(mapping) => return Ok(mapping),
+        Err(err) => err,
+    };
+    let gateway = SocketAddrV4::new(default_gateway(*internal.ip()), natpmp::NAT_PMP_PORT);
+    natpmp::NatPmp::new(gateway)
+        .map_port(transport, internal, lifetime)
+        .map_err(|natpmp_error| format!("UPnP: {upnp_error}, NAT-PMP: {natpmp_error}"))
+}
+
+/// Default gateway is taken from the routing table on linux,
+/// otherwise it is assumed to be the first address of the local /24 network.
+fn default_gateway(local: Ipv4Addr) -> Ipv4Addr {
+    #[cfg(target_os = "linux")]
+    if let Some(gateway) = linux_default_gateway() {
+        return gateway;
+    }
+    let [a, b, c, _] = local.octets();
+    Ipv4Addr::new(a, b, c, 1)
+}
+
+#[cfg(target_os = "linux")]
+fn linux_default_gateway() -> Option<Ipv4Addr> {
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    // Iface Destination Gateway Flags ..., addresses are hex encoded in network order
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let destination = fields.next()?;
+        let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+        (destination == "00000000" && gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
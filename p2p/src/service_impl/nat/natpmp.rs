@@ -0,0 +1,195 @@
+//! NAT Port Mapping Protocol client, see [RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886).
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket},
+    time::Duration,
+};
+
+use crate::nat::{P2pNatPortMapping, P2pNatProtocol, P2pNatTransport};
+
+use super::NatError;
+
+pub const NAT_PMP_PORT: u16 = 5351;
+
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+const OP_MAP_UDP: u8 = 1;
+const OP_MAP_TCP: u8 = 2;
+/// Response opcode is the request one plus 128.
+const OP_RESPONSE: u8 = 128;
+
+/// The RFC suggests 9 attempts, which takes more than a minute.
+const ATTEMPTS: usize = 4;
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+pub struct NatPmp {
+    gateway: SocketAddrV4,
+}
+
+impl NatPmp {
+    pub fn new(gateway: SocketAddrV4) -> Self {
+        NatPmp { gateway }
+    }
+
+    pub fn map_port(
+        &self,
+        transport: P2pNatTransport,
+        internal: SocketAddrV4,
+        lifetime: Duration,
+    ) -> Result<P2pNatPortMapping, NatError> {
+        let external_ip = self.external_ip()?;
+        let (external_port, lifetime) =
+            self.add_port_mapping(transport, internal.port(), internal.port(), lifetime)?;
+        Ok(P2pNatPortMapping {
+            protocol: P2pNatProtocol::NatPmp,
+            transport,
+            internal: internal.into(),
+            external: SocketAddr::new(external_ip.into(), external_port),
+            lifetime,
+        })
+    }
+
+    pub fn external_ip(&self) -> Result<Ipv4Addr, NatError> {
+        let response = self.request(&[0, OP_EXTERNAL_ADDRESS], 12)?;
+        Ok(Ipv4Addr::new(
+            response[8],
+            response[9],
+            response[10],
+            response[11],
+        ))
+    }
+
+    /// Returns the external port and the lifetime assigned by the gateway.
+    pub fn add_port_mapping(
+        &self,
+        transport: P2pNatTransport,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> Result<(u16, Duration), NatError> {
+        let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+        let mut request = [0; 12];
+        request[1] = match transport {
+            P2pNatTransport::Tcp => OP_MAP_TCP,
+            P2pNatTransport::Udp => OP_MAP_UDP,
+        };
+        request[4..6].copy_from_slice(&internal_port.to_be_bytes());
+        request[6..8].copy_from_slice(&external_port.to_be_bytes());
+        request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+
+        let response = self.request(&request, 16)?;
+        let external_port = u16::from_be_bytes([response[10], response[11]]);
+        let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+        Ok((external_port, Duration::from_secs(lifetime.into())))
+    }
+
+    /// Sends the request, retrying with the doubled timeout until the gateway responds.
+    fn request(&self, request: &[u8], response_len: usize) -> Result<Vec<u8>, NatError> {
+        let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))?;
+        socket.connect(self.gateway)?;
+        let opcode = request[1] + OP_RESPONSE;
+        let mut timeout = INITIAL_TIMEOUT;
+        let mut buf = [0; 16];
+        for _ in 0..ATTEMPTS {
+            socket.send(request)?;
+            socket.set_read_timeout(Some(timeout))?;
+            let len = match socket.recv(&mut buf) {
+                Ok(len) => len,
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    timeout *= 2;
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if len < 4 || buf[0] != 0 || buf[1] != opcode {
+                return Err(NatError::InvalidResponse(format!(
+                    "unexpected NAT-PMP response: {}",
+                    hex::encode(&buf[..len])
+                )));
+            }
+            let result = u16::from_be_bytes([buf[2], buf[3]]);
+            if result != 0 {
+                return Err(NatError::Gateway(format!("NAT-PMP result code {result}")));
+            }
+            if len < response_len {
+                return Err(NatError::InvalidResponse(format!(
+                    "NAT-PMP response is too short: {len}"
+                )));
+            }
+            return Ok(buf[..len].to_vec());
+        }
+        Err(NatError::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket},
+        time::Duration,
+    };
+
+    use crate::nat::{P2pNatProtocol, P2pNatTransport};
+
+    use super::NatPmp;
+
+    /// Responds to the external address and the port mapping requests like a gateway,
+    /// only the mapping requests with the `map_opcode` are expected.
+    fn mock_gateway(map_opcode: u8) -> SocketAddrV4 {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let SocketAddr::V4(addr) = socket.local_addr().unwrap() else {
+            unreachable!()
+        };
+        std::thread::spawn(move || {
+            let mut buf = [0; 12];
+            for _ in 0..2 {
+                let (len, peer) = socket.recv_from(&mut buf).unwrap();
+                let mut response = vec![0, buf[1] + 128, 0, 0, 0, 0, 0, 1];
+                match buf[1] {
+                    0 => response.extend_from_slice(&[203, 0, 113, 7]),
+                    op if op == map_opcode => {
+                        assert_eq!(len, 12);
+                        response.extend_from_slice(&buf[4..6]);
+                        // the requested port is taken, another one is assigned
+                        response.extend_from_slice(&40000_u16.to_be_bytes());
+                        response.extend_from_slice(&7200_u32.to_be_bytes());
+                    }
+                    op => panic!("unexpected opcode {op}"),
+                }
+                socket.send_to(&response, peer).unwrap();
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn test_map_port() {
+        let gateway = NatPmp::new(mock_gateway(2));
+        let internal = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 10), 8302);
+        let mapping = gateway
+            .map_port(P2pNatTransport::Tcp, internal, Duration::from_secs(3600))
+            .unwrap();
+        assert_eq!(mapping.protocol, P2pNatProtocol::NatPmp);
+        assert_eq!(mapping.transport, P2pNatTransport::Tcp);
+        assert_eq!(mapping.internal, SocketAddr::from(internal));
+        assert_eq!(mapping.external, "203.0.113.7:40000".parse().unwrap());
+        assert_eq!(mapping.lifetime, Duration::from_secs(7200));
+    }
+
+    #[test]
+    fn test_map_udp_port() {
+        let gateway = NatPmp::new(mock_gateway(1));
+        let internal = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 10), 8303);
+        let mapping = gateway
+            .map_port(P2pNatTransport::Udp, internal, Duration::from_secs(3600))
+            .unwrap();
+        assert_eq!(mapping.transport, P2pNatTransport::Udp);
+        assert_eq!(mapping.internal, SocketAddr::from(internal));
+        assert_eq!(mapping.external, "203.0.113.7:40000".parse().unwrap());
+    }
+}
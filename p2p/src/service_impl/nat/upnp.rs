@@ -0,0 +1,440 @@
+//! Minimal UPnP Internet Gateway Device client, only the requests needed to
+//! forward a port are supported.
+
+use std::{
+    fmt::Write as _,
+    io::{Read, Write},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpStream, UdpSocket},
+    time::Duration,
+};
+
+use crate::nat::{P2pNatPortMapping, P2pNatProtocol, P2pNatTransport};
+
+use super::NatError;
+
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+const SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+/// Services that are able to forward ports.
+const SERVICE_TYPES: [&str; 3] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+/// Error returned by gateways that don't support port mappings with limited lifetime.
+const ONLY_PERMANENT_LEASES_SUPPORTED: &str = "725";
+const MAX_RESPONSE_LEN: u64 = 64 * 1024;
+
+pub struct Gateway {
+    control_addr: SocketAddr,
+    control_path: String,
+    service_type: String,
+    timeout: Duration,
+}
+
+impl Gateway {
+    /// Searches for the gateway in the local network of the `local` address.
+    pub fn discover(local: Ipv4Addr, timeout: Duration) -> Result<Self, NatError> {
+        let socket = UdpSocket::bind(SocketAddrV4::new(local, 0))?;
+        socket.set_read_timeout(Some(timeout))?;
+        let request = format!(
+            "M-SEARCH * HTTP/1.1\r\n\
+             HOST: {SSDP_ADDR}\r\n\
+             ST: {SEARCH_TARGET}\r\n\
+             MAN: \"ssdp:discover\"\r\n\
+             MX: 2\r\n\r\n"
+        );
+        socket.send_to(request.as_bytes(), SSDP_ADDR)?;
+
+        let mut buf = [0; 2048];
+        let len = socket.recv(&mut buf).map_err(|_| NatError::Timeout)?;
+        let response = String::from_utf8_lossy(&buf[..len]);
+        let location = response
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("location")
+                    .then_some(value.trim())
+            })
+            .ok_or_else(|| NatError::InvalidResponse("no location in SSDP response".to_owned()))?;
+        Self::from_location(location, timeout)
+    }
+
+    /// Finds the control URL in the device description.
+    pub fn from_location(location: &str, timeout: Duration) -> Result<Self, NatError> {
+        let (addr, path) = parse_url(location)?;
+        let (status, description) = http_request(addr, "GET", path, &[], "", timeout)?;
+        if status != 200 {
+            return Err(NatError::InvalidResponse(format!(
+                "device description request failed with status {status}"
+            )));
+        }
+        let (service_type, control_url) = description
+            .split("<service>")
+            .skip(1)
+            .filter_map(|service| {
+                let service_type = xml_value(service, "serviceType")?;
+                let control_url = xml_value(service, "controlURL")?;
+                let priority = SERVICE_TYPES.iter().position(|t| *t == service_type)?;
+                Some((priority, service_type, control_url))
+            })
+            .min_by_key(|(priority, ..)| *priority)
+            .map(|(_, service_type, control_url)| (service_type, control_url))
+            .ok_or_else(|| {
+                NatError::InvalidResponse("no WAN connection service in description".to_owned())
+            })?;
+
+        let (control_addr, control_path) = if control_url.starts_with("http://") {
+            parse_url(control_url)?
+        } else {
+            let base = match xml_value(&description, "URLBase") {
+                Some(base) => parse_url(base)?.0,
+                None => addr,
+            };
+            (base, control_url)
+        };
+        let control_path = match control_path.starts_with('/') {
+            true => control_path.to_owned(),
+            false => format!("/{control_path}"),
+        };
+
+        Ok(Gateway {
+            control_addr,
+            control_path,
+            service_type: service_type.to_owned(),
+            timeout,
+        })
+    }
+
+    pub fn map_port(
+        &self,
+        transport: P2pNatTransport,
+        internal: SocketAddrV4,
+        lifetime: Duration,
+    ) -> Result<P2pNatPortMapping, NatError> {
+        let external_ip = self.external_ip()?;
+        match self.add_port_mapping(transport, internal, internal.port(), lifetime) {
+            Err(NatError::Gateway(error)) if error.starts_with(ONLY_PERMANENT_LEASES_SUPPORTED) => {
+                self.add_port_mapping(transport, internal, internal.port(), Duration::ZERO)?;
+            }
+            res => res?,
+        }
+        Ok(P2pNatPortMapping {
+            protocol: P2pNatProtocol::Upnp,
+            transport,
+            internal: internal.into(),
+            external: SocketAddr::new(external_ip.into(), internal.port()),
+            lifetime,
+        })
+    }
+
+    pub fn external_ip(&self) -> Result<Ipv4Addr, NatError> {
+        let response = self.soap_request("GetExternalIPAddress", "")?;
+        let ip = xml_value(&response, "NewExternalIPAddress").ok_or_else(|| {
+            NatError::InvalidResponse("no external address in response".to_owned())
+        })?;
+        ip.trim()
+            .parse()
+            .map_err(|_| NatError::InvalidResponse(format!("invalid external address: {ip}")))
+    }
+
+    /// Zero `lifetime` creates the permanent mapping.
+    pub fn add_port_mapping(
+        &self,
+        transport: P2pNatTransport,
+        internal: SocketAddrV4,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> Result<(), NatError> {
+        let args = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+             <NewExternalPort>{external_port}</NewExternalPort>\
+             <NewProtocol>{transport}</NewProtocol>\
+             <NewInternalPort>{}</NewInternalPort>\
+             <NewInternalClient>{}</NewInternalClient>\
+             <NewEnabled>1</NewEnabled>\
+             <NewPortMappingDescription>openmina</NewPortMappingDescription>\
+             <NewLeaseDuration>{}</NewLeaseDuration>",
+            internal.port(),
+            internal.ip(),
+            lifetime.as_secs(),
+        );
+        self.soap_request("AddPortMapping", &args).map(|_| ())
+    }
+
+    fn soap_request(&self, action: &str, args: &str) -> Result<String, NatError> {
+        let service_type = &self.service_type;
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{action} xmlns:u=\"{service_type}\">{args}</u:{action}></s:Body>\
+             </s:Envelope>"
+        );
+        let soap_action = format!("\"{service_type}#{action}\"");
+        let headers = [
+            ("Content-Type", "text/xml; charset=\"utf-8\""),
+            ("SOAPAction", soap_action.as_str()),
+        ];
+        let (status, response) = http_request(
+            self.control_addr,
+            "POST",
+            &self.control_path,
+            &headers,
+            &body,
+            self.timeout,
+        )?;
+        if status != 200 {
+            let code = xml_value(&response, "errorCode").unwrap_or_default();
+            let description = xml_value(&response, "errorDescription").unwrap_or_default();
+            return Err(NatError::Gateway(format!(
+                "{code} {description} ({action} failed with status {status})"
+            )));
+        }
+        Ok(response)
+    }
+}
+
+/// Splits the `http://host:port/path` URL, the host should be an IP address.
+fn parse_url(url: &str) -> Result<(SocketAddr, &str), NatError> {
+    let invalid = || NatError::InvalidResponse(format!("invalid url: {url}"));
+    let url = url.strip_prefix("http://").ok_or_else(invalid)?;
+    let (host, path) = match url.find('/') {
+        Some(i) => url.split_at(i),
+        None => (url, "/"),
+    };
+    let addr = match host.parse() {
+        Ok(addr) => addr,
+        Err(_) => SocketAddr::new(host.parse().map_err(|_| invalid())?, 80),
+    };
+    Ok((addr, path))
+}
+
+/// Returns the text of the first element with the `tag` name.
+fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let len = xml[start..].find(&format!("</{tag}>"))?;
+    Some(&xml[start..start + len])
+}
+
+/// Sends HTTP/1.0 request, so the response is not chunked and the connection
+/// is closed after it.
+fn http_request(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+    timeout: Duration,
+) -> Result<(u16, String), NatError> {
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut request = format!(
+        "{method} {path} HTTP/1.0\r\nHost: {addr}\r\nContent-Length: {}\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        let _ = write!(request, "{name}: {value}\r\n");
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = Vec::new();
+    stream.take(MAX_RESPONSE_LEN).read_to_end(&mut response)?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| NatError::InvalidResponse("incomplete HTTP response".to_owned()))?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| NatError::InvalidResponse("invalid HTTP status line".to_owned()))?;
+    Ok((status, body.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpListener},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{
+        nat::{P2pNatProtocol, P2pNatTransport},
+        service_impl::nat::NatError,
+    };
+
+    use super::Gateway;
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+        <controlURL>/ctl/L3F</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+        <controlURL>/ctl/IPConn</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    const EXTERNAL_IP: &str = r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>
+<u:GetExternalIPAddressResponse xmlns:u="urn:schemas-upnp-org:service:WANIPConnection:1">
+<NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>
+</u:GetExternalIPAddressResponse></s:Body></s:Envelope>"#;
+
+    const ONLY_PERMANENT_LEASES: &str = r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault>
+<detail><UPnPError xmlns="urn:schemas-upnp-org:control-1-0">
+<errorCode>725</errorCode><errorDescription>OnlyPermanentLeasesSupported</errorDescription>
+</UPnPError></detail></s:Fault></s:Body></s:Envelope>"#;
+
+    /// Mock IGD serving the device description and the port mapping requests.
+    /// Returns the address and the bodies of `AddPortMapping` requests.
+    fn mock_igd(permanent_only: bool) -> (SocketAddr, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mappings = Arc::new(Mutex::new(Vec::new()));
+        let requests = mappings.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut soap_action = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(": ").unwrap();
+                    match name {
+                        "Content-Length" => content_length = value.parse().unwrap(),
+                        "SOAPAction" => soap_action = value.to_owned(),
+                        _ => {}
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let body = String::from_utf8(body).unwrap();
+
+                let (status, response) = if request_line.starts_with("GET /rootDesc.xml") {
+                    (200, DESCRIPTION)
+                } else if soap_action.ends_with("#GetExternalIPAddress\"") {
+                    (200, EXTERNAL_IP)
+                } else if soap_action.ends_with("#AddPortMapping\"") {
+                    let permanent = body.contains("<NewLeaseDuration>0</NewLeaseDuration>");
+                    requests.lock().unwrap().push(body);
+                    if permanent_only && !permanent {
+                        (500, ONLY_PERMANENT_LEASES)
+                    } else {
+                        (200, "")
+                    }
+                } else {
+                    (404, "")
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} OK\r\nContent-Length: {}\r\n\r\n{response}",
+                    response.len()
+                )
+                .unwrap();
+            }
+        });
+        (addr, mappings)
+    }
+
+    fn internal() -> SocketAddrV4 {
+        SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 10), 8302)
+    }
+
+    #[test]
+    fn test_map_port() {
+        let (addr, mappings) = mock_igd(false);
+        let gateway = Gateway::from_location(
+            &format!("http://{addr}/rootDesc.xml"),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(gateway.control_addr, addr);
+        assert_eq!(gateway.control_path, "/ctl/IPConn");
+
+        let mapping = gateway
+            .map_port(P2pNatTransport::Tcp, internal(), Duration::from_secs(3600))
+            .unwrap();
+        assert_eq!(mapping.protocol, P2pNatProtocol::Upnp);
+        assert_eq!(mapping.transport, P2pNatTransport::Tcp);
+        assert_eq!(mapping.external, "203.0.113.7:8302".parse().unwrap());
+
+        let mappings = mappings.lock().unwrap();
+        assert_eq!(mappings.len(), 1);
+        assert!(mappings[0].contains("<NewProtocol>TCP</NewProtocol>"));
+        assert!(mappings[0].contains("<NewInternalClient>192.168.1.10</NewInternalClient>"));
+        assert!(mappings[0].contains("<NewExternalPort>8302</NewExternalPort>"));
+        assert!(mappings[0].contains("<NewLeaseDuration>3600</NewLeaseDuration>"));
+    }
+
+    #[test]
+    fn test_map_port_permanent_lease() {
+        let (addr, mappings) = mock_igd(true);
+        let gateway = Gateway::from_location(
+            &format!("http://{addr}/rootDesc.xml"),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            gateway.add_port_mapping(
+                P2pNatTransport::Tcp,
+                internal(),
+                8302,
+                Duration::from_secs(3600)
+            ),
+            Err(NatError::Gateway(error)) if error.starts_with("725")
+        ));
+        gateway
+            .map_port(P2pNatTransport::Tcp, internal(), Duration::from_secs(3600))
+            .unwrap();
+        let mappings = mappings.lock().unwrap();
+        assert_eq!(mappings.len(), 3);
+        assert!(mappings[2].contains("<NewLeaseDuration>0</NewLeaseDuration>"));
+    }
+
+    #[test]
+    fn test_map_udp_port() {
+        let (addr, mappings) = mock_igd(false);
+        let gateway = Gateway::from_location(
+            &format!("http://{addr}/rootDesc.xml"),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+
+        let internal = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 10), 8303);
+        let mapping = gateway
+            .map_port(P2pNatTransport::Udp, internal, Duration::from_secs(3600))
+            .unwrap();
+        assert_eq!(mapping.transport, P2pNatTransport::Udp);
+        assert_eq!(mapping.external, "203.0.113.7:8303".parse().unwrap());
+
+        let mappings = mappings.lock().unwrap();
+        assert_eq!(mappings.len(), 1);
+        assert!(mappings[0].contains("<NewProtocol>UDP</NewProtocol>"));
+        assert!(mappings[0].contains("<NewExternalPort>8303</NewExternalPort>"));
+        assert!(mappings[0].contains("<NewInternalPort>8303</NewInternalPort>"));
+    }
+}
@@ -0,0 +1,402 @@
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use libp2p_identity::Keypair;
+use openmina_core::channels::mpsc;
+use quinn::{Connecting, Connection, ConnectionError, Endpoint, ReadError, VarInt};
+
+use crate::{
+    identity::SecretKey, network::quic::P2pNetworkQuicConnectionState, Data, PeerId, QuicCmd,
+    QuicEvent, StreamId,
+};
+
+type Writer = mpsc::UnboundedSender<(Box<[u8]>, bool)>;
+
+enum Msg {
+    Cmd(QuicCmd),
+    Connected {
+        addr: SocketAddr,
+        connection: Connection,
+        incoming: bool,
+        peer_id: PeerId,
+    },
+    Stream {
+        addr: SocketAddr,
+        stream_id: StreamId,
+        writer: Writer,
+    },
+    Closed {
+        addr: SocketAddr,
+        id: usize,
+        result: Option<Result<(), String>>,
+    },
+}
+
+struct ConnectionState {
+    connection: Connection,
+    streams: BTreeMap<StreamId, Writer>,
+}
+
+struct Inner<F> {
+    keypair: Keypair,
+    event_sender: Arc<F>,
+    msg_sender: mpsc::UnboundedSender<Msg>,
+    server: Option<Endpoint>,
+    client: Option<Endpoint>,
+    connections: BTreeMap<SocketAddr, ConnectionState>,
+}
+
+pub(super) fn spawn<F>(
+    secret_key: SecretKey,
+    mut cmd_receiver: mpsc::UnboundedReceiver<QuicCmd>,
+    event_sender: F,
+) where
+    F: 'static + Send + Sync + Fn(QuicEvent),
+{
+    let keypair = Keypair::ed25519_from_bytes(secret_key.to_bytes())
+        .expect("secret key is always a valid ed25519 key");
+
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("cannot build tokio runtime for quic");
+        runtime.block_on(async move {
+            let (msg_sender, mut msg_receiver) = mpsc::unbounded_channel();
+            tokio::spawn({
+                let msg_sender = msg_sender.clone();
+                async move {
+                    while let Some(cmd) = cmd_receiver.recv().await {
+                        msg_sender.send(Msg::Cmd(cmd)).unwrap_or_default();
+                    }
+                }
+            });
+
+            let mut inner = Inner {
+                keypair,
+                event_sender: Arc::new(event_sender),
+                msg_sender,
+                server: None,
+                client: None,
+                connections: BTreeMap::default(),
+            };
+            while let Some(msg) = msg_receiver.recv().await {
+                inner.handle(msg);
+            }
+        });
+    });
+}
+
+impl<F> Inner<F>
+where
+    F: 'static + Send + Sync + Fn(QuicEvent),
+{
+    fn send(&self, event: QuicEvent) {
+        (self.event_sender)(event)
+    }
+
+    fn handle(&mut self, msg: Msg) {
+        match msg {
+            Msg::Cmd(QuicCmd::ListenOn(listener)) => match self.listen(listener) {
+                Ok(endpoint) => {
+                    self.accept(endpoint.clone());
+                    self.server = Some(endpoint);
+                    self.send(QuicEvent::ListenerReady { listener });
+                }
+                Err(error) => self.send(QuicEvent::ListenerError { listener, error }),
+            },
+            Msg::Cmd(QuicCmd::Connect(addr, peer_id)) => {
+                if let Err(err) = self.connect(addr, peer_id) {
+                    self.send(QuicEvent::OutgoingConnectionDidConnect(addr, Err(err)));
+                }
+            }
+            Msg::Cmd(QuicCmd::OpenStream(addr, stream_id)) => {
+                let Some(state) = self.connections.get_mut(&addr) else {
+                    return;
+                };
+                let (writer, data_receiver) = mpsc::unbounded_channel();
+                state.streams.insert(stream_id, writer);
+                let connection = state.connection.clone();
+                let event_sender = self.event_sender.clone();
+                tokio::spawn(async move {
+                    match connection.open_bi().await {
+                        Ok((send, recv)) => {
+                            tokio::spawn(write(send, data_receiver));
+                            read(addr, stream_id, recv, event_sender).await;
+                        }
+                        Err(err) => event_sender(QuicEvent::IncomingDataDidReceive(
+                            addr,
+                            stream_id,
+                            Err(err.to_string()),
+                        )),
+                    }
+                });
+            }
+            Msg::Cmd(QuicCmd::Send(addr, stream_id, data, fin)) => {
+                let Some(state) = self.connections.get_mut(&addr) else {
+                    return;
+                };
+                let writer = if fin {
+                    state.streams.remove(&stream_id)
+                } else {
+                    state.streams.get(&stream_id).cloned()
+                };
+                if let Some(writer) = writer {
+                    writer.send((data, fin)).unwrap_or_default();
+                }
+            }
+            Msg::Cmd(QuicCmd::Disconnect(addr)) => {
+                if let Some(state) = self.connections.remove(&addr) {
+                    state.connection.close(VarInt::from_u32(0), b"");
+                }
+            }
+            Msg::Connected {
+                addr,
+                connection,
+                incoming,
+                peer_id,
+            } => {
+                self.accept_streams(addr, connection.clone(), incoming);
+                self.watch(addr, connection.clone());
+                let state = ConnectionState {
+                    connection,
+                    streams: BTreeMap::default(),
+                };
+                if let Some(previous) = self.connections.insert(addr, state) {
+                    previous.connection.close(VarInt::from_u32(0), b"");
+                }
+                let result = Ok(peer_id);
+                self.send(if incoming {
+                    QuicEvent::IncomingConnectionDidAccept(addr, result)
+                } else {
+                    QuicEvent::OutgoingConnectionDidConnect(addr, result)
+                });
+            }
+            Msg::Stream {
+                addr,
+                stream_id,
+                writer,
+            } => {
+                let Some(state) = self.connections.get_mut(&addr) else {
+                    return;
+                };
+                state.streams.insert(stream_id, writer);
+                self.send(QuicEvent::IncomingStreamDidAccept(addr, stream_id));
+            }
+            Msg::Closed { addr, id, result } => {
+                if self
+                    .connections
+                    .get(&addr)
+                    .map_or(false, |state| state.connection.stable_id() == id)
+                {
+                    self.connections.remove(&addr);
+                    if let Some(result) = result {
+                        self.send(QuicEvent::ConnectionDidClose(addr, result));
+                    }
+                }
+            }
+        }
+    }
+
+    fn listen(&self, listener: SocketAddr) -> Result<Endpoint, String> {
+        let tls = libp2p_tls::make_server_config(&self.keypair).map_err(|err| err.to_string())?;
+        let mut config = quinn::ServerConfig::with_crypto(Arc::new(tls));
+        config.transport_config(transport_config());
+        Endpoint::server(config, listener).map_err(|err| err.to_string())
+    }
+
+    fn connect(&mut self, addr: SocketAddr, peer_id: PeerId) -> Result<(), String> {
+        // dial from the listening socket, so the remote peer sees our listening port
+        let endpoint = match self.server.as_ref().or(self.client.as_ref()) {
+            Some(endpoint) => endpoint.clone(),
+            None => {
+                let endpoint = Endpoint::client(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+                    .map_err(|err| err.to_string())?;
+                self.client = Some(endpoint.clone());
+                endpoint
+            }
+        };
+        let tls = libp2p_tls::make_client_config(&self.keypair, Some(peer_id.into()))
+            .map_err(|err| err.to_string())?;
+        let mut config = quinn::ClientConfig::new(Arc::new(tls));
+        config.transport_config(transport_config());
+        // the server name is not used, the certificate is verified against the peer id
+        let connecting = endpoint
+            .connect_with(config, addr, "l")
+            .map_err(|err| err.to_string())?;
+        self.handshake(addr, connecting, false);
+        Ok(())
+    }
+
+    fn accept(&self, endpoint: Endpoint) {
+        let msg_sender = self.msg_sender.clone();
+        let event_sender = self.event_sender.clone();
+        tokio::spawn(async move {
+            while let Some(connecting) = endpoint.accept().await {
+                let addr = connecting.remote_address();
+                tokio::spawn(handshake(
+                    addr,
+                    connecting,
+                    true,
+                    msg_sender.clone(),
+                    event_sender.clone(),
+                ));
+            }
+        });
+    }
+
+    fn handshake(&self, addr: SocketAddr, connecting: Connecting, incoming: bool) {
+        tokio::spawn(handshake(
+            addr,
+            connecting,
+            incoming,
+            self.msg_sender.clone(),
+            self.event_sender.clone(),
+        ));
+    }
+
+    fn accept_streams(&self, addr: SocketAddr, connection: Connection, incoming: bool) {
+        let msg_sender = self.msg_sender.clone();
+        let event_sender = self.event_sender.clone();
+        tokio::spawn(async move {
+            let mut index = 0;
+            while let Ok((send, recv)) = connection.accept_bi().await {
+                let Some(stream_id) =
+                    P2pNetworkQuicConnectionState::remote_stream_id(incoming, index)
+                else {
+                    connection.close(VarInt::from_u32(0), b"too many streams");
+                    return;
+                };
+                index += 1;
+                let (writer, data_receiver) = mpsc::unbounded_channel();
+                msg_sender
+                    .send(Msg::Stream {
+                        addr,
+                        stream_id,
+                        writer,
+                    })
+                    .unwrap_or_default();
+                tokio::spawn(write(send, data_receiver));
+                tokio::spawn(read(addr, stream_id, recv, event_sender.clone()));
+            }
+        });
+    }
+
+    fn watch(&self, addr: SocketAddr, connection: Connection) {
+        let msg_sender = self.msg_sender.clone();
+        tokio::spawn(async move {
+            let result = match connection.closed().await {
+                // closed by the state machine, it doesn't need to be notified
+                ConnectionError::LocallyClosed => None,
+                ConnectionError::ApplicationClosed(_) => Some(Ok(())),
+                err => Some(Err(err.to_string())),
+            };
+            let id = connection.stable_id();
+            msg_sender
+                .send(Msg::Closed { addr, id, result })
+                .unwrap_or_default();
+        });
+    }
+}
+
+async fn handshake<F>(
+    addr: SocketAddr,
+    connecting: Connecting,
+    incoming: bool,
+    msg_sender: mpsc::UnboundedSender<Msg>,
+    event_sender: Arc<F>,
+) where
+    F: Fn(QuicEvent),
+{
+    let result = match connecting.await {
+        Ok(connection) => match remote_peer_id(&connection) {
+            Ok(peer_id) => {
+                let msg = Msg::Connected {
+                    addr,
+                    connection,
+                    incoming,
+                    peer_id,
+                };
+                msg_sender.send(msg).unwrap_or_default();
+                return;
+            }
+            Err(err) => {
+                connection.close(VarInt::from_u32(0), b"");
+                Err(err)
+            }
+        },
+        Err(err) => Err(err.to_string()),
+    };
+    event_sender(if incoming {
+        QuicEvent::IncomingConnectionDidAccept(addr, result)
+    } else {
+        QuicEvent::OutgoingConnectionDidConnect(addr, result)
+    });
+}
+
+async fn read<F>(
+    addr: SocketAddr,
+    stream_id: StreamId,
+    mut recv: quinn::RecvStream,
+    event_sender: Arc<F>,
+) where
+    F: Fn(QuicEvent),
+{
+    loop {
+        let result = match recv.read_chunk(usize::MAX, true).await {
+            Ok(Some(chunk)) => Ok((Data::from(chunk.bytes.to_vec()), false)),
+            Ok(None) => Ok((Data::from(vec![]), true)),
+            // the connection error is reported when the connection is closed
+            Err(ReadError::ConnectionLost(_)) => return,
+            Err(err) => Err(err.to_string()),
+        };
+        let done = !matches!(result, Ok((_, false)));
+        event_sender(QuicEvent::IncomingDataDidReceive(addr, stream_id, result));
+        if done {
+            return;
+        }
+    }
+}
+
+async fn write(
+    mut send: quinn::SendStream,
+    mut data_receiver: mpsc::UnboundedReceiver<(Box<[u8]>, bool)>,
+) {
+    while let Some((data, fin)) = data_receiver.recv().await {
+        if send.write_all(&data).await.is_err() {
+            return;
+        }
+        if fin {
+            send.finish().await.unwrap_or_default();
+            return;
+        }
+    }
+}
+
+fn remote_peer_id(connection: &Connection) -> Result<PeerId, String> {
+    let certificates = connection
+        .peer_identity()
+        .and_then(|identity| identity.downcast::<Vec<rustls::Certificate>>().ok())
+        .ok_or_else(|| "remote peer has no certificate".to_owned())?;
+    let certificate = certificates
+        .first()
+        .ok_or_else(|| "remote peer has no certificate".to_owned())?;
+    let certificate = libp2p_tls::certificate::parse(certificate).map_err(|err| err.to_string())?;
+    Ok(certificate.peer_id().into())
+}
+
+/// The same parameters as rust-libp2p uses for its QUIC transport.
+fn transport_config() -> Arc<quinn::TransportConfig> {
+    let mut config = quinn::TransportConfig::default();
+    config
+        .max_idle_timeout(Some(VarInt::from_u32(10_000).into()))
+        .keep_alive_interval(Some(Duration::from_secs(5)))
+        .max_concurrent_bidi_streams(VarInt::from_u32(256))
+        .allow_spin(false);
+    Arc::new(config)
+}
@@ -0,0 +1,87 @@
+#[cfg(feature = "p2p-quic")]
+mod endpoint;
+
+use openmina_core::channels::mpsc;
+
+use crate::{identity::SecretKey, QuicCmd, QuicEvent};
+
+/// QUIC transport, the endpoint and its connections live in a separate thread.
+pub enum QuicService {
+    Pending(SecretKey),
+    Ready(mpsc::UnboundedSender<QuicCmd>),
+}
+
+impl redux::TimeService for QuicService {}
+
+impl redux::Service for QuicService {}
+
+impl QuicService {
+    pub fn pending(secret_key: SecretKey) -> Self {
+        QuicService::Pending(secret_key)
+    }
+
+    pub fn new<F>(secret_key: SecretKey, event_sender: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(QuicEvent),
+    {
+        let mut service = QuicService::pending(secret_key);
+        service.run(event_sender);
+        service
+    }
+
+    pub fn run<F>(&mut self, event_sender: F)
+    where
+        F: 'static + Send + Sync + Fn(QuicEvent),
+    {
+        let QuicService::Pending(secret_key) = self else {
+            debug_assert!(false, "quic service is already running");
+            return;
+        };
+        let (cmd_sender, cmd_receiver) = mpsc::unbounded_channel();
+        spawn(secret_key.clone(), cmd_receiver, event_sender);
+        *self = QuicService::Ready(cmd_sender);
+    }
+
+    pub fn send_cmd(&mut self, cmd: QuicCmd) {
+        let QuicService::Ready(cmd_sender) = self else {
+            debug_assert!(false, "quic service is not initialized");
+            return;
+        };
+        cmd_sender.send(cmd).unwrap_or_default();
+    }
+
+    pub fn mocked() -> Self {
+        QuicService::Ready(mpsc::unbounded_channel().0)
+    }
+}
+
+#[cfg(feature = "p2p-quic")]
+use self::endpoint::spawn;
+
+/// Without the `p2p-quic` feature every attempt to use the transport fails.
+#[cfg(not(feature = "p2p-quic"))]
+fn spawn<F>(
+    _secret_key: SecretKey,
+    mut cmd_receiver: mpsc::UnboundedReceiver<QuicCmd>,
+    event_sender: F,
+) where
+    F: 'static + Send + Sync + Fn(QuicEvent),
+{
+    const ERROR: &str = "QUIC transport is not enabled";
+
+    std::thread::spawn(move || {
+        while let Some(cmd) = cmd_receiver.blocking_recv() {
+            match cmd {
+                QuicCmd::ListenOn(listener) => event_sender(QuicEvent::ListenerError {
+                    listener,
+                    error: ERROR.to_owned(),
+                }),
+                QuicCmd::Connect(addr, _) => event_sender(QuicEvent::OutgoingConnectionDidConnect(
+                    addr,
+                    Err(ERROR.to_owned()),
+                )),
+                QuicCmd::OpenStream(..) | QuicCmd::Send(..) | QuicCmd::Disconnect(_) => {}
+            }
+        }
+    });
+}
@@ -112,6 +112,7 @@ pub type OnConnectionStateChangeHdlrFn = Box<
 
 pub struct RTCConfig {
     pub ice_servers: RTCConfigIceServers,
+    pub ice_transport_policy: webrtc::IceTransportPolicy,
     // TODO(binier): certificate
 }
 
@@ -130,26 +131,30 @@ pub struct RTCChannelConfig {
     pub negotiated: Option<u16>,
 }
 
-impl Default for RTCConfigIceServers {
-    fn default() -> Self {
-        Self(vec![
-            RTCConfigIceServer {
-                urls: vec!["stun:65.109.110.75:3478".to_owned()],
-                username: Some("openmina".to_owned()),
-                credential: Some("webrtc".to_owned()),
-            },
-            RTCConfigIceServer {
-                urls: vec![
-                    "stun:stun.l.google.com:19302".to_owned(),
-                    "stun:stun1.l.google.com:19302".to_owned(),
-                    "stun:stun2.l.google.com:19302".to_owned(),
-                    "stun:stun3.l.google.com:19302".to_owned(),
-                    "stun:stun4.l.google.com:19302".to_owned(),
-                ],
-                username: None,
-                credential: None,
-            },
-        ])
+impl RTCConfig {
+    /// Time-limited TURN credentials are generated for each connection,
+    /// so that they are valid at the time it is being established.
+    pub fn new(config: &webrtc::P2pIceConfig, now: redux::Timestamp) -> Self {
+        let ice_servers = config
+            .servers
+            .iter()
+            .map(|server| {
+                let (username, credential) = server
+                    .credentials
+                    .as_ref()
+                    .map(|credentials| credentials.resolve(now))
+                    .unzip();
+                RTCConfigIceServer {
+                    urls: server.urls.clone(),
+                    username,
+                    credential,
+                }
+            })
+            .collect();
+        Self {
+            ice_servers: RTCConfigIceServers(ice_servers),
+            ice_transport_policy: config.transport_policy,
+        }
     }
 }
 
@@ -197,7 +202,7 @@ async fn wait_for_ice_gathering_complete(pc: &RTCConnection) {
 }
 
 // TODO(binier): cancel future if peer cmd sender is dropped.
-async fn peer_start(args: PeerAddArgs) {
+async fn peer_start(args: PeerAddArgs, ice_config: Arc<webrtc::P2pIceConfig>) {
     let PeerAddArgs {
         peer_id,
         kind,
@@ -206,9 +211,7 @@ async fn peer_start(args: PeerAddArgs) {
     } = args;
     let is_outgoing = matches!(kind, PeerConnectionKind::Outgoing);
 
-    let config = RTCConfig {
-        ice_servers: Default::default(),
-    };
+    let config = RTCConfig::new(&ice_config, openmina_core::log::system_time());
     let fut = async {
         let pc = RTCConnection::create(config).await?;
         let main_channel = pc
@@ -332,6 +335,11 @@ async fn peer_start(args: PeerAddArgs) {
 
     let _ = event_sender(P2pConnectionEvent::Finalized(peer_id, Ok(())).into());
 
+    if let Some(candidate_pair) = pc.selected_candidate_pair().await {
+        let _ =
+            event_sender(P2pConnectionEvent::CandidatePairSelected(peer_id, candidate_pair).into());
+    }
+
     peer_loop(peer_id, event_sender, cmd_receiver, pc).await
 }
 
@@ -621,17 +629,22 @@ pub trait P2pServiceWebrtc: redux::Service {
 
     fn peers(&mut self) -> &mut BTreeMap<PeerId, PeerState>;
 
-    fn init<S: TaskSpawner>(secret_key: SecretKey, spawner: S) -> P2pServiceCtx {
+    fn init<S: TaskSpawner>(
+        secret_key: SecretKey,
+        ice_config: webrtc::P2pIceConfig,
+        spawner: S,
+    ) -> P2pServiceCtx {
         let (cmd_sender, mut cmd_receiver) = mpsc::unbounded_channel();
 
         // TODO: sing/verify SDP
         let _ = secret_key;
 
+        let ice_config = Arc::new(ice_config);
         spawner.spawn_main("webrtc", async move {
             while let Some(cmd) = cmd_receiver.recv().await {
                 match cmd {
                     Cmd::PeerAdd(args) => {
-                        spawn_local(peer_start(args));
+                        spawn_local(peer_start(args, ice_config.clone()));
                     }
                 }
             }
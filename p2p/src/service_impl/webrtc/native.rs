@@ -4,6 +4,7 @@ use std::sync::Arc;
 use webrtc::{
     api::APIBuilder,
     data_channel::{data_channel_init::RTCDataChannelInit, RTCDataChannel},
+    ice::candidate::CandidateType,
     ice_transport::{
         ice_credential_type::RTCIceCredentialType, ice_gatherer_state::RTCIceGathererState,
         ice_gathering_state::RTCIceGatheringState, ice_server::RTCIceServer,
@@ -13,11 +14,12 @@ use webrtc::{
         policy::ice_transport_policy::RTCIceTransportPolicy,
         sdp::session_description::RTCSessionDescription, RTCPeerConnection,
     },
+    stats::StatsReportType,
 };
 
 use crate::{
     connection::P2pConnectionResponse,
-    webrtc::{Answer, Offer},
+    webrtc::{Answer, IceCandidatePair, IceCandidateType, IceTransportPolicy, Offer},
 };
 
 use super::{OnConnectionStateChangeHdlrFn, RTCChannelConfig, RTCConfig};
@@ -109,6 +111,28 @@ impl RTCConnection {
         }
     }
 
+    pub async fn selected_candidate_pair(&self) -> Option<IceCandidatePair> {
+        let stats = self.0.get_stats().await;
+        let candidate_type = |id: &str| match stats.reports.get(id)? {
+            StatsReportType::LocalCandidate(candidate)
+            | StatsReportType::RemoteCandidate(candidate) => match candidate.candidate_type {
+                CandidateType::Host => Some(IceCandidateType::Host),
+                CandidateType::ServerReflexive => Some(IceCandidateType::Srflx),
+                CandidateType::PeerReflexive => Some(IceCandidateType::Prflx),
+                CandidateType::Relay => Some(IceCandidateType::Relay),
+                CandidateType::Unspecified => None,
+            },
+            _ => None,
+        };
+        stats.reports.values().find_map(|report| match report {
+            StatsReportType::CandidatePair(pair) if pair.nominated => Some(IceCandidatePair {
+                local: candidate_type(&pair.local_candidate_id)?,
+                remote: candidate_type(&pair.remote_candidate_id)?,
+            }),
+            _ => None,
+        })
+    }
+
     pub fn on_connection_state_change(&self, handler: OnConnectionStateChangeHdlrFn) {
         self.0.on_peer_connection_state_change(handler)
     }
@@ -188,7 +212,10 @@ impl From<RTCConfig> for RTCConfiguration {
     fn from(value: RTCConfig) -> Self {
         RTCConfiguration {
             ice_servers: value.ice_servers.0.into_iter().map(Into::into).collect(),
-            ice_transport_policy: RTCIceTransportPolicy::All,
+            ice_transport_policy: match value.ice_transport_policy {
+                IceTransportPolicy::All => RTCIceTransportPolicy::All,
+                IceTransportPolicy::Relay => RTCIceTransportPolicy::Relay,
+            },
             ..Default::default()
         }
     }
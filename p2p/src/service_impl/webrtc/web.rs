@@ -13,7 +13,7 @@ use openmina_core::channels::oneshot;
 
 use crate::{
     connection::P2pConnectionResponse,
-    webrtc::{Answer, Offer},
+    webrtc::{Answer, IceCandidatePair, IceCandidateType, IceTransportPolicy, Offer},
 };
 
 use super::{OnConnectionStateChangeHdlrFn, RTCChannelConfig, RTCConfig};
@@ -105,6 +105,44 @@ impl RTCConnection {
         }
     }
 
+    pub async fn selected_candidate_pair(&self) -> Option<IceCandidatePair> {
+        let stats: js_sys::Map = JsFuture::from(self.0.get_stats())
+            .await
+            .ok()?
+            .unchecked_into();
+        let field = |report: &JsValue, name: &str| js_sys::Reflect::get(report, &name.into()).ok();
+        let candidate_type = |id: JsValue| {
+            let candidate = stats.get(&id);
+            match field(&candidate, "candidateType")?.as_string()?.as_str() {
+                "host" => Some(IceCandidateType::Host),
+                "srflx" => Some(IceCandidateType::Srflx),
+                "prflx" => Some(IceCandidateType::Prflx),
+                "relay" => Some(IceCandidateType::Relay),
+                _ => None,
+            }
+        };
+
+        let mut selected = None;
+        stats.for_each(&mut |report, _| {
+            let is_pair = field(&report, "type")
+                .and_then(|v| v.as_string())
+                .as_deref()
+                == Some("candidate-pair");
+            let is_nominated = field(&report, "nominated").and_then(|v| v.as_bool()) == Some(true);
+            if selected.is_none() && is_pair && is_nominated {
+                selected = Some((
+                    field(&report, "localCandidateId"),
+                    field(&report, "remoteCandidateId"),
+                ));
+            }
+        });
+        let (local, remote) = selected?;
+        Some(IceCandidatePair {
+            local: candidate_type(local?)?,
+            remote: candidate_type(remote?)?,
+        })
+    }
+
     pub fn on_connection_state_change(&self, mut f: OnConnectionStateChangeHdlrFn) {
         // Closure::wrap(data)
         let callback = Closure::new(move |state: RTCConnectionState| {
@@ -246,7 +284,10 @@ impl From<RTCConfig> for RtcConfiguration {
         let mut config = Self::new();
         config
             .ice_servers(&JsValue::from_serde(&value.ice_servers).unwrap())
-            .ice_transport_policy(RtcIceTransportPolicy::All);
+            .ice_transport_policy(match value.ice_transport_policy {
+                IceTransportPolicy::All => RtcIceTransportPolicy::All,
+                IceTransportPolicy::Relay => RtcIceTransportPolicy::Relay,
+            });
         config
     }
 }
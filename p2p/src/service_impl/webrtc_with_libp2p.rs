@@ -3,13 +3,15 @@ use crate::{
     connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionService},
     disconnection::P2pDisconnectionService,
     identity::SecretKey,
-    P2pChannelEvent, P2pEvent, PeerId,
+    nat::{P2pNatService, P2pNatTransport},
+    webrtc::P2pIceConfig,
+    P2pChannelEvent, P2pEvent, P2pNatEvent, PeerId,
 };
 
 #[cfg(feature = "p2p-libp2p")]
-use super::mio::MioService;
+use super::{mio::MioService, quic::QuicService};
 #[cfg(feature = "p2p-libp2p")]
-use crate::P2pMioService;
+use crate::{P2pMioService, P2pQuicService};
 
 use super::{webrtc::P2pServiceWebrtc, TaskSpawner};
 
@@ -17,15 +19,26 @@ pub struct P2pServiceCtx {
     pub webrtc: super::webrtc::P2pServiceCtx,
     #[cfg(feature = "p2p-libp2p")]
     pub mio: MioService,
+    #[cfg(feature = "p2p-libp2p")]
+    pub quic: QuicService,
 }
 
 pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
     #[cfg(feature = "p2p-libp2p")]
     fn mio(&mut self) -> &mut MioService;
 
-    fn init<S: TaskSpawner>(secret_key: SecretKey, spawner: S) -> P2pServiceCtx {
+    #[cfg(feature = "p2p-libp2p")]
+    fn quic(&mut self) -> &mut QuicService;
+
+    fn init<S: TaskSpawner>(
+        secret_key: SecretKey,
+        ice_config: P2pIceConfig,
+        spawner: S,
+    ) -> P2pServiceCtx {
         P2pServiceCtx {
-            webrtc: <Self as P2pServiceWebrtc>::init(secret_key, spawner),
+            #[cfg(feature = "p2p-libp2p")]
+            quic: QuicService::pending(secret_key.clone()),
+            webrtc: <Self as P2pServiceWebrtc>::init(secret_key, ice_config, spawner),
             #[cfg(feature = "p2p-libp2p")]
             mio: MioService::default(),
         }
@@ -61,6 +74,18 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
                         addr, opts.port,
                     )));
             }
+            #[cfg(feature = "p2p-libp2p")]
+            P2pConnectionOutgoingInitOpts::LibP2PQuic(opts) => {
+                let addr = match std::net::SocketAddr::try_from(&opts) {
+                    Ok(addr) => addr,
+                    Err(err) => {
+                        openmina_core::error!(openmina_core::log::system_time(); "unsupported host for quic: {err}");
+                        return;
+                    }
+                };
+                self.quic()
+                    .send_cmd(crate::QuicCmd::Connect(addr, opts.peer_id));
+            }
         }
     }
 
@@ -133,3 +158,51 @@ where
         self.mio().send_cmd(cmd)
     }
 }
+
+impl<T> P2pQuicService for T
+where
+    T: P2pServiceWebrtcWithLibp2p,
+{
+    #[cfg(feature = "p2p-libp2p")]
+    fn start_quic(&mut self) {
+        let event_sender = self.event_sender().clone();
+        self.quic().run(move |quic_event| {
+            event_sender
+                .send(P2pEvent::QuicEvent(quic_event).into())
+                .unwrap_or_default()
+        });
+    }
+
+    #[cfg(feature = "p2p-libp2p")]
+    fn send_quic_cmd(&mut self, cmd: crate::QuicCmd) {
+        self.quic().send_cmd(cmd)
+    }
+}
+
+impl<T: P2pServiceWebrtcWithLibp2p> P2pNatService for T {
+    fn nat_map_port(
+        &mut self,
+        transport: P2pNatTransport,
+        internal: std::net::SocketAddr,
+        lifetime: std::time::Duration,
+    ) {
+        let event_sender = self.event_sender().clone();
+        let spawned = std::thread::Builder::new()
+            .name("nat-port-mapping".to_owned())
+            .spawn({
+                let event_sender = event_sender.clone();
+                move || {
+                    let result = super::nat::map_port(transport, internal, lifetime);
+                    event_sender
+                        .send(P2pEvent::Nat(P2pNatEvent::PortMapped(transport, result)).into())
+                        .unwrap_or_default();
+                }
+            });
+        if let Err(err) = spawned {
+            let result = Err(format!("error spawning thread: {err}"));
+            event_sender
+                .send(P2pEvent::Nat(P2pNatEvent::PortMapped(transport, result)).into())
+                .unwrap_or_default();
+        }
+    }
+}
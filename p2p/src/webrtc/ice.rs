@@ -0,0 +1,240 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// ICE servers and the transport policy used for WebRTC connections.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct P2pIceConfig {
+    pub servers: Vec<IceServer>,
+    pub transport_policy: IceTransportPolicy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IceServer {
+    /// `stun:`, `stuns:`, `turn:` or `turns:` urls of the server.
+    pub urls: Vec<String>,
+    pub credentials: Option<IceServerCredentials>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum IceServerCredentials {
+    Static {
+        username: String,
+        credential: String,
+    },
+    /// Time-limited credentials of the TURN REST API, derived from the
+    /// secret shared with the TURN server (coturn's `static-auth-secret`).
+    SharedSecret {
+        secret: String,
+        /// For how long the generated credentials are valid.
+        ttl: Duration,
+        user: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IceTransportPolicy {
+    /// Use all the gathered candidates.
+    #[default]
+    All,
+    /// Only use candidates relayed through TURN servers.
+    Relay,
+}
+
+/// Type of the ICE candidate, as defined in RFC 8445.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IceCandidateType {
+    Host,
+    Srflx,
+    Prflx,
+    Relay,
+}
+
+/// Candidate pair selected by ICE for the connection.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IceCandidatePair {
+    pub local: IceCandidateType,
+    pub remote: IceCandidateType,
+}
+
+#[derive(Error, Serialize, Deserialize, Debug, Clone)]
+pub enum IceServerParseError {
+    #[error("missing credential for the username")]
+    MissingCredential,
+    #[error("unsupported ice server url: {0}")]
+    UnsupportedUrl(String),
+}
+
+impl P2pIceConfig {
+    pub fn with_servers(mut self, servers: Vec<IceServer>) -> Self {
+        self.servers = servers;
+        self
+    }
+
+    pub fn with_transport_policy(mut self, transport_policy: IceTransportPolicy) -> Self {
+        self.transport_policy = transport_policy;
+        self
+    }
+}
+
+impl Default for P2pIceConfig {
+    fn default() -> Self {
+        Self {
+            servers: vec![
+                IceServer {
+                    urls: vec!["stun:65.109.110.75:3478".to_owned()],
+                    credentials: Some(IceServerCredentials::Static {
+                        username: "openmina".to_owned(),
+                        credential: "webrtc".to_owned(),
+                    }),
+                },
+                IceServer {
+                    urls: vec![
+                        "stun:stun.l.google.com:19302".to_owned(),
+                        "stun:stun1.l.google.com:19302".to_owned(),
+                        "stun:stun2.l.google.com:19302".to_owned(),
+                        "stun:stun3.l.google.com:19302".to_owned(),
+                        "stun:stun4.l.google.com:19302".to_owned(),
+                    ],
+                    credentials: None,
+                },
+            ],
+            transport_policy: IceTransportPolicy::All,
+        }
+    }
+}
+
+impl IceServer {
+    pub fn is_turn(&self) -> bool {
+        self.urls
+            .iter()
+            .any(|url| url.starts_with("turn:") || url.starts_with("turns:"))
+    }
+}
+
+impl IceServerCredentials {
+    /// Username and credential to be used for the connection started at `now`.
+    pub fn resolve(&self, now: redux::Timestamp) -> (String, String) {
+        match self {
+            Self::Static {
+                username,
+                credential,
+            } => (username.clone(), credential.clone()),
+            Self::SharedSecret { secret, ttl, user } => {
+                let expires_at = u64::from(now) / 1_000_000_000 + ttl.as_secs();
+                let username = match user {
+                    Some(user) => format!("{expires_at}:{user}"),
+                    None => expires_at.to_string(),
+                };
+                let mut mac = Hmac::<sha1::Sha1>::new_from_slice(secret.as_bytes())
+                    .expect("hmac accepts keys of any length");
+                mac.update(username.as_bytes());
+                let credential =
+                    base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes());
+                (username, credential)
+            }
+        }
+    }
+}
+
+impl IceCandidatePair {
+    /// Whether the traffic goes through a TURN server.
+    pub fn is_relayed(&self) -> bool {
+        self.local == IceCandidateType::Relay || self.remote == IceCandidateType::Relay
+    }
+}
+
+impl fmt::Display for IceCandidateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Host => write!(f, "host"),
+            Self::Srflx => write!(f, "srflx"),
+            Self::Prflx => write!(f, "prflx"),
+            Self::Relay => write!(f, "relay"),
+        }
+    }
+}
+
+impl fmt::Display for IceCandidatePair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.local, self.remote)
+    }
+}
+
+impl FromStr for IceServer {
+    type Err = IceServerParseError;
+
+    /// Parses `[USERNAME:CREDENTIAL@]URL`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (credentials, url) = match s.rsplit_once('@') {
+            Some((auth, url)) => {
+                let (username, credential) = auth
+                    .split_once(':')
+                    .ok_or(IceServerParseError::MissingCredential)?;
+                let credentials = IceServerCredentials::Static {
+                    username: username.to_owned(),
+                    credential: credential.to_owned(),
+                };
+                (Some(credentials), url)
+            }
+            None => (None, s),
+        };
+
+        if !["stun:", "stuns:", "turn:", "turns:"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme))
+        {
+            return Err(IceServerParseError::UnsupportedUrl(url.to_owned()));
+        }
+
+        Ok(Self {
+            urls: vec![url.to_owned()],
+            credentials,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ice_server_parse() {
+        let server = "stun:stun.l.google.com:19302".parse::<IceServer>().unwrap();
+        assert_eq!(server.urls, vec!["stun:stun.l.google.com:19302"]);
+        assert_eq!(server.credentials, None);
+        assert!(!server.is_turn());
+
+        let server = "user:pass@turn:10.0.0.1:3478?transport=udp"
+            .parse::<IceServer>()
+            .unwrap();
+        assert_eq!(server.urls, vec!["turn:10.0.0.1:3478?transport=udp"]);
+        assert_eq!(
+            server.credentials,
+            Some(IceServerCredentials::Static {
+                username: "user".to_owned(),
+                credential: "pass".to_owned(),
+            })
+        );
+        assert!(server.is_turn());
+
+        assert!("user@turn:10.0.0.1:3478".parse::<IceServer>().is_err());
+        assert!("http://10.0.0.1:3478".parse::<IceServer>().is_err());
+    }
+
+    #[test]
+    fn test_turn_rest_credentials() {
+        let credentials = IceServerCredentials::SharedSecret {
+            secret: "north".to_owned(),
+            ttl: Duration::from_secs(3600),
+            user: Some("openmina".to_owned()),
+        };
+        let now = redux::Timestamp::new(1_700_000_000 * 1_000_000_000);
+        let (username, credential) = credentials.resolve(now);
+        assert_eq!(username, "1700003600:openmina");
+        assert_eq!(credential, "yBVpm+3yoqqUvKXrr55rXU1BhZY=");
+    }
+}
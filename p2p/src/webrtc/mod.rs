@@ -4,5 +4,11 @@ pub use host::Host;
 mod signal;
 pub use signal::{Answer, Offer, Signal};
 
+mod ice;
+pub use ice::{
+    IceCandidatePair, IceCandidateType, IceServer, IceServerCredentials, IceServerParseError,
+    IceTransportPolicy, P2pIceConfig,
+};
+
 mod signaling_method;
 pub use signaling_method::{HttpSignalingInfo, SignalingMethod, SignalingMethodParseError};
@@ -1,3 +1,4 @@
+use binprot_derive::{BinProtRead, BinProtWrite};
 use derive_more::From;
 use serde::{Deserialize, Serialize};
 
@@ -5,7 +6,7 @@ use crate::identity::{PeerId, PublicKey};
 
 use super::Host;
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct Offer {
     pub sdp: String,
     /// Offerer's identity public key.
@@ -19,7 +20,7 @@ pub struct Offer {
     pub listen_port: u16,
 }
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct Answer {
     pub sdp: String,
     /// Offerer's identity public key.
@@ -7,10 +7,17 @@ use binprot_derive::{BinProtRead, BinProtWrite};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::PeerId;
+
 #[derive(BinProtWrite, BinProtRead, Eq, PartialEq, Ord, PartialOrd, Debug, Clone)]
 pub enum SignalingMethod {
     Http(HttpSignalingInfo),
     Https(HttpSignalingInfo),
+    /// Offer and answer are relayed through an already connected peer,
+    /// using the signaling channel.
+    P2p {
+        relay_peer_id: PeerId,
+    },
 }
 
 impl SignalingMethod {
@@ -20,7 +27,7 @@ impl SignalingMethod {
         let (http, info) = match self {
             Self::Http(info) => ("http", info),
             Self::Https(info) => ("https", info),
-            // _ => return None,
+            _ => return None,
         };
         Some(format!(
             "{http}://{}:{}/mina/webrtc/signal",
@@ -40,6 +47,9 @@ impl fmt::Display for SignalingMethod {
                 write!(f, "/https")?;
                 signaling.fmt(f)
             }
+            Self::P2p { relay_peer_id } => {
+                write!(f, "/p2p/{relay_peer_id}")
+            }
         }
     }
 }
@@ -54,6 +64,8 @@ pub enum SignalingMethodParseError {
     HostParseError(String),
     #[error("host parse error: {0}")]
     PortParseError(String),
+    #[error("relay peer id parse error: {0}")]
+    PeerIdParseError(String),
 }
 
 impl FromStr for SignalingMethod {
@@ -73,6 +85,13 @@ impl FromStr for SignalingMethod {
         match &s[1..method_end_index] {
             "http" => Ok(Self::Http(s[method_end_index..].parse()?)),
             "https" => Ok(Self::Https(s[method_end_index..].parse()?)),
+            "p2p" => {
+                let relay_peer_id = s[method_end_index + 1..]
+                    .trim_end_matches('/')
+                    .parse::<PeerId>()
+                    .map_err(|err| SignalingMethodParseError::PeerIdParseError(err.to_string()))?;
+                Ok(Self::P2p { relay_peer_id })
+            }
             method => Err(SignalingMethodParseError::UnknownSignalingMethod(
                 method.to_owned(),
             )),
@@ -98,3 +117,19 @@ impl<'de> serde::Deserialize<'de> for SignalingMethod {
         s.parse().map_err(serde::de::Error::custom)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p2p_signaling_method_roundtrip() {
+        let method = SignalingMethod::P2p {
+            relay_peer_id: PeerId::from_bytes([7; 32]),
+        };
+        let s = method.to_string();
+        assert!(s.starts_with("/p2p/"));
+        assert_eq!(s.parse::<SignalingMethod>().unwrap(), method);
+        assert_eq!(method.http_url(), None);
+    }
+}
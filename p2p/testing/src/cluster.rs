@@ -344,19 +344,30 @@ impl Cluster {
             Self::secret_key(config.peer_id, self.rust_nodes.len(), RUST_NODE_SIG_BYTE);
         let libp2p_port = self.next_port()?;
         let listen_port = self.next_port()?;
+        let relays = config
+            .relays
+            .into_iter()
+            .map(|p| self.init_opts(p))
+            .collect::<Result<Vec<_>>>()?;
         let initial_peers = config
             .initial_peers
             .into_iter()
             .map(|p| self.init_opts(p))
+            .chain(relays.iter().cloned().map(Ok))
             .collect::<Result<_>>()?;
         let config = P2pConfig {
             libp2p_port: Some(libp2p_port),
+            quic_port: config.quic.then_some(libp2p_port),
             listen_port,
             identity_pub_key: secret_key.public_key(),
             initial_peers,
+            address_book: Vec::new(),
             ask_initial_peers_interval: Duration::from_secs(5),
             enabled_channels: p2p::channels::ChannelId::for_libp2p().collect(),
             peer_discovery: config.discovery,
+            nat_port_mapping: false,
+            relay_hop: config.relay_hop,
+            relays: relays.iter().map(|opts| *opts.peer_id()).collect(),
             timeouts: config.timeouts,
             limits: config.limits,
             initial_time: Duration::ZERO,
@@ -368,6 +379,7 @@ impl Cluster {
     pub fn add_rust_node(&mut self, config: RustNodeConfig) -> Result<RustNodeId> {
         let node_idx = self.rust_nodes.len();
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let behind_nat = config.behind_nat;
         let (config, secret_key) = self.rust_node_config(config)?;
         let (cmd_sender, _cmd_receiver) = mpsc::unbounded_channel();
 
@@ -377,6 +389,7 @@ impl Cluster {
             event_sender,
             cmd_sender,
             self.last_idle_instant,
+            behind_nat,
         );
 
         let store = crate::redux::Store::new(
@@ -413,8 +426,14 @@ impl Cluster {
         let secret_key = Self::secret_key(config.peer_id, node_id.0, LIBP2P_NODE_SIG_BYTE);
         let libp2p_port = self.next_port()?;
 
-        let swarm = create_swarm(secret_key, libp2p_port, config.port_reuse, &self.chain_id)
-            .map_err(|err| Error::Libp2pSwarm(err.to_string()))?;
+        let swarm = create_swarm(
+            secret_key,
+            libp2p_port,
+            config.port_reuse,
+            config.quic,
+            &self.chain_id,
+        )
+        .map_err(|err| Error::Libp2pSwarm(err.to_string()))?;
         self.libp2p_nodes.push(Libp2pNode::new(swarm));
 
         Ok(node_id)
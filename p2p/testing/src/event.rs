@@ -7,7 +7,7 @@ use p2p::{
     identify::P2pIdentifyAction,
     network::identify::P2pNetworkIdentify,
     peer::P2pPeerAction,
-    MioEvent, P2pAction, P2pEvent, PeerId,
+    MioEvent, P2pAction, P2pEvent, PeerId, QuicEvent,
 };
 
 use crate::cluster::ClusterEvent;
@@ -202,6 +202,7 @@ pub fn is_error(event: &ClusterEvent) -> bool {
         RustNodeEvent::P2p { event } => match event {
             P2pEvent::Connection(_event) => false, // TODO
             P2pEvent::Channel(_event) => false,    // TODO
+            P2pEvent::Nat(_event) => false,
             P2pEvent::MioEvent(event) => matches!(
                 event,
                 MioEvent::ListenerError { .. }
@@ -211,6 +212,14 @@ pub fn is_error(event: &ClusterEvent) -> bool {
                     | MioEvent::OutgoingDataDidSend(_, Err(_))
                     | MioEvent::ConnectionDidClose(_, Err(_))
             ),
+            P2pEvent::QuicEvent(event) => matches!(
+                event,
+                QuicEvent::ListenerError { .. }
+                    | QuicEvent::IncomingConnectionDidAccept(_, Err(_))
+                    | QuicEvent::IncomingDataDidReceive(_, _, Err(_))
+                    | QuicEvent::OutgoingConnectionDidConnect(_, Err(_))
+                    | QuicEvent::ConnectionDidClose(_, Err(_))
+            ),
         },
         _ => false,
     }
@@ -227,6 +236,7 @@ pub fn allow_disconnections(event: &ClusterEvent) -> bool {
         RustNodeEvent::P2p { event } => match event {
             P2pEvent::Connection(_event) => false, // TODO
             P2pEvent::Channel(_event) => false,    // TODO
+            P2pEvent::Nat(_event) => false,
             P2pEvent::MioEvent(event) => matches!(
                 event,
                 MioEvent::ListenerError { .. } | MioEvent::IncomingConnectionDidAccept(_, Err(_)) // | MioEvent::IncomingDataDidReceive(_, Err(_))
@@ -234,6 +244,10 @@ pub fn allow_disconnections(event: &ClusterEvent) -> bool {
                                                                                                   // | MioEvent::OutgoingDataDidSend(_, Err(_))
                                                                                                   // | MioEvent::ConnectionDidClose(_, Err(_))
             ),
+            P2pEvent::QuicEvent(event) => matches!(
+                event,
+                QuicEvent::ListenerError { .. } | QuicEvent::IncomingConnectionDidAccept(_, Err(_))
+            ),
         },
         _ => false,
     }
@@ -1,6 +1,7 @@
 use std::{collections::BTreeMap, error::Error, time::Duration};
 
 use libp2p::{
+    core::muxing::StreamMuxerBox,
     gossipsub, identify,
     swarm::{NetworkBehaviour, SwarmEvent, THandlerErr},
     Transport,
@@ -22,6 +23,8 @@ pub struct Libp2pNodeId(pub(super) usize);
 pub struct Libp2pNodeConfig {
     pub peer_id: PeerIdConfig,
     pub port_reuse: bool,
+    /// Also listen for QUIC connections, on the UDP port with the same number.
+    pub quic: bool,
 }
 
 pub type Swarm = libp2p::Swarm<Libp2pBehaviour>;
@@ -91,6 +94,7 @@ pub(crate) fn create_swarm(
     secret_key: p2p::identity::SecretKey,
     port: u16,
     port_reuse: bool,
+    quic: bool,
     chain_id: &ChainId,
 ) -> Result<Swarm, Box<dyn Error>> {
     let identity_keys = libp2p::identity::Keypair::ed25519_from_bytes(secret_key.to_bytes())
@@ -166,7 +170,7 @@ pub(crate) fn create_swarm(
         ongoing_incoming: Default::default(),
     };
 
-    let swarm = libp2p::SwarmBuilder::with_existing_identity(identity_keys)
+    let mut swarm = libp2p::SwarmBuilder::with_existing_identity(identity_keys)
         .with_tokio()
         .with_other_transport(|key| {
             let noise_config = libp2p::noise::Config::new(key).unwrap();
@@ -187,12 +191,29 @@ pub(crate) fn create_swarm(
                 )
                 .expect("listen");
 
-            base_transport
+            let tcp_transport = base_transport
                 .and_then(move |socket, _| libp2p::pnet::PnetConfig::new(psk).handshake(socket))
                 .upgrade(libp2p::core::upgrade::Version::V1)
                 .authenticate(noise_config)
                 .multiplex(yamux_config)
                 .timeout(Duration::from_secs(60))
+                .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)));
+
+            if quic {
+                // QUIC has its own encryption and multiplexing, and no private network support
+                let quic_transport =
+                    libp2p::quic::tokio::Transport::new(libp2p::quic::Config::new(key))
+                        .map(|(peer_id, conn), _| (peer_id, StreamMuxerBox::new(conn)));
+                tcp_transport
+                    .or_transport(quic_transport)
+                    .map(|output, _| match output {
+                        futures::future::Either::Left(output)
+                        | futures::future::Either::Right(output) => output,
+                    })
+                    .boxed()
+            } else {
+                tcp_transport.boxed()
+            }
         })?
         .with_dns()?
         .with_behaviour(|_| behaviour)?
@@ -201,6 +222,14 @@ pub(crate) fn create_swarm(
         })
         .build();
 
+    if quic {
+        swarm.listen_on(libp2p::multiaddr::multiaddr!(
+            Ip4([127, 0, 0, 1]),
+            Udp(port),
+            QuicV1
+        ))?;
+    }
+
     //swarm.behaviour_mut().kademlia.set_mode(Some(Mode::Server));
 
     Ok(swarm)
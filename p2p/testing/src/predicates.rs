@@ -163,6 +163,15 @@ pub fn default_errors(event: &ClusterEvent) -> bool {
                         | p2p::MioEvent::OutgoingDataDidSend(_, Err(_))
                         | p2p::MioEvent::ConnectionDidClose(_, Err(_))
                 ),
+                p2p::P2pEvent::QuicEvent(e) => matches!(
+                    e,
+                    p2p::QuicEvent::ListenerError { .. }
+                        | p2p::QuicEvent::IncomingConnectionDidAccept(_, Err(_))
+                        | p2p::QuicEvent::IncomingDataDidReceive(_, _, Err(_))
+                        | p2p::QuicEvent::OutgoingConnectionDidConnect(_, Err(_))
+                        | p2p::QuicEvent::ConnectionDidClose(_, Err(_))
+                ),
+                p2p::P2pEvent::Nat(_) => false,
             },
             _ => false,
         },
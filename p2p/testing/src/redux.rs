@@ -8,7 +8,10 @@ use openmina_core::{
     },
     ActionEvent,
 };
-use p2p::{MioEvent, P2pAction, P2pEvent, P2pNetworkSchedulerAction, P2pState, PeerId};
+use p2p::{
+    MioEvent, P2pAction, P2pEvent, P2pNetworkQuicAction, P2pNetworkSchedulerAction, P2pState,
+    PeerId, QuicEvent,
+};
 use redux::{ActionMeta, EnablingCondition, SubStore};
 
 use crate::service::ClusterService;
@@ -153,6 +156,49 @@ pub(super) fn event_effect(store: &mut crate::redux::Store, event: P2pEvent) ->
                 }
             }
         },
+        P2pEvent::QuicEvent(event) => match event {
+            QuicEvent::ListenerReady { listener } => {
+                SubStore::dispatch(store, P2pNetworkQuicAction::ListenerReady { listener })
+            }
+            QuicEvent::ListenerError { listener, error } => SubStore::dispatch(
+                store,
+                P2pNetworkQuicAction::ListenerError { listener, error },
+            ),
+            QuicEvent::IncomingConnectionDidAccept(addr, result) => SubStore::dispatch(
+                store,
+                P2pNetworkQuicAction::IncomingDidAccept { addr, result },
+            ),
+            QuicEvent::OutgoingConnectionDidConnect(addr, result) => SubStore::dispatch(
+                store,
+                P2pNetworkQuicAction::OutgoingDidConnect { addr, result },
+            ),
+            QuicEvent::IncomingStreamDidAccept(addr, stream_id) => SubStore::dispatch(
+                store,
+                P2pNetworkQuicAction::IncomingStream { addr, stream_id },
+            ),
+            QuicEvent::IncomingDataDidReceive(addr, stream_id, Ok((data, fin))) => {
+                SubStore::dispatch(
+                    store,
+                    P2pNetworkQuicAction::IncomingData {
+                        addr,
+                        stream_id,
+                        data,
+                        fin,
+                    },
+                )
+            }
+            QuicEvent::IncomingDataDidReceive(addr, _, Err(e)) => SubStore::dispatch(
+                store,
+                P2pNetworkSchedulerAction::Error {
+                    addr,
+                    error: p2p::P2pNetworkConnectionError::QuicError(e),
+                },
+            ),
+            QuicEvent::ConnectionDidClose(addr, result) => SubStore::dispatch(
+                store,
+                P2pNetworkQuicAction::ConnectionClosed { addr, result },
+            ),
+        },
         _ => false,
     }
 }
@@ -27,6 +27,13 @@ pub struct RustNodeConfig {
     pub timeouts: P2pTimeouts,
     pub limits: P2pLimits,
     pub discovery: bool,
+    /// The node is behind a simulated NAT, the connections initiated by
+    /// the remote peers are dropped, so it can only dial out.
+    pub behind_nat: bool,
+    pub relay_hop: bool,
+    pub relays: Vec<Listener>,
+    /// The node also listens for QUIC connections, on the UDP port with the same number.
+    pub quic: bool,
 }
 
 impl RustNodeConfig {
@@ -57,6 +64,29 @@ impl RustNodeConfig {
         self.discovery = discovery;
         self
     }
+
+    pub fn with_behind_nat(mut self, behind_nat: bool) -> Self {
+        self.behind_nat = behind_nat;
+        self
+    }
+
+    pub fn with_relay_hop(mut self, relay_hop: bool) -> Self {
+        self.relay_hop = relay_hop;
+        self
+    }
+
+    pub fn with_relays<T>(mut self, relays: T) -> Self
+    where
+        T: IntoIterator<Item = Listener>,
+    {
+        self.relays = Vec::from_iter(relays);
+        self
+    }
+
+    pub fn with_quic(mut self, quic: bool) -> Self {
+        self.quic = quic;
+        self
+    }
 }
 
 pub struct RustNode {
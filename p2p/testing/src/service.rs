@@ -1,12 +1,18 @@
-use std::{collections::VecDeque, net::IpAddr, time::Instant};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use p2p::{
+    address_book::{P2pAddressBookEntry, P2pAddressBookService},
     identity::SecretKey,
     service_impl::{
-        mio::MioService, services::NativeP2pNetworkService, webrtc::P2pServiceWebrtc,
-        webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p,
+        mio::MioService, quic::QuicService, services::NativeP2pNetworkService,
+        webrtc::P2pServiceWebrtc, webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p,
     },
-    P2pCryptoService, P2pEvent,
+    MioEvent, P2pCryptoService, P2pEvent, QuicEvent,
 };
 use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use redux::{Service, TimeService};
@@ -19,6 +25,7 @@ pub struct ClusterService {
     pub event_sender: mpsc::UnboundedSender<P2pEvent>,
     pub cmd_sender: mpsc::UnboundedSender<p2p::service_impl::webrtc::Cmd>,
     mio: MioService,
+    quic: QuicService,
     peers: std::collections::BTreeMap<p2p::PeerId, p2p::service_impl::webrtc::PeerState>,
     time: Instant,
     keypair: libp2p::identity::Keypair,
@@ -27,6 +34,40 @@ pub struct ClusterService {
     network_service: NativeP2pNetworkService,
 }
 
+/// Simulated NAT in front of the node, it drops the connections initiated
+/// by the remote peers, only the ones dialed by the node itself pass.
+#[derive(Debug, Default)]
+struct SimulatedNat {
+    /// Remote addresses of the dropped QUIC connections. The QUIC endpoint
+    /// accepts them by itself, so all their further events are dropped too.
+    dropped_quic: Mutex<BTreeSet<SocketAddr>>,
+}
+
+impl SimulatedNat {
+    fn passes_mio(&self, event: &MioEvent) -> bool {
+        // the connection is never accepted, so the remote peer's dial times out
+        !matches!(event, MioEvent::IncomingConnectionIsReady { .. })
+    }
+
+    fn passes_quic(&self, event: &QuicEvent) -> bool {
+        let mut dropped = self.dropped_quic.lock().expect("poisoned lock");
+        match event {
+            QuicEvent::ListenerReady { .. } | QuicEvent::ListenerError { .. } => true,
+            QuicEvent::IncomingConnectionDidAccept(addr, _) => {
+                dropped.insert(*addr);
+                false
+            }
+            QuicEvent::OutgoingConnectionDidConnect(addr, _) => {
+                dropped.remove(addr);
+                true
+            }
+            QuicEvent::IncomingStreamDidAccept(addr, _)
+            | QuicEvent::IncomingDataDidReceive(addr, ..) => !dropped.contains(addr),
+            QuicEvent::ConnectionDidClose(addr, _) => !dropped.remove(addr),
+        }
+    }
+}
+
 impl ClusterService {
     pub fn new(
         node_idx: usize,
@@ -34,12 +75,28 @@ impl ClusterService {
         event_sender: mpsc::UnboundedSender<P2pEvent>,
         cmd_sender: mpsc::UnboundedSender<p2p::service_impl::webrtc::Cmd>,
         time: Instant,
+        behind_nat: bool,
     ) -> Self {
+        let nat = behind_nat.then(|| Arc::new(SimulatedNat::default()));
         let mio = {
             let event_sender = event_sender.clone();
+            let nat = nat.clone();
             MioService::new(move |mio_event| {
-                let _ = event_sender.send(mio_event.into());
-                //.expect("cannot send mio event")
+                if nat.as_ref().map_or(true, |nat| nat.passes_mio(&mio_event)) {
+                    let _ = event_sender.send(mio_event.into());
+                    //.expect("cannot send mio event")
+                }
+            })
+        };
+        let quic = {
+            let event_sender = event_sender.clone();
+            QuicService::new(secret_key.clone(), move |quic_event| {
+                if nat
+                    .as_ref()
+                    .map_or(true, |nat| nat.passes_quic(&quic_event))
+                {
+                    let _ = event_sender.send(quic_event.into());
+                }
             })
         };
         let keypair = libp2p::identity::Keypair::ed25519_from_bytes(secret_key.to_bytes())
@@ -49,6 +106,7 @@ impl ClusterService {
             event_sender,
             cmd_sender,
             mio,
+            quic,
             peers: Default::default(),
             time,
             keypair,
@@ -79,6 +137,10 @@ impl P2pServiceWebrtcWithLibp2p for ClusterService {
     fn mio(&mut self) -> &mut p2p::service_impl::mio::MioService {
         &mut self.mio
     }
+
+    fn quic(&mut self) -> &mut QuicService {
+        &mut self.quic
+    }
 }
 
 impl P2pServiceWebrtc for ClusterService {
@@ -148,6 +210,10 @@ impl p2p::P2pNetworkService for ClusterService {
     }
 }
 
+impl P2pAddressBookService for ClusterService {
+    fn address_book_save(&mut self, _entries: Vec<P2pAddressBookEntry>) {}
+}
+
 impl RustNodeEventStore for ClusterService {
     fn store_event(&mut self, event: RustNodeEvent) {
         self.rust_node_events.push_back(event);
@@ -1,7 +1,7 @@
-use std::{collections::BTreeSet, time::Duration};
+use std::{collections::BTreeSet, net::SocketAddr, time::Duration};
 
 use multiaddr::Multiaddr;
-use p2p::PeerId;
+use p2p::{nat::P2pNatAction, PeerId};
 use p2p_testing::{
     cluster::{Cluster, ClusterBuilder, ClusterEvent},
     event::RustNodeEvent,
@@ -112,3 +112,58 @@ async fn wait_for_identify(
     assert!(identified, "all peers should be identified");
     Ok(addrs)
 }
+
+/// Tests that the peers learn about the new listen address of the node
+/// without reconnecting.
+#[tokio::test]
+async fn identify_push_listen_addrs() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let peer_id1 = cluster.rust_node(node1).state().my_id();
+    let peer_id2 = cluster.rust_node(node2).state().my_id();
+
+    let listener_is_ready = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(2))
+        .try_any(listener_is_ready(node1))
+        .await?;
+    assert!(listener_is_ready, "node1 should be ready");
+
+    cluster.connect(node2, node1)?;
+    let mut not_identified = BTreeSet::from_iter([(node1, peer_id2), (node2, peer_id1)]);
+    wait_for_identify(&mut cluster, &mut not_identified, Duration::from_secs(10)).await?;
+
+    // node1 learns its external address, e.g. from the port mapping
+    let external = SocketAddr::from(([203, 0, 113, 7], 10_000));
+    let confirmed = cluster
+        .rust_node_mut(node1)
+        .dispatch_action(P2pNatAction::ExternalAddrConfirmed { addr: external });
+    assert!(confirmed, "external address should be confirmed");
+    let external = Multiaddr::from(external.ip()).with(multiaddr::Protocol::Tcp(external.port()));
+
+    let pushed = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(5))
+        .try_any(async_fn(|event| {
+            matches!(
+                event,
+                ClusterEvent::Rust {
+                    id,
+                    event: RustNodeEvent::Identify { peer_id, info },
+                } if id == node2 && peer_id == peer_id1 && info.listen_addrs.contains(&external)
+            )
+        }))
+        .await?;
+    assert!(
+        pushed,
+        "node2 should receive the new listen address of node1"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use std::{
+    collections::BTreeSet,
+    future::{ready, Ready},
+    time::Duration,
+};
+
+use p2p::{disconnection::P2pDisconnectionReason, peer::P2pPeerAction, P2pTimeouts, PeerId};
+use p2p_testing::{
+    cluster::{Cluster, ClusterBuilder, ClusterEvent},
+    event::{is_error, RustNodeEvent},
+    futures::TryStreamExt,
+    libp2p_node::Libp2pNodeConfig,
+    rust_node::{RustNodeConfig, RustNodeId},
+    stream::ClusterStreamExt,
+    utils::{
+        peer_ids, rust_nodes_from_config, try_run_cluster, try_wait_for_nodes_to_connect,
+        wait_for_all_nodes_to_listen,
+    },
+};
+
+/// The peer is expected to be disconnected after the ping timeout.
+fn is_error_except_disconnection(event: &ClusterEvent) -> bool {
+    !matches!(
+        event,
+        ClusterEvent::Rust {
+            event: RustNodeEvent::PeerDisconnected { .. },
+            ..
+        }
+    ) && is_error(event)
+}
+
+fn rpc_channel_is_ready<I>(node_peer: I) -> impl FnMut(ClusterEvent) -> Ready<bool>
+where
+    I: IntoIterator<Item = (RustNodeId, PeerId)>,
+{
+    let mut node_peer = BTreeSet::from_iter(node_peer);
+    move |event| {
+        ready(matches!(
+            event,
+            ClusterEvent::Rust { id, event: RustNodeEvent::RpcChannelReady { peer_id } }
+            if node_peer.remove(&(id, peer_id)) && node_peer.is_empty()
+        ))
+    }
+}
+
+/// Runs the cluster until the predicate on the node state holds.
+async fn wait_for_state<F>(
+    cluster: &mut Cluster,
+    node: RustNodeId,
+    time: Duration,
+    mut f: F,
+) -> anyhow::Result<bool>
+where
+    F: FnMut(&p2p::P2pState) -> bool,
+{
+    let step = Duration::from_millis(200);
+    let mut elapsed = Duration::ZERO;
+    while elapsed < time {
+        if f(cluster.rust_node(node).state()) {
+            return Ok(true);
+        }
+        try_run_cluster(cluster, step)
+            .await
+            .map_err(|event| anyhow::anyhow!("error event: {event:?}"))?;
+        elapsed += step;
+    }
+    Ok(f(cluster.rust_node(node).state()))
+}
+
+fn ping_timeouts(ping_interval: Option<Duration>) -> P2pTimeouts {
+    P2pTimeouts {
+        ping_interval,
+        ping_timeout: Some(Duration::from_secs(2)),
+        ..Default::default()
+    }
+}
+
+/// Tests that the round-trip time to the peer is measured by the pings.
+#[tokio::test]
+async fn rtt_is_measured() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let config =
+        RustNodeConfig::default().with_timeouts(ping_timeouts(Some(Duration::from_secs(1))));
+    let [node1, node2] = rust_nodes_from_config(&mut cluster, config)?;
+    let [peer_id1, peer_id2] = peer_ids(&cluster, [node1, node2]);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [node2], Duration::from_secs(2)).await;
+    assert!(listening, "node2 should be ready");
+
+    cluster.connect(node1, node2)?;
+    let connected = try_wait_for_nodes_to_connect(
+        &mut cluster,
+        [(node1, peer_id2), (node2, peer_id1)],
+        Duration::from_secs(5),
+    )
+    .await?;
+    assert!(connected, "nodes should be connected");
+
+    let measured = wait_for_state(&mut cluster, node1, Duration::from_secs(10), |state| {
+        state
+            .peers
+            .get(&peer_id2)
+            .and_then(|peer| peer.rtt)
+            .is_some()
+    })
+    .await?;
+    assert!(measured, "rtt to node2 should be measured");
+
+    let state = cluster.rust_node(node1).state();
+    let ping_peer = state
+        .network
+        .scheduler
+        .ping_state
+        .peers
+        .get(&peer_id2)
+        .expect("ping state for node2");
+    assert!(ping_peer.last_pong.is_some(), "pong should be received");
+    assert_eq!(
+        ping_peer.rtt,
+        state.peers.get(&peer_id2).and_then(|peer| peer.rtt),
+        "peer rtt should be the one measured by ping"
+    );
+
+    Ok(())
+}
+
+/// Tests that the peer that doesn't answer the pings is disconnected.
+#[tokio::test]
+async fn ping_timeout_disconnects() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .is_error(is_error_except_disconnection)
+        .start()
+        .await?;
+
+    let rust_node = cluster.add_rust_node(
+        RustNodeConfig::default().with_timeouts(ping_timeouts(Some(Duration::from_secs(1)))),
+    )?;
+    // the libp2p test node doesn't support the ping protocol
+    let libp2p_node = cluster.add_libp2p_node(Libp2pNodeConfig::default())?;
+    let libp2p_peer_id = cluster.peer_id(libp2p_node);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [libp2p_node], Duration::from_secs(2)).await;
+    assert!(listening, "libp2p node should be ready");
+
+    cluster.connect(rust_node, libp2p_node)?;
+    let connected = try_wait_for_nodes_to_connect(
+        &mut cluster,
+        [(rust_node, libp2p_peer_id)],
+        Duration::from_secs(5),
+    )
+    .await?;
+    assert!(connected, "nodes should be connected");
+
+    let disconnected = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(10))
+        .try_any(|event| {
+            ready(matches!(
+                &event,
+                ClusterEvent::Rust {
+                    id,
+                    event: RustNodeEvent::PeerDisconnected { peer_id, reason },
+                } if *id == rust_node
+                    && *peer_id == libp2p_peer_id
+                    && *reason == P2pDisconnectionReason::PingTimeout.to_string()
+            ))
+        })
+        .await?;
+    assert!(disconnected, "peer should be disconnected on ping timeout");
+
+    Ok(())
+}
+
+/// Tests that the peers with the lowest round-trip time are preferred for
+/// the RPC requests, and the ones with unknown round-trip time go last.
+#[tokio::test]
+async fn rpc_peers_follow_rtt_order() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(20)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    // no pings, so that the round-trip times are only set by the test
+    let config = RustNodeConfig::default().with_timeouts(ping_timeouts(None));
+    let [node, peer1, peer2, peer3] = rust_nodes_from_config(&mut cluster, config)?;
+    let [peer_id1, peer_id2, peer_id3] = peer_ids(&cluster, [peer1, peer2, peer3]);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [peer1, peer2, peer3], Duration::from_secs(2))
+            .await;
+    assert!(listening, "peers should be ready");
+
+    for peer in [peer1, peer2, peer3] {
+        cluster.connect(node, peer)?;
+    }
+    let rpc_ready = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(10))
+        .try_any(rpc_channel_is_ready([
+            (node, peer_id1),
+            (node, peer_id2),
+            (node, peer_id3),
+        ]))
+        .await?;
+    assert!(rpc_ready, "rpc channels should be ready");
+
+    for (peer_id, rtt) in [(peer_id1, 300), (peer_id3, 100)] {
+        let dispatched = cluster
+            .rust_node_mut(node)
+            .dispatch_action(P2pPeerAction::RttUpdate {
+                peer_id,
+                rtt: Duration::from_millis(rtt),
+            });
+        assert!(dispatched, "rtt update should be enabled");
+    }
+
+    let peers = cluster
+        .rust_node(node)
+        .state()
+        .ready_rpc_peers_by_rtt()
+        .into_iter()
+        .map(|(peer_id, _)| peer_id)
+        .collect::<Vec<_>>();
+    assert_eq!(peers, [peer_id3, peer_id1, peer_id2]);
+
+    Ok(())
+}
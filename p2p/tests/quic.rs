@@ -0,0 +1,170 @@
+#![cfg(feature = "p2p-quic")]
+
+use std::time::Duration;
+
+use multiaddr::multiaddr;
+use p2p::PeerId;
+use p2p_testing::{
+    cluster::{ClusterBuilder, Listener},
+    futures::TryStreamExt,
+    libp2p_node::Libp2pNodeConfig,
+    predicates::{listener_is_ready, peer_is_connected},
+    rust_node::RustNodeConfig,
+    stream::ClusterStreamExt,
+    test_node::TestNode,
+    utils::{try_wait_for_nodes_to_connect, wait_for_all_nodes_to_listen},
+};
+
+#[tokio::test]
+async fn rust_node_to_rust_node_over_quic() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default().with_quic(true))?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default().with_quic(true))?;
+
+    let peer_id1 = cluster.rust_node(node1).state().my_id();
+    let peer_id2 = cluster.rust_node(node2).state().my_id();
+    let quic_port = cluster
+        .rust_node(node1)
+        .state()
+        .config
+        .quic_port
+        .expect("quic port should be set");
+
+    let listener_is_ready = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(2))
+        .try_any(listener_is_ready(node1))
+        .await?;
+    assert!(listener_is_ready, "node1 should be ready");
+
+    let maddr = multiaddr!(Ip4([127, 0, 0, 1]), Udp(quic_port), QuicV1)
+        .with_p2p(peer_id1.into())
+        .expect("no error");
+    cluster.connect(node2, Listener::Multiaddr(maddr))?;
+
+    let connected = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(5))
+        .try_any(peer_is_connected(node2, peer_id1))
+        .await?;
+    assert!(
+        connected,
+        "node2 should be able to connect to {peer_id1} over QUIC\nnode state: {:#?}",
+        cluster.rust_node(node2).state().peers.get(&peer_id1)
+    );
+
+    let connected = is_quic_peer(cluster.rust_node(node1).state(), &peer_id2);
+    assert!(
+        connected,
+        "node1 should have a QUIC connection with {peer_id2}"
+    );
+
+    Ok(())
+}
+
+/// Tests that a Rust node can connect to a rust-libp2p node over QUIC.
+#[tokio::test]
+async fn rust_node_to_libp2p_node_over_quic() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let rust_node = cluster.add_rust_node(RustNodeConfig::default().with_quic(true))?;
+    let libp2p_node = cluster.add_libp2p_node(Libp2pNodeConfig {
+        quic: true,
+        ..Default::default()
+    })?;
+    let peer_id = cluster.peer_id(libp2p_node);
+    let quic_port = cluster.libp2p_node(libp2p_node).libp2p_port();
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [libp2p_node], Duration::from_secs(2)).await;
+    assert!(listening, "libp2p node should be listening");
+
+    let maddr = multiaddr!(Ip4([127, 0, 0, 1]), Udp(quic_port), QuicV1)
+        .with_p2p(peer_id.into())
+        .expect("no error");
+    cluster.connect(rust_node, Listener::Multiaddr(maddr))?;
+
+    let connected =
+        try_wait_for_nodes_to_connect(&mut cluster, [(rust_node, peer_id)], Duration::from_secs(5))
+            .await?;
+    assert!(
+        connected,
+        "rust node should be able to connect to {peer_id} over QUIC\nnode state: {:#?}",
+        cluster.rust_node(rust_node).state().peers.get(&peer_id)
+    );
+    assert!(
+        is_quic_peer(cluster.rust_node(rust_node).state(), &peer_id),
+        "rust node should have a QUIC connection with {peer_id}"
+    );
+
+    Ok(())
+}
+
+/// Tests that a rust-libp2p node can connect to a Rust node over QUIC.
+#[tokio::test]
+async fn libp2p_node_to_rust_node_over_quic() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let rust_node = cluster.add_rust_node(RustNodeConfig::default().with_quic(true))?;
+    let libp2p_node = cluster.add_libp2p_node(Libp2pNodeConfig {
+        quic: true,
+        ..Default::default()
+    })?;
+    let rust_peer_id = cluster.peer_id(rust_node);
+    let peer_id = cluster.peer_id(libp2p_node);
+    let quic_port = cluster
+        .rust_node(rust_node)
+        .state()
+        .config
+        .quic_port
+        .expect("quic port should be set");
+
+    let listening = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(2))
+        .try_any(listener_is_ready(rust_node))
+        .await?;
+    assert!(listening, "rust node should be listening");
+
+    let maddr = multiaddr!(Ip4([127, 0, 0, 1]), Udp(quic_port), QuicV1)
+        .with_p2p(rust_peer_id.into())
+        .expect("no error");
+    cluster.connect(libp2p_node, Listener::Multiaddr(maddr))?;
+
+    let connected =
+        try_wait_for_nodes_to_connect(&mut cluster, [(rust_node, peer_id)], Duration::from_secs(5))
+            .await?;
+    assert!(
+        connected,
+        "{peer_id} should be able to connect to the rust node over QUIC\nnode state: {:#?}",
+        cluster.rust_node(rust_node).state().peers.get(&peer_id)
+    );
+    assert!(
+        is_quic_peer(cluster.rust_node(rust_node).state(), &peer_id),
+        "rust node should have a QUIC connection with {peer_id}"
+    );
+
+    Ok(())
+}
+
+fn is_quic_peer(state: &p2p::P2pState, peer_id: &PeerId) -> bool {
+    state
+        .network
+        .scheduler
+        .connections
+        .values()
+        .any(|conn| conn.quic && conn.peer_id() == Some(peer_id))
+}
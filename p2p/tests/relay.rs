@@ -0,0 +1,269 @@
+use std::time::Duration;
+
+use p2p::{is_circuit_addr, P2pNetworkRelayAction, P2pNetworkRelayReservationState, PeerId};
+use p2p_testing::{
+    cluster::{Cluster, ClusterBuilder, ClusterEvent, Listener},
+    event::{is_error, RustNodeEvent},
+    futures::TryStreamExt,
+    predicates::{listener_is_ready, peer_is_connected},
+    rust_node::{RustNodeConfig, RustNodeId},
+    stream::ClusterStreamExt,
+    utils::{try_run_cluster, wait_for_all_nodes_to_listen},
+};
+
+/// The relayed connection is closed when it is upgraded to a direct one.
+fn is_error_except_disconnection(event: &ClusterEvent) -> bool {
+    !matches!(
+        event,
+        ClusterEvent::Rust {
+            event: RustNodeEvent::PeerDisconnected { .. },
+            ..
+        }
+    ) && is_error(event)
+}
+
+/// Runs the cluster until the predicate on the node state holds.
+async fn wait_for_state<F>(
+    cluster: &mut Cluster,
+    node: RustNodeId,
+    time: Duration,
+    mut f: F,
+) -> anyhow::Result<bool>
+where
+    F: FnMut(&p2p::P2pState) -> bool,
+{
+    let step = Duration::from_millis(200);
+    let mut elapsed = Duration::ZERO;
+    while elapsed < time {
+        if f(cluster.rust_node(node).state()) {
+            return Ok(true);
+        }
+        try_run_cluster(cluster, step)
+            .await
+            .map_err(|event| anyhow::anyhow!("error event: {event:?}"))?;
+        elapsed += step;
+    }
+    Ok(f(cluster.rust_node(node).state()))
+}
+
+#[tokio::test]
+async fn relayed_connection_upgrade() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .is_error(is_error_except_disconnection)
+        .start()
+        .await?;
+
+    let relay = cluster.add_rust_node(RustNodeConfig::default().with_relay_hop(true))?;
+    let relay_peer_id = cluster.peer_id(relay);
+
+    let listener_is_ready = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(2))
+        .try_any(listener_is_ready(relay))
+        .await?;
+    assert!(listener_is_ready, "relay should be ready");
+
+    // node behind NAT can only be reached through the relay
+    let node1 = cluster.add_rust_node(
+        RustNodeConfig::default()
+            .with_behind_nat(true)
+            .with_relays([Listener::Rust(relay)]),
+    )?;
+    let peer_id1 = cluster.peer_id(node1);
+    let node2 = cluster
+        .add_rust_node(RustNodeConfig::default().with_initial_peers([Listener::Rust(relay)]))?;
+    let peer_id2 = cluster.peer_id(node2);
+
+    let reserved = wait_for_state(&mut cluster, node1, Duration::from_secs(10), |state| {
+        matches!(
+            state
+                .network
+                .scheduler
+                .relay_state
+                .reservations
+                .get(&relay_peer_id),
+            Some(P2pNetworkRelayReservationState::Reserved { .. })
+        )
+    })
+    .await?;
+    assert!(
+        reserved,
+        "node behind NAT should have a reservation on the relay: {:#?}",
+        cluster
+            .rust_node(node1)
+            .state()
+            .network
+            .scheduler
+            .relay_state
+            .reservations
+    );
+
+    let connected = wait_for_state(&mut cluster, node2, Duration::from_secs(5), |state| {
+        state.get_ready_peer(&relay_peer_id).is_some()
+    })
+    .await?;
+    assert!(connected, "node should be connected to the relay");
+
+    assert!(
+        cluster
+            .rust_node_mut(node2)
+            .dispatch_action(P2pNetworkRelayAction::Connect {
+                relay_peer_id,
+                peer_id: peer_id1,
+            }),
+        "relayed connection should be initiated"
+    );
+
+    let connected = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(5))
+        .try_any(peer_is_connected(node1, peer_id2))
+        .await?;
+    assert!(
+        connected,
+        "node behind NAT should accept the relayed connection: {:#?}",
+        cluster.rust_node(node1).state().peers.get(&peer_id2)
+    );
+
+    let upgraded = wait_for_state(&mut cluster, node1, Duration::from_secs(10), |state| {
+        is_directly_connected(state, &peer_id2)
+    })
+    .await?;
+    assert!(
+        upgraded,
+        "relayed connection should be upgraded to a direct one: {:#?}",
+        cluster
+            .rust_node(node1)
+            .state()
+            .network
+            .scheduler
+            .connections
+    );
+    let upgraded = wait_for_state(&mut cluster, node2, Duration::from_secs(2), |state| {
+        is_directly_connected(state, &peer_id1)
+    })
+    .await?;
+    assert!(upgraded, "both nodes should see the direct connection");
+
+    // the node behind NAT dials the peer, so the NAT lets the connection through
+    assert_single_direct_connection(&cluster, node1, peer_id2, false);
+    assert_single_direct_connection(&cluster, node2, peer_id1, true);
+
+    let closed = wait_for_state(&mut cluster, relay, Duration::from_secs(5), |state| {
+        state.network.scheduler.relay_state.relayed_circuits() == 0
+    })
+    .await?;
+    assert!(
+        closed,
+        "relay should close the circuit after the upgrade: {:#?}",
+        cluster
+            .rust_node(relay)
+            .state()
+            .network
+            .scheduler
+            .relay_state
+            .streams
+    );
+    for (node, peer_id) in [(node1, peer_id2), (node2, peer_id1)] {
+        let closed = wait_for_state(&mut cluster, node, Duration::from_secs(2), |state| {
+            state
+                .network
+                .scheduler
+                .relay_state
+                .circuit_to_peer(&peer_id)
+                .is_none()
+        })
+        .await?;
+        assert!(
+            closed,
+            "relayed connection to {peer_id} should be closed: {:#?}",
+            cluster
+                .rust_node(node)
+                .state()
+                .network
+                .scheduler
+                .relay_state
+                .circuits
+        );
+    }
+
+    Ok(())
+}
+
+/// Tests that the connections initiated by the remote peers don't pass
+/// through the simulated NAT.
+#[tokio::test]
+async fn node_behind_nat_is_unreachable() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .is_error(|_| false)
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default().with_behind_nat(true))?;
+    let peer_id1 = cluster.peer_id(node1);
+    let node2 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let peer_id2 = cluster.peer_id(node2);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [node1, node2], Duration::from_secs(2)).await;
+    assert!(listening, "nodes should be ready");
+
+    cluster.connect(node2, node1)?;
+    let connected = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(3))
+        .try_any(peer_is_connected(node2, peer_id1))
+        .await?;
+    assert!(
+        !connected,
+        "node behind NAT shouldn't accept the connection"
+    );
+
+    cluster.connect(node1, node2)?;
+    let connected = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(5))
+        .try_any(peer_is_connected(node1, peer_id2))
+        .await?;
+    assert!(connected, "node behind NAT should be able to dial out");
+
+    Ok(())
+}
+
+/// Asserts that the node has a single connection with the peer, and it is a direct one.
+fn assert_single_direct_connection(
+    cluster: &Cluster,
+    node: RustNodeId,
+    peer_id: PeerId,
+    incoming: bool,
+) {
+    let connections = cluster
+        .rust_node(node)
+        .state()
+        .network
+        .scheduler
+        .connections
+        .iter()
+        .filter(|(_, conn)| conn.peer_id() == Some(&peer_id) && conn.closed.is_none())
+        .collect::<Vec<_>>();
+    assert!(
+        matches!(
+            connections[..],
+            [(addr, conn)] if !is_circuit_addr(addr) && conn.incoming == incoming
+        ),
+        "should be a single direct connection with {peer_id}: {connections:#?}"
+    );
+}
+
+fn is_directly_connected(state: &p2p::P2pState, peer_id: &PeerId) -> bool {
+    state.get_ready_peer(peer_id).is_some()
+        && state
+            .network
+            .scheduler
+            .find_peer(peer_id)
+            .map_or(false, |(addr, _)| !is_circuit_addr(addr))
+}
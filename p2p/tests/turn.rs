@@ -0,0 +1,95 @@
+#![cfg(feature = "p2p-webrtc")]
+
+use std::{net::IpAddr, sync::Arc, time::Duration};
+
+use p2p::webrtc::IceServerCredentials;
+use tokio::net::UdpSocket;
+use webrtc::{
+    turn::{
+        auth::LongTermAuthHandler,
+        client::{Client, ClientConfig},
+        relay::relay_static::RelayAddressGeneratorStatic,
+        server::{
+            config::{ConnConfig, ServerConfig},
+            Server,
+        },
+    },
+    util::vnet::net::Net,
+};
+
+const SHARED_SECRET: &str = "openmina-turn-secret";
+
+/// TURN server authenticating the clients the same way as coturn with
+/// `use-auth-secret` and `static-auth-secret` set.
+async fn start_turn_server() -> anyhow::Result<(Server, u16)> {
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let port = conn.local_addr()?.port();
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![ConnConfig {
+            conn,
+            relay_addr_generator: Box::new(RelayAddressGeneratorStatic {
+                relay_address: IpAddr::from([127, 0, 0, 1]),
+                address: "127.0.0.1".to_owned(),
+                net: Arc::new(Net::new(None)),
+            }),
+        }],
+        realm: "openmina".to_owned(),
+        auth_handler: Arc::new(LongTermAuthHandler::new(SHARED_SECRET.to_owned())),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+    })
+    .await?;
+    Ok((server, port))
+}
+
+async fn allocate(port: u16, (username, password): (String, String)) -> anyhow::Result<()> {
+    let client = Client::new(ClientConfig {
+        stun_serv_addr: format!("127.0.0.1:{port}"),
+        turn_serv_addr: format!("127.0.0.1:{port}"),
+        username,
+        password,
+        realm: String::new(),
+        software: String::new(),
+        rto_in_ms: 0,
+        conn: Arc::new(UdpSocket::bind("127.0.0.1:0").await?),
+        vnet: None,
+    })
+    .await?;
+    client.listen().await?;
+    let result = client.allocate().await;
+    client.close().await?;
+    result?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn turn_rest_credentials_are_accepted() -> anyhow::Result<()> {
+    let (server, port) = start_turn_server().await?;
+
+    let credentials = IceServerCredentials::SharedSecret {
+        secret: SHARED_SECRET.to_owned(),
+        ttl: Duration::from_secs(60),
+        user: None,
+    };
+    let now = openmina_core::log::system_time();
+    allocate(port, credentials.resolve(now)).await?;
+
+    let wrong_secret = IceServerCredentials::SharedSecret {
+        secret: "wrong-secret".to_owned(),
+        ttl: Duration::from_secs(60),
+        user: None,
+    };
+    assert!(
+        allocate(port, wrong_secret.resolve(now)).await.is_err(),
+        "credentials derived from another secret should be rejected"
+    );
+
+    let hour_ago = redux::Timestamp::new(u64::from(now) - 3600 * 1_000_000_000);
+    assert!(
+        allocate(port, credentials.resolve(hour_ago)).await.is_err(),
+        "expired credentials should be rejected"
+    );
+
+    server.close().await?;
+    Ok(())
+}
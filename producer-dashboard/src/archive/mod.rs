@@ -9,15 +9,9 @@ pub struct ArchiveConnector {
 }
 
 impl ArchiveConnector {
-    pub async fn connect() -> Self {
+    pub async fn connect(db_url: &str) -> Self {
         // TODO(adonagy): unwrap
-        let db_url = if let Ok(url) = dotenvy::var("DATABASE_URL") {
-            url
-        } else {
-            std::env::var("DATABASE_URL").expect("No db url found, check env var DATABASE_URL")
-        };
-
-        let pool = PgPool::connect(&db_url).await.unwrap();
+        let pool = PgPool::connect(db_url).await.unwrap();
 
         Self { pool }
     }
@@ -164,7 +158,8 @@ mod test {
 
     #[tokio::test]
     async fn test() {
-        let db = ArchiveConnector::connect().await;
+        let db_url = dotenvy::var("DATABASE_URL").expect("check env var DATABASE_URL");
+        let db = ArchiveConnector::connect(&db_url).await;
 
         let blocks = db
             ._get_producer_blocks("B62qkPpK6z4ktWjxcmFzM4cFWjWLzrjNh6USjUMiYGcF3YAVbdo2p4H")
@@ -11,11 +11,16 @@ pub struct ArchiveWatchdog {
 }
 
 impl ArchiveWatchdog {
-    pub fn spawn_new(db: Database, producer_pk: String, node_status: NodeStatus) -> JoinHandle<()> {
+    pub fn spawn_new(
+        archive_connector: ArchiveConnector,
+        db: Database,
+        producer_pk: String,
+        node_status: NodeStatus,
+    ) -> JoinHandle<()> {
         tokio::spawn(async move {
             Self {
                 producer_pk,
-                archive_connector: ArchiveConnector::connect().await,
+                archive_connector,
                 db,
                 node_status,
             }
@@ -36,19 +41,15 @@ impl ArchiveWatchdog {
 
             let current_slot = node_status.current_slot();
 
-            if self
-                .db
-                .has_slot(current_slot.global_slot().to_u32())
-                .unwrap()
-            {
-                let old = current_slot.global_slot().to_u32() - 1;
-                self.db
-                    .set_current_slot(old, current_slot.global_slot().to_u32())
-                    .unwrap();
-            }
-
             if let Some(best_tip) = node_status.best_tip() {
                 let (start, end) = best_tip.epoch_bounds().0;
+                // slots the node still has history for are resolved by
+                // the node watchdog
+                let end = match node_status.retention_start() {
+                    Some(retention_start) if retention_start <= start => continue,
+                    Some(retention_start) => end.min(retention_start - 1),
+                    None => end,
+                };
 
                 let cannonical_chain = match self
                     .archive_connector
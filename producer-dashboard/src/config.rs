@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use clap::Parser;
+use openmina_node_account::AccountPublicKey;
 
 /// Awesome producer proxy
 ///
@@ -11,17 +12,22 @@ pub struct Config {
     /// RPC port for the proxy
     #[arg(short, long, default_value_t = 3000)]
     pub rpc_port: u16,
-    // TODO(adonagy)
-    /// Path to the producer's private key file
-    ///
-    /// MINA_PRIVKEY_PASS environmental variable must be set!
-    #[arg(short, long)]
-    pub private_key_path: PathBuf,
+
+    /// Public key of the block producer
+    #[arg(long, env = "PRODUCER_PK")]
+    pub producer_pk: AccountPublicKey,
 
     /// Path to the database
     #[arg(short, long, default_value = "/tmp/producer-dashboard")]
     pub database_path: PathBuf,
 
+    /// URL of the openmina node's RPC (http server)
     #[arg(short, long)]
     pub node_url: String,
+
+    /// Archive database, only used for history older than the node's
+    /// retention. Without it, only the node's history is available, so
+    /// slots from before the node was started show no won blocks.
+    #[arg(long, env = "DATABASE_URL")]
+    pub archive_url: Option<String>,
 }
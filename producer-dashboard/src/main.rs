@@ -1,14 +1,12 @@
 use node::NodeData;
-use openmina_node_account::AccountSecretKey;
 
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::RwLock;
 
 use clap::Parser;
 
 use crate::{
-    archive::watchdog::ArchiveWatchdog,
-    evaluator::{EpochInit, Evaluator},
+    archive::{watchdog::ArchiveWatchdog, ArchiveConnector},
     node::{watchdog::spawn_watchdog, Node},
     storage::db_sled::Database,
 };
@@ -22,8 +20,10 @@ mod storage;
 
 #[derive(Debug, thiserror::Error)]
 pub enum StakingToolError {
-    #[error("Empty graphql response")]
-    EmptyGraphqlResponse,
+    #[error("Empty response")]
+    EmptyResponse,
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
@@ -36,41 +36,41 @@ pub type NodeStatus = Arc<RwLock<NodeData>>;
 
 #[tokio::main]
 async fn main() {
+    dotenvy::dotenv().ok();
     let config = config::Config::parse();
 
     let db = Database::open(config.database_path).expect("Failed to open Database");
     println!("[main] DB opened");
 
-    let key = AccountSecretKey::from_encrypted_file(config.private_key_path)
-        .expect("failed to decrypt secret key file");
-    println!("[main] Producer key loaded");
-
-    let (sender, receiver) = mpsc::unbounded_channel::<EpochInit>();
+    let producer_pk = config.producer_pk.to_string();
 
-    let evaluator_handle = Evaluator::spawn_new(key.clone(), db.clone(), receiver);
-    println!("[main] Evaluator created");
     let node_status = NodeStatus::default();
     let node = Node::new(config.node_url);
-    let node_watchdog = spawn_watchdog(
-        node,
-        node_status.clone(),
-        db.clone(),
-        sender,
-        key.public_key().to_string(),
-    );
+    let node_watchdog = spawn_watchdog(node, node_status.clone(), db.clone());
     println!("[main] Node watchdog created");
-    let archive_watchdog = ArchiveWatchdog::spawn_new(
-        db.clone(),
-        key.public_key().to_string(),
-        node_status.clone(),
-    );
-    println!("[main] Archive watchdog created");
+
+    let archive_watchdog = match config.archive_url {
+        Some(archive_url) => {
+            let archive_connector = ArchiveConnector::connect(&archive_url).await;
+            println!("[main] Archive watchdog created");
+            Some(ArchiveWatchdog::spawn_new(
+                archive_connector,
+                db.clone(),
+                producer_pk.clone(),
+                node_status.clone(),
+            ))
+        }
+        None => {
+            println!("[main] No archive configured, only the node's history is available");
+            None
+        }
+    };
 
     let rpc_handle = rpc::spawn_rpc_server(
-        3000,
+        config.rpc_port,
         db.clone(),
         node_status.clone(),
-        key.public_key().to_string(),
+        producer_pk,
     );
     println!("[main] RPC server created");
 
@@ -91,6 +91,5 @@ async fn main() {
     drop(archive_watchdog);
     drop(rpc_handle);
     drop(node_watchdog);
-    drop(evaluator_handle);
     println!("Shutdown successfull");
 }
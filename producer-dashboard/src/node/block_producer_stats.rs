@@ -0,0 +1,110 @@
+use serde::Deserialize;
+
+use crate::evaluator::epoch::{SlotBlockUpdate, SlotData, SlotStatus};
+
+use super::SLOT_DURATION_SECS;
+
+/// Number of blocks after which a block is final.
+const FINALITY_CONFIRMATIONS: u32 = 290;
+
+/// Subset of the node's `/stats/block_producer` response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockProducerStats {
+    pub current_time: u64,
+    pub current_global_slot: Option<u32>,
+    pub attempts: Vec<BlockProductionAttempt>,
+    pub future_won_slots: Vec<WonSlot>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockProductionAttempt {
+    pub won_slot: WonSlot,
+    pub block: Option<ProducedBlock>,
+    pub status: String,
+    pub last_observed_confirmations: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WonSlot {
+    /// Time of the slot, in nanoseconds.
+    pub slot_time: u64,
+    pub global_slot: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProducedBlock {
+    pub hash: String,
+    pub height: u32,
+}
+
+impl BlockProducerStats {
+    /// Genesis timestamp in seconds, derived from the won slots' times, or
+    /// from the current time if there are none.
+    pub fn genesis_timestamp(&self) -> Option<i64> {
+        let (time, slot) = self
+            .attempts
+            .iter()
+            .map(|attempt| &attempt.won_slot)
+            .chain(&self.future_won_slots)
+            .map(|won_slot| (won_slot.slot_time, won_slot.global_slot))
+            .next()
+            .or_else(|| Some((self.current_time, self.current_global_slot?)))?;
+        Some(nanos_to_secs(time) - slot as i64 * SLOT_DURATION_SECS)
+    }
+
+    /// First global slot the node has block production history for.
+    pub fn retention_start(&self) -> Option<u32> {
+        self.attempts
+            .iter()
+            .map(|attempt| attempt.won_slot.global_slot)
+            .min()
+            .or(self.current_global_slot)
+    }
+
+    /// Won slots known by the node, with their block production results.
+    pub fn slots(&self) -> impl Iterator<Item = SlotData> + '_ {
+        let attempts = self.attempts.iter().map(|attempt| {
+            let status = attempt.slot_status();
+            let block = attempt.block.as_ref().map(|block| {
+                SlotBlockUpdate::new(block.height, block.hash.clone(), status.clone())
+            });
+            let mut slot = SlotData::new(
+                attempt.won_slot.global_slot,
+                nanos_to_secs(attempt.won_slot.slot_time),
+                block,
+            );
+            slot.update_block_status(status);
+            slot
+        });
+        let future = self.future_won_slots.iter().map(|won_slot| {
+            SlotData::new(
+                won_slot.global_slot,
+                nanos_to_secs(won_slot.slot_time),
+                None,
+            )
+        });
+        attempts.chain(future)
+    }
+}
+
+impl BlockProductionAttempt {
+    fn slot_status(&self) -> SlotStatus {
+        match self.status.as_str() {
+            "Canonical"
+                if self.last_observed_confirmations.unwrap_or_default()
+                    >= FINALITY_CONFIRMATIONS =>
+            {
+                SlotStatus::Canonical
+            }
+            "Canonical" => SlotStatus::CanonicalPending,
+            "Orphaned" => SlotStatus::Orphaned,
+            "Discarded" => SlotStatus::Missed,
+            "Committed" => SlotStatus::Pending,
+            _ => SlotStatus::ToBeProduced,
+        }
+    }
+}
+
+fn nanos_to_secs(nanos: u64) -> i64 {
+    (nanos / 1_000_000_000) as i64
+}
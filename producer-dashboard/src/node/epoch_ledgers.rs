@@ -11,6 +11,11 @@ use std::str::FromStr;
 
 use crate::StakingToolError;
 
+use super::EpochLedgerAccount;
+
+/// Token id of MINA.
+const DEFAULT_TOKEN_ID: &str = "wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf";
+
 // TODO(adonagy): remove dead_code
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
@@ -165,6 +170,18 @@ pub struct LedgerEntry {
     pub delegate: Option<String>,
 }
 
+impl From<EpochLedgerAccount> for LedgerEntry {
+    fn from(account: EpochLedgerAccount) -> Self {
+        Self {
+            pk: account.public_key,
+            balance: MinaLedgerDumpBalanceStringNumber(account.balance.into()),
+            token: DEFAULT_TOKEN_ID.to_owned(),
+            token_symbol: String::new(),
+            delegate: account.delegate,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Ledger {
     inner: Vec<LedgerEntry>,
@@ -208,7 +225,7 @@ impl Ledger {
     pub fn total_currency(&self) -> BigInt {
         self.inner
             .iter()
-            .filter(|entry| entry.token == "wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf")
+            .filter(|entry| entry.token == DEFAULT_TOKEN_ID)
             .map(|entry| &entry.balance.0)
             .sum()
     }
@@ -1,63 +1,60 @@
 use ::reqwest::Client;
-use graphql_client::{reqwest::post_graphql, GraphQLQuery};
-use num_bigint::BigInt;
-use serde::{Deserialize, Deserializer, Serialize};
-use std::{collections::BTreeSet, process::Command, str::FromStr};
-use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeSet;
+use time::OffsetDateTime;
 
 use crate::{
     evaluator::epoch::{RawGlobalSlot, RawSlot},
     StakingToolError,
 };
 
+pub mod block_producer_stats;
 pub mod epoch_ledgers;
 pub mod watchdog;
 
-use self::{daemon_status::SyncStatus, epoch_ledgers::Ledger};
+use self::{
+    block_producer_stats::BlockProducerStats,
+    epoch_ledgers::{Ledger, LedgerEntry},
+};
 
-type PublicKey = String;
-type StateHash = String;
-type FeeTransferType = String;
-type UserCommandKind = String;
-type Amount = StringNumber;
-type Fee = StringNumber;
-type Epoch = String;
-type Length = String;
-type EpochSeed = String;
-type Slot = String;
-type Globalslot = String;
+pub const SLOT_DURATION_SECS: i64 = 3 * 60;
 
 pub fn calc_slot_timestamp(genesis_timestamp: i64, global_slot: u32) -> i64 {
-    genesis_timestamp + ((global_slot as i64) * 60 * 3)
+    genesis_timestamp + (global_slot as i64) * SLOT_DURATION_SECS
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Client of the openmina node's RPC.
+#[derive(Debug, Clone)]
 pub struct Node {
     url: String,
+    client: Client,
 }
 
 impl Node {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url: url.trim_end_matches('/').to_owned(),
+            client: Client::new(),
+        }
     }
 
-    pub async fn wait_for_graphql(&self) -> Result<(), StakingToolError> {
-        let client = Client::builder()
-            .user_agent("graphql-rust/0.10.0")
-            .build()
-            .unwrap();
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, StakingToolError> {
+        let response = self
+            .client
+            .get(format!("{}{path}", self.url))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
 
+    pub async fn wait_for_node(&self) -> Result<(), StakingToolError> {
         let timeout_duration = tokio::time::Duration::from_secs(120); // 2 minutes
         let start_time = tokio::time::Instant::now();
 
         while tokio::time::Instant::now() - start_time < timeout_duration {
-            match client.get(&self.url).send().await {
-                Ok(response) => {
-                    println!("[wait_for_graphql] Response status: {}", response.status());
-                    if response.status().is_client_error() {
-                        return Ok(()); // URL is reachable and returns a successful status
-                    }
-                }
+            match self.status().await {
+                Ok(_) => return Ok(()),
                 Err(_) => {
                     println!("Waiting for node...");
                 }
@@ -69,147 +66,108 @@ impl Node {
         Err(StakingToolError::NodeOffline)
     }
 
-    pub async fn sync_status(&self) -> SyncStatus {
-        let client = Client::builder()
-            .user_agent("graphql-rust/0.10.0")
-            .build()
-            .unwrap();
-
-        let variables = daemon_status::Variables {};
-
-        let response_body = post_graphql::<DaemonStatus, _>(&client, &self.url, variables)
-            .await
-            .unwrap();
-
-        let response_data: daemon_status::ResponseData = response_body
-            .data
-            .ok_or(StakingToolError::EmptyGraphqlResponse)
-            .unwrap();
-
-        response_data.daemon_status.sync_status
+    pub async fn status(&self) -> Result<NodeStatusResponse, StakingToolError> {
+        self.get::<Option<NodeStatusResponse>>("/status")
+            .await?
+            .ok_or(StakingToolError::EmptyResponse)
+    }
+
+    /// Block production attempts and won slots, as evaluated by the node.
+    pub async fn block_producer_stats(&self) -> Result<BlockProducerStats, StakingToolError> {
+        self.get::<Option<BlockProducerStats>>("/stats/block_producer")
+            .await?
+            .ok_or(StakingToolError::EmptyResponse)
+    }
+
+    /// Fetches the current staking ledger page by page. Returns the epoch
+    /// it is the staking ledger for along with the ledger.
+    pub async fn get_staking_ledger(&self) -> Result<(u32, Ledger), StakingToolError> {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page: EpochLedgerAccounts = self
+                .get(&format!("/epoch-ledger/staking/accounts?offset={offset}"))
+                .await?;
+            entries.extend(page.accounts.into_iter().map(LedgerEntry::from));
+            match page.next_offset {
+                Some(next_offset) => offset = next_offset,
+                None => return Ok((page.epoch, Ledger::new(entries))),
+            }
+        }
     }
+}
 
-    pub async fn get_genesis_timestmap(&self) -> Result<i64, StakingToolError> {
-        let client = Client::builder()
-            .user_agent("graphql-rust/0.10.0")
-            .build()
-            .unwrap();
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeStatusResponse {
+    pub transition_frontier: NodeStatusTransitionFrontier,
+}
 
-        let variables = genesis_timestamp::Variables {};
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeStatusTransitionFrontier {
+    pub best_tip: Option<BestTip>,
+    pub sync: NodeStatusSync,
+}
 
-        let response_body = post_graphql::<GenesisTimestamp, _>(&client, &self.url, variables)
-            .await
-            .unwrap();
-        let response_data: genesis_timestamp::ResponseData = response_body
-            .data
-            .ok_or(StakingToolError::EmptyGraphqlResponse)?;
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeStatusSync {
+    pub status: String,
+}
 
-        let timestamp_formatted = response_data.genesis_constants.genesis_timestamp;
-        let datetime = OffsetDateTime::parse(&timestamp_formatted, &Rfc3339).unwrap();
-        Ok(datetime.unix_timestamp())
-    }
+#[derive(Debug, Clone, Deserialize)]
+struct EpochLedgerAccounts {
+    epoch: u32,
+    next_offset: Option<usize>,
+    accounts: Vec<EpochLedgerAccount>,
+}
 
-    #[allow(dead_code)]
-    pub async fn get_best_chain(&self) -> Result<Vec<(u32, String)>, StakingToolError> {
-        let client = Client::builder()
-            .user_agent("graphql-rust/0.10.0")
-            .build()
-            .unwrap();
-
-        let variables = best_chain::Variables { max_length: 290 };
-        let response_body = post_graphql::<BestChain, _>(&client, &self.url, variables)
-            .await
-            .unwrap();
-
-        let response_data: best_chain::ResponseData = response_body
-            .data
-            .ok_or(StakingToolError::EmptyGraphqlResponse)?;
-
-        response_data
-            .best_chain
-            .ok_or(StakingToolError::EmptyGraphqlResponse)
-            .map(|v| {
-                v.iter()
-                    .map(|bc| {
-                        (
-                            bc.protocol_state
-                                .consensus_state
-                                .slot_since_genesis
-                                .parse()
-                                .unwrap(),
-                            bc.state_hash.clone(),
-                        )
-                    })
-                    .collect()
-            })
-    }
+#[derive(Debug, Clone, Deserialize)]
+pub struct EpochLedgerAccount {
+    pub public_key: String,
+    pub delegate: Option<String>,
+    pub balance: u64,
+}
 
-    pub async fn get_best_tip(&self) -> Result<BestTip, StakingToolError> {
-        let client = Client::builder()
-            .user_agent("graphql-rust/0.10.0")
-            .build()
-            .unwrap();
-
-        let variables = best_chain::Variables { max_length: 1 };
-        let response_body = post_graphql::<BestChain, _>(&client, &self.url, variables)
-            .await
-            .unwrap();
-
-        let response_data: best_chain::ResponseData = response_body
-            .data
-            .ok_or(StakingToolError::EmptyGraphqlResponse)?;
-
-        response_data
-            .best_chain
-            .map(|res| res.first().cloned().unwrap().into())
-            .ok_or(StakingToolError::EmptyGraphqlResponse)
-    }
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SyncStatus {
+    Offline,
+    Bootstrap,
+    Catchup,
+    Synced,
+}
 
-    fn dump_current_staking_ledger() -> impl AsRef<[u8]> {
-        let output = Command::new("mina")
-            .args([
-                "ledger",
-                "export",
-                "--daemon-port",
-                "mina:8301",
-                "staking-epoch-ledger",
-            ])
-            .output()
-            .expect("Failed to execute command");
-
-        if !output.status.success() {
-            let error_message = String::from_utf8_lossy(&output.stderr);
-            panic!("Command execution failed with error: {}", error_message);
+impl SyncStatus {
+    /// Maps the node's transition frontier sync state name.
+    pub fn from_node_sync_status(status: &str) -> Self {
+        match status {
+            "Synced" => Self::Synced,
+            "Idle" => Self::Offline,
+            "BlocksPending" | "BlocksSuccess" | "CommitPending" | "CommitSuccess" => Self::Catchup,
+            _ => Self::Bootstrap,
         }
-
-        output.stdout
-    }
-
-    pub fn get_staking_ledger(_epoch_number: u32) -> Ledger {
-        let raw = Self::dump_current_staking_ledger();
-        let inner = serde_json::from_slice(raw.as_ref()).unwrap();
-        Ledger::new(inner)
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeData {
     best_tip: Option<BestTip>,
-    best_chain: Vec<(u32, String)>,
     sync_status: SyncStatus,
     dumped_ledgers: BTreeSet<u32>,
     // TODO: make sure it's available, make it an option
     genesis_timestamp: i64,
+    /// First global slot for which the node still has block production
+    /// history. Older slots can only be resolved from the archive.
+    retention_start: Option<u32>,
 }
 
 impl Default for NodeData {
     fn default() -> Self {
         Self {
             best_tip: Default::default(),
-            best_chain: Default::default(),
-            sync_status: SyncStatus::OFFLINE,
+            sync_status: SyncStatus::Offline,
             dumped_ledgers: Default::default(),
             genesis_timestamp: 0,
+            retention_start: None,
         }
     }
 }
@@ -218,10 +176,6 @@ impl NodeData {
     // TODO(adonagy): Hydrate from db
     // pub fn new()
 
-    pub fn transition_frontier_root(&self) -> Option<u32> {
-        self.best_chain.first().map(|v| v.0)
-    }
-
     pub fn best_tip(&self) -> Option<BestTip> {
         // TODO
         self.best_tip.clone()
@@ -232,12 +186,12 @@ impl NodeData {
 
         let elapsed = now - self.genesis_timestamp;
 
-        let slot = (elapsed / (3 * 60)) as u32;
+        let slot = (elapsed / SLOT_DURATION_SECS) as u32;
         CurrentSlot::new(slot)
     }
 
-    pub fn best_chain(&self) -> &[(u32, String)] {
-        self.best_chain.as_slice()
+    pub fn retention_start(&self) -> Option<u32> {
+        self.retention_start
     }
 }
 
@@ -262,81 +216,18 @@ impl CurrentSlot {
     }
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
-struct StringNumber(BigInt);
-
-#[allow(dead_code)]
-impl StringNumber {
-    pub fn to_bigint(&self) -> BigInt {
-        self.0.clone()
-    }
-}
-
-impl From<BigInt> for StringNumber {
-    fn from(value: BigInt) -> Self {
-        Self(value)
-    }
-}
-
-impl<'de> Deserialize<'de> for StringNumber {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        BigInt::from_str(&s)
-            .map(StringNumber)
-            .map_err(serde::de::Error::custom)
-    }
-}
-
-impl Serialize for StringNumber {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let as_string = self.0.to_string();
-        serializer.serialize_str(&as_string)
-    }
-}
-
-#[derive(GraphQLQuery)]
-#[graphql(
-    schema_path = "src/graphql/schema.json",
-    query_path = "src/graphql/daemon_status.graphql",
-    response_derives = "Debug, Clone"
-)]
-struct DaemonStatus;
-
-#[derive(GraphQLQuery)]
-#[graphql(
-    schema_path = "src/graphql/schema.json",
-    query_path = "src/graphql/genesis_timestamp.graphql",
-    response_derives = "Debug, Clone"
-)]
-struct GenesisTimestamp;
-
-#[derive(GraphQLQuery)]
-#[graphql(
-    schema_path = "src/graphql/schema.json",
-    query_path = "src/graphql/best_chain.graphql",
-    response_derives = "Debug, Clone, Serialize"
-)]
-struct BestChain;
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BestTip(best_chain::BestChainBestChain);
+pub struct BestTip {
+    hash: String,
+    height: u32,
+    global_slot: u32,
+}
 
 impl BestTip {
-    pub fn consensus_state(&self) -> &best_chain::BestChainBestChainProtocolStateConsensusState {
-        &self.0.protocol_state.consensus_state
-    }
-
     pub fn epoch_bounds(&self) -> ((u32, u32), (u32, u32)) {
-        // TODO(adonagy): get the data from the node + unwrap
+        // TODO(adonagy): get the data from the node
         const SLOTS_PER_EPOCH: u32 = 7140;
-        let current_epoch = self.consensus_state().epoch.parse::<u32>().unwrap();
+        let current_epoch = self.epoch();
         let current_start = current_epoch * SLOTS_PER_EPOCH;
         let current_end = current_epoch * SLOTS_PER_EPOCH + SLOTS_PER_EPOCH - 1;
 
@@ -348,26 +239,14 @@ impl BestTip {
     }
 
     pub fn height(&self) -> u32 {
-        self.consensus_state().block_height.parse().unwrap()
+        self.height
     }
 
     pub fn epoch(&self) -> u32 {
-        self.consensus_state().epoch.parse().unwrap()
+        RawGlobalSlot::from(self.global_slot).epoch()
     }
 
     pub fn state_hash(&self) -> String {
-        self.0.state_hash.clone()
-    }
-}
-
-impl From<best_chain::BestChainBestChain> for BestTip {
-    fn from(value: best_chain::BestChainBestChain) -> Self {
-        BestTip(value)
-    }
-}
-
-impl From<BestTip> for best_chain::BestChainBestChain {
-    fn from(value: BestTip) -> Self {
-        value.0
+        self.hash.clone()
     }
 }
@@ -1,90 +1,109 @@
+//! Keeps the database in sync with the node's block producer stats.
+//!
+//! The node only knows the slots won since it was started (its retention
+//! start). Earlier slots of the epoch are stored as empty, even if they were
+//! won, unless the archive watchdog fills in the blocks produced in them.
+//! Won slots in which no block was produced before the node was started
+//! can't be recovered from either source.
+
+use tokio::task::JoinHandle;
 use tokio::time::Duration;
-use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
 
-use crate::evaluator::EpochInit;
+use crate::evaluator::epoch::SlotData;
 use crate::{storage::db_sled::Database, NodeStatus};
 
-use super::daemon_status::SyncStatus;
-use super::Node;
+use super::{calc_slot_timestamp, Node, SyncStatus};
 
 // TODO(adonagy): move to struct
-pub fn spawn_watchdog(
-    node: Node,
-    status: NodeStatus,
-    db: Database,
-    sender: UnboundedSender<EpochInit>,
-    producer_pk: String,
-) -> JoinHandle<()> {
-    tokio::spawn(async move { watch(node, status, db, sender, producer_pk).await })
+pub fn spawn_watchdog(node: Node, status: NodeStatus, db: Database) -> JoinHandle<()> {
+    tokio::spawn(async move { watch(node, status, db).await })
 }
 
-async fn watch(
-    node: Node,
-    status: NodeStatus,
-    db: Database,
-    sender: UnboundedSender<EpochInit>,
-    _producer_pk: String,
-) {
+async fn watch(node: Node, status: NodeStatus, db: Database) {
     // TODO(adonagy): From config
     let mut interval = tokio::time::interval(Duration::from_secs(5));
 
     // TODO(adonagy): do not ignore this error
-    if node.wait_for_graphql().await.is_err() {
+    if node.wait_for_node().await.is_err() {
         println!("[dbg] Error: returning");
         return;
     }
 
-    let genesis_timestamp = node.get_genesis_timestmap().await.unwrap();
-
-    {
-        let mut node_status = status.write().await;
-        node_status.genesis_timestamp = genesis_timestamp;
-    }
-
     loop {
         interval.tick().await;
 
         println!("[node-watchdog] Tick");
 
-        let sync_status = node.sync_status().await;
-
-        if matches!(sync_status, SyncStatus::SYNCED) {
-            // TODO(adonagy): Probably won't need 2 calls
-            let best_tip = node.get_best_tip().await.unwrap();
-            let best_chain = node.get_best_chain().await.unwrap();
-            {
-                let mut status: tokio::sync::RwLockWriteGuard<'_, super::NodeData> =
-                    status.write().await;
-                status.best_tip = Some(best_tip.clone());
-                status.best_chain = best_chain.clone();
-                status.sync_status = sync_status.clone()
-            }
-            let current_epoch: u32 = best_tip.consensus_state().epoch.parse().unwrap();
-
-            if !db.has_ledger(&current_epoch).unwrap() {
-                println!("Dumping staking ledger for epoch {current_epoch}");
-                let ledger = Node::get_staking_ledger(current_epoch);
-                let seed = best_tip.consensus_state().staking_epoch_data.seed.clone();
-                // TODO(adonagy): handle error
-                let _ = db.store_ledger(current_epoch, &ledger);
-                let _ = db.store_seed(current_epoch, seed.clone());
-
-                let mut status = status.write().await;
-
-                status.dumped_ledgers.insert(current_epoch);
-
-                let epoch_init = EpochInit::new(
-                    current_epoch,
-                    ledger,
-                    seed,
-                    best_tip.epoch_bounds().0,
-                    genesis_timestamp,
-                );
-                // TODO(adonagy): handle error
-                let _ = sender.send(epoch_init);
+        let node_status = match node.status().await {
+            Ok(node_status) => node_status,
+            Err(e) => {
+                eprintln!("[node-watchdog] {e}");
+                status.write().await.sync_status = SyncStatus::Offline;
+                continue;
             }
-        } else {
+        };
+        let sync_status =
+            SyncStatus::from_node_sync_status(&node_status.transition_frontier.sync.status);
+        status.write().await.sync_status = sync_status.clone();
+
+        let (SyncStatus::Synced, Some(best_tip)) =
+            (sync_status, node_status.transition_frontier.best_tip)
+        else {
             println!("Node not synced");
+            continue;
+        };
+
+        let stats = match node.block_producer_stats().await {
+            Ok(stats) => stats,
+            Err(e) => {
+                eprintln!("[node-watchdog] block producer stats not available: {e}");
+                continue;
+            }
+        };
+
+        let genesis_timestamp = {
+            let mut status = status.write().await;
+            status.best_tip = Some(best_tip.clone());
+            if let Some(genesis_timestamp) = stats.genesis_timestamp() {
+                status.genesis_timestamp = genesis_timestamp;
+            }
+            status.retention_start = stats.retention_start();
+            status.genesis_timestamp
+        };
+        let current_epoch = best_tip.epoch();
+
+        if !db.has_ledger(&current_epoch).unwrap() {
+            match node.get_staking_ledger().await {
+                Ok((epoch, ledger)) if epoch == current_epoch => {
+                    println!("Storing staking ledger for epoch {current_epoch}");
+                    // TODO(adonagy): handle error
+                    let _ = db.store_ledger(current_epoch, &ledger);
+                    status.write().await.dumped_ledgers.insert(current_epoch);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("[node-watchdog] staking ledger not available: {e}"),
+            }
+        }
+
+        // initially set all the epoch's slots to lost, won slots will
+        // overwrite them. Slots from before the node was started stay lost
+        // unless the archive has our blocks for them.
+        let (start, end) = best_tip.epoch_bounds().0;
+        if !db.has_slot(start).unwrap_or_default() {
+            for global_slot in start..=end {
+                let timestamp = calc_slot_timestamp(genesis_timestamp, global_slot);
+                let _ = db.store_slot(global_slot, &SlotData::new_lost(global_slot, timestamp));
+            }
+        }
+
+        for slot in stats.slots() {
+            // TODO(adonagy): handle error
+            let _ = db.store_slot(slot.global_slot().to_u32(), &slot);
+        }
+
+        let current_slot = status.read().await.current_slot().global_slot().to_u32();
+        if db.has_slot(current_slot).unwrap_or_default() {
+            let _ = db.set_current_slot(current_slot.saturating_sub(1), current_slot);
         }
     }
 }
@@ -1,18 +1,22 @@
+use std::ops::RangeInclusive;
+
 use ark_ec::AffineCurve;
 use ark_ff::PrimeField;
-use ledger::AccountIndex;
+use ledger::{AccountId, AccountIndex, BaseLedger, TokenId};
 use message::VrfMessage;
 use mina_p2p_messages::{
     bigint::BigInt as MinaBigInt,
-    v2::{EpochSeed, MinaBaseEpochSeedStableV1},
+    v2::{ConsensusVrfOutputTruncatedStableV1, EpochSeed, MinaBaseEpochSeedStableV1},
 };
 use num::{BigInt, ToPrimitive};
+use o1_utils::FieldHelpers;
 use output::VrfOutput;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use mina_curves::pasta::curves::pallas::Pallas as CurvePoint;
-use mina_signer::Keypair;
+use mina_signer::{CompressedPubKey, Keypair};
 use threshold::Threshold;
 
 mod message;
@@ -151,10 +155,34 @@ pub fn evaluate_vrf(vrf_input: VrfEvaluationInput) -> VrfResult<VrfEvaluationOut
         account_pub_key,
     } = vrf_input;
 
-    let vrf_output = calculate_vrf(&producer_key, epoch_seed, global_slot, &delegator_index)?;
+    let threshold = Threshold::new(delegated_stake, total_currency);
+
+    evaluate_vrf_with_threshold(
+        &producer_key,
+        &epoch_seed,
+        global_slot,
+        delegator_index,
+        account_pub_key,
+        &threshold,
+    )
+}
+
+fn evaluate_vrf_with_threshold(
+    producer_key: &Keypair,
+    epoch_seed: &EpochSeed,
+    global_slot: u32,
+    delegator_index: AccountIndex,
+    account_pub_key: String,
+    threshold: &Threshold,
+) -> VrfResult<VrfEvaluationOutput> {
+    let vrf_output = calculate_vrf(
+        producer_key,
+        epoch_seed.clone(),
+        global_slot,
+        &delegator_index,
+    )?;
 
     let value = vrf_output.truncated().into_repr();
-    let threshold = Threshold::new(delegated_stake, total_currency);
 
     if threshold.threshold_met(value) {
         Ok(VrfEvaluationOutput::SlotWon(VrfWonSlot {
@@ -175,6 +203,106 @@ pub fn evaluate_vrf(vrf_input: VrfEvaluationInput) -> VrfResult<VrfEvaluationOut
     }
 }
 
+/// Account delegating its stake to the producer, as found in the staking ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrfDelegator {
+    pub index: AccountIndex,
+    pub pub_key: String,
+    pub stake: BigInt,
+}
+
+/// Evaluate vrf for a range of slots over all the producer's delegators.
+///
+/// Slots are evaluated in parallel. For each slot, the output is the won slot
+/// of the first winning delegator (in the order of `delegators`), or
+/// [`VrfEvaluationOutput::SlotLost`] if none of them won it. Outputs are in
+/// the order of `global_slots`.
+pub fn evaluate_vrf_batch(
+    producer_key: &Keypair,
+    epoch_seed: &EpochSeed,
+    global_slots: RangeInclusive<u32>,
+    delegators: &[VrfDelegator],
+    total_currency: &BigInt,
+) -> VrfResult<Vec<VrfEvaluationOutput>> {
+    // thresholds only depend on the stake, compute them once per delegator
+    let delegators = delegators
+        .par_iter()
+        .map(|delegator| {
+            let threshold = Threshold::new(delegator.stake.clone(), total_currency.clone());
+            (delegator, threshold)
+        })
+        .collect::<Vec<_>>();
+
+    global_slots
+        .into_par_iter()
+        .map(|global_slot| {
+            delegators
+                .par_iter()
+                .map(|(delegator, threshold)| {
+                    evaluate_vrf_with_threshold(
+                        producer_key,
+                        epoch_seed,
+                        global_slot,
+                        delegator.index,
+                        delegator.pub_key.clone(),
+                        threshold,
+                    )
+                })
+                .find_map_first(|result| match result {
+                    Ok(VrfEvaluationOutput::SlotLost(_)) => None,
+                    result => Some(result),
+                })
+                .unwrap_or(Ok(VrfEvaluationOutput::SlotLost(global_slot)))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrfVerificationInput {
+    /// Truncated vrf output included in the block's consensus state.
+    pub vrf_output: ConsensusVrfOutputTruncatedStableV1,
+    /// Account which won the slot, as claimed by the block.
+    pub stake_winner: CompressedPubKey,
+    /// Creator of the block, to which the stake winner must delegate.
+    pub block_creator: CompressedPubKey,
+    /// Total currency of the staking ledger.
+    pub total_currency: BigInt,
+}
+
+/// Verifies a block's vrf claim against the staking ledger of its epoch.
+///
+/// The stake winner must delegate to the block creator in the ledger, and
+/// the vrf output must satisfy the threshold of the winner's stake. The block
+/// only carries the truncated output, and the evaluation itself is proven by
+/// the block's snark, so the output itself can't be recomputed here.
+pub fn verify_vrf<L: BaseLedger>(
+    staking_ledger: &L,
+    input: VrfVerificationInput,
+) -> VrfResult<bool> {
+    let VrfVerificationInput {
+        vrf_output,
+        stake_winner,
+        block_creator,
+        total_currency,
+    } = input;
+
+    let account_id = AccountId::new(stake_winner, TokenId::default());
+    let Some(account) = staking_ledger
+        .location_of_account(&account_id)
+        .and_then(|addr| staking_ledger.get(addr))
+    else {
+        return Ok(false);
+    };
+    if account.delegate.as_ref() != Some(&block_creator) {
+        return Ok(false);
+    }
+
+    let value = ScalarField::from_bytes(vrf_output.0.as_ref())?.into_repr();
+    let threshold = Threshold::new(account.balance.as_u64().into(), total_currency);
+
+    Ok(threshold.threshold_met(value))
+}
+
 fn keypair_from_bs58_string(str: &str) -> Keypair {
     let mut secret_hex_vec = bs58::decode(str).into_vec().unwrap();
     secret_hex_vec = secret_hex_vec[2..secret_hex_vec.len() - 4].to_vec();
@@ -187,11 +315,16 @@ fn keypair_from_bs58_string(str: &str) -> Keypair {
 mod test {
     use std::str::FromStr;
 
-    use ledger::AccountIndex;
+    use ledger::{
+        scan_state::currency::Balance, Account, AccountId, AccountIndex, BaseLedger, Mask, TokenId,
+    };
     use mina_p2p_messages::v2::EpochSeed;
     use num::BigInt;
 
-    use crate::{genesis_vrf, keypair_from_bs58_string, VrfEvaluationInput, VrfEvaluationOutput};
+    use crate::{
+        evaluate_vrf_batch, genesis_vrf, keypair_from_bs58_string, verify_vrf, VrfDelegator,
+        VrfEvaluationInput, VrfEvaluationOutput, VrfVerificationInput,
+    };
 
     use super::evaluate_vrf;
 
@@ -259,6 +392,115 @@ mod test {
         // assert_eq!(expected, evaluation_result)
     }
 
+    #[test]
+    fn test_evaluate_vrf_batch() {
+        let producer_key =
+            keypair_from_bs58_string("EKEEpMELfQkMbJDt2fB4cFXKwSf1x4t7YD4twREy5yuJ84HBZtF9");
+        let epoch_seed =
+            EpochSeed::from_str("2va9BGv9JrLTtrzZttiEMDYw1Zj6a6EHzXjmP9evHDTG3oEquURA").unwrap();
+        let total_currency = BigInt::from_str("6000000000001000").unwrap();
+        let delegators = (1..4)
+            .map(|index| VrfDelegator {
+                index: AccountIndex(index),
+                pub_key: format!("Placeholder{index}"),
+                stake: BigInt::from_str("1000000000000000").unwrap(),
+            })
+            .collect::<Vec<_>>();
+
+        let batch = evaluate_vrf_batch(
+            &producer_key,
+            &epoch_seed,
+            0..=10,
+            &delegators,
+            &total_currency,
+        )
+        .expect("Failed to evaluate vrf");
+
+        let sequential = (0..=10)
+            .map(|global_slot| {
+                delegators
+                    .iter()
+                    .map(|delegator| {
+                        evaluate_vrf(VrfEvaluationInput::new(
+                            producer_key.clone(),
+                            epoch_seed.clone(),
+                            delegator.pub_key.clone(),
+                            global_slot,
+                            delegator.index,
+                            delegator.stake.clone(),
+                            total_currency.clone(),
+                        ))
+                        .expect("Failed to evaluate vrf")
+                    })
+                    .find(|output| matches!(output, VrfEvaluationOutput::SlotWon(_)))
+                    .unwrap_or(VrfEvaluationOutput::SlotLost(global_slot))
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(batch, sequential);
+        assert!(matches!(batch[6], VrfEvaluationOutput::SlotWon(_)));
+    }
+
+    #[test]
+    fn test_verify_vrf() {
+        let vrf_input = VrfEvaluationInput {
+            producer_key: keypair_from_bs58_string(
+                "EKEEpMELfQkMbJDt2fB4cFXKwSf1x4t7YD4twREy5yuJ84HBZtF9",
+            ),
+            epoch_seed: EpochSeed::from_str("2va9BGv9JrLTtrzZttiEMDYw1Zj6a6EHzXjmP9evHDTG3oEquURA")
+                .unwrap(),
+            global_slot: 6,
+            delegator_index: AccountIndex(2),
+            delegated_stake: BigInt::from_str("1000000000000000")
+                .expect("Cannot convert to BigInt"),
+            total_currency: BigInt::from_str("6000000000001000").expect("Cannot convert to BigInt"),
+            account_pub_key: "Placeholder".to_string(),
+        };
+        let VrfEvaluationOutput::SlotWon(won_slot) =
+            evaluate_vrf(vrf_input.clone()).expect("Failed to evaluate vrf")
+        else {
+            panic!("Slot should have been won!")
+        };
+
+        let producer = vrf_input.producer_key.public.into_compressed();
+        let winner =
+            keypair_from_bs58_string("EKFKgDtU3rcuFTVSEpmpXSkukjmX4cKefYREi6Sdsk7E7wsT7KRw")
+                .public
+                .into_compressed();
+        let mut account = Account::create_with(
+            AccountId::new(winner.clone(), TokenId::default()),
+            Balance::from_u64(1_000_000_000_000_000),
+        );
+        account.delegate = Some(producer.clone());
+        let mut staking_ledger = Mask::new_unattached(10);
+        staking_ledger
+            .get_or_create_account(account.id(), account.clone())
+            .unwrap();
+
+        let verification_input = VrfVerificationInput {
+            vrf_output: (&won_slot.vrf_output).into(),
+            stake_winner: winner.clone(),
+            block_creator: producer.clone(),
+            total_currency: vrf_input.total_currency,
+        };
+        assert!(verify_vrf(&staking_ledger, verification_input.clone()).unwrap());
+
+        // winner doesn't delegate to the block creator
+        let verification_input_other_creator = VrfVerificationInput {
+            block_creator: winner.clone(),
+            ..verification_input.clone()
+        };
+        assert!(!verify_vrf(&staking_ledger, verification_input_other_creator).unwrap());
+
+        // the output (fractional ~0.17) doesn't meet the threshold of a tiny stake
+        let mut staking_ledger = Mask::new_unattached(10);
+        account.balance = Balance::from_u64(1_000_000_000);
+        staking_ledger
+            .get_or_create_account(account.id(), account)
+            .unwrap();
+        assert!(!verify_vrf(&staking_ledger, verification_input).unwrap());
+    }
+
     #[test]
     #[ignore]
     fn test_slot_calculation_time_big_producer() {